@@ -1,5 +1,18 @@
-// generated by `sqlx migrate build-script`
-fn main() {
+use vergen_gitcl::{BuildBuilder, Emitter, GitclBuilder};
+
+// generated by `sqlx migrate build-script`, plus the build info shown at
+// GET /version
+fn main() -> Result<(), Box<dyn std::error::Error>> {
     // trigger recompilation when a new migration is added
     println!("cargo:rerun-if-changed=migrations");
+
+    // Outside a git checkout the commit falls back to a placeholder rather
+    // than failing the build
+    Emitter::default()
+        .add_instructions(
+            &BuildBuilder::default().build_timestamp(true).build()?,
+        )?
+        .add_instructions(&GitclBuilder::default().sha(true).build()?)?
+        .emit()?;
+    Ok(())
 }
@@ -0,0 +1,11 @@
+DROP POLICY shifts_tenant_isolation ON shifts;
+DROP POLICY members_tenant_isolation ON members;
+DROP POLICY projects_list_tenant_isolation ON projects_list;
+
+ALTER TABLE shifts DISABLE ROW LEVEL SECURITY;
+ALTER TABLE members DISABLE ROW LEVEL SECURITY;
+ALTER TABLE projects_list DISABLE ROW LEVEL SECURITY;
+
+DROP FUNCTION current_tenant_id();
+
+REVOKE ALL ON projects_list, members, shifts FROM rota_tenant;
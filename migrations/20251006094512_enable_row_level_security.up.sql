@@ -0,0 +1,39 @@
+-- Queries against project data run as this role, with app.user_id set to the
+-- requesting user for the duration of the transaction. Roles are shared across
+-- databases, so tolerate it already existing.
+DO $$
+BEGIN
+    CREATE ROLE rota_tenant NOLOGIN;
+EXCEPTION
+    WHEN duplicate_object OR unique_violation THEN NULL;
+END
+$$;
+
+GRANT rota_tenant TO CURRENT_USER;
+GRANT SELECT, INSERT, UPDATE, DELETE ON projects_list, members, shifts TO rota_tenant;
+
+CREATE FUNCTION current_tenant_id() RETURNS UUID AS $$
+    SELECT NULLIF(current_setting('app.user_id', true), '')::UUID
+$$ LANGUAGE SQL STABLE;
+
+ALTER TABLE projects_list ENABLE ROW LEVEL SECURITY;
+ALTER TABLE members ENABLE ROW LEVEL SECURITY;
+ALTER TABLE shifts ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY projects_list_tenant_isolation ON projects_list
+    USING (user_id = current_tenant_id());
+
+CREATE POLICY members_tenant_isolation ON members
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = members.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
+
+CREATE POLICY shifts_tenant_isolation ON shifts
+    USING (EXISTS (
+        SELECT 1 FROM members
+        INNER JOIN projects_list ON members.project_id = projects_list.project_id
+        WHERE members.member_id = shifts.member_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1,15 @@
+-- Domain events are written here in the same transaction as the change that
+-- caused them, then relayed to subscribers. delivered_at is NULL until then.
+CREATE TABLE event_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    delivered_at TIMESTAMPTZ
+);
+
+CREATE INDEX event_outbox_undelivered_idx ON event_outbox (id)
+    WHERE delivered_at IS NULL;
+
+-- Project changes run as the tenant role, which must be able to enqueue events
+GRANT INSERT ON event_outbox TO rota_tenant;
+GRANT USAGE ON SEQUENCE event_outbox_id_seq TO rota_tenant;
@@ -0,0 +1,23 @@
+-- Every event that touched a project, kept so its history can be replayed.
+-- Written in the same transaction as the change, alongside the outbox.
+CREATE TABLE project_events (
+    id BIGSERIAL PRIMARY KEY,
+    project_id UUID NOT NULL,
+    entity_type TEXT NOT NULL,
+    event JSONB NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX project_events_project_id_idx ON project_events (project_id, id);
+
+GRANT SELECT, INSERT ON project_events TO rota_tenant;
+GRANT USAGE ON SEQUENCE project_events_id_seq TO rota_tenant;
+
+ALTER TABLE project_events ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY project_events_tenant_isolation ON project_events
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = project_events.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
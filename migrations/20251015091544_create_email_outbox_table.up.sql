@@ -0,0 +1,20 @@
+-- Notification emails are queued here and sent by the email relay, which caps
+-- how many each recipient gets per hour. Emails sent together as one digest
+-- share a delivery_id; sent_at and delivery_id are NULL until sent.
+CREATE TABLE email_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    recipient TEXT NOT NULL,
+    subject TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    sent_at TIMESTAMPTZ,
+    delivery_id BIGINT
+);
+
+CREATE INDEX email_outbox_unsent_idx ON email_outbox (id)
+    WHERE sent_at IS NULL;
+CREATE INDEX email_outbox_recipient_sent_at_idx
+    ON email_outbox (recipient, sent_at);
+
+GRANT INSERT ON email_outbox TO rota_tenant;
+GRANT USAGE ON SEQUENCE email_outbox_id_seq TO rota_tenant;
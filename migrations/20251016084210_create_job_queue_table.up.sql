@@ -0,0 +1,18 @@
+-- Delayed jobs for the job worker. While a job is waiting, scheduling another
+-- with the same kind and key bumps its count instead of adding a row.
+CREATE TABLE job_queue (
+    id BIGSERIAL PRIMARY KEY,
+    kind TEXT NOT NULL,
+    key TEXT NOT NULL,
+    job JSONB NOT NULL,
+    count INTEGER NOT NULL DEFAULT 1,
+    attempts INTEGER NOT NULL DEFAULT 0,
+    run_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    completed_at TIMESTAMPTZ
+);
+
+CREATE UNIQUE INDEX job_queue_waiting_key_idx ON job_queue (kind, key)
+    WHERE completed_at IS NULL;
+CREATE INDEX job_queue_run_at_idx ON job_queue (run_at)
+    WHERE completed_at IS NULL;
@@ -0,0 +1 @@
+ALTER TABLE projects_list DROP COLUMN shift_granularity;
@@ -0,0 +1,3 @@
+-- Shift start and end times must be multiples of this many minutes
+ALTER TABLE projects_list
+    ADD COLUMN shift_granularity SMALLINT NOT NULL DEFAULT 1;
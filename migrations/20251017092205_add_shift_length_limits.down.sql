@@ -0,0 +1,3 @@
+ALTER TABLE projects_list
+    DROP COLUMN min_shift_length,
+    DROP COLUMN max_shift_length;
@@ -0,0 +1,4 @@
+-- Shorter or longer shifts than these, in minutes, are rejected
+ALTER TABLE projects_list
+    ADD COLUMN min_shift_length SMALLINT NOT NULL DEFAULT 1,
+    ADD COLUMN max_shift_length SMALLINT NOT NULL DEFAULT 1440;
@@ -0,0 +1,3 @@
+DROP TABLE breaks;
+
+ALTER TABLE projects_list DROP COLUMN break_rules;
@@ -0,0 +1,29 @@
+-- Break rules are a list of {shiftLongerThan, breakLength, paid}, applied
+-- when shifts are created
+ALTER TABLE projects_list
+    ADD COLUMN break_rules JSONB NOT NULL DEFAULT '[]';
+
+CREATE TABLE breaks (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    shift_id UUID NOT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    start_time SMALLINT NOT NULL CHECK (start_time >= 0 AND start_time <= 1440),
+    end_time SMALLINT NOT NULL CHECK (end_time >= 0 AND end_time <= 1440),
+    paid BOOLEAN NOT NULL,
+    -- Set for breaks added by a project's break rules rather than by hand
+    auto_inserted BOOLEAN NOT NULL DEFAULT FALSE
+);
+
+CREATE INDEX breaks_shift_id_idx ON breaks (shift_id);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON breaks TO rota_tenant;
+
+ALTER TABLE breaks ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY breaks_tenant_isolation ON breaks
+    USING (EXISTS (
+        SELECT 1 FROM shifts
+        INNER JOIN members ON shifts.member_id = members.member_id
+        INNER JOIN projects_list ON members.project_id = projects_list.project_id
+        WHERE shifts.id = breaks.shift_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1,3 @@
+-- A second member lined up to cover the shift if the first can't make it
+ALTER TABLE shifts
+    ADD COLUMN standby_member_id UUID REFERENCES members (member_id) ON DELETE SET NULL;
@@ -0,0 +1,27 @@
+-- A member missing one week's occurrence of a shift
+CREATE TABLE absences (
+    id UUID PRIMARY KEY,
+    shift_id UUID NOT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    member_id UUID NOT NULL,
+    date DATE NOT NULL,
+    reason TEXT NOT NULL,
+    -- Who was lined up to cover; NULL means the shift was left open
+    standby_member_id UUID,
+    reported_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (shift_id, date)
+);
+
+CREATE INDEX absences_member_id_idx ON absences (member_id, date);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON absences TO rota_tenant;
+
+ALTER TABLE absences ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY absences_tenant_isolation ON absences
+    USING (EXISTS (
+        SELECT 1 FROM shifts
+        INNER JOIN members ON shifts.member_id = members.member_id
+        INNER JOIN projects_list ON members.project_id = projects_list.project_id
+        WHERE shifts.id = absences.shift_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
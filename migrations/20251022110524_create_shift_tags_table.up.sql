@@ -0,0 +1,20 @@
+CREATE TABLE shift_tags (
+    shift_id UUID NOT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    tag VARCHAR(32) NOT NULL,
+    PRIMARY KEY (shift_id, tag)
+);
+
+CREATE INDEX shift_tags_tag_idx ON shift_tags (tag);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON shift_tags TO rota_tenant;
+
+ALTER TABLE shift_tags ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY shift_tags_tenant_isolation ON shift_tags
+    USING (EXISTS (
+        SELECT 1 FROM shifts
+        INNER JOIN members ON shifts.member_id = members.member_id
+        INNER JOIN projects_list ON members.project_id = projects_list.project_id
+        WHERE shifts.id = shift_tags.shift_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1 @@
+ALTER TABLE users ADD COLUMN security_emails BOOLEAN NOT NULL DEFAULT TRUE;
@@ -0,0 +1,2 @@
+-- NULL until the user accepts a version of the terms and privacy policy
+ALTER TABLE users ADD COLUMN accepted_policy_version INTEGER;
@@ -0,0 +1,2 @@
+-- NULL to follow the Accept-Language header
+ALTER TABLE users ADD COLUMN locale TEXT;
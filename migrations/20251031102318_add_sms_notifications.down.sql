@@ -0,0 +1,4 @@
+DROP TABLE shift_reminders;
+DROP TABLE sms_outbox;
+ALTER TABLE members DROP COLUMN sms_opt_in;
+ALTER TABLE members DROP COLUMN phone_number;
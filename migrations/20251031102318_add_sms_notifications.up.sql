@@ -0,0 +1,26 @@
+-- Members opt in to SMS separately from giving a number
+ALTER TABLE members ADD COLUMN phone_number TEXT;
+ALTER TABLE members ADD COLUMN sms_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Text messages are queued here and sent by the SMS relay, the same way as
+-- email_outbox. sent_at is NULL until sent.
+CREATE TABLE sms_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    recipient TEXT NOT NULL,
+    content TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    sent_at TIMESTAMPTZ
+);
+
+CREATE INDEX sms_outbox_unsent_idx ON sms_outbox (id) WHERE sent_at IS NULL;
+
+GRANT INSERT ON sms_outbox TO rota_tenant;
+GRANT USAGE ON SEQUENCE sms_outbox_id_seq TO rota_tenant;
+
+-- Each occurrence of a weekly shift is only reminded about once
+CREATE TABLE shift_reminders (
+    shift_id UUID NOT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    shift_date DATE NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (shift_id, shift_date)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE shift_reminders DROP COLUMN swap_requested_at;
+ALTER TABLE shift_reminders DROP COLUMN confirmed_at;
@@ -0,0 +1,3 @@
+-- Members answer a reminder by text to confirm the shift or ask to swap it
+ALTER TABLE shift_reminders ADD COLUMN confirmed_at TIMESTAMPTZ;
+ALTER TABLE shift_reminders ADD COLUMN swap_requested_at TIMESTAMPTZ;
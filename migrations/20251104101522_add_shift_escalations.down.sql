@@ -0,0 +1,6 @@
+DROP TABLE shift_escalations;
+
+ALTER TABLE projects_list
+    DROP COLUMN escalation_steps,
+    DROP COLUMN chat_webhook_url,
+    DROP COLUMN escalation_phone;
@@ -0,0 +1,15 @@
+-- How uncovered critical shifts are escalated. Steps are a JSON list of
+-- {channel, hoursBeforeStart}; an empty list turns escalation off.
+ALTER TABLE projects_list
+    ADD COLUMN escalation_steps JSONB NOT NULL DEFAULT '[]',
+    ADD COLUMN chat_webhook_url TEXT,
+    ADD COLUMN escalation_phone TEXT;
+
+-- Each escalation step is only taken once per occurrence of a shift
+CREATE TABLE shift_escalations (
+    shift_id UUID NOT NULL REFERENCES shifts (id) ON DELETE CASCADE,
+    shift_date DATE NOT NULL,
+    channel TEXT NOT NULL,
+    sent_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (shift_id, shift_date, channel)
+);
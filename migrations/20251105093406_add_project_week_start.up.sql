@@ -0,0 +1,4 @@
+-- The day each rota week begins on, numbered like shifts.day (0 = Sunday)
+ALTER TABLE projects_list
+    ADD COLUMN week_start SMALLINT NOT NULL DEFAULT 0
+    CHECK (week_start BETWEEN 0 AND 6);
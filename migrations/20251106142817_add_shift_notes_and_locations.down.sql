@@ -0,0 +1 @@
+ALTER TABLE shifts DROP COLUMN note, DROP COLUMN location;
@@ -0,0 +1,4 @@
+-- Free text shown alongside a shift, e.g. on a printed rota
+ALTER TABLE shifts
+    ADD COLUMN note VARCHAR(200),
+    ADD COLUMN location VARCHAR(100);
@@ -0,0 +1,2 @@
+DROP TABLE week_template_shifts;
+DROP TABLE week_templates;
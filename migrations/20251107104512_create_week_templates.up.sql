@@ -0,0 +1,50 @@
+-- A project's week of shifts saved under a name, to lay out again later
+CREATE TABLE week_templates (
+    template_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    template_name VARCHAR(100) NOT NULL,
+    saved_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    UNIQUE (project_id, template_name)
+);
+
+-- Breaks aren't kept: they are worked out again from the project's rules
+-- when the template is applied
+CREATE TABLE week_template_shifts (
+    template_id UUID NOT NULL REFERENCES week_templates (template_id) ON DELETE CASCADE,
+    -- Not foreign keys, so that members who have left can be mapped to
+    -- others when the template is applied
+    member_id UUID NOT NULL,
+    standby_member_id UUID,
+    day SMALLINT NOT NULL CHECK (day BETWEEN 0 AND 6),
+    in_time SMALLINT NOT NULL CHECK (in_time BETWEEN 0 AND 1440),
+    out_time SMALLINT NOT NULL CHECK (out_time BETWEEN 0 AND 1440),
+    -- A JSON list, as arrays of arrays can't be unnested a row at a time
+    tags JSONB NOT NULL DEFAULT '[]',
+    note VARCHAR(200),
+    location VARCHAR(100)
+);
+
+CREATE INDEX week_template_shifts_template_id_idx
+    ON week_template_shifts (template_id);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON week_templates, week_template_shifts
+    TO rota_tenant;
+
+ALTER TABLE week_templates ENABLE ROW LEVEL SECURITY;
+ALTER TABLE week_template_shifts ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY week_templates_tenant_isolation ON week_templates
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = week_templates.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
+
+CREATE POLICY week_template_shifts_tenant_isolation ON week_template_shifts
+    USING (EXISTS (
+        SELECT 1 FROM week_templates
+        INNER JOIN projects_list
+            ON week_templates.project_id = projects_list.project_id
+        WHERE week_templates.template_id = week_template_shifts.template_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1,5 @@
+DROP TABLE rota_versions;
+
+ALTER TABLE projects_list
+    DROP COLUMN auto_publish,
+    DROP COLUMN auto_publish_at;
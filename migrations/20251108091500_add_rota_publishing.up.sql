@@ -0,0 +1,31 @@
+-- When next week's rota is published automatically, as a crontab schedule
+-- read in UTC. auto_publish_at is when it next runs; it is cleared whenever
+-- the schedule changes and set again by the publisher.
+ALTER TABLE projects_list
+    ADD COLUMN auto_publish TEXT,
+    ADD COLUMN auto_publish_at TIMESTAMP;
+
+-- A week of a project's shifts as published, kept as a JSON list so it
+-- reads the same however the shifts change afterwards
+CREATE TABLE rota_versions (
+    version_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    week_of DATE NOT NULL,
+    shifts JSONB NOT NULL,
+    automatic BOOLEAN NOT NULL DEFAULT false,
+    published_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX rota_versions_project_id_idx
+    ON rota_versions (project_id, published_at);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON rota_versions TO rota_tenant;
+
+ALTER TABLE rota_versions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY rota_versions_tenant_isolation ON rota_versions
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = rota_versions.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1,3 @@
+DROP TABLE rota_approvals;
+
+ALTER TABLE projects_list DROP COLUMN approver_email;
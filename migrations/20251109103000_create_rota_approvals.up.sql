@@ -0,0 +1,40 @@
+-- Who has to approve a project's rota before it's published. With nobody
+-- set, rotas are published straight away.
+ALTER TABLE projects_list ADD COLUMN approver_email TEXT;
+
+-- A week of shifts submitted for the approver's go-ahead. The shifts are
+-- published as they are here once approved; changes are against the version
+-- that was last published when it was submitted.
+CREATE TABLE rota_approvals (
+    approval_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    version_id UUID NOT NULL,
+    week_of DATE NOT NULL,
+    shifts JSONB NOT NULL,
+    changes JSONB NOT NULL,
+    automatic BOOLEAN NOT NULL DEFAULT false,
+    approver_email TEXT NOT NULL,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'approved', 'rejected', 'withdrawn')),
+    comment TEXT,
+    submitted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    reviewed_at TIMESTAMPTZ
+);
+
+CREATE INDEX rota_approvals_project_id_idx
+    ON rota_approvals (project_id, submitted_at);
+
+-- Approvers find what's waiting for them by email
+CREATE INDEX rota_approvals_pending_idx
+    ON rota_approvals (approver_email) WHERE status = 'pending';
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON rota_approvals TO rota_tenant;
+
+ALTER TABLE rota_approvals ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY rota_approvals_tenant_isolation ON rota_approvals
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = rota_approvals.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
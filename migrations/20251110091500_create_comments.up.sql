@@ -0,0 +1,28 @@
+-- Comments for coordinating cover and the like, on a project or on one of
+-- its shifts. Members mentioned by name are texted if they've opted in.
+CREATE TABLE comments (
+    comment_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    -- Null for comments on the project as a whole
+    shift_id UUID REFERENCES shifts (id) ON DELETE CASCADE,
+    author_id UUID NOT NULL,
+    body TEXT NOT NULL,
+    mentions UUID[] NOT NULL DEFAULT '{}',
+    posted_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+CREATE INDEX comments_project_id_idx ON comments (project_id, posted_at);
+
+CREATE INDEX comments_shift_id_idx ON comments (shift_id)
+    WHERE shift_id IS NOT NULL;
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON comments TO rota_tenant;
+
+ALTER TABLE comments ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY comments_tenant_isolation ON comments
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = comments.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1 @@
+DROP TABLE rota_reactions;
@@ -0,0 +1,21 @@
+-- Members' quick reactions to a published rota, e.g. 👍 once they've seen
+-- it. Reacting again replaces the reaction.
+CREATE TABLE rota_reactions (
+    version_id UUID NOT NULL REFERENCES rota_versions (version_id) ON DELETE CASCADE,
+    member_id UUID NOT NULL REFERENCES members (member_id) ON DELETE CASCADE,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    reaction TEXT NOT NULL,
+    reacted_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    PRIMARY KEY (version_id, member_id)
+);
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON rota_reactions TO rota_tenant;
+
+ALTER TABLE rota_reactions ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY rota_reactions_tenant_isolation ON rota_reactions
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = rota_reactions.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
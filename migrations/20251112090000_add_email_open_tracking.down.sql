@@ -0,0 +1,6 @@
+ALTER TABLE email_outbox
+    DROP COLUMN project_id,
+    DROP COLUMN tracked,
+    DROP COLUMN opened_at;
+
+ALTER TABLE users DROP COLUMN email_open_tracking;
@@ -0,0 +1,18 @@
+-- Whether opening notification emails may be recorded, using an invisible
+-- image in the email. On unless the user turns it off.
+ALTER TABLE users ADD COLUMN email_open_tracking BOOLEAN NOT NULL DEFAULT TRUE;
+
+-- Emails about a project keep its ID so the owner can see whether they were
+-- read. tracked is set when an email is sent with the image, and opened_at
+-- when the image is first loaded.
+ALTER TABLE email_outbox
+    ADD COLUMN project_id UUID
+        REFERENCES projects_list (project_id) ON DELETE SET NULL,
+    ADD COLUMN tracked BOOLEAN NOT NULL DEFAULT false,
+    ADD COLUMN opened_at TIMESTAMPTZ;
+
+CREATE INDEX email_outbox_project_id_idx ON email_outbox (project_id, id)
+    WHERE project_id IS NOT NULL;
+
+CREATE INDEX email_outbox_delivery_id_idx ON email_outbox (delivery_id)
+    WHERE tracked;
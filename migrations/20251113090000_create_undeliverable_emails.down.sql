@@ -0,0 +1,7 @@
+DROP INDEX email_outbox_unsent_idx;
+CREATE INDEX email_outbox_unsent_idx ON email_outbox (id)
+    WHERE sent_at IS NULL;
+
+ALTER TABLE email_outbox DROP COLUMN suppressed;
+
+DROP TABLE undeliverable_emails;
@@ -0,0 +1,16 @@
+-- Addresses Postmark reported as bouncing for good, or whose owner marked
+-- our email as spam. Queued email to them is suppressed until the address
+-- is confirmed again.
+CREATE TABLE undeliverable_emails (
+    email TEXT PRIMARY KEY,
+    reason TEXT NOT NULL,
+    marked_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
+
+-- Suppressed emails are never sent, so they leave the queue unsent
+ALTER TABLE email_outbox
+    ADD COLUMN suppressed BOOLEAN NOT NULL DEFAULT false;
+
+DROP INDEX email_outbox_unsent_idx;
+CREATE INDEX email_outbox_unsent_idx ON email_outbox (id)
+    WHERE sent_at IS NULL AND NOT suppressed;
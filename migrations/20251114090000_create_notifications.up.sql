@@ -0,0 +1,14 @@
+-- Users' in-app inbox, filled from domain events so that news like swap
+-- requests reaches users who don't read their email
+CREATE TABLE notifications (
+    id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    -- Null for notifications that aren't about a project
+    project_id UUID REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    read_at TIMESTAMPTZ
+);
+
+CREATE INDEX notifications_user_id_idx
+    ON notifications (user_id, created_at DESC);
@@ -0,0 +1,7 @@
+-- Long-polling clients ask for what's arrived since the last notification
+-- they saw. Notifications are added one at a time by the inbox subscriber,
+-- so they're committed in this order.
+ALTER TABLE notifications ADD COLUMN seq BIGSERIAL;
+
+CREATE UNIQUE INDEX notifications_user_id_seq_idx
+    ON notifications (user_id, seq);
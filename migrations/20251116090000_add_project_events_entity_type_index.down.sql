@@ -0,0 +1 @@
+DROP INDEX project_events_entity_type_idx;
@@ -0,0 +1,3 @@
+-- Paging through one kind of entry by cursor reads straight from here
+CREATE INDEX project_events_entity_type_idx
+    ON project_events (project_id, entity_type, id);
@@ -0,0 +1,5 @@
+DROP INDEX projects_list_user_id_idx;
+
+DROP INDEX shifts_member_id_day_idx;
+
+DROP INDEX members_project_id_idx;
@@ -0,0 +1,8 @@
+-- Every request for a project looks its members and their shifts up by
+-- these, and listing projects finds them by owner. Without indexes each
+-- lookup reads the whole table.
+CREATE INDEX members_project_id_idx ON members (project_id);
+
+CREATE INDEX shifts_member_id_day_idx ON shifts (member_id, day);
+
+CREATE INDEX projects_list_user_id_idx ON projects_list (user_id);
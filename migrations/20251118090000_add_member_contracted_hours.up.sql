@@ -0,0 +1,3 @@
+-- NULL when the member isn't on a fixed-hours contract
+ALTER TABLE members ADD COLUMN contracted_hours DOUBLE PRECISION
+    CHECK (contracted_hours BETWEEN 0 AND 168);
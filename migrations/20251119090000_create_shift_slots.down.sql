@@ -0,0 +1,2 @@
+ALTER TABLE shifts DROP COLUMN slot_id;
+DROP TABLE shift_slots;
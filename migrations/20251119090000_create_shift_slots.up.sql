@@ -0,0 +1,31 @@
+-- A shift needing several people at once. Each place filled is an ordinary
+-- shift pointing back at its slot, so rotas read shifts as they always have.
+CREATE TABLE shift_slots (
+    slot_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    day SMALLINT NOT NULL CHECK (day BETWEEN 0 AND 6),
+    in_time SMALLINT NOT NULL CHECK (in_time BETWEEN 0 AND 1440),
+    out_time SMALLINT NOT NULL CHECK (out_time BETWEEN 0 AND 1440),
+    places SMALLINT NOT NULL CHECK (places > 0),
+    -- The skill needed, given to each shift filling the slot
+    tag VARCHAR(32)
+);
+
+CREATE INDEX shift_slots_project_id_idx ON shift_slots (project_id);
+
+-- Clearing a shift frees its place
+ALTER TABLE shifts ADD COLUMN slot_id UUID
+    REFERENCES shift_slots (slot_id) ON DELETE SET NULL;
+
+CREATE INDEX shifts_slot_id_idx ON shifts (slot_id) WHERE slot_id IS NOT NULL;
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON shift_slots TO rota_tenant;
+
+ALTER TABLE shift_slots ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY shift_slots_tenant_isolation ON shift_slots
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = shift_slots.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
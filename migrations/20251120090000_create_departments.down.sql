@@ -0,0 +1,3 @@
+ALTER TABLE shift_slots DROP COLUMN department_id;
+ALTER TABLE members DROP COLUMN department_id;
+DROP TABLE departments;
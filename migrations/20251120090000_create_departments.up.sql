@@ -0,0 +1,25 @@
+-- Sub-teams within a project, e.g. bar, kitchen and floor at one venue
+CREATE TABLE departments (
+    department_id UUID PRIMARY KEY,
+    project_id UUID NOT NULL REFERENCES projects_list (project_id) ON DELETE CASCADE,
+    department_name VARCHAR(100) NOT NULL,
+    UNIQUE (project_id, department_name)
+);
+
+-- Deleting a department leaves its members and slots without one
+ALTER TABLE members ADD COLUMN department_id UUID
+    REFERENCES departments (department_id) ON DELETE SET NULL;
+
+ALTER TABLE shift_slots ADD COLUMN department_id UUID
+    REFERENCES departments (department_id) ON DELETE SET NULL;
+
+GRANT SELECT, INSERT, UPDATE, DELETE ON departments TO rota_tenant;
+
+ALTER TABLE departments ENABLE ROW LEVEL SECURITY;
+
+CREATE POLICY departments_tenant_isolation ON departments
+    USING (EXISTS (
+        SELECT 1 FROM projects_list
+        WHERE projects_list.project_id = departments.project_id
+        AND projects_list.user_id = current_tenant_id()
+    ));
@@ -0,0 +1,5 @@
+-- An authenticator app's shared secret, base32 encoded. It has to be read
+-- back to check codes, so unlike backup codes it can't be hashed.
+ALTER TABLE users ADD COLUMN totp_secret TEXT;
+-- The time step of the last code used, so a code can't be used twice
+ALTER TABLE users ADD COLUMN totp_last_step BIGINT;
@@ -0,0 +1,3 @@
+-- What else a member can do besides their usual work, matched against slot
+-- tags when suggesting who another department could borrow
+ALTER TABLE members ADD COLUMN skills TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- Where the account's data has to stay, or NULL for anywhere
+ALTER TABLE users ADD COLUMN data_region TEXT;
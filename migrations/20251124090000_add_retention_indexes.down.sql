@@ -0,0 +1,11 @@
+DROP INDEX rota_versions_week_of_idx;
+
+DROP INDEX notifications_created_at_idx;
+
+DROP INDEX sms_outbox_sent_at_idx;
+
+DROP INDEX email_outbox_sent_at_idx;
+
+DROP INDEX event_outbox_delivered_at_idx;
+
+DROP INDEX project_events_occurred_at_idx;
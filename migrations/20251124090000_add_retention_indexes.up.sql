@@ -0,0 +1,16 @@
+-- The retention job deletes the oldest rows of these tables in batches,
+-- by when they happened. Without indexes each batch reads the whole table.
+CREATE INDEX project_events_occurred_at_idx ON project_events (occurred_at);
+
+CREATE INDEX event_outbox_delivered_at_idx ON event_outbox (delivered_at)
+    WHERE delivered_at IS NOT NULL;
+
+CREATE INDEX email_outbox_sent_at_idx ON email_outbox (sent_at)
+    WHERE sent_at IS NOT NULL;
+
+CREATE INDEX sms_outbox_sent_at_idx ON sms_outbox (sent_at)
+    WHERE sent_at IS NOT NULL;
+
+CREATE INDEX notifications_created_at_idx ON notifications (created_at);
+
+CREATE INDEX rota_versions_week_of_idx ON rota_versions (week_of);
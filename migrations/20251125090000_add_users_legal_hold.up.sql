@@ -0,0 +1,4 @@
+-- When an admin put the account under legal hold, or NULL if it isn't.
+-- Held accounts can't be deleted or anonymized, and their records aren't
+-- pruned by the retention job.
+ALTER TABLE users ADD COLUMN legal_hold_at TIMESTAMPTZ;
@@ -0,0 +1,2 @@
+DROP INDEX event_outbox_user_id_idx;
+DROP TABLE integration_keys;
@@ -0,0 +1,19 @@
+-- Keys that let no-code tools poll an account's domain events without a
+-- session
+CREATE TABLE integration_keys (
+    key_id UUID PRIMARY KEY,
+    user_id UUID NOT NULL REFERENCES users (id) ON DELETE CASCADE,
+    key_name VARCHAR(100) NOT NULL,
+    -- SHA-256 of the key, which is only shown when it's created
+    key_hash TEXT NOT NULL UNIQUE,
+    -- The last event the key was given, where polling without a cursor
+    -- carries on from
+    last_event_id BIGINT NOT NULL DEFAULT 0,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+    last_used_at TIMESTAMPTZ
+);
+
+CREATE INDEX integration_keys_user_id_idx ON integration_keys (user_id);
+
+-- Polling reads one account's events in order
+CREATE INDEX event_outbox_user_id_idx ON event_outbox ((event->>'user_id'), id);
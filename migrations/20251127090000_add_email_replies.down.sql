@@ -0,0 +1,4 @@
+ALTER TABLE email_outbox DROP COLUMN reply_to;
+ALTER TABLE shift_reminders DROP COLUMN reminder_id;
+ALTER TABLE members DROP COLUMN email_opt_in;
+ALTER TABLE members DROP COLUMN email;
@@ -0,0 +1,10 @@
+-- Members opt in to email separately from giving an address, as with SMS
+ALTER TABLE members ADD COLUMN email TEXT;
+ALTER TABLE members ADD COLUMN email_opt_in BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Named in the signed reply-to address of reminder emails, so a reply can be
+-- matched to its reminder
+ALTER TABLE shift_reminders ADD COLUMN reminder_id BIGSERIAL UNIQUE;
+
+-- Where replies to the email go, when not to the sender
+ALTER TABLE email_outbox ADD COLUMN reply_to TEXT;
@@ -0,0 +1,4 @@
+-- Stable IDs given by infrastructure-as-code tools, so provisioning the same
+-- account or key again finds it rather than creating another
+ALTER TABLE users ADD COLUMN external_id TEXT UNIQUE;
+ALTER TABLE integration_keys ADD COLUMN external_id TEXT UNIQUE;
@@ -0,0 +1,4 @@
+-- What rotas and emails call the user, and the timezone times are shown in.
+-- Both are optional; the timezone is an IANA name such as Europe/London.
+ALTER TABLE users ADD COLUMN display_name TEXT;
+ALTER TABLE users ADD COLUMN timezone TEXT;
@@ -0,0 +1,3 @@
+ALTER TABLE rota_approvals DROP COLUMN seq;
+
+ALTER TABLE rota_versions DROP COLUMN seq;
@@ -0,0 +1,7 @@
+-- Versions and approvals made in the same instant need an order of their
+-- own, so the latest one is always the same row
+ALTER TABLE rota_versions ADD COLUMN seq BIGSERIAL;
+ALTER TABLE rota_approvals ADD COLUMN seq BIGSERIAL;
+
+GRANT USAGE ON SEQUENCE rota_versions_seq_seq, rota_approvals_seq_seq
+    TO rota_tenant;
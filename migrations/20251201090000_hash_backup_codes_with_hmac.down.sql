@@ -0,0 +1,3 @@
+DROP INDEX backup_codes_user_id_code_hash_idx;
+
+CREATE INDEX backup_codes_user_id_idx ON backup_codes (user_id);
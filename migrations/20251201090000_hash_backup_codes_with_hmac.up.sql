@@ -0,0 +1,9 @@
+-- Backup codes are now stored as an HMAC of the code, so one can be found
+-- by its hash. Codes stored with Argon2 can't be matched that way, so they
+-- are dropped; their users can set up 2FA again for a new set.
+DELETE FROM backup_codes WHERE code_hash LIKE '$argon2%';
+
+DROP INDEX backup_codes_user_id_idx;
+
+CREATE INDEX backup_codes_user_id_code_hash_idx
+    ON backup_codes (user_id, code_hash);
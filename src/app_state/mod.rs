@@ -1,38 +1,183 @@
-use std::sync::Arc;
+use secrecy::Secret;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::RwLock;
 
-use crate::domain::{
-    BannedTokenStore, EmailClient, ProjectStore, TwoFACodeStore, UserStore,
+use crate::{
+    domain::{
+        find_policy, latest_policy, BannedTokenStore, Clock,
+        DeprecatedUsageStore, EmailClient, EventBus, IdGenerator,
+        IntegrationStore, JobLockStore, PasswordResetTokenStore,
+        PolicyDocument, ProjectStore, RequestCaptureStore, ResidencyRules,
+        SessionStore, ShiftReminderStore, SmsClient, TwoFACodeStore, UserStore,
+        VerificationToken, VerificationTokenStore,
+    },
+    utils::tracing::LogFilter,
 };
 pub type UserStoreType = Arc<RwLock<dyn UserStore + Send + Sync>>;
 pub type BannedTokenStoreType = Arc<RwLock<dyn BannedTokenStore + Send + Sync>>;
 pub type TwoFACodeStoreType = Arc<RwLock<dyn TwoFACodeStore + Send + Sync>>;
+pub type VerificationTokenStoreType =
+    Arc<RwLock<dyn VerificationTokenStore + Send + Sync>>;
 pub type EmailClientType = Arc<dyn EmailClient + Send + Sync>;
+pub type SmsClientType = Arc<dyn SmsClient + Send + Sync>;
 pub type ProjectStoreType = Arc<RwLock<dyn ProjectStore + Send + Sync>>;
+pub type PasswordResetTokenStoreType =
+    Arc<RwLock<dyn PasswordResetTokenStore + Send + Sync>>;
+pub type DeprecatedUsageStoreType =
+    Arc<RwLock<dyn DeprecatedUsageStore + Send + Sync>>;
+pub type RequestCaptureStoreType =
+    Arc<RwLock<dyn RequestCaptureStore + Send + Sync>>;
+pub type SessionStoreType = Arc<RwLock<dyn SessionStore + Send + Sync>>;
+pub type EventBusType = Arc<dyn EventBus + Send + Sync>;
+pub type ClockType = Arc<dyn Clock + Send + Sync>;
+pub type IdGeneratorType = Arc<dyn IdGenerator + Send + Sync>;
+pub type JobLockStoreType = Arc<RwLock<dyn JobLockStore + Send + Sync>>;
+pub type ShiftReminderStoreType =
+    Arc<RwLock<dyn ShiftReminderStore + Send + Sync>>;
+pub type IntegrationStoreType = Arc<RwLock<dyn IntegrationStore + Send + Sync>>;
+
+// Options that change how a deployment behaves
+#[derive(Debug, Clone, Default)]
+pub struct Settings {
+    // Signing up needs an invitation code minted by an admin
+    pub invite_only_signup: bool,
+    // Lets the first account be created as an admin. Only set when the
+    // instance starts with no users.
+    pub bootstrap_token: Option<VerificationToken>,
+    // Users must have accepted this version of the terms of service and
+    // privacy policy to use their projects
+    pub required_policy_version: Option<i32>,
+    // Proves inbound texts were sent on by Twilio. Inbound texts are
+    // refused without it.
+    pub twilio_auth_token: Option<Secret<String>>,
+    // Sent by Postmark with bounce and complaint webhooks, which are refused
+    // without it
+    pub postmark_webhook_token: Option<Secret<String>>,
+    // The app is only reached through a proxy that sets X-Forwarded-For, so
+    // the header can be believed. Anyone could send it otherwise.
+    pub trust_proxy_headers: bool,
+    // Binds the listening address with SO_REUSEPORT, so a new instance can
+    // start on it before the old one has stopped
+    pub reuse_port: bool,
+    // How many requests that hash passwords may run at once, with the rest
+    // turned away. None uses the default.
+    pub auth_concurrency_limit: Option<usize>,
+    // Whether this instance serves the API, runs the background jobs, or
+    // both
+    pub role: InstanceRole,
+    // Lets admins change which logs are kept while the server runs. None
+    // when tracing wasn't set up by init_tracing, as in tests.
+    pub log_filter: Option<LogFilter>,
+    // Where the webhooks of accounts with a data region may be sent
+    pub residency_rules: ResidencyRules,
+}
+
+// What an instance runs, so the API and the background jobs can be scaled
+// separately
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub enum InstanceRole {
+    Api,
+    Worker,
+    #[default]
+    Both,
+}
+
+impl InstanceRole {
+    pub fn parse(role: &str) -> Option<Self> {
+        match role.to_ascii_lowercase().as_str() {
+            "api" => Some(Self::Api),
+            "worker" => Some(Self::Worker),
+            "both" => Some(Self::Both),
+            _ => None,
+        }
+    }
+
+    pub fn serves_api(&self) -> bool {
+        *self != Self::Worker
+    }
+
+    pub fn runs_workers(&self) -> bool {
+        *self != Self::Api
+    }
+}
+
+// How the app connects to PostgreSQL
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DatabaseSettings {
+    // Connecting through a pooler such as pgbouncer in transaction mode,
+    // where each transaction may run on a different server connection
+    pub pgbouncer_mode: bool,
+}
+
+impl Settings {
+    // The version new users accept, and existing users are asked to
+    pub fn current_policy(&self) -> &'static PolicyDocument {
+        self.required_policy_version
+            .and_then(find_policy)
+            .unwrap_or_else(latest_policy)
+    }
+}
 
 #[derive(Clone)]
 pub struct AppState {
     pub user_store: UserStoreType,
     pub banned_token_store: BannedTokenStoreType,
     pub two_fa_code_store: TwoFACodeStoreType,
+    pub verification_token_store: VerificationTokenStoreType,
+    pub password_reset_token_store: PasswordResetTokenStoreType,
     pub email_client: EmailClientType,
     pub project_store: ProjectStoreType,
+    pub deprecated_usage_store: DeprecatedUsageStoreType,
+    pub request_capture_store: RequestCaptureStoreType,
+    pub session_store: SessionStoreType,
+    pub shift_reminder_store: ShiftReminderStoreType,
+    pub integration_store: IntegrationStoreType,
+    pub request_timeout: Duration,
+    pub event_bus: EventBusType,
+    pub clock: ClockType,
+    pub id_generator: IdGeneratorType,
+    pub settings: Settings,
 }
 
 impl AppState {
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         user_store: UserStoreType,
         banned_token_store: BannedTokenStoreType,
         two_fa_code_store: TwoFACodeStoreType,
+        verification_token_store: VerificationTokenStoreType,
+        password_reset_token_store: PasswordResetTokenStoreType,
         email_client: EmailClientType,
         project_store: ProjectStoreType,
+        deprecated_usage_store: DeprecatedUsageStoreType,
+        request_capture_store: RequestCaptureStoreType,
+        session_store: SessionStoreType,
+        shift_reminder_store: ShiftReminderStoreType,
+        integration_store: IntegrationStoreType,
+        request_timeout: Duration,
+        event_bus: EventBusType,
+        clock: ClockType,
+        id_generator: IdGeneratorType,
+        settings: Settings,
     ) -> Self {
         Self {
             user_store,
             banned_token_store,
             two_fa_code_store,
+            verification_token_store,
+            password_reset_token_store,
             email_client,
             project_store,
+            deprecated_usage_store,
+            request_capture_store,
+            session_store,
+            shift_reminder_store,
+            integration_store,
+            request_timeout,
+            event_bus,
+            clock,
+            id_generator,
+            settings,
         }
     }
 }
@@ -0,0 +1,141 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, NaiveDate};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{Day, MemberId, Shift, ShiftId, ValidationError};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum AbsenceReason {
+    Sickness,
+}
+
+impl AbsenceReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AbsenceReason::Sickness => "sickness",
+        }
+    }
+}
+
+impl FromStr for AbsenceReason {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "sickness" => Ok(AbsenceReason::Sickness),
+            _ => Err(ValidationError::new(String::from(
+                "Invalid absence reason",
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for AbsenceReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// A member missing one occurrence of a weekly shift
+#[derive(Debug, Clone, PartialEq)]
+pub struct Absence {
+    pub id: Uuid,
+    pub shift_id: ShiftId,
+    pub member_id: MemberId,
+    pub date: NaiveDate,
+    pub reason: AbsenceReason,
+    // Who covers the gap. With nobody on standby the shift is open for any
+    // member to claim.
+    pub standby_member_id: Option<MemberId>,
+}
+
+impl Absence {
+    pub fn new(
+        shift: &Shift,
+        date: NaiveDate,
+        reason: AbsenceReason,
+    ) -> Result<Self, ValidationError> {
+        let day = Day::try_from(date.weekday().num_days_from_sunday() as i16)?;
+        if day != shift.day {
+            return Err(ValidationError::new(format!(
+                "Shift is on {}, but {} is a {}",
+                shift.day, date, day
+            )));
+        }
+
+        Ok(Self {
+            id: Uuid::new_v4(),
+            shift_id: shift.id.clone(),
+            member_id: shift.member_id.clone(),
+            date,
+            reason,
+            standby_member_id: shift.standby_member_id.clone(),
+        })
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.standby_member_id.is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{time::parse_date, Minute};
+
+    fn monday_shift() -> Shift {
+        Shift::new(
+            MemberId::default(),
+            Day::Monday,
+            Minute::parse(540).unwrap(),
+            Minute::parse(1020).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_absence_must_fall_on_shift_day() {
+        let shift = monday_shift();
+
+        let absence = Absence::new(
+            &shift,
+            parse_date("2025-10-20").unwrap(),
+            AbsenceReason::Sickness,
+        )
+        .unwrap();
+        assert_eq!(absence.shift_id, shift.id);
+        assert_eq!(absence.member_id, shift.member_id);
+
+        let error = Absence::new(
+            &shift,
+            parse_date("2025-10-21").unwrap(),
+            AbsenceReason::Sickness,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Validation error: Shift is on Monday, but 2025-10-21 is a Tuesday"
+        );
+    }
+
+    #[test]
+    fn test_absence_without_standby_is_open() {
+        let mut shift = monday_shift();
+        let date = parse_date("2025-10-20").unwrap();
+
+        let absence =
+            Absence::new(&shift, date, AbsenceReason::Sickness).unwrap();
+        assert!(absence.is_open());
+
+        shift.standby_member_id = Some(MemberId::default());
+        let absence =
+            Absence::new(&shift, date, AbsenceReason::Sickness).unwrap();
+        assert!(!absence.is_open());
+    }
+}
@@ -0,0 +1,432 @@
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Days, NaiveDate};
+use uuid::Uuid;
+
+use super::{
+    time::week_beginning, Absence, ContractedHours, Day, MemberId, MemberName,
+    Project, Shift, ValidationError,
+};
+
+const MAX_REPORT_DAYS: i64 = 366;
+
+// An inclusive range of dates to report on
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DateRange {
+    from: NaiveDate,
+    to: NaiveDate,
+}
+
+impl DateRange {
+    pub fn new(
+        from: NaiveDate,
+        to: NaiveDate,
+    ) -> Result<Self, ValidationError> {
+        if to < from {
+            return Err(ValidationError::new(String::from(
+                "Report must end on or after the day it starts",
+            )));
+        }
+        if (to - from).num_days() >= MAX_REPORT_DAYS {
+            return Err(ValidationError::new(format!(
+                "Report cannot cover more than {} days",
+                MAX_REPORT_DAYS
+            )));
+        }
+        Ok(Self { from, to })
+    }
+
+    pub fn from(&self) -> NaiveDate {
+        self.from
+    }
+
+    pub fn to(&self) -> NaiveDate {
+        self.to
+    }
+
+    pub fn contains(&self, date: NaiveDate) -> bool {
+        self.from <= date && date <= self.to
+    }
+
+    pub fn days(&self) -> i64 {
+        (self.to - self.from).num_days() + 1
+    }
+
+    pub fn dates(&self) -> Vec<NaiveDate> {
+        self.from.iter_days().take(self.days() as usize).collect()
+    }
+
+    // How many times `day` comes round in the range
+    pub fn occurrences(&self, day: Day) -> i64 {
+        let first = self.from.weekday().num_days_from_sunday() as i64;
+        let offset = (i16::from(day) as i64 - first).rem_euclid(7);
+        let days = self.days();
+        if offset >= days {
+            return 0;
+        }
+        (days - offset - 1) / 7 + 1
+    }
+
+    // The range split into weeks beginning on `week_start`. The first and
+    // last weeks are cut short when the range starts or ends mid-week.
+    pub fn weeks(&self, week_start: Day) -> Vec<DateRange> {
+        let mut weeks = Vec::new();
+        let mut from = self.from;
+        while from <= self.to {
+            let to =
+                (week_beginning(from, week_start) + Days::new(6)).min(self.to);
+            weeks.push(DateRange { from, to });
+            from = to + Days::new(1);
+        }
+        weeks
+    }
+}
+
+// A member's paid minutes in one week of a report
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekAttendance {
+    pub week: DateRange,
+    pub scheduled_minutes: i64,
+    pub missed_minutes: i64,
+    // The member's contract pro rata for the days of the week in the report
+    pub contracted_minutes: Option<i64>,
+}
+
+impl WeekAttendance {
+    pub fn completed_minutes(&self) -> i64 {
+        self.scheduled_minutes - self.missed_minutes
+    }
+
+    // Scheduled minutes over the contract, or under when negative
+    pub fn contract_delta(&self) -> Option<i64> {
+        self.contracted_minutes
+            .map(|contracted| self.scheduled_minutes - contracted)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberAttendance {
+    pub member_id: MemberId,
+    pub member_name: MemberName,
+    pub contracted_hours: Option<ContractedHours>,
+    pub scheduled_shifts: i64,
+    pub absences: i64,
+    // Paid minutes, so unpaid breaks don't count
+    pub scheduled_minutes: i64,
+    pub missed_minutes: i64,
+    // Completed minutes on shifts with each tag
+    pub tag_minutes: BTreeMap<String, i64>,
+    // In weeks beginning on the project's week start
+    pub weeks: Vec<WeekAttendance>,
+}
+
+impl MemberAttendance {
+    // Add `minutes` worked on `shift` to the totals for its tags, or take
+    // them off when negative
+    fn add_tag_minutes(&mut self, shift: &Shift, minutes: i64) {
+        for tag in &shift.tags {
+            *self.tag_minutes.entry(tag.as_ref().clone()).or_default() +=
+                minutes;
+        }
+    }
+
+    pub fn completed_minutes(&self) -> i64 {
+        self.scheduled_minutes - self.missed_minutes
+    }
+
+    // None when the member has no contracted hours
+    pub fn contracted_minutes(&self) -> Option<i64> {
+        self.weeks.iter().map(|week| week.contracted_minutes).sum()
+    }
+
+    // Scheduled minutes over the contract, or under when negative
+    pub fn contract_delta(&self) -> Option<i64> {
+        self.contracted_minutes()
+            .map(|contracted| self.scheduled_minutes - contracted)
+    }
+
+    // None when nothing was scheduled
+    pub fn completed_percentage(&self) -> Option<f64> {
+        if self.scheduled_minutes == 0 {
+            return None;
+        }
+        Some(
+            self.completed_minutes() as f64 * 100.0
+                / self.scheduled_minutes as f64,
+        )
+    }
+}
+
+// Each member's attendance over `range`. There is no record of shifts being
+// worked, so every scheduled shift without an absence counts as completed.
+pub fn attendance_report(
+    project: &Project,
+    absences: &[Absence],
+    range: &DateRange,
+) -> Vec<MemberAttendance> {
+    let weeks = range.weeks(project.week_start);
+    let mut shifts = HashMap::<Uuid, &Shift>::new();
+    let mut report: Vec<MemberAttendance> = project
+        .members
+        .iter()
+        .map(|member| {
+            let mut attendance = MemberAttendance {
+                member_id: member.member_id.clone(),
+                member_name: member.member_name.clone(),
+                contracted_hours: member.contracted_hours,
+                scheduled_shifts: 0,
+                absences: 0,
+                scheduled_minutes: 0,
+                missed_minutes: 0,
+                tag_minutes: BTreeMap::new(),
+                weeks: weeks
+                    .iter()
+                    .map(|week| WeekAttendance {
+                        week: *week,
+                        scheduled_minutes: 0,
+                        missed_minutes: 0,
+                        contracted_minutes: member
+                            .contracted_hours
+                            .map(|hours| hours.minutes_over(week.days())),
+                    })
+                    .collect(),
+            };
+            for shift in &member.shifts {
+                let occurrences = range.occurrences(shift.day);
+                shifts.insert(*shift.id.as_ref(), shift);
+                let minutes = occurrences * shift.paid_length() as i64;
+                attendance.scheduled_shifts += occurrences;
+                attendance.scheduled_minutes += minutes;
+                attendance.add_tag_minutes(shift, minutes);
+                for week in attendance.weeks.iter_mut() {
+                    week.scheduled_minutes += week.week.occurrences(shift.day)
+                        * shift.paid_length() as i64;
+                }
+            }
+            attendance
+        })
+        .collect();
+
+    for absence in absences.iter().filter(|a| range.contains(a.date)) {
+        let Some(shift) = shifts.get(absence.shift_id.as_ref()) else {
+            continue;
+        };
+        if let Some(attendance) = report
+            .iter_mut()
+            .find(|attendance| attendance.member_id == absence.member_id)
+        {
+            let minutes = shift.paid_length() as i64;
+            attendance.absences += 1;
+            attendance.missed_minutes += minutes;
+            attendance.add_tag_minutes(shift, -minutes);
+            if let Some(week) = attendance
+                .weeks
+                .iter_mut()
+                .find(|week| week.week.contains(absence.date))
+            {
+                week.missed_minutes += minutes;
+            }
+        }
+    }
+
+    report.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+    report
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        time::parse_date, AbsenceReason, BreakRule, Minute, ProjectId,
+        ProjectMember, ProjectName, ProjectSettings, ShiftTag,
+    };
+
+    fn range(from: &str, to: &str) -> DateRange {
+        DateRange::new(parse_date(from).unwrap(), parse_date(to).unwrap())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_date_range_new() {
+        assert!(DateRange::new(
+            parse_date("2025-10-21").unwrap(),
+            parse_date("2025-10-20").unwrap()
+        )
+        .is_err());
+        assert!(DateRange::new(
+            parse_date("2025-01-01").unwrap(),
+            parse_date("2026-01-01").unwrap()
+        )
+        .is_ok());
+        assert!(DateRange::new(
+            parse_date("2024-01-01").unwrap(),
+            parse_date("2025-01-01").unwrap()
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_occurrences() {
+        // Monday 20 to Sunday 26 October 2025
+        let week = range("2025-10-20", "2025-10-26");
+        assert_eq!(week.occurrences(Day::Monday), 1);
+        assert_eq!(week.occurrences(Day::Sunday), 1);
+
+        let single_day = range("2025-10-21", "2025-10-21");
+        assert_eq!(single_day.occurrences(Day::Tuesday), 1);
+        assert_eq!(single_day.occurrences(Day::Monday), 0);
+
+        // Wednesday 1 to Friday 31 October 2025
+        let october = range("2025-10-01", "2025-10-31");
+        assert_eq!(october.occurrences(Day::Wednesday), 5);
+        assert_eq!(october.occurrences(Day::Friday), 5);
+        assert_eq!(october.occurrences(Day::Monday), 4);
+    }
+
+    #[test]
+    fn test_weeks() {
+        let bounds = |weeks: Vec<DateRange>| {
+            weeks
+                .iter()
+                .map(|week| (week.from().to_string(), week.to().to_string()))
+                .collect::<Vec<_>>()
+        };
+        let pair = |from: &str, to: &str| (from.to_owned(), to.to_owned());
+
+        // Wednesday 1 to Friday 31 October 2025
+        let october = range("2025-10-01", "2025-10-31");
+        assert_eq!(
+            bounds(october.weeks(Day::Monday)),
+            vec![
+                pair("2025-10-01", "2025-10-05"),
+                pair("2025-10-06", "2025-10-12"),
+                pair("2025-10-13", "2025-10-19"),
+                pair("2025-10-20", "2025-10-26"),
+                pair("2025-10-27", "2025-10-31"),
+            ]
+        );
+        // Starting on the range's first day gives whole weeks
+        assert_eq!(
+            bounds(october.weeks(Day::Wednesday))[..2],
+            [
+                pair("2025-10-01", "2025-10-07"),
+                pair("2025-10-08", "2025-10-14")
+            ]
+        );
+
+        // Saturday to Monday wraps round a Sunday week start
+        let weekend = range("2025-10-18", "2025-10-20");
+        assert_eq!(
+            bounds(weekend.weeks(Day::Sunday)),
+            vec![
+                pair("2025-10-18", "2025-10-18"),
+                pair("2025-10-19", "2025-10-20")
+            ]
+        );
+        assert_eq!(
+            bounds(weekend.weeks(Day::Monday)),
+            vec![
+                pair("2025-10-18", "2025-10-19"),
+                pair("2025-10-20", "2025-10-20")
+            ]
+        );
+    }
+
+    #[test]
+    fn test_attendance_report() {
+        let settings = ProjectSettings {
+            break_rules: vec![BreakRule::new(360, 30, false).unwrap()],
+            ..Default::default()
+        };
+        let ted = MemberId::default();
+        let mut shift = settings
+            .new_shift(
+                ted.clone(),
+                Day::Monday,
+                Minute::parse(540).unwrap(),
+                Minute::parse(1020).unwrap(),
+                false,
+            )
+            .unwrap();
+        shift.tags = vec![ShiftTag::parse("training").unwrap()];
+        let project = Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Monday,
+            vec![
+                ProjectMember {
+                    contracted_hours: Some(
+                        ContractedHours::parse(14.0).unwrap(),
+                    ),
+                    ..ProjectMember::new(
+                        ted,
+                        MemberName::parse("Ted".to_owned()).unwrap(),
+                        vec![shift.clone()],
+                    )
+                },
+                ProjectMember::new(
+                    MemberId::default(),
+                    MemberName::parse("Dougal".to_owned()).unwrap(),
+                    Vec::new(),
+                ),
+            ],
+        );
+        let absence = |date: &str| {
+            Absence::new(
+                &shift,
+                parse_date(date).unwrap(),
+                AbsenceReason::Sickness,
+            )
+            .unwrap()
+        };
+        // The second absence is outside the range
+        let absences = vec![absence("2025-10-06"), absence("2025-11-03")];
+
+        let report = attendance_report(
+            &project,
+            &absences,
+            &range("2025-10-01", "2025-10-31"),
+        );
+
+        assert_eq!(report.len(), 2);
+        assert_eq!(report[0].member_name.as_ref(), "Dougal");
+        assert_eq!(report[0].completed_percentage(), None);
+        assert_eq!(report[0].contract_delta(), None);
+
+        let ted = &report[1];
+        assert_eq!(ted.scheduled_shifts, 4);
+        assert_eq!(ted.absences, 1);
+        assert_eq!(ted.scheduled_minutes, 4 * 450);
+        assert_eq!(ted.completed_minutes(), 3 * 450);
+        assert_eq!(ted.completed_percentage(), Some(75.0));
+        assert_eq!(
+            ted.tag_minutes,
+            BTreeMap::from([("training".to_owned(), 3 * 450)])
+        );
+
+        // Monday weeks, the first from Wednesday 1 and the last to Friday 31
+        let weekly: Vec<(i64, i64)> = ted
+            .weeks
+            .iter()
+            .map(|week| (week.scheduled_minutes, week.completed_minutes()))
+            .collect();
+        assert_eq!(
+            weekly,
+            vec![(0, 0), (450, 0), (450, 450), (450, 450), (450, 450)]
+        );
+
+        // 14 hours a week is 120 minutes a day, so the five-day weeks
+        // either end are pro rata
+        let deltas: Vec<Option<i64>> = ted
+            .weeks
+            .iter()
+            .map(WeekAttendance::contract_delta)
+            .collect();
+        assert_eq!(
+            deltas,
+            vec![Some(-600), Some(-390), Some(-390), Some(-390), Some(-150)]
+        );
+        assert_eq!(ted.contracted_minutes(), Some(31 * 120));
+        assert_eq!(ted.contract_delta(), Some(4 * 450 - 31 * 120));
+    }
+}
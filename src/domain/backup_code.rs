@@ -0,0 +1,162 @@
+use super::ValidationError;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use rand::Rng;
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+
+pub const BACKUP_CODE_COUNT: usize = 10;
+const BACKUP_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+const BACKUP_CODE_GROUP_LENGTH: usize = 5;
+
+// A single-use code that stands in for an emailed 2FA code, for when the
+// user can't get at their email. Written as two groups of five characters,
+// e.g. "k7mxq-2pbta", leaving out look-alikes such as 0/o and 1/l.
+#[derive(Debug, Clone)]
+pub struct BackupCode(Secret<String>);
+
+impl PartialEq for BackupCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl BackupCode {
+    pub fn parse(code: Secret<String>) -> Result<Self, ValidationError> {
+        let code = code.expose_secret().trim().to_lowercase();
+        let valid = code
+            .split('-')
+            .map(str::len)
+            .eq([BACKUP_CODE_GROUP_LENGTH, BACKUP_CODE_GROUP_LENGTH])
+            && code
+                .bytes()
+                .all(|b| b == b'-' || BACKUP_CODE_ALPHABET.contains(&b));
+        if !valid {
+            return Err(ValidationError::new(
+                "Backup code is not valid".to_string(),
+            ));
+        }
+        Ok(Self(Secret::new(code)))
+    }
+
+    // A fresh set of codes, handed to the user once and only stored hashed
+    pub fn generate_set() -> Vec<Self> {
+        (0..BACKUP_CODE_COUNT).map(|_| Self::default()).collect()
+    }
+}
+
+impl Default for BackupCode {
+    fn default() -> Self {
+        let mut rng = rand::thread_rng();
+        let mut group = || {
+            (0..BACKUP_CODE_GROUP_LENGTH)
+                .map(|_| {
+                    let i = rng.gen_range(0..BACKUP_CODE_ALPHABET.len());
+                    BACKUP_CODE_ALPHABET[i] as char
+                })
+                .collect::<String>()
+        };
+        let code = format!("{}-{}", group(), group());
+        BackupCode(Secret::new(code))
+    }
+}
+
+impl AsRef<Secret<String>> for BackupCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// How a backup code is stored. The codes are random rather than chosen by
+// the user, so a keyed HMAC is enough to keep them from being read out of
+// the database, and unlike Argon2 it is the same every time, so a code can
+// be looked up by its hash.
+#[derive(Debug, Clone)]
+pub struct BackupCodeHash(Secret<String>);
+
+impl PartialEq for BackupCodeHash {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl BackupCodeHash {
+    pub fn from_code(code: &BackupCode, secret: &Secret<String>) -> Self {
+        let mut mac =
+            Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+                .expect("HMAC takes keys of any length");
+        mac.update(b"backup-code:");
+        mac.update(code.0.expose_secret().as_bytes());
+        Self(Secret::new(
+            URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes()),
+        ))
+    }
+}
+
+impl AsRef<Secret<String>> for BackupCodeHash {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_codes() {
+        for (code, expected) in [
+            ("k7mxq-2pbta", "k7mxq-2pbta"),
+            (" K7MXQ-2PBTA ", "k7mxq-2pbta"),
+        ] {
+            let parsed =
+                BackupCode::parse(Secret::new(code.to_string())).expect(code);
+            assert_eq!(parsed.as_ref().expose_secret(), expected);
+        }
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        let invalid_codes = [
+            "",
+            "123456",
+            "k7mxq2pbta",
+            "k7mxq-2pbt",
+            "k7mxq-2pbt0",
+            "k-7mxq2pbta",
+        ];
+        for code in invalid_codes {
+            let error = BackupCode::parse(Secret::new(code.to_string()))
+                .expect_err(code);
+            assert_eq!(error.as_ref(), "Backup code is not valid");
+        }
+    }
+
+    #[test]
+    fn test_generated_codes_are_valid_and_distinct() {
+        let codes = BackupCode::generate_set();
+        assert_eq!(codes.len(), BACKUP_CODE_COUNT);
+        for (i, code) in codes.iter().enumerate() {
+            assert!(BackupCode::parse(code.as_ref().clone()).is_ok());
+            assert!(!codes[i + 1..].contains(code));
+        }
+    }
+
+    #[test]
+    fn test_hash_depends_on_code_and_secret() {
+        let secret = Secret::new("secret".to_owned());
+        let code = BackupCode::default();
+        let hash = BackupCodeHash::from_code(&code, &secret);
+
+        let same_code = BackupCode::parse(code.as_ref().clone()).unwrap();
+        assert_eq!(BackupCodeHash::from_code(&same_code, &secret), hash);
+        assert_ne!(
+            BackupCodeHash::from_code(&BackupCode::default(), &secret),
+            hash
+        );
+        assert_ne!(
+            BackupCodeHash::from_code(&code, &Secret::new("other".to_owned())),
+            hash
+        );
+    }
+}
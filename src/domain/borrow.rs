@@ -0,0 +1,236 @@
+use super::{
+    DepartmentId, MemberId, MemberName, Project, ProjectMember, ShiftSlotId,
+    SlotCoverage,
+};
+
+// A slot its own department can't fill, and who other departments could
+// lend for it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowSuggestion {
+    pub slot_id: ShiftSlotId,
+    pub department_id: DepartmentId,
+    pub open_places: i16,
+    // In name order
+    pub candidates: Vec<BorrowCandidate>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BorrowCandidate {
+    pub member_id: MemberId,
+    pub member_name: MemberName,
+    // The department lending them
+    pub department_id: DepartmentId,
+}
+
+// For each department slot with open places, the members of other
+// departments who could fill them. A member can be lent if they have the
+// slot's skill, aren't working at the time, and their own department has
+// no open places of its own at the time.
+pub fn borrow_suggestions(
+    project: &Project,
+    coverage: &[SlotCoverage],
+) -> Vec<BorrowSuggestion> {
+    coverage
+        .iter()
+        .filter(|short| !short.is_covered())
+        .filter_map(|short| {
+            let department_id = short.slot.department_id.as_ref()?;
+            let short_departments: Vec<&DepartmentId> = coverage
+                .iter()
+                .filter(|other| {
+                    !other.is_covered() && other.slot.overlaps(&short.slot)
+                })
+                .filter_map(|other| other.slot.department_id.as_ref())
+                .collect();
+
+            let mut candidates: Vec<&ProjectMember> = project
+                .members
+                .iter()
+                .filter(|member| {
+                    member.department_id.as_ref().is_some_and(|lender| {
+                        lender != department_id
+                            && !short_departments.contains(&lender)
+                    })
+                })
+                .filter(|member| {
+                    short
+                        .slot
+                        .tag
+                        .as_ref()
+                        .is_none_or(|skill| member.skills.contains(skill))
+                })
+                .filter(|member| !short.assigned.contains(&member.member_id))
+                .filter(|member| {
+                    !member
+                        .shifts
+                        .iter()
+                        .any(|shift| short.slot.clashes_with(shift))
+                })
+                .collect();
+            if candidates.is_empty() {
+                return None;
+            }
+            candidates.sort_by(|a, b| {
+                a.member_name.as_ref().cmp(b.member_name.as_ref())
+            });
+
+            Some(BorrowSuggestion {
+                slot_id: short.slot.slot_id.clone(),
+                department_id: department_id.clone(),
+                open_places: short.open_places(),
+                candidates: candidates
+                    .into_iter()
+                    .filter_map(|member| {
+                        Some(BorrowCandidate {
+                            member_id: member.member_id.clone(),
+                            member_name: member.member_name.clone(),
+                            department_id: member.department_id.clone()?,
+                        })
+                    })
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Day, Minute, ProjectId, ProjectName, ProjectSettings, ShiftSlot,
+        ShiftTag,
+    };
+
+    struct Venue {
+        bar: DepartmentId,
+        kitchen: DepartmentId,
+    }
+
+    impl Venue {
+        fn new() -> Self {
+            Self {
+                bar: DepartmentId::default(),
+                kitchen: DepartmentId::default(),
+            }
+        }
+
+        fn member(
+            &self,
+            name: &str,
+            department_id: &DepartmentId,
+            skills: &[&str],
+        ) -> ProjectMember {
+            ProjectMember {
+                department_id: Some(department_id.clone()),
+                skills: skills
+                    .iter()
+                    .map(|skill| ShiftTag::parse(skill).unwrap())
+                    .collect(),
+                ..ProjectMember::new(
+                    MemberId::default(),
+                    MemberName::parse(name.to_owned()).unwrap(),
+                    Vec::new(),
+                )
+            }
+        }
+
+        fn slot(
+            &self,
+            department_id: &DepartmentId,
+            places: i16,
+            tag: Option<&str>,
+        ) -> SlotCoverage {
+            let mut slot = ShiftSlot::new(
+                ShiftSlotId::default(),
+                ProjectId::default(),
+                Day::Saturday,
+                Minute::parse(540).unwrap(),
+                Minute::parse(1020).unwrap(),
+                places,
+            )
+            .unwrap();
+            slot.department_id = Some(department_id.clone());
+            slot.tag = tag.map(|tag| ShiftTag::parse(tag).unwrap());
+            SlotCoverage {
+                slot,
+                assigned: Vec::new(),
+            }
+        }
+    }
+
+    fn project(members: Vec<ProjectMember>) -> Project {
+        Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Monday,
+            members,
+        )
+    }
+
+    fn names(suggestion: &BorrowSuggestion) -> Vec<&str> {
+        suggestion
+            .candidates
+            .iter()
+            .map(|candidate| candidate.member_name.as_ref().as_str())
+            .collect()
+    }
+
+    #[test]
+    fn test_suggests_skilled_members_of_other_departments() {
+        let venue = Venue::new();
+        let project = project(vec![
+            venue.member("Ted", &venue.kitchen, &["barista"]),
+            venue.member("Dougal", &venue.kitchen, &[]),
+            venue.member("Jack", &venue.bar, &["barista"]),
+        ]);
+        let coverage = vec![venue.slot(&venue.bar, 2, Some("barista"))];
+
+        let suggestions = borrow_suggestions(&project, &coverage);
+
+        assert_eq!(suggestions.len(), 1);
+        assert_eq!(suggestions[0].department_id, venue.bar);
+        assert_eq!(suggestions[0].open_places, 2);
+        assert_eq!(names(&suggestions[0]), vec!["Ted"]);
+        assert_eq!(suggestions[0].candidates[0].department_id, venue.kitchen);
+    }
+
+    #[test]
+    fn test_leaves_out_busy_members_and_short_departments() {
+        let venue = Venue::new();
+        let mut ted = venue.member("Ted", &venue.kitchen, &[]);
+        ted.shifts.push(
+            ProjectSettings::default()
+                .new_shift(
+                    ted.member_id.clone(),
+                    Day::Saturday,
+                    Minute::parse(600).unwrap(),
+                    Minute::parse(700).unwrap(),
+                    false,
+                )
+                .unwrap(),
+        );
+        let project =
+            project(vec![ted, venue.member("Dougal", &venue.kitchen, &[])]);
+
+        // Dougal is free, but the kitchen is short itself
+        let coverage = vec![
+            venue.slot(&venue.bar, 1, None),
+            venue.slot(&venue.kitchen, 1, None),
+        ];
+        assert!(borrow_suggestions(&project, &coverage).is_empty());
+
+        let coverage = vec![venue.slot(&venue.bar, 1, None)];
+        let suggestions = borrow_suggestions(&project, &coverage);
+        assert_eq!(names(&suggestions[0]), vec!["Dougal"]);
+    }
+
+    #[test]
+    fn test_ignores_covered_slots() {
+        let venue = Venue::new();
+        let project = project(vec![venue.member("Ted", &venue.kitchen, &[])]);
+        let mut slot = venue.slot(&venue.bar, 1, None);
+        slot.assigned.push(MemberId::default());
+
+        assert!(borrow_suggestions(&project, &[slot]).is_empty());
+    }
+}
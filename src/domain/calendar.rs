@@ -0,0 +1,247 @@
+use chrono::{DateTime, NaiveDateTime, Utc};
+
+use super::{
+    time::{at_minute, date_of_day, week_beginning},
+    Project, ProjectId,
+};
+
+// Colours for telling projects apart in calendar apps, as a CSS colour name
+// (RFC 7986) and the hex value Apple's apps expect
+const PALETTE: [(&str, &str); 8] = [
+    ("royalblue", "#4169E1"),
+    ("seagreen", "#2E8B57"),
+    ("darkorange", "#FF8C00"),
+    ("crimson", "#DC143C"),
+    ("mediumpurple", "#9370DB"),
+    ("teal", "#008080"),
+    ("goldenrod", "#DAA520"),
+    ("slategray", "#708090"),
+];
+
+// Content lines longer than this many octets are folded (RFC 5545 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+// A project keeps the same colour in every feed
+pub fn project_colour(project_id: &ProjectId) -> (&'static str, &'static str) {
+    let sum = project_id
+        .as_ref()
+        .as_bytes()
+        .iter()
+        .fold(0usize, |sum, byte| sum + *byte as usize);
+    PALETTE[sum % PALETTE.len()]
+}
+
+// An iCalendar feed of every shift in `projects`, each repeating weekly from
+// the project's current week, going by the day its weeks start on. Rotas
+// have no timezone, so times are floating and show at the same wall-clock
+// time wherever the calendar is.
+pub fn rota_calendar(projects: &[Project], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_owned(),
+        "VERSION:2.0".to_owned(),
+        "PRODID:-//rota-manager//Rota//EN".to_owned(),
+        "CALSCALE:GREGORIAN".to_owned(),
+        "METHOD:PUBLISH".to_owned(),
+        "X-WR-CALNAME:Rota".to_owned(),
+    ];
+    let stamp = now.format("%Y%m%dT%H%M%SZ").to_string();
+
+    for project in projects {
+        let project_name = escape_text(project.project_name.as_ref());
+        let (colour_name, colour_hex) = project_colour(&project.project_id);
+        let week_start = week_beginning(now.date_naive(), project.week_start);
+        let rrule = format!(
+            "RRULE:FREQ=WEEKLY;WKST={}",
+            project.week_start.ical_code()
+        );
+
+        for member in &project.members {
+            for shift in &member.shifts {
+                let date = date_of_day(week_start, shift.day);
+                let categories = std::iter::once(project_name.clone())
+                    .chain(
+                        shift.tags.iter().map(|tag| escape_text(tag.as_ref())),
+                    )
+                    .collect::<Vec<_>>()
+                    .join(",");
+
+                lines.extend([
+                    "BEGIN:VEVENT".to_owned(),
+                    format!("UID:{}@rota-manager", shift.id.as_ref()),
+                    format!("DTSTAMP:{}", stamp),
+                    format!(
+                        "DTSTART:{}",
+                        format_local(at_minute(date, &shift.start_time))
+                    ),
+                    format!(
+                        "DTEND:{}",
+                        format_local(at_minute(date, &shift.end_time))
+                    ),
+                    rrule.clone(),
+                    format!(
+                        "SUMMARY:{} ({})",
+                        escape_text(member.member_name.as_ref()),
+                        project_name
+                    ),
+                    format!("CATEGORIES:{}", categories),
+                    format!("COLOR:{}", colour_name),
+                    format!("X-APPLE-CALENDAR-COLOR:{}", colour_hex),
+                    "END:VEVENT".to_owned(),
+                ]);
+            }
+        }
+    }
+    lines.push("END:VCALENDAR".to_owned());
+
+    lines
+        .iter()
+        .map(|line| fold_line(line))
+        .collect::<Vec<_>>()
+        .join("\r\n")
+        + "\r\n"
+}
+
+fn format_local(local: NaiveDateTime) -> String {
+    local.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+// Split a long line into 75-octet pieces, never inside a character, with
+// each continuation starting with a space
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Day, MemberId, MemberName, Minute, ProjectMember, ProjectName, Shift,
+        ShiftTag,
+    };
+
+    // Monday 20 October 2025
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2025-10-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn project(name: &str) -> Project {
+        project_with_shift(name, Day::Sunday, Day::Tuesday)
+    }
+
+    fn project_with_shift(name: &str, week_start: Day, day: Day) -> Project {
+        let member_id = MemberId::default();
+        let mut shift = Shift::new(
+            member_id.clone(),
+            day,
+            Minute::parse(1260).unwrap(),
+            Minute::parse(1440).unwrap(),
+        )
+        .unwrap();
+        shift.tags = vec![ShiftTag::parse("bar").unwrap()];
+        Project::new(
+            ProjectId::default(),
+            ProjectName::parse(name).unwrap(),
+            week_start,
+            vec![ProjectMember::new(
+                member_id,
+                MemberName::parse("Ted".to_owned()).unwrap(),
+                vec![shift],
+            )],
+        )
+    }
+
+    #[test]
+    fn test_rota_calendar() {
+        let projects = [project("Craggy Island"), project("Rugged, Island")];
+        let calendar = rota_calendar(&projects, now());
+
+        assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+        assert!(calendar.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+        assert!(calendar.contains("DTSTAMP:20251020T120000Z\r\n"));
+        // Midnight at the end of Tuesday is the start of Wednesday
+        assert!(calendar.contains("DTSTART:20251021T210000\r\n"));
+        assert!(calendar.contains("DTEND:20251022T000000\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=WEEKLY;WKST=SU\r\n"));
+        assert!(calendar.contains("SUMMARY:Ted (Craggy Island)\r\n"));
+        assert!(calendar.contains("CATEGORIES:Rugged\\, Island,bar\r\n"));
+        let (_, hex) = project_colour(&projects[0].project_id);
+        assert!(calendar.contains(&format!("X-APPLE-CALENDAR-COLOR:{}", hex)));
+    }
+
+    #[test]
+    fn test_rota_calendar_follows_week_start() {
+        // Sunday starts the week, so this week's Sunday has been
+        let calendar = rota_calendar(
+            &[project_with_shift(
+                "Craggy Island",
+                Day::Sunday,
+                Day::Sunday,
+            )],
+            now(),
+        );
+        assert!(calendar.contains("DTSTART:20251019T210000\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=WEEKLY;WKST=SU\r\n"));
+
+        // Sunday ends a Monday week, so this week's Sunday is still to come
+        let calendar = rota_calendar(
+            &[project_with_shift(
+                "Craggy Island",
+                Day::Monday,
+                Day::Sunday,
+            )],
+            now(),
+        );
+        assert!(calendar.contains("DTSTART:20251026T210000\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=WEEKLY;WKST=MO\r\n"));
+
+        // A Wednesday week began last Wednesday and runs until tomorrow
+        let calendar = rota_calendar(
+            &[project_with_shift(
+                "Craggy Island",
+                Day::Wednesday,
+                Day::Tuesday,
+            )],
+            now(),
+        );
+        assert!(calendar.contains("DTSTART:20251021T210000\r\n"));
+        assert!(calendar.contains("RRULE:FREQ=WEEKLY;WKST=WE\r\n"));
+    }
+
+    #[test]
+    fn test_fold_line() {
+        let short = "SUMMARY:Ted";
+        assert_eq!(fold_line(short), short);
+
+        let long = format!("SUMMARY:{}", "é".repeat(70));
+        let folded = fold_line(&long);
+        for line in folded.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS);
+        }
+        assert_eq!(folded.replace("\r\n ", ""), long);
+    }
+
+    #[test]
+    fn test_escape_text() {
+        assert_eq!(escape_text("a;b,c\\d\ne"), "a\\;b\\,c\\\\d\\ne");
+    }
+}
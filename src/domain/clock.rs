@@ -0,0 +1,7 @@
+use chrono::{DateTime, Utc};
+
+// Where the current time comes from, so anything that depends on it can be
+// tested at a time of the test's choosing
+pub trait Clock {
+    fn now(&self) -> DateTime<Utc>;
+}
@@ -0,0 +1,193 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::uuid_id, MemberId, ProjectId, ProjectMember, ShiftId, UserId,
+    ValidationError,
+};
+
+const MAX_BODY_LENGTH: usize = 1000;
+// How much of a comment is quoted in the text telling a member they were
+// mentioned
+const MAX_EXCERPT_LENGTH: usize = 100;
+
+uuid_id!(CommentId, "comment");
+
+// What someone had to say, e.g. "@Dougal can you cover Ted on Monday?"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CommentBody(#[schemars(length(min = 1, max = 1000))] String);
+
+impl CommentBody {
+    pub fn parse(body: &str) -> Result<Self, ValidationError> {
+        let body = body.trim();
+        if body.is_empty() {
+            return Err(ValidationError::new(String::from(
+                "Comments cannot be empty",
+            )));
+        }
+        if body.chars().count() > MAX_BODY_LENGTH {
+            return Err(ValidationError::new(format!(
+                "Comments cannot be longer than {} characters",
+                MAX_BODY_LENGTH
+            )));
+        }
+        Ok(Self(body.to_owned()))
+    }
+
+    // Members mentioned by name after an "@", in the order they're first
+    // mentioned. Longer names are tried first, so "@Ted Crilly" isn't taken
+    // for "@Ted".
+    pub fn mentions(&self, members: &[ProjectMember]) -> Vec<MemberId> {
+        let mut members: Vec<_> = members
+            .iter()
+            .map(|member| {
+                (
+                    member.member_name.as_ref().to_lowercase(),
+                    &member.member_id,
+                )
+            })
+            .collect();
+        members.sort_by_key(|(name, _)| std::cmp::Reverse(name.len()));
+
+        let body = self.0.to_lowercase();
+        let mut mentions = Vec::new();
+        for (at, _) in body.match_indices('@') {
+            let rest = &body[at + 1..];
+            let mentioned = members.iter().find(|(name, _)| {
+                rest.strip_prefix(name.as_str()).is_some_and(|after| {
+                    !after.starts_with(|c: char| c.is_alphanumeric())
+                })
+            });
+            if let Some((_, member_id)) = mentioned {
+                if !mentions.contains(*member_id) {
+                    mentions.push((*member_id).clone());
+                }
+            }
+        }
+        mentions
+    }
+}
+
+impl AsRef<String> for CommentBody {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// A comment on a project, or on one of its shifts when `shift_id` is set
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    pub comment_id: CommentId,
+    pub project_id: ProjectId,
+    pub shift_id: Option<ShiftId>,
+    pub author_id: UserId,
+    pub body: CommentBody,
+    pub mentions: Vec<MemberId>,
+    pub posted_at: DateTime<Utc>,
+}
+
+impl Comment {
+    pub fn new(
+        project_id: ProjectId,
+        shift_id: Option<ShiftId>,
+        author_id: UserId,
+        body: CommentBody,
+        members: &[ProjectMember],
+        now: DateTime<Utc>,
+    ) -> Self {
+        let mentions = body.mentions(members);
+        Self {
+            comment_id: CommentId::default(),
+            project_id,
+            shift_id,
+            author_id,
+            body,
+            mentions,
+            posted_at: now,
+        }
+    }
+
+    // The text sent to members mentioned in the comment
+    pub fn mention_message(&self, project_name: &str) -> String {
+        let body = self.body.as_ref();
+        let excerpt = if body.chars().count() > MAX_EXCERPT_LENGTH {
+            let cut: String = body.chars().take(MAX_EXCERPT_LENGTH).collect();
+            format!("{}…", cut.trim_end())
+        } else {
+            body.clone()
+        };
+        format!(
+            "You were mentioned in a comment on the {} rota: \"{}\"",
+            project_name, excerpt
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::MemberName;
+
+    fn member(name: &str) -> ProjectMember {
+        ProjectMember::new(
+            MemberId::default(),
+            MemberName::parse(String::from(name)).unwrap(),
+            Vec::new(),
+        )
+    }
+
+    #[test]
+    fn test_parse_body() {
+        assert_eq!(
+            CommentBody::parse(" Who has the keys?\n").unwrap().as_ref(),
+            "Who has the keys?"
+        );
+        assert!(CommentBody::parse("  ").is_err());
+        assert!(CommentBody::parse(&"a".repeat(1001)).is_err());
+        assert!(CommentBody::parse(&"a".repeat(1000)).is_ok());
+    }
+
+    #[test]
+    fn test_mentions() {
+        let ted = member("Ted");
+        let ted_crilly = member("Ted Crilly");
+        let dougal = member("Dougal");
+        let members = [ted.clone(), ted_crilly.clone(), dougal.clone()];
+        let mentions =
+            |body: &str| CommentBody::parse(body).unwrap().mentions(&members);
+
+        assert_eq!(
+            mentions("@dougal can you cover @Ted Crilly? Thanks @Dougal"),
+            [dougal.member_id.clone(), ted_crilly.member_id.clone()]
+        );
+        assert_eq!(mentions("@Ted, the keys"), [ted.member_id]);
+        // Only whole names count
+        assert!(mentions("@Teddy or ted@example.com").is_empty());
+        assert!(mentions("No mentions here").is_empty());
+    }
+
+    #[test]
+    fn test_mention_message() {
+        let comment = |body: &str| {
+            Comment::new(
+                ProjectId::default(),
+                None,
+                UserId::default(),
+                CommentBody::parse(body).unwrap(),
+                &[],
+                Utc::now(),
+            )
+        };
+
+        assert_eq!(
+            comment("@Dougal can you cover Monday?")
+                .mention_message("Craggy Island"),
+            "You were mentioned in a comment on the Craggy Island rota: \
+             \"@Dougal can you cover Monday?\""
+        );
+        let message =
+            comment(&"a".repeat(150)).mention_message("Craggy Island");
+        assert!(message.ends_with(&format!("{}…\"", "a".repeat(100))));
+    }
+}
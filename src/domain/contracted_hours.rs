@@ -0,0 +1,67 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+// Every hour of the week
+const MAX_CONTRACTED_HOURS: f64 = 168.0;
+
+// Paid hours a week a member is contracted for, to the nearest minute
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ContractedHours(#[schemars(range(min = 0, max = 168))] f64);
+
+impl ContractedHours {
+    pub fn parse(hours: f64) -> Result<Self, ValidationError> {
+        if !(0.0..=MAX_CONTRACTED_HOURS).contains(&hours) {
+            return Err(ValidationError::new(format!(
+                "Contracted hours must be between 0 and {} a week",
+                MAX_CONTRACTED_HOURS
+            )));
+        }
+        Ok(Self((hours * 60.0).round() / 60.0))
+    }
+
+    pub fn minutes_per_week(&self) -> i64 {
+        (self.0 * 60.0).round() as i64
+    }
+
+    // The contract's share of `days` days, for weeks cut short
+    pub fn minutes_over(&self, days: i64) -> i64 {
+        self.minutes_per_week() * days / 7
+    }
+}
+
+impl AsRef<f64> for ContractedHours {
+    fn as_ref(&self) -> &f64 {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_contracted_hours_range() {
+        for hours in [0.0, 37.5, 168.0] {
+            assert!(ContractedHours::parse(hours).is_ok());
+        }
+        for hours in [-1.0, 168.5, f64::NAN, f64::INFINITY] {
+            assert!(ContractedHours::parse(hours).is_err());
+        }
+    }
+
+    #[test]
+    fn test_contracted_hours_round_to_the_minute() {
+        let hours = ContractedHours::parse(37.504).unwrap();
+        assert_eq!(hours.minutes_per_week(), 2250);
+        assert_eq!(*hours.as_ref(), 37.5);
+    }
+
+    #[test]
+    fn test_contracted_minutes_over_part_of_a_week() {
+        let hours = ContractedHours::parse(35.0).unwrap();
+        assert_eq!(hours.minutes_over(7), 2100);
+        assert_eq!(hours.minutes_over(3), 900);
+    }
+}
@@ -0,0 +1,259 @@
+use chrono::{Datelike, Days, NaiveDate, NaiveDateTime, TimeDelta, Timelike};
+
+use super::ValidationError;
+
+// How far ahead to look for the next run. Long enough for a schedule that
+// only runs on 29 February, on a day of the week, to come round.
+const SEARCH_DAYS: u64 = 366 * 28;
+
+const MONTHS: [&str; 12] = [
+    "JAN", "FEB", "MAR", "APR", "MAY", "JUN", "JUL", "AUG", "SEP", "OCT",
+    "NOV", "DEC",
+];
+const DAYS: [&str; 7] = ["SUN", "MON", "TUE", "WED", "THU", "FRI", "SAT"];
+
+// When something recurs, as the five fields of a crontab line: minute, hour,
+// day of the month, month and day of the week, e.g. "0 17 * * FRI" for 17:00
+// every Friday. Fields take `*`, numbers, names, lists, ranges and steps.
+// As in cron, when both day fields are restricted either can match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CronSchedule {
+    expression: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    any_day_of_month: bool,
+    any_day_of_week: bool,
+}
+
+impl CronSchedule {
+    pub fn parse(expression: &str) -> Result<Self, ValidationError> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day_of_month, month, day_of_week] = fields[..]
+        else {
+            return Err(ValidationError::new(String::from(
+                "Schedule must have five fields: minute, hour, day of the \
+                 month, month and day of the week",
+            )));
+        };
+
+        // Sunday can be 0 or 7
+        let days_of_week =
+            parse_field(day_of_week, "day of the week", 0, 7, &DAYS)?;
+        let schedule = Self {
+            expression: fields.join(" "),
+            minutes: parse_field(minute, "minute", 0, 59, &[])?,
+            hours: parse_field(hour, "hour", 0, 23, &[])?,
+            days_of_month: parse_field(
+                day_of_month,
+                "day of the month",
+                1,
+                31,
+                &[],
+            )?,
+            months: parse_field(month, "month", 1, 12, &MONTHS)?,
+            days_of_week: (days_of_week | days_of_week >> 7) & 0x7f,
+            any_day_of_month: day_of_month == "*",
+            any_day_of_week: day_of_week == "*",
+        };
+
+        let epoch = NaiveDate::from_ymd_opt(2000, 1, 1)
+            .expect("2000-01-01 is a valid date")
+            .and_hms_opt(0, 0, 0)
+            .expect("Midnight is a valid time");
+        if schedule.next_after(epoch).is_none() {
+            return Err(ValidationError::new(String::from(
+                "Schedule never runs",
+            )));
+        }
+        Ok(schedule)
+    }
+
+    // The first time the schedule runs after `time`, to the minute
+    pub fn next_after(&self, time: NaiveDateTime) -> Option<NaiveDateTime> {
+        let start =
+            time.with_second(0)?.with_nanosecond(0)? + TimeDelta::minutes(1);
+        (0..SEARCH_DAYS)
+            .filter_map(|offset| {
+                start.date().checked_add_days(Days::new(offset))
+            })
+            .filter(|date| self.runs_on(*date))
+            .find_map(|date| {
+                let from = if date == start.date() {
+                    start.hour() * 60 + start.minute()
+                } else {
+                    0
+                };
+                (from..24 * 60)
+                    .find(|minute| {
+                        has(self.hours, minute / 60)
+                            && has(self.minutes, minute % 60)
+                    })
+                    .and_then(|minute| {
+                        date.and_hms_opt(minute / 60, minute % 60, 0)
+                    })
+            })
+    }
+
+    fn runs_on(&self, date: NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week =
+            has(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.any_day_of_month, self.any_day_of_week) {
+            (true, true) => true,
+            (true, false) => day_of_week,
+            (false, true) => day_of_month,
+            (false, false) => day_of_month || day_of_week,
+        }
+    }
+}
+
+impl AsRef<str> for CronSchedule {
+    fn as_ref(&self) -> &str {
+        &self.expression
+    }
+}
+
+fn has(set: u64, value: u32) -> bool {
+    set & 1 << value != 0
+}
+
+// The values a field allows, as a bit set
+fn parse_field(
+    field: &str,
+    label: &str,
+    min: u32,
+    max: u32,
+    names: &[&str],
+) -> Result<u64, ValidationError> {
+    let invalid = || {
+        ValidationError::new(format!(
+            "Invalid {} in schedule: {}",
+            label, field
+        ))
+    };
+    let value = |text: &str| -> Result<u32, ValidationError> {
+        let value = match names
+            .iter()
+            .position(|name| name.eq_ignore_ascii_case(text))
+        {
+            Some(index) => index as u32 + min,
+            None => text.parse().map_err(|_| invalid())?,
+        };
+        if !(min..=max).contains(&value) {
+            return Err(invalid());
+        }
+        Ok(value)
+    };
+
+    let mut set = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => {
+                (range, step.parse::<u32>().map_err(|_| invalid())?)
+            }
+            None => (item, 1),
+        };
+        if step == 0 {
+            return Err(invalid());
+        }
+        let (first, last) = match range.split_once('-') {
+            _ if range == "*" => (min, max),
+            Some((first, last)) => (value(first)?, value(last)?),
+            // "5/15" runs from 5 to the end of the range
+            None if item.contains('/') => (value(range)?, max),
+            None => {
+                let value = value(range)?;
+                (value, value)
+            }
+        };
+        if first > last {
+            return Err(invalid());
+        }
+        for value in (first..=last).step_by(step as usize) {
+            set |= 1 << value;
+        }
+    }
+    Ok(set)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::time::parse_date;
+
+    fn at(date: &str, hour: u32, minute: u32) -> NaiveDateTime {
+        parse_date(date)
+            .unwrap()
+            .and_hms_opt(hour, minute, 0)
+            .unwrap()
+    }
+
+    fn next(expression: &str, time: NaiveDateTime) -> NaiveDateTime {
+        CronSchedule::parse(expression)
+            .unwrap()
+            .next_after(time)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_parse_normalises_whitespace() {
+        let schedule = CronSchedule::parse("  0 17  * * fri ").unwrap();
+        assert_eq!(schedule.as_ref(), "0 17 * * fri");
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_schedules() {
+        for expression in [
+            "",
+            "0 17 * *",
+            "0 17 * * FRI *",
+            "60 17 * * *",
+            "0 24 * * *",
+            "0 17 0 * *",
+            "0 17 * 13 *",
+            "0 17 * * 8",
+            "0 17 * * FUN",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "0 17 31 2 *",
+        ] {
+            assert!(
+                CronSchedule::parse(expression).is_err(),
+                "{:?}",
+                expression
+            );
+        }
+    }
+
+    #[test]
+    fn test_next_after() {
+        // Monday 20 October 2025
+        let monday = at("2025-10-20", 9, 30);
+        assert_eq!(next("0 17 * * FRI", monday), at("2025-10-24", 17, 0));
+        assert_eq!(next("0 17 * * 5", monday), at("2025-10-24", 17, 0));
+        assert_eq!(next("*/15 * * * *", monday), at("2025-10-20", 9, 45));
+        assert_eq!(next("0 9 * * *", monday), at("2025-10-21", 9, 0));
+        assert_eq!(next("0 9,12 * * *", monday), at("2025-10-20", 12, 0));
+        assert_eq!(next("0 8 * * MON-WED", monday), at("2025-10-21", 8, 0));
+        assert_eq!(next("0 8 1 * *", monday), at("2025-11-01", 8, 0));
+        assert_eq!(next("0 0 * * 7", monday), at("2025-10-26", 0, 0));
+        assert_eq!(next("0 0 * JAN *", monday), at("2026-01-01", 0, 0));
+        // Either day field can match when both are restricted
+        assert_eq!(next("0 0 1 * SAT", monday), at("2025-10-25", 0, 0));
+        assert_eq!(next("0 0 29 2 *", monday), at("2028-02-29", 0, 0));
+    }
+
+    #[test]
+    fn test_next_after_is_strictly_later() {
+        let friday = at("2025-10-24", 17, 0);
+        assert_eq!(next("0 17 * * FRI", friday), at("2025-10-31", 17, 0));
+        let seconds_in = friday + TimeDelta::seconds(59);
+        assert_eq!(next("* * * * *", seconds_in), at("2025-10-24", 17, 1));
+    }
+}
@@ -0,0 +1,163 @@
+use reqwest::Url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{ChatWebhookUrl, ValidationError};
+
+// Where an account's data has to stay, as agreed with enterprise customers.
+// Accounts without one have no restrictions.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum DataRegion {
+    Eu,
+    Uk,
+    Us,
+}
+
+const DATA_REGIONS: [DataRegion; 3] =
+    [DataRegion::Eu, DataRegion::Uk, DataRegion::Us];
+
+impl DataRegion {
+    pub fn parse(region: &str) -> Result<Self, ValidationError> {
+        let region = region.trim().to_lowercase();
+        DATA_REGIONS
+            .iter()
+            .find(|known| known.as_str() == region)
+            .copied()
+            .ok_or_else(|| {
+                ValidationError::new(format!(
+                    "Unsupported data region: {}",
+                    region
+                ))
+            })
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DataRegion::Eu => "eu",
+            DataRegion::Uk => "uk",
+            DataRegion::Us => "us",
+        }
+    }
+}
+
+// The hosts each region's webhooks may be sent to. Regions without a list
+// of their own aren't restricted.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ResidencyRules {
+    webhook_hosts: Vec<(DataRegion, Vec<String>)>,
+}
+
+impl ResidencyRules {
+    // e.g. "eu=hooks.slack.com,chat.example.eu;us=hooks.slack.com". A host
+    // allows its subdomains too.
+    pub fn parse(rules: &str) -> Result<Self, ValidationError> {
+        let mut webhook_hosts: Vec<(DataRegion, Vec<String>)> = Vec::new();
+        for rule in rules.split(';').filter(|rule| !rule.trim().is_empty()) {
+            let (region, hosts) = rule.split_once('=').ok_or_else(|| {
+                ValidationError::new(format!(
+                    "Residency rule must be region=hosts: {}",
+                    rule.trim()
+                ))
+            })?;
+            let region = DataRegion::parse(region)?;
+            if webhook_hosts.iter().any(|(known, _)| *known == region) {
+                return Err(ValidationError::new(format!(
+                    "Data region {} has more than one residency rule",
+                    region.as_str()
+                )));
+            }
+            let hosts = hosts
+                .split(',')
+                .map(|host| host.trim().trim_matches('.').to_lowercase())
+                .filter(|host| !host.is_empty())
+                .collect();
+            webhook_hosts.push((region, hosts));
+        }
+        Ok(Self { webhook_hosts })
+    }
+
+    pub fn check_webhook(
+        &self,
+        region: Option<DataRegion>,
+        url: &ChatWebhookUrl,
+    ) -> Result<(), ValidationError> {
+        let Some(region) = region else {
+            return Ok(());
+        };
+        let Some((_, allowed)) = self
+            .webhook_hosts
+            .iter()
+            .find(|(known, _)| *known == region)
+        else {
+            return Ok(());
+        };
+        let host = Url::parse(url.as_ref())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_lowercase))
+            .unwrap_or_default();
+        let is_allowed = allowed.iter().any(|allowed| {
+            host == *allowed || host.ends_with(&format!(".{}", allowed))
+        });
+        match is_allowed {
+            true => Ok(()),
+            false => Err(ValidationError::new(format!(
+                "Webhooks to {} aren't allowed for data region {}",
+                host,
+                region.as_str()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(url: &str) -> ChatWebhookUrl {
+        ChatWebhookUrl::parse(url.to_owned()).unwrap()
+    }
+
+    #[test]
+    fn test_parse_data_regions() {
+        assert_eq!(DataRegion::parse(" EU ").unwrap(), DataRegion::Eu);
+        assert_eq!(DataRegion::parse("us").unwrap(), DataRegion::Us);
+        assert!(DataRegion::parse("mars").is_err());
+    }
+
+    #[test]
+    fn test_webhooks_are_checked_against_region_hosts() {
+        let rules =
+            ResidencyRules::parse("eu=chat.example.eu, hooks.eu.slack.com;")
+                .unwrap();
+        let eu = Some(DataRegion::Eu);
+
+        assert!(rules
+            .check_webhook(eu, &url("https://chat.example.eu/hook"))
+            .is_ok());
+        assert!(rules
+            .check_webhook(eu, &url("https://team.chat.example.eu/hook"))
+            .is_ok());
+        assert!(rules
+            .check_webhook(eu, &url("https://hooks.slack.com/services/x"))
+            .is_err());
+        assert!(rules
+            .check_webhook(eu, &url("https://evilchat.example.eu/hook"))
+            .is_err());
+
+        // Only regions with rules, and accounts with a region, are restricted
+        let anywhere = url("https://hooks.slack.com/services/x");
+        assert!(rules.check_webhook(Some(DataRegion::Us), &anywhere).is_ok());
+        assert!(rules.check_webhook(None, &anywhere).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_residency_rules() {
+        assert_eq!(ResidencyRules::parse("").unwrap(), Default::default());
+        assert!(ResidencyRules::parse("eu").is_err());
+        assert!(ResidencyRules::parse("mars=example.com").is_err());
+        assert!(ResidencyRules::parse("eu=a.com;eu=b.com").is_err());
+    }
+}
@@ -1,17 +1,40 @@
 use crate::domain::Project;
 
 use super::{
-    Email, LoginAttemptId, Member, MemberId, Password, ProjectId, ProjectName,
-    Shift, TwoFACode, User, UserId,
+    Absence, BackupCodeHash, CapturedRequest, Comment, CommentId, DataRegion,
+    DateRange, Day, Department, DisplayName, Email, EmailReceipt, EntityType,
+    ExternalId, HistoryCursor, IntegrationKey, IntegrationKeyId,
+    IntegrationKeyName, IntegrationUpdate, Invitation, InvitationCode,
+    LegalHold, Locale, LoginAttemptId, Member, MemberContact, MemberId,
+    MemberShiftTotals, Notification, NotificationId, Password, PasswordReset,
+    PasswordResetToken, PendingEmailChange, PhoneNumber, ProjectHistoryEntry,
+    ProjectId, ProjectInclude, ProjectName, ProjectSettings, ProjectSummary,
+    ProjectWindow, QuotaUsage, ReactedRota, Reaction, RemindedShift,
+    ReviewComment, RotaApproval, RotaApprovalId, RotaReaction, RotaVersion,
+    RotaVersionId, Session, SessionId, Shift, ShiftId, ShiftSlot, ShiftSlotId,
+    SlotCoverage, SmsReply, TotpSecret, TwoFACode, UndeliverableEmail,
+    UndeliverableReason, User, UserId, UserPasswordHash, VerificationToken,
+    WeekTemplate, WeekTemplateId,
 };
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use color_eyre::eyre::{Report, Result};
 use secrecy::Secret;
+use std::time::Duration;
 use thiserror::Error;
 
 #[async_trait::async_trait]
 pub trait UserStore {
-    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError>;
+    // With an invitation, the user is only added if it can still be used
+    async fn add_user(
+        &mut self,
+        user: User,
+        invitation: Option<&InvitationCode>,
+    ) -> Result<(), UserStoreError>;
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError>;
+    // Opens every connection the store may use and prepares the lookup
+    // logins make on each, returning how many connections were opened
+    async fn warm_up(&self) -> Result<usize, UserStoreError>;
     async fn validate_user(
         &self,
         email: &Email,
@@ -21,6 +44,190 @@ pub trait UserStore {
         &mut self,
         email: &Email,
     ) -> Result<(), UserStoreError>;
+    // Scramble the user's email and remove their personal details, keeping
+    // their projects and history. The hash should be of a password nobody
+    // knows, so the account can't be signed in to again. Like deleting, this
+    // fails with LegalHold while the account is held.
+    async fn anonymize_user(
+        &mut self,
+        user_id: &UserId,
+        hash: &UserPasswordHash,
+    ) -> Result<(), UserStoreError>;
+    // Confirming an address makes it deliverable again
+    async fn update_email(
+        &mut self,
+        user_id: &UserId,
+        email: &Email,
+    ) -> Result<(), UserStoreError>;
+    async fn update_password(
+        &mut self,
+        user_id: &UserId,
+        hash: &UserPasswordHash,
+    ) -> Result<(), UserStoreError>;
+    // Replaces any codes the user already has
+    async fn set_backup_codes(
+        &mut self,
+        user_id: &UserId,
+        hashes: &[BackupCodeHash],
+    ) -> Result<(), UserStoreError>;
+    // Fails with InvalidCredentials unless the code is one of the user's
+    // unused codes, which is then used up
+    async fn use_backup_code(
+        &mut self,
+        user_id: &UserId,
+        hash: &BackupCodeHash,
+    ) -> Result<(), UserStoreError>;
+    // Replaces any authenticator app the user had set up
+    async fn set_totp_secret(
+        &mut self,
+        user_id: &UserId,
+        secret: &TotpSecret,
+    ) -> Result<(), UserStoreError>;
+    // Fails with InvalidCredentials unless the user has an authenticator app
+    // set up and `code` is its current code, not used before
+    async fn use_totp_code(
+        &mut self,
+        user_id: &UserId,
+        code: &TwoFACode,
+        now: DateTime<Utc>,
+    ) -> Result<(), UserStoreError>;
+    // Whether logging in needs a second factor after the password
+    async fn set_requires_2fa(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError>;
+    // Critical security emails are sent regardless
+    async fn set_security_emails(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError>;
+    // Whether opening notification emails sent to the user may be recorded
+    async fn set_email_open_tracking(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError>;
+    // Only the first open of a tracked delivery is kept
+    async fn record_email_opened(
+        &mut self,
+        delivery_id: i64,
+    ) -> Result<(), UserStoreError>;
+    // Works for any address, not just users'. Marking it again updates the
+    // reason.
+    async fn mark_email_undeliverable(
+        &mut self,
+        email: &Email,
+        reason: UndeliverableReason,
+    ) -> Result<(), UserStoreError>;
+    // None while email to the address is delivered as normal
+    async fn get_undeliverable_email(
+        &self,
+        email: &Email,
+    ) -> Result<Option<UndeliverableEmail>, UserStoreError>;
+    // Newest first, at most NOTIFICATIONS_LIMIT of them
+    async fn get_notifications(
+        &self,
+        user_id: &UserId,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, UserStoreError>;
+    // Oldest first, at most NOTIFICATIONS_LIMIT of them
+    async fn get_notifications_since(
+        &self,
+        user_id: &UserId,
+        cursor: i64,
+    ) -> Result<Vec<Notification>, UserStoreError>;
+    // The cursor of the user's latest notification, or 0 when they have none
+    async fn get_notification_cursor(
+        &self,
+        user_id: &UserId,
+    ) -> Result<i64, UserStoreError>;
+    async fn count_unread_notifications(
+        &self,
+        user_id: &UserId,
+    ) -> Result<i64, UserStoreError>;
+    // Every unread notification when `notification_ids` is None. Returns how
+    // many were marked; ones already read, or not the user's, are skipped.
+    async fn mark_notifications_read(
+        &mut self,
+        user_id: &UserId,
+        notification_ids: Option<&[NotificationId]>,
+    ) -> Result<u64, UserStoreError>;
+    // None until the user has accepted any version
+    async fn get_accepted_policy_version(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<i32>, UserStoreError>;
+    async fn accept_policy(
+        &mut self,
+        user_id: &UserId,
+        version: i32,
+    ) -> Result<(), UserStoreError>;
+    // None when the user hasn't picked a locale
+    async fn get_locale(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<Locale>, UserStoreError>;
+    async fn set_locale(
+        &mut self,
+        user_id: &UserId,
+        locale: Option<Locale>,
+    ) -> Result<(), UserStoreError>;
+    // Replaces both fields, so None clears one
+    async fn set_profile(
+        &mut self,
+        user_id: &UserId,
+        display_name: Option<&DisplayName>,
+        timezone: Option<Tz>,
+    ) -> Result<(), UserStoreError>;
+    // None when the user's data can be kept anywhere
+    async fn get_data_region(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<DataRegion>, UserStoreError>;
+    async fn set_data_region(
+        &mut self,
+        user_id: &UserId,
+        region: Option<DataRegion>,
+    ) -> Result<(), UserStoreError>;
+    // None when the account isn't under legal hold
+    async fn get_legal_hold(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<DateTime<Utc>>, UserStoreError>;
+    // None lifts the hold. Holding an account already held keeps when it was
+    // first placed.
+    async fn set_legal_hold(
+        &mut self,
+        user_id: &UserId,
+        placed_at: Option<DateTime<Utc>>,
+    ) -> Result<(), UserStoreError>;
+    // Oldest first
+    async fn get_legal_holds(&self) -> Result<Vec<LegalHold>, UserStoreError>;
+    async fn add_invitation(
+        &mut self,
+        invitation: &Invitation,
+    ) -> Result<(), UserStoreError>;
+    async fn has_users(&self) -> Result<bool, UserStoreError>;
+    // The user an infrastructure-as-code tool provisioned with `external_id`
+    async fn get_provisioned_user(
+        &self,
+        external_id: &ExternalId,
+    ) -> Result<User, UserStoreError>;
+    // Like add_user, failing with UserAlreadyExists if the address or
+    // `external_id` is taken
+    async fn add_provisioned_user(
+        &mut self,
+        user: User,
+        external_id: &ExternalId,
+    ) -> Result<(), UserStoreError>;
+    // Adds the user as an admin, failing with AlreadyBootstrapped unless
+    // there are no users yet
+    async fn add_first_admin(
+        &mut self,
+        user: User,
+    ) -> Result<(), UserStoreError>;
 }
 
 #[derive(Debug, Error)]
@@ -31,17 +238,52 @@ pub enum UserStoreError {
     UserNotFound,
     #[error("Invalid credentials")]
     InvalidCredentials,
+    #[error("Invitation is unknown, used up or expired")]
+    InvalidInvitation,
+    #[error("Users already exist")]
+    AlreadyBootstrapped,
+    #[error("Account is under legal hold")]
+    LegalHold,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
 
 #[async_trait::async_trait]
 pub trait BannedTokenStore {
-    async fn add_token(&mut self, token: &Secret<String>) -> Result<()>;
+    // Bans are only kept until `expires_at`, when the token would be refused
+    // anyway. One that has already expired is not kept at all.
+    async fn add_token(
+        &mut self,
+        token: &Secret<String>,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()>;
     async fn check_token(
         &self,
         token: &Secret<String>,
     ) -> Result<(), BannedTokenStoreError>;
+    // Bans every token issued for the session, until the last of them
+    // expires
+    async fn ban_session(
+        &mut self,
+        session_id: &SessionId,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()>;
+    async fn check_session(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<(), BannedTokenStoreError>;
+    // Both checks made for every authenticated request, which stores can
+    // answer together
+    async fn check_token_and_session(
+        &self,
+        token: &Secret<String>,
+        session_id: &SessionId,
+    ) -> Result<(), BannedTokenStoreError> {
+        self.check_token(token).await?;
+        self.check_session(session_id).await
+    }
 }
 
 #[derive(Debug, Error)]
@@ -99,12 +341,213 @@ impl PartialEq for TwoFACodeStoreError {
     }
 }
 
+#[async_trait::async_trait]
+pub trait VerificationTokenStore {
+    async fn add_token(
+        &mut self,
+        token: VerificationToken,
+        change: PendingEmailChange,
+    ) -> Result<(), VerificationTokenStoreError>;
+    async fn get_token(
+        &self,
+        token: &VerificationToken,
+    ) -> Result<PendingEmailChange, VerificationTokenStoreError>;
+    async fn remove_token(
+        &mut self,
+        token: &VerificationToken,
+    ) -> Result<(), VerificationTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum VerificationTokenStoreError {
+    #[error("Verification token not found")]
+    TokenNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for VerificationTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait PasswordResetTokenStore {
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        reset: PasswordReset,
+    ) -> Result<(), PasswordResetTokenStoreError>;
+    // Removes the token as it's returned, so it can only be used once
+    async fn take_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<PasswordReset, PasswordResetTokenStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum PasswordResetTokenStoreError {
+    #[error("Password reset token not found")]
+    TokenNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+impl PartialEq for PasswordResetTokenStoreError {
+    fn eq(&self, other: &Self) -> bool {
+        matches!(
+            (self, other),
+            (Self::TokenNotFound, Self::TokenNotFound)
+                | (Self::UnexpectedError(_), Self::UnexpectedError(_))
+        )
+    }
+}
+
+#[async_trait::async_trait]
+pub trait DeprecatedUsageStore {
+    async fn record_usage(
+        &mut self,
+        path: &str,
+        client: &str,
+    ) -> Result<(), DeprecatedUsageStoreError>;
+    async fn get_usage(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, u64)>, DeprecatedUsageStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum DeprecatedUsageStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+// Requests made with chosen users' sessions, kept to help reproduce bugs
+// they report. Capturing stops by itself after REQUEST_CAPTURE_TTL.
+#[async_trait::async_trait]
+pub trait RequestCaptureStore {
+    // Clears anything captured for the user before
+    async fn start_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError>;
+    // What was captured is kept until it expires
+    async fn stop_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError>;
+    async fn is_capturing(
+        &self,
+        user_id: &UserId,
+    ) -> Result<bool, RequestCaptureStoreError>;
+    // Only the latest REQUEST_CAPTURE_LIMIT are kept
+    async fn record(
+        &mut self,
+        user_id: &UserId,
+        request: &CapturedRequest,
+    ) -> Result<(), RequestCaptureStoreError>;
+    // Newest first
+    async fn get_captured(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<CapturedRequest>, RequestCaptureStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum RequestCaptureStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+// Who is signed in where. Sessions are forgotten once they expire.
+#[async_trait::async_trait]
+pub trait SessionStore {
+    async fn add_session(
+        &mut self,
+        session: &Session,
+    ) -> Result<(), SessionStoreError>;
+    // The user's sessions that haven't expired by `now`, newest first
+    async fn get_sessions(
+        &self,
+        user_id: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, SessionStoreError>;
+    // Fails with SessionNotFound unless the session is the user's
+    async fn remove_session(
+        &mut self,
+        user_id: &UserId,
+        session_id: &SessionId,
+    ) -> Result<(), SessionStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum SessionStoreError {
+    #[error("Session not found")]
+    SessionNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+// Leases that keep a background job to one instance at a time. A lease runs
+// out on its own, so an instance that dies holding one doesn't stop the job
+// for good.
+#[async_trait::async_trait]
+pub trait JobLockStore {
+    // None while another holder's lease on the job is still running
+    async fn acquire(
+        &mut self,
+        job: &str,
+        duration: Duration,
+    ) -> Result<Option<JobLease>, JobLockStoreError>;
+    // Does nothing if the lease ran out and the job was taken by another
+    async fn release(
+        &mut self,
+        lease: JobLease,
+    ) -> Result<(), JobLockStoreError>;
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct JobLease {
+    pub job: String,
+    // Tells this holder's lease apart from whoever holds the job next
+    pub token: String,
+}
+
+#[derive(Debug, Error)]
+pub enum JobLockStoreError {
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
 #[async_trait::async_trait]
 pub trait ProjectStore {
     async fn get_project_list(
         &mut self,
         user_id: &UserId,
     ) -> Result<Vec<(ProjectId, ProjectName)>, ProjectStoreError>;
+    // Those of the projects that are the user's, in no particular order
+    async fn get_project_summaries(
+        &mut self,
+        user_id: &UserId,
+        project_ids: &[ProjectId],
+    ) -> Result<Vec<ProjectSummary>, ProjectStoreError>;
+    // How much of each quota the user's projects take up
+    async fn get_quota_usage(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<QuotaUsage, ProjectStoreError>;
+    // Each member's shifts over the seven days from `from`, ordered by name
+    async fn get_upcoming_shift_totals(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        from: NaiveDate,
+    ) -> Result<Vec<MemberShiftTotals>, ProjectStoreError>;
     async fn add_project(
         &mut self,
         user_id: &UserId,
@@ -135,6 +578,25 @@ pub trait ProjectStore {
         user_id: &UserId,
         project_id: &ProjectId,
     ) -> Result<Vec<Member>, ProjectStoreError>;
+    async fn get_member_contact(
+        &mut self,
+        user_id: &UserId,
+        member_id: &MemberId,
+    ) -> Result<MemberContact, ProjectStoreError>;
+    async fn update_member_contact(
+        &mut self,
+        user_id: &UserId,
+        member_id: &MemberId,
+        contact: &MemberContact,
+    ) -> Result<(), ProjectStoreError>;
+    // Queue a text message to every member of the project who has opted in
+    // to SMS. Returns how many were queued.
+    async fn broadcast_sms(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        content: &str,
+    ) -> Result<usize, ProjectStoreError>;
     async fn delete_members(
         &mut self,
         user_id: &UserId,
@@ -145,11 +607,237 @@ pub trait ProjectStore {
         user_id: &UserId,
         shift: &Shift,
     ) -> Result<(), ProjectStoreError>;
+    async fn get_shift(
+        &mut self,
+        user_id: &UserId,
+        shift_id: &ShiftId,
+    ) -> Result<Shift, ProjectStoreError>;
+    // Delete every shift in the project, or only those on `day`, returning
+    // how many there were. A dry run only counts them.
+    async fn clear_shifts(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        day: Option<Day>,
+        dry_run: bool,
+    ) -> Result<u64, ProjectStoreError>;
+    async fn add_absence(
+        &mut self,
+        user_id: &UserId,
+        absence: &Absence,
+    ) -> Result<(), ProjectStoreError>;
+    async fn get_absences(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        range: &DateRange,
+    ) -> Result<Vec<Absence>, ProjectStoreError>;
+    // With its members and all their shifts
     async fn get_project(
         &mut self,
         user_id: &UserId,
         project_id: &ProjectId,
+    ) -> Result<Project, ProjectStoreError> {
+        self.get_project_with(
+            user_id,
+            project_id,
+            ProjectInclude::default(),
+            &ProjectWindow::default(),
+        )
+        .await
+    }
+    // Members that aren't loaded are left out, as are shifts, and so are
+    // those outside the window
+    async fn get_project_with(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        include: ProjectInclude,
+        window: &ProjectWindow,
     ) -> Result<Project, ProjectStoreError>;
+    async fn get_project_settings(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<ProjectSettings, ProjectStoreError>;
+    async fn update_project_settings(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        settings: &ProjectSettings,
+    ) -> Result<(), ProjectStoreError>;
+    // Save `template`, replacing any of the project's templates with the same
+    // name. Returns the ID it was saved under.
+    async fn save_week_template(
+        &mut self,
+        user_id: &UserId,
+        template: &WeekTemplate,
+    ) -> Result<WeekTemplateId, ProjectStoreError>;
+    // In name order
+    async fn get_week_templates(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<WeekTemplate>, ProjectStoreError>;
+    async fn get_week_template(
+        &mut self,
+        user_id: &UserId,
+        template_id: &WeekTemplateId,
+    ) -> Result<WeekTemplate, ProjectStoreError>;
+    // Add `shifts`, laid out from `template`, in one go. With `replace` the
+    // project's other shifts are cleared first. Returns how many were
+    // cleared.
+    async fn apply_week_template(
+        &mut self,
+        user_id: &UserId,
+        template: &WeekTemplate,
+        shifts: &[Shift],
+        replace: bool,
+    ) -> Result<u64, ProjectStoreError>;
+    // Add the members and shifts of a rota imported from another tool in
+    // one go. With `replace` the project's shifts are cleared first. Returns
+    // how many were cleared.
+    async fn import_rota(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        members: &[Member],
+        shifts: &[Shift],
+        replace: bool,
+    ) -> Result<u64, ProjectStoreError>;
+    async fn add_department(
+        &mut self,
+        user_id: &UserId,
+        department: &Department,
+    ) -> Result<(), ProjectStoreError>;
+    // In name order
+    async fn get_departments(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<Department>, ProjectStoreError>;
+    async fn add_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot: &ShiftSlot,
+    ) -> Result<(), ProjectStoreError>;
+    async fn get_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot_id: &ShiftSlotId,
+    ) -> Result<ShiftSlot, ProjectStoreError>;
+    // Each of the project's slots with who fills it, in the order they come
+    // in the week
+    async fn get_slot_coverage(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<SlotCoverage>, ProjectStoreError>;
+    // Add `shift` as filling a place in the slot, as long as one is left
+    // and the shift's member isn't in the slot already
+    async fn fill_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot_id: &ShiftSlotId,
+        shift: &Shift,
+    ) -> Result<(), ProjectStoreError>;
+    async fn publish_rota(
+        &mut self,
+        user_id: &UserId,
+        version: &RotaVersion,
+    ) -> Result<(), ProjectStoreError>;
+    async fn get_rota_version(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        version_id: &RotaVersionId,
+    ) -> Result<RotaVersion, ProjectStoreError>;
+    // The version published most recently, if any has been
+    async fn get_latest_rota_version(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Option<RotaVersion>, ProjectStoreError>;
+    // Oldest first
+    async fn get_rota_reactions(
+        &mut self,
+        user_id: &UserId,
+        version_id: &RotaVersionId,
+    ) -> Result<Vec<RotaReaction>, ProjectStoreError>;
+    // The latest emails about the project, newest first
+    async fn get_project_emails(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<EmailReceipt>, ProjectStoreError>;
+    // Any of the project's approvals still pending are withdrawn, and the
+    // approver is emailed
+    async fn submit_rota(
+        &mut self,
+        user_id: &UserId,
+        approval: &RotaApproval,
+    ) -> Result<(), ProjectStoreError>;
+    // Newest first
+    async fn get_rota_approvals(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<RotaApproval>, ProjectStoreError>;
+    // Approvers needn't own the projects they approve rotas for, so these
+    // find approvals by the signed-in user's email instead. Oldest first.
+    async fn get_pending_approvals(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<Vec<RotaApproval>, ProjectStoreError>;
+    // Approving publishes the rota. Either way the owner is emailed.
+    async fn review_rota(
+        &mut self,
+        user_id: &UserId,
+        approval_id: &RotaApprovalId,
+        approve: bool,
+        comment: Option<&ReviewComment>,
+    ) -> Result<RotaApproval, ProjectStoreError>;
+    // Review several approvals at once, all in one transaction. Approvals
+    // that aren't found or were already reviewed fail on their own, in the
+    // order given; any other error fails the lot.
+    async fn review_rotas(
+        &mut self,
+        user_id: &UserId,
+        approval_ids: &[RotaApprovalId],
+        approve: bool,
+        comment: Option<&ReviewComment>,
+    ) -> Result<Vec<Result<RotaApproval, ProjectStoreError>>, ProjectStoreError>;
+    // Members mentioned in the comment who have opted in to SMS are sent
+    // `mention_message`. Returns how many were.
+    async fn add_comment(
+        &mut self,
+        user_id: &UserId,
+        comment: &Comment,
+        mention_message: &str,
+    ) -> Result<usize, ProjectStoreError>;
+    // Oldest first. Without `shift_id` that's every comment in the project,
+    // including those on its shifts.
+    async fn get_comments(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        shift_id: Option<&ShiftId>,
+    ) -> Result<Vec<Comment>, ProjectStoreError>;
+    async fn delete_comment(
+        &mut self,
+        user_id: &UserId,
+        comment_id: &CommentId,
+    ) -> Result<(), ProjectStoreError>;
+    // Newest first, skipping `offset` entries, or starting after `cursor`
+    async fn get_project_history(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        entity_type: Option<EntityType>,
+        cursor: Option<HistoryCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ProjectHistoryEntry>, ProjectStoreError>;
 }
 
 #[derive(Debug, Error)]
@@ -164,6 +852,30 @@ pub enum ProjectStoreError {
     ProjectIDNotFound,
     #[error("Shift ID exists")]
     ShiftIdExists,
+    #[error("Shift ID not found")]
+    ShiftIDNotFound,
+    #[error("Absence already reported")]
+    AbsenceExists,
+    #[error("Template ID not found")]
+    TemplateIDNotFound,
+    #[error("Rota version ID not found")]
+    VersionIDNotFound,
+    #[error("Approval ID not found")]
+    ApprovalIDNotFound,
+    #[error("Rota has already been reviewed")]
+    AlreadyReviewed,
+    #[error("Comment ID not found")]
+    CommentIDNotFound,
+    #[error("Slot ID not found")]
+    SlotIDNotFound,
+    #[error("Slot has no places left")]
+    SlotFull,
+    #[error("Member already fills a place in the slot")]
+    AlreadyInSlot,
+    #[error("Department ID not found")]
+    DepartmentIDNotFound,
+    #[error("Department name exists")]
+    DepartmentNameExists,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
 }
@@ -176,7 +888,131 @@ impl PartialEq for ProjectStoreError {
                 | (Self::MemberIDNotFound, Self::MemberIDNotFound)
                 | (Self::ProjectIDExists, Self::ProjectIDExists)
                 | (Self::ProjectIDNotFound, Self::ProjectIDNotFound)
+                | (Self::ShiftIDNotFound, Self::ShiftIDNotFound)
+                | (Self::AbsenceExists, Self::AbsenceExists)
+                | (Self::TemplateIDNotFound, Self::TemplateIDNotFound)
+                | (Self::VersionIDNotFound, Self::VersionIDNotFound)
+                | (Self::ApprovalIDNotFound, Self::ApprovalIDNotFound)
+                | (Self::AlreadyReviewed, Self::AlreadyReviewed)
+                | (Self::CommentIDNotFound, Self::CommentIDNotFound)
+                | (Self::SlotIDNotFound, Self::SlotIDNotFound)
+                | (Self::SlotFull, Self::SlotFull)
+                | (Self::AlreadyInSlot, Self::AlreadyInSlot)
+                | (Self::DepartmentIDNotFound, Self::DepartmentIDNotFound)
+                | (Self::DepartmentNameExists, Self::DepartmentNameExists)
                 | (Self::UnexpectedError(_), Self::UnexpectedError(_))
         )
     }
 }
+
+// Members reply to shift reminders and react to published rotas by text, so
+// replies are matched by phone number rather than by a signed-in user
+#[async_trait::async_trait]
+pub trait ShiftReminderStore {
+    // Record `reply` against the latest reminder sent to `phone_number` for
+    // a shift on or after `today`. Asking to swap also emails the project
+    // owner.
+    async fn record_reply(
+        &mut self,
+        phone_number: &PhoneNumber,
+        reply: SmsReply,
+        today: NaiveDate,
+    ) -> Result<RemindedShift, ShiftReminderStoreError>;
+    // Record `reply` against the reminder emailed with `reminder_id`, if
+    // its shift is on or after `today`. Declining also emails the project
+    // owner.
+    async fn record_email_reply(
+        &mut self,
+        reminder_id: i64,
+        reply: SmsReply,
+        today: NaiveDate,
+    ) -> Result<RemindedShift, ShiftReminderStoreError>;
+    // Record `reaction` against the rota published most recently for any
+    // project the member with `phone_number` belongs to
+    async fn record_rota_reaction(
+        &mut self,
+        phone_number: &PhoneNumber,
+        reaction: &Reaction,
+    ) -> Result<ReactedRota, ShiftReminderStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum ShiftReminderStoreError {
+    #[error("Reminder not found")]
+    ReminderNotFound,
+    #[error("Published rota not found")]
+    RotaNotFound,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
+
+// Keys that let no-code tools poll a user's domain events. Tools send the
+// key rather than a session, so keys are looked up by their hash.
+#[async_trait::async_trait]
+pub trait IntegrationStore {
+    async fn add_integration_key(
+        &mut self,
+        key: &IntegrationKey,
+        key_hash: &str,
+    ) -> Result<(), IntegrationStoreError>;
+    // Oldest first
+    async fn get_integration_keys(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<IntegrationKey>, IntegrationStoreError>;
+    async fn delete_integration_key(
+        &mut self,
+        user_id: &UserId,
+        key_id: &IntegrationKeyId,
+    ) -> Result<(), IntegrationStoreError>;
+    // Find the key with `key_hash`, recording that it was used at `now`
+    async fn authenticate_integration_key(
+        &mut self,
+        key_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<IntegrationKey, IntegrationStoreError>;
+    // Up to `limit` of the user's events after `after` that were written
+    // before `settled_before`, oldest first
+    async fn get_integration_updates(
+        &self,
+        user_id: &UserId,
+        after: i64,
+        settled_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<IntegrationUpdate>, IntegrationStoreError>;
+    // Record the last update given with the key, unless it has already been
+    // given a later one
+    async fn save_integration_cursor(
+        &mut self,
+        key_id: &IntegrationKeyId,
+        last_event_id: i64,
+    ) -> Result<(), IntegrationStoreError>;
+    // The key an infrastructure-as-code tool provisioned with `external_id`
+    async fn get_provisioned_integration_key(
+        &self,
+        external_id: &ExternalId,
+    ) -> Result<IntegrationKey, IntegrationStoreError>;
+    // Like add_integration_key, failing with KeyAlreadyExists if
+    // `external_id` is taken
+    async fn add_provisioned_integration_key(
+        &mut self,
+        key: &IntegrationKey,
+        key_hash: &str,
+        external_id: &ExternalId,
+    ) -> Result<(), IntegrationStoreError>;
+    async fn rename_integration_key(
+        &mut self,
+        key_id: &IntegrationKeyId,
+        name: &IntegrationKeyName,
+    ) -> Result<(), IntegrationStoreError>;
+}
+
+#[derive(Debug, Error)]
+pub enum IntegrationStoreError {
+    #[error("Integration key not found")]
+    KeyNotFound,
+    #[error("Integration key already exists")]
+    KeyAlreadyExists,
+    #[error("Unexpected error")]
+    UnexpectedError(#[source] Report),
+}
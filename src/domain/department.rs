@@ -0,0 +1,52 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{id::uuid_id, ProjectId, ValidationError};
+
+uuid_id!(DepartmentId, "department");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DepartmentName(#[schemars(length(min = 1, max = 100))] String);
+
+impl DepartmentName {
+    pub fn parse(name: &str) -> Result<Self, ValidationError> {
+        let name = name.trim();
+        match name.chars().count() {
+            0 => Err(ValidationError::new(String::from(
+                "Department name cannot be empty",
+            ))),
+            x if x > 100 => Err(ValidationError::new(String::from(
+                "Department names cannot be longer than 100 characters",
+            ))),
+            _ => Ok(Self(name.to_owned())),
+        }
+    }
+}
+
+impl AsRef<String> for DepartmentName {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// A sub-team within a project, such as the kitchen at a large venue.
+// Members and slots can each belong to one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Department {
+    pub department_id: DepartmentId,
+    pub project_id: ProjectId,
+    pub department_name: DepartmentName,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_department_names() {
+        assert_eq!(DepartmentName::parse("  Bar ").unwrap().as_ref(), "Bar");
+        assert!(DepartmentName::parse(&"a".repeat(100)).is_ok());
+        assert!(DepartmentName::parse("   ").is_err());
+        assert!(DepartmentName::parse(&"a".repeat(101)).is_err());
+    }
+}
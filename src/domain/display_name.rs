@@ -0,0 +1,53 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+// What rotas and emails call a user, e.g. "Ted Smith"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DisplayName(#[schemars(length(min = 1, max = 100))] String);
+
+impl DisplayName {
+    pub fn parse(name: &str) -> Result<Self, ValidationError> {
+        let name = name.trim();
+        if name.chars().any(char::is_control) {
+            return Err(ValidationError::new(String::from(
+                "Display names cannot contain control characters",
+            )));
+        }
+        match name.chars().count() {
+            0 => Err(ValidationError::new(String::from(
+                "Display name cannot be empty",
+            ))),
+            x if x > 100 => Err(ValidationError::new(String::from(
+                "Display names cannot be longer than 100 characters",
+            ))),
+            _ => Ok(Self(name.to_owned())),
+        }
+    }
+}
+
+impl AsRef<String> for DisplayName {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_display_name() {
+        assert_eq!(
+            DisplayName::parse("  Ted Smith ").unwrap().as_ref(),
+            "Ted Smith"
+        );
+        assert!(DisplayName::parse(&"a".repeat(100)).is_ok());
+
+        for name in ["", "   ", "Ted\nSmith"] {
+            assert!(DisplayName::parse(name).is_err(), "{:?}", name);
+        }
+        assert!(DisplayName::parse(&"a".repeat(101)).is_err());
+    }
+}
@@ -0,0 +1,730 @@
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+
+use chrono::NaiveDate;
+
+use super::{
+    AbsenceReason, Day, EntityType, IntegrationKeyId, IntegrationKeyName,
+    MemberId, MemberName, Minute, ProjectId, ProjectName, ReviewComment,
+    ShiftId, UserId, WeekTemplateName,
+};
+
+// Something a request changed. Stores write these to the outbox in the same
+// transaction as the change and the relay publishes them to the event bus;
+// side effects subscribe to them rather than being called inline.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum DomainEvent {
+    UserSignedUp {
+        user_id: UserId,
+    },
+    UserDeleted {
+        user_id: UserId,
+    },
+    // Deleted in all but name, with their projects and history kept
+    UserAnonymized {
+        user_id: UserId,
+    },
+    UserEmailChanged {
+        user_id: UserId,
+    },
+    UserPasswordChanged {
+        user_id: UserId,
+    },
+    BackupCodeUsed {
+        user_id: UserId,
+        // Unused codes the user has left
+        remaining: i64,
+    },
+    // Replacing any authenticator app set up before
+    AuthenticatorAppSetUp {
+        user_id: UserId,
+    },
+    // By the user, rather than provisioned for them
+    IntegrationKeyCreated {
+        user_id: UserId,
+        key_id: IntegrationKeyId,
+        key_name: IntegrationKeyName,
+    },
+    // 2FA at login was turned on or off
+    TwoFactorChanged {
+        user_id: UserId,
+        enabled: bool,
+    },
+    ProjectCreated {
+        user_id: UserId,
+        project_id: ProjectId,
+        project_name: ProjectName,
+    },
+    MemberAdded {
+        user_id: UserId,
+        project_id: ProjectId,
+        member_id: MemberId,
+        member_name: MemberName,
+    },
+    MemberUpdated {
+        user_id: UserId,
+        project_id: ProjectId,
+        member_id: MemberId,
+        member_name: MemberName,
+    },
+    ShiftCreated {
+        user_id: UserId,
+        project_id: ProjectId,
+        member_id: MemberId,
+        member_name: MemberName,
+        shift_id: ShiftId,
+        day: Day,
+        start_time: Minute,
+        end_time: Minute,
+        #[serde(default)]
+        standby_member_id: Option<MemberId>,
+        #[serde(default)]
+        standby_member_name: Option<MemberName>,
+    },
+    AbsenceReported {
+        user_id: UserId,
+        project_id: ProjectId,
+        shift_id: ShiftId,
+        member_id: MemberId,
+        member_name: MemberName,
+        date: NaiveDate,
+        reason: AbsenceReason,
+        // Without a standby member the shift is open for anyone to claim
+        standby_member_id: Option<MemberId>,
+        standby_member_name: Option<MemberName>,
+    },
+    ShiftsCleared {
+        user_id: UserId,
+        project_id: ProjectId,
+        // Only this day's shifts were cleared, rather than the whole week
+        day: Option<Day>,
+        shifts: u64,
+    },
+    WeekTemplateApplied {
+        user_id: UserId,
+        project_id: ProjectId,
+        template_name: WeekTemplateName,
+        shifts: u64,
+        // Shifts cleared to make way for the template's
+        replaced: u64,
+    },
+    // From a rota exported by another tool
+    RotaImported {
+        user_id: UserId,
+        project_id: ProjectId,
+        // Members added for names that weren't in the project
+        members: u64,
+        shifts: u64,
+        // Shifts cleared to make way for the imported ones
+        replaced: u64,
+    },
+    RotaPublished {
+        user_id: UserId,
+        project_id: ProjectId,
+        week_of: NaiveDate,
+        shifts: u64,
+        // Published by the project's schedule rather than by hand
+        automatic: bool,
+    },
+    RotaSubmitted {
+        user_id: UserId,
+        project_id: ProjectId,
+        week_of: NaiveDate,
+        shifts: u64,
+        // Submitted by the project's schedule rather than by hand
+        automatic: bool,
+    },
+    // By the project's approver, who needn't be the owner in `user_id`
+    RotaReviewed {
+        user_id: UserId,
+        project_id: ProjectId,
+        week_of: NaiveDate,
+        approved: bool,
+        comment: Option<ReviewComment>,
+    },
+    // A member texted SWAP in reply to a shift reminder
+    SwapRequested {
+        user_id: UserId,
+        project_id: ProjectId,
+        shift_id: ShiftId,
+        member_id: MemberId,
+        member_name: MemberName,
+        shift_date: NaiveDate,
+    },
+}
+
+impl DomainEvent {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DomainEvent::UserSignedUp { .. } => "UserSignedUp",
+            DomainEvent::UserDeleted { .. } => "UserDeleted",
+            DomainEvent::UserAnonymized { .. } => "UserAnonymized",
+            DomainEvent::UserEmailChanged { .. } => "UserEmailChanged",
+            DomainEvent::UserPasswordChanged { .. } => "UserPasswordChanged",
+            DomainEvent::BackupCodeUsed { .. } => "BackupCodeUsed",
+            DomainEvent::AuthenticatorAppSetUp { .. } => {
+                "AuthenticatorAppSetUp"
+            }
+            DomainEvent::IntegrationKeyCreated { .. } => {
+                "IntegrationKeyCreated"
+            }
+            DomainEvent::TwoFactorChanged { .. } => "TwoFactorChanged",
+            DomainEvent::ProjectCreated { .. } => "ProjectCreated",
+            DomainEvent::MemberAdded { .. } => "MemberAdded",
+            DomainEvent::MemberUpdated { .. } => "MemberUpdated",
+            DomainEvent::ShiftCreated { .. } => "ShiftCreated",
+            DomainEvent::AbsenceReported { .. } => "AbsenceReported",
+            DomainEvent::ShiftsCleared { .. } => "ShiftsCleared",
+            DomainEvent::WeekTemplateApplied { .. } => "WeekTemplateApplied",
+            DomainEvent::RotaImported { .. } => "RotaImported",
+            DomainEvent::RotaPublished { .. } => "RotaPublished",
+            DomainEvent::RotaSubmitted { .. } => "RotaSubmitted",
+            DomainEvent::RotaReviewed { .. } => "RotaReviewed",
+            DomainEvent::SwapRequested { .. } => "SwapRequested",
+        }
+    }
+
+    pub fn user_id(&self) -> &UserId {
+        match self {
+            DomainEvent::UserSignedUp { user_id }
+            | DomainEvent::UserDeleted { user_id }
+            | DomainEvent::UserAnonymized { user_id }
+            | DomainEvent::UserEmailChanged { user_id }
+            | DomainEvent::UserPasswordChanged { user_id }
+            | DomainEvent::BackupCodeUsed { user_id, .. }
+            | DomainEvent::AuthenticatorAppSetUp { user_id }
+            | DomainEvent::IntegrationKeyCreated { user_id, .. }
+            | DomainEvent::TwoFactorChanged { user_id, .. }
+            | DomainEvent::ProjectCreated { user_id, .. }
+            | DomainEvent::MemberAdded { user_id, .. }
+            | DomainEvent::MemberUpdated { user_id, .. }
+            | DomainEvent::ShiftCreated { user_id, .. }
+            | DomainEvent::AbsenceReported { user_id, .. }
+            | DomainEvent::ShiftsCleared { user_id, .. }
+            | DomainEvent::WeekTemplateApplied { user_id, .. }
+            | DomainEvent::RotaImported { user_id, .. }
+            | DomainEvent::RotaPublished { user_id, .. }
+            | DomainEvent::RotaSubmitted { user_id, .. }
+            | DomainEvent::RotaReviewed { user_id, .. }
+            | DomainEvent::SwapRequested { user_id, .. } => user_id,
+        }
+    }
+
+    // Events that touch a project are also kept in its history
+    pub fn project_id(&self) -> Option<&ProjectId> {
+        match self {
+            DomainEvent::UserSignedUp { .. }
+            | DomainEvent::UserDeleted { .. }
+            | DomainEvent::UserAnonymized { .. }
+            | DomainEvent::UserEmailChanged { .. }
+            | DomainEvent::UserPasswordChanged { .. }
+            | DomainEvent::BackupCodeUsed { .. }
+            | DomainEvent::AuthenticatorAppSetUp { .. }
+            | DomainEvent::IntegrationKeyCreated { .. }
+            | DomainEvent::TwoFactorChanged { .. } => None,
+            DomainEvent::ProjectCreated { project_id, .. }
+            | DomainEvent::MemberAdded { project_id, .. }
+            | DomainEvent::MemberUpdated { project_id, .. }
+            | DomainEvent::ShiftCreated { project_id, .. }
+            | DomainEvent::AbsenceReported { project_id, .. }
+            | DomainEvent::ShiftsCleared { project_id, .. }
+            | DomainEvent::WeekTemplateApplied { project_id, .. }
+            | DomainEvent::RotaImported { project_id, .. }
+            | DomainEvent::RotaPublished { project_id, .. }
+            | DomainEvent::RotaSubmitted { project_id, .. }
+            | DomainEvent::RotaReviewed { project_id, .. }
+            | DomainEvent::SwapRequested { project_id, .. } => Some(project_id),
+        }
+    }
+
+    pub fn entity_type(&self) -> Option<EntityType> {
+        match self {
+            DomainEvent::UserSignedUp { .. }
+            | DomainEvent::UserDeleted { .. }
+            | DomainEvent::UserAnonymized { .. }
+            | DomainEvent::UserEmailChanged { .. }
+            | DomainEvent::UserPasswordChanged { .. }
+            | DomainEvent::BackupCodeUsed { .. }
+            | DomainEvent::AuthenticatorAppSetUp { .. }
+            | DomainEvent::IntegrationKeyCreated { .. }
+            | DomainEvent::TwoFactorChanged { .. } => None,
+            DomainEvent::ProjectCreated { .. }
+            | DomainEvent::RotaPublished { .. }
+            | DomainEvent::RotaSubmitted { .. }
+            | DomainEvent::RotaReviewed { .. } => Some(EntityType::Project),
+            DomainEvent::MemberAdded { .. }
+            | DomainEvent::MemberUpdated { .. } => Some(EntityType::Member),
+            DomainEvent::ShiftCreated { .. }
+            | DomainEvent::AbsenceReported { .. }
+            | DomainEvent::ShiftsCleared { .. }
+            | DomainEvent::WeekTemplateApplied { .. }
+            | DomainEvent::RotaImported { .. }
+            | DomainEvent::SwapRequested { .. } => Some(EntityType::Shift),
+        }
+    }
+
+    // A sentence for the project history timeline, e.g.
+    // "Alice added shift for Ted on Monday 09:00–17:00 with Dougal on standby"
+    pub fn describe(&self, actor: &str) -> String {
+        match self {
+            DomainEvent::UserSignedUp { .. } => format!("{} signed up", actor),
+            DomainEvent::UserDeleted { .. } => {
+                format!("{} deleted their account", actor)
+            }
+            DomainEvent::UserAnonymized { .. } => {
+                format!("{} anonymized their account", actor)
+            }
+            DomainEvent::UserEmailChanged { .. } => {
+                format!("{} changed their email address", actor)
+            }
+            DomainEvent::UserPasswordChanged { .. } => {
+                format!("{} changed their password", actor)
+            }
+            DomainEvent::BackupCodeUsed { .. } => {
+                format!("{} signed in with a backup code", actor)
+            }
+            DomainEvent::AuthenticatorAppSetUp { .. } => {
+                format!("{} set up an authenticator app", actor)
+            }
+            DomainEvent::IntegrationKeyCreated { key_name, .. } => {
+                format!(
+                    "{} created integration key {}",
+                    actor,
+                    key_name.as_ref()
+                )
+            }
+            DomainEvent::TwoFactorChanged { enabled, .. } => {
+                let state = if *enabled { "on" } else { "off" };
+                format!("{} turned two-factor authentication {}", actor, state)
+            }
+            DomainEvent::ProjectCreated { project_name, .. } => {
+                format!("{} created project {}", actor, project_name.as_ref())
+            }
+            DomainEvent::MemberAdded { member_name, .. } => {
+                format!("{} added member {}", actor, member_name.as_ref())
+            }
+            DomainEvent::MemberUpdated { member_name, .. } => {
+                format!(
+                    "{} renamed a member to {}",
+                    actor,
+                    member_name.as_ref()
+                )
+            }
+            DomainEvent::ShiftCreated {
+                member_name,
+                day,
+                start_time,
+                end_time,
+                standby_member_name,
+                ..
+            } => {
+                let standby = standby_member_name
+                    .as_ref()
+                    .map(|name| format!(" with {} on standby", name.as_ref()))
+                    .unwrap_or_default();
+                format!(
+                    "{} added shift for {} on {} {}–{}{}",
+                    actor,
+                    member_name.as_ref(),
+                    day,
+                    start_time,
+                    end_time,
+                    standby
+                )
+            }
+            DomainEvent::AbsenceReported {
+                member_name,
+                date,
+                reason,
+                standby_member_name,
+                ..
+            } => {
+                let absent = match reason {
+                    AbsenceReason::Sickness => "off sick",
+                };
+                let cover = match standby_member_name {
+                    Some(name) => format!("{} is on standby", name.as_ref()),
+                    None => String::from("the shift is open to claim"),
+                };
+                format!(
+                    "{} reported {} {} on {}; {}",
+                    actor,
+                    member_name.as_ref(),
+                    absent,
+                    date,
+                    cover
+                )
+            }
+            DomainEvent::ShiftsCleared { day, shifts, .. } => {
+                let plural = if *shifts == 1 { "" } else { "s" };
+                match day {
+                    Some(day) => format!(
+                        "{} cleared {} shift{} on {}",
+                        actor, shifts, plural, day
+                    ),
+                    None => format!(
+                        "{} cleared all {} shift{}",
+                        actor, shifts, plural
+                    ),
+                }
+            }
+            DomainEvent::WeekTemplateApplied {
+                template_name,
+                shifts,
+                replaced,
+                ..
+            } => {
+                let plural = if *shifts == 1 { "" } else { "s" };
+                let replacing = match replaced {
+                    0 => String::new(),
+                    1 => String::from(" in place of 1 shift"),
+                    n => format!(" in place of {} shifts", n),
+                };
+                format!(
+                    "{} applied template {}, adding {} shift{}{}",
+                    actor,
+                    template_name.as_ref(),
+                    shifts,
+                    plural,
+                    replacing
+                )
+            }
+            DomainEvent::RotaImported {
+                members,
+                shifts,
+                replaced,
+                ..
+            } => {
+                let plural = if *shifts == 1 { "" } else { "s" };
+                let adding = match members {
+                    0 => String::new(),
+                    1 => String::from(" and 1 new member"),
+                    n => format!(" and {} new members", n),
+                };
+                let replacing = match replaced {
+                    0 => String::new(),
+                    1 => String::from(" in place of 1 shift"),
+                    n => format!(" in place of {} shifts", n),
+                };
+                format!(
+                    "{} imported {} shift{}{}{}",
+                    actor, shifts, plural, adding, replacing
+                )
+            }
+            DomainEvent::RotaPublished {
+                week_of,
+                shifts,
+                automatic,
+                ..
+            } => {
+                let plural = if *shifts == 1 { "" } else { "s" };
+                let published = if *automatic {
+                    format!(
+                        "The rota for the week of {} was published \
+                         automatically",
+                        week_of
+                    )
+                } else {
+                    format!(
+                        "{} published the rota for the week of {}",
+                        actor, week_of
+                    )
+                };
+                format!("{} with {} shift{}", published, shifts, plural)
+            }
+            DomainEvent::RotaSubmitted {
+                week_of,
+                shifts,
+                automatic,
+                ..
+            } => {
+                let plural = if *shifts == 1 { "" } else { "s" };
+                let submitted = if *automatic {
+                    format!(
+                        "The rota for the week of {} was submitted \
+                         automatically",
+                        week_of
+                    )
+                } else {
+                    format!(
+                        "{} submitted the rota for the week of {}",
+                        actor, week_of
+                    )
+                };
+                format!(
+                    "{} with {} shift{} for approval",
+                    submitted, shifts, plural
+                )
+            }
+            DomainEvent::RotaReviewed {
+                week_of,
+                approved,
+                comment,
+                ..
+            } => {
+                let decision = if *approved { "approved" } else { "rejected" };
+                let comment = comment
+                    .as_ref()
+                    .map(|comment| format!(": {}", comment.as_ref()))
+                    .unwrap_or_default();
+                format!(
+                    "The rota for the week of {} was {}{}",
+                    week_of, decision, comment
+                )
+            }
+            DomainEvent::SwapRequested {
+                member_name,
+                shift_date,
+                ..
+            } => format!(
+                "{} asked to swap their shift on {}",
+                member_name.as_ref(),
+                shift_date
+            ),
+        }
+    }
+}
+
+pub type EventReceiver = broadcast::Receiver<DomainEvent>;
+
+pub trait EventBus {
+    // Publishing never fails the request; an event with no subscribers is
+    // simply dropped.
+    fn publish(&self, event: DomainEvent);
+    fn subscribe(&self) -> EventReceiver;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialises_with_type_tag() {
+        let user_id = UserId::default();
+        let event = DomainEvent::UserSignedUp {
+            user_id: user_id.clone(),
+        };
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["type"], event.name());
+
+        let round_trip: DomainEvent = serde_json::from_value(value).unwrap();
+        assert_eq!(round_trip, event);
+    }
+
+    #[test]
+    fn test_describe_shift_created() {
+        let mut event = DomainEvent::ShiftCreated {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            member_id: MemberId::default(),
+            member_name: MemberName::parse("Ted".to_owned()).unwrap(),
+            shift_id: ShiftId::default(),
+            day: Day::Monday,
+            start_time: Minute::parse(540).unwrap(),
+            end_time: Minute::parse(1020).unwrap(),
+            standby_member_id: None,
+            standby_member_name: None,
+        };
+
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice added shift for Ted on Monday 09:00–17:00"
+        );
+        assert_eq!(event.entity_type(), Some(EntityType::Shift));
+
+        let DomainEvent::ShiftCreated {
+            standby_member_id,
+            standby_member_name,
+            ..
+        } = &mut event
+        else {
+            unreachable!()
+        };
+        *standby_member_id = Some(MemberId::default());
+        *standby_member_name =
+            Some(MemberName::parse("Dougal".to_owned()).unwrap());
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice added shift for Ted on Monday 09:00–17:00 with Dougal on \
+             standby"
+        );
+    }
+
+    #[test]
+    fn test_describe_shifts_cleared() {
+        let mut event = DomainEvent::ShiftsCleared {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            day: None,
+            shifts: 12,
+        };
+        assert_eq!(event.describe("Alice"), "Alice cleared all 12 shifts");
+        assert_eq!(event.entity_type(), Some(EntityType::Shift));
+
+        let DomainEvent::ShiftsCleared { day, shifts, .. } = &mut event else {
+            unreachable!()
+        };
+        *day = Some(Day::Monday);
+        *shifts = 1;
+        assert_eq!(event.describe("Alice"), "Alice cleared 1 shift on Monday");
+    }
+
+    #[test]
+    fn test_describe_week_template_applied() {
+        let mut event = DomainEvent::WeekTemplateApplied {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            template_name: WeekTemplateName::parse("Summer").unwrap(),
+            shifts: 12,
+            replaced: 0,
+        };
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice applied template Summer, adding 12 shifts"
+        );
+
+        let DomainEvent::WeekTemplateApplied { replaced, .. } = &mut event
+        else {
+            unreachable!()
+        };
+        *replaced = 10;
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice applied template Summer, adding 12 shifts in place of 10 \
+             shifts"
+        );
+    }
+
+    #[test]
+    fn test_describe_rota_imported() {
+        let mut event = DomainEvent::RotaImported {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            members: 0,
+            shifts: 1,
+            replaced: 0,
+        };
+        assert_eq!(event.describe("Alice"), "Alice imported 1 shift");
+        assert_eq!(event.entity_type(), Some(EntityType::Shift));
+
+        let DomainEvent::RotaImported {
+            members, replaced, ..
+        } = &mut event
+        else {
+            unreachable!()
+        };
+        *members = 3;
+        *replaced = 10;
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice imported 1 shift and 3 new members in place of 10 shifts"
+        );
+    }
+
+    #[test]
+    fn test_describe_rota_published() {
+        let mut event = DomainEvent::RotaPublished {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            week_of: NaiveDate::from_ymd_opt(2025, 10, 26).unwrap(),
+            shifts: 1,
+            automatic: false,
+        };
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice published the rota for the week of 2025-10-26 with 1 shift"
+        );
+
+        let DomainEvent::RotaPublished { automatic, .. } = &mut event else {
+            unreachable!()
+        };
+        *automatic = true;
+        assert_eq!(
+            event.describe("Alice"),
+            "The rota for the week of 2025-10-26 was published automatically \
+             with 1 shift"
+        );
+    }
+
+    #[test]
+    fn test_describe_rota_submitted() {
+        let event = DomainEvent::RotaSubmitted {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            week_of: NaiveDate::from_ymd_opt(2025, 10, 26).unwrap(),
+            shifts: 2,
+            automatic: false,
+        };
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice submitted the rota for the week of 2025-10-26 with 2 shifts \
+             for approval"
+        );
+    }
+
+    #[test]
+    fn test_describe_rota_reviewed() {
+        let mut event = DomainEvent::RotaReviewed {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            week_of: NaiveDate::from_ymd_opt(2025, 10, 26).unwrap(),
+            approved: true,
+            comment: None,
+        };
+        assert_eq!(
+            event.describe("Alice"),
+            "The rota for the week of 2025-10-26 was approved"
+        );
+
+        let DomainEvent::RotaReviewed {
+            approved, comment, ..
+        } = &mut event
+        else {
+            unreachable!()
+        };
+        *approved = false;
+        *comment = ReviewComment::parse("Ted can't do Mondays").unwrap();
+        assert_eq!(
+            event.describe("Alice"),
+            "The rota for the week of 2025-10-26 was rejected: Ted can't do \
+             Mondays"
+        );
+    }
+
+    #[test]
+    fn test_describe_absence_reported() {
+        let event = DomainEvent::AbsenceReported {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            shift_id: ShiftId::default(),
+            member_id: MemberId::default(),
+            member_name: MemberName::parse("Ted".to_owned()).unwrap(),
+            date: NaiveDate::from_ymd_opt(2025, 10, 20).unwrap(),
+            reason: AbsenceReason::Sickness,
+            standby_member_id: None,
+            standby_member_name: None,
+        };
+
+        assert_eq!(
+            event.describe("Alice"),
+            "Alice reported Ted off sick on 2025-10-20; the shift is open to \
+             claim"
+        );
+
+        let value = serde_json::to_value(&event).unwrap();
+        assert_eq!(value["date"], "2025-10-20");
+        assert_eq!(value["reason"], "sickness");
+    }
+
+    #[test]
+    fn test_user_events_have_no_project() {
+        let event = DomainEvent::UserDeleted {
+            user_id: UserId::default(),
+        };
+
+        assert!(event.project_id().is_none());
+        assert!(event.entity_type().is_none());
+    }
+}
@@ -9,4 +9,31 @@ pub trait EmailClient {
         subject: &str,
         content: &str,
     ) -> Result<()>;
+
+    // Send with an invisible image loaded from `pixel_url`, so the email
+    // being opened can be recorded. Clients that can't send HTML send it
+    // untracked.
+    async fn send_tracked_email(
+        &self,
+        recipient: &Email,
+        subject: &str,
+        content: &str,
+        pixel_url: &str,
+    ) -> Result<()> {
+        let _ = pixel_url;
+        self.send_email(recipient, subject, content).await
+    }
+
+    // Send with replies going to `reply_to` rather than the sender, so they
+    // can be acted on. Clients that can't set it send it as usual.
+    async fn send_email_with_reply_to(
+        &self,
+        recipient: &Email,
+        subject: &str,
+        content: &str,
+        reply_to: &str,
+    ) -> Result<()> {
+        let _ = reply_to;
+        self.send_email(recipient, subject, content).await
+    }
 }
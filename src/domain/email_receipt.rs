@@ -0,0 +1,77 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum EmailStatus {
+    Queued,
+    // Held back because the address was undeliverable
+    Suppressed,
+    Sent,
+    Opened,
+}
+
+// What happened to an email about a project. Emails are only known to have
+// been opened when they were tracked, so a sent email may still have been
+// read.
+#[derive(Debug, Clone, PartialEq)]
+pub struct EmailReceipt {
+    pub recipient: String,
+    pub subject: String,
+    pub queued_at: DateTime<Utc>,
+    pub sent_at: Option<DateTime<Utc>>,
+    pub suppressed: bool,
+    pub tracked: bool,
+    pub opened_at: Option<DateTime<Utc>>,
+}
+
+impl EmailReceipt {
+    pub fn status(&self) -> EmailStatus {
+        if self.suppressed {
+            return EmailStatus::Suppressed;
+        }
+        match (self.sent_at, self.opened_at) {
+            (_, Some(_)) => EmailStatus::Opened,
+            (Some(_), None) => EmailStatus::Sent,
+            (None, None) => EmailStatus::Queued,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status() {
+        let now = Utc::now();
+        let mut receipt = EmailReceipt {
+            recipient: "mrs.doyle@example.com".to_owned(),
+            subject: "The Craggy Island rota needs approving".to_owned(),
+            queued_at: now,
+            sent_at: None,
+            suppressed: false,
+            tracked: false,
+            opened_at: None,
+        };
+        assert_eq!(receipt.status(), EmailStatus::Queued);
+
+        receipt.sent_at = Some(now);
+        receipt.tracked = true;
+        assert_eq!(receipt.status(), EmailStatus::Sent);
+
+        receipt.opened_at = Some(now);
+        assert_eq!(receipt.status(), EmailStatus::Opened);
+
+        let suppressed = EmailReceipt {
+            suppressed: true,
+            sent_at: None,
+            opened_at: None,
+            ..receipt
+        };
+        assert_eq!(suppressed.status(), EmailStatus::Suppressed);
+    }
+}
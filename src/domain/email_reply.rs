@@ -0,0 +1,63 @@
+use super::SmsReply;
+
+// What a member can answer a shift reminder email with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailReply {
+    Accept,
+    Decline,
+}
+
+impl EmailReply {
+    // Only the first word of the reply counts, so "Accept, see you then"
+    // accepts. Mail clients quote the reminder below the reply, so nothing
+    // after the first line with text on it is read.
+    pub fn parse(body: &str) -> Option<Self> {
+        let line = body.lines().find(|line| !line.trim().is_empty())?;
+        let keyword = line.split_whitespace().next()?.to_uppercase();
+        match keyword.trim_end_matches(['.', '!', ',']) {
+            "ACCEPT" => Some(Self::Accept),
+            "DECLINE" => Some(Self::Decline),
+            _ => None,
+        }
+    }
+}
+
+// Accepting confirms the shift like texting YES, and declining asks the
+// project owner to find cover like texting SWAP
+impl From<EmailReply> for SmsReply {
+    fn from(reply: EmailReply) -> Self {
+        match reply {
+            EmailReply::Accept => Self::Confirm,
+            EmailReply::Decline => Self::Swap,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_email_reply() {
+        for (body, expected) in [
+            ("ACCEPT", Some(EmailReply::Accept)),
+            ("accept", Some(EmailReply::Accept)),
+            ("Accept, see you then", Some(EmailReply::Accept)),
+            ("\n\n  Decline.\n", Some(EmailReply::Decline)),
+            (
+                "Decline\n\n> Reply ACCEPT to confirm",
+                Some(EmailReply::Decline),
+            ),
+            ("Sorry\nDECLINE", None),
+            ("accepted", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                EmailReply::parse(body),
+                expected,
+                "Failed for {:?}",
+                body
+            );
+        }
+    }
+}
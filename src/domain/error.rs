@@ -1,14 +1,31 @@
 use color_eyre::eyre::Report;
 use thiserror::Error;
 
+use super::{
+    CommentId, DepartmentId, IntegrationKeyId, MemberId, ProjectId,
+    RotaApprovalId, RotaVersionId, ShiftId, ShiftSlotId, WeekTemplateId,
+};
+
 #[derive(Debug, Error)]
 pub enum AuthAPIError {
+    #[error("Already bootstrapped")]
+    AlreadyBootstrapped,
     #[error("Invalid credentials")]
     IncorrectCredentials,
     #[error("Invalid token")]
     InvalidToken,
+    #[error("Invitation required")]
+    InvitationRequired,
+    #[error("Account is under legal hold")]
+    LegalHold,
+    #[error("Policy version {0} not accepted")]
+    PolicyNotAccepted(i32),
+    #[error("Missing credentials")]
+    MissingCredentials,
     #[error("Missing token")]
     MissingToken,
+    #[error("Session not found")]
+    SessionNotFound,
     #[error("Unexpected error")]
     UnexpectedError(#[source] Report),
     #[error("User already exists")]
@@ -23,8 +40,26 @@ pub enum AuthAPIError {
 pub enum ProjectAPIError {
     #[error("Authentication error")]
     AuthenticationError(#[from] AuthAPIError),
-    #[error("Resource with ID not found: {0}")]
-    IDNotFoundError(uuid::Uuid),
+    #[error("Project not found: {0}")]
+    ProjectNotFound(ProjectId),
+    #[error("Member not found: {0}")]
+    MemberNotFound(MemberId),
+    #[error("Shift not found: {0}")]
+    ShiftNotFound(ShiftId),
+    #[error("Department not found: {0}")]
+    DepartmentNotFound(DepartmentId),
+    #[error("Comment not found: {0}")]
+    CommentNotFound(CommentId),
+    #[error("Shift slot not found: {0}")]
+    ShiftSlotNotFound(ShiftSlotId),
+    #[error("Week template not found: {0}")]
+    WeekTemplateNotFound(WeekTemplateId),
+    #[error("Rota version not found: {0}")]
+    RotaVersionNotFound(RotaVersionId),
+    #[error("Rota approval not found: {0}")]
+    RotaApprovalNotFound(RotaApprovalId),
+    #[error("Integration key not found: {0}")]
+    IntegrationKeyNotFound(IntegrationKeyId),
     #[error("Resource with ID already exists: {0}")]
     IDExistsError(uuid::Uuid),
     #[error("Unexpected error")]
@@ -33,6 +68,39 @@ pub enum ProjectAPIError {
     ValidationError(#[from] ValidationError),
 }
 
+impl ProjectAPIError {
+    // The kind of thing that wasn't found, as named in error bodies, and its
+    // ID. None for errors other than something not being found.
+    pub fn not_found(&self) -> Option<(&'static str, uuid::Uuid)> {
+        let (entity, id) = match self {
+            ProjectAPIError::ProjectNotFound(id) => ("project", id.as_ref()),
+            ProjectAPIError::MemberNotFound(id) => ("member", id.as_ref()),
+            ProjectAPIError::ShiftNotFound(id) => ("shift", id.as_ref()),
+            ProjectAPIError::DepartmentNotFound(id) => {
+                ("department", id.as_ref())
+            }
+            ProjectAPIError::CommentNotFound(id) => ("comment", id.as_ref()),
+            ProjectAPIError::ShiftSlotNotFound(id) => {
+                ("shift_slot", id.as_ref())
+            }
+            ProjectAPIError::WeekTemplateNotFound(id) => {
+                ("week_template", id.as_ref())
+            }
+            ProjectAPIError::RotaVersionNotFound(id) => {
+                ("rota_version", id.as_ref())
+            }
+            ProjectAPIError::RotaApprovalNotFound(id) => {
+                ("rota_approval", id.as_ref())
+            }
+            ProjectAPIError::IntegrationKeyNotFound(id) => {
+                ("integration_key", id.as_ref())
+            }
+            _ => return None,
+        };
+        Some((entity, *id))
+    }
+}
+
 #[derive(Debug, Error)]
 #[error("Validation error: {0}")]
 pub struct ValidationError(String);
@@ -0,0 +1,246 @@
+use chrono::{NaiveDateTime, TimeDelta};
+use reqwest::Url;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Locale, MemberName, ValidationError};
+
+// Shifts with this tag are escalated when nobody has them covered
+pub const CRITICAL_SHIFT_TAG: &str = "critical";
+
+const MAX_ESCALATION_HOURS: i16 = 168;
+
+// How an uncovered critical shift is escalated, from least to most
+// intrusive: an email to the project owner, a text to the team, a post to
+// the project's chat channel and a text to the owner's escalation phone
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum EscalationChannel {
+    Email,
+    Sms,
+    Chat,
+    OwnerPhone,
+}
+
+impl EscalationChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EscalationChannel::Email => "email",
+            EscalationChannel::Sms => "sms",
+            EscalationChannel::Chat => "chat",
+            EscalationChannel::OwnerPhone => "ownerPhone",
+        }
+    }
+}
+
+// Escalate through `channel` once a critical shift is still uncovered
+// `hours_before_start` hours before it starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EscalationStep {
+    channel: EscalationChannel,
+    #[serde(rename = "hoursBeforeStart")]
+    #[schemars(range(min = 1, max = 168))]
+    hours_before_start: i16,
+}
+
+impl EscalationStep {
+    pub fn new(
+        channel: EscalationChannel,
+        hours_before_start: i16,
+    ) -> Result<Self, ValidationError> {
+        if !(1..=MAX_ESCALATION_HOURS).contains(&hours_before_start) {
+            return Err(ValidationError::new(format!(
+                "Escalation steps must be between 1 and {} hours before the \
+                 shift",
+                MAX_ESCALATION_HOURS
+            )));
+        }
+        Ok(Self {
+            channel,
+            hours_before_start,
+        })
+    }
+
+    pub fn channel(&self) -> EscalationChannel {
+        self.channel
+    }
+
+    pub fn hours_before_start(&self) -> i16 {
+        self.hours_before_start
+    }
+
+    pub fn lead_time(&self) -> TimeDelta {
+        TimeDelta::hours(self.hours_before_start.into())
+    }
+
+    pub fn is_due(&self, now: NaiveDateTime, start: NaiveDateTime) -> bool {
+        now >= start - self.lead_time()
+    }
+}
+
+// Where chat escalations are posted, e.g. a Slack incoming webhook
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChatWebhookUrl(String);
+
+impl ChatWebhookUrl {
+    pub fn parse(url: String) -> Result<Self, ValidationError> {
+        let invalid = || {
+            ValidationError::new(String::from(
+                "Chat webhook URL must be an http or https URL",
+            ))
+        };
+        let parsed = Url::parse(&url).map_err(|_| invalid())?;
+        if !matches!(parsed.scheme(), "http" | "https")
+            || parsed.host().is_none()
+        {
+            return Err(invalid());
+        }
+        Ok(Self(url))
+    }
+}
+
+impl AsRef<str> for ChatWebhookUrl {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+// Why a critical shift needs escalating
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ShiftCoverage {
+    // Its member is absent and nobody is standing by
+    Open,
+    // Its member hasn't confirmed their reminder
+    Unconfirmed,
+    Covered,
+}
+
+impl ShiftCoverage {
+    pub fn new(absent: bool, standby: bool, confirmed: bool) -> Self {
+        match (absent, standby, confirmed) {
+            (true, false, _) => ShiftCoverage::Open,
+            (true, true, _) | (false, _, true) => ShiftCoverage::Covered,
+            (false, _, false) => ShiftCoverage::Unconfirmed,
+        }
+    }
+}
+
+pub fn escalation_message(
+    coverage: ShiftCoverage,
+    member_name: &MemberName,
+    project_name: &str,
+    start: NaiveDateTime,
+) -> String {
+    let start = Locale::default().format_date_time(start);
+    match coverage {
+        ShiftCoverage::Open => format!(
+            "Critical shift not covered: {} can't make the {} shift on {} and \
+             nobody is standing by.",
+            member_name.as_ref(),
+            project_name,
+            start
+        ),
+        _ => format!(
+            "Critical shift not confirmed: {} hasn't confirmed the {} shift \
+             on {}.",
+            member_name.as_ref(),
+            project_name,
+            start
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn start() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 10, 20)
+            .unwrap()
+            .and_hms_opt(9, 0, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_step_hours_range() {
+        for hours in [1, 12, 168] {
+            assert!(
+                EscalationStep::new(EscalationChannel::Email, hours).is_ok()
+            );
+        }
+        for hours in [-1, 0, 169] {
+            assert!(
+                EscalationStep::new(EscalationChannel::Email, hours).is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_step_is_due() {
+        let step = EscalationStep::new(EscalationChannel::Sms, 6).unwrap();
+
+        assert!(!step.is_due(start() - TimeDelta::hours(7), start()));
+        assert!(step.is_due(start() - TimeDelta::hours(6), start()));
+        assert!(step.is_due(start() - TimeDelta::minutes(5), start()));
+    }
+
+    #[test]
+    fn test_chat_webhook_url() {
+        for url in [
+            "https://hooks.slack.com/services/T000/B000/XXXX",
+            "http://127.0.0.1:8080/hook",
+        ] {
+            assert!(ChatWebhookUrl::parse(url.to_owned()).is_ok(), "{}", url);
+        }
+        for url in ["", "hooks.slack.com/services", "ftp://example.com/hook"] {
+            assert!(ChatWebhookUrl::parse(url.to_owned()).is_err(), "{}", url);
+        }
+    }
+
+    #[test]
+    fn test_shift_coverage() {
+        assert_eq!(ShiftCoverage::new(true, false, true), ShiftCoverage::Open);
+        assert_eq!(
+            ShiftCoverage::new(true, true, false),
+            ShiftCoverage::Covered
+        );
+        assert_eq!(
+            ShiftCoverage::new(false, false, true),
+            ShiftCoverage::Covered
+        );
+        assert_eq!(
+            ShiftCoverage::new(false, false, false),
+            ShiftCoverage::Unconfirmed
+        );
+    }
+
+    #[test]
+    fn test_escalation_message() {
+        let ted = MemberName::parse("Ted".to_owned()).unwrap();
+
+        assert_eq!(
+            escalation_message(
+                ShiftCoverage::Open,
+                &ted,
+                "Craggy Island",
+                start()
+            ),
+            "Critical shift not covered: Ted can't make the Craggy Island \
+             shift on Monday 20/10/2025 at 09:00 and nobody is standing by."
+        );
+        assert_eq!(
+            escalation_message(
+                ShiftCoverage::Unconfirmed,
+                &ted,
+                "Craggy Island",
+                start()
+            ),
+            "Critical shift not confirmed: Ted hasn't confirmed the Craggy \
+             Island shift on Monday 20/10/2025 at 09:00."
+        );
+    }
+}
@@ -0,0 +1,104 @@
+// Declares a UUID ID newtype, such as ProjectId, with the same parsing,
+// formatting and (de)serialising as every other. The label goes in the
+// parse error, e.g. "Invalid project ID: ...". It deserialises from a UUID
+// string, so works as a path parameter (`Path<SessionId>`), and binds
+// straight to a UUID column in queries.
+macro_rules! uuid_id {
+    ($name:ident, $label:literal) => {
+        #[derive(
+            Debug,
+            Clone,
+            PartialEq,
+            Eq,
+            Hash,
+            serde::Serialize,
+            serde::Deserialize,
+            schemars::JsonSchema,
+            sqlx::Type,
+        )]
+        #[sqlx(transparent)]
+        pub struct $name(uuid::Uuid);
+
+        impl $name {
+            pub fn parse(
+                id: &str,
+            ) -> Result<Self, $crate::domain::ValidationError> {
+                let parsed = uuid::Uuid::try_parse(id).map_err(|e| {
+                    $crate::domain::ValidationError::new(format!(
+                        concat!("Invalid ", $label, " ID: {}"),
+                        e
+                    ))
+                })?;
+                Ok(Self(parsed))
+            }
+
+            pub fn new(uuid: uuid::Uuid) -> Self {
+                Self(uuid)
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self(uuid::Uuid::new_v4())
+            }
+        }
+
+        impl AsRef<uuid::Uuid> for $name {
+            fn as_ref(&self) -> &uuid::Uuid {
+                &self.0
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        impl std::str::FromStr for $name {
+            type Err = $crate::domain::ValidationError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::parse(s)
+            }
+        }
+    };
+}
+
+pub(super) use uuid_id;
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    uuid_id!(WidgetId, "widget");
+
+    const VALID_ID: &str = "5e90ca28-e1ad-4795-a190-089959c16e0b";
+
+    #[test]
+    fn test_display_and_from_str_round_trip() {
+        let id: WidgetId = VALID_ID.parse().expect(VALID_ID);
+        assert_eq!(id.to_string(), VALID_ID);
+        assert_eq!(id, WidgetId::new(*id.as_ref()));
+    }
+
+    #[test]
+    fn test_parse_error_names_the_id() {
+        let error = "not-a-uuid".parse::<WidgetId>().expect_err("not-a-uuid");
+        assert!(error.as_ref().starts_with("Invalid widget ID: "));
+    }
+
+    #[test]
+    fn test_serialises_as_a_uuid_string() {
+        let id = WidgetId::parse(VALID_ID).unwrap();
+        let json = serde_json::to_value(&id).unwrap();
+        assert_eq!(json, serde_json::json!(VALID_ID));
+        assert_eq!(serde_json::from_value::<WidgetId>(json).unwrap(), id);
+    }
+
+    #[test]
+    fn test_defaults_are_unique() {
+        let ids: HashSet<_> = (0..10).map(|_| WidgetId::default()).collect();
+        assert_eq!(ids.len(), 10);
+    }
+}
@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use super::{DepartmentId, MemberId, ProjectId, ShiftId, ShiftSlotId};
+
+// Where new IDs come from, so tests can know the IDs they'll be given
+pub trait IdGenerator {
+    fn new_id(&self) -> Uuid;
+
+    fn project_id(&self) -> ProjectId {
+        ProjectId::new(self.new_id())
+    }
+
+    fn member_id(&self) -> MemberId {
+        MemberId::new(self.new_id())
+    }
+
+    fn shift_id(&self) -> ShiftId {
+        ShiftId::new(self.new_id())
+    }
+
+    fn slot_id(&self) -> ShiftSlotId {
+        ShiftSlotId::new(self.new_id())
+    }
+
+    fn department_id(&self) -> DepartmentId {
+        DepartmentId::new(self.new_id())
+    }
+}
@@ -0,0 +1,196 @@
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{id::uuid_id, ProjectId, UserId, ValidationError};
+
+pub const MAX_INTEGRATION_KEYS: usize = 10;
+// Marks the key as ours in a tool's settings, and in leaked secret scans
+const KEY_PREFIX: &str = "rmk_";
+const KEY_RANDOM_LENGTH: usize = 40;
+
+uuid_id!(IntegrationKeyId, "integration key");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationKeyName(#[schemars(length(min = 1, max = 100))] String);
+
+impl IntegrationKeyName {
+    pub fn parse(name: &str) -> Result<Self, ValidationError> {
+        let name = name.trim();
+        match name.chars().count() {
+            0 => Err(ValidationError::new(String::from(
+                "Key name cannot be empty",
+            ))),
+            x if x > 100 => Err(ValidationError::new(String::from(
+                "Key names cannot be longer than 100 characters",
+            ))),
+            _ => Ok(Self(name.to_owned())),
+        }
+    }
+}
+
+impl AsRef<String> for IntegrationKeyName {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// The secret a tool sends to poll for updates. Only its hash is kept, so
+// it's shown to the user once, when it's created.
+pub struct IntegrationKeySecret(Secret<String>);
+
+impl IntegrationKeySecret {
+    pub fn generate() -> Self {
+        let random: String = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(KEY_RANDOM_LENGTH)
+            .map(char::from)
+            .collect();
+        Self(Secret::new(format!("{}{}", KEY_PREFIX, random)))
+    }
+
+    pub fn parse(key: Secret<String>) -> Result<Self, ValidationError> {
+        let valid = key.expose_secret().strip_prefix(KEY_PREFIX).is_some_and(
+            |random| {
+                random.len() == KEY_RANDOM_LENGTH
+                    && random.chars().all(|c| c.is_ascii_alphanumeric())
+            },
+        );
+        match valid {
+            true => Ok(Self(key)),
+            false => Err(ValidationError::new(String::from(
+                "Invalid integration key",
+            ))),
+        }
+    }
+
+    // Keys are long and random, so a fast hash is enough to keep them safe
+    // at rest
+    pub fn hash(&self) -> String {
+        Sha256::digest(self.0.expose_secret().as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+impl AsRef<Secret<String>> for IntegrationKeySecret {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// Lets a no-code tool, such as Zapier, poll a user's updates
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrationKey {
+    pub key_id: IntegrationKeyId,
+    pub user_id: UserId,
+    pub name: IntegrationKeyName,
+    // The last update the key was given, where polling without a cursor
+    // carries on from
+    pub last_event_id: i64,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+impl IntegrationKey {
+    pub fn new(
+        user_id: UserId,
+        name: IntegrationKeyName,
+        created_at: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            key_id: IntegrationKeyId::default(),
+            user_id,
+            name,
+            last_event_id: 0,
+            created_at,
+            last_used_at: None,
+        }
+    }
+}
+
+// A domain event as integrations see it. The event's own fields are passed
+// through as they're stored, less its type and the user it belongs to,
+// which every update shares.
+#[derive(Debug, Clone, PartialEq)]
+pub struct IntegrationUpdate {
+    // Increases with each event, so it doubles as the cursor
+    pub id: i64,
+    pub event_type: String,
+    pub project_id: Option<ProjectId>,
+    pub occurred_at: DateTime<Utc>,
+    pub data: Value,
+}
+
+impl IntegrationUpdate {
+    pub fn new(id: i64, occurred_at: DateTime<Utc>, mut event: Value) -> Self {
+        let mut event_type = String::new();
+        if let Some(fields) = event.as_object_mut() {
+            if let Some(Value::String(name)) = fields.remove("type") {
+                event_type = name;
+            }
+            fields.remove("user_id");
+        }
+        let project_id = event
+            .get("project_id")
+            .and_then(Value::as_str)
+            .and_then(|id| Uuid::parse_str(id).ok())
+            .map(ProjectId::new);
+        Self {
+            id,
+            event_type,
+            project_id,
+            occurred_at,
+            data: event,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_generated_keys_parse() {
+        let key = IntegrationKeySecret::generate();
+        let parsed = IntegrationKeySecret::parse(key.as_ref().clone()).unwrap();
+        assert_eq!(parsed.hash(), key.hash());
+        assert_eq!(key.hash().len(), 64);
+
+        for key in ["", "rmk_short", "not-a-key"] {
+            assert!(IntegrationKeySecret::parse(Secret::new(key.to_owned()))
+                .is_err());
+        }
+    }
+
+    #[test]
+    fn test_update_from_event() {
+        let project_id = Uuid::new_v4();
+        let update = IntegrationUpdate::new(
+            7,
+            Utc::now(),
+            json!({
+                "type": "ShiftsCleared",
+                "user_id": Uuid::new_v4(),
+                "project_id": project_id,
+                "day": null,
+                "shifts": 3
+            }),
+        );
+
+        assert_eq!(update.event_type, "ShiftsCleared");
+        assert_eq!(update.project_id, Some(ProjectId::new(project_id)));
+        assert_eq!(
+            update.data,
+            json!({"project_id": project_id, "day": null, "shifts": 3})
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use chrono::{DateTime, Duration, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use secrecy::{ExposeSecret, Secret};
+
+use super::ValidationError;
+
+const INVITATION_CODE_LENGTH: usize = 16;
+const MAX_INVITATION_USES: i32 = 1000;
+const MAX_INVITATION_DAYS: i64 = 90;
+
+// Lets someone sign up when signup is invitation-only
+#[derive(Debug, Clone)]
+pub struct InvitationCode(Secret<String>);
+
+impl PartialEq for InvitationCode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl InvitationCode {
+    pub fn parse(code: Secret<String>) -> Result<Self, ValidationError> {
+        let code = code.expose_secret().trim().to_owned();
+        if code.len() != INVITATION_CODE_LENGTH
+            || !code.chars().all(|c| c.is_ascii_alphanumeric())
+        {
+            return Err(ValidationError::new(
+                "Invalid invitation code".to_string(),
+            ));
+        }
+        Ok(Self(Secret::new(code)))
+    }
+}
+
+impl Default for InvitationCode {
+    fn default() -> Self {
+        let code = rand::thread_rng()
+            .sample_iter(&Alphanumeric)
+            .take(INVITATION_CODE_LENGTH)
+            .map(char::from)
+            .collect();
+        Self(Secret::new(code))
+    }
+}
+
+impl AsRef<Secret<String>> for InvitationCode {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// An invitation code that can be used `max_uses` times before it expires
+#[derive(Debug, Clone, PartialEq)]
+pub struct Invitation {
+    pub code: InvitationCode,
+    pub max_uses: i32,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Invitation {
+    pub fn new(
+        max_uses: i32,
+        expires_in_days: i64,
+    ) -> Result<Self, ValidationError> {
+        if !(1..=MAX_INVITATION_USES).contains(&max_uses) {
+            return Err(ValidationError::new(format!(
+                "An invitation must allow between 1 and {} uses",
+                MAX_INVITATION_USES
+            )));
+        }
+        if !(1..=MAX_INVITATION_DAYS).contains(&expires_in_days) {
+            return Err(ValidationError::new(format!(
+                "An invitation must expire within 1 to {} days",
+                MAX_INVITATION_DAYS
+            )));
+        }
+        Ok(Self {
+            code: InvitationCode::default(),
+            max_uses,
+            expires_at: Utc::now() + Duration::days(expires_in_days),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generated_codes_parse() {
+        let code = InvitationCode::default();
+        assert_eq!(InvitationCode::parse(code.as_ref().clone()).unwrap(), code);
+    }
+
+    #[test]
+    fn test_invalid_codes() {
+        for code in ["", "short", "abcdefghijklmnop!", "abcdefgh-ijklmno"] {
+            let error = InvitationCode::parse(Secret::new(code.to_string()))
+                .expect_err(code);
+            assert_eq!(error.as_ref(), "Invalid invitation code");
+        }
+    }
+
+    #[test]
+    fn test_new_invitation_limits() {
+        assert!(Invitation::new(1, 1).is_ok());
+        assert!(
+            Invitation::new(MAX_INVITATION_USES, MAX_INVITATION_DAYS).is_ok()
+        );
+        assert!(Invitation::new(0, 7).is_err());
+        assert!(Invitation::new(MAX_INVITATION_USES + 1, 7).is_err());
+        assert!(Invitation::new(5, 0).is_err());
+        assert!(Invitation::new(5, MAX_INVITATION_DAYS + 1).is_err());
+    }
+}
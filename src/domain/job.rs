@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use super::{MemberId, MemberName, ProjectId, UserId};
+
+// Work to be done later by the job worker. Scheduling a job while one with
+// the same key is still waiting doesn't add another; the waiting job's count
+// goes up instead, so the handler sees how many times it was asked for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Job {
+    RotaChangeDigest {
+        user_id: UserId,
+        project_id: ProjectId,
+        member_id: MemberId,
+        member_name: MemberName,
+    },
+}
+
+impl Job {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Job::RotaChangeDigest { .. } => "RotaChangeDigest",
+        }
+    }
+
+    pub fn key(&self) -> String {
+        match self {
+            Job::RotaChangeDigest {
+                project_id,
+                member_id,
+                ..
+            } => format!("{}:{}", project_id.as_ref(), member_id.as_ref()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rota_change_digest_keyed_by_project_and_member() {
+        let project_id = ProjectId::default();
+        let member_id = MemberId::default();
+        let job = |member_name: &str| Job::RotaChangeDigest {
+            user_id: UserId::default(),
+            project_id: project_id.clone(),
+            member_id: member_id.clone(),
+            member_name: MemberName::parse(member_name.to_owned()).unwrap(),
+        };
+
+        assert_eq!(job("Ted").key(), job("Father Ted").key());
+        assert_eq!(
+            job("Ted").key(),
+            format!("{}:{}", project_id.as_ref(), member_id.as_ref())
+        );
+    }
+}
@@ -0,0 +1,12 @@
+use chrono::{DateTime, Utc};
+
+use super::{Email, UserId};
+
+// An account that has to be kept as it is, for litigation or an
+// investigation, until an admin lifts the hold
+#[derive(Debug, Clone, PartialEq)]
+pub struct LegalHold {
+    pub user_id: UserId,
+    pub email: Email,
+    pub placed_at: DateTime<Utc>,
+}
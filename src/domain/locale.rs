@@ -0,0 +1,280 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, Timelike};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Day, Minute, ValidationError};
+
+// The languages and regional conventions dates and times can be shown in
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    Default,
+    PartialEq,
+    Eq,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en-GB")]
+    EnGb,
+    #[serde(rename = "en-US")]
+    EnUs,
+    #[serde(rename = "de-DE")]
+    DeDe,
+    #[serde(rename = "fr-FR")]
+    FrFr,
+    #[serde(rename = "es-ES")]
+    EsEs,
+}
+
+const LOCALES: [Locale; 5] = [
+    Locale::EnGb,
+    Locale::EnUs,
+    Locale::DeDe,
+    Locale::FrFr,
+    Locale::EsEs,
+];
+
+impl Locale {
+    // A BCP 47 tag such as "en-US". A bare language such as "de" gets that
+    // language's first listed locale.
+    pub fn parse(tag: &str) -> Result<Self, ValidationError> {
+        let tag = tag.trim().to_lowercase();
+        LOCALES
+            .iter()
+            .find(|locale| locale.tag().to_lowercase() == tag)
+            .or_else(|| LOCALES.iter().find(|locale| locale.language() == tag))
+            .copied()
+            .ok_or_else(|| {
+                ValidationError::new(format!("Unsupported locale: {}", tag))
+            })
+    }
+
+    // The most preferred supported locale in an Accept-Language header,
+    // e.g. "fr-CH, fr;q=0.9, en;q=0.8"
+    pub fn from_accept_language(header: &str) -> Option<Self> {
+        let mut ranges = header
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let tag = parts.next()?.trim();
+                let quality = parts
+                    .find_map(|param| param.trim().strip_prefix("q="))
+                    .map_or(Some(1.0), |q| q.trim().parse::<f32>().ok())?;
+                Some((tag, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect::<Vec<_>>();
+        // Stable, so equally preferred ranges keep their order
+        ranges.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        ranges.into_iter().find_map(|(tag, _)| {
+            Self::parse(tag).ok().or_else(|| {
+                // "fr-CH" is better served by "fr-FR" than by the default
+                let language = tag.split('-').next()?;
+                Self::parse(language).ok()
+            })
+        })
+    }
+
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Self::EnGb => "en-GB",
+            Self::EnUs => "en-US",
+            Self::DeDe => "de-DE",
+            Self::FrFr => "fr-FR",
+            Self::EsEs => "es-ES",
+        }
+    }
+
+    fn language(&self) -> &'static str {
+        match self {
+            Self::EnGb | Self::EnUs => "en",
+            Self::DeDe => "de",
+            Self::FrFr => "fr",
+            Self::EsEs => "es",
+        }
+    }
+
+    pub fn weekday(&self, day: Day) -> &'static str {
+        let names = match self.language() {
+            "de" => [
+                "Sonntag",
+                "Montag",
+                "Dienstag",
+                "Mittwoch",
+                "Donnerstag",
+                "Freitag",
+                "Samstag",
+            ],
+            "fr" => [
+                "dimanche", "lundi", "mardi", "mercredi", "jeudi", "vendredi",
+                "samedi",
+            ],
+            "es" => [
+                "domingo",
+                "lunes",
+                "martes",
+                "miércoles",
+                "jueves",
+                "viernes",
+                "sábado",
+            ],
+            _ => [
+                "Sunday",
+                "Monday",
+                "Tuesday",
+                "Wednesday",
+                "Thursday",
+                "Friday",
+                "Saturday",
+            ],
+        };
+        names[i16::from(day) as usize]
+    }
+
+    // e.g. "Tuesday 21/10/2025", or "Tuesday 10/21/2025" in the US
+    pub fn format_date(&self, date: NaiveDate) -> String {
+        let day = Day::try_from(date.weekday().num_days_from_sunday() as i16)
+            .expect("Weekdays are numbered 0 to 6");
+        let (d, m, y) = (date.day(), date.month(), date.year());
+        match self {
+            Self::EnUs => format!("{} {}/{}/{}", self.weekday(day), m, d, y),
+            Self::DeDe => {
+                format!("{}, {:02}.{:02}.{}", self.weekday(day), d, m, y)
+            }
+            _ => format!("{} {:02}/{:02}/{}", self.weekday(day), d, m, y),
+        }
+    }
+
+    // A time of day, on a 12-hour clock in the US and 24-hour elsewhere.
+    // The end of the day is shown as midnight.
+    pub fn format_time(&self, minute: &Minute) -> String {
+        let (hours, minutes) = minute.to_hours();
+        let hours = hours % 24;
+        match self {
+            Self::EnUs => {
+                let period = if hours < 12 { "AM" } else { "PM" };
+                let hours = match hours % 12 {
+                    0 => 12,
+                    hours => hours,
+                };
+                format!("{}:{:02} {}", hours, minutes, period)
+            }
+            _ => format!("{:02}:{:02}", hours, minutes),
+        }
+    }
+
+    pub fn format_date_time(&self, date_time: NaiveDateTime) -> String {
+        let minute =
+            Minute::parse((date_time.hour() * 60 + date_time.minute()) as i16)
+                .expect("A time of day is within the day");
+        let separator = match self.language() {
+            "de" => " um ",
+            "fr" => " à ",
+            "es" => " a las ",
+            _ => " at ",
+        };
+        format!(
+            "{}{}{}",
+            self.format_date(date_time.date()),
+            separator,
+            self.format_time(&minute)
+        )
+    }
+
+    // A length of time in hours and minutes, e.g. "7h 30m"
+    pub fn format_duration(&self, minutes: i64) -> String {
+        let (hours, minutes) = (minutes / 60, minutes % 60);
+        match self.language() {
+            "de" => format!("{} Std. {} Min.", hours, minutes),
+            "fr" | "es" => format!("{} h {:02} min", hours, minutes),
+            _ => format!("{}h {}m", hours, minutes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 10, 21).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Locale::parse("en-US").unwrap(), Locale::EnUs);
+        assert_eq!(Locale::parse(" EN-gb ").unwrap(), Locale::EnGb);
+        assert_eq!(Locale::parse("de").unwrap(), Locale::DeDe);
+        assert_eq!(Locale::parse("en").unwrap(), Locale::EnGb);
+        assert!(Locale::parse("xx-YY").is_err());
+    }
+
+    #[test]
+    fn test_from_accept_language() {
+        for (header, expected) in [
+            ("en-US,en;q=0.5", Some(Locale::EnUs)),
+            ("fr-CH, fr;q=0.9, en;q=0.8", Some(Locale::FrFr)),
+            ("ja, es;q=0.5, de;q=0.7", Some(Locale::DeDe)),
+            ("de;q=0, en-US;q=0.1", Some(Locale::EnUs)),
+            ("ja, zh", None),
+            ("*", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                Locale::from_accept_language(header),
+                expected,
+                "Failed for {:?}",
+                header
+            );
+        }
+    }
+
+    #[test]
+    fn test_format_date() {
+        assert_eq!(Locale::EnGb.format_date(date()), "Tuesday 21/10/2025");
+        assert_eq!(Locale::EnUs.format_date(date()), "Tuesday 10/21/2025");
+        assert_eq!(Locale::DeDe.format_date(date()), "Dienstag, 21.10.2025");
+        assert_eq!(Locale::FrFr.format_date(date()), "mardi 21/10/2025");
+        assert_eq!(Locale::EsEs.format_date(date()), "martes 21/10/2025");
+    }
+
+    #[test]
+    fn test_format_time() {
+        for (minute, gb, us) in [
+            (0, "00:00", "12:00 AM"),
+            (545, "09:05", "9:05 AM"),
+            (720, "12:00", "12:00 PM"),
+            (1260, "21:00", "9:00 PM"),
+            (1440, "00:00", "12:00 AM"),
+        ] {
+            let minute = Minute::parse(minute).unwrap();
+            assert_eq!(Locale::EnGb.format_time(&minute), gb);
+            assert_eq!(Locale::EnUs.format_time(&minute), us);
+        }
+    }
+
+    #[test]
+    fn test_format_date_time() {
+        let date_time = date().and_hms_opt(21, 30, 0).unwrap();
+        assert_eq!(
+            Locale::EnUs.format_date_time(date_time),
+            "Tuesday 10/21/2025 at 9:30 PM"
+        );
+        assert_eq!(
+            Locale::DeDe.format_date_time(date_time),
+            "Dienstag, 21.10.2025 um 21:30"
+        );
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(Locale::EnGb.format_duration(450), "7h 30m");
+        assert_eq!(Locale::DeDe.format_duration(450), "7 Std. 30 Min.");
+        assert_eq!(Locale::FrFr.format_duration(65), "1 h 05 min");
+    }
+}
@@ -1,18 +1,81 @@
-use super::{MemberId, MemberName, ProjectId};
+use super::{
+    ContractedHours, DepartmentId, MemberId, MemberName, ProjectId, ShiftTag,
+    ValidationError,
+};
+
+pub const MAX_MEMBER_SKILLS: usize = 10;
 
 #[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
 pub struct Member {
     pub project_id: ProjectId,
     pub member_id: MemberId,
     pub member_name: MemberName,
+    // None when the member isn't on a fixed-hours contract
+    pub contracted_hours: Option<ContractedHours>,
+    pub department_id: Option<DepartmentId>,
+    // Secondary skills, in the same terms as shift tags
+    pub skills: Vec<ShiftTag>,
 }
 
 impl Member {
-    pub fn new(project_id: ProjectId, member_name: MemberName) -> Self {
+    pub fn new(
+        project_id: ProjectId,
+        member_id: MemberId,
+        member_name: MemberName,
+    ) -> Self {
         Self {
             project_id,
-            member_id: MemberId::default(),
+            member_id,
             member_name,
+            contracted_hours: None,
+            department_id: None,
+            skills: Vec::new(),
         }
     }
+
+    // Parse a member's skills, dropping duplicates
+    pub fn parse_skills(
+        skills: &[String],
+    ) -> Result<Vec<ShiftTag>, ValidationError> {
+        let mut parsed = skills
+            .iter()
+            .map(|skill| ShiftTag::parse(skill))
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        parsed.dedup();
+        if parsed.len() > MAX_MEMBER_SKILLS {
+            return Err(ValidationError::new(format!(
+                "A member cannot have more than {} skills",
+                MAX_MEMBER_SKILLS
+            )));
+        }
+        Ok(parsed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_skills() {
+        let skills = Member::parse_skills(&[
+            "Barista".to_owned(),
+            "first aid".to_owned(),
+            "barista".to_owned(),
+        ])
+        .unwrap();
+        assert_eq!(
+            skills,
+            vec![
+                ShiftTag::parse("barista").unwrap(),
+                ShiftTag::parse("first aid").unwrap()
+            ]
+        );
+
+        let too_many: Vec<String> = (0..=MAX_MEMBER_SKILLS)
+            .map(|i| format!("skill {}", i))
+            .collect();
+        assert!(Member::parse_skills(&too_many).is_err());
+    }
 }
@@ -0,0 +1,75 @@
+use super::{Email, PhoneNumber, ValidationError};
+
+// How to reach a member outside the app. SMS and email are each opt-in, and
+// need a number or an address.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MemberContact {
+    pub phone_number: Option<PhoneNumber>,
+    pub sms_opt_in: bool,
+    pub email: Option<Email>,
+    pub email_opt_in: bool,
+}
+
+impl MemberContact {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.sms_opt_in && self.phone_number.is_none() {
+            return Err(ValidationError::new(String::from(
+                "A phone number is needed to receive SMS",
+            )));
+        }
+        if self.email_opt_in && self.email.is_none() {
+            return Err(ValidationError::new(String::from(
+                "An email address is needed to receive email",
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+
+    use super::*;
+
+    #[test]
+    fn test_sms_opt_in_needs_phone_number() {
+        assert!(MemberContact::default().validate().is_ok());
+        assert!(MemberContact {
+            phone_number: None,
+            sms_opt_in: true,
+            ..MemberContact::default()
+        }
+        .validate()
+        .is_err());
+        assert!(MemberContact {
+            phone_number: Some(
+                PhoneNumber::parse("+447700900123".to_owned()).unwrap()
+            ),
+            sms_opt_in: true,
+            ..MemberContact::default()
+        }
+        .validate()
+        .is_ok());
+    }
+
+    #[test]
+    fn test_email_opt_in_needs_address() {
+        assert!(MemberContact {
+            email_opt_in: true,
+            ..MemberContact::default()
+        }
+        .validate()
+        .is_err());
+        assert!(MemberContact {
+            email: Some(
+                Email::parse(Secret::new("ted@example.com".to_owned()))
+                    .unwrap()
+            ),
+            email_opt_in: true,
+            ..MemberContact::default()
+        }
+        .validate()
+        .is_ok());
+    }
+}
@@ -1,34 +1,6 @@
-use super::ValidationError;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use super::id::uuid_id;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct MemberId(Uuid);
-
-impl MemberId {
-    pub fn parse(id: &str) -> Result<Self, ValidationError> {
-        let parsed = uuid::Uuid::try_parse(id).map_err(|e| {
-            ValidationError::new(format!("Invalid member ID: {e}"))
-        })?;
-        Ok(Self(parsed))
-    }
-
-    pub fn new(uuid: Uuid) -> Self {
-        Self(uuid)
-    }
-}
-
-impl Default for MemberId {
-    fn default() -> Self {
-        Self(uuid::Uuid::new_v4())
-    }
-}
-
-impl AsRef<Uuid> for MemberId {
-    fn as_ref(&self) -> &Uuid {
-        &self.0
-    }
-}
+uuid_id!(MemberId, "member");
 
 #[test]
 fn test_valid_ids() {
@@ -1,8 +1,9 @@
 use super::ValidationError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct MemberName(String);
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MemberName(#[schemars(length(min = 1, max = 255))] String);
 
 impl MemberName {
     pub fn parse(name: String) -> Result<Self, ValidationError> {
@@ -1,35 +1,135 @@
+mod absence;
+mod attendance;
+mod backup_code;
+mod borrow;
+pub mod calendar;
+mod clock;
+mod comment;
+mod contracted_hours;
+mod cron;
+mod data_region;
 mod data_stores;
+mod department;
+mod display_name;
+mod domain_event;
 mod email;
 mod email_client;
+mod email_receipt;
+mod email_reply;
 mod error;
+mod escalation;
+mod id;
+mod id_generator;
+mod integration;
+mod invitation;
+mod job;
+mod legal_hold;
+mod locale;
 mod login_attempt_id;
 mod member;
+mod member_contact;
 mod member_id;
 mod member_name;
+mod notification;
 mod password;
+mod password_reset;
+mod phone_number;
+mod policy;
+pub mod print;
 mod project;
+mod project_history;
 mod project_id;
 mod project_name;
+mod project_settings;
+mod provisioning;
+mod quota;
+mod request_capture;
+mod retention;
+mod rota_approval;
+mod rota_import;
+mod rota_reaction;
+mod rota_version;
+mod security_alert;
+mod session;
 mod shift;
+mod shift_break;
+mod shift_note;
+mod shift_slot;
+mod shift_tag;
+mod sms_client;
+mod sms_reply;
+pub mod time;
+mod totp;
 mod two_fa_code;
+mod undeliverable_email;
 mod user;
 mod user_id;
 mod user_password_hash;
+mod verification_token;
+mod week_template;
 
+pub use absence::*;
+pub use attendance::*;
+pub use backup_code::*;
+pub use borrow::*;
+pub use clock::*;
+pub use comment::*;
+pub use contracted_hours::*;
+pub use cron::*;
+pub use data_region::*;
 pub use data_stores::*;
+pub use department::*;
+pub use display_name::*;
+pub use domain_event::*;
 pub use email::*;
 pub use email_client::*;
+pub use email_receipt::*;
+pub use email_reply::*;
 pub use error::*;
+pub use escalation::*;
+pub use id_generator::*;
+pub use integration::*;
+pub use invitation::*;
+pub use job::*;
+pub use legal_hold::*;
+pub use locale::*;
 pub use login_attempt_id::*;
 pub use member::*;
+pub use member_contact::*;
 pub use member_id::*;
 pub use member_name::*;
+pub use notification::*;
 pub use password::*;
+pub use password_reset::*;
+pub use phone_number::*;
+pub use policy::*;
 pub use project::*;
+pub use project_history::*;
 pub use project_id::*;
 pub use project_name::*;
+pub use project_settings::*;
+pub use provisioning::*;
+pub use quota::*;
+pub use request_capture::*;
+pub use retention::*;
+pub use rota_approval::*;
+pub use rota_import::*;
+pub use rota_reaction::*;
+pub use rota_version::*;
+pub use security_alert::*;
+pub use session::*;
 pub use shift::*;
+pub use shift_break::*;
+pub use shift_note::*;
+pub use shift_slot::*;
+pub use shift_tag::*;
+pub use sms_client::*;
+pub use sms_reply::*;
+pub use totp::*;
 pub use two_fa_code::*;
+pub use undeliverable_email::*;
 pub use user::*;
 pub use user_id::*;
 pub use user_password_hash::*;
+pub use verification_token::*;
+pub use week_template::*;
@@ -0,0 +1,201 @@
+use chrono::{DateTime, Utc};
+
+use super::{id::uuid_id, DomainEvent, ProjectId};
+
+uuid_id!(NotificationId, "notification");
+
+// Something in a user's in-app inbox, kept until the user is deleted
+#[derive(Debug, Clone, PartialEq)]
+pub struct Notification {
+    pub notification_id: NotificationId,
+    // Increases with each notification, for asking what's new since
+    pub cursor: i64,
+    pub project_id: Option<ProjectId>,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+    pub read_at: Option<DateTime<Utc>>,
+}
+
+// Who an event is news to. Approvers are found by the email address the
+// rota was submitted to, so they may not have an account.
+#[derive(Debug, Clone, PartialEq)]
+pub enum NotificationRecipient {
+    Owner,
+    Approver,
+}
+
+// What goes in the inbox for an event, worded for the recipient. Events the
+// owner caused themselves aren't news to them, so only rotas published by
+// the project's schedule are.
+#[derive(Debug, Clone, PartialEq)]
+pub struct InboxNotice {
+    pub recipient: NotificationRecipient,
+    pub message: String,
+}
+
+impl InboxNotice {
+    pub fn from_event(event: &DomainEvent, project_name: &str) -> Option<Self> {
+        let (recipient, message) = match event {
+            DomainEvent::SwapRequested {
+                member_name,
+                shift_date,
+                ..
+            } => (
+                NotificationRecipient::Owner,
+                format!(
+                    "{} asked to swap their {} shift on the {} rota",
+                    member_name.as_ref(),
+                    shift_date.format("%A %-d %B"),
+                    project_name
+                ),
+            ),
+            DomainEvent::RotaPublished {
+                week_of,
+                automatic: true,
+                ..
+            } => (
+                NotificationRecipient::Owner,
+                format!(
+                    "The {} rota for the week of {} was published \
+                     automatically",
+                    project_name, week_of
+                ),
+            ),
+            DomainEvent::RotaSubmitted { week_of, .. } => (
+                NotificationRecipient::Approver,
+                format!(
+                    "The {} rota for the week of {} is waiting for your \
+                     approval",
+                    project_name, week_of
+                ),
+            ),
+            DomainEvent::RotaReviewed {
+                week_of,
+                approved,
+                comment,
+                ..
+            } => {
+                let decision = if *approved { "approved" } else { "rejected" };
+                let comment = comment
+                    .as_ref()
+                    .map(|comment| format!(": {}", comment.as_ref()))
+                    .unwrap_or_default();
+                (
+                    NotificationRecipient::Owner,
+                    format!(
+                        "The {} rota for the week of {} was {}{}",
+                        project_name, week_of, decision, comment
+                    ),
+                )
+            }
+            _ => return None,
+        };
+        Some(Self { recipient, message })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::domain::{MemberId, MemberName, ShiftId, UserId};
+
+    fn week_of() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2025, 10, 26).unwrap()
+    }
+
+    #[test]
+    fn test_swap_request_notice() {
+        let notice = InboxNotice::from_event(
+            &DomainEvent::SwapRequested {
+                user_id: UserId::default(),
+                project_id: ProjectId::default(),
+                shift_id: ShiftId::default(),
+                member_id: MemberId::default(),
+                member_name: MemberName::parse("Ted".to_owned()).unwrap(),
+                shift_date: NaiveDate::from_ymd_opt(2025, 10, 27).unwrap(),
+            },
+            "Craggy Island",
+        );
+        assert_eq!(
+            notice,
+            Some(InboxNotice {
+                recipient: NotificationRecipient::Owner,
+                message: String::from(
+                    "Ted asked to swap their Monday 27 October shift on the \
+                     Craggy Island rota"
+                ),
+            })
+        );
+    }
+
+    #[test]
+    fn test_only_scheduled_publishes_are_news() {
+        let published = |automatic| DomainEvent::RotaPublished {
+            user_id: UserId::default(),
+            project_id: ProjectId::default(),
+            week_of: week_of(),
+            shifts: 2,
+            automatic,
+        };
+        assert_eq!(
+            InboxNotice::from_event(&published(true), "Craggy Island")
+                .map(|notice| notice.message),
+            Some(String::from(
+                "The Craggy Island rota for the week of 2025-10-26 was \
+                 published automatically"
+            ))
+        );
+        assert_eq!(
+            InboxNotice::from_event(&published(false), "Craggy Island"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_approval_notices() {
+        let submitted = InboxNotice::from_event(
+            &DomainEvent::RotaSubmitted {
+                user_id: UserId::default(),
+                project_id: ProjectId::default(),
+                week_of: week_of(),
+                shifts: 2,
+                automatic: false,
+            },
+            "Craggy Island",
+        )
+        .unwrap();
+        assert_eq!(submitted.recipient, NotificationRecipient::Approver);
+
+        let rejected = InboxNotice::from_event(
+            &DomainEvent::RotaReviewed {
+                user_id: UserId::default(),
+                project_id: ProjectId::default(),
+                week_of: week_of(),
+                approved: false,
+                comment: None,
+            },
+            "Craggy Island",
+        )
+        .unwrap();
+        assert_eq!(rejected.recipient, NotificationRecipient::Owner);
+        assert_eq!(
+            rejected.message,
+            "The Craggy Island rota for the week of 2025-10-26 was rejected"
+        );
+    }
+
+    #[test]
+    fn test_other_events_are_not_news() {
+        assert_eq!(
+            InboxNotice::from_event(
+                &DomainEvent::UserSignedUp {
+                    user_id: UserId::default()
+                },
+                "Craggy Island"
+            ),
+            None
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use secrecy::{ExposeSecret, Secret};
+
+use super::{UserId, ValidationError};
+
+// A single-use token emailed to a user who has forgotten their password
+#[derive(Debug, Clone)]
+pub struct PasswordResetToken(Secret<String>);
+
+impl PartialEq for PasswordResetToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl PasswordResetToken {
+    pub fn parse(token: Secret<String>) -> Result<Self, ValidationError> {
+        let parsed =
+            uuid::Uuid::try_parse(token.expose_secret()).map_err(|_| {
+                ValidationError::new("Invalid password reset token".to_string())
+            })?;
+        Ok(Self(Secret::new(parsed.to_string())))
+    }
+}
+
+impl Default for PasswordResetToken {
+    fn default() -> Self {
+        let token = String::from(uuid::Uuid::new_v4());
+        PasswordResetToken(Secret::new(token))
+    }
+}
+
+impl AsRef<Secret<String>> for PasswordResetToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// Whose password a reset token lets someone set, and until when
+#[derive(Debug, Clone, PartialEq)]
+pub struct PasswordReset {
+    pub user_id: UserId,
+    pub expires_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_tokens() {
+        let token = PasswordResetToken::default();
+        assert_eq!(
+            PasswordResetToken::parse(token.as_ref().clone()).unwrap(),
+            token
+        );
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        for token in ["", "123456", "5e90ca28-e1ad-4795-a190-089959c16e0"] {
+            let error =
+                PasswordResetToken::parse(Secret::new(token.to_string()))
+                    .expect_err(token);
+            assert_eq!(error.as_ref(), "Invalid password reset token");
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use super::ValidationError;
+
+// A phone number in E.164 format, e.g. +447700900123. Spaces, dashes and
+// brackets are allowed on input and dropped.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PhoneNumber(String);
+
+impl PhoneNumber {
+    pub fn parse(s: String) -> Result<Self, ValidationError> {
+        let number: String = s
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '-' | '(' | ')'))
+            .collect();
+        let digits = number.strip_prefix('+').unwrap_or_default();
+        if !(8..=15).contains(&digits.len())
+            || digits.starts_with('0')
+            || !digits.chars().all(|c| c.is_ascii_digit())
+        {
+            return Err(ValidationError::new(String::from(
+                "Phone number must be in international format, e.g. \
+                 +447700900123",
+            )));
+        }
+        Ok(Self(number))
+    }
+}
+
+impl AsRef<str> for PhoneNumber {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_phone_numbers() {
+        for (input, expected) in [
+            ("+447700900123", "+447700900123"),
+            ("+44 7700 900123", "+447700900123"),
+            ("+1 (202) 555-0143", "+12025550143"),
+        ] {
+            assert_eq!(
+                PhoneNumber::parse(input.to_owned()).unwrap().as_ref(),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_invalid_phone_numbers() {
+        for input in [
+            "",
+            "07700900123",
+            "+0447700900123",
+            "+44",
+            "+4477009001234567",
+            "+44 7700 9001x3",
+        ] {
+            assert!(
+                PhoneNumber::parse(input.to_owned()).is_err(),
+                "Should reject {:?}",
+                input
+            );
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use super::ValidationError;
+
+// A published version of the terms of service and privacy policy. They are
+// versioned together, and users accept both at once.
+#[derive(Debug, PartialEq)]
+pub struct PolicyDocument {
+    pub version: i32,
+    pub terms_of_service: &'static str,
+    pub privacy_policy: &'static str,
+}
+
+// Oldest first. Publish a new version by adding it here, then raise
+// REQUIRED_POLICY_VERSION to make existing users accept it.
+pub const POLICY_DOCUMENTS: &[PolicyDocument] = &[PolicyDocument {
+    version: 1,
+    terms_of_service: include_str!("../../policies/v1/terms_of_service.md"),
+    privacy_policy: include_str!("../../policies/v1/privacy_policy.md"),
+}];
+
+pub fn find_policy(version: i32) -> Option<&'static PolicyDocument> {
+    POLICY_DOCUMENTS
+        .iter()
+        .find(|policy| policy.version == version)
+}
+
+// A version a user says they accept, which must have been published
+pub fn parse_policy_version(
+    version: i32,
+) -> Result<&'static PolicyDocument, ValidationError> {
+    find_policy(version).ok_or_else(|| {
+        ValidationError::new(format!("Unknown policy version {}", version))
+    })
+}
+
+pub fn latest_policy() -> &'static PolicyDocument {
+    POLICY_DOCUMENTS
+        .last()
+        .expect("At least one policy version must be published")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_versions_increase() {
+        assert!(POLICY_DOCUMENTS
+            .windows(2)
+            .all(|pair| pair[0].version < pair[1].version));
+        assert!(POLICY_DOCUMENTS.iter().all(|policy| policy.version > 0));
+    }
+
+    #[test]
+    fn test_find_policy() {
+        assert_eq!(find_policy(1).map(|policy| policy.version), Some(1));
+        assert!(find_policy(0).is_none());
+        assert_eq!(find_policy(latest_policy().version), Some(latest_policy()));
+    }
+
+    #[test]
+    fn test_parse_policy_version() {
+        assert!(parse_policy_version(1).is_ok());
+        let error = parse_policy_version(99).expect_err("99");
+        assert_eq!(error.as_ref(), "Unknown policy version 99");
+    }
+}
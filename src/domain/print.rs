@@ -0,0 +1,400 @@
+use std::str::FromStr;
+
+use chrono::{Datelike, NaiveDate};
+
+use super::{
+    DateRange, Locale, Project, ProjectMember, Shift, ValidationError,
+};
+
+// Enough for a month on the wall
+pub const MAX_PRINT_DAYS: i64 = 31;
+
+// Which way round a printed rota goes: a row per member with a column per
+// day, or a row per day with a column per member
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PrintLayout {
+    #[default]
+    Members,
+    Days,
+}
+
+impl FromStr for PrintLayout {
+    type Err = ValidationError;
+
+    fn from_str(layout: &str) -> Result<Self, Self::Err> {
+        match layout {
+            "members" => Ok(PrintLayout::Members),
+            "days" => Ok(PrintLayout::Days),
+            _ => Err(ValidationError::new(String::from(
+                "Layout must be members or days",
+            ))),
+        }
+    }
+}
+
+// What is printed beside each shift's times
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct PrintOptions {
+    pub notes: bool,
+    pub locations: bool,
+}
+
+// A project's rota laid out as a table, with everything already formatted
+// for the reader's locale
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintedRota {
+    pub title: String,
+    pub period: String,
+    // The heading above the row headings
+    pub corner: String,
+    pub columns: Vec<String>,
+    pub rows: Vec<PrintedRow>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintedRow {
+    pub heading: String,
+    // One cell per column, each holding that day's shifts for that member
+    pub cells: Vec<Vec<PrintedShift>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PrintedShift {
+    pub times: String,
+    pub location: Option<String>,
+    pub note: Option<String>,
+}
+
+impl PrintedShift {
+    fn new(shift: &Shift, options: PrintOptions, locale: &Locale) -> Self {
+        Self {
+            times: format!(
+                "{}–{}",
+                locale.format_time(&shift.start_time),
+                locale.format_time(&shift.end_time)
+            ),
+            location: shift
+                .location
+                .as_ref()
+                .filter(|_| options.locations)
+                .map(|location| location.as_ref().clone()),
+            note: shift
+                .note
+                .as_ref()
+                .filter(|_| options.notes)
+                .map(|note| note.as_ref().clone()),
+        }
+    }
+
+    // e.g. "09:00–17:00 · Front desk · Bring the float"
+    pub fn summary(&self) -> String {
+        [
+            Some(&self.times),
+            self.location.as_ref(),
+            self.note.as_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(String::as_str)
+        .collect::<Vec<_>>()
+        .join(" · ")
+    }
+}
+
+// Lay out `project`'s shifts on each date in `range`. Members are listed
+// alphabetically and each cell's shifts in start time order.
+pub fn print_rota(
+    project: &Project,
+    range: &DateRange,
+    layout: PrintLayout,
+    options: PrintOptions,
+    locale: &Locale,
+) -> Result<PrintedRota, ValidationError> {
+    if range.days() > MAX_PRINT_DAYS {
+        return Err(ValidationError::new(format!(
+            "Printed rotas cannot cover more than {} days",
+            MAX_PRINT_DAYS
+        )));
+    }
+
+    let mut members: Vec<&ProjectMember> = project.members.iter().collect();
+    members.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+    let dates = range.dates();
+    let member_names: Vec<String> = members
+        .iter()
+        .map(|member| member.member_name.as_ref().clone())
+        .collect();
+    let date_labels: Vec<String> =
+        dates.iter().map(|date| locale.format_date(*date)).collect();
+
+    let (corner, columns, rows) = match layout {
+        PrintLayout::Members => (
+            "Member",
+            date_labels,
+            members
+                .iter()
+                .zip(member_names)
+                .map(|(member, heading)| PrintedRow {
+                    heading,
+                    cells: dates
+                        .iter()
+                        .map(|date| shifts_on(member, *date, options, locale))
+                        .collect(),
+                })
+                .collect(),
+        ),
+        PrintLayout::Days => (
+            "Day",
+            member_names,
+            dates
+                .iter()
+                .zip(date_labels)
+                .map(|(date, heading)| PrintedRow {
+                    heading,
+                    cells: members
+                        .iter()
+                        .map(|member| shifts_on(member, *date, options, locale))
+                        .collect(),
+                })
+                .collect(),
+        ),
+    };
+
+    Ok(PrintedRota {
+        title: project.project_name.as_ref().clone(),
+        period: format!(
+            "{} – {}",
+            locale.format_date(range.from()),
+            locale.format_date(range.to())
+        ),
+        corner: corner.to_owned(),
+        columns,
+        rows,
+    })
+}
+
+fn shifts_on(
+    member: &ProjectMember,
+    date: NaiveDate,
+    options: PrintOptions,
+    locale: &Locale,
+) -> Vec<PrintedShift> {
+    // A project's shifts are kept in start time order within each day
+    let weekday = date.weekday().num_days_from_sunday() as i16;
+    member
+        .shifts
+        .iter()
+        .filter(|shift| i16::from(shift.day) == weekday)
+        .map(|shift| PrintedShift::new(shift, options, locale))
+        .collect()
+}
+
+impl PrintedRota {
+    // The same table as CSV, with a cell's shifts on separate lines
+    pub fn to_csv(&self) -> String {
+        let mut lines = Vec::with_capacity(self.rows.len() + 1);
+        lines.push(csv_line(
+            std::iter::once(self.corner.clone()).chain(self.columns.clone()),
+        ));
+        for row in &self.rows {
+            lines.push(csv_line(std::iter::once(row.heading.clone()).chain(
+                row.cells.iter().map(|shifts| {
+                    shifts
+                        .iter()
+                        .map(PrintedShift::summary)
+                        .collect::<Vec<_>>()
+                        .join("\n")
+                }),
+            )));
+        }
+        lines.join("\r\n") + "\r\n"
+    }
+}
+
+fn csv_line(fields: impl Iterator<Item = String>) -> String {
+    fields
+        .map(|field| csv_field(&field))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+// Quote fields as RFC 4180 needs, and stop names and notes that look like
+// formulas from being run by spreadsheets
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_owned()
+    };
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        Day, MemberId, MemberName, Minute, ProjectId, ProjectName,
+        ShiftLocation, ShiftNote,
+    };
+
+    fn shift(day: Day, start_time: i16) -> Shift {
+        let mut shift = Shift::new(
+            MemberId::default(),
+            day,
+            Minute::parse(start_time).unwrap(),
+            Minute::parse(start_time + 240).unwrap(),
+        )
+        .unwrap();
+        shift.location = ShiftLocation::parse("Front desk").unwrap();
+        shift.note = ShiftNote::parse("Bring the float").unwrap();
+        shift
+    }
+
+    fn member(name: &str, shifts: Vec<Shift>) -> ProjectMember {
+        ProjectMember::new(
+            MemberId::default(),
+            MemberName::parse(name.to_owned()).unwrap(),
+            shifts,
+        )
+    }
+
+    fn project() -> Project {
+        Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Monday,
+            vec![
+                member(
+                    "Ted",
+                    vec![shift(Day::Tuesday, 780), shift(Day::Monday, 540)],
+                ),
+                member("Dougal", vec![shift(Day::Monday, 600)]),
+            ],
+        )
+    }
+
+    // Monday 20 to Tuesday 21 October 2025
+    fn range() -> DateRange {
+        DateRange::new(
+            NaiveDate::from_ymd_opt(2025, 10, 20).unwrap(),
+            NaiveDate::from_ymd_opt(2025, 10, 21).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn times(cell: &[PrintedShift]) -> Vec<&str> {
+        cell.iter().map(|shift| shift.times.as_str()).collect()
+    }
+
+    #[test]
+    fn test_layout_from_str() {
+        assert_eq!(
+            PrintLayout::from_str("members").unwrap(),
+            PrintLayout::Members
+        );
+        assert_eq!(PrintLayout::from_str("days").unwrap(), PrintLayout::Days);
+        assert!(PrintLayout::from_str("weeks").is_err());
+    }
+
+    #[test]
+    fn test_print_by_member() {
+        let rota = print_rota(
+            &project(),
+            &range(),
+            PrintLayout::Members,
+            PrintOptions::default(),
+            &Locale::EnGb,
+        )
+        .unwrap();
+
+        assert_eq!(rota.title, "Craggy Island");
+        assert_eq!(rota.period, "Monday 20/10/2025 – Tuesday 21/10/2025");
+        assert_eq!(rota.columns, ["Monday 20/10/2025", "Tuesday 21/10/2025"]);
+        let headings: Vec<&str> =
+            rota.rows.iter().map(|row| row.heading.as_str()).collect();
+        assert_eq!(headings, ["Dougal", "Ted"]);
+        assert_eq!(times(&rota.rows[0].cells[0]), ["10:00–14:00"]);
+        assert!(rota.rows[0].cells[1].is_empty());
+        assert_eq!(times(&rota.rows[1].cells[1]), ["13:00–17:00"]);
+        assert_eq!(rota.rows[1].cells[0][0].location, None);
+        assert_eq!(rota.rows[1].cells[0][0].note, None);
+    }
+
+    #[test]
+    fn test_print_by_day() {
+        let rota = print_rota(
+            &project(),
+            &range(),
+            PrintLayout::Days,
+            PrintOptions {
+                notes: true,
+                locations: true,
+            },
+            &Locale::EnUs,
+        )
+        .unwrap();
+
+        assert_eq!(rota.corner, "Day");
+        assert_eq!(rota.columns, ["Dougal", "Ted"]);
+        assert_eq!(rota.rows[0].heading, "Monday 10/20/2025");
+        assert_eq!(times(&rota.rows[0].cells[1]), ["9:00 AM–1:00 PM"]);
+        assert_eq!(
+            rota.rows[0].cells[1][0].summary(),
+            "9:00 AM–1:00 PM · Front desk · Bring the float"
+        );
+        assert!(rota.rows[1].cells[0].is_empty());
+    }
+
+    #[test]
+    fn test_print_range_is_limited() {
+        let from = NaiveDate::from_ymd_opt(2025, 10, 1).unwrap();
+        for (days, ok) in [(31, true), (32, false)] {
+            let range =
+                DateRange::new(from, from + chrono::Days::new(days - 1))
+                    .unwrap();
+            let result = print_rota(
+                &project(),
+                &range,
+                PrintLayout::Members,
+                PrintOptions::default(),
+                &Locale::EnGb,
+            );
+            assert_eq!(result.is_ok(), ok, "{} days", days);
+        }
+    }
+
+    #[test]
+    fn test_to_csv() {
+        let rota = print_rota(
+            &project(),
+            &range(),
+            PrintLayout::Members,
+            PrintOptions {
+                notes: true,
+                locations: false,
+            },
+            &Locale::EnGb,
+        )
+        .unwrap();
+
+        assert_eq!(
+            rota.to_csv(),
+            "Member,Monday 20/10/2025,Tuesday 21/10/2025\r\n\
+             Dougal,10:00–14:00 · Bring the float,\r\n\
+             Ted,09:00–13:00 · Bring the float,13:00–17:00 · Bring the float\r\n"
+        );
+    }
+
+    #[test]
+    fn test_csv_field_escaping() {
+        assert_eq!(csv_field("Ted"), "Ted");
+        assert_eq!(csv_field("Ted, Dougal"), "\"Ted, Dougal\"");
+        assert_eq!(csv_field("a \"quote\""), "\"a \"\"quote\"\"\"");
+        assert_eq!(csv_field("a\nb"), "\"a\nb\"");
+        assert_eq!(csv_field("=SUM(A1)"), "'=SUM(A1)");
+    }
+}
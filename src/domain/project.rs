@@ -1,15 +1,23 @@
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::domain::{ProjectName, Shift};
 
-use super::{MemberId, MemberName, ProjectId};
+use super::{
+    ContractedHours, Day, DepartmentId, MemberId, MemberName, ProjectId,
+    ShiftTag, ValidationError,
+};
 
-#[derive(Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize, JsonSchema,
+)]
 pub struct Project {
     #[serde(rename = "projectId")]
     pub project_id: ProjectId,
     #[serde(rename = "projectName")]
     pub project_name: ProjectName,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
     pub members: Vec<ProjectMember>,
 }
 
@@ -17,22 +25,56 @@ impl Project {
     pub fn new(
         project_id: ProjectId,
         project_name: ProjectName,
+        week_start: Day,
         members: Vec<ProjectMember>,
     ) -> Self {
-        Self {
+        let mut project = Self {
             project_id,
             project_name,
+            week_start,
             members,
+        };
+        project.sort_shifts();
+        project
+    }
+
+    // Put each member's shifts in the order they come in the project's
+    // week, and by start time within a day
+    pub fn sort_shifts(&mut self) {
+        let week_start = self.week_start;
+        for member in self.members.iter_mut() {
+            member.shifts.sort_by_key(|shift| {
+                (
+                    shift.day.days_after(week_start),
+                    shift.start_time.value_of(),
+                )
+            });
         }
     }
 }
 
-#[derive(Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize, JsonSchema,
+)]
 pub struct ProjectMember {
     #[serde(rename = "memberId")]
     pub member_id: MemberId,
     #[serde(rename = "memberName")]
     pub member_name: MemberName,
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contracted_hours: Option<ContractedHours>,
+    #[serde(
+        rename = "departmentId",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub department_id: Option<DepartmentId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
     pub shifts: Vec<Shift>,
 }
 
@@ -45,7 +87,178 @@ impl ProjectMember {
         Self {
             member_id,
             member_name,
+            contracted_hours: None,
+            department_id: None,
+            skills: Vec::new(),
             shifts,
         }
     }
 }
+
+// A project at a glance, for dashboards showing several at once
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSummary {
+    pub project_id: ProjectId,
+    pub project_name: ProjectName,
+    pub week_start: Day,
+    pub members: i64,
+    pub shifts: i64,
+}
+
+// A member's shifts over the seven days from a given date. Every shift
+// happens once a week, so these are the member's shifts less any they're
+// off for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemberShiftTotals {
+    pub member_id: MemberId,
+    pub member_name: MemberName,
+    pub shifts: i64,
+    // Paid minutes, so less unpaid breaks
+    pub minutes: i64,
+    pub absences: i64,
+}
+
+// How much of a project to load, from the project on its own up to its
+// members with all their shifts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ProjectInclude {
+    Nothing,
+    Members,
+    #[default]
+    MembersAndShifts,
+}
+
+impl ProjectInclude {
+    // A comma-separated list of relations, e.g. "members.shifts". Shifts
+    // come with the members they belong to; empty is the project on its own.
+    pub fn parse(include: &str) -> Result<Self, ValidationError> {
+        include
+            .split(',')
+            .map(str::trim)
+            .filter(|relation| !relation.is_empty())
+            .try_fold(Self::Nothing, |include, relation| {
+                let relation = match relation {
+                    "members" => Self::Members,
+                    "members.shifts" => Self::MembersAndShifts,
+                    _ => {
+                        return Err(ValidationError::new(format!(
+                            "Cannot include {}; use members or members.shifts",
+                            relation
+                        )))
+                    }
+                };
+                Ok(include.max(relation))
+            })
+    }
+}
+
+// Which members, and which of their shifts, to load. With a limit, members
+// come a page at a time in the order they're returned in, by name.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProjectWindow {
+    pub department_id: Option<DepartmentId>,
+    // Only shifts on these days
+    pub days: Option<Vec<Day>>,
+    pub offset: i64,
+    pub limit: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::Minute;
+
+    fn shift(member_id: &MemberId, day: Day, start_time: i16) -> Shift {
+        Shift::new(
+            member_id.clone(),
+            day,
+            Minute::parse(start_time).unwrap(),
+            Minute::parse(start_time + 60).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn shift_order(week_start: Day) -> Vec<(Day, i16)> {
+        let member_id = MemberId::default();
+        let shifts = vec![
+            shift(&member_id, Day::Sunday, 540),
+            shift(&member_id, Day::Wednesday, 600),
+            shift(&member_id, Day::Monday, 540),
+            shift(&member_id, Day::Wednesday, 480),
+            shift(&member_id, Day::Saturday, 540),
+        ];
+        let project = Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            week_start,
+            vec![ProjectMember::new(
+                member_id,
+                MemberName::parse("Ted".to_owned()).unwrap(),
+                shifts,
+            )],
+        );
+        project.members[0]
+            .shifts
+            .iter()
+            .map(|shift| (shift.day, shift.start_time.value_of()))
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_include() {
+        for (include, expected) in [
+            ("", ProjectInclude::Nothing),
+            ("members", ProjectInclude::Members),
+            ("members.shifts", ProjectInclude::MembersAndShifts),
+            ("members.shifts, members", ProjectInclude::MembersAndShifts),
+        ] {
+            assert_eq!(
+                ProjectInclude::parse(include).unwrap(),
+                expected,
+                "Failed for {:?}",
+                include
+            );
+        }
+        for include in ["shifts", "members.absences", "Members"] {
+            assert!(ProjectInclude::parse(include).is_err(), "{:?}", include);
+        }
+    }
+
+    #[test]
+    fn test_shifts_sorted_from_sunday() {
+        assert_eq!(
+            shift_order(Day::Sunday),
+            vec![
+                (Day::Sunday, 540),
+                (Day::Monday, 540),
+                (Day::Wednesday, 480),
+                (Day::Wednesday, 600),
+                (Day::Saturday, 540),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_shifts_sorted_from_week_start_wrap_around() {
+        assert_eq!(
+            shift_order(Day::Monday),
+            vec![
+                (Day::Monday, 540),
+                (Day::Wednesday, 480),
+                (Day::Wednesday, 600),
+                (Day::Saturday, 540),
+                (Day::Sunday, 540),
+            ]
+        );
+        assert_eq!(
+            shift_order(Day::Thursday),
+            vec![
+                (Day::Saturday, 540),
+                (Day::Sunday, 540),
+                (Day::Monday, 540),
+                (Day::Wednesday, 480),
+                (Day::Wednesday, 600),
+            ]
+        );
+    }
+}
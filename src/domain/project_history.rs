@@ -0,0 +1,116 @@
+use std::{fmt, str::FromStr};
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{DomainEvent, ValidationError};
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum EntityType {
+    Project,
+    Member,
+    Shift,
+}
+
+impl EntityType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EntityType::Project => "project",
+            EntityType::Member => "member",
+            EntityType::Shift => "shift",
+        }
+    }
+}
+
+impl FromStr for EntityType {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "project" => Ok(EntityType::Project),
+            "member" => Ok(EntityType::Member),
+            "shift" => Ok(EntityType::Shift),
+            _ => Err(ValidationError::new(String::from("Invalid entity type"))),
+        }
+    }
+}
+
+impl fmt::Display for EntityType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectHistoryEntry {
+    pub id: i64,
+    pub occurred_at: DateTime<Utc>,
+    pub event: DomainEvent,
+}
+
+const CURSOR_PREFIX: &str = "history:";
+
+// Where the next page of history starts: after the last entry the client
+// saw, however many entries have been added since. Opaque to clients.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryCursor(i64);
+
+impl HistoryCursor {
+    pub fn after(entry: &ProjectHistoryEntry) -> Self {
+        Self(entry.id)
+    }
+
+    pub fn parse(cursor: &str) -> Result<Self, ValidationError> {
+        URL_SAFE_NO_PAD
+            .decode(cursor)
+            .ok()
+            .and_then(|bytes| String::from_utf8(bytes).ok())
+            .and_then(|decoded| {
+                decoded.strip_prefix(CURSOR_PREFIX)?.parse::<i64>().ok()
+            })
+            .map(Self)
+            .ok_or_else(|| ValidationError::new(String::from("Invalid cursor")))
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}{}", CURSOR_PREFIX, self.0))
+    }
+}
+
+impl AsRef<i64> for HistoryCursor {
+    fn as_ref(&self) -> &i64 {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_entity_type_round_trip() {
+        for entity_type in
+            [EntityType::Project, EntityType::Member, EntityType::Shift]
+        {
+            assert_eq!(
+                EntityType::from_str(&entity_type.to_string()).unwrap(),
+                entity_type
+            );
+        }
+        assert!(EntityType::from_str("user").is_err());
+    }
+
+    #[test]
+    fn test_cursor_round_trip() {
+        let cursor = HistoryCursor(42);
+        assert_eq!(HistoryCursor::parse(&cursor.encode()).unwrap(), cursor);
+        for cursor in ["", "42", "not base64!", &URL_SAFE_NO_PAD.encode("42")] {
+            assert!(HistoryCursor::parse(cursor).is_err(), "{:?}", cursor);
+        }
+    }
+}
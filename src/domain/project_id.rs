@@ -1,34 +1,6 @@
-use super::ValidationError;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use super::id::uuid_id;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ProjectId(Uuid);
-
-impl ProjectId {
-    pub fn parse(id: &str) -> Result<Self, ValidationError> {
-        let parsed = uuid::Uuid::try_parse(id).map_err(|e| {
-            ValidationError::new(format!("Invalid project ID: {e}"))
-        })?;
-        Ok(Self(parsed))
-    }
-
-    pub fn new(uuid: Uuid) -> Self {
-        Self(uuid)
-    }
-}
-
-impl Default for ProjectId {
-    fn default() -> Self {
-        Self(uuid::Uuid::new_v4())
-    }
-}
-
-impl AsRef<Uuid> for ProjectId {
-    fn as_ref(&self) -> &Uuid {
-        &self.0
-    }
-}
+uuid_id!(ProjectId, "project");
 
 #[test]
 fn test_valid_ids() {
@@ -1,8 +1,9 @@
 use super::ValidationError;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ProjectName(String);
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectName(#[schemars(length(min = 1, max = 255))] String);
 
 impl ProjectName {
     pub fn parse(name: &str) -> Result<Self, ValidationError> {
@@ -0,0 +1,415 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    auto_breaks, BreakRule, ChatWebhookUrl, CronSchedule, Day, Email,
+    EscalationChannel, EscalationStep, MemberId, Minute, PhoneNumber, Shift,
+    ValidationError,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProjectSettings {
+    pub shift_granularity: ShiftGranularity,
+    pub min_shift_length: ShiftLength,
+    pub max_shift_length: ShiftLength,
+    pub break_rules: Vec<BreakRule>,
+    // Rota weeks run from this day, for ordering shifts and grouping reports
+    pub week_start: Day,
+    pub escalation_steps: Vec<EscalationStep>,
+    pub chat_webhook_url: Option<ChatWebhookUrl>,
+    pub escalation_phone: Option<PhoneNumber>,
+    // When next week's rota is published without anyone doing it by hand
+    pub auto_publish: Option<CronSchedule>,
+    // Who has to approve a rota before it's published, if anyone
+    pub approver_email: Option<Email>,
+}
+
+const MAX_BREAK_RULES: usize = 10;
+
+impl ProjectSettings {
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.min_shift_length.value_of() > self.max_shift_length.value_of() {
+            return Err(ValidationError::new(String::from(
+                "Minimum shift length cannot be more than the maximum",
+            )));
+        }
+        if self.break_rules.len() > MAX_BREAK_RULES {
+            return Err(ValidationError::new(format!(
+                "A project cannot have more than {} break rules",
+                MAX_BREAK_RULES
+            )));
+        }
+        let mut thresholds: Vec<i16> = self
+            .break_rules
+            .iter()
+            .map(|rule| rule.shift_longer_than())
+            .collect();
+        thresholds.sort_unstable();
+        thresholds.dedup();
+        if thresholds.len() != self.break_rules.len() {
+            return Err(ValidationError::new(String::from(
+                "Break rules must apply to different shift lengths",
+            )));
+        }
+        self.validate_escalation()
+    }
+
+    fn validate_escalation(&self) -> Result<(), ValidationError> {
+        let mut channels: Vec<EscalationChannel> = self
+            .escalation_steps
+            .iter()
+            .map(|step| step.channel())
+            .collect();
+        channels.sort_by_key(|channel| channel.as_str());
+        channels.dedup();
+        if channels.len() != self.escalation_steps.len() {
+            return Err(ValidationError::new(String::from(
+                "Each escalation channel can only be used once",
+            )));
+        }
+        if channels.contains(&EscalationChannel::Chat)
+            && self.chat_webhook_url.is_none()
+        {
+            return Err(ValidationError::new(String::from(
+                "Escalating to chat needs a chat webhook URL",
+            )));
+        }
+        if channels.contains(&EscalationChannel::OwnerPhone)
+            && self.escalation_phone.is_none()
+        {
+            return Err(ValidationError::new(String::from(
+                "Escalating to the owner's phone needs an escalation phone",
+            )));
+        }
+        Ok(())
+    }
+
+    // Make a shift that follows the project's rules. With `snap`, start and
+    // end times are rounded to the nearest allowed increment instead of being
+    // rejected. Breaks owed under the project's break rules are added.
+    pub fn new_shift(
+        &self,
+        member_id: MemberId,
+        day: Day,
+        start_time: Minute,
+        end_time: Minute,
+        snap: bool,
+    ) -> Result<Shift, ValidationError> {
+        let (start_time, end_time) = if snap {
+            (
+                self.shift_granularity.snap(&start_time),
+                self.shift_granularity.snap(&end_time),
+            )
+        } else {
+            self.shift_granularity.check(&start_time, "Start time")?;
+            self.shift_granularity.check(&end_time, "End time")?;
+            (start_time, end_time)
+        };
+
+        let mut shift = Shift::new(member_id, day, start_time, end_time)?;
+        self.check_shift_length(&shift)?;
+        shift.breaks =
+            auto_breaks(&self.break_rules, &shift, &self.shift_granularity);
+        Ok(shift)
+    }
+
+    // Whether an existing shift still follows the project's rules, which may
+    // have changed since it was added
+    pub fn check_shift(&self, shift: &Shift) -> Result<(), ValidationError> {
+        self.shift_granularity
+            .check(&shift.start_time, "Start time")?;
+        self.shift_granularity.check(&shift.end_time, "End time")?;
+        self.check_shift_length(shift)
+    }
+
+    fn check_shift_length(&self, shift: &Shift) -> Result<(), ValidationError> {
+        let length = shift.length();
+        if length < self.min_shift_length.value_of() {
+            return Err(ValidationError::new(format!(
+                "Shift must be at least {} minutes long",
+                self.min_shift_length.value_of()
+            )));
+        }
+        if length > self.max_shift_length.value_of() {
+            return Err(ValidationError::new(format!(
+                "Shift cannot be longer than {} minutes",
+                self.max_shift_length.value_of()
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProjectSettings {
+    fn default() -> Self {
+        Self {
+            shift_granularity: ShiftGranularity::default(),
+            min_shift_length: ShiftLength(1),
+            max_shift_length: ShiftLength(MINUTES_PER_DAY),
+            break_rules: Vec::new(),
+            week_start: Day::Sunday,
+            escalation_steps: Vec::new(),
+            chat_webhook_url: None,
+            escalation_phone: None,
+            auto_publish: None,
+            approver_email: None,
+        }
+    }
+}
+
+// The increment, in minutes, that shift times must fall on. It must divide
+// the day evenly so that midnight is always allowed.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftGranularity(#[schemars(range(min = 1, max = 1440))] i16);
+
+const MINUTES_PER_DAY: i16 = 1440;
+
+impl ShiftGranularity {
+    pub fn parse(minutes: i16) -> Result<Self, ValidationError> {
+        if !(1..=MINUTES_PER_DAY).contains(&minutes) {
+            return Err(ValidationError::new(String::from(
+                "Shift granularity must be between 1 and 1440 minutes",
+            )));
+        }
+        if MINUTES_PER_DAY % minutes != 0 {
+            return Err(ValidationError::new(String::from(
+                "Shift granularity must divide the day evenly",
+            )));
+        }
+        Ok(Self(minutes))
+    }
+
+    pub fn value_of(&self) -> i16 {
+        self.0
+    }
+
+    pub fn allows(&self, minute: &Minute) -> bool {
+        minute.value_of() % self.0 == 0
+    }
+
+    pub fn snap(&self, minute: &Minute) -> Minute {
+        let snapped = (minute.value_of() + self.0 / 2) / self.0 * self.0;
+        Minute::parse(snapped.min(MINUTES_PER_DAY))
+            .expect("Snapped minute is within the day")
+    }
+
+    fn check(
+        &self,
+        minute: &Minute,
+        label: &str,
+    ) -> Result<(), ValidationError> {
+        if self.allows(minute) {
+            return Ok(());
+        }
+        Err(ValidationError::new(format!(
+            "{} must be a multiple of {} minutes",
+            label, self.0
+        )))
+    }
+}
+
+impl Default for ShiftGranularity {
+    fn default() -> Self {
+        Self(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftLength(#[schemars(range(min = 1, max = 1440))] i16);
+
+impl ShiftLength {
+    pub fn parse(minutes: i16) -> Result<Self, ValidationError> {
+        if !(1..=MINUTES_PER_DAY).contains(&minutes) {
+            return Err(ValidationError::new(String::from(
+                "Shift length must be between 1 and 1440 minutes",
+            )));
+        }
+        Ok(Self(minutes))
+    }
+
+    pub fn value_of(&self) -> i16 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn minute(value: i16) -> Minute {
+        Minute::parse(value).unwrap()
+    }
+
+    #[test]
+    fn test_granularity_parse() {
+        for minutes in [1, 5, 15, 30, 60, 1440] {
+            assert!(ShiftGranularity::parse(minutes).is_ok());
+        }
+        for minutes in [0, -15, 7, 1441] {
+            assert!(ShiftGranularity::parse(minutes).is_err());
+        }
+    }
+
+    #[test]
+    fn test_granularity_snap() {
+        let granularity = ShiftGranularity::parse(15).unwrap();
+        assert_eq!(granularity.snap(&minute(0)).value_of(), 0);
+        assert_eq!(granularity.snap(&minute(547)).value_of(), 540);
+        assert_eq!(granularity.snap(&minute(548)).value_of(), 555);
+        assert_eq!(granularity.snap(&minute(1439)).value_of(), 1440);
+    }
+
+    #[test]
+    fn test_new_shift_rejects_unaligned_times() {
+        let settings = ProjectSettings {
+            shift_granularity: ShiftGranularity::parse(15).unwrap(),
+            ..Default::default()
+        };
+        let new_shift = |start, end, snap| {
+            settings.new_shift(
+                MemberId::default(),
+                Day::Monday,
+                minute(start),
+                minute(end),
+                snap,
+            )
+        };
+
+        assert!(new_shift(540, 1020, false).is_ok());
+        assert!(new_shift(541, 1020, false).is_err());
+        assert!(new_shift(540, 1021, false).is_err());
+
+        let shift = new_shift(541, 1021, true).unwrap();
+        assert_eq!(shift.start_time.value_of(), 540);
+        assert_eq!(shift.end_time.value_of(), 1020);
+
+        // Snapping both ends to the same time leaves no shift
+        assert!(new_shift(541, 543, true).is_err());
+    }
+
+    #[test]
+    fn test_new_shift_enforces_length_limits() {
+        let settings = ProjectSettings {
+            min_shift_length: ShiftLength::parse(60).unwrap(),
+            max_shift_length: ShiftLength::parse(600).unwrap(),
+            ..Default::default()
+        };
+        let new_shift = |start, end| {
+            settings.new_shift(
+                MemberId::default(),
+                Day::Monday,
+                minute(start),
+                minute(end),
+                false,
+            )
+        };
+
+        assert!(new_shift(540, 600).is_ok());
+        assert!(new_shift(0, 600).is_ok());
+        assert_eq!(
+            new_shift(540, 550).unwrap_err().to_string(),
+            "Validation error: Shift must be at least 60 minutes long"
+        );
+        assert_eq!(
+            new_shift(0, 960).unwrap_err().to_string(),
+            "Validation error: Shift cannot be longer than 600 minutes"
+        );
+    }
+
+    #[test]
+    fn test_settings_validate() {
+        assert!(ProjectSettings::default().validate().is_ok());
+
+        let settings = ProjectSettings {
+            min_shift_length: ShiftLength::parse(120).unwrap(),
+            max_shift_length: ShiftLength::parse(60).unwrap(),
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ProjectSettings {
+            break_rules: vec![
+                BreakRule::new(360, 30, false).unwrap(),
+                BreakRule::new(360, 45, false).unwrap(),
+            ],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_settings_validate_escalation() {
+        let step = |channel| EscalationStep::new(channel, 6).unwrap();
+        let settings = ProjectSettings {
+            escalation_steps: vec![
+                step(EscalationChannel::Email),
+                step(EscalationChannel::Sms),
+            ],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+
+        let settings = ProjectSettings {
+            escalation_steps: vec![
+                step(EscalationChannel::Email),
+                step(EscalationChannel::Sms),
+                step(EscalationChannel::Email),
+            ],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+
+        let settings = ProjectSettings {
+            escalation_steps: vec![step(EscalationChannel::Chat)],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+        let settings = ProjectSettings {
+            chat_webhook_url: Some(
+                ChatWebhookUrl::parse("https://example.com/hook".to_owned())
+                    .unwrap(),
+            ),
+            ..settings
+        };
+        assert!(settings.validate().is_ok());
+
+        let settings = ProjectSettings {
+            escalation_steps: vec![step(EscalationChannel::OwnerPhone)],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_err());
+        let settings = ProjectSettings {
+            escalation_phone: Some(
+                PhoneNumber::parse("+447700900123".to_owned()).unwrap(),
+            ),
+            ..settings
+        };
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_new_shift_inserts_breaks() {
+        let settings = ProjectSettings {
+            break_rules: vec![BreakRule::new(360, 30, false).unwrap()],
+            ..Default::default()
+        };
+        let new_shift = |start, end| {
+            settings
+                .new_shift(
+                    MemberId::default(),
+                    Day::Monday,
+                    minute(start),
+                    minute(end),
+                    false,
+                )
+                .unwrap()
+        };
+
+        assert!(new_shift(540, 900).breaks.is_empty());
+
+        let shift = new_shift(540, 1020);
+        assert_eq!(shift.breaks.len(), 1);
+        assert!(shift.breaks[0].auto_inserted);
+        assert_eq!(shift.paid_length(), 450);
+    }
+}
@@ -0,0 +1,57 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+// The ID an infrastructure-as-code tool knows a resource by, such as
+// "acme-staging". Provisioning with the same ID again finds the resource
+// rather than creating another.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ExternalId(#[schemars(length(min = 1, max = 128))] String);
+
+impl ExternalId {
+    pub fn parse(id: &str) -> Result<Self, ValidationError> {
+        let valid = (1..=128).contains(&id.len())
+            && id
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-_.:".contains(c));
+        match valid {
+            true => Ok(Self(id.to_owned())),
+            false => Err(ValidationError::new(String::from(
+                "External IDs must be 1 to 128 letters, digits, '-', '_', '.' \
+                 or ':'",
+            ))),
+        }
+    }
+}
+
+impl AsRef<String> for ExternalId {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// What provisioning a resource did, so tools can tell whether anything
+// drifted
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ProvisionStatus {
+    Created,
+    Updated,
+    Unchanged,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_external_id() {
+        for id in ["acme", "acme-staging", "org:acme/ted", "", "acme staging"] {
+            let valid = !id.is_empty() && !id.contains([' ', '/']);
+            assert_eq!(ExternalId::parse(id).is_ok(), valid, "{}", id);
+        }
+        assert!(ExternalId::parse(&"a".repeat(128)).is_ok());
+        assert!(ExternalId::parse(&"a".repeat(129)).is_err());
+    }
+}
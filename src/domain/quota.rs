@@ -0,0 +1,89 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Soft limits for now: going over them is allowed, but clients are told how
+// close they are so they can warn users before the limits are enforced
+pub const PROJECT_QUOTA: i64 = 25;
+pub const MEMBER_QUOTA: i64 = 500;
+pub const SHIFT_QUOTA: i64 = 20_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum QuotaResource {
+    Projects,
+    Members,
+    Shifts,
+}
+
+impl QuotaResource {
+    pub const ALL: [QuotaResource; 3] = [
+        QuotaResource::Projects,
+        QuotaResource::Members,
+        QuotaResource::Shifts,
+    ];
+
+    pub fn limit(&self) -> i64 {
+        match self {
+            QuotaResource::Projects => PROJECT_QUOTA,
+            QuotaResource::Members => MEMBER_QUOTA,
+            QuotaResource::Shifts => SHIFT_QUOTA,
+        }
+    }
+}
+
+// What an account has, across all its projects
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct QuotaUsage {
+    pub projects: i64,
+    pub members: i64,
+    pub shifts: i64,
+}
+
+impl QuotaUsage {
+    pub fn quota(&self, resource: QuotaResource) -> Quota {
+        let used = match resource {
+            QuotaResource::Projects => self.projects,
+            QuotaResource::Members => self.members,
+            QuotaResource::Shifts => self.shifts,
+        };
+        Quota {
+            resource,
+            limit: resource.limit(),
+            used,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Quota {
+    pub resource: QuotaResource,
+    pub limit: i64,
+    pub used: i64,
+}
+
+impl Quota {
+    // Never negative, even for accounts already over the limit
+    pub fn remaining(&self) -> i64 {
+        (self.limit - self.used).max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remaining() {
+        let usage = QuotaUsage {
+            projects: 3,
+            members: MEMBER_QUOTA + 10,
+            shifts: 0,
+        };
+
+        let projects = usage.quota(QuotaResource::Projects);
+        assert_eq!(projects.limit, PROJECT_QUOTA);
+        assert_eq!(projects.remaining(), PROJECT_QUOTA - 3);
+        assert_eq!(usage.quota(QuotaResource::Members).remaining(), 0);
+        assert_eq!(usage.quota(QuotaResource::Shifts).remaining(), SHIFT_QUOTA);
+    }
+}
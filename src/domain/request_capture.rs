@@ -0,0 +1,186 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+// Keys whose values are never captured, matched case-insensitively anywhere
+// in the key, e.g. "newPassword", "2FACode", "backupCodes" and an
+// integration "key"
+const SECRET_KEY_PARTS: &[&str] =
+    &["password", "token", "code", "secret", "otpauth", "key"];
+const REDACTED: &str = "[REDACTED]";
+// Larger bodies are left out, as are bodies that aren't JSON, since only
+// JSON can be redacted reliably
+const MAX_CAPTURED_BODY_BYTES: usize = 16 * 1024;
+
+// A request made with a user's session and the response it got, with
+// secrets redacted, for reproducing a bug the user reported
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CapturedRequest {
+    pub captured_at: DateTime<Utc>,
+    pub method: String,
+    // With its query string
+    pub uri: String,
+    pub status: u16,
+    pub request_body: Option<Value>,
+    pub response_body: Option<Value>,
+}
+
+impl CapturedRequest {
+    pub fn new(
+        captured_at: DateTime<Utc>,
+        method: &str,
+        path: &str,
+        query: Option<&str>,
+        status: u16,
+        request_body: &[u8],
+        response_body: &[u8],
+    ) -> Self {
+        let uri = match query {
+            Some(query) => format!("{}?{}", path, redact_query(query)),
+            None => path.to_owned(),
+        };
+        Self {
+            captured_at,
+            method: method.to_owned(),
+            uri,
+            status,
+            request_body: capture_body(request_body),
+            response_body: capture_body(response_body),
+        }
+    }
+}
+
+fn is_secret(key: &str) -> bool {
+    let key = key.to_ascii_lowercase();
+    SECRET_KEY_PARTS.iter().any(|part| key.contains(part))
+}
+
+fn capture_body(body: &[u8]) -> Option<Value> {
+    if body.is_empty() {
+        return None;
+    }
+    if body.len() > MAX_CAPTURED_BODY_BYTES {
+        return Some(Value::String(format!(
+            "[{} bytes not captured]",
+            body.len()
+        )));
+    }
+    match serde_json::from_slice::<Value>(body) {
+        Ok(mut value) => {
+            redact_json(&mut value);
+            Some(value)
+        }
+        Err(_) => Some(Value::String(format!(
+            "[{} bytes of non-JSON not captured]",
+            body.len()
+        ))),
+    }
+}
+
+fn redact_json(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            for (key, field) in fields.iter_mut() {
+                match is_secret(key) {
+                    true => *field = Value::String(REDACTED.to_owned()),
+                    false => redact_json(field),
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(redact_json),
+        _ => (),
+    }
+}
+
+fn redact_query(query: &str) -> String {
+    form_urlencoded::parse(query.as_bytes())
+        .fold(
+            form_urlencoded::Serializer::new(String::new()),
+            |mut serializer, (key, value)| {
+                match is_secret(&key) {
+                    true => serializer.append_pair(&key, REDACTED),
+                    false => serializer.append_pair(&key, &value),
+                };
+                serializer
+            },
+        )
+        .finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn capture(query: Option<&str>, request_body: &[u8]) -> CapturedRequest {
+        CapturedRequest::new(
+            Utc::now(),
+            "POST",
+            "/auth/verify-2fa",
+            query,
+            400,
+            request_body,
+            br#"{"error": "Validation error: Invalid code"}"#,
+        )
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        let body = json!({
+            "email": "ted@example.com",
+            "password": "hunter22",
+            "2FACode": "123456",
+            "nested": [{"newPassword": "hunter23", "memberName": "Ted"}],
+            "backupCodes": ["a", "b"],
+            "key": "rk_abc"
+        });
+
+        let captured =
+            capture(None, serde_json::to_vec(&body).unwrap().as_slice());
+
+        assert_eq!(
+            captured.request_body,
+            Some(json!({
+                "email": "ted@example.com",
+                "password": REDACTED,
+                "2FACode": REDACTED,
+                "nested": [{"newPassword": REDACTED, "memberName": "Ted"}],
+                "backupCodes": REDACTED,
+                "key": REDACTED
+            }))
+        );
+        assert_eq!(
+            captured.response_body,
+            Some(json!({"error": "Validation error: Invalid code"}))
+        );
+    }
+
+    #[test]
+    fn test_query_secrets_are_redacted() {
+        let captured = capture(Some("token=abc&projectId=1"), b"");
+        assert_eq!(
+            captured.uri,
+            "/auth/verify-2fa?token=%5BREDACTED%5D&projectId=1"
+        );
+        assert_eq!(captured.request_body, None);
+    }
+
+    #[test]
+    fn test_unredactable_bodies_are_left_out() {
+        let captured = capture(None, b"password=hunter22");
+        assert_eq!(
+            captured.request_body,
+            Some(json!("[17 bytes of non-JSON not captured]"))
+        );
+
+        let large = vec![b' '; MAX_CAPTURED_BODY_BYTES + 1];
+        assert_eq!(
+            capture(None, &large).request_body,
+            Some(json!(format!(
+                "[{} bytes not captured]",
+                MAX_CAPTURED_BODY_BYTES + 1
+            )))
+        );
+    }
+}
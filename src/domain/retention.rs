@@ -0,0 +1,23 @@
+use chrono::TimeDelta;
+
+// How long each kind of record is kept before the retention job deletes it
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetentionPolicy {
+    // Project history, and domain events once they've been delivered
+    pub audit_log: TimeDelta,
+    // Sent emails and text messages, and in-app notifications
+    pub notification_log: TimeDelta,
+    // Published rotas and decided approvals, counted from the week they
+    // were for
+    pub rota_history: TimeDelta,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            audit_log: TimeDelta::days(365),
+            notification_log: TimeDelta::days(90),
+            rota_history: TimeDelta::days(2 * 365),
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::uuid_id, Email, Project, ProjectName, RotaChanges, RotaVersion,
+    ValidationError,
+};
+
+const MAX_COMMENT_LENGTH: usize = 1000;
+
+uuid_id!(RotaApprovalId, "rota approval");
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalStatus {
+    Pending,
+    Approved,
+    Rejected,
+    // Replaced by a later submission before anyone reviewed it
+    Withdrawn,
+}
+
+impl ApprovalStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApprovalStatus::Pending => "pending",
+            ApprovalStatus::Approved => "approved",
+            ApprovalStatus::Rejected => "rejected",
+            ApprovalStatus::Withdrawn => "withdrawn",
+        }
+    }
+}
+
+impl FromStr for ApprovalStatus {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "pending" => Ok(ApprovalStatus::Pending),
+            "approved" => Ok(ApprovalStatus::Approved),
+            "rejected" => Ok(ApprovalStatus::Rejected),
+            "withdrawn" => Ok(ApprovalStatus::Withdrawn),
+            _ => Err(ValidationError::new(String::from(
+                "Invalid approval status",
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ApprovalStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// What the approver had to say about a rota, e.g. "Ted can't do Mondays"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ReviewComment(#[schemars(length(min = 1, max = 1000))] String);
+
+impl ReviewComment {
+    // Blank comments are treated as no comment
+    pub fn parse(comment: &str) -> Result<Option<Self>, ValidationError> {
+        let comment = comment.trim();
+        if comment.is_empty() {
+            return Ok(None);
+        }
+        if comment.chars().count() > MAX_COMMENT_LENGTH {
+            return Err(ValidationError::new(format!(
+                "Comments cannot be longer than {} characters",
+                MAX_COMMENT_LENGTH
+            )));
+        }
+        Ok(Some(Self(comment.to_owned())))
+    }
+}
+
+impl AsRef<String> for ReviewComment {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// A rota waiting for, or given, the approver's go-ahead. The version is
+// published as it stands once approved; `changes` are against the version
+// that was last published when it was submitted.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotaApproval {
+    pub approval_id: RotaApprovalId,
+    pub project_name: ProjectName,
+    pub version: RotaVersion,
+    pub changes: RotaChanges,
+    pub approver_email: Email,
+    pub status: ApprovalStatus,
+    pub comment: Option<ReviewComment>,
+    pub submitted_at: DateTime<Utc>,
+    pub reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl RotaApproval {
+    pub fn new(
+        project: &Project,
+        week_of: NaiveDate,
+        automatic: bool,
+        published: Option<&RotaVersion>,
+        approver_email: Email,
+        now: DateTime<Utc>,
+    ) -> Self {
+        let version = RotaVersion::new(project, week_of, automatic, now);
+        let changes = RotaChanges::between(
+            published.map(|version| &version.shifts[..]).unwrap_or(&[]),
+            &version.shifts,
+        );
+        Self {
+            approval_id: RotaApprovalId::default(),
+            project_name: project.project_name.clone(),
+            version,
+            changes,
+            approver_email,
+            status: ApprovalStatus::Pending,
+            comment: None,
+            submitted_at: now,
+            reviewed_at: None,
+        }
+    }
+
+    // The subject and body of the email asking the approver to review it
+    pub fn review_request_email(&self) -> (String, String) {
+        let changes = &self.changes;
+        (
+            format!(
+                "A {} rota is waiting for your approval",
+                self.project_name.as_ref()
+            ),
+            format!(
+                "The {} rota for the week of {} has been submitted for your \
+                 approval. Since the last published rota, {} shift(s) were \
+                 added, {} removed and {} changed. Sign in to approve or \
+                 reject it.",
+                self.project_name.as_ref(),
+                self.version.week_of,
+                changes.added.len(),
+                changes.removed.len(),
+                changes.changed.len()
+            ),
+        )
+    }
+
+    // The subject and body of the email telling the owner how it went
+    pub fn review_email(&self) -> (String, String) {
+        let (decision, outcome) = match self.status {
+            ApprovalStatus::Approved => ("approved", "approved and published"),
+            _ => ("rejected", "rejected"),
+        };
+        let comment = self
+            .comment
+            .as_ref()
+            .map(|comment| format!("\n\nComment:\n{}", comment.as_ref()))
+            .unwrap_or_default();
+        (
+            format!(
+                "Your {} rota was {}",
+                self.project_name.as_ref(),
+                decision
+            ),
+            format!(
+                "The {} rota for the week of {} was {}.{}",
+                self.project_name.as_ref(),
+                self.version.week_of,
+                outcome,
+                comment
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        time::parse_date, Day, MemberId, MemberName, Minute, ProjectId,
+        ProjectMember, Shift,
+    };
+    use secrecy::Secret;
+
+    fn project(shifts: &[Day]) -> Project {
+        let ted = MemberId::default();
+        Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Sunday,
+            vec![ProjectMember::new(
+                ted.clone(),
+                MemberName::parse(String::from("Ted")).unwrap(),
+                shifts
+                    .iter()
+                    .map(|day| {
+                        Shift::new(
+                            ted.clone(),
+                            *day,
+                            Minute::parse(540).unwrap(),
+                            Minute::parse(1020).unwrap(),
+                        )
+                        .unwrap()
+                    })
+                    .collect(),
+            )],
+        )
+    }
+
+    fn approver() -> Email {
+        Email::parse(Secret::new(String::from("len@example.com"))).unwrap()
+    }
+
+    #[test]
+    fn test_parse_status() {
+        for status in [
+            ApprovalStatus::Pending,
+            ApprovalStatus::Approved,
+            ApprovalStatus::Rejected,
+            ApprovalStatus::Withdrawn,
+        ] {
+            assert_eq!(
+                ApprovalStatus::from_str(status.as_str()).unwrap(),
+                status
+            );
+        }
+        assert!(ApprovalStatus::from_str("maybe").is_err());
+    }
+
+    #[test]
+    fn test_parse_comment() {
+        assert_eq!(
+            ReviewComment::parse(" Ted can't do Mondays\n")
+                .unwrap()
+                .unwrap()
+                .as_ref(),
+            "Ted can't do Mondays"
+        );
+        assert_eq!(ReviewComment::parse("  ").unwrap(), None);
+        assert!(ReviewComment::parse(&"a".repeat(1001)).is_err());
+        assert!(ReviewComment::parse(&"a".repeat(1000)).is_ok());
+    }
+
+    #[test]
+    fn test_new_approval_compares_with_published_version() {
+        let week_of = parse_date("2025-10-26").unwrap();
+        let first = RotaApproval::new(
+            &project(&[Day::Monday]),
+            week_of,
+            false,
+            None,
+            approver(),
+            Utc::now(),
+        );
+        assert_eq!(first.status, ApprovalStatus::Pending);
+        assert_eq!(first.changes.added.len(), 1);
+
+        let project = project(&[Day::Tuesday]);
+        let second = RotaApproval::new(
+            &project,
+            week_of,
+            false,
+            Some(&first.version),
+            approver(),
+            Utc::now(),
+        );
+        assert_eq!(second.changes.added.len(), 1);
+        assert_eq!(second.changes.removed.len(), 1);
+        assert_eq!(second.version.project_id, project.project_id);
+    }
+
+    #[test]
+    fn test_review_email() {
+        let mut approval = RotaApproval::new(
+            &project(&[Day::Monday]),
+            parse_date("2025-10-26").unwrap(),
+            false,
+            None,
+            approver(),
+            Utc::now(),
+        );
+        approval.status = ApprovalStatus::Rejected;
+        approval.comment =
+            ReviewComment::parse("Ted can't do Mondays").unwrap();
+
+        let (subject, content) = approval.review_email();
+        assert_eq!(subject, "Your Craggy Island rota was rejected");
+        assert_eq!(
+            content,
+            "The Craggy Island rota for the week of 2025-10-26 was \
+             rejected.\n\nComment:\nTed can't do Mondays"
+        );
+    }
+}
@@ -0,0 +1,527 @@
+use std::{fmt, str::FromStr};
+
+use chrono::{Datelike, NaiveDate, NaiveTime, Timelike};
+
+use super::{
+    Day, Member, MemberName, Minute, ProjectSettings, Shift, ShiftTag,
+    ValidationError,
+};
+
+// Enough for a few weeks of a large venue's rota
+pub const MAX_IMPORT_ROWS: usize = 5000;
+const TIME_FORMATS: &[&str] = &["%H:%M", "%H:%M:%S", "%I:%M %p", "%I:%M%p"];
+
+// The rota tools whose CSV exports can be imported
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Deputy,
+    WhenIWork,
+}
+
+impl FromStr for ImportFormat {
+    type Err = ValidationError;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "deputy" => Ok(ImportFormat::Deputy),
+            "when-i-work" => Ok(ImportFormat::WhenIWork),
+            _ => Err(ValidationError::new(String::from(
+                "Format must be deputy or when-i-work",
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ImportFormat::Deputy => write!(f, "deputy"),
+            ImportFormat::WhenIWork => write!(f, "when-i-work"),
+        }
+    }
+}
+
+// Where each field is in a file's rows, found from its header
+#[derive(Debug, Clone, PartialEq)]
+struct Columns {
+    format: ImportFormat,
+    // The whole name, or the first name with the last name after it
+    name: Vec<usize>,
+    date: usize,
+    start: usize,
+    end: usize,
+    // When I Work gives the date a shift ends separately
+    end_date: Option<usize>,
+    // Deputy's area or When I Work's position, kept as a tag
+    tag: Option<usize>,
+}
+
+impl Columns {
+    fn find(header: &[String], format: Option<ImportFormat>) -> Option<Self> {
+        let find = |names: &[&str]| {
+            header.iter().position(|heading| {
+                names.contains(&heading.trim().to_lowercase().as_str())
+            })
+        };
+        let deputy = || {
+            Some(Self {
+                format: ImportFormat::Deputy,
+                name: vec![find(&["employee", "employee name"])?],
+                date: find(&["date"])?,
+                start: find(&["start", "start time"])?,
+                end: find(&["end", "end time"])?,
+                end_date: None,
+                tag: find(&["area", "area name"]),
+            })
+        };
+        let when_i_work = || {
+            let name = match find(&["employee", "employee name"]) {
+                Some(name) => vec![name],
+                None => vec![find(&["first name"])?, find(&["last name"])?],
+            };
+            Some(Self {
+                format: ImportFormat::WhenIWork,
+                name,
+                date: find(&["start date"])?,
+                start: find(&["start time"])?,
+                end: find(&["end time"])?,
+                end_date: find(&["end date"]),
+                tag: find(&["position"]),
+            })
+        };
+        match format {
+            Some(ImportFormat::Deputy) => deputy(),
+            Some(ImportFormat::WhenIWork) => when_i_work(),
+            None => when_i_work().or_else(deputy),
+        }
+    }
+}
+
+// A shift read from an import file, before it's matched to a member
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportedShift {
+    // Counted from 1, with the header as line 1
+    pub line: usize,
+    pub member_name: MemberName,
+    pub day: Day,
+    pub start_time: Minute,
+    pub end_time: Minute,
+    pub tag: Option<ShiftTag>,
+}
+
+impl ImportedShift {
+    fn is_same_as(&self, other: &ImportedShift) -> bool {
+        same_name(&self.member_name, &other.member_name)
+            && self.day == other.day
+            && self.start_time == other.start_time
+            && self.end_time == other.end_time
+    }
+}
+
+// A row that couldn't be imported, or a clash between rows
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImportProblem {
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for ImportProblem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "Line {}: {}", line, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+
+// A rota exported from another tool. Shifts repeat every week here, so each
+// dated shift becomes a shift on its day of the week, and the same shift
+// in several weeks of an export is only imported once.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotaImport {
+    pub format: ImportFormat,
+    pub shifts: Vec<ImportedShift>,
+    pub problems: Vec<ImportProblem>,
+}
+
+impl RotaImport {
+    // The format is worked out from the header when it isn't given
+    pub fn parse(
+        csv: &str,
+        format: Option<ImportFormat>,
+    ) -> Result<Self, ValidationError> {
+        let mut rows = parse_csv(csv)
+            .into_iter()
+            .enumerate()
+            .map(|(i, row)| (i + 1, row))
+            .filter(|(_, row)| {
+                row.iter().any(|field| !field.trim().is_empty())
+            });
+        let (_, header) = rows.next().ok_or_else(|| {
+            ValidationError::new(String::from("The file is empty"))
+        })?;
+        let columns = Columns::find(&header, format).ok_or_else(|| {
+            ValidationError::new(match format {
+                Some(format) => {
+                    format!("The file's columns don't match {}'s", format)
+                }
+                None => String::from(
+                    "The file's columns don't match any format that can be \
+                     imported",
+                ),
+            })
+        })?;
+        let rows: Vec<(usize, Vec<String>)> = rows.collect();
+        if rows.len() > MAX_IMPORT_ROWS {
+            return Err(ValidationError::new(format!(
+                "Files cannot have more than {} rows",
+                MAX_IMPORT_ROWS
+            )));
+        }
+
+        let mut import = Self {
+            format: columns.format,
+            shifts: Vec::new(),
+            problems: Vec::new(),
+        };
+        for (line, row) in rows {
+            match parse_row(&columns, line, &row) {
+                Ok(shift) => {
+                    if !import.shifts.iter().any(|s| s.is_same_as(&shift)) {
+                        import.shifts.push(shift);
+                    }
+                }
+                Err(e) => import.problems.push(ImportProblem {
+                    line: Some(line),
+                    message: e.as_ref().to_owned(),
+                }),
+            }
+        }
+        Ok(import)
+    }
+
+    // Names in the file that aren't the name of any of `members`, in the
+    // order they first come. Names are matched ignoring case.
+    pub fn new_member_names(&self, members: &[Member]) -> Vec<MemberName> {
+        let mut names: Vec<MemberName> = Vec::new();
+        for shift in &self.shifts {
+            let known = members
+                .iter()
+                .map(|member| &member.member_name)
+                .chain(&names)
+                .any(|name| same_name(name, &shift.member_name));
+            if !known {
+                names.push(shift.member_name.clone());
+            }
+        }
+        names
+    }
+
+    // The file's shifts as new shifts under the project's rules, with times
+    // rounded to the project's increments. `members` must include everyone
+    // named in the file. Shifts that break the rules are left out and
+    // returned as problems.
+    pub fn instantiate(
+        &self,
+        settings: &ProjectSettings,
+        members: &[Member],
+    ) -> (Vec<Shift>, Vec<ImportProblem>) {
+        let mut shifts = Vec::new();
+        let mut problems = Vec::new();
+        for imported in &self.shifts {
+            let shift = members
+                .iter()
+                .find(|member| {
+                    same_name(&member.member_name, &imported.member_name)
+                })
+                .ok_or_else(|| {
+                    ValidationError::new(String::from(
+                        "Member is not in the project",
+                    ))
+                })
+                .and_then(|member| {
+                    settings.new_shift(
+                        member.member_id.clone(),
+                        imported.day,
+                        imported.start_time.clone(),
+                        imported.end_time.clone(),
+                        true,
+                    )
+                });
+            match shift {
+                Ok(mut shift) => {
+                    shift.tags = imported.tag.iter().cloned().collect();
+                    shifts.push(shift);
+                }
+                Err(e) => problems.push(ImportProblem {
+                    line: Some(imported.line),
+                    message: e.as_ref().to_owned(),
+                }),
+            }
+        }
+        (shifts, problems)
+    }
+}
+
+fn same_name(a: &MemberName, b: &MemberName) -> bool {
+    a.as_ref().to_lowercase() == b.as_ref().to_lowercase()
+}
+
+fn parse_row(
+    columns: &Columns,
+    line: usize,
+    row: &[String],
+) -> Result<ImportedShift, ValidationError> {
+    let field = |i: usize| row.get(i).map(|field| field.trim()).unwrap_or("");
+
+    let name = columns
+        .name
+        .iter()
+        .map(|&i| field(i))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let member_name = MemberName::parse(name)?;
+    let date = parse_date(field(columns.date), columns.format)?;
+    let start_time = parse_time(field(columns.start))?;
+    let end_date = match columns.end_date.map(field) {
+        Some(end_date) if !end_date.is_empty() => {
+            parse_date(end_date, columns.format)?
+        }
+        _ => date,
+    };
+    let end_time = match (end_date == date, parse_time(field(columns.end))?) {
+        // A shift ending at midnight, given as 00:00 on either day
+        (true, 0) => 1440,
+        (false, 0) if date.succ_opt() == Some(end_date) => 1440,
+        (true, end_time) if end_time > start_time => end_time,
+        _ => {
+            return Err(ValidationError::new(String::from(
+                "Shifts running past midnight can't be imported",
+            )))
+        }
+    };
+
+    Ok(ImportedShift {
+        line,
+        member_name,
+        day: Day::try_from(date.weekday().num_days_from_sunday() as i16)?,
+        start_time: Minute::parse(start_time)?,
+        end_time: Minute::parse(end_time)?,
+        tag: match field(columns.tag.unwrap_or(usize::MAX)) {
+            "" => None,
+            tag => Some(ShiftTag::parse(tag)?),
+        },
+    })
+}
+
+// ISO dates are read from either tool. Otherwise Deputy, from Australia,
+// writes the day first, and When I Work, from the US, the month first.
+fn parse_date(
+    date: &str,
+    format: ImportFormat,
+) -> Result<NaiveDate, ValidationError> {
+    let slashed = match format {
+        ImportFormat::Deputy => "%d/%m/%Y",
+        ImportFormat::WhenIWork => "%m/%d/%Y",
+    };
+    ["%Y-%m-%d", slashed]
+        .iter()
+        .find_map(|f| NaiveDate::parse_from_str(date, f).ok())
+        .ok_or_else(|| {
+            ValidationError::new(format!("\"{}\" is not a date", date))
+        })
+}
+
+// In minutes since midnight. Times on the hour may leave out the minutes,
+// as in "5pm".
+fn parse_time(time: &str) -> Result<i16, ValidationError> {
+    let mut upper = time.to_uppercase();
+    if !upper.contains(':') {
+        let hour_end = upper
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(upper.len());
+        upper.insert_str(hour_end, ":00");
+    }
+    TIME_FORMATS
+        .iter()
+        .find_map(|f| NaiveTime::parse_from_str(&upper, f).ok())
+        .map(|time| (time.hour() * 60 + time.minute()) as i16)
+        .ok_or_else(|| {
+            ValidationError::new(format!("\"{}\" is not a time", time))
+        })
+}
+
+// RFC 4180, as every spreadsheet writes it: fields separated by commas,
+// quoted when they hold commas, quotes or line breaks, with quotes doubled
+fn parse_csv(csv: &str) -> Vec<Vec<String>> {
+    let csv = csv.strip_prefix('\u{feff}').unwrap_or(csv);
+    let mut rows = Vec::new();
+    let mut row = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = csv.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (quoted, c) {
+            (true, '"') if chars.peek() == Some(&'"') => {
+                chars.next();
+                field.push('"');
+            }
+            (true, '"') => quoted = false,
+            (true, c) => field.push(c),
+            (false, '"') if field.is_empty() => quoted = true,
+            (false, ',') => row.push(std::mem::take(&mut field)),
+            (false, '\r') if chars.peek() == Some(&'\n') => (),
+            (false, '\n') => {
+                row.push(std::mem::take(&mut field));
+                rows.push(std::mem::take(&mut row));
+            }
+            (false, c) => field.push(c),
+        }
+    }
+    if !field.is_empty() || !row.is_empty() {
+        row.push(field);
+        rows.push(row);
+    }
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MemberId, ProjectId, ShiftGranularity};
+
+    fn member(name: &str) -> Member {
+        Member::new(
+            ProjectId::default(),
+            MemberId::default(),
+            MemberName::parse(name.to_owned()).unwrap(),
+        )
+    }
+
+    fn minutes(shift: &ImportedShift) -> (i16, i16) {
+        (shift.start_time.value_of(), shift.end_time.value_of())
+    }
+
+    #[test]
+    fn test_deputy_export() {
+        let csv = "\u{feff}Employee,Date,Start,End,Area\r\n\
+                   Ted Crilly,06/01/2025,09:00,17:00,Bar\r\n\
+                   \"Dougal McGuire\",07/01/2025,9:30 am,5pm,\r\n";
+
+        let import = RotaImport::parse(csv, None).unwrap();
+
+        assert_eq!(import.format, ImportFormat::Deputy);
+        assert!(import.problems.is_empty());
+        assert_eq!(import.shifts.len(), 2);
+        assert_eq!(import.shifts[0].member_name.as_ref(), "Ted Crilly");
+        assert_eq!(import.shifts[0].day, Day::Monday);
+        assert_eq!(minutes(&import.shifts[0]), (540, 1020));
+        assert_eq!(import.shifts[0].tag, Some(ShiftTag::parse("bar").unwrap()));
+        assert_eq!(import.shifts[1].day, Day::Tuesday);
+        assert_eq!(minutes(&import.shifts[1]), (570, 1020));
+        assert_eq!(import.shifts[1].tag, None);
+    }
+
+    #[test]
+    fn test_when_i_work_export() {
+        let csv = "First Name,Last Name,Position,Start Date,Start Time,\
+                   End Date,End Time\n\
+                   Ted,Crilly,Barista,01/06/2025,6:00 PM,01/07/2025,12:00 AM\n\
+                   Ted,Crilly,Barista,01/13/2025,6:00 PM,01/14/2025,12:00 AM\n\
+                   Jack,Hackett,,01/06/2025,10:00 PM,01/07/2025,2:00 AM\n";
+
+        let import = RotaImport::parse(csv, None).unwrap();
+
+        assert_eq!(import.format, ImportFormat::WhenIWork);
+        // The second week's shift is the same as the first's
+        assert_eq!(import.shifts.len(), 1);
+        assert_eq!(import.shifts[0].member_name.as_ref(), "Ted Crilly");
+        assert_eq!(import.shifts[0].day, Day::Monday);
+        assert_eq!(minutes(&import.shifts[0]), (1080, 1440));
+        assert_eq!(
+            import.problems,
+            vec![ImportProblem {
+                line: Some(4),
+                message: String::from(
+                    "Shifts running past midnight can't be imported"
+                ),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_bad_rows_are_problems() {
+        let csv = "Employee,Date,Start,End\n\
+                   ,06/01/2025,09:00,17:00\n\
+                   Ted,2025-13-01,09:00,17:00\n\
+                   Ted,2025-01-06,nine,17:00\n";
+
+        let import =
+            RotaImport::parse(csv, Some(ImportFormat::Deputy)).unwrap();
+
+        assert!(import.shifts.is_empty());
+        let problems: Vec<String> =
+            import.problems.iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            problems,
+            vec![
+                "Line 2: Member name cannot be empty",
+                "Line 3: \"2025-13-01\" is not a date",
+                "Line 4: \"nine\" is not a time",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unrecognised_files() {
+        assert!(RotaImport::parse("", None).is_err());
+        assert!(
+            RotaImport::parse("Name,Day,Hours\nTed,Monday,8\n", None).is_err()
+        );
+        assert!(RotaImport::parse(
+            "Employee,Date,Start,End\n",
+            Some(ImportFormat::WhenIWork)
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_csv_quoting() {
+        assert_eq!(
+            parse_csv("a,\"b, \"\"c\"\"\",\"d\ne\"\nf"),
+            vec![vec!["a", "b, \"c\"", "d\ne"], vec!["f"],]
+        );
+    }
+
+    #[test]
+    fn test_members_are_matched_by_name() {
+        let csv = "Employee,Date,Start,End\n\
+                   ted crilly,06/01/2025,09:00,17:07\n\
+                   Mrs Doyle,06/01/2025,09:00,17:00\n\
+                   MRS DOYLE,07/01/2025,09:00,17:00\n";
+        let import = RotaImport::parse(csv, None).unwrap();
+        let mut members = vec![member("Ted Crilly")];
+
+        let names = import.new_member_names(&members);
+        assert_eq!(names.len(), 1);
+        assert_eq!(names[0].as_ref(), "Mrs Doyle");
+
+        members.push(member("Mrs Doyle"));
+        let settings = ProjectSettings {
+            shift_granularity: ShiftGranularity::parse(15).unwrap(),
+            ..ProjectSettings::default()
+        };
+        let (shifts, problems) = import.instantiate(&settings, &members);
+        assert!(problems.is_empty());
+        assert_eq!(shifts.len(), 3);
+        assert_eq!(shifts[0].member_id, members[0].member_id);
+        // Rounded to the project's increments
+        assert_eq!(shifts[0].end_time.value_of(), 1020);
+        assert_eq!(shifts[2].member_id, members[1].member_id);
+
+        let (shifts, problems) = import.instantiate(&settings, &members[..1]);
+        assert_eq!(shifts.len(), 1);
+        assert_eq!(problems.len(), 2);
+    }
+}
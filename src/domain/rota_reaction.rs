@@ -0,0 +1,104 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{MemberId, MemberName, ProjectName, ValidationError};
+
+// Long enough for emoji built from several characters, like 👍🏽 or 🧑‍🍳
+const MAX_REACTION_LENGTH: usize = 10;
+const SEEN: &str = "👍";
+
+// A member's quick reaction to a published rota, e.g. "👍"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Reaction(#[schemars(length(min = 1, max = 10))] String);
+
+impl Reaction {
+    // Only emoji, so no letters, digits, spaces or ASCII punctuation
+    pub fn parse(reaction: &str) -> Result<Self, ValidationError> {
+        let reaction = reaction.trim();
+        if reaction.is_empty()
+            || reaction.chars().count() > MAX_REACTION_LENGTH
+            || reaction.chars().any(|c| {
+                c.is_alphanumeric()
+                    || c.is_whitespace()
+                    || c.is_control()
+                    || c.is_ascii_punctuation()
+            })
+        {
+            return Err(ValidationError::new(String::from(
+                "Reactions must be a single emoji",
+            )));
+        }
+        Ok(Self(reaction.to_owned()))
+    }
+
+    // A text reacting to the rota: an emoji on its own, or SEEN for 👍
+    pub fn from_sms(body: &str) -> Option<Self> {
+        let keyword = body.split_whitespace().next()?.to_uppercase();
+        if keyword.trim_end_matches(['.', ',', '!']) == "SEEN" {
+            return Some(Self(SEEN.to_owned()));
+        }
+        Self::parse(body).ok()
+    }
+}
+
+impl AsRef<String> for Reaction {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// How a member reacted to a published version of the rota. Reacting again
+// replaces it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotaReaction {
+    pub member_id: MemberId,
+    pub member_name: MemberName,
+    pub reaction: Reaction,
+    pub reacted_at: DateTime<Utc>,
+}
+
+// The rota a member reacted to by text
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReactedRota {
+    pub project_name: ProjectName,
+    pub week_of: NaiveDate,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_reaction() {
+        for reaction in ["👍", " 🎉 ", "👍🏽", "🧑‍🍳"] {
+            assert!(Reaction::parse(reaction).is_ok(), "{:?}", reaction);
+        }
+        for reaction in ["", "ok", "👍 thanks", "!", "1", &"👍".repeat(11)]
+        {
+            assert!(Reaction::parse(reaction).is_err(), "{:?}", reaction);
+        }
+    }
+
+    #[test]
+    fn test_reaction_from_sms() {
+        for (body, expected) in [
+            ("SEEN", Some("👍")),
+            ("seen, thanks", Some("👍")),
+            ("Seen!", Some("👍")),
+            (" 🎉\n", Some("🎉")),
+            ("YES", None),
+            ("seems fine", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                Reaction::from_sms(body)
+                    .as_ref()
+                    .map(|reaction| reaction.as_ref().as_str()),
+                expected,
+                "Failed for {:?}",
+                body
+            );
+        }
+    }
+}
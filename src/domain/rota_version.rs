@@ -0,0 +1,420 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::uuid_id, Absence, Day, MemberId, MemberName, Minute, Project,
+    ProjectId, ProjectSettings, ShiftId, ShiftLocation, ShiftNote, ShiftTag,
+};
+
+// How far under their contracted hours a member can be scheduled before
+// publishing warns about it
+const UNDER_CONTRACT_TOLERANCE_PERCENT: i64 = 10;
+
+uuid_id!(RotaVersionId, "rota version");
+
+// A shift as it was published. Members' names are kept so a published week
+// still reads the same after someone is renamed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PublishedShift {
+    #[serde(rename = "shiftId")]
+    pub shift_id: ShiftId,
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    pub day: Day,
+    #[serde(rename = "startTime")]
+    pub start_time: Minute,
+    #[serde(rename = "endTime")]
+    pub end_time: Minute,
+    #[serde(rename = "standbyMemberId", default)]
+    pub standby_member_id: Option<MemberId>,
+    #[serde(default)]
+    pub tags: Vec<ShiftTag>,
+    #[serde(default)]
+    pub note: Option<ShiftNote>,
+    #[serde(default)]
+    pub location: Option<ShiftLocation>,
+}
+
+// A project's week of shifts as published for the week beginning `week_of`.
+// The shifts themselves repeat every week and can be changed at any time;
+// a published version is what members were told they are working.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RotaVersion {
+    pub version_id: RotaVersionId,
+    pub project_id: ProjectId,
+    pub week_of: NaiveDate,
+    pub shifts: Vec<PublishedShift>,
+    // Published by the project's schedule rather than by hand
+    pub automatic: bool,
+    pub published_at: DateTime<Utc>,
+}
+
+impl RotaVersion {
+    pub fn new(
+        project: &Project,
+        week_of: NaiveDate,
+        automatic: bool,
+        now: DateTime<Utc>,
+    ) -> Self {
+        Self {
+            version_id: RotaVersionId::default(),
+            project_id: project.project_id.clone(),
+            week_of,
+            shifts: published_shifts(project),
+            automatic,
+            published_at: now,
+        }
+    }
+}
+
+// The project's shifts as they would be published now, by member name
+pub fn published_shifts(project: &Project) -> Vec<PublishedShift> {
+    let mut members: Vec<_> = project.members.iter().collect();
+    members.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+    members
+        .into_iter()
+        .flat_map(|member| {
+            member.shifts.iter().map(|shift| PublishedShift {
+                shift_id: shift.id.clone(),
+                member_id: member.member_id.clone(),
+                member_name: member.member_name.clone(),
+                day: shift.day,
+                start_time: shift.start_time.clone(),
+                end_time: shift.end_time.clone(),
+                standby_member_id: shift.standby_member_id.clone(),
+                tags: shift.tags.clone(),
+                note: shift.note.clone(),
+                location: shift.location.clone(),
+            })
+        })
+        .collect()
+}
+
+// How one set of published shifts differs from another, matching shifts up
+// by ID. Shifts keep their ID when they are edited, so an edited shift is
+// changed rather than removed and added again.
+#[derive(
+    Debug, Clone, Default, PartialEq, Serialize, Deserialize, JsonSchema,
+)]
+pub struct RotaChanges {
+    pub added: Vec<PublishedShift>,
+    pub removed: Vec<PublishedShift>,
+    pub changed: Vec<ShiftChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftChange {
+    pub before: PublishedShift,
+    pub after: PublishedShift,
+}
+
+impl RotaChanges {
+    // Added and changed shifts are in the order of `to`, removed shifts in
+    // the order of `from`
+    pub fn between(from: &[PublishedShift], to: &[PublishedShift]) -> Self {
+        let find = |shifts: &[PublishedShift], shift: &PublishedShift| {
+            shifts
+                .iter()
+                .find(|other| other.shift_id == shift.shift_id)
+                .cloned()
+        };
+
+        let mut changes = Self::default();
+        for after in to {
+            match find(from, after) {
+                None => changes.added.push(after.clone()),
+                Some(before) if before != *after => {
+                    changes.changed.push(ShiftChange {
+                        before,
+                        after: after.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        changes.removed = from
+            .iter()
+            .filter(|before| find(to, before).is_none())
+            .cloned()
+            .collect();
+        changes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+// What stops the week beginning `week_of` being published as the project
+// stands: shifts that break the project's rules, members on two shifts at
+// once and absences nobody is covering. Empty when it's fine to publish.
+pub fn publish_problems(
+    project: &Project,
+    settings: &ProjectSettings,
+    absences: &[Absence],
+) -> Vec<String> {
+    let mut members: Vec<_> = project.members.iter().collect();
+    members.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+
+    let mut problems = Vec::new();
+    for member in &members {
+        let name = member.member_name.as_ref();
+        for (i, shift) in member.shifts.iter().enumerate() {
+            if let Err(e) = settings.check_shift(shift) {
+                problems.push(format!(
+                    "{}'s {} {}–{} shift: {}",
+                    name,
+                    shift.day,
+                    shift.start_time,
+                    shift.end_time,
+                    e.as_ref()
+                ));
+            }
+            if member.shifts[i + 1..]
+                .iter()
+                .any(|other| shift.overlaps(other))
+            {
+                problems.push(format!(
+                    "{} has overlapping shifts on {}",
+                    name, shift.day
+                ));
+            }
+        }
+    }
+
+    for absence in absences {
+        if absence.standby_member_id.is_some() {
+            continue;
+        }
+        let name = members
+            .iter()
+            .find(|member| member.member_id == absence.member_id)
+            .map(|member| member.member_name.as_ref().as_str())
+            .unwrap_or("A member");
+        problems.push(format!(
+            "{} is off on {} and nobody is covering their shift",
+            name, absence.date
+        ));
+    }
+
+    problems
+}
+
+// Members scheduled well short of their contracted hours. Unlike
+// `publish_problems` these don't stop the rota being published.
+pub fn publish_warnings(project: &Project) -> Vec<String> {
+    let mut members: Vec<_> = project.members.iter().collect();
+    members.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+
+    let mut warnings = Vec::new();
+    for member in members {
+        let Some(contracted_hours) = member.contracted_hours else {
+            continue;
+        };
+        let contracted = contracted_hours.minutes_per_week();
+        let scheduled: i64 = member
+            .shifts
+            .iter()
+            .map(|shift| shift.paid_length() as i64)
+            .sum();
+        if scheduled * 100
+            < contracted * (100 - UNDER_CONTRACT_TOLERANCE_PERCENT)
+        {
+            warnings.push(format!(
+                "{} is scheduled for {}h {}m of their {}h {}m contracted \
+                 hours",
+                member.member_name.as_ref(),
+                scheduled / 60,
+                scheduled % 60,
+                contracted / 60,
+                contracted % 60
+            ));
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{
+        time::parse_date, AbsenceReason, ContractedHours, ProjectMember,
+        ProjectName, Shift, ShiftLength,
+    };
+
+    fn shift(member_id: &MemberId, day: Day, start: i16, end: i16) -> Shift {
+        Shift::new(
+            member_id.clone(),
+            day,
+            Minute::parse(start).unwrap(),
+            Minute::parse(end).unwrap(),
+        )
+        .unwrap()
+    }
+
+    fn project(members: Vec<(&str, &MemberId, Vec<Shift>)>) -> Project {
+        Project::new(
+            ProjectId::default(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Sunday,
+            members
+                .into_iter()
+                .map(|(name, member_id, shifts)| {
+                    ProjectMember::new(
+                        member_id.clone(),
+                        MemberName::parse(name.to_owned()).unwrap(),
+                        shifts,
+                    )
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_new_version_snapshots_shifts_by_member_name() {
+        let ted = MemberId::default();
+        let dougal = MemberId::default();
+        let project = project(vec![
+            ("Ted", &ted, vec![shift(&ted, Day::Monday, 540, 1020)]),
+            (
+                "Dougal",
+                &dougal,
+                vec![shift(&dougal, Day::Friday, 540, 780)],
+            ),
+        ]);
+        let week_of = parse_date("2025-10-26").unwrap();
+
+        let version = RotaVersion::new(&project, week_of, true, Utc::now());
+
+        assert_eq!(version.project_id, project.project_id);
+        assert_eq!(version.week_of, week_of);
+        assert!(version.automatic);
+        let names: Vec<&str> = version
+            .shifts
+            .iter()
+            .map(|shift| shift.member_name.as_ref().as_str())
+            .collect();
+        assert_eq!(names, ["Dougal", "Ted"]);
+        assert_eq!(version.shifts[1].member_id, ted);
+        assert_eq!(version.shifts[1].day, Day::Monday);
+    }
+
+    #[test]
+    fn test_changes_between_versions() {
+        let ted = MemberId::default();
+        let dougal = MemberId::default();
+        let monday = shift(&ted, Day::Monday, 540, 1020);
+        let tuesday = shift(&ted, Day::Tuesday, 540, 1020);
+        let mut friday = shift(&dougal, Day::Friday, 540, 780);
+        let week_of = parse_date("2025-10-26").unwrap();
+        let before = RotaVersion::new(
+            &project(vec![
+                ("Ted", &ted, vec![monday.clone(), tuesday]),
+                ("Dougal", &dougal, vec![friday.clone()]),
+            ]),
+            week_of,
+            false,
+            Utc::now(),
+        );
+        friday.end_time = Minute::parse(840).unwrap();
+        let wednesday = shift(&ted, Day::Wednesday, 540, 1020);
+        let after = RotaVersion::new(
+            &project(vec![
+                ("Ted", &ted, vec![monday, wednesday.clone()]),
+                ("Dougal", &dougal, vec![friday]),
+            ]),
+            week_of,
+            false,
+            Utc::now(),
+        );
+
+        let changes = RotaChanges::between(&before.shifts, &after.shifts);
+
+        assert_eq!(changes.added.len(), 1);
+        assert_eq!(changes.added[0].shift_id, wednesday.id);
+        assert_eq!(changes.removed.len(), 1);
+        assert_eq!(changes.removed[0].day, Day::Tuesday);
+        assert_eq!(changes.changed.len(), 1);
+        assert_eq!(changes.changed[0].before.end_time.value_of(), 780);
+        assert_eq!(changes.changed[0].after.end_time.value_of(), 840);
+        assert!(RotaChanges::between(&after.shifts, &after.shifts).is_empty());
+    }
+
+    #[test]
+    fn test_publish_problems() {
+        let ted = MemberId::default();
+        let dougal = MemberId::default();
+        let monday = shift(&ted, Day::Monday, 540, 1020);
+        let project = project(vec![
+            (
+                "Ted",
+                &ted,
+                vec![monday.clone(), shift(&ted, Day::Monday, 960, 1080)],
+            ),
+            (
+                "Dougal",
+                &dougal,
+                vec![shift(&dougal, Day::Friday, 540, 570)],
+            ),
+        ]);
+        let settings = ProjectSettings {
+            min_shift_length: ShiftLength::parse(60).unwrap(),
+            ..Default::default()
+        };
+        let mut absence = Absence::new(
+            &monday,
+            parse_date("2025-10-27").unwrap(),
+            AbsenceReason::Sickness,
+        )
+        .unwrap();
+
+        assert_eq!(
+            publish_problems(&project, &settings, &[absence.clone()]),
+            [
+                "Dougal's Friday 09:00–09:30 shift: Shift must be at least 60 \
+                 minutes long",
+                "Ted has overlapping shifts on Monday",
+                "Ted is off on 2025-10-27 and nobody is covering their shift",
+            ]
+        );
+
+        absence.standby_member_id = Some(dougal.clone());
+        let project = self::project(vec![("Ted", &ted, vec![monday])]);
+        assert!(publish_problems(&project, &settings, &[absence]).is_empty());
+    }
+
+    #[test]
+    fn test_publish_warnings() {
+        let ted = MemberId::default();
+        let dougal = MemberId::default();
+        let mut project = project(vec![
+            ("Ted", &ted, vec![shift(&ted, Day::Monday, 540, 1020)]),
+            (
+                "Dougal",
+                &dougal,
+                vec![
+                    shift(&dougal, Day::Monday, 540, 1020),
+                    shift(&dougal, Day::Tuesday, 540, 1020),
+                ],
+            ),
+        ]);
+        assert!(publish_warnings(&project).is_empty());
+
+        // Dougal's 16 hours are within 10% of the 17 contracted, but Ted's 8
+        // are well short of 16
+        project.members[0].contracted_hours =
+            Some(ContractedHours::parse(16.0).unwrap());
+        project.members[1].contracted_hours =
+            Some(ContractedHours::parse(17.0).unwrap());
+
+        assert_eq!(
+            publish_warnings(&project),
+            ["Ted is scheduled for 8h 0m of their 16h 0m contracted hours"]
+        );
+    }
+}
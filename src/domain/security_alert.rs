@@ -0,0 +1,202 @@
+use super::{DomainEvent, IntegrationKeyName};
+
+// A change to an account's security that its owner should hear about, in
+// case it wasn't them
+#[derive(Debug, Clone, PartialEq)]
+pub enum SecurityAlert {
+    EmailChanged,
+    PasswordChanged,
+    BackupCodeUsed { remaining: i64 },
+    AuthenticatorAppSetUp,
+    IntegrationKeyCreated { name: IntegrationKeyName },
+    TwoFactorChanged { enabled: bool },
+}
+
+impl SecurityAlert {
+    pub fn from_event(event: &DomainEvent) -> Option<Self> {
+        match event {
+            DomainEvent::UserEmailChanged { .. } => Some(Self::EmailChanged),
+            DomainEvent::UserPasswordChanged { .. } => {
+                Some(Self::PasswordChanged)
+            }
+            DomainEvent::BackupCodeUsed { remaining, .. } => {
+                Some(Self::BackupCodeUsed {
+                    remaining: *remaining,
+                })
+            }
+            DomainEvent::AuthenticatorAppSetUp { .. } => {
+                Some(Self::AuthenticatorAppSetUp)
+            }
+            DomainEvent::IntegrationKeyCreated { key_name, .. } => {
+                Some(Self::IntegrationKeyCreated {
+                    name: key_name.clone(),
+                })
+            }
+            DomainEvent::TwoFactorChanged { enabled, .. } => {
+                Some(Self::TwoFactorChanged { enabled: *enabled })
+            }
+            _ => None,
+        }
+    }
+
+    // Critical alerts are sent even if the user has turned security emails
+    // off, as they may be the only sign someone else has the account
+    pub fn is_critical(&self) -> bool {
+        match self {
+            Self::EmailChanged
+            | Self::PasswordChanged
+            | Self::AuthenticatorAppSetUp => true,
+            // A key keeps working after the password is changed
+            Self::IntegrationKeyCreated { .. } => true,
+            Self::BackupCodeUsed { .. } => false,
+            // Turning 2FA off leaves only the password guarding the account
+            Self::TwoFactorChanged { enabled } => !enabled,
+        }
+    }
+
+    pub fn summary(&self) -> &'static str {
+        match self {
+            Self::EmailChanged => "Your email address was changed",
+            Self::PasswordChanged => "Your password was changed",
+            Self::BackupCodeUsed { .. } => {
+                "A backup code was used to sign in to your account"
+            }
+            Self::AuthenticatorAppSetUp => {
+                "An authenticator app was set up for your account"
+            }
+            Self::IntegrationKeyCreated { .. } => {
+                "An integration key was created for your account"
+            }
+            Self::TwoFactorChanged { enabled: true } => {
+                "Two-factor authentication was turned on"
+            }
+            Self::TwoFactorChanged { enabled: false } => {
+                "Two-factor authentication was turned off"
+            }
+        }
+    }
+
+    pub fn detail(&self) -> String {
+        match self {
+            Self::EmailChanged => String::from(
+                "Your account's email address was changed and confirmed \
+                 from both the old and the new address.",
+            ),
+            Self::PasswordChanged => String::from(
+                "Your account's password was reset with a link sent to your \
+                 email address. If this wasn't you, reset it again and check \
+                 who has access to your email.",
+            ),
+            Self::BackupCodeUsed { remaining } => {
+                let codes = if *remaining == 1 { "code" } else { "codes" };
+                format!(
+                    "Someone signed in with one of your backup codes instead \
+                     of an emailed code. You have {} backup {} left.",
+                    remaining, codes
+                )
+            }
+            Self::AuthenticatorAppSetUp => String::from(
+                "Codes from a newly set up authenticator app can now be used \
+                 to sign in to your account, in place of any app set up \
+                 before. New backup codes were issued with it.",
+            ),
+            Self::IntegrationKeyCreated { name } => format!(
+                "An integration key named {} was created. Tools using it \
+                 can read updates to your projects until it's revoked in \
+                 your account settings.",
+                name.as_ref()
+            ),
+            Self::TwoFactorChanged { enabled: true } => String::from(
+                "Signing in to your account now needs a code as well as \
+                 your password.",
+            ),
+            Self::TwoFactorChanged { enabled: false } => String::from(
+                "Signing in to your account now needs only your password. \
+                 If this wasn't you, change your password and turn \
+                 two-factor authentication back on.",
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{IntegrationKeyId, UserId};
+
+    #[test]
+    fn test_from_event() {
+        let user_id = UserId::default();
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::UserEmailChanged {
+                user_id: user_id.clone()
+            }),
+            Some(SecurityAlert::EmailChanged)
+        );
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::UserPasswordChanged {
+                user_id: user_id.clone()
+            }),
+            Some(SecurityAlert::PasswordChanged)
+        );
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::BackupCodeUsed {
+                user_id: user_id.clone(),
+                remaining: 9
+            }),
+            Some(SecurityAlert::BackupCodeUsed { remaining: 9 })
+        );
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::AuthenticatorAppSetUp {
+                user_id: user_id.clone()
+            }),
+            Some(SecurityAlert::AuthenticatorAppSetUp)
+        );
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::TwoFactorChanged {
+                user_id: user_id.clone(),
+                enabled: false
+            }),
+            Some(SecurityAlert::TwoFactorChanged { enabled: false })
+        );
+        assert_eq!(
+            SecurityAlert::from_event(&DomainEvent::UserSignedUp { user_id }),
+            None
+        );
+    }
+
+    #[test]
+    fn test_integration_key_detail_names_the_key() {
+        let name = IntegrationKeyName::parse("Zapier").unwrap();
+        let alert =
+            SecurityAlert::from_event(&DomainEvent::IntegrationKeyCreated {
+                user_id: UserId::default(),
+                key_id: IntegrationKeyId::default(),
+                key_name: name.clone(),
+            })
+            .unwrap();
+
+        assert_eq!(alert, SecurityAlert::IntegrationKeyCreated { name });
+        assert!(alert.detail().contains("named Zapier"));
+    }
+
+    #[test]
+    fn test_only_turning_2fa_off_is_critical() {
+        assert!(
+            SecurityAlert::TwoFactorChanged { enabled: false }.is_critical()
+        );
+        assert!(
+            !SecurityAlert::TwoFactorChanged { enabled: true }.is_critical()
+        );
+    }
+
+    #[test]
+    fn test_detail() {
+        assert!(SecurityAlert::BackupCodeUsed { remaining: 1 }
+            .detail()
+            .ends_with("You have 1 backup code left."));
+        assert!(SecurityAlert::BackupCodeUsed { remaining: 0 }
+            .detail()
+            .ends_with("You have 0 backup codes left."));
+    }
+}
@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{id::uuid_id, UserId};
+
+uuid_id!(SessionId, "session");
+
+// A sign-in, lasting as long as the auth token it was given. Its device and
+// IP address are as reported when it started, so only good for helping the
+// user recognise it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Session {
+    pub session_id: SessionId,
+    pub user_id: UserId,
+    // The User-Agent it signed in with
+    pub device: Option<String>,
+    pub ip: Option<String>,
+    pub issued_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl Session {
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at > now
+    }
+}
@@ -1,19 +1,36 @@
-use super::{MemberId, ValidationError};
+use super::{
+    id::uuid_id, Member, MemberId, ShiftBreak, ShiftLocation, ShiftNote,
+    ShiftTag, ValidationError,
+};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use std::str::FromStr;
-use uuid::Uuid;
 
-#[derive(Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, PartialEq, sqlx::FromRow, Serialize, Deserialize, JsonSchema,
+)]
 pub struct Shift {
     pub id: ShiftId,
     #[serde(skip_serializing)]
+    #[schemars(skip)]
     pub member_id: MemberId,
     pub day: Day,
     #[serde(rename = "startTime")]
     pub start_time: Minute,
     #[serde(rename = "endTime")]
     pub end_time: Minute,
+    #[serde(default)]
+    pub breaks: Vec<ShiftBreak>,
+    // Who covers the shift if the member on it calls in sick
+    #[serde(rename = "standbyMemberId", default)]
+    pub standby_member_id: Option<MemberId>,
+    #[serde(default)]
+    pub tags: Vec<ShiftTag>,
+    #[serde(default)]
+    pub note: Option<ShiftNote>,
+    #[serde(default)]
+    pub location: Option<ShiftLocation>,
 }
 
 impl Shift {
@@ -31,20 +48,68 @@ impl Shift {
             day,
             start_time,
             end_time,
+            breaks: Vec::new(),
+            standby_member_id: None,
+            tags: Vec::new(),
+            note: None,
+            location: None,
         })
     }
 
+    pub fn has_tag(&self, tag: &ShiftTag) -> bool {
+        self.tags.contains(tag)
+    }
+
+    // `member` is the member on the shift
+    pub fn assign_standby(
+        &mut self,
+        member: &Member,
+        standby: &Member,
+    ) -> Result<(), ValidationError> {
+        if standby.member_id == member.member_id {
+            return Err(ValidationError::new(String::from(
+                "A member cannot be on standby for their own shift",
+            )));
+        }
+        if standby.project_id != member.project_id {
+            return Err(ValidationError::new(String::from(
+                "Standby member must be in the same project",
+            )));
+        }
+        self.standby_member_id = Some(standby.member_id.clone());
+        Ok(())
+    }
+
+    // Whether the same member would be on both shifts at once
+    pub fn overlaps(&self, other: &Shift) -> bool {
+        self.member_id == other.member_id
+            && self.day == other.day
+            && self.start_time.value_of() < other.end_time.value_of()
+            && other.start_time.value_of() < self.end_time.value_of()
+    }
+
     pub fn length(&self) -> i16 {
         self.end_time.value_of() - self.start_time.value_of()
     }
 
+    // The length of the shift less any unpaid breaks
+    pub fn paid_length(&self) -> i16 {
+        let unpaid: i16 = self
+            .breaks
+            .iter()
+            .filter(|b| !b.paid)
+            .map(|b| b.length())
+            .sum();
+        self.length() - unpaid
+    }
+
     pub fn length_hours(&self) -> (i16, i16) {
         let minutes = self.end_time.value_of() - self.start_time.value_of();
         (minutes / 60, minutes % 60)
     }
 }
 
-fn validate_shift(
+pub(super) fn validate_shift(
     start_time: &Minute,
     end_time: &Minute,
 ) -> Result<(), ValidationError> {
@@ -56,36 +121,12 @@ fn validate_shift(
     )))
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct ShiftId(Uuid);
-
-impl ShiftId {
-    pub fn parse(id: &str) -> Result<Self, ValidationError> {
-        let parsed = uuid::Uuid::try_parse(id).map_err(|e| {
-            ValidationError::new(format!("Invalid member ID: {e}"))
-        })?;
-        Ok(Self(parsed))
-    }
-
-    pub fn new(uuid: Uuid) -> Self {
-        Self(uuid)
-    }
-}
-
-impl Default for ShiftId {
-    fn default() -> Self {
-        Self(uuid::Uuid::new_v4())
-    }
-}
-
-impl AsRef<Uuid> for ShiftId {
-    fn as_ref(&self) -> &Uuid {
-        &self.0
-    }
-}
+uuid_id!(ShiftId, "shift");
 
 #[repr(i16)]
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
 pub enum Day {
     Sunday = 0,
     Monday = 1,
@@ -121,6 +162,27 @@ impl From<Day> for i16 {
     }
 }
 
+impl Day {
+    // How far into a week beginning on `week_start` this day falls, from 0
+    // for `week_start` itself to 6 for the day before it
+    pub fn days_after(&self, week_start: Day) -> i16 {
+        (i16::from(*self) - i16::from(week_start)).rem_euclid(7)
+    }
+
+    // The two-letter form iCalendar uses (RFC 5545 3.3.10)
+    pub fn ical_code(&self) -> &'static str {
+        match self {
+            Day::Sunday => "SU",
+            Day::Monday => "MO",
+            Day::Tuesday => "TU",
+            Day::Wednesday => "WE",
+            Day::Thursday => "TH",
+            Day::Friday => "FR",
+            Day::Saturday => "SA",
+        }
+    }
+}
+
 impl FromStr for Day {
     type Err = ValidationError;
 
@@ -156,8 +218,8 @@ impl fmt::Display for Day {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct Minute(i16);
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Minute(#[schemars(range(min = 0, max = 1440))] i16);
 
 const MINUTE_MIN: i16 = 0;
 const MINUTE_MAX: i16 = 1440;
@@ -202,6 +264,13 @@ impl Minute {
     }
 }
 
+impl fmt::Display for Minute {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (hours, minutes) = self.to_hours();
+        write!(f, "{:02}:{:02}", hours, minutes)
+    }
+}
+
 fn validate_minute(num: i16) -> Result<(), ValidationError> {
     match num {
         num if num < MINUTE_MIN => Err(ValidationError::new(String::from(
@@ -217,6 +286,47 @@ fn validate_minute(num: i16) -> Result<(), ValidationError> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::{MemberName, ProjectId};
+
+    #[test]
+    fn test_assign_standby() {
+        let project_id = ProjectId::default();
+        let member = |project_id: &ProjectId, name: &str| {
+            Member::new(
+                project_id.clone(),
+                MemberId::default(),
+                MemberName::parse(name.to_owned()).unwrap(),
+            )
+        };
+        let ted = member(&project_id, "Ted");
+        let dougal = member(&project_id, "Dougal");
+        let mut shift = Shift::new(
+            ted.member_id.clone(),
+            Day::Monday,
+            Minute::parse(540).unwrap(),
+            Minute::parse(1020).unwrap(),
+        )
+        .unwrap();
+
+        assert!(shift.assign_standby(&ted, &ted).is_err());
+        assert!(shift
+            .assign_standby(&ted, &member(&ProjectId::default(), "Jack"))
+            .is_err());
+        assert!(shift.standby_member_id.is_none());
+
+        assert!(shift.assign_standby(&ted, &dougal).is_ok());
+        assert_eq!(shift.standby_member_id, Some(dougal.member_id));
+    }
+
+    #[test]
+    fn test_days_after_wraps_round_the_week() {
+        assert_eq!(Day::Sunday.days_after(Day::Sunday), 0);
+        assert_eq!(Day::Saturday.days_after(Day::Sunday), 6);
+        assert_eq!(Day::Monday.days_after(Day::Monday), 0);
+        assert_eq!(Day::Sunday.days_after(Day::Monday), 6);
+        assert_eq!(Day::Tuesday.days_after(Day::Wednesday), 6);
+        assert_eq!(Day::Tuesday.days_after(Day::Saturday), 3);
+    }
 
     #[test]
     fn test_minute_parse() {
@@ -234,6 +344,13 @@ mod tests {
         assert!(Minute::parse(1339).is_ok_and(|m| m.value_of() == 1339));
     }
 
+    #[test]
+    fn test_minute_display() {
+        assert_eq!(Minute::parse(0).unwrap().to_string(), "00:00");
+        assert_eq!(Minute::parse(545).unwrap().to_string(), "09:05");
+        assert_eq!(Minute::parse(1440).unwrap().to_string(), "24:00");
+    }
+
     #[test]
     fn test_minute_is_before_and_after() {
         let first = Minute::parse(0).expect("Failed to parse minute");
@@ -273,7 +390,7 @@ mod tests {
         let invalid_id = "5b5b32e3a66cc-45bc-82d1-d41582139f1e";
         let result = ShiftId::parse(invalid_id);
         let error = result.expect_err(invalid_id);
-        assert_eq!(error.as_ref(), "Invalid member ID: failed to parse a UUID");
+        assert_eq!(error.as_ref(), "Invalid shift ID: failed to parse a UUID");
     }
 
     #[test]
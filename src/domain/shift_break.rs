@@ -0,0 +1,197 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{Minute, Shift, ShiftGranularity, ValidationError};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftBreak {
+    #[serde(rename = "startTime")]
+    pub start_time: Minute,
+    #[serde(rename = "endTime")]
+    pub end_time: Minute,
+    pub paid: bool,
+    // Added by the project's break rules rather than by hand
+    #[serde(rename = "autoInserted")]
+    pub auto_inserted: bool,
+}
+
+impl ShiftBreak {
+    pub fn length(&self) -> i16 {
+        self.end_time.value_of() - self.start_time.value_of()
+    }
+}
+
+// Shifts longer than `shift_longer_than` minutes get a break of
+// `break_length` minutes
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BreakRule {
+    #[serde(rename = "shiftLongerThan")]
+    #[schemars(range(min = 0, max = 1439))]
+    shift_longer_than: i16,
+    #[serde(rename = "breakLength")]
+    #[schemars(range(min = 1, max = 1439))]
+    break_length: i16,
+    paid: bool,
+}
+
+impl BreakRule {
+    pub fn new(
+        shift_longer_than: i16,
+        break_length: i16,
+        paid: bool,
+    ) -> Result<Self, ValidationError> {
+        if !(0..1440).contains(&shift_longer_than) {
+            return Err(ValidationError::new(String::from(
+                "Break rule shift length must be between 0 and 1439 minutes",
+            )));
+        }
+        // The break always fits inside the shift it applies to
+        if !(1..=shift_longer_than).contains(&break_length) {
+            return Err(ValidationError::new(String::from(
+                "Break length must be at least 1 minute and no longer than \
+                 the shift length it applies to",
+            )));
+        }
+        Ok(Self {
+            shift_longer_than,
+            break_length,
+            paid,
+        })
+    }
+
+    pub fn shift_longer_than(&self) -> i16 {
+        self.shift_longer_than
+    }
+
+    pub fn break_length(&self) -> i16 {
+        self.break_length
+    }
+
+    pub fn paid(&self) -> bool {
+        self.paid
+    }
+
+    pub fn applies_to(&self, shift: &Shift) -> bool {
+        shift.length() > self.shift_longer_than
+    }
+
+    // Put the break in the middle of the shift, moved earlier if needed so it
+    // starts on the project's granularity
+    pub fn place(
+        &self,
+        shift: &Shift,
+        granularity: &ShiftGranularity,
+    ) -> ShiftBreak {
+        let shift_start = shift.start_time.value_of();
+        let middle = shift_start + (shift.length() - self.break_length) / 2;
+        let start = (middle - middle % granularity.value_of()).max(shift_start);
+
+        ShiftBreak {
+            start_time: Minute::parse(start)
+                .expect("Break starts within the shift"),
+            end_time: Minute::parse(start + self.break_length)
+                .expect("Break ends within the shift"),
+            paid: self.paid,
+            auto_inserted: true,
+        }
+    }
+}
+
+// The break owed for a shift under `rules`: the one from the rule for the
+// longest shifts it is long enough for, if any
+pub fn auto_breaks(
+    rules: &[BreakRule],
+    shift: &Shift,
+    granularity: &ShiftGranularity,
+) -> Vec<ShiftBreak> {
+    rules
+        .iter()
+        .filter(|rule| rule.applies_to(shift))
+        .max_by_key(|rule| rule.shift_longer_than)
+        .map(|rule| rule.place(shift, granularity))
+        .into_iter()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Day, MemberId};
+
+    fn shift(start: i16, end: i16) -> Shift {
+        Shift::new(
+            MemberId::default(),
+            Day::Monday,
+            Minute::parse(start).unwrap(),
+            Minute::parse(end).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_break_rule_new() {
+        assert!(BreakRule::new(360, 30, false).is_ok());
+        assert!(BreakRule::new(0, 0, false).is_err());
+        assert!(BreakRule::new(-1, 30, false).is_err());
+        assert!(BreakRule::new(1440, 30, false).is_err());
+        assert!(BreakRule::new(360, 0, false).is_err());
+        assert!(BreakRule::new(30, 31, false).is_err());
+    }
+
+    #[test]
+    fn test_no_break_for_short_shifts() {
+        let rules = vec![BreakRule::new(360, 30, false).unwrap()];
+        let granularity = ShiftGranularity::default();
+
+        assert!(auto_breaks(&rules, &shift(540, 900), &granularity).is_empty());
+        assert_eq!(
+            auto_breaks(&rules, &shift(540, 901), &granularity).len(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_break_placed_mid_shift() {
+        let rules = vec![BreakRule::new(360, 30, false).unwrap()];
+
+        let breaks = auto_breaks(
+            &rules,
+            &shift(540, 1020),
+            &ShiftGranularity::default(),
+        );
+        assert_eq!(
+            breaks,
+            vec![ShiftBreak {
+                start_time: Minute::parse(765).unwrap(),
+                end_time: Minute::parse(795).unwrap(),
+                paid: false,
+                auto_inserted: true,
+            }]
+        );
+
+        let granularity = ShiftGranularity::parse(15).unwrap();
+        let breaks = auto_breaks(&rules, &shift(540, 1020), &granularity);
+        assert_eq!(breaks[0].start_time.value_of(), 765);
+        let breaks = auto_breaks(&rules, &shift(540, 1005), &granularity);
+        assert_eq!(breaks[0].start_time.value_of(), 750);
+        assert_eq!(breaks[0].end_time.value_of(), 780);
+    }
+
+    #[test]
+    fn test_longest_matching_rule_wins() {
+        let rules = vec![
+            BreakRule::new(600, 45, true).unwrap(),
+            BreakRule::new(360, 30, false).unwrap(),
+        ];
+        let granularity = ShiftGranularity::default();
+
+        let breaks = auto_breaks(&rules, &shift(480, 1200), &granularity);
+        assert_eq!(breaks.len(), 1);
+        assert_eq!(breaks[0].length(), 45);
+        assert!(breaks[0].paid);
+
+        let breaks = auto_breaks(&rules, &shift(480, 960), &granularity);
+        assert_eq!(breaks[0].length(), 30);
+        assert!(!breaks[0].paid);
+    }
+}
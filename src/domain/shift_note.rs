@@ -0,0 +1,92 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+const MAX_NOTE_LENGTH: usize = 200;
+const MAX_LOCATION_LENGTH: usize = 100;
+
+// A short note on a shift for whoever works it, e.g. "Bring the float"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftNote(#[schemars(length(min = 1, max = 200))] String);
+
+impl ShiftNote {
+    // Blank notes are treated as no note
+    pub fn parse(note: &str) -> Result<Option<Self>, ValidationError> {
+        parse_text(note, MAX_NOTE_LENGTH, "Notes").map(|note| note.map(Self))
+    }
+}
+
+impl AsRef<String> for ShiftNote {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// Where a shift is worked, e.g. "Front desk"
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftLocation(#[schemars(length(min = 1, max = 100))] String);
+
+impl ShiftLocation {
+    // Blank locations are treated as no location
+    pub fn parse(location: &str) -> Result<Option<Self>, ValidationError> {
+        parse_text(location, MAX_LOCATION_LENGTH, "Locations")
+            .map(|location| location.map(Self))
+    }
+}
+
+impl AsRef<String> for ShiftLocation {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+fn parse_text(
+    text: &str,
+    max_length: usize,
+    what: &str,
+) -> Result<Option<String>, ValidationError> {
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(None);
+    }
+    if text.chars().count() > max_length {
+        return Err(ValidationError::new(format!(
+            "{} cannot be longer than {} characters",
+            what, max_length
+        )));
+    }
+    if text.chars().any(char::is_control) {
+        return Err(ValidationError::new(format!(
+            "{} cannot contain control characters",
+            what
+        )));
+    }
+    Ok(Some(text.to_owned()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_trims_and_drops_blank_text() {
+        assert_eq!(
+            ShiftNote::parse("  Bring the float ")
+                .unwrap()
+                .unwrap()
+                .as_ref(),
+            "Bring the float"
+        );
+        assert_eq!(ShiftNote::parse("   ").unwrap(), None);
+        assert_eq!(ShiftLocation::parse("").unwrap(), None);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_text() {
+        assert!(ShiftNote::parse(&"a".repeat(201)).is_err());
+        assert!(ShiftNote::parse(&"a".repeat(200)).is_ok());
+        assert!(ShiftLocation::parse(&"a".repeat(101)).is_err());
+        assert!(ShiftLocation::parse("Front\ndesk").is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use super::{
+    id::uuid_id, shift::validate_shift, Day, DepartmentId, MemberId, Minute,
+    ProjectId, ProjectSettings, Shift, ShiftTag, ValidationError,
+};
+
+const MAX_SLOT_PLACES: i16 = 50;
+
+uuid_id!(ShiftSlotId, "shift slot");
+
+// A shift needing several people at once, such as three baristas on
+// Saturday 9–5. It's defined once, and each member filling a place is given
+// a shift of their own from it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShiftSlot {
+    pub slot_id: ShiftSlotId,
+    pub project_id: ProjectId,
+    pub day: Day,
+    pub start_time: Minute,
+    pub end_time: Minute,
+    // How many people the slot needs
+    pub places: i16,
+    // The skill needed, given to each shift filling the slot
+    pub tag: Option<ShiftTag>,
+    // The department whose members fill it
+    pub department_id: Option<DepartmentId>,
+}
+
+impl ShiftSlot {
+    pub fn new(
+        slot_id: ShiftSlotId,
+        project_id: ProjectId,
+        day: Day,
+        start_time: Minute,
+        end_time: Minute,
+        places: i16,
+    ) -> Result<Self, ValidationError> {
+        validate_shift(&start_time, &end_time)?;
+        if !(1..=MAX_SLOT_PLACES).contains(&places) {
+            return Err(ValidationError::new(format!(
+                "A slot must need between 1 and {} people",
+                MAX_SLOT_PLACES
+            )));
+        }
+        Ok(Self {
+            slot_id,
+            project_id,
+            day,
+            start_time,
+            end_time,
+            places,
+            tag: None,
+            department_id: None,
+        })
+    }
+
+    // Whether anyone on `shift` would be busy during the slot
+    pub fn clashes_with(&self, shift: &Shift) -> bool {
+        self.day == shift.day
+            && self.start_time.value_of() < shift.end_time.value_of()
+            && shift.start_time.value_of() < self.end_time.value_of()
+    }
+
+    pub fn overlaps(&self, other: &ShiftSlot) -> bool {
+        self.day == other.day
+            && self.start_time.value_of() < other.end_time.value_of()
+            && other.start_time.value_of() < self.end_time.value_of()
+    }
+
+    // `member_id`'s shift filling a place, following the project's rules
+    pub fn shift_for(
+        &self,
+        member_id: MemberId,
+        settings: &ProjectSettings,
+    ) -> Result<Shift, ValidationError> {
+        let mut shift = settings.new_shift(
+            member_id,
+            self.day,
+            self.start_time.clone(),
+            self.end_time.clone(),
+            false,
+        )?;
+        shift.tags = self.tag.iter().cloned().collect();
+        Ok(shift)
+    }
+}
+
+// A slot and the members filling its places
+#[derive(Debug, Clone, PartialEq)]
+pub struct SlotCoverage {
+    pub slot: ShiftSlot,
+    pub assigned: Vec<MemberId>,
+}
+
+impl SlotCoverage {
+    // Places nobody has filled yet
+    pub fn open_places(&self) -> i16 {
+        (self.slot.places - self.assigned.len() as i16).max(0)
+    }
+
+    pub fn is_covered(&self) -> bool {
+        self.open_places() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::BreakRule;
+
+    fn slot(places: i16) -> Result<ShiftSlot, ValidationError> {
+        ShiftSlot::new(
+            ShiftSlotId::default(),
+            ProjectId::default(),
+            Day::Saturday,
+            Minute::parse(540).unwrap(),
+            Minute::parse(1020).unwrap(),
+            places,
+        )
+    }
+
+    #[test]
+    fn test_slot_places_range() {
+        for places in [1, 3, MAX_SLOT_PLACES] {
+            assert!(slot(places).is_ok());
+        }
+        for places in [-1, 0, MAX_SLOT_PLACES + 1] {
+            assert!(slot(places).is_err());
+        }
+    }
+
+    #[test]
+    fn test_slot_must_end_after_it_starts() {
+        let result = ShiftSlot::new(
+            ShiftSlotId::default(),
+            ProjectId::default(),
+            Day::Saturday,
+            Minute::parse(1020).unwrap(),
+            Minute::parse(540).unwrap(),
+            2,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_shift_for_follows_project_rules() {
+        let mut slot = slot(3).unwrap();
+        slot.tag = Some(ShiftTag::parse("barista").unwrap());
+        let settings = ProjectSettings {
+            break_rules: vec![BreakRule::new(360, 30, false).unwrap()],
+            ..Default::default()
+        };
+        let ted = MemberId::default();
+
+        let shift = slot.shift_for(ted.clone(), &settings).unwrap();
+
+        assert_eq!(shift.member_id, ted);
+        assert_eq!(shift.day, Day::Saturday);
+        assert_eq!(shift.breaks.len(), 1);
+        assert_eq!(shift.tags, vec![ShiftTag::parse("barista").unwrap()]);
+    }
+
+    #[test]
+    fn test_open_places() {
+        let mut coverage = SlotCoverage {
+            slot: slot(2).unwrap(),
+            assigned: vec![MemberId::default()],
+        };
+        assert_eq!(coverage.open_places(), 1);
+        assert!(!coverage.is_covered());
+
+        coverage.assigned.push(MemberId::default());
+        assert_eq!(coverage.open_places(), 0);
+        assert!(coverage.is_covered());
+    }
+}
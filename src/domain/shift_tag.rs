@@ -0,0 +1,114 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+pub const MAX_SHIFT_TAGS: usize = 10;
+const MAX_TAG_LENGTH: usize = 32;
+
+// A free-form label on a shift, e.g. "training". Tags are compared
+// case-insensitively, so they are stored in lower case.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+    JsonSchema,
+)]
+pub struct ShiftTag(#[schemars(length(min = 1, max = 32))] String);
+
+impl ShiftTag {
+    pub fn parse(tag: &str) -> Result<Self, ValidationError> {
+        let tag = tag.trim().to_lowercase();
+        if tag.is_empty() {
+            return Err(ValidationError::new(String::from(
+                "Tag cannot be empty",
+            )));
+        }
+        if tag.chars().count() > MAX_TAG_LENGTH {
+            return Err(ValidationError::new(format!(
+                "Tags cannot be longer than {} characters",
+                MAX_TAG_LENGTH
+            )));
+        }
+        if !tag
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, ' ' | '-' | '_'))
+        {
+            return Err(ValidationError::new(String::from(
+                "Tags can only contain letters, numbers, spaces, hyphens and \
+                 underscores",
+            )));
+        }
+        Ok(Self(tag))
+    }
+
+    // Parse a shift's tags, dropping duplicates
+    pub fn parse_all(tags: &[String]) -> Result<Vec<Self>, ValidationError> {
+        let mut parsed = tags
+            .iter()
+            .map(|tag| Self::parse(tag))
+            .collect::<Result<Vec<_>, _>>()?;
+        parsed.sort();
+        parsed.dedup();
+        if parsed.len() > MAX_SHIFT_TAGS {
+            return Err(ValidationError::new(format!(
+                "A shift cannot have more than {} tags",
+                MAX_SHIFT_TAGS
+            )));
+        }
+        Ok(parsed)
+    }
+}
+
+impl AsRef<String> for ShiftTag {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_normalises_tags() {
+        assert_eq!(ShiftTag::parse(" Training ").unwrap().as_ref(), "training");
+        assert_eq!(
+            ShiftTag::parse("night-shift_2").unwrap().as_ref(),
+            "night-shift_2"
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_tags() {
+        for tag in ["", "   ", "a;b", "<script>", &"a".repeat(33)] {
+            assert!(ShiftTag::parse(tag).is_err(), "Should reject {:?}", tag);
+        }
+    }
+
+    #[test]
+    fn test_parse_all_drops_duplicates_and_limits_count() {
+        let tags = ShiftTag::parse_all(&[
+            "Training".to_owned(),
+            "training".to_owned(),
+            "bar".to_owned(),
+        ])
+        .unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                ShiftTag::parse("bar").unwrap(),
+                ShiftTag::parse("training").unwrap()
+            ]
+        );
+
+        let too_many: Vec<String> =
+            (0..=MAX_SHIFT_TAGS).map(|i| format!("tag {}", i)).collect();
+        assert!(ShiftTag::parse_all(&too_many).is_err());
+    }
+}
@@ -0,0 +1,11 @@
+use super::PhoneNumber;
+use color_eyre::eyre::Result;
+
+#[async_trait::async_trait]
+pub trait SmsClient {
+    async fn send_sms(
+        &self,
+        recipient: &PhoneNumber,
+        content: &str,
+    ) -> Result<()>;
+}
@@ -0,0 +1,99 @@
+use chrono::NaiveDate;
+
+use super::{
+    time::at_minute, Locale, MemberName, Minute, ProjectName, ShiftId,
+};
+
+// What a member can text back in answer to a shift reminder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SmsReply {
+    Confirm,
+    Swap,
+}
+
+impl SmsReply {
+    // Only the first word counts, so "Yes see you then" confirms
+    pub fn parse(body: &str) -> Option<Self> {
+        let keyword = body.split_whitespace().next()?.to_uppercase();
+        match keyword.trim_end_matches(['.', '!']) {
+            "YES" | "Y" | "CONFIRM" => Some(Self::Confirm),
+            "SWAP" => Some(Self::Swap),
+            _ => None,
+        }
+    }
+}
+
+// The shift a member was last reminded about
+#[derive(Debug, Clone, PartialEq)]
+pub struct RemindedShift {
+    pub shift_id: ShiftId,
+    pub shift_date: NaiveDate,
+    pub start_time: Minute,
+    pub member_name: MemberName,
+    pub project_name: ProjectName,
+}
+
+impl RemindedShift {
+    // The subject and body of the email asking the project owner to find
+    // someone to swap with
+    pub fn swap_request_email(&self) -> (String, String) {
+        let locale = Locale::default();
+        let start = at_minute(self.shift_date, &self.start_time);
+        (
+            format!("{} asked to swap a shift", self.member_name.as_ref()),
+            format!(
+                "{} can't make their {} shift on {} and asked to swap it. \
+                 Find someone to cover it in the rota.",
+                self.member_name.as_ref(),
+                self.project_name.as_ref(),
+                locale.format_date_time(start)
+            ),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_sms_reply() {
+        for (body, expected) in [
+            ("YES", Some(SmsReply::Confirm)),
+            ("yes", Some(SmsReply::Confirm)),
+            (" Y ", Some(SmsReply::Confirm)),
+            ("Yes! See you then", Some(SmsReply::Confirm)),
+            ("confirm", Some(SmsReply::Confirm)),
+            ("swap", Some(SmsReply::Swap)),
+            ("SWAP please", Some(SmsReply::Swap)),
+            ("yesterday", None),
+            ("no", None),
+            ("", None),
+        ] {
+            assert_eq!(
+                SmsReply::parse(body),
+                expected,
+                "Failed for {:?}",
+                body
+            );
+        }
+    }
+
+    #[test]
+    fn test_swap_request_email() {
+        let shift = RemindedShift {
+            shift_id: ShiftId::default(),
+            shift_date: NaiveDate::from_ymd_opt(2025, 10, 20).unwrap(),
+            start_time: Minute::parse(540).unwrap(),
+            member_name: MemberName::parse("Dougal".to_owned()).unwrap(),
+            project_name: ProjectName::parse("Craggy Island").unwrap(),
+        };
+
+        let (subject, content) = shift.swap_request_email();
+        assert_eq!(subject, "Dougal asked to swap a shift");
+        assert!(content.starts_with(
+            "Dougal can't make their Craggy Island shift on Monday 20/10/2025 \
+             at 09:00 and asked to swap it."
+        ));
+    }
+}
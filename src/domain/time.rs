@@ -0,0 +1,235 @@
+use std::str::FromStr;
+
+use chrono::{
+    DateTime, Datelike, Days, LocalResult, NaiveDate, NaiveDateTime, NaiveTime,
+    Offset, TimeDelta, TimeZone, Utc,
+};
+use chrono_tz::Tz;
+
+use super::{Day, Minute, ValidationError};
+
+// Rotas are stored as (Day, Minute) wall-clock times, which only become real
+// instants once a week and a timezone are known. These helpers are the one
+// place that conversion happens, so DST is handled the same way everywhere:
+//
+// - A time that doesn't exist because the clocks went forward (01:30 on the
+//   last Sunday of March in London) is read with the offset from before the
+//   change, so it lands the same distance after the gap (02:30 BST).
+// - A time that happens twice because the clocks went back (01:30 on the
+//   last Sunday of October in London) is the first occurrence.
+// - Minute 1440 is midnight at the end of the day, not the start.
+//
+// Durations worked out from these instants are real elapsed time, so a shift
+// spanning a change is an hour shorter or longer than its wall-clock length.
+
+pub fn parse_timezone(timezone: &str) -> Result<Tz, ValidationError> {
+    Tz::from_str(timezone)
+        .map_err(|_| ValidationError::new(String::from("Invalid timezone")))
+}
+
+pub fn parse_date(date: &str) -> Result<NaiveDate, ValidationError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d").map_err(|_| {
+        ValidationError::new(String::from("Date must be in YYYY-MM-DD format"))
+    })
+}
+
+// The date `day` falls on in the rota week beginning on `week_start`
+pub fn date_of_day(week_start: NaiveDate, day: Day) -> NaiveDate {
+    let start = week_start.weekday().num_days_from_sunday() as i16;
+    let offset = (i16::from(day) - start).rem_euclid(7) as u64;
+    week_start + Days::new(offset)
+}
+
+// The first day of the week containing `date`, for weeks beginning on
+// `week_start`
+pub fn week_beginning(date: NaiveDate, week_start: Day) -> NaiveDate {
+    let day = Day::try_from(date.weekday().num_days_from_sunday() as i16)
+        .expect("Weekdays are numbered 0 to 6");
+    date - Days::new(day.days_after(week_start) as u64)
+}
+
+// The UTC instant of a wall-clock time in the rota week beginning on
+// `week_start`
+pub fn to_utc(
+    week_start: NaiveDate,
+    day: Day,
+    minute: &Minute,
+    timezone: Tz,
+) -> DateTime<Utc> {
+    local_to_utc(at_minute(date_of_day(week_start, day), minute), timezone)
+}
+
+// The real start and end of a shift. A shift ending at or before it starts
+// is taken to run past midnight.
+pub fn shift_to_utc(
+    week_start: NaiveDate,
+    day: Day,
+    start_time: &Minute,
+    end_time: &Minute,
+    timezone: Tz,
+) -> (DateTime<Utc>, DateTime<Utc>) {
+    let date = date_of_day(week_start, day);
+    let end_date = if end_time.is_after(start_time) {
+        date
+    } else {
+        date + Days::new(1)
+    };
+    (
+        local_to_utc(at_minute(date, start_time), timezone),
+        local_to_utc(at_minute(end_date, end_time), timezone),
+    )
+}
+
+// How long a calendar day really is: 24 hours, or 23 or 25 when the clocks
+// change
+pub fn day_length(date: NaiveDate, timezone: Tz) -> TimeDelta {
+    let start = local_to_utc(date.and_time(NaiveTime::MIN), timezone);
+    let end =
+        local_to_utc((date + Days::new(1)).and_time(NaiveTime::MIN), timezone);
+    end - start
+}
+
+// The wall-clock time `minute` minutes into `date`
+pub fn at_minute(date: NaiveDate, minute: &Minute) -> NaiveDateTime {
+    date.and_time(NaiveTime::MIN) + TimeDelta::minutes(minute.value_of().into())
+}
+
+fn local_to_utc(local: NaiveDateTime, timezone: Tz) -> DateTime<Utc> {
+    match timezone.from_local_datetime(&local) {
+        LocalResult::Single(instant) => instant.with_timezone(&Utc),
+        LocalResult::Ambiguous(earliest, _) => earliest.with_timezone(&Utc),
+        LocalResult::None => {
+            // Clock changes never happen twice in a day, so the offset a day
+            // earlier is the one in force before the gap
+            let before = timezone
+                .offset_from_utc_datetime(&(local - TimeDelta::days(1)))
+                .fix();
+            (local - before).and_utc()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono_tz::{America::New_York, Europe::London};
+
+    use super::*;
+
+    fn date(year: i32, month: u32, day: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(year, month, day).unwrap()
+    }
+
+    fn minute(value: i16) -> Minute {
+        Minute::parse(value).unwrap()
+    }
+
+    fn utc(s: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(s).unwrap().with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_date_of_day() {
+        // A week starting on Monday 24 March 2025
+        let week_start = date(2025, 3, 24);
+        assert_eq!(date_of_day(week_start, Day::Monday), week_start);
+        assert_eq!(date_of_day(week_start, Day::Sunday), date(2025, 3, 30));
+        assert_eq!(date_of_day(week_start, Day::Saturday), date(2025, 3, 29));
+    }
+
+    #[test]
+    fn test_week_beginning() {
+        // Wednesday 22 October 2025
+        let wednesday = date(2025, 10, 22);
+        assert_eq!(week_beginning(wednesday, Day::Sunday), date(2025, 10, 19));
+        assert_eq!(week_beginning(wednesday, Day::Monday), date(2025, 10, 20));
+        assert_eq!(week_beginning(wednesday, Day::Wednesday), wednesday);
+        assert_eq!(
+            week_beginning(wednesday, Day::Thursday),
+            date(2025, 10, 16)
+        );
+
+        // A Sunday is the end of a Monday week, not the start of the next
+        let sunday = date(2025, 10, 26);
+        assert_eq!(week_beginning(sunday, Day::Monday), date(2025, 10, 20));
+        assert_eq!(week_beginning(sunday, Day::Sunday), sunday);
+    }
+
+    #[test]
+    fn test_parse_timezone() {
+        assert_eq!(parse_timezone("Europe/London").unwrap(), London);
+        assert!(parse_timezone("Europe/Craggy_Island").is_err());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2025-10-20").unwrap(), date(2025, 10, 20));
+        assert!(parse_date("20/10/2025").is_err());
+        assert!(parse_date("2025-02-30").is_err());
+    }
+
+    #[test]
+    fn test_to_utc_outside_transitions() {
+        let week_start = date(2025, 1, 6);
+        assert_eq!(
+            to_utc(week_start, Day::Monday, &minute(540), London),
+            utc("2025-01-06T09:00:00Z")
+        );
+        assert_eq!(
+            to_utc(week_start, Day::Monday, &minute(540), New_York),
+            utc("2025-01-06T14:00:00Z")
+        );
+        assert_eq!(
+            to_utc(week_start, Day::Monday, &minute(1440), London),
+            utc("2025-01-07T00:00:00Z")
+        );
+    }
+
+    #[test]
+    fn test_nonexistent_time_moves_past_gap() {
+        // Clocks go forward at 01:00 GMT on 30 March 2025
+        let week_start = date(2025, 3, 24);
+        assert_eq!(
+            to_utc(week_start, Day::Sunday, &minute(90), London),
+            utc("2025-03-30T01:30:00Z")
+        );
+        assert_eq!(
+            to_utc(week_start, Day::Sunday, &minute(150), London),
+            utc("2025-03-30T01:30:00Z")
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_time_is_first_occurrence() {
+        // Clocks go back at 02:00 BST on 26 October 2025
+        let week_start = date(2025, 10, 20);
+        assert_eq!(
+            to_utc(week_start, Day::Sunday, &minute(90), London),
+            utc("2025-10-26T00:30:00Z")
+        );
+    }
+
+    #[test]
+    fn test_day_length_across_transitions() {
+        assert_eq!(day_length(date(2025, 3, 30), London), TimeDelta::hours(23));
+        assert_eq!(
+            day_length(date(2025, 10, 26), London),
+            TimeDelta::hours(25)
+        );
+        assert_eq!(day_length(date(2025, 6, 1), London), TimeDelta::hours(24));
+    }
+
+    #[test]
+    fn test_overnight_shift_spanning_transition() {
+        // 22:00 Saturday to 06:00 Sunday is nine hours when the clocks go back
+        let (start, end) = shift_to_utc(
+            date(2025, 10, 20),
+            Day::Saturday,
+            &minute(1320),
+            &minute(360),
+            London,
+        );
+        assert_eq!(start, utc("2025-10-25T21:00:00Z"));
+        assert_eq!(end, utc("2025-10-26T06:00:00Z"));
+        assert_eq!(end - start, TimeDelta::hours(9));
+    }
+}
@@ -0,0 +1,225 @@
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use secrecy::{ExposeSecret, Secret};
+use sha1::Sha1;
+
+use super::{Email, TwoFACode, ValidationError};
+
+const SECRET_BYTES: usize = 20;
+const STEP_SECONDS: i64 = 30;
+const DIGITS: u32 = 6;
+// Codes from the steps either side of now are accepted too, to allow for
+// the authenticator's clock being a little out
+const ALLOWED_DRIFT_STEPS: i64 = 1;
+const ISSUER: &str = "Rota Manager";
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+// The shared secret behind an authenticator app's codes (RFC 6238, with
+// HMAC-SHA1, six digits and 30 second steps, which every app supports)
+pub struct TotpSecret(Secret<Vec<u8>>);
+
+impl TotpSecret {
+    pub fn generate() -> Self {
+        let mut bytes = vec![0; SECRET_BYTES];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        Self(Secret::new(bytes))
+    }
+
+    pub fn from_base32(encoded: &str) -> Result<Self, ValidationError> {
+        base32_decode(encoded)
+            .map(|bytes| Self(Secret::new(bytes)))
+            .ok_or_else(|| {
+                ValidationError::new(String::from("TOTP secret is not valid"))
+            })
+    }
+
+    // As typed into an authenticator app by hand
+    pub fn to_base32(&self) -> Secret<String> {
+        Secret::new(base32_encode(self.0.expose_secret()))
+    }
+
+    // For a QR code, which authenticator apps scan to add the account
+    pub fn otpauth_uri(&self, email: &Email) -> Secret<String> {
+        let issuer = uri_encode(ISSUER);
+        Secret::new(format!(
+            "otpauth://totp/{issuer}:{account}?secret={secret}\
+             &issuer={issuer}&algorithm=SHA1&digits={DIGITS}\
+             &period={STEP_SECONDS}",
+            account = uri_encode(email.as_ref().expose_secret()),
+            secret = self.to_base32().expose_secret(),
+        ))
+    }
+
+    // The code an authenticator app would show at `now`
+    pub fn code_at(&self, now: DateTime<Utc>) -> TwoFACode {
+        let step = now.timestamp().div_euclid(STEP_SECONDS);
+        TwoFACode::parse(Secret::new(format!(
+            "{:06}",
+            self.code_for_step(step)
+        )))
+        .expect("TOTP codes are six digits")
+    }
+
+    // The time step `code` was generated in, if it's the code for a step
+    // close enough to `now`
+    pub fn matching_step(
+        &self,
+        code: &TwoFACode,
+        now: DateTime<Utc>,
+    ) -> Option<i64> {
+        let current = now.timestamp().div_euclid(STEP_SECONDS);
+        (current - ALLOWED_DRIFT_STEPS..=current + ALLOWED_DRIFT_STEPS).find(
+            |&step| {
+                format!("{:06}", self.code_for_step(step))
+                    == *code.as_ref().expose_secret()
+            },
+        )
+    }
+
+    fn code_for_step(&self, step: i64) -> u32 {
+        let mut mac =
+            Hmac::<Sha1>::new_from_slice(self.0.expose_secret().as_slice())
+                .expect("HMAC accepts keys of any length");
+        mac.update(&step.to_be_bytes());
+        let digest = mac.finalize().into_bytes();
+        let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+        let truncated = u32::from_be_bytes([
+            digest[offset] & 0x7f,
+            digest[offset + 1],
+            digest[offset + 2],
+            digest[offset + 3],
+        ]);
+        truncated % 10u32.pow(DIGITS)
+    }
+}
+
+// RFC 4648 base32, without padding
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 8).div_ceil(5));
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | byte as u32;
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            encoded
+                .push(BASE32_ALPHABET[(buffer >> bits) as usize & 31] as char);
+        }
+    }
+    if bits > 0 {
+        encoded.push(
+            BASE32_ALPHABET[(buffer << (5 - bits)) as usize & 31] as char,
+        );
+    }
+    encoded
+}
+
+// Case and padding are ignored, as apps differ in how they show secrets
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut bytes = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').chars() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a as char == c.to_ascii_uppercase())?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    (!bytes.is_empty()).then_some(bytes)
+}
+
+fn uri_encode(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes())
+        .collect::<String>()
+        .replace('+', "%20")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    // The SHA1 secret from RFC 6238's test vectors
+    fn rfc_secret() -> TotpSecret {
+        TotpSecret(Secret::new(b"12345678901234567890".to_vec()))
+    }
+
+    fn code(code: &str) -> TwoFACode {
+        TwoFACode::parse(Secret::new(code.to_owned())).unwrap()
+    }
+
+    #[test]
+    fn test_rfc_6238_vectors() {
+        // The RFC's eight-digit codes, less their first two digits
+        for (timestamp, expected) in [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ] {
+            let now = Utc.timestamp_opt(timestamp, 0).unwrap();
+            assert_eq!(
+                rfc_secret().matching_step(&code(expected), now),
+                Some(timestamp / STEP_SECONDS),
+                "Failed for {}",
+                timestamp
+            );
+        }
+    }
+
+    #[test]
+    fn test_codes_allow_for_drift() {
+        let issued = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let secret = rfc_secret();
+        let code = code("081804");
+
+        for seconds in [-30, 30] {
+            let now = issued + chrono::Duration::seconds(seconds);
+            assert!(secret.matching_step(&code, now).is_some());
+        }
+        for seconds in [-90, 90] {
+            let now = issued + chrono::Duration::seconds(seconds);
+            assert!(secret.matching_step(&code, now).is_none());
+        }
+    }
+
+    #[test]
+    fn test_base32_round_trip() {
+        assert_eq!(
+            base32_encode(b"12345678901234567890"),
+            "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ"
+        );
+        assert_eq!(base32_encode(b"f"), "MY");
+        assert_eq!(base32_decode("my======"), Some(b"f".to_vec()));
+
+        let secret = TotpSecret::generate();
+        let decoded =
+            TotpSecret::from_base32(secret.to_base32().expose_secret())
+                .unwrap();
+        assert_eq!(decoded.0.expose_secret(), secret.0.expose_secret());
+
+        assert!(TotpSecret::from_base32("not base32!").is_err());
+        assert!(TotpSecret::from_base32("").is_err());
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let email =
+            Email::parse(Secret::new("ted@example.com".to_owned())).unwrap();
+        assert_eq!(
+            rfc_secret().otpauth_uri(&email).expose_secret(),
+            "otpauth://totp/Rota%20Manager:ted%40example.com?\
+             secret=GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ&issuer=Rota%20Manager&\
+             algorithm=SHA1&digits=6&period=30"
+        );
+    }
+}
@@ -0,0 +1,118 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::ValidationError;
+
+// Postmark bounce types meaning the address will never accept email, as
+// opposed to a full mailbox or a server that's down for now
+const PERMANENT_BOUNCE_TYPES: [&str; 3] =
+    ["HardBounce", "BadEmailAddress", "ManuallyDeactivated"];
+
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema,
+)]
+#[serde(rename_all = "camelCase")]
+pub enum UndeliverableReason {
+    Bounced,
+    // The recipient marked our email as spam
+    Complained,
+}
+
+impl UndeliverableReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            UndeliverableReason::Bounced => "bounced",
+            UndeliverableReason::Complained => "complained",
+        }
+    }
+
+    // Why a Postmark webhook record makes its address undeliverable, if it
+    // does. Other records, such as deliveries and soft bounces, don't.
+    pub fn from_postmark(
+        record_type: &str,
+        bounce_type: Option<&str>,
+    ) -> Option<Self> {
+        match (record_type, bounce_type) {
+            ("SpamComplaint", _) => Some(UndeliverableReason::Complained),
+            ("Bounce", Some(bounce_type))
+                if PERMANENT_BOUNCE_TYPES.contains(&bounce_type) =>
+            {
+                Some(UndeliverableReason::Bounced)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for UndeliverableReason {
+    type Err = ValidationError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "bounced" => Ok(UndeliverableReason::Bounced),
+            "complained" => Ok(UndeliverableReason::Complained),
+            _ => Err(ValidationError::new(String::from(
+                "Invalid undeliverable reason",
+            ))),
+        }
+    }
+}
+
+// Why, and since when, email to an address is being held back
+#[derive(Debug, Clone, PartialEq)]
+pub struct UndeliverableEmail {
+    pub reason: UndeliverableReason,
+    pub marked_at: DateTime<Utc>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_postmark() {
+        for (record_type, bounce_type, expected) in [
+            (
+                "Bounce",
+                Some("HardBounce"),
+                Some(UndeliverableReason::Bounced),
+            ),
+            (
+                "Bounce",
+                Some("BadEmailAddress"),
+                Some(UndeliverableReason::Bounced),
+            ),
+            (
+                "SpamComplaint",
+                Some("SpamComplaint"),
+                Some(UndeliverableReason::Complained),
+            ),
+            ("Bounce", Some("SoftBounce"), None),
+            ("Bounce", Some("Transient"), None),
+            ("Bounce", None, None),
+            ("Delivery", None, None),
+        ] {
+            assert_eq!(
+                UndeliverableReason::from_postmark(record_type, bounce_type),
+                expected,
+                "Failed for {} {:?}",
+                record_type,
+                bounce_type
+            );
+        }
+    }
+
+    #[test]
+    fn test_reason_round_trip() {
+        for reason in [
+            UndeliverableReason::Bounced,
+            UndeliverableReason::Complained,
+        ] {
+            assert_eq!(reason.as_str().parse().ok(), Some(reason));
+        }
+        assert!("spam".parse::<UndeliverableReason>().is_err());
+    }
+}
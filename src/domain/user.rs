@@ -1,4 +1,6 @@
-use super::{Email, UserId, UserPasswordHash};
+use chrono_tz::Tz;
+
+use super::{DisplayName, Email, UserId, UserPasswordHash};
 
 #[derive(Debug, Clone, PartialEq, sqlx::FromRow)]
 pub struct User {
@@ -6,6 +8,10 @@ pub struct User {
     pub hash: UserPasswordHash,
     pub requires_2fa: bool,
     pub id: UserId,
+    pub accepted_policy_version: Option<i32>,
+    pub display_name: Option<DisplayName>,
+    // Times are shown in UTC until the user picks a timezone
+    pub timezone: Option<Tz>,
 }
 
 impl User {
@@ -19,6 +25,9 @@ impl User {
             hash,
             requires_2fa,
             id: UserId::default(),
+            accepted_policy_version: None,
+            display_name: None,
+            timezone: None,
         }
     }
 }
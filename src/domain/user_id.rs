@@ -1,33 +1,6 @@
-use super::ValidationError;
-use serde::{Deserialize, Serialize};
-use uuid::Uuid;
+use super::id::uuid_id;
 
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
-pub struct UserId(Uuid);
-
-impl UserId {
-    pub fn parse(id: &str) -> Result<Self, ValidationError> {
-        let parsed = uuid::Uuid::try_parse(id)
-            .map_err(|_| ValidationError::new("Invalid user ID".to_string()))?;
-        Ok(Self(parsed))
-    }
-
-    pub fn new(uuid: Uuid) -> Self {
-        Self(uuid)
-    }
-}
-
-impl Default for UserId {
-    fn default() -> Self {
-        Self(uuid::Uuid::new_v4())
-    }
-}
-
-impl AsRef<Uuid> for UserId {
-    fn as_ref(&self) -> &Uuid {
-        &self.0
-    }
-}
+uuid_id!(UserId, "user");
 
 #[test]
 fn test_valid_ids() {
@@ -45,5 +18,5 @@ fn test_invalid_ids() {
     let invalid_id = "5b5b32e3a66cc-45bc-82d1-d41582139f1e";
     let result = UserId::parse(invalid_id);
     let error = result.expect_err(invalid_id);
-    assert_eq!(error.as_ref(), "Invalid user ID");
+    assert_eq!(error.as_ref(), "Invalid user ID: failed to parse a UUID");
 }
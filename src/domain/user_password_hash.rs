@@ -1,11 +1,13 @@
 use super::Password;
 use argon2::{
-    password_hash::SaltString, Algorithm, Argon2, Params, PasswordHash,
-    PasswordHasher, PasswordVerifier, Version,
+    password_hash::SaltString, Algorithm, Argon2, PasswordHash, PasswordHasher,
+    PasswordVerifier, Version,
 };
 use color_eyre::eyre::{Result, WrapErr};
 use secrecy::{ExposeSecret, Secret};
 
+use crate::utils::{constants::ARGON2_PARAMS, hashing_pool::HASHING_POOL};
+
 #[derive(Debug, Clone)]
 pub struct UserPasswordHash(Secret<String>);
 
@@ -46,20 +48,21 @@ pub async fn verify_password_hash(
 ) -> Result<()> {
     let current_span: tracing::Span = tracing::Span::current();
 
-    tokio::task::spawn_blocking(move || {
-        current_span.in_scope(|| {
-            let expected_password_hash: PasswordHash<'_> =
-                PasswordHash::new(&expected_password_hash.expose_secret())?;
-
-            Argon2::default()
-                .verify_password(
-                    password_candidate.expose_secret().as_bytes(),
-                    &expected_password_hash,
-                )
-                .wrap_err("failed to verify password hash")
+    HASHING_POOL
+        .run(move || {
+            current_span.in_scope(|| {
+                let expected_password_hash: PasswordHash<'_> =
+                    PasswordHash::new(&expected_password_hash.expose_secret())?;
+
+                Argon2::default()
+                    .verify_password(
+                        password_candidate.expose_secret().as_bytes(),
+                        &expected_password_hash,
+                    )
+                    .wrap_err("failed to verify password hash")
+            })
         })
-    })
-    .await?
+        .await?
 }
 
 #[tracing::instrument(name = "Computing password hash", skip_all)]
@@ -68,22 +71,23 @@ pub async fn compute_password_hash(
 ) -> Result<Secret<String>> {
     let current_span: tracing::Span = tracing::Span::current();
 
-    tokio::task::spawn_blocking(move || {
-        current_span.in_scope(|| {
-            let salt: SaltString =
-                SaltString::generate(&mut rand::thread_rng());
-            let password_hash = Argon2::new(
-                Algorithm::Argon2id,
-                Version::V0x13,
-                Params::new(15000, 2, 1, None)?,
-            )
-            .hash_password(password.expose_secret().as_bytes(), &salt)?
-            .to_string();
-
-            Ok(Secret::new(password_hash))
+    HASHING_POOL
+        .run(move || {
+            current_span.in_scope(|| {
+                let salt: SaltString =
+                    SaltString::generate(&mut rand::thread_rng());
+                let password_hash = Argon2::new(
+                    Algorithm::Argon2id,
+                    Version::V0x13,
+                    ARGON2_PARAMS.clone(),
+                )
+                .hash_password(password.expose_secret().as_bytes(), &salt)?
+                .to_string();
+
+                Ok(Secret::new(password_hash))
+            })
         })
-    })
-    .await?
+        .await?
 }
 
 #[cfg(test)]
@@ -157,4 +161,20 @@ mod tests {
             );
         }
     }
+
+    #[tokio::test]
+    async fn hash_with_configured_params() {
+        let hash = UserPasswordHash::compute(Secret::new("passw123".into()))
+            .await
+            .expect("Failed to hash valid password");
+        let parsed = PasswordHash::new(hash.as_ref().expose_secret())
+            .expect("Failed to parse hash");
+
+        let params =
+            argon2::Params::try_from(&parsed).expect("Failed to read params");
+
+        assert_eq!(params.m_cost(), ARGON2_PARAMS.m_cost());
+        assert_eq!(params.t_cost(), ARGON2_PARAMS.t_cost());
+        assert_eq!(params.p_cost(), ARGON2_PARAMS.p_cost());
+    }
 }
@@ -0,0 +1,80 @@
+use super::{Email, UserId, ValidationError};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+// A single-use token sent by email to prove the recipient controls the
+// address
+#[derive(Debug, Clone)]
+pub struct VerificationToken(Secret<String>);
+
+impl PartialEq for VerificationToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.expose_secret() == other.0.expose_secret()
+    }
+}
+
+impl VerificationToken {
+    pub fn parse(token: Secret<String>) -> Result<Self, ValidationError> {
+        let parsed =
+            uuid::Uuid::try_parse(token.expose_secret()).map_err(|_| {
+                ValidationError::new("Invalid verification token".to_string())
+            })?;
+        Ok(Self(Secret::new(parsed.to_string())))
+    }
+}
+
+impl Default for VerificationToken {
+    fn default() -> Self {
+        let token = String::from(uuid::Uuid::new_v4());
+        VerificationToken(Secret::new(token))
+    }
+}
+
+impl AsRef<Secret<String>> for VerificationToken {
+    fn as_ref(&self) -> &Secret<String> {
+        &self.0
+    }
+}
+
+// Which address a token was sent to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum VerificationTokenType {
+    ConfirmOldEmail,
+    ConfirmNewEmail,
+}
+
+// An email address change waiting on one of its two confirmations. A change
+// is stored once per token, each pointing at the other, and only goes
+// through once both addresses have confirmed it, so a stolen session alone
+// cannot move an account to an attacker's address.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEmailChange {
+    pub user_id: UserId,
+    pub new_email: Email,
+    pub token_type: VerificationTokenType,
+    pub counterpart: VerificationToken,
+    pub confirmed: bool,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_valid_tokens() {
+        let token = "5e90ca28-e1ad-4795-a190-089959c16e0b";
+        let parsed = VerificationToken::parse(Secret::new(token.to_string()))
+            .expect(token);
+        assert_eq!(parsed.as_ref().expose_secret(), token);
+    }
+
+    #[test]
+    fn test_invalid_tokens() {
+        for token in ["", "123456", "5e90ca28-e1ad-4795-a190-089959c16e0"] {
+            let error =
+                VerificationToken::parse(Secret::new(token.to_string()))
+                    .expect_err(token);
+            assert_eq!(error.as_ref(), "Invalid verification token");
+        }
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::HashMap;
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    id::uuid_id, Day, Member, MemberId, Minute, Project, ProjectId,
+    ProjectSettings, Shift, ShiftLocation, ShiftNote, ShiftTag,
+    ValidationError,
+};
+
+uuid_id!(WeekTemplateId, "week template");
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WeekTemplateName(#[schemars(length(min = 1, max = 100))] String);
+
+impl WeekTemplateName {
+    pub fn parse(name: &str) -> Result<Self, ValidationError> {
+        let name = name.trim();
+        match name.chars().count() {
+            0 => Err(ValidationError::new(String::from(
+                "Template name cannot be empty",
+            ))),
+            x if x > 100 => Err(ValidationError::new(String::from(
+                "Template names cannot be longer than 100 characters",
+            ))),
+            _ => Ok(Self(name.to_owned())),
+        }
+    }
+}
+
+impl AsRef<String> for WeekTemplateName {
+    fn as_ref(&self) -> &String {
+        &self.0
+    }
+}
+
+// A shift as it was when its week was saved. Breaks aren't kept, since
+// they follow from the project's rules when the template is applied.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateShift {
+    pub member_id: MemberId,
+    pub standby_member_id: Option<MemberId>,
+    pub day: Day,
+    pub start_time: Minute,
+    pub end_time: Minute,
+    pub tags: Vec<ShiftTag>,
+    pub note: Option<ShiftNote>,
+    pub location: Option<ShiftLocation>,
+}
+
+impl From<&Shift> for TemplateShift {
+    fn from(shift: &Shift) -> Self {
+        Self {
+            member_id: shift.member_id.clone(),
+            standby_member_id: shift.standby_member_id.clone(),
+            day: shift.day,
+            start_time: shift.start_time.clone(),
+            end_time: shift.end_time.clone(),
+            tags: shift.tags.clone(),
+            note: shift.note.clone(),
+            location: shift.location.clone(),
+        }
+    }
+}
+
+// A project's week of shifts saved under a name, so it can be laid out again
+// once the rota has moved on
+#[derive(Debug, Clone, PartialEq)]
+pub struct WeekTemplate {
+    pub template_id: WeekTemplateId,
+    pub project_id: ProjectId,
+    pub name: WeekTemplateName,
+    pub shifts: Vec<TemplateShift>,
+}
+
+impl WeekTemplate {
+    pub fn new(project: &Project, name: WeekTemplateName) -> Self {
+        Self {
+            template_id: WeekTemplateId::default(),
+            project_id: project.project_id.clone(),
+            name,
+            shifts: project
+                .members
+                .iter()
+                .flat_map(|member| {
+                    member.shifts.iter().map(TemplateShift::from)
+                })
+                .collect(),
+        }
+    }
+
+    // The template's shifts as new shifts under the project's current rules.
+    // `member_map` puts other members on a template member's shifts, e.g. to
+    // cover someone who has left; everyone else keeps their own shifts.
+    pub fn instantiate(
+        &self,
+        settings: &ProjectSettings,
+        members: &[Member],
+        member_map: &HashMap<MemberId, MemberId>,
+    ) -> Result<Vec<Shift>, ValidationError> {
+        for member_id in member_map.keys() {
+            let in_template = self.shifts.iter().any(|shift| {
+                &shift.member_id == member_id
+                    || shift.standby_member_id.as_ref() == Some(member_id)
+            });
+            if !in_template {
+                return Err(ValidationError::new(format!(
+                    "Member {} has no shifts in the template to map",
+                    member_id.as_ref()
+                )));
+            }
+        }
+
+        let find_member = |member_id: &MemberId| {
+            let member_id = member_map.get(member_id).unwrap_or(member_id);
+            members
+                .iter()
+                .find(|member| &member.member_id == member_id)
+                .ok_or_else(|| {
+                    ValidationError::new(format!(
+                        "Member {} is not in the project, so their shifts \
+                         need mapping to someone who is",
+                        member_id.as_ref()
+                    ))
+                })
+        };
+
+        self.shifts
+            .iter()
+            .map(|template_shift| {
+                let member = find_member(&template_shift.member_id)?;
+                let mut shift = settings.new_shift(
+                    member.member_id.clone(),
+                    template_shift.day,
+                    template_shift.start_time.clone(),
+                    template_shift.end_time.clone(),
+                    false,
+                )?;
+                if let Some(standby_member_id) =
+                    &template_shift.standby_member_id
+                {
+                    shift.assign_standby(
+                        member,
+                        find_member(standby_member_id)?,
+                    )?;
+                }
+                shift.tags = template_shift.tags.clone();
+                shift.note = template_shift.note.clone();
+                shift.location = template_shift.location.clone();
+                Ok(shift)
+            })
+            .collect()
+    }
+}
+
+// Stop a member being put on two shifts at once, between `new` shifts or
+// with the `existing` ones they would join
+pub fn check_overlaps(
+    existing: &[Shift],
+    new: &[Shift],
+    members: &[Member],
+) -> Result<(), ValidationError> {
+    for (i, shift) in new.iter().enumerate() {
+        let clash = existing
+            .iter()
+            .chain(&new[i + 1..])
+            .find(|other| shift.overlaps(other));
+        if clash.is_some() {
+            let name = members
+                .iter()
+                .find(|member| member.member_id == shift.member_id)
+                .map(|member| member.member_name.as_ref().as_str())
+                .unwrap_or("A member");
+            return Err(ValidationError::new(format!(
+                "{} would have overlapping shifts on {}",
+                name, shift.day
+            )));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{MemberName, ProjectMember, ProjectName};
+
+    fn member(project_id: &ProjectId, name: &str) -> Member {
+        Member::new(
+            project_id.clone(),
+            MemberId::default(),
+            MemberName::parse(name.to_owned()).unwrap(),
+        )
+    }
+
+    fn shift(member: &Member, day: Day, start_time: i16) -> Shift {
+        let mut shift = Shift::new(
+            member.member_id.clone(),
+            day,
+            Minute::parse(start_time).unwrap(),
+            Minute::parse(start_time + 480).unwrap(),
+        )
+        .unwrap();
+        shift.tags = vec![ShiftTag::parse("training").unwrap()];
+        shift.note = ShiftNote::parse("Bring the float").unwrap();
+        shift
+    }
+
+    fn template(
+        project_id: &ProjectId,
+        shifts: Vec<(&Member, Shift)>,
+    ) -> WeekTemplate {
+        let members = shifts
+            .into_iter()
+            .map(|(member, shift)| {
+                ProjectMember::new(
+                    member.member_id.clone(),
+                    member.member_name.clone(),
+                    vec![shift],
+                )
+            })
+            .collect();
+        let project = Project::new(
+            project_id.clone(),
+            ProjectName::parse("Craggy Island").unwrap(),
+            Day::Sunday,
+            members,
+        );
+        WeekTemplate::new(&project, WeekTemplateName::parse("Usual").unwrap())
+    }
+
+    #[test]
+    fn test_template_names() {
+        assert_eq!(
+            WeekTemplateName::parse(" Summer week ").unwrap().as_ref(),
+            "Summer week"
+        );
+        assert!(WeekTemplateName::parse("  ").is_err());
+        assert!(WeekTemplateName::parse(&"a".repeat(100)).is_ok());
+        assert!(WeekTemplateName::parse(&"a".repeat(101)).is_err());
+    }
+
+    #[test]
+    fn test_instantiate_keeps_shift_details() {
+        let project_id = ProjectId::default();
+        let ted = member(&project_id, "Ted");
+        let dougal = member(&project_id, "Dougal");
+        let mut saved = shift(&ted, Day::Monday, 540);
+        saved.assign_standby(&ted, &dougal).unwrap();
+        let template = template(&project_id, vec![(&ted, saved.clone())]);
+
+        let shifts = template
+            .instantiate(
+                &ProjectSettings::default(),
+                &[ted.clone(), dougal.clone()],
+                &HashMap::new(),
+            )
+            .unwrap();
+
+        assert_eq!(shifts.len(), 1);
+        assert_ne!(shifts[0].id, saved.id);
+        assert_eq!(shifts[0].member_id, ted.member_id);
+        assert_eq!(shifts[0].standby_member_id, Some(dougal.member_id));
+        assert_eq!(shifts[0].day, Day::Monday);
+        assert_eq!(shifts[0].tags, saved.tags);
+        assert_eq!(shifts[0].note, saved.note);
+    }
+
+    #[test]
+    fn test_instantiate_maps_members() {
+        let project_id = ProjectId::default();
+        let ted = member(&project_id, "Ted");
+        let dougal = member(&project_id, "Dougal");
+        let jack = member(&project_id, "Jack");
+        let template = template(
+            &project_id,
+            vec![
+                (&ted, shift(&ted, Day::Monday, 540)),
+                (&jack, shift(&jack, Day::Tuesday, 540)),
+            ],
+        );
+
+        // Jack has left the project
+        let members = [ted.clone(), dougal.clone()];
+        let error = template
+            .instantiate(&ProjectSettings::default(), &members, &HashMap::new())
+            .unwrap_err();
+        assert!(error.as_ref().starts_with(&format!(
+            "Member {} is not in the project",
+            jack.member_id.as_ref()
+        )));
+
+        let member_map =
+            HashMap::from([(jack.member_id.clone(), dougal.member_id.clone())]);
+        let shifts = template
+            .instantiate(&ProjectSettings::default(), &members, &member_map)
+            .unwrap();
+        let assigned: Vec<(&MemberId, Day)> = shifts
+            .iter()
+            .map(|shift| (&shift.member_id, shift.day))
+            .collect();
+        assert_eq!(
+            assigned,
+            [
+                (&ted.member_id, Day::Monday),
+                (&dougal.member_id, Day::Tuesday)
+            ]
+        );
+
+        // Dougal has no shifts of his own in the template
+        let member_map =
+            HashMap::from([(dougal.member_id.clone(), ted.member_id.clone())]);
+        assert!(template
+            .instantiate(&ProjectSettings::default(), &members, &member_map)
+            .is_err());
+    }
+
+    #[test]
+    fn test_check_overlaps() {
+        let project_id = ProjectId::default();
+        let ted = member(&project_id, "Ted");
+        let dougal = member(&project_id, "Dougal");
+        let members = [ted.clone(), dougal.clone()];
+        let existing = [shift(&ted, Day::Monday, 540)];
+
+        // Back to back, on another day or with someone else
+        let new = [
+            shift(&ted, Day::Monday, 60),
+            shift(&ted, Day::Tuesday, 540),
+            shift(&dougal, Day::Monday, 540),
+        ];
+        assert!(check_overlaps(&existing, &new, &members).is_ok());
+
+        let error = check_overlaps(
+            &existing,
+            &[shift(&ted, Day::Monday, 600)],
+            &members,
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.as_ref(),
+            "Ted would have overlapping shifts on Monday"
+        );
+
+        let new = [
+            shift(&dougal, Day::Friday, 540),
+            shift(&dougal, Day::Friday, 900),
+        ];
+        assert!(check_overlaps(&[], &new, &members).is_err());
+    }
+}
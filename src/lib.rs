@@ -1,5 +1,8 @@
 use axum::{
-    http::{Method, StatusCode},
+    error_handling::HandleErrorLayer,
+    extract::connect_info::{ConnectInfo, IntoMakeServiceWithConnectInfo},
+    http::{header, Method, StatusCode},
+    middleware::{self, AddExtension},
     response::{IntoResponse, Response},
     routing::{delete, get, post, put},
     serve::Serve,
@@ -7,114 +10,370 @@ use axum::{
 };
 
 use redis::{Client, RedisResult};
+use schemars::JsonSchema;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
-use sqlx::{postgres::PgPoolOptions, PgPool};
-use std::error::Error;
+use sqlx::{
+    migrate::MigrateError,
+    postgres::{PgConnectOptions, PgPoolOptions},
+    PgPool,
+};
+use std::{error::Error, net::SocketAddr, str::FromStr, sync::LazyLock};
 use tokio::signal;
+use tower::{limit::GlobalConcurrencyLimitLayer, BoxError, ServiceBuilder};
 use tower_http::{cors::CorsLayer, trace::TraceLayer};
 use tracing::Level;
 
 use domain::{AuthAPIError, ProjectAPIError};
 pub mod routes;
-use crate::utils::tracing::*;
+use crate::utils::{
+    access::enforce_route_access,
+    constants::{
+        AUTH_RETRY_AFTER, DATABASE_ACQUIRE_TIMEOUT, DATABASE_RETRY_AFTER,
+        DEFAULT_AUTH_CONCURRENCY_LIMIT,
+    },
+    database::is_database_unavailable,
+    deadline::enforce_deadline,
+    deprecation::deprecation_telemetry,
+    hashing_pool::is_hashing_overloaded,
+    listener::bind_listener,
+    metrics::METRICS_HANDLE,
+    policy_consent::require_policy_acceptance,
+    request_capture::capture_requests,
+    tracing::*,
+    warm_up::warm_up,
+};
 use routes::{
-    auth::{delete_user, login, logout, signup, verify_2fa, verify_token},
+    admin::{
+        bootstrap, create_invitation, get_deprecated_usage, get_legal_holds,
+        get_log_filter, get_request_capture, provision_api_key, provision_user,
+        update_data_region, update_legal_hold, update_log_filter,
+        update_request_capture,
+    },
+    api_docs::get_schemas,
+    auth::{
+        accept_policy, change_email, confirm_email_change, delete_session,
+        delete_user, forgot_password, get_quota, get_sessions, login, logout,
+        request_magic_link, reset_password, setup_2fa, signup,
+        update_email_tracking, update_locale, update_profile,
+        update_requires_2fa, update_security_emails, verify_2fa,
+        verify_magic_link, verify_token,
+    },
+    email::{open_email, receive_email_webhook, receive_inbound_email},
+    integrations::{
+        create_integration_key, delete_integration_key, get_integration_keys,
+        get_integration_updates,
+    },
+    me::{
+        bulk_review_rotas, get_calendar_feed, get_email_status,
+        get_pending_approvals, review_rota,
+    },
+    metrics::get_metrics,
+    notifications::{
+        get_notifications, mark_notifications_read, poll_notifications,
+    },
+    policies::get_policy,
     projects::{
-        add_member, add_shift, get_member, get_member_list_for_project,
-        get_project, get_project_list, new_project, update_member,
+        add_comment, add_department, add_member, add_shift, add_shift_slot,
+        apply_week_template, broadcast_open_shift, clear_shifts,
+        delete_comment, fill_shift_slot, get_attendance_report,
+        get_borrow_suggestions, get_comments, get_departments, get_member,
+        get_member_contact, get_member_list_for_project, get_printed_rota,
+        get_project, get_project_batch, get_project_emails,
+        get_project_history, get_project_list, get_project_settings,
+        get_project_summary, get_rota_approvals, get_rota_diff,
+        get_rota_reactions, get_shift_slots, get_week_templates, import_rota,
+        new_project, publish_rota, report_absence, save_week_template,
+        submit_rota, update_member, update_member_contact,
+        update_project_settings, validate_shift,
     },
+    sms::receive_sms,
+    version::get_version,
 };
 pub mod app_state;
 pub mod domain;
 pub mod services;
-use app_state::AppState;
+use app_state::{AppState, DatabaseSettings};
 pub mod utils;
 
-#[derive(Serialize, Deserialize)]
+#[derive(Serialize, Deserialize, JsonSchema)]
 pub struct ErrorResponse {
+    // Stable identifier for the kind of error, for clients to match on
+    pub code: String,
     pub error: String,
+    // Which parts of the request were at fault, where that is known
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<FieldError>,
+    // What wasn't found, for not_found errors
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<MissingEntity>,
+}
+
+impl ErrorResponse {
+    pub fn new(code: &str, error: String) -> Self {
+        Self {
+            code: code.to_owned(),
+            error,
+            details: Vec::new(),
+            entity: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Vec<FieldError>) -> Self {
+        self.details = details;
+        self
+    }
+
+    pub fn with_entity(mut self, kind: &str, id: uuid::Uuid) -> Self {
+        self.entity = Some(MissingEntity {
+            kind: kind.to_owned(),
+            id,
+        });
+        self
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MissingEntity {
+    // e.g. "project", "member" or "shift"
+    pub kind: String,
+    pub id: uuid::Uuid,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct FieldError {
+    pub field: String,
+    pub message: String,
 }
 
 impl IntoResponse for AuthAPIError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
+        let (status, code, error_message) = match &self {
             AuthAPIError::UserAlreadyExists => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::CONFLICT, "User already exists".to_string())
+                (
+                    StatusCode::CONFLICT,
+                    "user_already_exists",
+                    "User already exists".to_string(),
+                )
             }
             AuthAPIError::ValidationError(message) => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::BAD_REQUEST, format!("{message}"))
+                (
+                    StatusCode::BAD_REQUEST,
+                    "validation_error",
+                    format!("{message}"),
+                )
             }
             AuthAPIError::UserNotFound => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::NOT_FOUND, "User not found".to_string())
+                (
+                    StatusCode::NOT_FOUND,
+                    "user_not_found",
+                    "User not found".to_string(),
+                )
+            }
+            AuthAPIError::LegalHold => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::CONFLICT,
+                    "legal_hold",
+                    "The account is under legal hold".to_string(),
+                )
+            }
+            AuthAPIError::SessionNotFound => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::NOT_FOUND,
+                    "session_not_found",
+                    "Session not found".to_string(),
+                )
             }
             AuthAPIError::IncorrectCredentials => {
                 log_error_chain(&self, Level::DEBUG);
                 (
                     StatusCode::UNAUTHORIZED,
+                    "incorrect_credentials",
                     "Incorrect credentials".to_string(),
                 )
             }
+            AuthAPIError::UnexpectedError(_)
+                if is_database_unavailable(&self) =>
+            {
+                log_error_chain(&self, Level::WARN);
+                return database_unavailable_response();
+            }
+            AuthAPIError::UnexpectedError(_)
+                if is_hashing_overloaded(&self) =>
+            {
+                log_error_chain(&self, Level::WARN);
+                return auth_overloaded_response();
+            }
             AuthAPIError::UnexpectedError(_) => {
                 log_error_chain(&self, Level::ERROR);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
+                    "unexpected_error",
                     "Unexpected error".to_string(),
                 )
             }
+            AuthAPIError::MissingCredentials => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::BAD_REQUEST,
+                    "missing_credentials",
+                    "Missing credentials".to_string(),
+                )
+            }
             AuthAPIError::MissingToken => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::BAD_REQUEST, "Missing token".to_string())
+                (
+                    StatusCode::BAD_REQUEST,
+                    "missing_token",
+                    "Missing token".to_string(),
+                )
             }
             AuthAPIError::InvalidToken => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::UNAUTHORIZED, "Invalid token".to_string())
+                (
+                    StatusCode::UNAUTHORIZED,
+                    "invalid_token",
+                    "Invalid token".to_string(),
+                )
+            }
+            AuthAPIError::AlreadyBootstrapped => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::CONFLICT,
+                    "already_bootstrapped",
+                    "This instance has already been set up".to_string(),
+                )
+            }
+            AuthAPIError::PolicyNotAccepted(version) => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::UNAVAILABLE_FOR_LEGAL_REASONS,
+                    "policy_not_accepted",
+                    format!(
+                        "Version {} of the terms of service and privacy \
+                         policy must be accepted",
+                        version
+                    ),
+                )
+            }
+            AuthAPIError::InvitationRequired => {
+                log_error_chain(&self, Level::DEBUG);
+                (
+                    StatusCode::FORBIDDEN,
+                    "invitation_required",
+                    "A valid invitation code is required to sign up"
+                        .to_string(),
+                )
             }
         };
-        let body = Json(ErrorResponse {
-            error: error_message,
-        });
+        let body = Json(ErrorResponse::new(code, error_message));
         (status, body).into_response()
     }
 }
 
 impl IntoResponse for ProjectAPIError {
     fn into_response(self) -> Response {
-        let (status, error_message) = match &self {
-            ProjectAPIError::IDNotFoundError(id) => {
+        let (status, code, error_message) = match &self {
+            ProjectAPIError::ProjectNotFound(_)
+            | ProjectAPIError::MemberNotFound(_)
+            | ProjectAPIError::ShiftNotFound(_)
+            | ProjectAPIError::DepartmentNotFound(_)
+            | ProjectAPIError::CommentNotFound(_)
+            | ProjectAPIError::ShiftSlotNotFound(_)
+            | ProjectAPIError::WeekTemplateNotFound(_)
+            | ProjectAPIError::RotaVersionNotFound(_)
+            | ProjectAPIError::RotaApprovalNotFound(_)
+            | ProjectAPIError::IntegrationKeyNotFound(_) => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::NOT_FOUND, format!("{id}"))
+                (StatusCode::NOT_FOUND, "not_found", format!("{self}"))
             }
             ProjectAPIError::IDExistsError(id) => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::CONFLICT, format!("{id}"))
+                (StatusCode::CONFLICT, "already_exists", format!("{id}"))
             }
             ProjectAPIError::AuthenticationError(auth_error) => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::UNAUTHORIZED, format!("{auth_error}"))
+                (
+                    StatusCode::UNAUTHORIZED,
+                    "authentication_error",
+                    format!("{auth_error}"),
+                )
+            }
+            ProjectAPIError::UnexpectedError(_)
+                if is_database_unavailable(&self) =>
+            {
+                log_error_chain(&self, Level::WARN);
+                return database_unavailable_response();
+            }
+            ProjectAPIError::UnexpectedError(_)
+                if is_hashing_overloaded(&self) =>
+            {
+                log_error_chain(&self, Level::WARN);
+                return auth_overloaded_response();
             }
             ProjectAPIError::UnexpectedError(_) => {
                 log_error_chain(&self, Level::ERROR);
                 (
                     StatusCode::INTERNAL_SERVER_ERROR,
+                    "unexpected_error",
                     "Unexpected error".to_string(),
                 )
             }
             ProjectAPIError::ValidationError(message) => {
                 log_error_chain(&self, Level::DEBUG);
-                (StatusCode::BAD_REQUEST, format!("{message}"))
+                (
+                    StatusCode::BAD_REQUEST,
+                    "validation_error",
+                    format!("{message}"),
+                )
             }
         };
-        let body = Json(ErrorResponse {
-            error: error_message,
-        });
+        let mut body = ErrorResponse::new(code, error_message);
+        if let Some((kind, id)) = self.not_found() {
+            body = body.with_entity(kind, id);
+        }
+        let body = Json(body);
         (status, body).into_response()
     }
 }
 
+// PostgreSQL is out of reach, most likely failing over, so ask the client to
+// try again shortly rather than report the request as broken
+fn database_unavailable_response() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(
+            header::RETRY_AFTER,
+            DATABASE_RETRY_AFTER.as_secs().to_string(),
+        )],
+        Json(ErrorResponse::new(
+            "database_unavailable",
+            "The service is briefly unavailable".to_string(),
+        )),
+    )
+        .into_response()
+}
+
+// Too many requests are already hashing passwords, whether shed by the
+// concurrency limit or by the hashing pool's queue. Turning this one away
+// straight away keeps the rest of the API responsive.
+fn auth_overloaded_response() -> Response {
+    (
+        StatusCode::SERVICE_UNAVAILABLE,
+        [(header::RETRY_AFTER, AUTH_RETRY_AFTER.as_secs().to_string())],
+        Json(ErrorResponse::new(
+            "auth_overloaded",
+            "Too many sign-ins at once, please try again shortly".to_string(),
+        )),
+    )
+        .into_response()
+}
+
 fn log_error_chain(e: &(dyn Error + 'static), debug_level: Level) {
     let separator =
         "\n-----------------------------------------------------------------------------------\n";
@@ -136,7 +395,12 @@ fn log_error_chain(e: &(dyn Error + 'static), debug_level: Level) {
 }
 
 pub struct Application {
-    server: Serve<Router, Router>,
+    // With the peer address, which sessions record for requests that
+    // didn't come through a proxy
+    server: Serve<
+        IntoMakeServiceWithConnectInfo<Router, SocketAddr>,
+        AddExtension<Router, ConnectInfo<SocketAddr>>,
+    >,
     pub address: String,
 }
 
@@ -145,6 +409,15 @@ impl Application {
         app_state: AppState,
         address: &str,
     ) -> Result<Self, Box<dyn Error>> {
+        LazyLock::force(&METRICS_HANDLE);
+        warm_up(&app_state).await;
+
+        let reuse_port = app_state.settings.reuse_port;
+        let auth_concurrency_limit = app_state
+            .settings
+            .auth_concurrency_limit
+            .unwrap_or(DEFAULT_AUTH_CONCURRENCY_LIMIT);
+
         let allowed_origins = [
             "http://localhost:3000".parse()?,
             "http://127.0.0.1:3000".parse()?,
@@ -152,25 +425,168 @@ impl Application {
         ];
 
         let cors = CorsLayer::new()
-            .allow_methods([Method::GET, Method::POST])
+            .allow_methods([
+                Method::GET,
+                Method::POST,
+                Method::PUT,
+                Method::DELETE,
+            ])
             .allow_credentials(true)
             .allow_origin(allowed_origins);
 
-        let router = Router::new()
+        // The routes that hash passwords or backup codes share one pool of
+        // permits, and requests beyond it are shed rather than queued
+        let hashing_routes = Router::new()
             .route("/auth/signup", post(signup))
             .route("/auth/login", post(login))
             .route("/auth/verify-2fa", post(verify_2fa))
+            .route("/auth/2fa", put(update_requires_2fa))
+            .route("/auth/2fa/setup", post(setup_2fa))
+            .route("/auth/reset-password", post(reset_password))
+            .layer(
+                ServiceBuilder::new()
+                    .layer(HandleErrorLayer::new(|_: BoxError| async {
+                        auth_overloaded_response()
+                    }))
+                    .load_shed()
+                    .layer(GlobalConcurrencyLimitLayer::new(
+                        auth_concurrency_limit,
+                    )),
+            );
+
+        let router = Router::new()
+            .merge(hashing_routes)
             .route("/auth/logout", post(logout))
             .route("/auth/verify-token", post(verify_token))
             .route("/auth/delete-user", delete(delete_user))
+            .route("/auth/email", put(change_email))
+            .route("/auth/change-email", post(change_email))
+            .route("/auth/confirm-email-change", post(confirm_email_change))
+            .route("/auth/forgot-password", post(forgot_password))
+            .route("/auth/magic-link", post(request_magic_link))
+            .route("/auth/magic-link/verify", get(verify_magic_link))
+            .route("/auth/security-emails", put(update_security_emails))
+            .route("/auth/email-tracking", put(update_email_tracking))
+            .route("/auth/accept-policy", post(accept_policy))
+            .route("/auth/locale", put(update_locale))
+            .route("/auth/profile", put(update_profile))
+            .route("/auth/quota", get(get_quota))
+            .route("/auth/sessions", get(get_sessions))
+            .route("/auth/sessions/:id", delete(delete_session))
+            .route("/policies", get(get_policy))
+            .route("/me/calendar.ics", get(get_calendar_feed))
+            .route("/me/email", get(get_email_status))
+            .route("/me/approvals", get(get_pending_approvals))
+            .route("/me/approvals/review", post(review_rota))
+            .route("/me/approvals/bulk-review", post(bulk_review_rotas))
+            .route("/notifications", get(get_notifications))
+            .route("/notifications/mark-read", post(mark_notifications_read))
+            .route("/notifications/poll", get(poll_notifications))
+            .route(
+                "/integrations/keys",
+                get(get_integration_keys).post(create_integration_key),
+            )
+            .route("/integrations/keys/:id", delete(delete_integration_key))
+            .route("/integrations/updates", get(get_integration_updates))
             .route("/projects/new", post(new_project))
             .route("/projects/list", get(get_project_list))
+            .route(
+                "/projects/departments",
+                get(get_departments).post(add_department),
+            )
             .route("/projects/add-member", post(add_member))
             .route("/projects/get-members", get(get_member_list_for_project))
             .route("/projects/get-member", get(get_member))
             .route("/projects/update-member", put(update_member))
+            .route(
+                "/projects/member-contact",
+                get(get_member_contact).put(update_member_contact),
+            )
             .route("/projects/shifts", post(add_shift))
+            .route("/projects/shifts/validate", post(validate_shift))
+            .route("/projects/shifts/clear", post(clear_shifts))
+            .route("/projects/slots", get(get_shift_slots).post(add_shift_slot))
+            .route("/projects/slots/fill", post(fill_shift_slot))
+            .route(
+                "/projects/slots/borrow-suggestions",
+                get(get_borrow_suggestions),
+            )
+            .route(
+                "/projects/templates",
+                get(get_week_templates).post(save_week_template),
+            )
+            .route("/projects/templates/apply", post(apply_week_template))
+            .route("/projects/import", post(import_rota))
+            .route("/projects/rota/publish", post(publish_rota))
+            .route("/projects/rota/submit", post(submit_rota))
+            .route("/projects/rota/approvals", get(get_rota_approvals))
+            .route("/projects/rota/diff", get(get_rota_diff))
+            .route("/projects/rota/reactions", get(get_rota_reactions))
+            .route("/projects/absences", post(report_absence))
+            .route(
+                "/projects/comments",
+                get(get_comments).post(add_comment).delete(delete_comment),
+            )
+            .route(
+                "/projects/open-shifts/broadcast",
+                post(broadcast_open_shift),
+            )
+            .route("/projects/attendance-report", get(get_attendance_report))
+            .route("/projects/print", get(get_printed_rota))
             .route("/projects/project", get(get_project))
+            .route("/projects/batch", get(get_project_batch))
+            .route("/projects/summary", get(get_project_summary))
+            .route("/projects/history", get(get_project_history))
+            .route("/projects/emails", get(get_project_emails))
+            .route(
+                "/projects/settings",
+                get(get_project_settings).put(update_project_settings),
+            )
+            .route("/sms/inbound", post(receive_sms))
+            .route("/email/open", get(open_email))
+            .route("/email/webhook", post(receive_email_webhook))
+            .route("/email/inbound", post(receive_inbound_email))
+            .route("/api-docs/schemas", get(get_schemas))
+            .route("/metrics", get(get_metrics))
+            .route("/version", get(get_version))
+            .route("/admin/deprecated-usage", get(get_deprecated_usage))
+            .route("/admin/invitations", post(create_invitation))
+            .route("/admin/bootstrap", post(bootstrap))
+            .route(
+                "/admin/log-filter",
+                get(get_log_filter).put(update_log_filter),
+            )
+            .route("/admin/data-region", put(update_data_region))
+            .route(
+                "/admin/request-capture",
+                get(get_request_capture).put(update_request_capture),
+            )
+            .route(
+                "/admin/legal-holds",
+                get(get_legal_holds).put(update_legal_hold),
+            )
+            .route("/admin/provisioning/users/:id", put(provision_user))
+            .route("/admin/provisioning/api-keys/:id", put(provision_api_key))
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                require_policy_acceptance,
+            ))
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                enforce_route_access,
+            ))
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                deprecation_telemetry,
+            ))
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                enforce_deadline,
+            ))
+            .layer(middleware::from_fn_with_state(
+                app_state.clone(),
+                capture_requests,
+            ))
             .with_state(app_state)
             .layer(cors)
             .layer(
@@ -180,9 +596,12 @@ impl Application {
                     .on_response(on_response),
             );
 
-        let listener = tokio::net::TcpListener::bind(address).await?;
+        let listener = bind_listener(address, reuse_port).await?;
         let address = listener.local_addr()?.to_string();
-        let server = axum::serve(listener, router);
+        let server = axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        );
 
         Ok(Application { server, address })
     }
@@ -193,8 +612,7 @@ impl Application {
     }
 }
 
-#[allow(dead_code)]
-async fn shutdown_signal() {
+pub async fn shutdown_signal() {
     let ctrl_c = async {
         signal::ctrl_c()
             .await
@@ -220,13 +638,38 @@ async fn shutdown_signal() {
 
 pub async fn get_postgres_pool(
     url: &Secret<String>,
+    settings: &DatabaseSettings,
 ) -> Result<PgPool, sqlx::Error> {
+    let mut options = PgConnectOptions::from_str(url.expose_secret())?;
+    if settings.pgbouncer_mode {
+        // A statement cached on one server connection isn't there on the
+        // next, so prepare each query afresh rather than reusing it
+        options = options.statement_cache_capacity(0);
+    }
     PgPoolOptions::new()
         .max_connections(5)
-        .connect(url.expose_secret())
+        // Fail fast while the server is unreachable, so the caller can back
+        // off and retry within the request deadline
+        .acquire_timeout(DATABASE_ACQUIRE_TIMEOUT)
+        // Connections left dead by a failover are pinged, found out and
+        // replaced before they're handed out
+        .test_before_acquire(true)
+        .connect_with(options)
         .await
 }
 
+pub async fn run_migrations(
+    pool: &PgPool,
+    settings: &DatabaseSettings,
+) -> Result<(), MigrateError> {
+    let mut migrator = sqlx::migrate!();
+    // The migration lock is a session advisory lock, which a pooler in
+    // transaction mode may release on a different server connection to the
+    // one that took it. Only one instance should migrate at a time.
+    migrator.set_locking(!settings.pgbouncer_mode);
+    migrator.run(pool).await
+}
+
 pub fn get_redis_client(redis_hostname: String) -> RedisResult<Client> {
     let redis_url = format!("redis://{}/", redis_hostname);
     redis::Client::open(redis_url)
@@ -1,56 +1,169 @@
 use reqwest::Client;
+use secrecy::ExposeSecret;
 use sqlx::PgPool;
 use std::sync::{Arc, LazyLock};
 use tokio::sync::RwLock;
 
+#[cfg(not(feature = "postmark"))]
+use rota_manager::services::mock_email_client::MockEmailClient;
 use rota_manager::{
-    app_state::AppState,
-    domain::Email,
-    get_postgres_pool, get_redis_client,
+    app_state::{
+        AppState, ClockType, DatabaseSettings, EmailClientType, EventBusType,
+        JobLockStoreType, Settings, SmsClientType, UserStoreType,
+    },
+    domain::{find_policy, PhoneNumber, VerificationToken},
+    get_postgres_pool, get_redis_client, run_migrations,
     services::{
+        broadcast_event_bus::BroadcastEventBus,
+        clock::SystemClock,
         data_stores::{
-            PostgresProjectStore, PostgresUserStore, RedisBannedTokenStore,
-            RedisTwoFACodeStore,
+            PostgresIntegrationStore, PostgresProjectStore,
+            PostgresShiftReminderStore, PostgresUserStore,
+            RedisBannedTokenStore, RedisDeprecatedUsageStore,
+            RedisJobLockStore, RedisPasswordResetTokenStore,
+            RedisRequestCaptureStore, RedisSessionStore, RedisTwoFACodeStore,
+            RedisVerificationTokenStore,
         },
-        postmark_email_client::PostmarkEmailClient,
+        email_outbox_relay::spawn_email_outbox_relay,
+        event_subscribers::{audit_log, spawn_subscriber},
+        id_generator::RandomIdGenerator,
+        job_worker::spawn_job_worker,
+        mock_sms_client::MockSmsClient,
+        notification_digest::spawn_rota_change_digests,
+        notification_inbox::spawn_notification_inbox,
+        outbox_relay::spawn_outbox_relay,
+        retention::spawn_retention_pruning,
+        rota_publisher::spawn_rota_publisher,
+        security_emails::spawn_security_emails,
+        shift_escalations::spawn_shift_escalations,
+        shift_reminders::spawn_shift_reminders,
+        sms_outbox_relay::spawn_sms_outbox_relay,
+        twilio_sms_client::TwilioSmsClient,
     },
+    shutdown_signal,
     utils::{
         constants::{
-            prod, DATABASE_URL, POSTMARK_AUTH_TOKEN,
-            POSTMARK_EMAIL_SENDER_ADDRESS, REDIS_HOST_NAME, TWO_FA_CODE_REGEX,
+            build_info, prod, AUTH_CONCURRENCY_LIMIT, DATABASE_URL,
+            EMAILS_PER_RECIPIENT_PER_HOUR, EVENT_BUS_CAPACITY, INSTANCE_ROLE,
+            INVITE_ONLY_SIGNUP, PGBOUNCER_MODE, POSTMARK_INBOUND_ADDRESS,
+            POSTMARK_WEBHOOK_TOKEN, REDIS_HOST_NAME, REQUIRED_POLICY_VERSION,
+            RESIDENCY_RULES, RETENTION_POLICY, REUSE_PORT, TRUST_PROXY_HEADERS,
+            TWILIO_ACCOUNT_SID, TWILIO_AUTH_TOKEN, TWILIO_SENDER_NUMBER,
+            TWO_FA_CODE_REGEX,
         },
-        tracing::init_tracing,
+        tracing::{init_tracing, spawn_log_filter_signal},
     },
     Application,
 };
+#[cfg(feature = "postmark")]
+use rota_manager::{
+    domain::Email,
+    services::postmark_email_client::PostmarkEmailClient,
+    utils::constants::{POSTMARK_AUTH_TOKEN, POSTMARK_EMAIL_SENDER_ADDRESS},
+};
 
 #[tokio::main]
 async fn main() {
     LazyLock::force(&TWO_FA_CODE_REGEX);
+    if let Some(version) = *REQUIRED_POLICY_VERSION {
+        find_policy(version)
+            .expect("REQUIRED_POLICY_VERSION must be a published version");
+    }
     color_eyre::install().expect("Failed to install color_eyre");
-    init_tracing().expect("Failed to initialise tracing");
+    let log_filter = init_tracing().expect("Failed to initialise tracing");
+    #[cfg(unix)]
+    spawn_log_filter_signal(log_filter.clone());
+    tracing::info!(
+        version = build_info::VERSION,
+        git_sha = build_info::GIT_SHA,
+        build_timestamp = build_info::BUILD_TIMESTAMP,
+        features = ?build_info::FEATURES,
+        "starting rota-manager"
+    );
 
     let pg_pool = configure_postgresql().await;
-    let user_store =
+    let user_store: UserStoreType =
         Arc::new(RwLock::new(PostgresUserStore::new(pg_pool.clone())));
     let project_store =
-        Arc::new(RwLock::new(PostgresProjectStore::new(pg_pool)));
+        Arc::new(RwLock::new(PostgresProjectStore::new(pg_pool.clone())));
+    let shift_reminder_store = Arc::new(RwLock::new(
+        PostgresShiftReminderStore::new(pg_pool.clone()),
+    ));
+    let integration_store =
+        Arc::new(RwLock::new(PostgresIntegrationStore::new(pg_pool.clone())));
 
     let redis_connection = Arc::new(RwLock::new(configure_redis()));
     let banned_token_store = Arc::new(RwLock::new(RedisBannedTokenStore::new(
         redis_connection.clone(),
     )));
 
-    let two_fa_code_store =
-        Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+    let two_fa_code_store = Arc::new(RwLock::new(RedisTwoFACodeStore::new(
+        redis_connection.clone(),
+    )));
+
+    let verification_token_store = Arc::new(RwLock::new(
+        RedisVerificationTokenStore::new(redis_connection.clone()),
+    ));
+
+    let password_reset_token_store = Arc::new(RwLock::new(
+        RedisPasswordResetTokenStore::new(redis_connection.clone()),
+    ));
+
+    let deprecated_usage_store = Arc::new(RwLock::new(
+        RedisDeprecatedUsageStore::new(redis_connection.clone()),
+    ));
+    let request_capture_store = Arc::new(RwLock::new(
+        RedisRequestCaptureStore::new(redis_connection.clone()),
+    ));
+    let session_store =
+        Arc::new(RwLock::new(RedisSessionStore::new(redis_connection)));
 
-    let email_client = Arc::new(configure_postmark_email_client());
+    let event_bus: EventBusType =
+        Arc::new(BroadcastEventBus::new(EVENT_BUS_CAPACITY));
+    let email_client = configure_email_client();
+    let clock: ClockType = Arc::new(SystemClock);
+
+    let role = *INSTANCE_ROLE;
+    if role.runs_workers() {
+        spawn_workers(pg_pool, &event_bus, &email_client, &clock);
+    }
+    if !role.serves_api() {
+        tracing::info!("running background jobs only");
+        shutdown_signal().await;
+        return;
+    }
+
+    let bootstrap_token = configure_bootstrap_token(&user_store).await;
     let app_state = AppState::new(
         user_store,
         banned_token_store,
         two_fa_code_store,
+        verification_token_store,
+        password_reset_token_store,
         email_client,
         project_store,
+        deprecated_usage_store,
+        request_capture_store,
+        session_store,
+        shift_reminder_store,
+        integration_store,
+        prod::REQUEST_TIMEOUT,
+        event_bus,
+        clock,
+        Arc::new(RandomIdGenerator),
+        Settings {
+            invite_only_signup: *INVITE_ONLY_SIGNUP,
+            bootstrap_token,
+            required_policy_version: *REQUIRED_POLICY_VERSION,
+            twilio_auth_token: TWILIO_AUTH_TOKEN.clone(),
+            postmark_webhook_token: POSTMARK_WEBHOOK_TOKEN.clone(),
+            trust_proxy_headers: *TRUST_PROXY_HEADERS,
+            reuse_port: *REUSE_PORT,
+            auth_concurrency_limit: Some(*AUTH_CONCURRENCY_LIMIT),
+            role,
+            log_filter: Some(log_filter),
+            residency_rules: RESIDENCY_RULES.clone(),
+        },
     );
 
     let application = Application::build(app_state, prod::APP_ADDRESS)
@@ -62,19 +175,121 @@ async fn main() {
         .expect("Failed to run auth-service application");
 }
 
+// The outbox relays, the pollers and the event subscribers. Events only
+// reach subscribers through the outbox relay, so they run alongside it.
+fn spawn_workers(
+    pg_pool: PgPool,
+    event_bus: &EventBusType,
+    email_client: &EmailClientType,
+    clock: &ClockType,
+) {
+    // A connection of its own, so background jobs polling for their leases
+    // don't queue behind requests
+    let job_lock: JobLockStoreType = Arc::new(RwLock::new(
+        RedisJobLockStore::new(Arc::new(RwLock::new(configure_redis()))),
+    ));
+
+    spawn_outbox_relay(
+        pg_pool.clone(),
+        event_bus.clone(),
+        job_lock.clone(),
+        prod::OUTBOX_POLL_INTERVAL,
+    );
+    spawn_email_outbox_relay(
+        pg_pool.clone(),
+        email_client.clone(),
+        job_lock.clone(),
+        prod::OUTBOX_POLL_INTERVAL,
+        *EMAILS_PER_RECIPIENT_PER_HOUR,
+    );
+    spawn_sms_outbox_relay(
+        pg_pool.clone(),
+        configure_sms_client(),
+        job_lock.clone(),
+        prod::OUTBOX_POLL_INTERVAL,
+    );
+    spawn_shift_reminders(
+        pg_pool.clone(),
+        job_lock.clone(),
+        clock.clone(),
+        prod::SHIFT_REMINDER_POLL_INTERVAL,
+        prod::SHIFT_REMINDER_LEAD_TIME,
+        POSTMARK_INBOUND_ADDRESS.clone(),
+    );
+    spawn_shift_escalations(
+        pg_pool.clone(),
+        Client::builder()
+            .timeout(prod::CHAT_WEBHOOK_TIMEOUT)
+            .build()
+            .expect("Failed to build HTTP client"),
+        job_lock.clone(),
+        clock.clone(),
+        RESIDENCY_RULES.clone(),
+        prod::SHIFT_ESCALATION_POLL_INTERVAL,
+    );
+    spawn_rota_publisher(
+        pg_pool.clone(),
+        job_lock.clone(),
+        clock.clone(),
+        prod::ROTA_PUBLISH_POLL_INTERVAL,
+    );
+    spawn_retention_pruning(
+        pg_pool.clone(),
+        job_lock.clone(),
+        clock.clone(),
+        *RETENTION_POLICY,
+        prod::RETENTION_POLL_INTERVAL,
+    );
+    spawn_job_worker(pg_pool.clone(), job_lock, prod::JOB_POLL_INTERVAL);
+    spawn_subscriber(event_bus, "audit_log", audit_log);
+    spawn_security_emails(event_bus, pg_pool.clone());
+    spawn_notification_inbox(event_bus, pg_pool.clone());
+    spawn_rota_change_digests(
+        event_bus,
+        pg_pool,
+        prod::ROTA_CHANGE_DIGEST_WINDOW,
+    );
+}
+
 async fn configure_postgresql() -> PgPool {
-    let pg_pool = get_postgres_pool(&DATABASE_URL)
+    let settings = DatabaseSettings {
+        pgbouncer_mode: *PGBOUNCER_MODE,
+    };
+    let pg_pool = get_postgres_pool(&DATABASE_URL, &settings)
         .await
         .expect("Failed to create Postgres connection pool");
 
-    sqlx::migrate!()
-        .run(&pg_pool)
+    run_migrations(&pg_pool, &settings)
         .await
         .expect("Failed to run migrations");
 
     pg_pool
 }
 
+// A fresh instance has no way in, so print a one-time token for creating
+// the first admin account through POST /admin/bootstrap
+async fn configure_bootstrap_token(
+    user_store: &UserStoreType,
+) -> Option<VerificationToken> {
+    let has_users = user_store
+        .read()
+        .await
+        .has_users()
+        .await
+        .expect("Failed to check for existing users");
+    if has_users {
+        return None;
+    }
+
+    let token = VerificationToken::default();
+    tracing::warn!(
+        "No users found. Create the admin account with POST /admin/bootstrap \
+         using bootstrap token {}",
+        token.as_ref().expose_secret()
+    );
+    Some(token)
+}
+
 fn configure_redis() -> redis::Connection {
     get_redis_client(REDIS_HOST_NAME.to_owned())
         .expect("Failed to get Redis client")
@@ -82,17 +297,52 @@ fn configure_redis() -> redis::Connection {
         .expect("Failed to get Redis connection")
 }
 
-fn configure_postmark_email_client() -> PostmarkEmailClient {
+#[cfg(feature = "postmark")]
+fn configure_email_client() -> EmailClientType {
     let http_client = Client::builder()
         .timeout(prod::email_client::TIMEOUT)
         .build()
         .expect("Failed to build HTTP client");
 
-    PostmarkEmailClient::new(
+    Arc::new(PostmarkEmailClient::new(
         prod::email_client::BASE_URL.to_owned(),
         Email::parse(POSTMARK_EMAIL_SENDER_ADDRESS.to_owned())
             .expect("Failed to parse POSTMARK_EMAIL_SENDER_ADDRESS"),
         POSTMARK_AUTH_TOKEN.to_owned(),
         http_client,
-    )
+    ))
+}
+
+// Builds without Postmark, such as small ones for a Raspberry Pi, only log
+// emails
+#[cfg(not(feature = "postmark"))]
+fn configure_email_client() -> EmailClientType {
+    tracing::warn!("Built without Postmark, emails will not be sent");
+    Arc::new(MockEmailClient)
+}
+
+// Text messages are only logged unless Twilio is configured
+fn configure_sms_client() -> SmsClientType {
+    let (Some(account_sid), Some(auth_token), Some(sender)) = (
+        TWILIO_ACCOUNT_SID.as_ref(),
+        TWILIO_AUTH_TOKEN.as_ref(),
+        TWILIO_SENDER_NUMBER.as_ref(),
+    ) else {
+        tracing::warn!("Twilio is not configured, SMS will not be sent");
+        return Arc::new(MockSmsClient);
+    };
+
+    let http_client = Client::builder()
+        .timeout(prod::sms_client::TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    Arc::new(TwilioSmsClient::new(
+        prod::sms_client::BASE_URL.to_owned(),
+        account_sid.to_owned(),
+        auth_token.to_owned(),
+        PhoneNumber::parse(sender.to_owned())
+            .expect("Failed to parse TWILIO_SENDER_NUMBER"),
+        http_client,
+    ))
 }
@@ -0,0 +1,80 @@
+use axum::{extract::State, http::StatusCode};
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, Email, Password, User, UserPasswordHash, UserStoreError,
+        VerificationToken,
+    },
+    utils::{
+        admin::constant_time_eq, json::Json,
+        pwned_passwords::reject_pwned_password,
+    },
+};
+
+// Create the first account on a fresh instance as an admin, using the
+// one-time token logged at startup
+#[tracing::instrument(name = "Bootstrap route handler", skip_all)]
+pub async fn bootstrap(
+    State(state): State<AppState>,
+    Json(request): Json<BootstrapRequest>,
+) -> Result<(StatusCode, Json<BootstrapResponse>), AuthAPIError> {
+    // No token is issued once there are users
+    let expected = state
+        .settings
+        .bootstrap_token
+        .as_ref()
+        .ok_or(AuthAPIError::AlreadyBootstrapped)?;
+    let token = VerificationToken::parse(Secret::new(request.token))?;
+    if !constant_time_eq(
+        token.as_ref().expose_secret().as_bytes(),
+        expected.as_ref().expose_secret().as_bytes(),
+    ) {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    let email = Email::parse(Secret::new(request.email))?;
+    let password = Password::parse(request.password)?;
+    reject_pwned_password(&password).await?;
+    let hash = UserPasswordHash::from_password(password)
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    // Whoever sets up the instance is the one publishing its policies
+    let mut user = User::new(email, hash, false);
+    user.accepted_policy_version = state.settings.required_policy_version;
+
+    state
+        .user_store
+        .write()
+        .await
+        .add_first_admin(user)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::AlreadyBootstrapped => {
+                AuthAPIError::AlreadyBootstrapped
+            }
+            e => AuthAPIError::UnexpectedError(e.into()),
+        })?;
+
+    let response = Json(BootstrapResponse {
+        message: "Admin account created".to_string(),
+    });
+
+    Ok((StatusCode::CREATED, response))
+}
+
+#[derive(Deserialize)]
+pub struct BootstrapRequest {
+    pub token: String,
+    pub email: String,
+    pub password: Secret<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BootstrapResponse {
+    pub message: String,
+}
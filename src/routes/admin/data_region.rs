@@ -0,0 +1,65 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, DataRegion, UserId, UserStoreError},
+    utils::{admin::check_admin_key, json::Json},
+};
+
+// Set where an account's data has to stay, as agreed in its contract. A
+// null region lifts the restriction. Webhooks already set up outside the
+// region stop being sent rather than being removed.
+#[tracing::instrument(name = "Update data region route handler", skip_all)]
+pub async fn update_data_region(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<DataRegionRequest>,
+) -> Result<(StatusCode, Json<DataRegionResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let user_id = UserId::new(request.user_id);
+    let region = request
+        .region
+        .as_deref()
+        .map(DataRegion::parse)
+        .transpose()?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_data_region(&user_id, region)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        Json(DataRegionResponse {
+            user_id: request.user_id,
+            region,
+        }),
+    ))
+}
+
+#[derive(Deserialize)]
+pub struct DataRegionRequest {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub region: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DataRegionResponse {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub region: Option<DataRegion>,
+}
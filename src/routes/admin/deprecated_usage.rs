@@ -0,0 +1,63 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::AuthAPIError,
+    utils::{admin::check_admin_key, deprecation::DEPRECATED_ENDPOINTS},
+};
+
+#[tracing::instrument(
+    name = "Get deprecated endpoint usage route handler",
+    skip_all
+)]
+pub async fn get_deprecated_usage(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<DeprecatedUsageResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let store = state.deprecated_usage_store.read().await;
+    let mut endpoints = Vec::with_capacity(DEPRECATED_ENDPOINTS.len());
+    for endpoint in DEPRECATED_ENDPOINTS {
+        let clients = store
+            .get_usage(endpoint.path)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?
+            .into_iter()
+            .map(|(client, count)| ClientUsage { client, count })
+            .collect();
+
+        endpoints.push(DeprecatedEndpointUsage {
+            path: endpoint.path.to_owned(),
+            sunset: endpoint.sunset.to_owned(),
+            clients,
+        });
+    }
+
+    Ok((StatusCode::OK, Json(DeprecatedUsageResponse { endpoints })))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeprecatedUsageResponse {
+    pub endpoints: Vec<DeprecatedEndpointUsage>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DeprecatedEndpointUsage {
+    pub path: String,
+    pub sunset: String,
+    pub clients: Vec<ClientUsage>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ClientUsage {
+    pub client: String,
+    pub count: u64,
+}
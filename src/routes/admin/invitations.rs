@@ -0,0 +1,60 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Invitation},
+    utils::{admin::check_admin_key, json::Json},
+};
+
+// Mint an invitation code for invitation-only signup
+#[tracing::instrument(name = "Create invitation route handler", skip_all)]
+pub async fn create_invitation(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<CreateInvitationRequest>,
+) -> Result<(StatusCode, Json<InvitationResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let invitation =
+        Invitation::new(request.max_uses, request.expires_in_days)?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .add_invitation(&invitation)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(InvitationResponse {
+        code: invitation.code.as_ref().expose_secret().to_owned(),
+        max_uses: invitation.max_uses,
+        expires_at: invitation.expires_at.to_rfc3339(),
+    });
+
+    Ok((StatusCode::CREATED, response))
+}
+
+#[derive(Deserialize)]
+pub struct CreateInvitationRequest {
+    #[serde(rename = "maxUses")]
+    pub max_uses: i32,
+    #[serde(rename = "expiresInDays")]
+    pub expires_in_days: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct InvitationResponse {
+    pub code: String,
+    #[serde(rename = "maxUses")]
+    pub max_uses: i32,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+}
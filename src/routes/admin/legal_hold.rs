@@ -0,0 +1,119 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, LegalHold, UserId, UserStoreError},
+    utils::{admin::check_admin_key, json::Json},
+};
+
+// Every account under legal hold, longest held first
+#[tracing::instrument(name = "Get legal holds route handler", skip_all)]
+pub async fn get_legal_holds(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<LegalHoldListResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let holds = state
+        .user_store
+        .read()
+        .await
+        .get_legal_holds()
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?
+        .into_iter()
+        .map(HeldUserResponse::from)
+        .collect();
+
+    Ok((StatusCode::OK, Json(LegalHoldListResponse { holds })))
+}
+
+// Place or lift a legal hold on an account. While held, the account can't
+// be deleted or anonymized and its records are kept past their retention
+// period. Placing a hold that's already in place keeps when it was placed.
+#[tracing::instrument(name = "Update legal hold route handler", skip_all)]
+pub async fn update_legal_hold(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<LegalHoldRequest>,
+) -> Result<(StatusCode, Json<LegalHoldResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let user_id = UserId::new(request.user_id);
+    let placed_at = request.held.then(|| state.clock.now());
+    let map_error = |e| match e {
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        e => AuthAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut user_store = state.user_store.write().await;
+    user_store
+        .set_legal_hold(&user_id, placed_at)
+        .await
+        .map_err(map_error)?;
+    let placed_at = user_store
+        .get_legal_hold(&user_id)
+        .await
+        .map_err(map_error)?;
+    drop(user_store);
+
+    tracing::warn!(
+        "legal hold on user {} {} by an admin",
+        user_id.as_ref(),
+        if request.held { "placed" } else { "lifted" }
+    );
+    let response = Json(LegalHoldResponse {
+        user_id: *user_id.as_ref(),
+        held: placed_at.is_some(),
+        placed_at: placed_at.map(|placed_at| placed_at.to_rfc3339()),
+    });
+
+    Ok((StatusCode::OK, response))
+}
+
+#[derive(Deserialize)]
+pub struct LegalHoldRequest {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub held: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LegalHoldResponse {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub held: bool,
+    #[serde(rename = "placedAt")]
+    pub placed_at: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LegalHoldListResponse {
+    pub holds: Vec<HeldUserResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct HeldUserResponse {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub email: String,
+    #[serde(rename = "placedAt")]
+    pub placed_at: String,
+}
+
+impl From<LegalHold> for HeldUserResponse {
+    fn from(hold: LegalHold) -> Self {
+        Self {
+            user_id: *hold.user_id.as_ref(),
+            email: hold.email.as_ref().expose_secret().to_owned(),
+            placed_at: hold.placed_at.to_rfc3339(),
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::AuthAPIError,
+    utils::{
+        admin::check_admin_key,
+        json::Json,
+        tracing::{parse_log_filter, LogFilter},
+    },
+};
+
+#[tracing::instrument(name = "Get log filter route handler", skip_all)]
+pub async fn get_log_filter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+) -> Result<(StatusCode, Json<LogFilterResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let filter = log_filter(&state)?
+        .current()
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    Ok((StatusCode::OK, Json(LogFilterResponse { filter })))
+}
+
+// Change which logs are kept without a restart, e.g.
+// "info,rota_manager::services::job_worker=debug" while investigating the
+// job worker. It lasts until the next change or restart.
+#[tracing::instrument(name = "Update log filter route handler", skip_all)]
+pub async fn update_log_filter(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<LogFilterRequest>,
+) -> Result<(StatusCode, Json<LogFilterResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let filter = parse_log_filter(&request.filter)?;
+    let log_filter = log_filter(&state)?;
+    log_filter
+        .set(filter)
+        .map_err(AuthAPIError::UnexpectedError)?;
+    let filter = log_filter
+        .current()
+        .map_err(AuthAPIError::UnexpectedError)?;
+    tracing::warn!("log filter changed by an admin to {}", filter);
+
+    Ok((StatusCode::OK, Json(LogFilterResponse { filter })))
+}
+
+fn log_filter(state: &AppState) -> Result<&LogFilter, AuthAPIError> {
+    state.settings.log_filter.as_ref().ok_or_else(|| {
+        AuthAPIError::UnexpectedError(eyre!(
+            "tracing was set up without a reloadable log filter"
+        ))
+    })
+}
+
+#[derive(Deserialize)]
+pub struct LogFilterRequest {
+    // In RUST_LOG's format
+    pub filter: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct LogFilterResponse {
+    pub filter: String,
+}
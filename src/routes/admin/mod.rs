@@ -0,0 +1,17 @@
+mod bootstrap;
+mod data_region;
+mod deprecated_usage;
+mod invitations;
+mod legal_hold;
+mod log_filter;
+mod provisioning;
+mod request_capture;
+
+pub use bootstrap::*;
+pub use data_region::*;
+pub use deprecated_usage::*;
+pub use invitations::*;
+pub use legal_hold::*;
+pub use log_filter::*;
+pub use provisioning::*;
+pub use request_capture::*;
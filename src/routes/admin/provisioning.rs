@@ -0,0 +1,324 @@
+use axum::{
+    extract::{Path, State},
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, Email, ExternalId, IntegrationKey, IntegrationKeyId,
+        IntegrationKeyName, IntegrationKeySecret, IntegrationStore,
+        IntegrationStoreError, Password, ProvisionStatus, User, UserId,
+        UserPasswordHash, UserStoreError, ValidationError,
+        MAX_INTEGRATION_KEYS,
+    },
+    utils::{admin::check_admin_key, json::Json},
+};
+
+// Create or update the account an infrastructure-as-code tool knows by
+// `external_id`, so tools can manage tenants declaratively. Accounts are
+// the tenants here; each owns its projects. Provisioned accounts get a
+// password nobody knows, so users sign in with a magic link or reset it.
+#[tracing::instrument(name = "Provision user route handler", skip_all)]
+pub async fn provision_user(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(external_id): Path<String>,
+    Json(request): Json<ProvisionUserRequest>,
+) -> Result<(StatusCode, Json<ProvisionedUserResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let external_id = ExternalId::parse(&external_id)?;
+    let email = Email::parse(Secret::new(request.email))?;
+
+    let existing = state
+        .user_store
+        .read()
+        .await
+        .get_provisioned_user(&external_id)
+        .await;
+    let (user, status) = match existing {
+        Ok(user) => {
+            update_provisioned_user(&state, user, email, request.requires_2fa)
+                .await?
+        }
+        Err(UserStoreError::UserNotFound) => {
+            let password =
+                Password::parse(Secret::new(uuid::Uuid::new_v4().to_string()))?;
+            let hash = UserPasswordHash::from_password(password)
+                .await
+                .map_err(AuthAPIError::UnexpectedError)?;
+            let user = User::new(email.clone(), hash, request.requires_2fa);
+            let added = state
+                .user_store
+                .write()
+                .await
+                .add_provisioned_user(user.clone(), &external_id)
+                .await;
+            match added {
+                Ok(()) => (user, ProvisionStatus::Created),
+                // Either another request provisioned the account first, in
+                // which case this one applies to it, or the email is taken
+                Err(UserStoreError::UserAlreadyExists) => {
+                    let existing = state
+                        .user_store
+                        .read()
+                        .await
+                        .get_provisioned_user(&external_id)
+                        .await;
+                    match existing {
+                        Ok(user) => {
+                            update_provisioned_user(
+                                &state,
+                                user,
+                                email,
+                                request.requires_2fa,
+                            )
+                            .await?
+                        }
+                        Err(UserStoreError::UserNotFound) => {
+                            return Err(AuthAPIError::UserAlreadyExists)
+                        }
+                        Err(e) => return Err(map_user_store_error(e)),
+                    }
+                }
+                Err(e) => return Err(map_user_store_error(e)),
+            }
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    };
+
+    let response = Json(ProvisionedUserResponse {
+        user_id: *user.id.as_ref(),
+        external_id,
+        email: user.email.as_ref().expose_secret().to_owned(),
+        requires_2fa: user.requires_2fa,
+        status,
+    });
+
+    Ok((status_code(status), response))
+}
+
+// Bring an account that's already provisioned in line with the request
+async fn update_provisioned_user(
+    state: &AppState,
+    mut user: User,
+    email: Email,
+    requires_2fa: bool,
+) -> Result<(User, ProvisionStatus), AuthAPIError> {
+    let mut status = ProvisionStatus::Unchanged;
+    let mut user_store = state.user_store.write().await;
+    if user.email != email {
+        user_store
+            .update_email(&user.id, &email)
+            .await
+            .map_err(map_user_store_error)?;
+        user.email = email;
+        status = ProvisionStatus::Updated;
+    }
+    if user.requires_2fa != requires_2fa {
+        user_store
+            .set_requires_2fa(&user.id, requires_2fa)
+            .await
+            .map_err(map_user_store_error)?;
+        user.requires_2fa = requires_2fa;
+        status = ProvisionStatus::Updated;
+    }
+    Ok((user, status))
+}
+
+fn map_user_store_error(e: UserStoreError) -> AuthAPIError {
+    match e {
+        UserStoreError::UserAlreadyExists => AuthAPIError::UserAlreadyExists,
+        UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+        e => AuthAPIError::UnexpectedError(eyre!(e)),
+    }
+}
+
+// Create or rename the integration key a tool knows by `external_id`, for
+// the account provisioned as `userExternalId`. The key itself is only in
+// the response that creates it.
+#[tracing::instrument(name = "Provision API key route handler", skip_all)]
+pub async fn provision_api_key(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Path(external_id): Path<String>,
+    Json(request): Json<ProvisionApiKeyRequest>,
+) -> Result<(StatusCode, Json<ProvisionedApiKeyResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let external_id = ExternalId::parse(&external_id)?;
+    let user_external_id = ExternalId::parse(&request.user_external_id)?;
+    let name = IntegrationKeyName::parse(&request.name)?;
+    let user = state
+        .user_store
+        .read()
+        .await
+        .get_provisioned_user(&user_external_id)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let mut integration_store = state.integration_store.write().await;
+    let existing = integration_store
+        .get_provisioned_integration_key(&external_id)
+        .await;
+    let (key, secret, status) = match existing {
+        Ok(key) => {
+            let (key, status) = update_provisioned_key(
+                &mut *integration_store,
+                key,
+                &user.id,
+                name,
+                &external_id,
+            )
+            .await?;
+            (key, None, status)
+        }
+        Err(IntegrationStoreError::KeyNotFound) => {
+            let keys = integration_store
+                .get_integration_keys(&user.id)
+                .await
+                .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+            if keys.len() >= MAX_INTEGRATION_KEYS {
+                return Err(ValidationError::new(format!(
+                    "Accounts can have at most {} integration keys",
+                    MAX_INTEGRATION_KEYS
+                ))
+                .into());
+            }
+
+            let secret = IntegrationKeySecret::generate();
+            let key = IntegrationKey::new(
+                user.id.clone(),
+                name.clone(),
+                state.clock.now(),
+            );
+            let added = integration_store
+                .add_provisioned_integration_key(
+                    &key,
+                    &secret.hash(),
+                    &external_id,
+                )
+                .await;
+            match added {
+                Ok(()) => (key, Some(secret), ProvisionStatus::Created),
+                // Another instance provisioned the key first
+                Err(IntegrationStoreError::KeyAlreadyExists) => {
+                    let key = integration_store
+                        .get_provisioned_integration_key(&external_id)
+                        .await
+                        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+                    let (key, status) = update_provisioned_key(
+                        &mut *integration_store,
+                        key,
+                        &user.id,
+                        name,
+                        &external_id,
+                    )
+                    .await?;
+                    (key, None, status)
+                }
+                Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+            }
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    };
+    drop(integration_store);
+
+    let response = Json(ProvisionedApiKeyResponse {
+        key_id: key.key_id,
+        external_id,
+        user_id: *key.user_id.as_ref(),
+        name: key.name,
+        key: secret.map(|secret| secret.as_ref().expose_secret().to_owned()),
+        created_at: key.created_at.to_rfc3339(),
+        status,
+    });
+
+    Ok((status_code(status), response))
+}
+
+// Rename a key that's already provisioned to match the request
+async fn update_provisioned_key(
+    integration_store: &mut (dyn IntegrationStore + Send + Sync),
+    mut key: IntegrationKey,
+    user_id: &UserId,
+    name: IntegrationKeyName,
+    external_id: &ExternalId,
+) -> Result<(IntegrationKey, ProvisionStatus), AuthAPIError> {
+    // Keys can't be moved, as the tool holding one would start seeing
+    // another account's updates
+    if &key.user_id != user_id {
+        return Err(ValidationError::new(format!(
+            "API key {} belongs to another user",
+            external_id.as_ref()
+        ))
+        .into());
+    }
+    if key.name == name {
+        return Ok((key, ProvisionStatus::Unchanged));
+    }
+    integration_store
+        .rename_integration_key(&key.key_id, &name)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    key.name = name;
+    Ok((key, ProvisionStatus::Updated))
+}
+
+fn status_code(status: ProvisionStatus) -> StatusCode {
+    match status {
+        ProvisionStatus::Created => StatusCode::CREATED,
+        ProvisionStatus::Updated | ProvisionStatus::Unchanged => StatusCode::OK,
+    }
+}
+
+#[derive(Deserialize)]
+pub struct ProvisionUserRequest {
+    pub email: String,
+    #[serde(rename = "requires2FA", default)]
+    pub requires_2fa: bool,
+}
+
+#[derive(Deserialize)]
+pub struct ProvisionApiKeyRequest {
+    #[serde(rename = "userExternalId")]
+    pub user_external_id: String,
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProvisionedUserResponse {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    #[serde(rename = "externalId")]
+    pub external_id: ExternalId,
+    pub email: String,
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+    pub status: ProvisionStatus,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProvisionedApiKeyResponse {
+    #[serde(rename = "keyId")]
+    pub key_id: IntegrationKeyId,
+    #[serde(rename = "externalId")]
+    pub external_id: ExternalId,
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub name: IntegrationKeyName,
+    // Sent in the X-Api-Key header. Only in the response that creates the
+    // key, since just its hash is kept.
+    pub key: Option<String>,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    pub status: ProvisionStatus,
+}
@@ -0,0 +1,132 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, CapturedRequest, UserId},
+    utils::{admin::check_admin_key, json::Json, query::ValidatedQuery},
+};
+
+#[derive(Deserialize)]
+pub struct RequestCaptureQueryParams {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+}
+
+// What's been captured for a user, newest first
+#[tracing::instrument(name = "Get request capture route handler", skip_all)]
+pub async fn get_request_capture(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    query_params: ValidatedQuery<RequestCaptureQueryParams>,
+) -> Result<(StatusCode, Json<RequestCaptureResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let user_id = UserId::new(query_params.user_id);
+    let response = capture_response(&state, &user_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+// Start or stop capturing the requests made with a user's sessions, to
+// reproduce a bug they've reported. Capture stops by itself after a day.
+// Stopping keeps what's been captured until it expires.
+#[tracing::instrument(name = "Update request capture route handler", skip_all)]
+pub async fn update_request_capture(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<RequestCaptureRequest>,
+) -> Result<(StatusCode, Json<RequestCaptureResponse>), AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    let user_id = UserId::new(request.user_id);
+    {
+        let mut store = state.request_capture_store.write().await;
+        match request.enabled {
+            true => store.start_capture(&user_id).await,
+            false => store.stop_capture(&user_id).await,
+        }
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    }
+    tracing::warn!(
+        "request capture for user {} turned {} by an admin",
+        user_id.as_ref(),
+        if request.enabled { "on" } else { "off" }
+    );
+    let response = capture_response(&state, &user_id).await?;
+
+    Ok((StatusCode::OK, Json(response)))
+}
+
+async fn capture_response(
+    state: &AppState,
+    user_id: &UserId,
+) -> Result<RequestCaptureResponse, AuthAPIError> {
+    let store = state.request_capture_store.read().await;
+    let capturing = store
+        .is_capturing(user_id)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    let requests = store
+        .get_captured(user_id)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?
+        .into_iter()
+        .map(CapturedRequestResponse::from)
+        .collect();
+
+    Ok(RequestCaptureResponse {
+        user_id: *user_id.as_ref(),
+        capturing,
+        requests,
+    })
+}
+
+#[derive(Deserialize)]
+pub struct RequestCaptureRequest {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub enabled: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RequestCaptureResponse {
+    #[serde(rename = "userId")]
+    pub user_id: uuid::Uuid,
+    pub capturing: bool,
+    pub requests: Vec<CapturedRequestResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CapturedRequestResponse {
+    #[serde(rename = "capturedAt")]
+    pub captured_at: String,
+    pub method: String,
+    pub uri: String,
+    pub status: u16,
+    // Secrets are redacted, and bodies that aren't JSON are described
+    // rather than captured
+    #[serde(rename = "requestBody")]
+    pub request_body: Option<Value>,
+    #[serde(rename = "responseBody")]
+    pub response_body: Option<Value>,
+}
+
+impl From<CapturedRequest> for CapturedRequestResponse {
+    fn from(captured: CapturedRequest) -> Self {
+        Self {
+            captured_at: captured.captured_at.to_rfc3339(),
+            method: captured.method,
+            uri: captured.uri,
+            status: captured.status,
+            request_body: captured.request_body,
+            response_body: captured.response_body,
+        }
+    }
+}
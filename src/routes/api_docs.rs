@@ -0,0 +1,252 @@
+use std::collections::BTreeMap;
+
+use axum::{http::StatusCode, Json};
+use schemars::{schema::RootSchema, schema_for, JsonSchema};
+
+use crate::{
+    domain::Project,
+    routes::{
+        admin::{
+            BootstrapResponse, DataRegionResponse, DeprecatedUsageResponse,
+            InvitationResponse, LegalHoldListResponse, LegalHoldResponse,
+            LogFilterResponse, ProvisionedApiKeyResponse,
+            ProvisionedUserResponse, RequestCaptureResponse,
+        },
+        auth::{
+            AcceptPolicyResponse, ChangeEmailResponse, DeleteUserResponse,
+            EmailTrackingResponse, LocaleResponse, MagicLinkResponse,
+            PasswordResetResponse, ProfileResponse, QuotaResponse,
+            Requires2FAResponse, SecurityEmailsResponse, SessionListResponse,
+            SignupResponse, TotpSetupResponse, TwoFactorAuthResponse,
+        },
+        integrations::{
+            CreatedIntegrationKeyResponse, IntegrationKeyListResponse,
+            IntegrationUpdatesResponse,
+        },
+        me::{
+            BulkReviewRotasResponse, EmailStatusResponse,
+            PendingApprovalListResponse,
+        },
+        notifications::{
+            MarkReadResponse, NotificationListResponse,
+            NotificationPollResponse,
+        },
+        policies::PolicyResponse,
+        projects::{
+            AbsenceResponse, AddCommentResponse, AddMemberResponse,
+            AddShiftResponse, ApplyWeekTemplateResponse,
+            AttendanceReportResponse, BorrowSuggestionsResponse,
+            ClearShiftsResponse, CommentListResponse, DepartmentListResponse,
+            DepartmentResponse, ImportRotaResponse, MemberContactResponse,
+            MemberListResponse, MemberResponse, NewProjectResponse,
+            OpenShiftBroadcastResponse, ProjectBatchResponse,
+            ProjectEmailListResponse, ProjectHistoryResponse,
+            ProjectListResponse, ProjectOverviewResponse, ProjectResponse,
+            ProjectSettingsResponse, RotaApprovalListResponse,
+            RotaApprovalResponse, RotaDiffResponse, RotaReactionsResponse,
+            RotaVersionResponse, ShiftSlotListResponse, ShiftSlotResponse,
+            UpdateMemberResponse, ValidateShiftResponse,
+            WeekTemplateListResponse, WeekTemplateResponse,
+        },
+        version::VersionResponse,
+    },
+    ErrorResponse,
+};
+
+pub type SchemaRegistry = BTreeMap<String, RootSchema>;
+
+#[tracing::instrument(name = "Get API schemas route handler", skip_all)]
+pub async fn get_schemas() -> (StatusCode, Json<SchemaRegistry>) {
+    (StatusCode::OK, Json(schema_registry()))
+}
+
+// Every DTO returned by the API, keyed by its schema name. Integration tests
+// validate responses against these, so changing a DTO changes the contract.
+pub fn schema_registry() -> SchemaRegistry {
+    let mut registry = SchemaRegistry::new();
+
+    register::<ErrorResponse>(&mut registry);
+    register::<SignupResponse>(&mut registry);
+    register::<TotpSetupResponse>(&mut registry);
+    register::<TwoFactorAuthResponse>(&mut registry);
+    register::<Requires2FAResponse>(&mut registry);
+    register::<DeleteUserResponse>(&mut registry);
+    register::<ChangeEmailResponse>(&mut registry);
+    register::<PasswordResetResponse>(&mut registry);
+    register::<MagicLinkResponse>(&mut registry);
+    register::<SecurityEmailsResponse>(&mut registry);
+    register::<NewProjectResponse>(&mut registry);
+    register::<ProjectListResponse>(&mut registry);
+    register::<DepartmentResponse>(&mut registry);
+    register::<DepartmentListResponse>(&mut registry);
+    register::<AddMemberResponse>(&mut registry);
+    register::<MemberResponse>(&mut registry);
+    register::<MemberListResponse>(&mut registry);
+    register::<UpdateMemberResponse>(&mut registry);
+    register::<AddShiftResponse>(&mut registry);
+    register::<Project>(&mut registry);
+    register::<ProjectResponse>(&mut registry);
+    register::<ProjectBatchResponse>(&mut registry);
+    register::<ProjectOverviewResponse>(&mut registry);
+    register::<ProjectHistoryResponse>(&mut registry);
+    register::<ProjectSettingsResponse>(&mut registry);
+    register::<ValidateShiftResponse>(&mut registry);
+    register::<ClearShiftsResponse>(&mut registry);
+    register::<ShiftSlotResponse>(&mut registry);
+    register::<ShiftSlotListResponse>(&mut registry);
+    register::<BorrowSuggestionsResponse>(&mut registry);
+    register::<WeekTemplateResponse>(&mut registry);
+    register::<WeekTemplateListResponse>(&mut registry);
+    register::<ApplyWeekTemplateResponse>(&mut registry);
+    register::<ImportRotaResponse>(&mut registry);
+    register::<AbsenceResponse>(&mut registry);
+    register::<AttendanceReportResponse>(&mut registry);
+    register::<DeprecatedUsageResponse>(&mut registry);
+    register::<InvitationResponse>(&mut registry);
+    register::<LogFilterResponse>(&mut registry);
+    register::<RequestCaptureResponse>(&mut registry);
+    register::<DataRegionResponse>(&mut registry);
+    register::<LegalHoldResponse>(&mut registry);
+    register::<LegalHoldListResponse>(&mut registry);
+    register::<ProvisionedUserResponse>(&mut registry);
+    register::<ProvisionedApiKeyResponse>(&mut registry);
+    register::<BootstrapResponse>(&mut registry);
+    register::<PolicyResponse>(&mut registry);
+    register::<VersionResponse>(&mut registry);
+    register::<AcceptPolicyResponse>(&mut registry);
+    register::<LocaleResponse>(&mut registry);
+    register::<ProfileResponse>(&mut registry);
+    register::<SessionListResponse>(&mut registry);
+    register::<QuotaResponse>(&mut registry);
+    register::<MemberContactResponse>(&mut registry);
+    register::<OpenShiftBroadcastResponse>(&mut registry);
+    register::<RotaVersionResponse>(&mut registry);
+    register::<RotaApprovalResponse>(&mut registry);
+    register::<RotaApprovalListResponse>(&mut registry);
+    register::<PendingApprovalListResponse>(&mut registry);
+    register::<BulkReviewRotasResponse>(&mut registry);
+    register::<RotaDiffResponse>(&mut registry);
+    register::<AddCommentResponse>(&mut registry);
+    register::<CommentListResponse>(&mut registry);
+    register::<RotaReactionsResponse>(&mut registry);
+    register::<EmailTrackingResponse>(&mut registry);
+    register::<ProjectEmailListResponse>(&mut registry);
+    register::<EmailStatusResponse>(&mut registry);
+    register::<NotificationListResponse>(&mut registry);
+    register::<MarkReadResponse>(&mut registry);
+    register::<NotificationPollResponse>(&mut registry);
+    register::<CreatedIntegrationKeyResponse>(&mut registry);
+    register::<IntegrationKeyListResponse>(&mut registry);
+    register::<IntegrationUpdatesResponse>(&mut registry);
+
+    registry
+}
+
+fn register<T: JsonSchema>(registry: &mut SchemaRegistry) {
+    registry.insert(T::schema_name(), schema_for!(T));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn registry_contains_every_response_dto() {
+        let registry = schema_registry();
+        let expected = [
+            "AbsenceResponse",
+            "AcceptPolicyResponse",
+            "AddCommentResponse",
+            "AddMemberResponse",
+            "AddShiftResponse",
+            "ApplyWeekTemplateResponse",
+            "AttendanceReportResponse",
+            "BootstrapResponse",
+            "BorrowSuggestionsResponse",
+            "BulkReviewRotasResponse",
+            "ChangeEmailResponse",
+            "ClearShiftsResponse",
+            "CommentListResponse",
+            "CreatedIntegrationKeyResponse",
+            "DataRegionResponse",
+            "DeleteUserResponse",
+            "DepartmentListResponse",
+            "DepartmentResponse",
+            "DeprecatedUsageResponse",
+            "EmailStatusResponse",
+            "EmailTrackingResponse",
+            "ErrorResponse",
+            "ImportRotaResponse",
+            "IntegrationKeyListResponse",
+            "IntegrationUpdatesResponse",
+            "InvitationResponse",
+            "LegalHoldListResponse",
+            "LegalHoldResponse",
+            "LocaleResponse",
+            "LogFilterResponse",
+            "MagicLinkResponse",
+            "MarkReadResponse",
+            "MemberContactResponse",
+            "MemberListResponse",
+            "MemberResponse",
+            "NewProjectResponse",
+            "NotificationListResponse",
+            "NotificationPollResponse",
+            "OpenShiftBroadcastResponse",
+            "PasswordResetResponse",
+            "PendingApprovalListResponse",
+            "PolicyResponse",
+            "ProfileResponse",
+            "Project",
+            "ProjectBatchResponse",
+            "ProjectEmailListResponse",
+            "ProjectHistoryResponse",
+            "ProjectListResponse",
+            "ProjectOverviewResponse",
+            "ProjectResponse",
+            "ProjectSettingsResponse",
+            "ProvisionedApiKeyResponse",
+            "ProvisionedUserResponse",
+            "QuotaResponse",
+            "RequestCaptureResponse",
+            "Requires2FAResponse",
+            "RotaApprovalListResponse",
+            "RotaApprovalResponse",
+            "RotaDiffResponse",
+            "RotaReactionsResponse",
+            "RotaVersionResponse",
+            "SecurityEmailsResponse",
+            "SessionListResponse",
+            "ShiftSlotListResponse",
+            "ShiftSlotResponse",
+            "SignupResponse",
+            "TotpSetupResponse",
+            "TwoFactorAuthResponse",
+            "UpdateMemberResponse",
+            "ValidateShiftResponse",
+            "VersionResponse",
+            "WeekTemplateListResponse",
+            "WeekTemplateResponse",
+        ];
+
+        assert_eq!(
+            registry.keys().map(String::as_str).collect::<Vec<_>>(),
+            expected
+        );
+    }
+
+    #[test]
+    fn shift_schema_does_not_require_member_id() {
+        let schema = serde_json::to_value(schema_for!(Project))
+            .expect("Failed to serialise schema");
+        let required = &schema["definitions"]["Shift"]["required"];
+
+        assert!(
+            !required
+                .as_array()
+                .expect("Shift schema should list required fields")
+                .contains(&serde_json::json!("member_id")),
+            "memberId is never serialised, so it cannot be required"
+        );
+    }
+}
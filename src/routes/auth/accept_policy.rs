@@ -0,0 +1,59 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{parse_policy_version, AuthAPIError, UserStoreError},
+    utils::{auth::Claims, json::Json},
+};
+
+// Record that the signed-in user accepts a version of the terms of service
+// and privacy policy
+#[tracing::instrument(name = "Accept policy route handler", skip_all)]
+pub async fn accept_policy(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<AcceptPolicyRequest>,
+) -> Result<(StatusCode, CookieJar, Json<AcceptPolicyResponse>), AuthAPIError> {
+    let user_id = claims.id;
+    let policy = parse_policy_version(request.version)?;
+
+    // Accepting an older version wouldn't let the user back in
+    if let Some(required) = state.settings.required_policy_version {
+        if policy.version < required {
+            return Err(AuthAPIError::PolicyNotAccepted(required));
+        }
+    }
+
+    state
+        .user_store
+        .write()
+        .await
+        .accept_policy(&user_id, policy.version)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(AcceptPolicyResponse {
+        accepted_version: policy.version,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Deserialize)]
+pub struct AcceptPolicyRequest {
+    pub version: i32,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct AcceptPolicyResponse {
+    #[serde(rename = "acceptedVersion")]
+    pub accepted_version: i32,
+}
@@ -0,0 +1,217 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, Email, PendingEmailChange, UserStoreError,
+        VerificationToken, VerificationTokenStoreError, VerificationTokenType,
+    },
+    utils::{
+        auth::Claims, constants::APP_SERVICE_EXTERNAL_ADDRESS, json::Json,
+    },
+};
+
+// Start changing the signed-in user's email address, with PUT /auth/email or
+// the deprecated POST /auth/change-email. Nothing changes until the links
+// sent to both the old and the new address have been followed. Projects
+// belong to the user's ID, so they stay with the account.
+// These links are sent straight away, even to an undeliverable address, as
+// following them is how it's confirmed again.
+#[tracing::instrument(name = "Change email route handler", skip_all)]
+pub async fn change_email(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ChangeEmailRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ChangeEmailResponse>), AuthAPIError> {
+    let old_email = Email::parse(Secret::new(claims.sub))
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    let new_email = Email::parse(Secret::new(request.new_email))?;
+
+    // Asking for the current address confirms it again, so email to it is
+    // sent again after bouncing
+    if new_email != old_email {
+        match state.user_store.read().await.get_user(&new_email).await {
+            Ok(_) => return Err(AuthAPIError::UserAlreadyExists),
+            Err(UserStoreError::UserNotFound) => (),
+            Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+        }
+    }
+
+    let old_token = VerificationToken::default();
+    let new_token = VerificationToken::default();
+    {
+        let mut token_store = state.verification_token_store.write().await;
+        for (token, token_type, counterpart) in [
+            (
+                &old_token,
+                VerificationTokenType::ConfirmOldEmail,
+                &new_token,
+            ),
+            (
+                &new_token,
+                VerificationTokenType::ConfirmNewEmail,
+                &old_token,
+            ),
+        ] {
+            let change = PendingEmailChange {
+                user_id: claims.id.clone(),
+                new_email: new_email.clone(),
+                token_type,
+                counterpart: counterpart.clone(),
+                confirmed: false,
+            };
+            token_store
+                .add_token(token.clone(), change)
+                .await
+                .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+        }
+    }
+
+    for (recipient, token, content) in [
+        (
+            &old_email,
+            &old_token,
+            format!(
+                "Someone asked to change your account's email address to {}. \
+                 If this was you, confirm the change at",
+                new_email.as_ref().expose_secret()
+            ),
+        ),
+        (
+            &new_email,
+            &new_token,
+            String::from("Confirm this as your account's new email address at"),
+        ),
+    ] {
+        state
+            .email_client
+            .send_email(
+                recipient,
+                "Confirm your email address change",
+                &format!("{} {}", content, confirmation_link(token)),
+            )
+            .await
+            .map_err(AuthAPIError::UnexpectedError)?;
+    }
+
+    let response = Json(ChangeEmailResponse {
+        message: String::from(
+            "Confirm the change using the links sent to both addresses",
+        ),
+    });
+
+    Ok((StatusCode::ACCEPTED, jar, response))
+}
+
+// Confirm one side of an email address change. The change is applied by
+// whichever confirmation arrives second.
+#[tracing::instrument(name = "Confirm email change route handler", skip_all)]
+pub async fn confirm_email_change(
+    State(state): State<AppState>,
+    Json(request): Json<ConfirmEmailChangeRequest>,
+) -> Result<(StatusCode, Json<ChangeEmailResponse>), AuthAPIError> {
+    let token = VerificationToken::parse(Secret::new(request.token))?;
+    let mut token_store = state.verification_token_store.write().await;
+
+    let map_token_error = |e| match e {
+        VerificationTokenStoreError::TokenNotFound => {
+            AuthAPIError::InvalidToken
+        }
+        e => AuthAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut change = token_store
+        .get_token(&token)
+        .await
+        .map_err(map_token_error)?;
+
+    // The other half has expired, so the change can never complete
+    let counterpart = match token_store.get_token(&change.counterpart).await {
+        Ok(counterpart) => counterpart,
+        Err(e) => {
+            token_store
+                .remove_token(&token)
+                .await
+                .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+            return Err(map_token_error(e));
+        }
+    };
+
+    if !counterpart.confirmed {
+        change.confirmed = true;
+        token_store
+            .add_token(token, change)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+        let waiting_for = match counterpart.token_type {
+            VerificationTokenType::ConfirmOldEmail => "old",
+            VerificationTokenType::ConfirmNewEmail => "new",
+        };
+        let response = Json(ChangeEmailResponse {
+            message: format!(
+                "Confirmed. Follow the link sent to your {} address to finish \
+                 changing your email address",
+                waiting_for
+            ),
+        });
+        return Ok((StatusCode::ACCEPTED, response));
+    }
+
+    state
+        .user_store
+        .write()
+        .await
+        .update_email(&change.user_id, &change.new_email)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserAlreadyExists => {
+                AuthAPIError::UserAlreadyExists
+            }
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    for token in [&token, &change.counterpart] {
+        token_store
+            .remove_token(token)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    }
+
+    let response = Json(ChangeEmailResponse {
+        message: String::from("Email address changed"),
+    });
+
+    Ok((StatusCode::OK, response))
+}
+
+fn confirmation_link(token: &VerificationToken) -> String {
+    format!(
+        "{}/confirm-email-change?token={}",
+        *APP_SERVICE_EXTERNAL_ADDRESS,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ChangeEmailRequest {
+    #[serde(rename = "newEmail")]
+    pub new_email: String,
+}
+
+#[derive(Deserialize)]
+pub struct ConfirmEmailChangeRequest {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct ChangeEmailResponse {
+    pub message: String,
+}
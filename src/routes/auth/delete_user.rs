@@ -1,32 +1,142 @@
 use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::{cookie, CookieJar};
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
-    domain::{AuthAPIError, Email},
-    utils::{auth::get_claims, constants::JWT_COOKIE_NAME},
+    domain::{
+        AuthAPIError, Email, Password, UserId, UserPasswordHash, UserStoreError,
+    },
+    utils::{
+        auth::{end_sessions, Claims},
+        constants::JWT_COOKIE_NAME,
+        query::ValidatedQuery,
+    },
 };
 
+#[derive(Deserialize)]
+pub struct DeleteUserQueryParams {
+    // Keep the user's projects and shift history for payroll, removing only
+    // their personal details
+    #[serde(default)]
+    pub anonymize: bool,
+}
+
 #[tracing::instrument(name = "Delete user route handler", skip_all)]
 pub async fn delete_user(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
+    query_params: ValidatedQuery<DeleteUserQueryParams>,
 ) -> Result<(StatusCode, CookieJar, Json<DeleteUserResponse>), AuthAPIError> {
-    let claims = get_claims(&jar, &state.banned_token_store).await?;
-
+    // How long the token needs banning for once the user is gone
+    let accepted_until = claims.accepted_until();
     let user_id = claims.id;
 
     let email = Email::parse(Secret::new(claims.sub))
         .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
 
+    // Checked before anything is removed, as projects are deleted ahead of
+    // the user. The stores check again when removing the user.
+    let held = state
+        .user_store
+        .read()
+        .await
+        .get_legal_hold(&user_id)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    if held.is_some() {
+        return Err(AuthAPIError::LegalHold);
+    }
+
+    let message = match query_params.anonymize {
+        true => {
+            anonymize_user(&state, &user_id).await?;
+            // The anonymized account still owns projects, so the user's
+            // other sessions are signed out too, and the devices and IP
+            // addresses they were started from forgotten
+            end_sessions(
+                &user_id,
+                &state.session_store,
+                &state.banned_token_store,
+                &state.clock,
+            )
+            .await
+            .map_err(AuthAPIError::UnexpectedError)?;
+            format!("User anonymized: {}", email.as_ref().expose_secret())
+        }
+        false => {
+            delete_user_and_projects(&state, &user_id, &email).await?;
+            format!("User deleted: {}", email.as_ref().expose_secret())
+        }
+    };
+
+    let cookie =
+        jar.get(JWT_COOKIE_NAME)
+            .ok_or(AuthAPIError::UnexpectedError(eyre!(
+                "No JWT cookie found during delete user"
+            )))?;
+
+    let token = Secret::new(cookie.value().to_string());
+
+    state
+        .banned_token_store
+        .write()
+        .await
+        .add_token(&token, accepted_until, state.clock.now())
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(e))?;
+
+    let jar = jar.remove(cookie::Cookie::from(JWT_COOKIE_NAME));
+
+    let response = Json(DeleteUserResponse { message: message });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+// The password is random and never kept, so nobody can sign in as the
+// anonymized user
+#[tracing::instrument(name = "Anonymizing user", skip_all)]
+async fn anonymize_user(
+    state: &AppState,
+    user_id: &UserId,
+) -> Result<(), AuthAPIError> {
+    let password =
+        Password::parse(Secret::new(uuid::Uuid::new_v4().to_string()))?;
+    let hash = UserPasswordHash::from_password(password)
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .anonymize_user(user_id, &hash)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            UserStoreError::LegalHold => AuthAPIError::LegalHold,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })
+}
+
+#[tracing::instrument(name = "Deleting user and projects", skip_all)]
+async fn delete_user_and_projects(
+    state: &AppState,
+    user_id: &UserId,
+    email: &Email,
+) -> Result<(), AuthAPIError> {
     state
         .project_store
         .write()
         .await
-        .delete_projects(&user_id)
+        .delete_projects(user_id)
         .await
         .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
 
@@ -34,7 +144,7 @@ pub async fn delete_user(
         .project_store
         .write()
         .await
-        .get_project_list(&user_id)
+        .get_project_list(user_id)
         .await
         .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
 
@@ -42,7 +152,7 @@ pub async fn delete_user(
         let mut project_store = state.project_store.write().await;
         for (project_id, _project_name) in &user_projects {
             project_store
-                .delete_members(&user_id, &project_id)
+                .delete_members(user_id, &project_id)
                 .await
                 .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
         }
@@ -52,7 +162,7 @@ pub async fn delete_user(
         .project_store
         .write()
         .await
-        .delete_projects(&user_id)
+        .delete_projects(user_id)
         .await
         .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
 
@@ -60,35 +170,17 @@ pub async fn delete_user(
         .user_store
         .write()
         .await
-        .delete_user(&email)
+        .delete_user(email)
         .await
-        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+        .map_err(|e| match e {
+            UserStoreError::LegalHold => AuthAPIError::LegalHold,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
 
-    let cookie =
-        jar.get(JWT_COOKIE_NAME)
-            .ok_or(AuthAPIError::UnexpectedError(eyre!(
-                "No JWT cookie found during delete user"
-            )))?;
-
-    let token = Secret::new(cookie.value().to_string());
-
-    state
-        .banned_token_store
-        .write()
-        .await
-        .add_token(&token)
-        .await
-        .map_err(|e| AuthAPIError::UnexpectedError(e))?;
-
-    let jar = jar.remove(cookie::Cookie::from(JWT_COOKIE_NAME));
-
-    let message = format!("User deleted: {}", email.as_ref().expose_secret());
-    let response = Json(DeleteUserResponse { message: message });
-
-    Ok((StatusCode::OK, jar, response))
+    Ok(())
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct DeleteUserResponse {
     pub message: String,
 }
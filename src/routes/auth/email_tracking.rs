@@ -0,0 +1,51 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, UserStoreError},
+    utils::{auth::Claims, json::Json},
+};
+
+// Turn recording when notification emails are opened on or off. Emails sent
+// while it's off never carry the image that records it.
+#[tracing::instrument(name = "Update email tracking route handler", skip_all)]
+pub async fn update_email_tracking(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<EmailTrackingRequest>,
+) -> Result<(StatusCode, CookieJar, Json<EmailTrackingResponse>), AuthAPIError>
+{
+    let user_id = claims.id;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_email_open_tracking(&user_id, request.enabled)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(EmailTrackingResponse {
+        enabled: request.enabled,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Deserialize)]
+pub struct EmailTrackingRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct EmailTrackingResponse {
+    pub enabled: bool,
+}
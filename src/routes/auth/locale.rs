@@ -0,0 +1,47 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Locale, UserStoreError},
+    utils::{auth::Claims, json::Json},
+};
+
+// Set the locale dates and times are formatted in for the signed-in user.
+// A null locale goes back to following the Accept-Language header.
+#[tracing::instrument(name = "Update locale route handler", skip_all)]
+pub async fn update_locale(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<LocaleRequest>,
+) -> Result<(StatusCode, CookieJar, Json<LocaleResponse>), AuthAPIError> {
+    let user_id = claims.id;
+    let locale = request.locale.as_deref().map(Locale::parse).transpose()?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_locale(&user_id, locale)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((StatusCode::OK, jar, Json(LocaleResponse { locale })))
+}
+
+#[derive(Deserialize)]
+pub struct LocaleRequest {
+    pub locale: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct LocaleResponse {
+    pub locale: Option<Locale>,
+}
@@ -1,6 +1,7 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
@@ -10,13 +11,17 @@ use crate::{
         AuthAPIError, Email, LoginAttemptId, Password, TwoFACode, UserId,
         UserStoreError,
     },
-    utils::auth::generate_auth_cookie,
+    utils::{
+        auth::{start_session, SessionClient},
+        json::Json,
+    },
 };
 
 #[tracing::instrument(name = "Login", skip_all)]
 pub async fn login(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: SessionClient,
     Json(request): Json<LoginRequest>,
 ) -> Result<(StatusCode, CookieJar, Json<LoginResponse>), AuthAPIError> {
     let email = Email::parse(Secret::new(request.email))?;
@@ -41,7 +46,9 @@ pub async fn login(
 
     match user.requires_2fa {
         true => handle_2fa(&user.email, &state, jar).await,
-        false => handle_no_2fa(&user.email, &user.id, jar).await,
+        false => {
+            handle_no_2fa(&user.email, &user.id, client, &state, jar).await
+        }
     }
 }
 
@@ -52,7 +59,7 @@ pub struct LoginRequest {
 }
 
 #[tracing::instrument(name = "Handling 2FA login", skip_all)]
-async fn handle_2fa(
+pub(super) async fn handle_2fa(
     email: &Email,
     state: &AppState,
     jar: CookieJar,
@@ -95,13 +102,22 @@ async fn handle_2fa(
 }
 
 #[tracing::instrument(name = "Handling login without 2FA", skip_all)]
-async fn handle_no_2fa(
+pub(super) async fn handle_no_2fa(
     email: &Email,
     user_id: &UserId,
+    client: SessionClient,
+    state: &AppState,
     jar: CookieJar,
 ) -> Result<(StatusCode, CookieJar, Json<LoginResponse>), AuthAPIError> {
-    let auth_cookie = generate_auth_cookie(&email, &user_id)
-        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    let auth_cookie = start_session(
+        email,
+        user_id,
+        client,
+        &state.session_store,
+        &state.clock,
+    )
+    .await
+    .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
 
     let updated_jar = jar.add(auth_cookie);
 
@@ -119,7 +135,7 @@ pub enum LoginResponse {
     TwoFactorAuth(TwoFactorAuthResponse),
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Deserialize, Serialize, JsonSchema)]
 pub struct TwoFactorAuthResponse {
     pub message: String,
     #[serde(rename = "loginAttemptId")]
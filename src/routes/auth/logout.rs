@@ -4,7 +4,7 @@ use color_eyre::eyre::eyre;
 use secrecy::Secret;
 
 use crate::{
-    domain::AuthAPIError,
+    domain::{AuthAPIError, SessionStoreError},
     utils::{auth::validate_token, constants::JWT_COOKIE_NAME},
     AppState,
 };
@@ -21,8 +21,14 @@ pub async fn logout(
 
     let token = Secret::new(cookie.value().to_string());
 
-    match validate_token(&token, state.banned_token_store.clone()).await {
-        Ok(_) => (),
+    let claims = match validate_token(
+        &token,
+        state.banned_token_store.clone(),
+        &state.clock,
+    )
+    .await
+    {
+        Ok(claims) => claims,
         Err(_) => return (jar, Err(AuthAPIError::InvalidToken)),
     };
 
@@ -30,7 +36,7 @@ pub async fn logout(
         .banned_token_store
         .write()
         .await
-        .add_token(&token)
+        .add_token(&token, claims.accepted_until(), state.clock.now())
         .await
     {
         Ok(()) => (),
@@ -39,6 +45,20 @@ pub async fn logout(
         }
     }
 
+    // It no longer needs listing, its token being banned
+    match state
+        .session_store
+        .write()
+        .await
+        .remove_session(&claims.id, &claims.sid)
+        .await
+    {
+        Ok(()) | Err(SessionStoreError::SessionNotFound) => (),
+        Err(err) => {
+            return (jar, Err(AuthAPIError::UnexpectedError(eyre!(err))))
+        }
+    }
+
     let jar = jar.remove(cookie::Cookie::from(JWT_COOKIE_NAME));
 
     (jar, Ok(StatusCode::OK))
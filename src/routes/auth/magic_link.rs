@@ -0,0 +1,124 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    login::{handle_2fa, handle_no_2fa},
+    LoginResponse,
+};
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Email, UserStoreError},
+    utils::{
+        auth::{
+            generate_magic_link_token, use_magic_link_token, SessionClient,
+        },
+        constants::APP_SERVICE_EXTERNAL_ADDRESS,
+        json::Json,
+        query::ValidatedQuery,
+    },
+};
+
+// Email a link that signs the user in without their password. As with
+// password resets, the response is the same whether or not the address has
+// an account.
+#[tracing::instrument(name = "Request magic link route handler", skip_all)]
+pub async fn request_magic_link(
+    State(state): State<AppState>,
+    Json(request): Json<MagicLinkRequest>,
+) -> Result<(StatusCode, Json<MagicLinkResponse>), AuthAPIError> {
+    let email = Email::parse(Secret::new(request.email))?;
+    let response = Json(MagicLinkResponse {
+        message: String::from(
+            "If the address has an account, a link to sign in has been sent \
+             to it",
+        ),
+    });
+
+    let user = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => {
+            return Ok((StatusCode::ACCEPTED, response))
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    };
+
+    let token = generate_magic_link_token(&user.email, &user.id, &state.clock)
+        .map_err(AuthAPIError::UnexpectedError)?;
+    state
+        .email_client
+        .send_email(
+            &email,
+            "Your sign-in link",
+            &format!(
+                "Sign in with this link, which works once, for 10 \
+                 minutes:\n\n{}\n\nIf you didn't ask for it, you can ignore \
+                 this email.",
+                magic_link(&token)
+            ),
+        )
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    Ok((StatusCode::ACCEPTED, response))
+}
+
+// Where a magic link goes. Signs the user in as logging in with their
+// password would, including asking for a 2FA code if they use one.
+#[tracing::instrument(name = "Verify magic link route handler", skip_all)]
+pub async fn verify_magic_link(
+    State(state): State<AppState>,
+    jar: CookieJar,
+    client: SessionClient,
+    query_params: ValidatedQuery<MagicLinkQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<LoginResponse>), AuthAPIError> {
+    let token = Secret::new(query_params.token.clone());
+    let claims =
+        use_magic_link_token(&token, &state.banned_token_store, &state.clock)
+            .await?;
+
+    // The account may have changed address, or gone, since the link was
+    // sent
+    let email = Email::parse(Secret::new(claims.sub))
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+    let user = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) if user.id == claims.id => user,
+        Ok(_) | Err(UserStoreError::UserNotFound) => {
+            return Err(AuthAPIError::InvalidToken)
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    };
+
+    match user.requires_2fa {
+        true => handle_2fa(&user.email, &state, jar).await,
+        false => {
+            handle_no_2fa(&user.email, &user.id, client, &state, jar).await
+        }
+    }
+}
+
+fn magic_link(token: &Secret<String>) -> String {
+    format!(
+        "{}/auth/magic-link/verify?token={}",
+        *APP_SERVICE_EXTERNAL_ADDRESS,
+        token.expose_secret()
+    )
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkQueryParams {
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct MagicLinkResponse {
+    pub message: String,
+}
@@ -1,13 +1,37 @@
+mod accept_policy;
+mod change_email;
 mod delete_user;
+mod email_tracking;
+mod locale;
 mod login;
 mod logout;
+mod magic_link;
+mod password_reset;
+mod profile;
+mod quota;
+mod requires_2fa;
+mod security_emails;
+mod sessions;
+mod setup_2fa;
 mod signup;
 mod verify_2fa;
 mod verify_token;
 
+pub use accept_policy::*;
+pub use change_email::*;
 pub use delete_user::*;
+pub use email_tracking::*;
+pub use locale::*;
 pub use login::*;
 pub use logout::*;
+pub use magic_link::*;
+pub use password_reset::*;
+pub use profile::*;
+pub use quota::*;
+pub use requires_2fa::*;
+pub use security_emails::*;
+pub use sessions::*;
+pub use setup_2fa::*;
 pub use signup::*;
 pub use verify_2fa::*;
 pub use verify_token::*;
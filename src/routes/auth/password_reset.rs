@@ -0,0 +1,155 @@
+use axum::{extract::State, http::StatusCode};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, Email, Password, PasswordResetToken,
+        PasswordResetTokenStoreError, UserPasswordHash, UserStoreError,
+    },
+    utils::{
+        auth::{
+            end_sessions, generate_password_reset_token,
+            validate_password_reset,
+        },
+        constants::APP_SERVICE_EXTERNAL_ADDRESS,
+        json::Json,
+        pwned_passwords::reject_pwned_password,
+    },
+};
+
+// Email a link for setting a new password. The response is the same
+// whether or not the address has an account, so it can't be used to find
+// out who has one.
+#[tracing::instrument(name = "Forgot password route handler", skip_all)]
+pub async fn forgot_password(
+    State(state): State<AppState>,
+    Json(request): Json<ForgotPasswordRequest>,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), AuthAPIError> {
+    let email = Email::parse(Secret::new(request.email))?;
+    let response = Json(PasswordResetResponse {
+        message: String::from(
+            "If the address has an account, a link to reset its password \
+             has been sent to it",
+        ),
+    });
+
+    let user = match state.user_store.read().await.get_user(&email).await {
+        Ok(user) => user,
+        Err(UserStoreError::UserNotFound) => {
+            return Ok((StatusCode::ACCEPTED, response))
+        }
+        Err(e) => return Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    };
+
+    let (token, reset) = generate_password_reset_token(&user.id, &state.clock)
+        .map_err(AuthAPIError::UnexpectedError)?;
+    state
+        .password_reset_token_store
+        .write()
+        .await
+        .add_token(token.clone(), reset)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    state
+        .email_client
+        .send_email(
+            &email,
+            "Reset your password",
+            &format!(
+                "Someone asked to reset your account's password. If this was \
+                 you, choose a new password at {}. The link works once, for \
+                 an hour. If it wasn't you, you can ignore this email.",
+                reset_link(&token)
+            ),
+        )
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    Ok((StatusCode::ACCEPTED, response))
+}
+
+// Set a new password with a token from a reset email
+#[tracing::instrument(name = "Reset password route handler", skip_all)]
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(request): Json<ResetPasswordRequest>,
+) -> Result<(StatusCode, Json<PasswordResetResponse>), AuthAPIError> {
+    let token = PasswordResetToken::parse(Secret::new(request.token))?;
+    // Checked before the token is used up, so a rejected password can be
+    // tried again with the same link
+    let password = Password::parse(request.new_password)?;
+    reject_pwned_password(&password).await?;
+
+    let reset = state
+        .password_reset_token_store
+        .write()
+        .await
+        .take_token(&token)
+        .await
+        .map_err(|e| match e {
+            PasswordResetTokenStoreError::TokenNotFound => {
+                AuthAPIError::InvalidToken
+            }
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    validate_password_reset(&reset, &state.clock)?;
+
+    let hash = UserPasswordHash::from_password(password)
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+    state
+        .user_store
+        .write()
+        .await
+        .update_password(&reset.user_id, &hash)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    // Whoever knew the old password may still be signed in with it
+    end_sessions(
+        &reset.user_id,
+        &state.session_store,
+        &state.banned_token_store,
+        &state.clock,
+    )
+    .await
+    .map_err(AuthAPIError::UnexpectedError)?;
+
+    let response = Json(PasswordResetResponse {
+        message: String::from("Password changed"),
+    });
+
+    Ok((StatusCode::OK, response))
+}
+
+fn reset_link(token: &PasswordResetToken) -> String {
+    format!(
+        "{}/reset-password?token={}",
+        *APP_SERVICE_EXTERNAL_ADDRESS,
+        token.as_ref().expose_secret()
+    )
+}
+
+#[derive(Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    #[serde(rename = "newPassword")]
+    pub new_password: Secret<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct PasswordResetResponse {
+    pub message: String,
+}
@@ -0,0 +1,66 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{time::parse_timezone, AuthAPIError, DisplayName, UserStoreError},
+    utils::{auth::Claims, json::Json},
+};
+
+// Set what the signed-in user is called in rotas and emails, and the
+// timezone times are shown to them in. A null field clears it.
+#[tracing::instrument(name = "Update profile route handler", skip_all)]
+pub async fn update_profile(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ProfileRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ProfileResponse>), AuthAPIError> {
+    let user_id = claims.id;
+    let display_name = request
+        .display_name
+        .as_deref()
+        .map(DisplayName::parse)
+        .transpose()?;
+    let timezone = request
+        .timezone
+        .as_deref()
+        .map(parse_timezone)
+        .transpose()?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_profile(&user_id, display_name.as_ref(), timezone)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(ProfileResponse {
+        display_name,
+        timezone: timezone.map(|timezone| timezone.name().to_owned()),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Deserialize)]
+pub struct ProfileRequest {
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+    // An IANA timezone name, such as "Europe/London"
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct ProfileResponse {
+    #[serde(rename = "displayName")]
+    pub display_name: Option<DisplayName>,
+    pub timezone: Option<String>,
+}
@@ -0,0 +1,65 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Quota, QuotaResource},
+    utils::{auth::Claims, json::Json},
+};
+
+// How much of each quota the signed-in user has used, so clients can warn
+// them before they run out
+#[tracing::instrument(name = "Get quota route handler", skip_all)]
+pub async fn get_quota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<(StatusCode, CookieJar, Json<QuotaResponse>), AuthAPIError> {
+    let user_id = claims.id;
+
+    let usage = state
+        .project_store
+        .write()
+        .await
+        .get_quota_usage(&user_id)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(QuotaResponse {
+        quotas: QuotaResource::ALL
+            .into_iter()
+            .map(|resource| QuotaEntry::from(usage.quota(resource)))
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuotaResponse {
+    pub quotas: Vec<QuotaEntry>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct QuotaEntry {
+    pub resource: QuotaResource,
+    pub limit: i64,
+    // Across all the user's projects. Can be more than the limit, since
+    // quotas aren't enforced yet.
+    pub used: i64,
+    pub remaining: i64,
+}
+
+impl From<Quota> for QuotaEntry {
+    fn from(quota: Quota) -> Self {
+        Self {
+            resource: quota.resource,
+            limit: quota.limit,
+            used: quota.used,
+            remaining: quota.remaining(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, BackupCode, BackupCodeHash, Email, Password,
+        UserStoreError,
+    },
+    utils::{auth::Claims, constants::JWT_SECRET, json::Json},
+};
+
+// Turn 2FA at login on or off after signing up. The password is asked for
+// again so a session left signed in can't be used to weaken the account.
+// Turning it on issues a new set of backup codes, as at signup.
+#[tracing::instrument(name = "Update requires 2FA route handler", skip_all)]
+pub async fn update_requires_2fa(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<Requires2FARequest>,
+) -> Result<(StatusCode, CookieJar, Json<Requires2FAResponse>), AuthAPIError> {
+    let email = Email::parse(Secret::new(claims.sub))
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    let password = Password::parse(request.password)?;
+
+    let backup_codes = match request.requires_2fa {
+        true => BackupCode::generate_set(),
+        false => Vec::new(),
+    };
+    let backup_code_hashes = backup_codes
+        .iter()
+        .map(|code| BackupCodeHash::from_code(code, &JWT_SECRET))
+        .collect::<Vec<_>>();
+    {
+        let mut user_store = state.user_store.write().await;
+        user_store.validate_user(&email, &password).await.map_err(
+            |e| match e {
+                UserStoreError::InvalidCredentials
+                | UserStoreError::UserNotFound => {
+                    AuthAPIError::IncorrectCredentials
+                }
+                e => AuthAPIError::UnexpectedError(eyre!(e)),
+            },
+        )?;
+
+        let map_store_error = |e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        };
+        user_store
+            .set_requires_2fa(&claims.id, request.requires_2fa)
+            .await
+            .map_err(map_store_error)?;
+        if !backup_codes.is_empty() {
+            user_store
+                .set_backup_codes(&claims.id, &backup_code_hashes)
+                .await
+                .map_err(map_store_error)?;
+        }
+    }
+
+    let response = Json(Requires2FAResponse {
+        requires_2fa: request.requires_2fa,
+        backup_codes: backup_codes
+            .iter()
+            .map(|code| code.as_ref().expose_secret().to_owned())
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Deserialize)]
+pub struct Requires2FARequest {
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+    pub password: Secret<String>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct Requires2FAResponse {
+    #[serde(rename = "requires2FA")]
+    pub requires_2fa: bool,
+    // Replacing any the user had when 2FA is turned on, and empty when it's
+    // turned off. Only shown here, as just their hashes are kept.
+    #[serde(rename = "backupCodes")]
+    pub backup_codes: Vec<String>,
+}
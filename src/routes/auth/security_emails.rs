@@ -0,0 +1,51 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, UserStoreError},
+    utils::{auth::Claims, json::Json},
+};
+
+// Turn security alert emails on or off. Critical alerts, such as the
+// account's email address changing, are sent either way.
+#[tracing::instrument(name = "Update security emails route handler", skip_all)]
+pub async fn update_security_emails(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<SecurityEmailsRequest>,
+) -> Result<(StatusCode, CookieJar, Json<SecurityEmailsResponse>), AuthAPIError>
+{
+    let user_id = claims.id;
+
+    state
+        .user_store
+        .write()
+        .await
+        .set_security_emails(&user_id, request.enabled)
+        .await
+        .map_err(|e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(SecurityEmailsResponse {
+        enabled: request.enabled,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Deserialize)]
+pub struct SecurityEmailsRequest {
+    pub enabled: bool,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct SecurityEmailsResponse {
+    pub enabled: bool,
+}
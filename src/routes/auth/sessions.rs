@@ -0,0 +1,112 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{AuthAPIError, Session, SessionId, SessionStoreError},
+    utils::{
+        auth::{session_accepted_until, Claims},
+        json::Json,
+    },
+};
+
+// The signed-in user's active sessions, newest first
+#[tracing::instrument(name = "Get sessions route handler", skip_all)]
+pub async fn get_sessions(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<(StatusCode, CookieJar, Json<SessionListResponse>), AuthAPIError> {
+    let sessions = state
+        .session_store
+        .read()
+        .await
+        .get_sessions(&claims.id, state.clock.now())
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(SessionListResponse {
+        sessions: sessions
+            .into_iter()
+            .map(|session| SessionResponse::new(session, &claims.sid))
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+// Sign out a session, such as one on a lost device. Its tokens stop being
+// accepted straight away.
+#[tracing::instrument(name = "Delete session route handler", skip_all)]
+pub async fn delete_session(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Path(session_id): Path<SessionId>,
+) -> Result<(StatusCode, CookieJar), AuthAPIError> {
+    let user_id = claims.id;
+
+    state
+        .session_store
+        .write()
+        .await
+        .remove_session(&user_id, &session_id)
+        .await
+        .map_err(|e| match e {
+            SessionStoreError::SessionNotFound => AuthAPIError::SessionNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    state
+        .banned_token_store
+        .write()
+        .await
+        .ban_session(
+            &session_id,
+            session_accepted_until(state.clock.now()),
+            state.clock.now(),
+        )
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((StatusCode::NO_CONTENT, jar))
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct SessionListResponse {
+    pub sessions: Vec<SessionResponse>,
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct SessionResponse {
+    #[serde(rename = "sessionId")]
+    pub session_id: SessionId,
+    // From the User-Agent header at sign-in
+    pub device: Option<String>,
+    pub ip: Option<String>,
+    #[serde(rename = "issuedAt")]
+    pub issued_at: String,
+    #[serde(rename = "expiresAt")]
+    pub expires_at: String,
+    // Whether this is the session making the request
+    pub current: bool,
+}
+
+impl SessionResponse {
+    fn new(session: Session, current: &SessionId) -> Self {
+        Self {
+            current: session.session_id == *current,
+            session_id: session.session_id,
+            device: session.device,
+            ip: session.ip,
+            issued_at: session.issued_at.to_rfc3339(),
+            expires_at: session.expires_at.to_rfc3339(),
+        }
+    }
+}
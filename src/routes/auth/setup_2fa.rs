@@ -0,0 +1,74 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    app_state::AppState,
+    domain::{
+        AuthAPIError, BackupCode, BackupCodeHash, Email, TotpSecret,
+        UserStoreError,
+    },
+    utils::{auth::Claims, constants::JWT_SECRET, json::Json},
+};
+
+// Set up an authenticator app, whose codes can then be given at login in
+// place of the emailed code. Setting up again replaces the old app. A new
+// set of backup codes comes with it, for if the app is lost.
+#[tracing::instrument(name = "Set up 2FA route handler", skip_all)]
+pub async fn setup_2fa(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<(StatusCode, CookieJar, Json<TotpSetupResponse>), AuthAPIError> {
+    let email = Email::parse(Secret::new(claims.sub))
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let secret = TotpSecret::generate();
+    let backup_codes = BackupCode::generate_set();
+    let backup_code_hashes = backup_codes
+        .iter()
+        .map(|code| BackupCodeHash::from_code(code, &JWT_SECRET))
+        .collect::<Vec<_>>();
+    {
+        let map_store_error = |e| match e {
+            UserStoreError::UserNotFound => AuthAPIError::UserNotFound,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        };
+        let mut user_store = state.user_store.write().await;
+        user_store
+            .set_totp_secret(&claims.id, &secret)
+            .await
+            .map_err(map_store_error)?;
+        user_store
+            .set_backup_codes(&claims.id, &backup_code_hashes)
+            .await
+            .map_err(map_store_error)?;
+    }
+
+    let response = Json(TotpSetupResponse {
+        secret: secret.to_base32().expose_secret().to_owned(),
+        otpauth_uri: secret.otpauth_uri(&email).expose_secret().to_owned(),
+        backup_codes: backup_codes
+            .iter()
+            .map(|code| code.as_ref().expose_secret().to_owned())
+            .collect(),
+    });
+
+    Ok((StatusCode::CREATED, jar, response))
+}
+
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
+pub struct TotpSetupResponse {
+    // For typing into the app by hand
+    pub secret: String,
+    // For showing as a QR code for the app to scan
+    #[serde(rename = "otpauthUri")]
+    pub otpauth_uri: String,
+    // Replacing any the user had. Only shown here, as just their hashes are
+    // kept.
+    #[serde(rename = "backupCodes")]
+    pub backup_codes: Vec<String>,
+}
@@ -1,11 +1,17 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
-use secrecy::Secret;
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
+use schemars::JsonSchema;
+use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
     app_state::AppState,
     domain::{
-        AuthAPIError, Email, Password, User, UserPasswordHash, UserStoreError,
+        parse_policy_version, AuthAPIError, BackupCode, BackupCodeHash, Email,
+        InvitationCode, Password, User, UserPasswordHash, UserStoreError,
+    },
+    utils::{
+        constants::JWT_SECRET, json::Json,
+        pwned_passwords::reject_pwned_password,
     },
 };
 
@@ -19,25 +25,75 @@ pub async fn signup(
 
     let password = Password::parse(request.password)
         .map_err(|e| AuthAPIError::ValidationError(e))?;
+    reject_pwned_password(&password).await?;
+
+    // Any code given is ignored while signup is open
+    let invitation = match state.settings.invite_only_signup {
+        true => Some(
+            request
+                .invitation_code
+                .map(|code| InvitationCode::parse(Secret::new(code)))
+                .transpose()?
+                .ok_or(AuthAPIError::InvitationRequired)?,
+        ),
+        false => None,
+    };
+
+    let accepted_policy = request
+        .accepted_policy_version
+        .map(parse_policy_version)
+        .transpose()?;
+    if let Some(required) = state.settings.required_policy_version {
+        if accepted_policy.is_none_or(|policy| policy.version < required) {
+            return Err(AuthAPIError::PolicyNotAccepted(required));
+        }
+    }
 
     let hash = UserPasswordHash::from_password(password)
         .await
         .map_err(|e| AuthAPIError::UnexpectedError(e))?;
 
-    let user = User::new(email, hash, request.requires_2fa);
+    let mut user = User::new(email, hash, request.requires_2fa);
+    user.accepted_policy_version = accepted_policy.map(|policy| policy.version);
+    let user_id = user.id.clone();
+    let backup_codes = match request.requires_2fa {
+        true => BackupCode::generate_set(),
+        false => Vec::new(),
+    };
+    let backup_code_hashes = backup_codes
+        .iter()
+        .map(|code| BackupCodeHash::from_code(code, &JWT_SECRET))
+        .collect::<Vec<_>>();
 
     {
         let mut user_store = state.user_store.write().await;
-        user_store.add_user(user).await.map_err(|e| match e {
-            UserStoreError::UserAlreadyExists => {
-                AuthAPIError::UserAlreadyExists
-            }
-            err => AuthAPIError::UnexpectedError(err.into()),
-        })?;
+        user_store
+            .add_user(user, invitation.as_ref())
+            .await
+            .map_err(|e| match e {
+                UserStoreError::UserAlreadyExists => {
+                    AuthAPIError::UserAlreadyExists
+                }
+                UserStoreError::InvalidInvitation => {
+                    AuthAPIError::InvitationRequired
+                }
+                err => AuthAPIError::UnexpectedError(err.into()),
+            })?;
+
+        if !backup_codes.is_empty() {
+            user_store
+                .set_backup_codes(&user_id, &backup_code_hashes)
+                .await
+                .map_err(|e| AuthAPIError::UnexpectedError(e.into()))?;
+        }
     }
 
     let response = Json(SignupResponse {
         message: "User created successfully".to_string(),
+        backup_codes: backup_codes
+            .iter()
+            .map(|code| code.as_ref().expose_secret().to_owned())
+            .collect(),
     });
 
     Ok((StatusCode::CREATED, response))
@@ -49,9 +105,17 @@ pub struct SignupRequest {
     pub password: Secret<String>,
     #[serde(rename = "requires2FA")]
     pub requires_2fa: bool,
+    #[serde(rename = "invitationCode")]
+    pub invitation_code: Option<String>,
+    // The version of the terms of service and privacy policy agreed to
+    #[serde(rename = "acceptedPolicyVersion")]
+    pub accepted_policy_version: Option<i32>,
 }
 
-#[derive(Debug, Deserialize, PartialEq, Serialize)]
+#[derive(Debug, Deserialize, PartialEq, Serialize, JsonSchema)]
 pub struct SignupResponse {
     pub message: String,
+    // Only shown here, as just their hashes are kept. Empty without 2FA.
+    #[serde(rename = "backupCodes")]
+    pub backup_codes: Vec<String>,
 }
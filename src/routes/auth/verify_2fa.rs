@@ -1,4 +1,4 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
 use secrecy::Secret;
@@ -6,8 +6,15 @@ use serde::Deserialize;
 
 use crate::{
     app_state::AppState,
-    domain::{Email, LoginAttemptId, TwoFACode},
-    utils::auth::generate_auth_cookie,
+    domain::{
+        BackupCode, BackupCodeHash, Email, LoginAttemptId, TwoFACode,
+        UserStoreError,
+    },
+    utils::{
+        auth::{start_session, SessionClient},
+        constants::JWT_SECRET,
+        json::Json,
+    },
     AuthAPIError,
 };
 
@@ -15,6 +22,7 @@ use crate::{
 pub async fn verify_2fa(
     State(state): State<AppState>,
     jar: CookieJar,
+    client: SessionClient,
     Json(request): Json<Verify2FARequest>,
 ) -> (CookieJar, Result<impl IntoResponse, AuthAPIError>) {
     let email = match Email::parse(Secret::new(request.email)) {
@@ -28,10 +36,16 @@ pub async fn verify_2fa(
             Err(e) => return (jar, Err(AuthAPIError::ValidationError(e))),
         };
 
-    let two_fa_code = match TwoFACode::parse(Secret::new(request.two_fa_code)) {
-        Ok(two_fa_code) => two_fa_code,
-        Err(e) => return (jar, Err(AuthAPIError::ValidationError(e))),
-    };
+    // A backup code can be given in place of the emailed code too
+    let second_factor =
+        match TwoFACode::parse(Secret::new(request.two_fa_code.clone())) {
+            Ok(two_fa_code) => SecondFactor::Code(two_fa_code),
+            Err(e) => match BackupCode::parse(Secret::new(request.two_fa_code))
+            {
+                Ok(backup_code) => SecondFactor::BackupCode(backup_code),
+                Err(_) => return (jar, Err(AuthAPIError::ValidationError(e))),
+            },
+        };
 
     let (expected_login_attempt_id, expected_two_fa_code) =
         match state.two_fa_code_store.read().await.get_code(&email).await {
@@ -39,9 +53,7 @@ pub async fn verify_2fa(
             Err(_) => return (jar, Err(AuthAPIError::IncorrectCredentials)),
         };
 
-    if login_attempt_id != expected_login_attempt_id
-        || two_fa_code != expected_two_fa_code
-    {
+    if login_attempt_id != expected_login_attempt_id {
         return (jar, Err(AuthAPIError::IncorrectCredentials));
     }
 
@@ -50,7 +62,56 @@ pub async fn verify_2fa(
         Err(_) => return (jar, Err(AuthAPIError::IncorrectCredentials)),
     };
 
-    let auth_cookie = match generate_auth_cookie(&email, &user_id) {
+    match second_factor {
+        SecondFactor::Code(two_fa_code)
+            if two_fa_code == expected_two_fa_code => {}
+        // Otherwise it may be from the user's authenticator app
+        SecondFactor::Code(two_fa_code) => {
+            match state
+                .user_store
+                .write()
+                .await
+                .use_totp_code(&user_id, &two_fa_code, state.clock.now())
+                .await
+            {
+                Ok(()) => (),
+                Err(UserStoreError::InvalidCredentials) => {
+                    return (jar, Err(AuthAPIError::IncorrectCredentials))
+                }
+                Err(e) => {
+                    return (jar, Err(AuthAPIError::UnexpectedError(eyre!(e))))
+                }
+            }
+        }
+        SecondFactor::BackupCode(backup_code) => {
+            let hash = BackupCodeHash::from_code(&backup_code, &JWT_SECRET);
+            match state
+                .user_store
+                .write()
+                .await
+                .use_backup_code(&user_id, &hash)
+                .await
+            {
+                Ok(()) => (),
+                Err(UserStoreError::InvalidCredentials) => {
+                    return (jar, Err(AuthAPIError::IncorrectCredentials))
+                }
+                Err(e) => {
+                    return (jar, Err(AuthAPIError::UnexpectedError(eyre!(e))))
+                }
+            }
+        }
+    }
+
+    let auth_cookie = match start_session(
+        &email,
+        &user_id,
+        client,
+        &state.session_store,
+        &state.clock,
+    )
+    .await
+    {
         Ok(cookie) => cookie,
         Err(err) => {
             return (jar, Err(AuthAPIError::UnexpectedError(eyre!(err))))
@@ -74,11 +135,18 @@ pub async fn verify_2fa(
     (updated_jar, Ok(StatusCode::OK.into_response()))
 }
 
+enum SecondFactor {
+    Code(TwoFACode),
+    BackupCode(BackupCode),
+}
+
 #[derive(Debug, Deserialize)]
 pub struct Verify2FARequest {
     email: String,
     #[serde(rename = "loginAttemptId")]
     login_attempt_id: String,
+    // The emailed code, a code from the user's authenticator app or one of
+    // their backup codes
     #[serde(rename = "2FACode")]
     two_fa_code: String,
 }
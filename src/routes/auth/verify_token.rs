@@ -1,8 +1,12 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, Json};
+use axum::{extract::State, http::StatusCode, response::IntoResponse};
 use secrecy::Secret;
 use serde::Deserialize;
 
-use crate::{app_state::AppState, utils::auth::validate_token, AuthAPIError};
+use crate::{
+    app_state::AppState,
+    utils::{auth::validate_token, json::Json},
+    AuthAPIError,
+};
 
 #[tracing::instrument(name = "Verify token route handler", skip_all)]
 pub async fn verify_token(
@@ -11,7 +15,8 @@ pub async fn verify_token(
 ) -> Result<impl IntoResponse, AuthAPIError> {
     let token = Secret::new(request.token);
     let _claims =
-        validate_token(&token, state.banned_token_store.clone()).await?;
+        validate_token(&token, state.banned_token_store.clone(), &state.clock)
+            .await?;
 
     Ok(StatusCode::OK.into_response())
 }
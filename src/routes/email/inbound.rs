@@ -0,0 +1,75 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use super::webhook::verify_postmark_token;
+use crate::{
+    domain::{AuthAPIError, EmailReply, ShiftReminderStoreError},
+    utils::{
+        constants::JWT_SECRET, email_replies::verify_reply_token, json::Json,
+    },
+    AppState,
+};
+
+// Replies to reminder emails, forwarded by Postmark's inbound webhook.
+// Members answer with ACCEPT to confirm the shift or DECLINE to ask the
+// project owner to find cover. The reply-to address names the reminder and
+// is signed, so the sender's address isn't trusted. Replies that can't be
+// acted on are accepted and ignored, so Postmark doesn't retry them.
+#[tracing::instrument(name = "Receive inbound email route handler", skip_all)]
+pub async fn receive_inbound_email(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<PostmarkInboundRequest>,
+) -> Result<StatusCode, AuthAPIError> {
+    verify_postmark_token(&state, &headers)?;
+
+    let Some(reminder_id) =
+        verify_reply_token(&JWT_SECRET, &request.mailbox_hash)
+    else {
+        tracing::debug!("Ignoring inbound email without a valid reply token");
+        return Ok(StatusCode::OK);
+    };
+    // Postmark strips the quoted reminder from replies it recognises
+    let body = match request.stripped_text_reply.trim().is_empty() {
+        true => &request.text_body,
+        false => &request.stripped_text_reply,
+    };
+    let Some(reply) = EmailReply::parse(body) else {
+        tracing::debug!("Ignoring inbound email without a command");
+        return Ok(StatusCode::OK);
+    };
+
+    let result = state
+        .shift_reminder_store
+        .write()
+        .await
+        .record_email_reply(
+            reminder_id,
+            reply.into(),
+            state.clock.now().date_naive(),
+        )
+        .await;
+    match result {
+        Ok(_) | Err(ShiftReminderStoreError::ReminderNotFound) => {
+            Ok(StatusCode::OK)
+        }
+        Err(e) => Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    }
+}
+
+// Just the parts of Postmark's inbound email we use
+#[derive(Deserialize)]
+pub struct PostmarkInboundRequest {
+    // What came after the plus sign in the address replied to
+    #[serde(rename = "MailboxHash", default)]
+    pub mailbox_hash: String,
+    #[serde(rename = "TextBody", default)]
+    pub text_body: String,
+    // The reply without the quoted email it answers
+    #[serde(rename = "StrippedTextReply", default)]
+    pub stripped_text_reply: String,
+}
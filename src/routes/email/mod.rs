@@ -0,0 +1,7 @@
+mod inbound;
+mod open;
+mod webhook;
+
+pub use inbound::*;
+pub use open::*;
+pub use webhook::*;
@@ -0,0 +1,60 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use crate::{
+    domain::AuthAPIError,
+    utils::{
+        constants::JWT_SECRET, email_tracking::verify_open_token,
+        query::ValidatedQuery,
+    },
+    AppState,
+};
+
+// A transparent 1x1 GIF
+const PIXEL: [u8; 43] = [
+    0x47, 0x49, 0x46, 0x38, 0x39, 0x61, 0x01, 0x00, 0x01, 0x00, 0x80, 0x00,
+    0x00, 0x00, 0x00, 0x00, 0xff, 0xff, 0xff, 0x21, 0xf9, 0x04, 0x01, 0x00,
+    0x00, 0x00, 0x00, 0x2c, 0x00, 0x00, 0x00, 0x00, 0x01, 0x00, 0x01, 0x00,
+    0x00, 0x02, 0x02, 0x44, 0x01, 0x00, 0x3b,
+];
+
+#[derive(Deserialize)]
+pub struct OpenEmailQueryParams {
+    pub token: String,
+}
+
+// The invisible image in tracked notification emails, loaded by the
+// recipient's mail client when the email is opened. The image comes back
+// whether or not the token is valid, so it can't be used to check tokens.
+#[tracing::instrument(name = "Open email route handler", skip_all)]
+pub async fn open_email(
+    State(state): State<AppState>,
+    query_params: ValidatedQuery<OpenEmailQueryParams>,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    if let Some(delivery_id) =
+        verify_open_token(&JWT_SECRET, &query_params.token)
+    {
+        state
+            .user_store
+            .write()
+            .await
+            .record_email_opened(delivery_id)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    }
+
+    Ok((
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "image/gif"),
+            // Opened again later, the image is fetched again
+            (header::CACHE_CONTROL, "no-store"),
+        ],
+        PIXEL,
+    ))
+}
@@ -0,0 +1,83 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use color_eyre::eyre::eyre;
+use secrecy::{ExposeSecret, Secret};
+use serde::Deserialize;
+
+use crate::{
+    domain::{AuthAPIError, Email, UndeliverableReason},
+    utils::{
+        admin::constant_time_eq, constants::POSTMARK_WEBHOOK_TOKEN_HEADER,
+        json::Json,
+    },
+    AppState,
+};
+
+// Bounce and spam complaint webhooks from Postmark. An address that bounces
+// for good, or whose owner complains, is marked undeliverable and queued
+// email to it is suppressed until it's confirmed again. Other records are
+// accepted and ignored, so Postmark doesn't retry them.
+#[tracing::instrument(name = "Email webhook route handler", skip_all)]
+pub async fn receive_email_webhook(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Json(request): Json<PostmarkWebhookRequest>,
+) -> Result<StatusCode, AuthAPIError> {
+    verify_postmark_token(&state, &headers)?;
+
+    let reason = UndeliverableReason::from_postmark(
+        &request.record_type,
+        request.bounce_type.as_deref(),
+    );
+    let (Some(reason), Some(email)) = (reason, request.email) else {
+        return Ok(StatusCode::OK);
+    };
+    let email = Email::parse(Secret::new(email))?;
+
+    state
+        .user_store
+        .write()
+        .await
+        .mark_email_undeliverable(&email, reason)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok(StatusCode::OK)
+}
+
+// Postmark sends the token we gave it in a header with each webhook
+pub(super) fn verify_postmark_token(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Result<(), AuthAPIError> {
+    let expected = state
+        .settings
+        .postmark_webhook_token
+        .as_ref()
+        .ok_or(AuthAPIError::IncorrectCredentials)?;
+    let provided = headers
+        .get(POSTMARK_WEBHOOK_TOKEN_HEADER)
+        .ok_or(AuthAPIError::MissingCredentials)?
+        .to_str()
+        .map_err(|_| AuthAPIError::IncorrectCredentials)?;
+    match constant_time_eq(
+        provided.as_bytes(),
+        expected.expose_secret().as_bytes(),
+    ) {
+        true => Ok(()),
+        false => Err(AuthAPIError::IncorrectCredentials),
+    }
+}
+
+// Just the parts of Postmark's bounce and spam complaint records we use
+#[derive(Deserialize)]
+pub struct PostmarkWebhookRequest {
+    #[serde(rename = "RecordType")]
+    pub record_type: String,
+    #[serde(rename = "Type")]
+    pub bounce_type: Option<String>,
+    #[serde(rename = "Email")]
+    pub email: Option<String>,
+}
@@ -0,0 +1,165 @@
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        IntegrationKey, IntegrationKeyId, IntegrationKeyName,
+        IntegrationKeySecret, IntegrationStoreError, ProjectAPIError,
+        ValidationError, MAX_INTEGRATION_KEYS,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Create a key for a no-code tool to poll the signed-in user's updates
+// with. The key is only ever shown in this response.
+#[tracing::instrument(name = "Create integration key route handler", skip_all)]
+pub async fn create_integration_key(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<CreateIntegrationKeyRequest>,
+) -> Result<
+    (StatusCode, CookieJar, Json<CreatedIntegrationKeyResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let name = IntegrationKeyName::parse(&request.name)?;
+
+    let mut integration_store = state.integration_store.write().await;
+    let keys = integration_store
+        .get_integration_keys(&user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    if keys.len() >= MAX_INTEGRATION_KEYS {
+        return Err(ValidationError::new(format!(
+            "Accounts can have at most {} integration keys",
+            MAX_INTEGRATION_KEYS
+        ))
+        .into());
+    }
+
+    let secret = IntegrationKeySecret::generate();
+    let key = IntegrationKey::new(user_id, name, state.clock.now());
+    integration_store
+        .add_integration_key(&key, &secret.hash())
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    drop(integration_store);
+
+    let response = Json(CreatedIntegrationKeyResponse {
+        key_id: key.key_id,
+        name: key.name,
+        key: secret.as_ref().expose_secret().to_owned(),
+        created_at: key.created_at.to_rfc3339(),
+    });
+
+    Ok((StatusCode::CREATED, jar, response))
+}
+
+// The signed-in user's integration keys, oldest first
+#[tracing::instrument(name = "Get integration keys route handler", skip_all)]
+pub async fn get_integration_keys(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<
+    (StatusCode, CookieJar, Json<IntegrationKeyListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+
+    let keys = state
+        .integration_store
+        .read()
+        .await
+        .get_integration_keys(&user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(IntegrationKeyListResponse {
+        keys: keys.into_iter().map(IntegrationKeyResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+// Revoke a key, such as one for a tool that's no longer used. Polling with
+// it fails straight away.
+#[tracing::instrument(name = "Delete integration key route handler", skip_all)]
+pub async fn delete_integration_key(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Path(key_id): Path<IntegrationKeyId>,
+) -> Result<(StatusCode, CookieJar), ProjectAPIError> {
+    let user_id = claims.id;
+
+    state
+        .integration_store
+        .write()
+        .await
+        .delete_integration_key(&user_id, &key_id)
+        .await
+        .map_err(|e| match e {
+            IntegrationStoreError::KeyNotFound => {
+                ProjectAPIError::IntegrationKeyNotFound(key_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((StatusCode::NO_CONTENT, jar))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct CreateIntegrationKeyRequest {
+    // To tell keys apart, e.g. "Zapier"
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CreatedIntegrationKeyResponse {
+    #[serde(rename = "keyId")]
+    pub key_id: IntegrationKeyId,
+    pub name: IntegrationKeyName,
+    // Sent in the X-Api-Key header to poll for updates. Only its hash is
+    // kept, so it can't be shown again.
+    pub key: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationKeyListResponse {
+    pub keys: Vec<IntegrationKeyResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationKeyResponse {
+    #[serde(rename = "keyId")]
+    pub key_id: IntegrationKeyId,
+    pub name: IntegrationKeyName,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    // Null until a tool has polled with it
+    #[serde(rename = "lastUsedAt")]
+    pub last_used_at: Option<String>,
+}
+
+impl From<IntegrationKey> for IntegrationKeyResponse {
+    fn from(key: IntegrationKey) -> Self {
+        Self {
+            key_id: key.key_id,
+            name: key.name,
+            created_at: key.created_at.to_rfc3339(),
+            last_used_at: key.last_used_at.map(|used_at| used_at.to_rfc3339()),
+        }
+    }
+}
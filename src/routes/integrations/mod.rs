@@ -0,0 +1,5 @@
+mod keys;
+mod updates;
+
+pub use keys::*;
+pub use updates::*;
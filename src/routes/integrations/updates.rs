@@ -0,0 +1,153 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+};
+use chrono::TimeDelta;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::{
+    domain::{
+        AuthAPIError, IntegrationKeySecret, IntegrationStoreError,
+        IntegrationUpdate, ProjectAPIError, ProjectId, ValidationError,
+    },
+    utils::{
+        constants::{INTEGRATION_KEY_HEADER, INTEGRATION_UPDATE_SETTLE_TIME},
+        json::Json,
+        query::ValidatedQuery,
+    },
+    AppState,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct IntegrationUpdatesQueryParams {
+    // The cursor from the last response. Without it, updates carry on from
+    // the last one given with the key.
+    pub cursor: Option<i64>,
+    pub limit: Option<i64>,
+}
+
+// For no-code tools, such as Zapier, that poll for new items rather than
+// take webhooks: the domain events of the key's user after the cursor,
+// oldest first, once they're a minute old. Authenticated by an integration
+// key rather than a session.
+#[tracing::instrument(name = "Get integration updates route handler", skip_all)]
+pub async fn get_integration_updates(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    query_params: ValidatedQuery<IntegrationUpdatesQueryParams>,
+) -> Result<(StatusCode, Json<IntegrationUpdatesResponse>), ProjectAPIError> {
+    let secret = headers
+        .get(INTEGRATION_KEY_HEADER)
+        .ok_or(AuthAPIError::MissingCredentials)?
+        .to_str()
+        .map_err(|_| AuthAPIError::IncorrectCredentials)?;
+    let secret = IntegrationKeySecret::parse(Secret::new(secret.to_owned()))
+        .map_err(|_| AuthAPIError::IncorrectCredentials)?;
+
+    let limit = query_params.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ValidationError::new(format!(
+            "Limit must be between 1 and {}",
+            MAX_LIMIT
+        ))
+        .into());
+    }
+    if query_params.cursor.is_some_and(|cursor| cursor < 0) {
+        return Err(ValidationError::new(String::from(
+            "Cursor cannot be negative",
+        ))
+        .into());
+    }
+
+    let mut integration_store = state.integration_store.write().await;
+    let key = integration_store
+        .authenticate_integration_key(&secret.hash(), state.clock.now())
+        .await
+        .map_err(|e| match e {
+            IntegrationStoreError::KeyNotFound => {
+                AuthAPIError::IncorrectCredentials.into()
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    let after = query_params.cursor.unwrap_or(key.last_event_id);
+
+    // Only updates old enough that none before them can still appear, so
+    // the cursor never skips one
+    let settled_before = state.clock.now()
+        - TimeDelta::from_std(INTEGRATION_UPDATE_SETTLE_TIME)
+            .expect("Settle time is out of range");
+    // Fetch one extra update to tell whether there are more
+    let mut updates = integration_store
+        .get_integration_updates(&key.user_id, after, settled_before, limit + 1)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    let has_more = updates.len() as i64 > limit;
+    updates.truncate(limit as usize);
+
+    let cursor = updates.last().map_or(after, |update| update.id);
+    if cursor > after {
+        integration_store
+            .save_integration_cursor(&key.key_id, cursor)
+            .await
+            .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    }
+    drop(integration_store);
+
+    let response = Json(IntegrationUpdatesResponse {
+        updates: updates
+            .into_iter()
+            .map(IntegrationUpdateResponse::from)
+            .collect(),
+        cursor,
+        has_more,
+    });
+
+    Ok((StatusCode::OK, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationUpdatesResponse {
+    pub updates: Vec<IntegrationUpdateResponse>,
+    // To poll for updates after these
+    pub cursor: i64,
+    // Whether there were more updates than the limit, so polling again
+    // straight away would return more
+    #[serde(rename = "hasMore")]
+    pub has_more: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct IntegrationUpdateResponse {
+    // Unique, and increasing with each update, so tools can deduplicate by
+    // it
+    pub id: i64,
+    // The domain event's name, e.g. "ShiftAdded"
+    #[serde(rename = "type")]
+    pub event_type: String,
+    // Null for updates that aren't about a project
+    #[serde(rename = "projectId")]
+    pub project_id: Option<ProjectId>,
+    #[serde(rename = "occurredAt")]
+    pub occurred_at: String,
+    // The event's own fields, which depend on its type
+    pub data: Value,
+}
+
+impl From<IntegrationUpdate> for IntegrationUpdateResponse {
+    fn from(update: IntegrationUpdate) -> Self {
+        Self {
+            id: update.id,
+            event_type: update.event_type,
+            project_id: update.project_id,
+            occurred_at: update.occurred_at.to_rfc3339(),
+            data: update.data,
+        }
+    }
+}
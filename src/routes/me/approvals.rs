@@ -0,0 +1,214 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        ProjectAPIError, ProjectStoreError, ReviewComment, RotaApprovalId,
+        ValidationError,
+    },
+    routes::projects::RotaApprovalResponse,
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+const MAX_BULK_REVIEWS: usize = 50;
+
+// Rotas waiting for the signed-in user's approval, in any project that
+// names them as its approver
+#[tracing::instrument(name = "Get pending approvals route handler", skip_all)]
+pub async fn get_pending_approvals(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<
+    (StatusCode, CookieJar, Json<PendingApprovalListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+
+    let approvals = state
+        .project_store
+        .write()
+        .await
+        .get_pending_approvals(&user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(PendingApprovalListResponse {
+        approvals: approvals
+            .into_iter()
+            .map(RotaApprovalResponse::from)
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+// Approve a rota waiting for the signed-in user's approval, which publishes
+// it, or reject it. The project's owner is emailed either way.
+#[tracing::instrument(name = "Review rota route handler", skip_all)]
+pub async fn review_rota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ReviewRotaRequest>,
+) -> Result<(StatusCode, CookieJar, Json<RotaApprovalResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let approval_id = RotaApprovalId::new(request.approval_id);
+    let comment = request
+        .comment
+        .as_deref()
+        .map(ReviewComment::parse)
+        .transpose()?
+        .flatten();
+
+    let approval = state
+        .project_store
+        .write()
+        .await
+        .review_rota(&user_id, &approval_id, request.approve, comment.as_ref())
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ApprovalIDNotFound => {
+                ProjectAPIError::RotaApprovalNotFound(approval_id.clone())
+            }
+            ProjectStoreError::AlreadyReviewed => ValidationError::new(
+                String::from("This rota is no longer waiting for approval"),
+            )
+            .into(),
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(RotaApprovalResponse::from(approval)),
+    ))
+}
+
+// Approve or reject several rotas waiting for the signed-in user's approval
+// at once. They're reviewed together in one transaction, and each gets its
+// own result, so one that was already reviewed doesn't hold up the rest.
+#[tracing::instrument(name = "Bulk review rotas route handler", skip_all)]
+pub async fn bulk_review_rotas(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<BulkReviewRotasRequest>,
+) -> Result<
+    (StatusCode, CookieJar, Json<BulkReviewRotasResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    if request.approval_ids.is_empty() {
+        return Err(ValidationError::new(String::from(
+            "At least one approval ID is needed",
+        ))
+        .into());
+    }
+    if request.approval_ids.len() > MAX_BULK_REVIEWS {
+        return Err(ValidationError::new(format!(
+            "At most {} rotas can be reviewed at once",
+            MAX_BULK_REVIEWS
+        ))
+        .into());
+    }
+    let approval_ids: Vec<RotaApprovalId> = request
+        .approval_ids
+        .iter()
+        .copied()
+        .map(RotaApprovalId::new)
+        .collect();
+    let comment = request
+        .comment
+        .as_deref()
+        .map(ReviewComment::parse)
+        .transpose()?
+        .flatten();
+
+    let results = state
+        .project_store
+        .write()
+        .await
+        .review_rotas(
+            &user_id,
+            &approval_ids,
+            request.approve,
+            comment.as_ref(),
+        )
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let results = request
+        .approval_ids
+        .into_iter()
+        .zip(results)
+        .map(|(approval_id, result)| match result {
+            Ok(approval) => BulkReviewResult {
+                approval_id,
+                approval: Some(RotaApprovalResponse::from(approval)),
+                error: None,
+            },
+            Err(e) => BulkReviewResult {
+                approval_id,
+                approval: None,
+                error: Some(match e {
+                    ProjectStoreError::AlreadyReviewed => String::from(
+                        "This rota is no longer waiting for approval",
+                    ),
+                    e => e.to_string(),
+                }),
+            },
+        })
+        .collect();
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(BulkReviewRotasResponse { results }),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ReviewRotaRequest {
+    #[serde(rename = "approvalId")]
+    pub approval_id: uuid::Uuid,
+    pub approve: bool,
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct BulkReviewRotasRequest {
+    #[serde(rename = "approvalIds")]
+    pub approval_ids: Vec<uuid::Uuid>,
+    pub approve: bool,
+    // Given with every review
+    pub comment: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BulkReviewRotasResponse {
+    // In the order the approvals were given
+    pub results: Vec<BulkReviewResult>,
+}
+
+// The reviewed approval, or why it couldn't be reviewed
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BulkReviewResult {
+    #[serde(rename = "approvalId")]
+    pub approval_id: uuid::Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub approval: Option<RotaApprovalResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PendingApprovalListResponse {
+    // Oldest first
+    pub approvals: Vec<RotaApprovalResponse>,
+}
@@ -0,0 +1,55 @@
+use axum::{
+    extract::State,
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+
+use crate::{
+    domain::{calendar::rota_calendar, ProjectAPIError},
+    utils::{
+        auth::Claims,
+        residency::{data_region, data_region_header},
+    },
+    AppState,
+};
+
+// Every shift in every project the user owns, as one iCalendar feed, so
+// they can subscribe once rather than per project
+#[tracing::instrument(name = "Get calendar feed route handler", skip_all)]
+pub async fn get_calendar_feed(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<impl IntoResponse, ProjectAPIError> {
+    let user_id = claims.id;
+
+    let mut project_store = state.project_store.write().await;
+    let project_list = project_store
+        .get_project_list(&user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let mut projects = Vec::with_capacity(project_list.len());
+    for (project_id, _) in project_list {
+        projects.push(
+            project_store
+                .get_project(&user_id, &project_id)
+                .await
+                .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?,
+        );
+    }
+    drop(project_store);
+    let region = data_region(&state, &user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        [(header::CONTENT_TYPE, "text/calendar; charset=utf-8")],
+        data_region_header(region),
+        rota_calendar(&projects, state.clock.now()),
+    ))
+}
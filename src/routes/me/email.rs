@@ -0,0 +1,55 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::Secret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Email, UndeliverableReason},
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Whether email to the signed-in user's address is being delivered. Once it
+// has bounced or they complained, notification emails are held back until
+// they confirm the address again through PUT /auth/email.
+#[tracing::instrument(name = "Get email status route handler", skip_all)]
+pub async fn get_email_status(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+) -> Result<(StatusCode, CookieJar, Json<EmailStatusResponse>), AuthAPIError> {
+    let email = Email::parse(Secret::new(claims.sub.clone()))
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let undeliverable = state
+        .user_store
+        .read()
+        .await
+        .get_undeliverable_email(&email)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(EmailStatusResponse {
+        email: claims.sub,
+        deliverable: undeliverable.is_none(),
+        reason: undeliverable
+            .as_ref()
+            .map(|undeliverable| undeliverable.reason),
+        undeliverable_since: undeliverable
+            .map(|undeliverable| undeliverable.marked_at.to_rfc3339()),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct EmailStatusResponse {
+    pub email: String,
+    pub deliverable: bool,
+    // Why email isn't being delivered, when it isn't
+    pub reason: Option<UndeliverableReason>,
+    #[serde(rename = "undeliverableSince")]
+    pub undeliverable_since: Option<String>,
+}
@@ -0,0 +1,7 @@
+mod approvals;
+mod calendar;
+mod email;
+
+pub use approvals::*;
+pub use calendar::*;
+pub use email::*;
@@ -0,0 +1,23 @@
+use axum::{
+    http::{header, HeaderMap, StatusCode},
+    response::IntoResponse,
+};
+
+use crate::{
+    domain::AuthAPIError,
+    utils::{admin::check_admin_key, metrics::METRICS_HANDLE},
+};
+
+// Scraped with the admin key, as the metrics name tables and operations
+#[tracing::instrument(name = "Metrics route handler", skip_all)]
+pub async fn get_metrics(
+    headers: HeaderMap,
+) -> Result<impl IntoResponse, AuthAPIError> {
+    check_admin_key(&headers)?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        METRICS_HANDLE.render(),
+    ))
+}
@@ -1,2 +1,12 @@
+pub mod admin;
+pub mod api_docs;
 pub mod auth;
+pub mod email;
+pub mod integrations;
+pub mod me;
+pub mod metrics;
+pub mod notifications;
+pub mod policies;
 pub mod projects;
+pub mod sms;
+pub mod version;
@@ -0,0 +1,88 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, Notification, NotificationId, ProjectId},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct NotificationsQueryParams {
+    // Leave out notifications that have been read
+    #[serde(default)]
+    pub unread: bool,
+}
+
+// The signed-in user's inbox: swap requests, rotas published on schedule
+// and approvals, most recent first
+#[tracing::instrument(name = "Get notifications route handler", skip_all)]
+pub async fn get_notifications(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<NotificationsQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<NotificationListResponse>), AuthAPIError>
+{
+    let user_id = claims.id;
+
+    let user_store = state.user_store.read().await;
+    let notifications = user_store
+        .get_notifications(&user_id, query_params.unread)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    let unread = user_store
+        .count_unread_notifications(&user_id)
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+    drop(user_store);
+
+    let response = Json(NotificationListResponse {
+        notifications: notifications
+            .into_iter()
+            .map(NotificationResponse::from)
+            .collect(),
+        unread,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationListResponse {
+    pub notifications: Vec<NotificationResponse>,
+    // All of the user's unread notifications, including any too old to list
+    pub unread: i64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationResponse {
+    #[serde(rename = "notificationId")]
+    pub notification_id: NotificationId,
+    // To long-poll for notifications after this one
+    pub cursor: i64,
+    #[serde(rename = "projectId")]
+    pub project_id: Option<ProjectId>,
+    pub message: String,
+    #[serde(rename = "createdAt")]
+    pub created_at: String,
+    // Null until it's marked read
+    #[serde(rename = "readAt")]
+    pub read_at: Option<String>,
+}
+
+impl From<Notification> for NotificationResponse {
+    fn from(notification: Notification) -> Self {
+        Self {
+            notification_id: notification.notification_id,
+            cursor: notification.cursor,
+            project_id: notification.project_id,
+            message: notification.message,
+            created_at: notification.created_at.to_rfc3339(),
+            read_at: notification.read_at.map(|read_at| read_at.to_rfc3339()),
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{AuthAPIError, NotificationId},
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Mark some of the signed-in user's notifications read, or all of them when
+// no IDs are given
+#[tracing::instrument(name = "Mark notifications read route handler", skip_all)]
+pub async fn mark_notifications_read(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<MarkReadRequest>,
+) -> Result<(StatusCode, CookieJar, Json<MarkReadResponse>), AuthAPIError> {
+    let user_id = claims.id;
+    let notification_ids: Option<Vec<_>> = request
+        .notification_ids
+        .map(|ids| ids.into_iter().map(NotificationId::new).collect());
+
+    let marked = state
+        .user_store
+        .write()
+        .await
+        .mark_notifications_read(&user_id, notification_ids.as_deref())
+        .await
+        .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((StatusCode::OK, jar, Json(MarkReadResponse { marked })))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct MarkReadRequest {
+    #[serde(rename = "notificationIds")]
+    pub notification_ids: Option<Vec<uuid::Uuid>>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MarkReadResponse {
+    // Notifications that were unread until now
+    pub marked: u64,
+}
@@ -0,0 +1,7 @@
+mod list;
+mod mark_read;
+mod poll;
+
+pub use list::*;
+pub use mark_read::*;
+pub use poll::*;
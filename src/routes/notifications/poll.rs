@@ -0,0 +1,119 @@
+use std::time::Duration;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::time::Instant;
+
+use crate::{
+    domain::AuthAPIError,
+    utils::{
+        auth::Claims,
+        constants::{
+            DEFAULT_NOTIFICATION_WAIT, MAX_NOTIFICATION_WAIT,
+            NOTIFICATION_POLL_INTERVAL,
+        },
+        deadline::Deadline,
+        json::Json,
+        query::ValidatedQuery,
+    },
+    AppState,
+};
+
+use super::NotificationResponse;
+
+#[derive(Deserialize)]
+pub struct NotificationPollQueryParams {
+    // The cursor from the last response. Without it the response comes
+    // straight back with the cursor to start from.
+    pub since: Option<i64>,
+    // Seconds to wait for a notification before answering with none
+    pub wait: Option<u64>,
+}
+
+// For clients that can't hold a stream open: answers as soon as the
+// signed-in user has notifications after `since`, or with none once the
+// wait is up
+#[tracing::instrument(name = "Poll notifications route handler", skip_all)]
+pub async fn poll_notifications(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<NotificationPollQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<NotificationPollResponse>), AuthAPIError>
+{
+    let user_id = claims.id;
+
+    let Some(since) = query_params.since else {
+        let cursor = state
+            .user_store
+            .read()
+            .await
+            .get_notification_cursor(&user_id)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+        return Ok((
+            StatusCode::OK,
+            jar,
+            Json(NotificationPollResponse {
+                notifications: Vec::new(),
+                cursor,
+            }),
+        ));
+    };
+
+    // Leave time to answer before the request is given up on
+    let mut wait = query_params
+        .wait
+        .map(Duration::from_secs)
+        .unwrap_or(DEFAULT_NOTIFICATION_WAIT)
+        .min(MAX_NOTIFICATION_WAIT);
+    if let Some(deadline) = Deadline::current() {
+        wait = wait.min(
+            deadline
+                .remaining()
+                .saturating_sub(NOTIFICATION_POLL_INTERVAL),
+        );
+    }
+    let give_up = Instant::now() + wait;
+
+    let notifications = loop {
+        // Not held between checks, so waiting doesn't hold up writes
+        let notifications = state
+            .user_store
+            .read()
+            .await
+            .get_notifications_since(&user_id, since)
+            .await
+            .map_err(|e| AuthAPIError::UnexpectedError(eyre!(e)))?;
+        let now = Instant::now();
+        if !notifications.is_empty() || now >= give_up {
+            break notifications;
+        }
+        tokio::time::sleep_until(give_up.min(now + NOTIFICATION_POLL_INTERVAL))
+            .await;
+    };
+
+    let response = Json(NotificationPollResponse {
+        cursor: notifications
+            .last()
+            .map(|notification| notification.cursor)
+            .unwrap_or(since),
+        notifications: notifications
+            .into_iter()
+            .map(NotificationResponse::from)
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct NotificationPollResponse {
+    // Oldest first. When there are more than fit, poll again straight away.
+    pub notifications: Vec<NotificationResponse>,
+    // Pass as `since` to the next poll
+    pub cursor: i64,
+}
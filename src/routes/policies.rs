@@ -0,0 +1,32 @@
+use axum::{extract::State, http::StatusCode, Json};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::app_state::AppState;
+
+// The terms of service and privacy policy users are asked to accept
+#[tracing::instrument(name = "Get policy route handler", skip_all)]
+pub async fn get_policy(
+    State(state): State<AppState>,
+) -> (StatusCode, Json<PolicyResponse>) {
+    let policy = state.settings.current_policy();
+    let response = Json(PolicyResponse {
+        version: policy.version,
+        required: state.settings.required_policy_version.is_some(),
+        terms_of_service: policy.terms_of_service.to_owned(),
+        privacy_policy: policy.privacy_policy.to_owned(),
+    });
+
+    (StatusCode::OK, response)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct PolicyResponse {
+    pub version: i32,
+    // Whether accepting this version is needed to use the service
+    pub required: bool,
+    #[serde(rename = "termsOfService")]
+    pub terms_of_service: String,
+    #[serde(rename = "privacyPolicy")]
+    pub privacy_policy: String,
+}
@@ -0,0 +1,123 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Comment, CommentBody, ProjectAPIError, ProjectId, ProjectStoreError,
+        ShiftId,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Comment on a project, or one of its shifts. Members mentioned with an "@"
+// are texted if they've opted in to SMS.
+#[tracing::instrument(name = "Add comment route handler", skip_all)]
+pub async fn add_comment(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<AddCommentRequest>,
+) -> Result<(StatusCode, CookieJar, Json<AddCommentResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let body = CommentBody::parse(&request.body)?;
+
+    let map_store_error = |e| match (e, request.shift_id) {
+        (ProjectStoreError::ProjectIDNotFound, _) => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        (ProjectStoreError::ShiftIDNotFound, Some(shift_id)) => {
+            ProjectAPIError::ShiftNotFound(ShiftId::new(shift_id))
+        }
+        (e, _) => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project_store = state.project_store.write().await;
+    let project = project_store
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+    let comment = Comment::new(
+        project_id.clone(),
+        request.shift_id.map(ShiftId::new),
+        user_id.clone(),
+        body,
+        &project.members,
+        state.clock.now(),
+    );
+    let notified = project_store
+        .add_comment(
+            &user_id,
+            &comment,
+            &comment.mention_message(project.project_name.as_ref()),
+        )
+        .await
+        .map_err(map_store_error)?;
+    drop(project_store);
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(AddCommentResponse {
+            comment: CommentResponse::from(comment),
+            notified,
+        }),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct AddCommentRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Leave out to comment on the project as a whole
+    #[serde(rename = "shiftId")]
+    pub shift_id: Option<uuid::Uuid>,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CommentResponse {
+    #[serde(rename = "commentId")]
+    pub comment_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    #[serde(rename = "shiftId")]
+    pub shift_id: Option<uuid::Uuid>,
+    #[serde(rename = "authorId")]
+    pub author_id: uuid::Uuid,
+    pub body: String,
+    // The IDs of the members mentioned
+    pub mentions: Vec<uuid::Uuid>,
+    #[serde(rename = "postedAt")]
+    pub posted_at: String,
+}
+
+impl From<Comment> for CommentResponse {
+    fn from(comment: Comment) -> Self {
+        Self {
+            comment_id: *comment.comment_id.as_ref(),
+            project_id: *comment.project_id.as_ref(),
+            shift_id: comment.shift_id.map(|shift_id| *shift_id.as_ref()),
+            author_id: *comment.author_id.as_ref(),
+            body: comment.body.as_ref().clone(),
+            mentions: comment
+                .mentions
+                .iter()
+                .map(|member_id| *member_id.as_ref())
+                .collect(),
+            posted_at: comment.posted_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AddCommentResponse {
+    pub comment: CommentResponse,
+    // How many of the members mentioned were texted
+    pub notified: usize,
+}
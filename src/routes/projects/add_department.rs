@@ -0,0 +1,84 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Department, DepartmentName, ProjectAPIError, ProjectId,
+        ProjectStoreError, ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Add a sub-team to the project, e.g. the kitchen at a large venue
+#[tracing::instrument(name = "Add department route handler", skip_all)]
+pub async fn add_department(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<AddDepartmentRequest>,
+) -> Result<(StatusCode, CookieJar, Json<DepartmentResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let department = Department {
+        department_id: state.id_generator.department_id(),
+        project_id: project_id.clone(),
+        department_name: DepartmentName::parse(&request.name)?,
+    };
+
+    state
+        .project_store
+        .write()
+        .await
+        .add_department(&user_id, &department)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            ProjectStoreError::DepartmentNameExists => {
+                ValidationError::new(String::from(
+                    "The project already has a department by that name",
+                ))
+                .into()
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(DepartmentResponse::from(&department)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct AddDepartmentRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DepartmentResponse {
+    #[serde(rename = "departmentId")]
+    pub department_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    #[schemars(length(min = 1, max = 100))]
+    pub name: String,
+}
+
+impl From<&Department> for DepartmentResponse {
+    fn from(department: &Department) -> Self {
+        Self {
+            department_id: *department.department_id.as_ref(),
+            project_id: *department.project_id.as_ref(),
+            name: department.department_name.as_ref().to_owned(),
+        }
+    }
+}
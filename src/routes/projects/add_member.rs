@@ -1,28 +1,45 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::{
-        Member, MemberName, ProjectAPIError, ProjectId, ProjectStoreError,
+        ContractedHours, DepartmentId, Member, MemberName, ProjectAPIError,
+        ProjectId, ProjectStoreError, QuotaResource, ShiftTag,
+    },
+    utils::{
+        auth::Claims,
+        json::Json,
+        quota::{quota_headers, QuotaHeaders},
     },
-    utils::auth::get_claims,
     AppState,
 };
 
 #[tracing::instrument(name = "Add member to project route handler", skip_all)]
 pub async fn add_member(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
     Json(request): Json<AddMemberRequest>,
-) -> Result<(StatusCode, CookieJar, Json<AddMemberResponse>), ProjectAPIError> {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+) -> Result<
+    (StatusCode, CookieJar, QuotaHeaders, Json<AddMemberResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
 
     let project_id = ProjectId::parse(&request.project_id)?;
 
     let member_name = MemberName::parse(request.member_name)?;
-    let member = Member::new(project_id, member_name);
+    let mut member =
+        Member::new(project_id, state.id_generator.member_id(), member_name);
+    member.contracted_hours = request
+        .contracted_hours_per_week
+        .map(ContractedHours::parse)
+        .transpose()?;
+    member.department_id = request.department_id.map(DepartmentId::new);
+    member.skills = Member::parse_skills(&request.skills)?;
 
     state
         .project_store
@@ -32,7 +49,13 @@ pub async fn add_member(
         .await
         .map_err(|e| match e {
             ProjectStoreError::ProjectIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*member.project_id.as_ref())
+                ProjectAPIError::ProjectNotFound(member.project_id.clone())
+            }
+            e @ ProjectStoreError::DepartmentIDNotFound => {
+                match member.department_id.as_ref() {
+                    Some(id) => ProjectAPIError::DepartmentNotFound(id.clone()),
+                    None => ProjectAPIError::UnexpectedError(eyre!(e)),
+                }
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
@@ -41,19 +64,39 @@ pub async fn add_member(
         project_id: *member.project_id.as_ref(),
         member_id: *member.member_id.as_ref(),
         member_name: member.member_name.as_ref().to_owned(),
+        contracted_hours_per_week: member
+            .contracted_hours
+            .map(|hours| *hours.as_ref()),
+        department_id: member.department_id.map(|id| *id.as_ref()),
+        skills: member.skills,
     });
 
-    Ok((StatusCode::CREATED, jar, response))
+    let headers = quota_headers(&state, &user_id, QuotaResource::Members).await;
+
+    Ok((StatusCode::CREATED, jar, headers, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AddMemberResponse {
     #[serde(rename = "projectId")]
     pub project_id: uuid::Uuid,
     #[serde(rename = "memberId")]
     pub member_id: uuid::Uuid,
     #[serde(rename = "memberName")]
+    #[schemars(length(min = 1, max = 255))]
     pub member_name: String,
+    // Left out for members not on a fixed-hours contract
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(min = 0, max = 168))]
+    pub contracted_hours_per_week: Option<f64>,
+    // Left out for members not in a department
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
 }
 
 #[derive(Debug, PartialEq, Deserialize)]
@@ -62,4 +105,11 @@ pub struct AddMemberRequest {
     pub project_id: String,
     #[serde(rename = "memberName")]
     pub member_name: String,
+    #[serde(rename = "contractedHoursPerWeek")]
+    pub contracted_hours_per_week: Option<f64>,
+    #[serde(rename = "departmentId")]
+    pub department_id: Option<uuid::Uuid>,
+    // Secondary skills, in the same terms as shift tags
+    #[serde(default)]
+    pub skills: Vec<String>,
 }
@@ -1,67 +1,173 @@
 use std::str::FromStr;
 
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::{
-        Day, MemberId, Minute, ProjectAPIError, ProjectStoreError, Shift,
+        Day, MemberId, Minute, ProjectAPIError, ProjectStoreError,
+        QuotaResource, Shift, ShiftBreak, ShiftLocation, ShiftNote, ShiftTag,
+        UserId,
+    },
+    utils::{
+        auth::Claims,
+        json::Json,
+        query::ValidatedQuery,
+        quota::{quota_headers, QuotaHeaders},
     },
-    utils::auth::get_claims,
     AppState,
 };
 
+#[derive(Deserialize)]
+pub struct AddShiftQueryParams {
+    // Round times to the project's shift granularity instead of rejecting
+    // them
+    #[serde(default)]
+    pub snap: bool,
+}
+
 #[tracing::instrument(name = "Add shift to project route handler", skip_all)]
 pub async fn add_shift(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
+    query_params: ValidatedQuery<AddShiftQueryParams>,
     Json(request): Json<AddShiftRequest>,
-) -> Result<(StatusCode, CookieJar, Json<AddShiftResponse>), ProjectAPIError> {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+) -> Result<
+    (StatusCode, CookieJar, QuotaHeaders, Json<AddShiftResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let shift =
+        build_shift(&state, &user_id, &request, query_params.snap).await?;
+
+    state
+        .project_store
+        .write()
+        .await
+        .add_shift(&user_id, &shift)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::MemberIDNotFound => {
+                ProjectAPIError::MemberNotFound(shift.member_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let headers = quota_headers(&state, &user_id, QuotaResource::Shifts).await;
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        headers,
+        Json(AddShiftResponse::from(shift)),
+    ))
+}
 
+// Check a requested shift against its project's rules, without saving it
+pub(super) async fn build_shift(
+    state: &AppState,
+    user_id: &UserId,
+    request: &AddShiftRequest,
+    snap: bool,
+) -> Result<Shift, ProjectAPIError> {
     let member_id = MemberId::new(request.member_id);
     let day = Day::from_str(&request.day)?;
     let start_time = Minute::parse(request.start_time)?;
     let end_time = Minute::parse(request.end_time)?;
-    let shift = Shift::new(member_id, day, start_time, end_time)?;
 
-    state
+    let member = state
         .project_store
         .write()
         .await
-        .add_shift(&user_id, &shift)
+        .get_member(user_id, &member_id)
         .await
         .map_err(|e| match e {
             ProjectStoreError::MemberIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*shift.member_id.as_ref())
+                ProjectAPIError::MemberNotFound(member_id.clone())
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
 
-    let response = Json(AddShiftResponse {
-        id: *shift.id.as_ref(),
-        member_id: *shift.member_id.as_ref(),
-        day: shift.day.to_string(),
-        start_time: shift.start_time.value_of(),
-        end_time: shift.end_time.value_of(),
-    });
+    let settings = state
+        .project_store
+        .write()
+        .await
+        .get_project_settings(user_id, &member.project_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let mut shift =
+        settings.new_shift(member_id, day, start_time, end_time, snap)?;
+    shift.id = state.id_generator.shift_id();
+    shift.tags = ShiftTag::parse_all(&request.tags)?;
+    if let Some(note) = &request.note {
+        shift.note = ShiftNote::parse(note)?;
+    }
+    if let Some(location) = &request.location {
+        shift.location = ShiftLocation::parse(location)?;
+    }
 
-    Ok((StatusCode::CREATED, jar, response))
+    if let Some(standby_member_id) = request.standby_member_id {
+        let standby_member_id = MemberId::new(standby_member_id);
+        let standby_member = state
+            .project_store
+            .write()
+            .await
+            .get_member(user_id, &standby_member_id)
+            .await
+            .map_err(|e| match e {
+                ProjectStoreError::MemberIDNotFound => {
+                    ProjectAPIError::MemberNotFound(standby_member_id.clone())
+                }
+                e => ProjectAPIError::UnexpectedError(eyre!(e)),
+            })?;
+        shift.assign_standby(&member, &standby_member)?;
+    }
+
+    Ok(shift)
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct AddShiftResponse {
     #[serde(rename = "id")]
     pub id: uuid::Uuid,
     #[serde(rename = "memberId")]
     pub member_id: uuid::Uuid,
+    #[schemars(with = "Day")]
     pub day: String,
     #[serde(rename = "startTime")]
+    #[schemars(range(min = 0, max = 1440))]
     pub start_time: i16,
     #[serde(rename = "endTime")]
+    #[schemars(range(min = 0, max = 1440))]
     pub end_time: i16,
+    pub breaks: Vec<ShiftBreak>,
+    #[serde(rename = "standbyMemberId")]
+    pub standby_member_id: Option<uuid::Uuid>,
+    pub tags: Vec<ShiftTag>,
+    pub note: Option<ShiftNote>,
+    pub location: Option<ShiftLocation>,
+}
+
+impl From<Shift> for AddShiftResponse {
+    fn from(shift: Shift) -> Self {
+        Self {
+            id: *shift.id.as_ref(),
+            member_id: *shift.member_id.as_ref(),
+            day: shift.day.to_string(),
+            start_time: shift.start_time.value_of(),
+            end_time: shift.end_time.value_of(),
+            breaks: shift.breaks,
+            standby_member_id: shift.standby_member_id.map(|id| *id.as_ref()),
+            tags: shift.tags,
+            note: shift.note,
+            location: shift.location,
+        }
+    }
 }
 
 #[derive(Debug, PartialEq, Deserialize)]
@@ -73,4 +179,12 @@ pub struct AddShiftRequest {
     pub start_time: i16,
     #[serde(rename = "endTime")]
     pub end_time: i16,
+    #[serde(rename = "standbyMemberId", default)]
+    pub standby_member_id: Option<uuid::Uuid>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
 }
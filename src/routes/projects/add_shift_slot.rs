@@ -0,0 +1,150 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Day, DepartmentId, MemberId, Minute, ProjectAPIError, ProjectId,
+        ProjectStoreError, ShiftSlot, ShiftTag, SlotCoverage,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Define a shift needing several people at once. Its places are filled one
+// member at a time through /projects/slots/fill.
+#[tracing::instrument(name = "Add shift slot route handler", skip_all)]
+pub async fn add_shift_slot(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<AddShiftSlotRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ShiftSlotResponse>), ProjectAPIError> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+
+    let mut slot = ShiftSlot::new(
+        state.id_generator.slot_id(),
+        project_id.clone(),
+        Day::from_str(&request.day)?,
+        Minute::parse(request.start_time)?,
+        Minute::parse(request.end_time)?,
+        request.places,
+    )?;
+    slot.tag = request.tag.as_deref().map(ShiftTag::parse).transpose()?;
+    slot.department_id = request.department_id.map(DepartmentId::new);
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e @ ProjectStoreError::DepartmentIDNotFound => {
+            match request.department_id {
+                Some(id) => {
+                    ProjectAPIError::DepartmentNotFound(DepartmentId::new(id))
+                }
+                None => ProjectAPIError::UnexpectedError(eyre!(e)),
+            }
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    // Every shift filling the slot has to follow the project's rules, so
+    // there's no point in a slot that can't be filled
+    let settings = state
+        .project_store
+        .write()
+        .await
+        .get_project_settings(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+    slot.shift_for(MemberId::default(), &settings)?;
+
+    state
+        .project_store
+        .write()
+        .await
+        .add_shift_slot(&user_id, &slot)
+        .await
+        .map_err(map_store_error)?;
+
+    let coverage = SlotCoverage {
+        slot,
+        assigned: Vec::new(),
+    };
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(ShiftSlotResponse::from(coverage)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct AddShiftSlotRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub day: String,
+    #[serde(rename = "startTime")]
+    pub start_time: i16,
+    #[serde(rename = "endTime")]
+    pub end_time: i16,
+    // How many people the slot needs
+    pub places: i16,
+    // The skill needed, given to each shift filling the slot as a tag
+    #[serde(default)]
+    pub tag: Option<String>,
+    // The department whose members fill it
+    #[serde(rename = "departmentId", default)]
+    pub department_id: Option<uuid::Uuid>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftSlotResponse {
+    #[serde(rename = "slotId")]
+    pub slot_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub day: Day,
+    #[serde(rename = "startTime")]
+    #[schemars(range(min = 0, max = 1440))]
+    pub start_time: i16,
+    #[serde(rename = "endTime")]
+    #[schemars(range(min = 0, max = 1440))]
+    pub end_time: i16,
+    pub places: i16,
+    pub tag: Option<ShiftTag>,
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<uuid::Uuid>,
+    // Members filling the slot's places
+    #[serde(rename = "assignedMemberIds")]
+    pub assigned_member_ids: Vec<uuid::Uuid>,
+    #[serde(rename = "openPlaces")]
+    pub open_places: i16,
+}
+
+impl From<SlotCoverage> for ShiftSlotResponse {
+    fn from(coverage: SlotCoverage) -> Self {
+        let open_places = coverage.open_places();
+        let slot = coverage.slot;
+        Self {
+            slot_id: *slot.slot_id.as_ref(),
+            project_id: *slot.project_id.as_ref(),
+            day: slot.day,
+            start_time: slot.start_time.value_of(),
+            end_time: slot.end_time.value_of(),
+            places: slot.places,
+            tag: slot.tag,
+            department_id: slot.department_id.map(|id| *id.as_ref()),
+            assigned_member_ids: coverage
+                .assigned
+                .iter()
+                .map(|member_id| *member_id.as_ref())
+                .collect(),
+            open_places,
+        }
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        check_overlaps, MemberId, ProjectAPIError, ProjectStoreError, Shift,
+        WeekTemplateId,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Lay a saved week out again as the project's shifts. Shifts repeat every
+// week, so this sets the rota for the weeks to come.
+#[tracing::instrument(name = "Apply week template route handler", skip_all)]
+pub async fn apply_week_template(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ApplyWeekTemplateRequest>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ApplyWeekTemplateResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let template_id = WeekTemplateId::new(request.template_id);
+
+    let template = state
+        .project_store
+        .write()
+        .await
+        .get_week_template(&user_id, &template_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::TemplateIDNotFound => {
+                ProjectAPIError::WeekTemplateNotFound(template_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let mut project_store = state.project_store.write().await;
+    let unexpected =
+        |e: ProjectStoreError| ProjectAPIError::UnexpectedError(eyre!(e));
+    let settings = project_store
+        .get_project_settings(&user_id, &template.project_id)
+        .await
+        .map_err(unexpected)?;
+    let members = project_store
+        .get_members(&user_id, &template.project_id)
+        .await
+        .map_err(unexpected)?;
+    let existing: Vec<Shift> = if request.replace {
+        Vec::new()
+    } else {
+        project_store
+            .get_project(&user_id, &template.project_id)
+            .await
+            .map_err(unexpected)?
+            .members
+            .into_iter()
+            .flat_map(|member| member.shifts)
+            .collect()
+    };
+    drop(project_store);
+
+    let member_map: HashMap<MemberId, MemberId> = request
+        .members
+        .iter()
+        .map(|(from, to)| (MemberId::new(*from), MemberId::new(*to)))
+        .collect();
+    let mut shifts = template.instantiate(&settings, &members, &member_map)?;
+    for shift in &mut shifts {
+        shift.id = state.id_generator.shift_id();
+    }
+    check_overlaps(&existing, &shifts, &members)?;
+
+    let replaced = state
+        .project_store
+        .write()
+        .await
+        .apply_week_template(&user_id, &template, &shifts, request.replace)
+        .await
+        .map_err(unexpected)?;
+
+    let response = Json(ApplyWeekTemplateResponse {
+        template_id: request.template_id,
+        shifts: shifts.len() as u64,
+        replaced,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+fn default_replace() -> bool {
+    true
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ApplyWeekTemplateRequest {
+    #[serde(rename = "templateId")]
+    pub template_id: uuid::Uuid,
+    // Template member ID to the member who takes their shifts instead
+    #[serde(default)]
+    pub members: HashMap<uuid::Uuid, uuid::Uuid>,
+    // Clear the project's shifts first, rather than adding to them
+    #[serde(default = "default_replace")]
+    pub replace: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ApplyWeekTemplateResponse {
+    #[serde(rename = "templateId")]
+    pub template_id: uuid::Uuid,
+    // How many shifts were added
+    pub shifts: u64,
+    // How many shifts were cleared to make way for them
+    pub replaced: u64,
+}
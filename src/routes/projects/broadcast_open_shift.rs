@@ -0,0 +1,151 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Day, Locale, Minute, ProjectAPIError, ProjectId, ProjectStoreError,
+        ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+const MAX_NOTE_LENGTH: usize = 160;
+
+// Text every member who has opted in to SMS asking for someone to cover a
+// shift urgently
+#[tracing::instrument(name = "Broadcast open shift route handler", skip_all)]
+pub async fn broadcast_open_shift(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<OpenShiftBroadcastRequest>,
+) -> Result<
+    (StatusCode, CookieJar, Json<OpenShiftBroadcastResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let start_time = Minute::parse(request.start_time)?;
+    let end_time = Minute::parse(request.end_time)?;
+    if let Some(note) = &request.note {
+        if note.chars().count() > MAX_NOTE_LENGTH {
+            return Err(ValidationError::new(format!(
+                "Note cannot be longer than {} characters",
+                MAX_NOTE_LENGTH
+            ))
+            .into());
+        }
+    }
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let project = state
+        .project_store
+        .write()
+        .await
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+
+    let content = open_shift_message(
+        project.project_name.as_ref(),
+        request.day,
+        &start_time,
+        &end_time,
+        request.note.as_deref(),
+    );
+    let recipients = state
+        .project_store
+        .write()
+        .await
+        .broadcast_sms(&user_id, &project_id, &content)
+        .await
+        .map_err(map_store_error)?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(OpenShiftBroadcastResponse { recipients }),
+    ))
+}
+
+pub fn open_shift_message(
+    project_name: &str,
+    day: Day,
+    start_time: &Minute,
+    end_time: &Minute,
+    note: Option<&str>,
+) -> String {
+    let locale = Locale::default();
+    let mut message = format!(
+        "Urgent: {} needs cover on {} from {} to {}.",
+        project_name,
+        locale.weekday(day),
+        locale.format_time(start_time),
+        locale.format_time(end_time)
+    );
+    if let Some(note) = note.filter(|note| !note.trim().is_empty()) {
+        message.push(' ');
+        message.push_str(note.trim());
+    }
+    message
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct OpenShiftBroadcastRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub day: Day,
+    #[serde(rename = "startTime")]
+    pub start_time: i16,
+    #[serde(rename = "endTime")]
+    pub end_time: i16,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct OpenShiftBroadcastResponse {
+    // How many members were texted
+    pub recipients: usize,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_open_shift_message() {
+        let minute = |value| Minute::parse(value).unwrap();
+
+        assert_eq!(
+            open_shift_message(
+                "Craggy Island",
+                Day::Friday,
+                &minute(540),
+                &minute(1020),
+                None
+            ),
+            "Urgent: Craggy Island needs cover on Friday from 09:00 to 17:00."
+        );
+        assert_eq!(
+            open_shift_message(
+                "Craggy Island",
+                Day::Friday,
+                &minute(540),
+                &minute(1020),
+                Some(" Call Mrs Doyle. ")
+            ),
+            "Urgent: Craggy Island needs cover on Friday from 09:00 to 17:00. \
+             Call Mrs Doyle."
+        );
+    }
+}
@@ -0,0 +1,69 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{Day, ProjectAPIError, ProjectId, ProjectStoreError},
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Delete a project's whole week of shifts, or one day's, so it can be
+// planned again from scratch. A dry run reports how many shifts would go.
+#[tracing::instrument(name = "Clear shifts route handler", skip_all)]
+pub async fn clear_shifts(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ClearShiftsRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ClearShiftsResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let day = request.day.as_deref().map(Day::from_str).transpose()?;
+
+    let shifts = state
+        .project_store
+        .write()
+        .await
+        .clear_shifts(&user_id, &project_id, day, request.dry_run)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(ClearShiftsResponse {
+        day,
+        dry_run: request.dry_run,
+        shifts,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ClearShiftsRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Leave out to clear the whole week
+    #[serde(default)]
+    pub day: Option<String>,
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ClearShiftsResponse {
+    pub day: Option<Day>,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    // How many shifts were deleted, or would be on a dry run
+    pub shifts: u64,
+}
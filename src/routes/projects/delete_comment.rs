@@ -0,0 +1,42 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use crate::{
+    domain::{CommentId, ProjectAPIError, ProjectStoreError},
+    utils::{auth::Claims, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct DeleteCommentQueryParams {
+    #[serde(rename = "commentId")]
+    pub comment_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Delete comment route handler", skip_all)]
+pub async fn delete_comment(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<DeleteCommentQueryParams>,
+) -> Result<(StatusCode, CookieJar), ProjectAPIError> {
+    let user_id = claims.id;
+    let comment_id = CommentId::new(query_params.comment_id);
+
+    state
+        .project_store
+        .write()
+        .await
+        .delete_comment(&user_id, &comment_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::CommentIDNotFound => {
+                ProjectAPIError::CommentNotFound(comment_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((StatusCode::NO_CONTENT, jar))
+}
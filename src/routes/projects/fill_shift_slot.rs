@@ -0,0 +1,78 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use super::add_shift::AddShiftResponse;
+use crate::{
+    domain::{
+        MemberId, ProjectAPIError, ProjectStoreError, ShiftSlotId,
+        ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Give a member a place in a slot, as a shift of their own at the slot's
+// times. Each member can only take one place in a slot.
+#[tracing::instrument(name = "Fill shift slot route handler", skip_all)]
+pub async fn fill_shift_slot(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<FillShiftSlotRequest>,
+) -> Result<(StatusCode, CookieJar, Json<AddShiftResponse>), ProjectAPIError> {
+    let user_id = claims.id;
+    let slot_id = ShiftSlotId::new(request.slot_id);
+    let member_id = MemberId::new(request.member_id);
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::SlotIDNotFound => {
+            ProjectAPIError::ShiftSlotNotFound(slot_id.clone())
+        }
+        ProjectStoreError::MemberIDNotFound => {
+            ProjectAPIError::MemberNotFound(member_id.clone())
+        }
+        ProjectStoreError::SlotFull => ValidationError::new(String::from(
+            "Every place in the slot is filled",
+        ))
+        .into(),
+        ProjectStoreError::AlreadyInSlot => ValidationError::new(String::from(
+            "The member already has a place in the slot",
+        ))
+        .into(),
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project_store = state.project_store.write().await;
+    let slot = project_store
+        .get_shift_slot(&user_id, &slot_id)
+        .await
+        .map_err(map_store_error)?;
+    let settings = project_store
+        .get_project_settings(&user_id, &slot.project_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let mut shift = slot.shift_for(member_id.clone(), &settings)?;
+    shift.id = state.id_generator.shift_id();
+    project_store
+        .fill_shift_slot(&user_id, &slot_id, &shift)
+        .await
+        .map_err(map_store_error)?;
+    drop(project_store);
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(AddShiftResponse::from(shift)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct FillShiftSlotRequest {
+    #[serde(rename = "slotId")]
+    pub slot_id: uuid::Uuid,
+    #[serde(rename = "memberId")]
+    pub member_id: uuid::Uuid,
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    Json,
+};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        attendance_report, time::parse_date, DateRange, Day, DepartmentId,
+        Locale, MemberAttendance, ProjectAPIError, ProjectId,
+        ProjectStoreError, WeekAttendance,
+    },
+    utils::{
+        auth::Claims,
+        locale::resolve_locale,
+        query::ValidatedQuery,
+        residency::{data_region, data_region_header, DataRegionHeader},
+    },
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct AttendanceReportQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // YYYY-MM-DD, inclusive
+    pub from: String,
+    pub to: String,
+    // Only report on members of this department
+    #[serde(rename = "departmentId")]
+    pub department_id: Option<uuid::Uuid>,
+}
+
+#[tracing::instrument(name = "Get attendance report route handler", skip_all)]
+pub async fn get_attendance_report(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    headers: HeaderMap,
+    query_params: ValidatedQuery<AttendanceReportQueryParams>,
+) -> Result<
+    (
+        StatusCode,
+        CookieJar,
+        DataRegionHeader,
+        Json<AttendanceReportResponse>,
+    ),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+    let range = DateRange::new(
+        parse_date(&query_params.from)?,
+        parse_date(&query_params.to)?,
+    )?;
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project = state
+        .project_store
+        .write()
+        .await
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+    if let Some(department_id) =
+        query_params.department_id.map(DepartmentId::new)
+    {
+        project.members.retain(|member| {
+            member.department_id.as_ref() == Some(&department_id)
+        });
+    }
+
+    let absences = state
+        .project_store
+        .write()
+        .await
+        .get_absences(&user_id, &project_id, &range)
+        .await
+        .map_err(map_store_error)?;
+
+    let locale = resolve_locale(&state, &user_id, &headers)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    let region = data_region(&state, &user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    let response = Json(AttendanceReportResponse {
+        project_id,
+        from: range.from().to_string(),
+        to: range.to().to_string(),
+        week_start: project.week_start,
+        locale,
+        from_label: locale.format_date(range.from()),
+        to_label: locale.format_date(range.to()),
+        members: attendance_report(&project, &absences, &range)
+            .into_iter()
+            .map(|attendance| {
+                MemberAttendanceResponse::new(attendance, &locale)
+            })
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, data_region_header(region), response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AttendanceReportResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    pub from: String,
+    pub to: String,
+    // Each member's weeks begin on this day
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
+    // The locale the labels below are formatted in
+    pub locale: Locale,
+    #[serde(rename = "fromLabel")]
+    pub from_label: String,
+    #[serde(rename = "toLabel")]
+    pub to_label: String,
+    pub members: Vec<MemberAttendanceResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MemberAttendanceResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: uuid::Uuid,
+    #[serde(rename = "memberName")]
+    pub member_name: String,
+    #[serde(rename = "scheduledShifts")]
+    pub scheduled_shifts: i64,
+    pub absences: i64,
+    #[serde(rename = "scheduledMinutes")]
+    pub scheduled_minutes: i64,
+    #[serde(rename = "completedMinutes")]
+    pub completed_minutes: i64,
+    #[serde(rename = "scheduledLabel")]
+    pub scheduled_label: String,
+    #[serde(rename = "completedLabel")]
+    pub completed_label: String,
+    // This and the delta are left out for members without contracted hours
+    #[serde(
+        rename = "contractedMinutes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contracted_minutes: Option<i64>,
+    // Scheduled minutes over the contract, or under when negative
+    #[serde(
+        rename = "contractDeltaMinutes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contract_delta_minutes: Option<i64>,
+    // Null when nothing was scheduled
+    #[serde(rename = "completedPercentage")]
+    pub completed_percentage: Option<f64>,
+    // Completed minutes on shifts with each tag
+    #[serde(rename = "tagMinutes")]
+    pub tag_minutes: BTreeMap<String, i64>,
+    pub weeks: Vec<WeekAttendanceResponse>,
+}
+
+impl MemberAttendanceResponse {
+    pub fn new(attendance: MemberAttendance, locale: &Locale) -> Self {
+        let completed_minutes = attendance.completed_minutes();
+        Self {
+            member_id: *attendance.member_id.as_ref(),
+            member_name: attendance.member_name.as_ref().to_owned(),
+            scheduled_shifts: attendance.scheduled_shifts,
+            absences: attendance.absences,
+            scheduled_minutes: attendance.scheduled_minutes,
+            completed_minutes,
+            scheduled_label: locale
+                .format_duration(attendance.scheduled_minutes),
+            completed_label: locale.format_duration(completed_minutes),
+            contracted_minutes: attendance.contracted_minutes(),
+            contract_delta_minutes: attendance.contract_delta(),
+            completed_percentage: attendance.completed_percentage(),
+            tag_minutes: attendance.tag_minutes,
+            weeks: attendance
+                .weeks
+                .iter()
+                .map(WeekAttendanceResponse::from)
+                .collect(),
+        }
+    }
+}
+
+// Paid minutes in one week of the report. The first and last weeks are
+// shorter when the report starts or ends mid-week.
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WeekAttendanceResponse {
+    pub from: String,
+    pub to: String,
+    #[serde(rename = "scheduledMinutes")]
+    pub scheduled_minutes: i64,
+    #[serde(rename = "completedMinutes")]
+    pub completed_minutes: i64,
+    #[serde(
+        rename = "contractDeltaMinutes",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contract_delta_minutes: Option<i64>,
+}
+
+impl From<&WeekAttendance> for WeekAttendanceResponse {
+    fn from(week: &WeekAttendance) -> Self {
+        Self {
+            from: week.week.from().to_string(),
+            to: week.week.to().to_string(),
+            scheduled_minutes: week.scheduled_minutes,
+            completed_minutes: week.completed_minutes(),
+            contract_delta_minutes: week.contract_delta(),
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        borrow_suggestions, BorrowCandidate, BorrowSuggestion, DepartmentId,
+        MemberId, MemberName, ProjectAPIError, ProjectId, ProjectStoreError,
+        ShiftSlotId,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct BorrowSuggestionsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+// Department slots with places still open, and the members of other
+// departments who could be lent to fill them
+#[tracing::instrument(name = "Get borrow suggestions route handler", skip_all)]
+pub async fn get_borrow_suggestions(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<BorrowSuggestionsQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<BorrowSuggestionsResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let mut project_store = state.project_store.write().await;
+    let coverage = project_store
+        .get_slot_coverage(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    let project = project_store
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    drop(project_store);
+
+    let response = Json(BorrowSuggestionsResponse {
+        project_id: *project_id.as_ref(),
+        suggestions: borrow_suggestions(&project, &coverage)
+            .into_iter()
+            .map(BorrowSuggestionResponse::from)
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BorrowSuggestionsResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Only slots someone could be borrowed for
+    pub suggestions: Vec<BorrowSuggestionResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BorrowSuggestionResponse {
+    #[serde(rename = "slotId")]
+    pub slot_id: ShiftSlotId,
+    #[serde(rename = "departmentId")]
+    pub department_id: DepartmentId,
+    #[serde(rename = "openPlaces")]
+    pub open_places: i16,
+    pub candidates: Vec<BorrowCandidateResponse>,
+}
+
+impl From<BorrowSuggestion> for BorrowSuggestionResponse {
+    fn from(suggestion: BorrowSuggestion) -> Self {
+        Self {
+            slot_id: suggestion.slot_id,
+            department_id: suggestion.department_id,
+            open_places: suggestion.open_places,
+            candidates: suggestion
+                .candidates
+                .into_iter()
+                .map(BorrowCandidateResponse::from)
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct BorrowCandidateResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    // The department they'd be lent from
+    #[serde(rename = "departmentId")]
+    pub department_id: DepartmentId,
+}
+
+impl From<BorrowCandidate> for BorrowCandidateResponse {
+    fn from(candidate: BorrowCandidate) -> Self {
+        Self {
+            member_id: candidate.member_id,
+            member_name: candidate.member_name,
+            department_id: candidate.department_id,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::add_comment::CommentResponse;
+use crate::{
+    domain::{ProjectAPIError, ProjectId, ProjectStoreError, ShiftId},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct CommentsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Only the comments on this shift. Otherwise, every comment in the
+    // project.
+    #[serde(rename = "shiftId")]
+    pub shift_id: Option<uuid::Uuid>,
+}
+
+#[tracing::instrument(name = "Get comments route handler", skip_all)]
+pub async fn get_comments(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<CommentsQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<CommentListResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+    let shift_id = query_params.shift_id.map(ShiftId::new);
+
+    let comments = state
+        .project_store
+        .write()
+        .await
+        .get_comments(&user_id, &project_id, shift_id.as_ref())
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(CommentListResponse {
+        project_id,
+        comments: comments.into_iter().map(CommentResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct CommentListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // Oldest first
+    pub comments: Vec<CommentResponse>,
+}
@@ -0,0 +1,60 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::add_department::DepartmentResponse;
+use crate::{
+    domain::{ProjectAPIError, ProjectId, ProjectStoreError},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct DepartmentsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Get departments route handler", skip_all)]
+pub async fn get_departments(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<DepartmentsQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<DepartmentListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let departments = state
+        .project_store
+        .write()
+        .await
+        .get_departments(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(DepartmentListResponse {
+        project_id,
+        departments: departments.iter().map(DepartmentResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct DepartmentListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // In name order
+    pub departments: Vec<DepartmentResponse>,
+}
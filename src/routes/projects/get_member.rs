@@ -1,11 +1,12 @@
-use axum::{extract::Query, extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{MemberId, ProjectAPIError, ProjectStoreError},
-    utils::auth::get_claims,
+    domain::{MemberId, ProjectAPIError, ProjectStoreError, ShiftTag},
+    utils::{auth::Claims, query::ValidatedQuery},
     AppState,
 };
 
@@ -18,10 +19,11 @@ pub struct QueryParams {
 #[tracing::instrument(name = "Get member route handler", skip_all)]
 pub async fn get_member(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
-    query_params: Query<QueryParams>,
+    query_params: ValidatedQuery<QueryParams>,
 ) -> Result<(StatusCode, CookieJar, Json<MemberResponse>), ProjectAPIError> {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+    let user_id = claims.id;
     tracing::debug!("user_id: {}", user_id.as_ref().to_string(),);
 
     let member_id = MemberId::new(query_params.member_id);
@@ -35,7 +37,7 @@ pub async fn get_member(
         .await
         .map_err(|e| match e {
             ProjectStoreError::MemberIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*member_id.as_ref())
+                ProjectAPIError::MemberNotFound(member_id.clone())
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
@@ -43,13 +45,32 @@ pub async fn get_member(
     let response = Json(MemberResponse {
         id: member.member_id.as_ref().to_string(),
         name: member.member_name.as_ref().to_owned(),
+        contracted_hours_per_week: member
+            .contracted_hours
+            .map(|hours| *hours.as_ref()),
+        department_id: member.department_id.map(|id| *id.as_ref()),
+        skills: member.skills,
     });
 
     Ok((StatusCode::OK, jar, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MemberResponse {
+    #[schemars(length(equal = 36))]
     pub id: String,
+    #[schemars(length(min = 1, max = 255))]
     pub name: String,
+    // Left out for members not on a fixed-hours contract
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(min = 0, max = 168))]
+    pub contracted_hours_per_week: Option<f64>,
+    // Left out for members not in a department
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
 }
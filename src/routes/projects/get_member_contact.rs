@@ -0,0 +1,81 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{MemberContact, MemberId, ProjectAPIError, ProjectStoreError},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct MemberContactQueryParams {
+    #[serde(rename = "memberId")]
+    pub member_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Get member contact route handler", skip_all)]
+pub async fn get_member_contact(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<MemberContactQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<MemberContactResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let member_id = MemberId::new(query_params.member_id);
+
+    let contact = state
+        .project_store
+        .write()
+        .await
+        .get_member_contact(&user_id, &member_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::MemberIDNotFound => {
+                ProjectAPIError::MemberNotFound(member_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(MemberContactResponse::new(member_id, contact)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct MemberContactResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: uuid::Uuid,
+    // E.164, e.g. +447700900123
+    #[serde(rename = "phoneNumber")]
+    pub phone_number: Option<String>,
+    #[serde(rename = "smsOptIn")]
+    pub sms_opt_in: bool,
+    pub email: Option<String>,
+    // Shift reminders are emailed as well as texted, and can be answered by
+    // replying
+    #[serde(rename = "emailOptIn")]
+    pub email_opt_in: bool,
+}
+
+impl MemberContactResponse {
+    pub fn new(member_id: MemberId, contact: MemberContact) -> Self {
+        Self {
+            member_id: *member_id.as_ref(),
+            phone_number: contact
+                .phone_number
+                .map(|number| number.as_ref().to_owned()),
+            sms_opt_in: contact.sms_opt_in,
+            email: contact
+                .email
+                .map(|email| email.as_ref().expose_secret().to_owned()),
+            email_opt_in: contact.email_opt_in,
+        }
+    }
+}
@@ -1,11 +1,12 @@
-use axum::{extract::Query, extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{ProjectAPIError, ProjectId, ProjectStoreError},
-    utils::auth::get_claims,
+    domain::{ProjectAPIError, ProjectId, ProjectStoreError, ShiftTag},
+    utils::{auth::Claims, query::ValidatedQuery},
     AppState,
 };
 
@@ -18,11 +19,12 @@ pub struct GetMemberListQueryParams {
 #[tracing::instrument(name = "Get member list route handler", skip_all)]
 pub async fn get_member_list_for_project(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
-    query_params: Query<GetMemberListQueryParams>,
+    query_params: ValidatedQuery<GetMemberListQueryParams>,
 ) -> Result<(StatusCode, CookieJar, Json<MemberListResponse>), ProjectAPIError>
 {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+    let user_id = claims.id;
     tracing::debug!("user_id: {}", user_id.as_ref().to_string(),);
 
     let project_id = ProjectId::new(query_params.project_id);
@@ -36,7 +38,7 @@ pub async fn get_member_list_for_project(
         .await
         .map_err(|e| match e {
             ProjectStoreError::ProjectIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*project_id.as_ref())
+                ProjectAPIError::ProjectNotFound(project_id.clone())
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
@@ -48,6 +50,11 @@ pub async fn get_member_list_for_project(
             .map(|member| Member {
                 id: member.member_id.as_ref().to_string(),
                 name: member.member_name.as_ref().to_owned(),
+                contracted_hours_per_week: member
+                    .contracted_hours
+                    .map(|hours| *hours.as_ref()),
+                department_id: member.department_id.map(|id| *id.as_ref()),
+                skills: member.skills,
             })
             .collect(),
     });
@@ -55,15 +62,29 @@ pub async fn get_member_list_for_project(
     Ok((StatusCode::OK, jar, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct MemberListResponse {
     #[serde(rename = "projectId")]
     pub project_id: ProjectId,
     pub members: Vec<Member>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Member {
+    #[schemars(length(equal = 36))]
     pub id: String,
+    #[schemars(length(min = 1, max = 255))]
     pub name: String,
+    // Left out for members not on a fixed-hours contract
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(min = 0, max = 168))]
+    pub contracted_hours_per_week: Option<f64>,
+    // Left out for members not in a department
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
 }
@@ -0,0 +1,157 @@
+use askama::Template;
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use chrono::Days;
+use color_eyre::eyre::eyre;
+use serde::Deserialize;
+
+use crate::{
+    domain::{
+        print::{print_rota, PrintLayout, PrintOptions, PrintedRota},
+        time::{parse_date, week_beginning},
+        DateRange, ProjectAPIError, ProjectId, ProjectStoreError,
+        ValidationError,
+    },
+    utils::{
+        auth::Claims,
+        locale::resolve_locale,
+        query::ValidatedQuery,
+        residency::{data_region, data_region_header},
+    },
+    AppState,
+};
+
+// Tables wider than this are printed landscape
+const MAX_PORTRAIT_COLUMNS: usize = 5;
+
+#[derive(Deserialize)]
+pub struct PrintedRotaQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // "members" for a row per member, or "days" for a row per day
+    pub layout: Option<String>,
+    // YYYY-MM-DD, inclusive. Defaults to the project's current week.
+    pub from: Option<String>,
+    pub to: Option<String>,
+    #[serde(default)]
+    pub notes: bool,
+    #[serde(default)]
+    pub locations: bool,
+    // "html" to print from the browser, or "csv" to download
+    pub format: Option<String>,
+}
+
+#[derive(Template)]
+#[template(path = "print/rota.html")]
+struct PrintedRotaPage<'a> {
+    lang: &'a str,
+    orientation: &'a str,
+    rota: &'a PrintedRota,
+}
+
+// A project's rota as a page to print or save as a PDF, or as a CSV file
+#[tracing::instrument(name = "Get printed rota route handler", skip_all)]
+pub async fn get_printed_rota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    headers: HeaderMap,
+    query_params: ValidatedQuery<PrintedRotaQueryParams>,
+) -> Result<Response, ProjectAPIError> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+    let layout = query_params
+        .layout
+        .as_deref()
+        .map(str::parse::<PrintLayout>)
+        .transpose()?
+        .unwrap_or_default();
+    let csv = match query_params.format.as_deref() {
+        None | Some("html") => false,
+        Some("csv") => true,
+        Some(_) => {
+            return Err(ValidationError::new(String::from(
+                "Format must be html or csv",
+            ))
+            .into())
+        }
+    };
+
+    let project = state
+        .project_store
+        .write()
+        .await
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let from = match &query_params.from {
+        Some(from) => parse_date(from)?,
+        None => {
+            week_beginning(state.clock.now().date_naive(), project.week_start)
+        }
+    };
+    let to = match &query_params.to {
+        Some(to) => parse_date(to)?,
+        None => from + Days::new(6),
+    };
+    let range = DateRange::new(from, to)?;
+
+    let locale = resolve_locale(&state, &user_id, &headers)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    let options = PrintOptions {
+        notes: query_params.notes,
+        locations: query_params.locations,
+    };
+    let rota = print_rota(&project, &range, layout, options, &locale)?;
+    let region = data_region(&state, &user_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    if csv {
+        let disposition =
+            format!("attachment; filename=\"rota-{}-{}.csv\"", from, to);
+        return Ok((
+            StatusCode::OK,
+            jar,
+            [
+                (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_owned()),
+                (header::CONTENT_DISPOSITION, disposition),
+            ],
+            data_region_header(region),
+            rota.to_csv(),
+        )
+            .into_response());
+    }
+
+    let page = PrintedRotaPage {
+        lang: locale.tag(),
+        orientation: if rota.columns.len() > MAX_PORTRAIT_COLUMNS {
+            "landscape"
+        } else {
+            "portrait"
+        },
+        rota: &rota,
+    }
+    .render()
+    .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        [(header::CONTENT_TYPE, "text/html; charset=utf-8")],
+        data_region_header(region),
+        page,
+    )
+        .into_response())
+}
@@ -1,11 +1,18 @@
-use axum::{extract::Query, extract::State, http::StatusCode, Json};
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
-use serde::Deserialize;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{Project, ProjectAPIError, ProjectId},
-    utils::auth::get_claims,
+    domain::{
+        ContractedHours, Day, DepartmentId, MemberId, MemberName, Project,
+        ProjectAPIError, ProjectId, ProjectInclude, ProjectMember, ProjectName,
+        ProjectWindow, Shift, ShiftTag, ValidationError,
+    },
+    utils::{auth::Claims, query::ValidatedQuery},
     AppState,
 };
 
@@ -13,26 +20,198 @@ use crate::{
 pub struct GetProjectQueryParams {
     #[serde(rename = "projectId")]
     project_id: uuid::Uuid,
+    // Only include shifts with this tag
+    tag: Option<String>,
+    // Only include members of this department
+    #[serde(rename = "departmentId")]
+    department_id: Option<uuid::Uuid>,
+    // Which relations to load, e.g. "members" for a project picker without
+    // any shifts. Everything when left out.
+    include: Option<String>,
+    // Only include shifts on these days, comma-separated, e.g.
+    // "Monday,Tuesday"
+    days: Option<String>,
+    // How many members to include, for fetching a large project a page at a
+    // time. All of them when left out.
+    limit: Option<i64>,
+    offset: Option<i64>,
 }
 
+const MAX_LIMIT: i64 = 100;
+
 #[tracing::instrument(name = "Get project route handler", skip_all)]
 pub async fn get_project(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
-    query_params: Query<GetProjectQueryParams>,
-) -> Result<(StatusCode, CookieJar, Json<Project>), ProjectAPIError> {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+    query_params: ValidatedQuery<GetProjectQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<ProjectResponse>), ProjectAPIError> {
+    let user_id = claims.id;
     let project_id = ProjectId::new(query_params.project_id);
+    let tag = query_params
+        .tag
+        .as_deref()
+        .map(ShiftTag::parse)
+        .transpose()?;
+    let include = query_params
+        .include
+        .as_deref()
+        .map(ProjectInclude::parse)
+        .transpose()?
+        .unwrap_or_default();
+    if tag.is_some() && include != ProjectInclude::MembersAndShifts {
+        return Err(ValidationError::new(String::from(
+            "Shifts can only be filtered by tag when they're included",
+        ))
+        .into());
+    }
+    let days = query_params
+        .days
+        .as_deref()
+        .map(|days| {
+            days.split(',')
+                .map(|day| Day::from_str(day.trim()))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .transpose()?;
+    if days.is_some() && include != ProjectInclude::MembersAndShifts {
+        return Err(ValidationError::new(String::from(
+            "Shifts can only be filtered by day when they're included",
+        ))
+        .into());
+    }
+    let department_id = query_params.department_id.map(DepartmentId::new);
+    if department_id.is_some() && include == ProjectInclude::Nothing {
+        return Err(ValidationError::new(String::from(
+            "Members can only be filtered by department when they're included",
+        ))
+        .into());
+    }
+    if let Some(limit) = query_params.limit {
+        if !(1..=MAX_LIMIT).contains(&limit) {
+            return Err(ValidationError::new(format!(
+                "Limit must be between 1 and {}",
+                MAX_LIMIT
+            ))
+            .into());
+        }
+    }
+    let offset = query_params.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(ValidationError::new(String::from(
+            "Offset cannot be negative",
+        ))
+        .into());
+    }
+    if (query_params.limit.is_some() || offset > 0)
+        && include == ProjectInclude::Nothing
+    {
+        return Err(ValidationError::new(String::from(
+            "Members can only be paged through when they're included",
+        ))
+        .into());
+    }
 
-    let project = state
+    // Fetch one extra member to tell whether there is another page
+    let window = ProjectWindow {
+        department_id,
+        days,
+        offset,
+        limit: query_params.limit.map(|limit| limit + 1),
+    };
+    let mut project = state
         .project_store
         .write()
         .await
-        .get_project(&user_id, &project_id)
+        .get_project_with(&user_id, &project_id, include, &window)
         .await
         .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
 
-    let response = Json(project);
+    let has_more = query_params
+        .limit
+        .is_some_and(|limit| project.members.len() as i64 > limit);
+    if let Some(limit) = query_params.limit {
+        project.members.truncate(limit as usize);
+    }
+    if let Some(tag) = tag {
+        for member in project.members.iter_mut() {
+            member.shifts.retain(|shift| shift.has_tag(&tag));
+        }
+    }
+
+    let response = Json(ProjectResponse::new(project, include, has_more));
 
     Ok((StatusCode::OK, jar, response))
 }
+
+// A Project, less whatever wasn't included. Relations left out are missing
+// rather than empty.
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    #[serde(rename = "projectName")]
+    pub project_name: ProjectName,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
+    // By name, then by ID for members with the same name, so the order is
+    // the same every time. Each member's shifts are in the order they come
+    // in the project's week.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub members: Option<Vec<ProjectMemberResponse>>,
+    // Whether there are members after these, when fetching them a page at
+    // a time
+    #[serde(rename = "hasMore", skip_serializing_if = "Option::is_none")]
+    pub has_more: Option<bool>,
+}
+
+impl ProjectResponse {
+    fn new(project: Project, include: ProjectInclude, has_more: bool) -> Self {
+        Self {
+            project_id: project.project_id,
+            project_name: project.project_name,
+            week_start: project.week_start,
+            members: (include >= ProjectInclude::Members).then(|| {
+                project
+                    .members
+                    .into_iter()
+                    .map(|member| ProjectMemberResponse::new(member, include))
+                    .collect()
+            }),
+            has_more: (include >= ProjectInclude::Members).then_some(has_more),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectMemberResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub contracted_hours: Option<ContractedHours>,
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<DepartmentId>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shifts: Option<Vec<Shift>>,
+}
+
+impl ProjectMemberResponse {
+    fn new(member: ProjectMember, include: ProjectInclude) -> Self {
+        Self {
+            member_id: member.member_id,
+            member_name: member.member_name,
+            contracted_hours: member.contracted_hours,
+            department_id: member.department_id,
+            skills: member.skills,
+            shifts: (include == ProjectInclude::MembersAndShifts)
+                .then_some(member.shifts),
+        }
+    }
+}
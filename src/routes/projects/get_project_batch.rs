@@ -0,0 +1,106 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Day, ProjectAPIError, ProjectId, ProjectName, ProjectSummary,
+        ValidationError,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+const MAX_PROJECTS: usize = 50;
+
+#[derive(Deserialize)]
+pub struct ProjectBatchQueryParams {
+    // Comma-separated project IDs
+    pub ids: String,
+}
+
+// Summaries of several of the user's projects in one go, for dashboards.
+// Any project that isn't the user's fails the lot with a 404, as fetching
+// it on its own would.
+#[tracing::instrument(name = "Get project batch route handler", skip_all)]
+pub async fn get_project_batch(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ProjectBatchQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<ProjectBatchResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+
+    let mut project_ids: Vec<ProjectId> = Vec::new();
+    for id in query_params.ids.split(',').map(str::trim) {
+        let project_id =
+            uuid::Uuid::parse_str(id).map(ProjectId::new).map_err(|_| {
+                ValidationError::new(format!("Invalid project ID: {}", id))
+            })?;
+        if !project_ids.contains(&project_id) {
+            project_ids.push(project_id);
+        }
+    }
+    if project_ids.len() > MAX_PROJECTS {
+        return Err(ValidationError::new(format!(
+            "At most {} projects can be fetched at once",
+            MAX_PROJECTS
+        ))
+        .into());
+    }
+
+    let mut summaries = state
+        .project_store
+        .write()
+        .await
+        .get_project_summaries(&user_id, &project_ids)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+
+    // In the order asked for
+    let mut projects = Vec::with_capacity(project_ids.len());
+    for project_id in project_ids {
+        let Some(index) = summaries
+            .iter()
+            .position(|summary| summary.project_id == project_id)
+        else {
+            return Err(ProjectAPIError::ProjectNotFound(project_id));
+        };
+        projects
+            .push(ProjectSummaryResponse::from(summaries.swap_remove(index)));
+    }
+
+    Ok((StatusCode::OK, jar, Json(ProjectBatchResponse { projects })))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectBatchResponse {
+    pub projects: Vec<ProjectSummaryResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectSummaryResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    #[serde(rename = "projectName")]
+    pub project_name: ProjectName,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
+    pub members: i64,
+    pub shifts: i64,
+}
+
+impl From<ProjectSummary> for ProjectSummaryResponse {
+    fn from(summary: ProjectSummary) -> Self {
+        Self {
+            project_id: summary.project_id,
+            project_name: summary.project_name,
+            week_start: summary.week_start,
+            members: summary.members,
+            shifts: summary.shifts,
+        }
+    }
+}
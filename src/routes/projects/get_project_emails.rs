@@ -0,0 +1,96 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        EmailReceipt, EmailStatus, ProjectAPIError, ProjectId,
+        ProjectStoreError,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ProjectEmailsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+// The emails sent about a project, such as rota approval requests, and
+// whether they were delivered and opened
+#[tracing::instrument(name = "Get project emails route handler", skip_all)]
+pub async fn get_project_emails(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ProjectEmailsQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ProjectEmailListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let emails = state
+        .project_store
+        .write()
+        .await
+        .get_project_emails(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(ProjectEmailListResponse {
+        project_id,
+        emails: emails.into_iter().map(ProjectEmailResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectEmailListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // Newest first
+    pub emails: Vec<ProjectEmailResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectEmailResponse {
+    pub recipient: String,
+    pub subject: String,
+    pub status: EmailStatus,
+    #[serde(rename = "queuedAt")]
+    pub queued_at: String,
+    #[serde(rename = "sentAt")]
+    pub sent_at: Option<String>,
+    // Whether opening the email could be recorded; the recipient may have
+    // turned it off
+    pub tracked: bool,
+    #[serde(rename = "openedAt")]
+    pub opened_at: Option<String>,
+}
+
+impl From<EmailReceipt> for ProjectEmailResponse {
+    fn from(receipt: EmailReceipt) -> Self {
+        Self {
+            status: receipt.status(),
+            recipient: receipt.recipient,
+            subject: receipt.subject,
+            queued_at: receipt.queued_at.to_rfc3339(),
+            sent_at: receipt.sent_at.map(|sent_at| sent_at.to_rfc3339()),
+            tracked: receipt.tracked,
+            opened_at: receipt
+                .opened_at
+                .map(|opened_at| opened_at.to_rfc3339()),
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        EntityType, HistoryCursor, ProjectAPIError, ProjectId,
+        ProjectStoreError, ValidationError,
+    },
+    utils::{auth::Claims, query::ValidatedQuery},
+    AppState,
+};
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 100;
+
+#[derive(Deserialize)]
+pub struct GetProjectHistoryQueryParams {
+    #[serde(rename = "projectId")]
+    project_id: uuid::Uuid,
+    #[serde(rename = "entityType")]
+    entity_type: Option<String>,
+    limit: Option<i64>,
+    offset: Option<i64>,
+    // From `nextCursor`. Unlike an offset, entries added meanwhile don't
+    // shift the page, and later pages are as quick to fetch as the first.
+    cursor: Option<String>,
+}
+
+#[tracing::instrument(name = "Get project history route handler", skip_all)]
+pub async fn get_project_history(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<GetProjectHistoryQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ProjectHistoryResponse>),
+    ProjectAPIError,
+> {
+    tracing::debug!("user_id: {}", claims.id.as_ref().to_string());
+
+    let project_id = ProjectId::new(query_params.project_id);
+    tracing::debug!("project_id: {}", project_id.as_ref().to_string());
+
+    let entity_type = query_params
+        .entity_type
+        .as_deref()
+        .map(EntityType::from_str)
+        .transpose()?;
+
+    let limit = query_params.limit.unwrap_or(DEFAULT_LIMIT);
+    if !(1..=MAX_LIMIT).contains(&limit) {
+        return Err(ValidationError::new(format!(
+            "Limit must be between 1 and {}",
+            MAX_LIMIT
+        ))
+        .into());
+    }
+
+    let offset = query_params.offset.unwrap_or(0);
+    if offset < 0 {
+        return Err(ValidationError::new(String::from(
+            "Offset cannot be negative",
+        ))
+        .into());
+    }
+
+    let cursor = query_params
+        .cursor
+        .as_deref()
+        .map(HistoryCursor::parse)
+        .transpose()?;
+    if cursor.is_some() && query_params.offset.is_some() {
+        return Err(ValidationError::new(String::from(
+            "Use either a cursor or an offset, not both",
+        ))
+        .into());
+    }
+
+    // Fetch one extra entry to tell whether there is another page
+    let mut history = state
+        .project_store
+        .write()
+        .await
+        .get_project_history(
+            &claims.id,
+            &project_id,
+            entity_type,
+            cursor,
+            limit + 1,
+            offset,
+        )
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let has_more = history.len() as i64 > limit;
+    history.truncate(limit as usize);
+    let next_cursor = history
+        .last()
+        .filter(|_| has_more)
+        .map(|entry| HistoryCursor::after(entry).encode());
+    // Offsets can't be mixed with cursors, so paging by cursor carries on
+    // by cursor
+    let next_offset = (has_more && cursor.is_none()).then_some(offset + limit);
+
+    let response = Json(ProjectHistoryResponse {
+        project_id,
+        entries: history
+            .into_iter()
+            .filter_map(|entry| {
+                let entity_type = entry.event.entity_type()?;
+                let actor = if entry.event.user_id() == &claims.id {
+                    claims.sub.as_str()
+                } else {
+                    "A former user"
+                };
+                Some(ProjectHistoryItem {
+                    id: entry.id,
+                    entity_type,
+                    occurred_at: entry.occurred_at.to_rfc3339(),
+                    description: entry.event.describe(actor),
+                })
+            })
+            .collect(),
+        next_offset,
+        next_cursor,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectHistoryResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    pub entries: Vec<ProjectHistoryItem>,
+    #[serde(rename = "nextOffset")]
+    pub next_offset: Option<i64>,
+    #[serde(rename = "nextCursor")]
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectHistoryItem {
+    pub id: i64,
+    #[serde(rename = "entityType")]
+    pub entity_type: EntityType,
+    #[serde(rename = "occurredAt")]
+    pub occurred_at: String,
+    pub description: String,
+}
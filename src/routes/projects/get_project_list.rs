@@ -1,21 +1,23 @@
 use axum::{extract::State, http::StatusCode, Json};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     domain::{ProjectAPIError, ProjectId, ProjectName},
-    utils::auth::get_claims,
+    utils::auth::Claims,
     AppState,
 };
 
 #[tracing::instrument(name = "Get project list route handler", skip_all)]
 pub async fn get_project_list(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
 ) -> Result<(StatusCode, CookieJar, Json<ProjectListResponse>), ProjectAPIError>
 {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+    let user_id = claims.id;
 
     let project_list = state
         .project_store
@@ -35,12 +37,12 @@ pub async fn get_project_list(
     Ok((StatusCode::OK, jar, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectListResponse {
     pub projects: Vec<Project>,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct Project {
     pub id: ProjectId,
     pub name: ProjectName,
@@ -0,0 +1,109 @@
+use axum::{extract::State, http::StatusCode, Json};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        BreakRule, Day, EscalationStep, ProjectAPIError, ProjectId,
+        ProjectSettings, ProjectStoreError, ShiftGranularity, ShiftLength,
+    },
+    utils::{auth::Claims, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ProjectSettingsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Get project settings route handler", skip_all)]
+pub async fn get_project_settings(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ProjectSettingsQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ProjectSettingsResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let settings = state
+        .project_store
+        .write()
+        .await
+        .get_project_settings(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(ProjectSettingsResponse::new(project_id, settings)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectSettingsResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    #[serde(rename = "shiftGranularity")]
+    pub shift_granularity: ShiftGranularity,
+    #[serde(rename = "minShiftLength")]
+    pub min_shift_length: ShiftLength,
+    #[serde(rename = "maxShiftLength")]
+    pub max_shift_length: ShiftLength,
+    #[serde(rename = "breakRules")]
+    pub break_rules: Vec<BreakRule>,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
+    #[serde(rename = "escalationSteps")]
+    pub escalation_steps: Vec<EscalationStep>,
+    #[serde(rename = "chatWebhookUrl")]
+    pub chat_webhook_url: Option<String>,
+    // E.164, e.g. +447700900123
+    #[serde(rename = "escalationPhone")]
+    pub escalation_phone: Option<String>,
+    // A crontab schedule in UTC, e.g. "0 17 * * FRI"
+    #[serde(rename = "autoPublish")]
+    pub auto_publish: Option<String>,
+    // Rotas are submitted to them for approval rather than published
+    #[serde(rename = "approverEmail")]
+    pub approver_email: Option<String>,
+}
+
+impl ProjectSettingsResponse {
+    pub fn new(project_id: ProjectId, settings: ProjectSettings) -> Self {
+        Self {
+            project_id,
+            shift_granularity: settings.shift_granularity,
+            min_shift_length: settings.min_shift_length,
+            max_shift_length: settings.max_shift_length,
+            break_rules: settings.break_rules,
+            week_start: settings.week_start,
+            escalation_steps: settings.escalation_steps,
+            chat_webhook_url: settings
+                .chat_webhook_url
+                .map(|url| url.as_ref().to_owned()),
+            escalation_phone: settings
+                .escalation_phone
+                .map(|number| number.as_ref().to_owned()),
+            auto_publish: settings
+                .auto_publish
+                .map(|schedule| schedule.as_ref().to_owned()),
+            approver_email: settings
+                .approver_email
+                .map(|email| email.as_ref().expose_secret().to_owned()),
+        }
+    }
+}
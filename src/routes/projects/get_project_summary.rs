@@ -0,0 +1,109 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use chrono::Days;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        Day, MemberId, MemberName, MemberShiftTotals, ProjectAPIError,
+        ProjectId, ProjectName,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ProjectSummaryQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+// Counts and each member's shifts over the next seven days, without the
+// shifts themselves, for the mobile app's home screen on slow connections.
+// The totals are added up by the database rather than from loaded shifts.
+#[tracing::instrument(name = "Get project summary route handler", skip_all)]
+pub async fn get_project_summary(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ProjectSummaryQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ProjectOverviewResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+    let from = state.clock.now().date_naive();
+
+    let mut project_store = state.project_store.write().await;
+    let summary = project_store
+        .get_project_summaries(&user_id, std::slice::from_ref(&project_id))
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?
+        .pop()
+        .ok_or_else(|| ProjectAPIError::ProjectNotFound(project_id.clone()))?;
+    let upcoming = project_store
+        .get_upcoming_shift_totals(&user_id, &project_id, from)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    drop(project_store);
+
+    let response = ProjectOverviewResponse {
+        project_id: summary.project_id,
+        project_name: summary.project_name,
+        week_start: summary.week_start,
+        members: summary.members,
+        shifts: summary.shifts,
+        from: from.to_string(),
+        to: (from + Days::new(6)).to_string(),
+        upcoming: upcoming
+            .into_iter()
+            .map(UpcomingShiftTotals::from)
+            .collect(),
+    };
+
+    Ok((StatusCode::OK, jar, Json(response)))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectOverviewResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    #[serde(rename = "projectName")]
+    pub project_name: ProjectName,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Day,
+    pub members: i64,
+    pub shifts: i64,
+    // YYYY-MM-DD, inclusive; today and the six days after it
+    pub from: String,
+    pub to: String,
+    pub upcoming: Vec<UpcomingShiftTotals>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct UpcomingShiftTotals {
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    // Shifts the member is down to work, less those they're off for
+    pub shifts: i64,
+    // Paid minutes across those shifts
+    pub minutes: i64,
+    pub absences: i64,
+}
+
+impl From<MemberShiftTotals> for UpcomingShiftTotals {
+    fn from(totals: MemberShiftTotals) -> Self {
+        Self {
+            member_id: totals.member_id,
+            member_name: totals.member_name,
+            shifts: totals.shifts,
+            minutes: totals.minutes,
+            absences: totals.absences,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::submit_rota::RotaApprovalResponse;
+use crate::{
+    domain::{ProjectAPIError, ProjectId, ProjectStoreError},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RotaApprovalsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Get rota approvals route handler", skip_all)]
+pub async fn get_rota_approvals(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<RotaApprovalsQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<RotaApprovalListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let approvals = state
+        .project_store
+        .write()
+        .await
+        .get_rota_approvals(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(RotaApprovalListResponse {
+        project_id,
+        approvals: approvals
+            .into_iter()
+            .map(RotaApprovalResponse::from)
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaApprovalListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // Newest first
+    pub approvals: Vec<RotaApprovalResponse>,
+}
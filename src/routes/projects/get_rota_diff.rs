@@ -0,0 +1,115 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        published_shifts, ProjectAPIError, ProjectId, ProjectStoreError,
+        RotaChanges, RotaVersionId,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RotaDiffQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Defaults to the version published most recently
+    pub from: Option<uuid::Uuid>,
+    // Defaults to the project's shifts as they stand
+    pub to: Option<uuid::Uuid>,
+}
+
+// How two published versions of a project's rota differ, or how the rota
+// as it stands differs from one that was published
+#[tracing::instrument(name = "Get rota diff route handler", skip_all)]
+pub async fn get_rota_diff(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<RotaDiffQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<RotaDiffResponse>), ProjectAPIError> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    // Only looking up a version by ID can fail to find it
+    let map_store_error =
+        |e, version_id: Option<uuid::Uuid>| match (e, version_id) {
+            (ProjectStoreError::ProjectIDNotFound, _) => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            (ProjectStoreError::VersionIDNotFound, Some(version_id)) => {
+                ProjectAPIError::RotaVersionNotFound(RotaVersionId::new(
+                    version_id,
+                ))
+            }
+            (e, _) => ProjectAPIError::UnexpectedError(eyre!(e)),
+        };
+
+    let mut project_store = state.project_store.write().await;
+    let from = match query_params.from {
+        Some(version_id) => Some(
+            project_store
+                .get_rota_version(
+                    &user_id,
+                    &project_id,
+                    &RotaVersionId::new(version_id),
+                )
+                .await
+                .map_err(|e| map_store_error(e, Some(version_id)))?,
+        ),
+        None => project_store
+            .get_latest_rota_version(&user_id, &project_id)
+            .await
+            .map_err(|e| map_store_error(e, None))?,
+    };
+    let to = match query_params.to {
+        Some(version_id) => {
+            project_store
+                .get_rota_version(
+                    &user_id,
+                    &project_id,
+                    &RotaVersionId::new(version_id),
+                )
+                .await
+                .map_err(|e| map_store_error(e, Some(version_id)))?
+                .shifts
+        }
+        None => published_shifts(
+            &project_store
+                .get_project(&user_id, &project_id)
+                .await
+                .map_err(|e| map_store_error(e, None))?,
+        ),
+    };
+    drop(project_store);
+
+    let changes = RotaChanges::between(
+        from.as_ref()
+            .map(|version| &version.shifts[..])
+            .unwrap_or(&[]),
+        &to,
+    );
+    let response = Json(RotaDiffResponse {
+        project_id,
+        from: from.map(|version| *version.version_id.as_ref()),
+        to: query_params.to,
+        changes,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaDiffResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // Null when nothing has been published, so every shift is added
+    pub from: Option<uuid::Uuid>,
+    // Null for the project's shifts as they stand
+    pub to: Option<uuid::Uuid>,
+    pub changes: RotaChanges,
+}
@@ -0,0 +1,158 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        MemberId, MemberName, ProjectAPIError, ProjectId, ProjectStoreError,
+        Reaction, RotaReaction, RotaVersionId,
+    },
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct RotaReactionsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Defaults to the version published most recently
+    #[serde(rename = "versionId")]
+    pub version_id: Option<uuid::Uuid>,
+}
+
+// Who has reacted to a published rota, by texting an emoji or SEEN, and who
+// hasn't yet
+#[tracing::instrument(name = "Get rota reactions route handler", skip_all)]
+pub async fn get_rota_reactions(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<RotaReactionsQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<RotaReactionsResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let map_store_error = |e| match (e, query_params.version_id) {
+        (ProjectStoreError::ProjectIDNotFound, _) => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        (ProjectStoreError::VersionIDNotFound, Some(version_id)) => {
+            ProjectAPIError::RotaVersionNotFound(RotaVersionId::new(version_id))
+        }
+        (e, _) => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project_store = state.project_store.write().await;
+    let version = match query_params.version_id {
+        Some(version_id) => Some(
+            project_store
+                .get_rota_version(
+                    &user_id,
+                    &project_id,
+                    &RotaVersionId::new(version_id),
+                )
+                .await
+                .map_err(map_store_error)?,
+        ),
+        None => project_store
+            .get_latest_rota_version(&user_id, &project_id)
+            .await
+            .map_err(map_store_error)?,
+    };
+    let Some(version) = version else {
+        return Ok((
+            StatusCode::OK,
+            jar,
+            Json(RotaReactionsResponse {
+                project_id,
+                version_id: None,
+                week_of: None,
+                reactions: Vec::new(),
+                waiting: Vec::new(),
+            }),
+        ));
+    };
+    let reactions = project_store
+        .get_rota_reactions(&user_id, &version.version_id)
+        .await
+        .map_err(map_store_error)?;
+    let mut members = project_store
+        .get_members(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+    drop(project_store);
+
+    members.retain(|member| {
+        !reactions
+            .iter()
+            .any(|reaction| reaction.member_id == member.member_id)
+    });
+    members.sort_by(|a, b| a.member_name.as_ref().cmp(b.member_name.as_ref()));
+
+    let response = Json(RotaReactionsResponse {
+        project_id,
+        version_id: Some(*version.version_id.as_ref()),
+        week_of: Some(version.week_of.to_string()),
+        reactions: reactions
+            .into_iter()
+            .map(RotaReactionResponse::from)
+            .collect(),
+        waiting: members
+            .into_iter()
+            .map(|member| WaitingMemberResponse {
+                member_id: member.member_id,
+                member_name: member.member_name,
+            })
+            .collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaReactionsResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // Null, with nothing else, until a rota is published
+    #[serde(rename = "versionId")]
+    pub version_id: Option<uuid::Uuid>,
+    #[serde(rename = "weekOf")]
+    pub week_of: Option<String>,
+    // Oldest first
+    pub reactions: Vec<RotaReactionResponse>,
+    // Members who haven't reacted, by name
+    pub waiting: Vec<WaitingMemberResponse>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaReactionResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    pub reaction: Reaction,
+    #[serde(rename = "reactedAt")]
+    pub reacted_at: String,
+}
+
+impl From<RotaReaction> for RotaReactionResponse {
+    fn from(reaction: RotaReaction) -> Self {
+        Self {
+            member_id: reaction.member_id,
+            member_name: reaction.member_name,
+            reaction: reaction.reaction,
+            reacted_at: reaction.reacted_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WaitingMemberResponse {
+    #[serde(rename = "memberId")]
+    pub member_id: MemberId,
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+}
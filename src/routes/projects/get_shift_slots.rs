@@ -0,0 +1,73 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::add_shift_slot::ShiftSlotResponse;
+use crate::{
+    domain::{DepartmentId, ProjectAPIError, ProjectId, ProjectStoreError},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct ShiftSlotsQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Only include slots for this department
+    #[serde(rename = "departmentId")]
+    pub department_id: Option<uuid::Uuid>,
+}
+
+// The project's slots in the order they come in its week, with how many of
+// each one's places are still open
+#[tracing::instrument(name = "Get shift slots route handler", skip_all)]
+pub async fn get_shift_slots(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ShiftSlotsQueryParams>,
+) -> Result<(StatusCode, CookieJar, Json<ShiftSlotListResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let mut coverage = state
+        .project_store
+        .write()
+        .await
+        .get_slot_coverage(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    if let Some(department_id) =
+        query_params.department_id.map(DepartmentId::new)
+    {
+        coverage.retain(|slot| {
+            slot.slot.department_id.as_ref() == Some(&department_id)
+        });
+    }
+
+    let uncovered = coverage.iter().filter(|slot| !slot.is_covered()).count();
+    let response = Json(ShiftSlotListResponse {
+        project_id: *project_id.as_ref(),
+        uncovered,
+        slots: coverage.into_iter().map(ShiftSlotResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ShiftSlotListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Slots with places still open
+    pub uncovered: usize,
+    pub slots: Vec<ShiftSlotResponse>,
+}
@@ -0,0 +1,60 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::save_week_template::WeekTemplateResponse;
+use crate::{
+    domain::{ProjectAPIError, ProjectId, ProjectStoreError},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+#[derive(Deserialize)]
+pub struct WeekTemplatesQueryParams {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+}
+
+#[tracing::instrument(name = "Get week templates route handler", skip_all)]
+pub async fn get_week_templates(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<WeekTemplatesQueryParams>,
+) -> Result<
+    (StatusCode, CookieJar, Json<WeekTemplateListResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let templates = state
+        .project_store
+        .write()
+        .await
+        .get_week_templates(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    let response = Json(WeekTemplateListResponse {
+        project_id,
+        templates: templates.iter().map(WeekTemplateResponse::from).collect(),
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WeekTemplateListResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: ProjectId,
+    // In name order
+    pub templates: Vec<WeekTemplateResponse>,
+}
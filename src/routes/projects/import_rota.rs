@@ -0,0 +1,228 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        check_overlaps, Day, ImportFormat, ImportProblem, Member, MemberName,
+        Minute, ProjectAPIError, ProjectId, ProjectStoreError, RotaImport,
+        Shift, ShiftTag, ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Import a rota exported as CSV from another rota tool, adding members for
+// names the project doesn't have yet. A dry run previews what would be
+// imported and every problem with the file; otherwise nothing is imported
+// unless the whole file can be.
+#[tracing::instrument(name = "Import rota route handler", skip_all)]
+pub async fn import_rota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ImportRotaRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ImportRotaResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let format = request
+        .format
+        .as_deref()
+        .map(ImportFormat::from_str)
+        .transpose()?;
+    let import = RotaImport::parse(&request.csv, format)?;
+
+    let mut project_store = state.project_store.write().await;
+    let settings = project_store
+        .get_project_settings(&user_id, &project_id)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    let mut members = project_store
+        .get_members(&user_id, &project_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+    let existing: Vec<Shift> = project_store
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?
+        .members
+        .into_iter()
+        .flat_map(|member| member.shifts)
+        .collect();
+    drop(project_store);
+
+    let new_members: Vec<Member> = import
+        .new_member_names(&members)
+        .into_iter()
+        .map(|name| {
+            Member::new(
+                project_id.clone(),
+                state.id_generator.member_id(),
+                name,
+            )
+        })
+        .collect();
+    members.extend(new_members.iter().cloned());
+
+    let (mut shifts, mut problems) = import.instantiate(&settings, &members);
+    problems.extend(import.problems.iter().cloned());
+    problems.sort_by_key(|problem| problem.line);
+    for shift in &mut shifts {
+        shift.id = state.id_generator.shift_id();
+    }
+    let kept = if request.replace { &[][..] } else { &existing };
+    if let Err(e) = check_overlaps(kept, &shifts, &members) {
+        problems.push(ImportProblem {
+            line: None,
+            message: e.as_ref().to_owned(),
+        });
+    }
+
+    let replaced = if request.dry_run {
+        if request.replace {
+            existing.len() as u64
+        } else {
+            0
+        }
+    } else {
+        if let Some(problem) = problems.first() {
+            return Err(ValidationError::new(format!(
+                "{} ({} problem{} in all, which a dry run lists)",
+                problem,
+                problems.len(),
+                if problems.len() == 1 { "" } else { "s" }
+            ))
+            .into());
+        }
+        if shifts.is_empty() {
+            return Err(ValidationError::new(String::from(
+                "The file has no shifts to import",
+            ))
+            .into());
+        }
+        state
+            .project_store
+            .write()
+            .await
+            .import_rota(
+                &user_id,
+                &project_id,
+                &new_members,
+                &shifts,
+                request.replace,
+            )
+            .await
+            .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?
+    };
+
+    let response = Json(ImportRotaResponse {
+        project_id: request.project_id,
+        format: import.format.to_string(),
+        dry_run: request.dry_run,
+        new_members: new_members
+            .into_iter()
+            .map(|member| member.member_name)
+            .collect(),
+        shifts: shifts
+            .iter()
+            .filter_map(|shift| {
+                let member = members
+                    .iter()
+                    .find(|member| member.member_id == shift.member_id)?;
+                Some(ImportedShiftResponse::new(shift, member))
+            })
+            .collect(),
+        problems: problems
+            .into_iter()
+            .map(ImportProblemResponse::from)
+            .collect(),
+        replaced,
+    });
+
+    Ok((StatusCode::OK, jar, response))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ImportRotaRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // The exported file's contents
+    pub csv: String,
+    // deputy or when-i-work, worked out from the file's columns when left
+    // out
+    #[serde(default)]
+    pub format: Option<String>,
+    #[serde(rename = "dryRun", default)]
+    pub dry_run: bool,
+    // Clear the project's shifts first, rather than adding to them
+    #[serde(default)]
+    pub replace: bool,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImportRotaResponse {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub format: String,
+    #[serde(rename = "dryRun")]
+    pub dry_run: bool,
+    // Members added for names the project didn't have, or who would be on
+    // a dry run
+    #[serde(rename = "newMembers")]
+    pub new_members: Vec<MemberName>,
+    pub shifts: Vec<ImportedShiftResponse>,
+    // Only ever on a dry run, since problems stop an import
+    pub problems: Vec<ImportProblemResponse>,
+    // How many shifts were cleared to make way, or would be on a dry run
+    pub replaced: u64,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImportedShiftResponse {
+    #[serde(rename = "memberName")]
+    pub member_name: MemberName,
+    pub day: Day,
+    #[serde(rename = "startTime")]
+    pub start_time: Minute,
+    #[serde(rename = "endTime")]
+    pub end_time: Minute,
+    pub tags: Vec<ShiftTag>,
+}
+
+impl ImportedShiftResponse {
+    fn new(shift: &Shift, member: &Member) -> Self {
+        Self {
+            member_name: member.member_name.clone(),
+            day: shift.day,
+            start_time: shift.start_time.clone(),
+            end_time: shift.end_time.clone(),
+            tags: shift.tags.clone(),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ImportProblemResponse {
+    // The line of the file at fault, if it's down to one line
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl From<ImportProblem> for ImportProblemResponse {
+    fn from(problem: ImportProblem) -> Self {
+        Self {
+            line: problem.line,
+            message: problem.message,
+        }
+    }
+}
@@ -1,17 +1,91 @@
+mod add_comment;
+mod add_department;
 mod add_member;
 mod add_shift;
+mod add_shift_slot;
+mod apply_week_template;
+mod broadcast_open_shift;
+mod clear_shifts;
+mod delete_comment;
+mod fill_shift_slot;
+mod get_attendance_report;
+mod get_borrow_suggestions;
+mod get_comments;
+mod get_departments;
 mod get_member;
+mod get_member_contact;
 mod get_members;
+mod get_printed_rota;
 mod get_project;
+mod get_project_batch;
+mod get_project_emails;
+mod get_project_history;
 mod get_project_list;
+mod get_project_settings;
+mod get_project_summary;
+mod get_rota_approvals;
+mod get_rota_diff;
+mod get_rota_reactions;
+mod get_shift_slots;
+mod get_week_templates;
+mod import_rota;
 mod new_project;
+mod publish_rota;
+mod report_absence;
+mod save_week_template;
+mod submit_rota;
 mod update_member;
+mod update_member_contact;
+mod update_project_settings;
+mod validate_shift;
 
-pub use add_member::add_member;
-pub use add_shift::add_shift;
-pub use get_member::get_member;
-pub use get_members::get_member_list_for_project;
-pub use get_project::get_project;
-pub use get_project_list::get_project_list;
-pub use new_project::new_project;
-pub use update_member::update_member;
+pub use add_comment::{add_comment, AddCommentResponse, CommentResponse};
+pub use add_department::{add_department, DepartmentResponse};
+pub use add_member::{add_member, AddMemberResponse};
+pub use add_shift::{add_shift, AddShiftResponse};
+pub use add_shift_slot::{add_shift_slot, ShiftSlotResponse};
+pub use apply_week_template::{apply_week_template, ApplyWeekTemplateResponse};
+pub use broadcast_open_shift::{
+    broadcast_open_shift, OpenShiftBroadcastResponse,
+};
+pub use clear_shifts::{clear_shifts, ClearShiftsResponse};
+pub use delete_comment::delete_comment;
+pub use fill_shift_slot::fill_shift_slot;
+pub use get_attendance_report::{
+    get_attendance_report, AttendanceReportResponse, MemberAttendanceResponse,
+};
+pub use get_borrow_suggestions::{
+    get_borrow_suggestions, BorrowSuggestionsResponse,
+};
+pub use get_comments::{get_comments, CommentListResponse};
+pub use get_departments::{get_departments, DepartmentListResponse};
+pub use get_member::{get_member, MemberResponse};
+pub use get_member_contact::{
+    get_member_contact, MemberContactQueryParams, MemberContactResponse,
+};
+pub use get_members::{get_member_list_for_project, MemberListResponse};
+pub use get_printed_rota::get_printed_rota;
+pub use get_project::{get_project, ProjectResponse};
+pub use get_project_batch::{get_project_batch, ProjectBatchResponse};
+pub use get_project_emails::{get_project_emails, ProjectEmailListResponse};
+pub use get_project_history::{get_project_history, ProjectHistoryResponse};
+pub use get_project_list::{get_project_list, ProjectListResponse};
+pub use get_project_settings::{
+    get_project_settings, ProjectSettingsQueryParams, ProjectSettingsResponse,
+};
+pub use get_project_summary::{get_project_summary, ProjectOverviewResponse};
+pub use get_rota_approvals::{get_rota_approvals, RotaApprovalListResponse};
+pub use get_rota_diff::{get_rota_diff, RotaDiffResponse};
+pub use get_rota_reactions::{get_rota_reactions, RotaReactionsResponse};
+pub use get_shift_slots::{get_shift_slots, ShiftSlotListResponse};
+pub use get_week_templates::{get_week_templates, WeekTemplateListResponse};
+pub use import_rota::{import_rota, ImportRotaResponse};
+pub use new_project::{new_project, NewProjectResponse};
+pub use publish_rota::{publish_rota, RotaVersionResponse};
+pub use report_absence::{report_absence, AbsenceResponse};
+pub use save_week_template::{save_week_template, WeekTemplateResponse};
+pub use submit_rota::{submit_rota, RotaApprovalResponse};
+pub use update_member::{update_member, UpdateMemberResponse};
+pub use update_member_contact::update_member_contact;
+pub use update_project_settings::update_project_settings;
+pub use validate_shift::{validate_shift, ValidateShiftResponse};
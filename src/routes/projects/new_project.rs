@@ -1,23 +1,36 @@
-use axum::{extract::State, http::StatusCode, Json};
+use axum::{extract::State, http::StatusCode};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{ProjectAPIError, ProjectId, ProjectName},
-    utils::auth::get_claims,
+    domain::{ProjectAPIError, ProjectName, QuotaResource},
+    utils::{
+        auth::Claims,
+        json::Json,
+        quota::{quota_headers, QuotaHeaders},
+    },
     AppState,
 };
 
 #[tracing::instrument(name = "Create new project route handler", skip_all)]
 pub async fn new_project(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
     Json(request): Json<NewProjectRequest>,
-) -> Result<(StatusCode, CookieJar, Json<NewProjectResponse>), ProjectAPIError>
-{
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
-    let project_id = ProjectId::default();
+) -> Result<
+    (
+        StatusCode,
+        CookieJar,
+        QuotaHeaders,
+        Json<NewProjectResponse>,
+    ),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = state.id_generator.project_id();
     let project_name = ProjectName::parse(&request.name)?;
 
     state
@@ -33,12 +46,17 @@ pub async fn new_project(
         name: project_name.as_ref().to_string(),
     });
 
-    Ok((StatusCode::CREATED, jar, response))
+    let headers =
+        quota_headers(&state, &user_id, QuotaResource::Projects).await;
+
+    Ok((StatusCode::CREATED, jar, headers, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct NewProjectResponse {
+    #[schemars(length(min = 1, max = 255))]
     pub name: String,
+    #[schemars(length(equal = 36))]
     pub id: String,
 }
 
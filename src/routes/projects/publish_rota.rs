@@ -0,0 +1,154 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use chrono::{Days, NaiveDate};
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        publish_problems, publish_warnings,
+        time::{parse_date, week_beginning},
+        DateRange, Project, ProjectAPIError, ProjectId, ProjectSettings,
+        ProjectStoreError, PublishedShift, RotaVersion, UserId,
+        ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Publish the project's shifts as they stand for a week, unless the project
+// needs the rota approved first
+#[tracing::instrument(name = "Publish rota route handler", skip_all)]
+pub async fn publish_rota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<PublishRotaRequest>,
+) -> Result<(StatusCode, CookieJar, Json<RotaVersionResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+
+    let (project, settings, week_of) =
+        prepare_rota(&state, &user_id, &project_id, &request).await?;
+    if settings.approver_email.is_some() {
+        return Err(ValidationError::new(String::from(
+            "This project's rota has to be approved; submit it for approval \
+             instead",
+        ))
+        .into());
+    }
+
+    let warnings = publish_warnings(&project);
+    let version = RotaVersion::new(&project, week_of, false, state.clock.now());
+    state
+        .project_store
+        .write()
+        .await
+        .publish_rota(&user_id, &version)
+        .await
+        .map_err(|e| match e {
+            ProjectStoreError::ProjectIDNotFound => {
+                ProjectAPIError::ProjectNotFound(project_id.clone())
+            }
+            e => ProjectAPIError::UnexpectedError(eyre!(e)),
+        })?;
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(RotaVersionResponse::new(version, warnings)),
+    ))
+}
+
+// The project and its settings, and the week to publish, once it's clear
+// there is nothing stopping the rota being published
+pub(super) async fn prepare_rota(
+    state: &AppState,
+    user_id: &UserId,
+    project_id: &ProjectId,
+    request: &PublishRotaRequest,
+) -> Result<(Project, ProjectSettings, NaiveDate), ProjectAPIError> {
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project_store = state.project_store.write().await;
+    let settings = project_store
+        .get_project_settings(user_id, project_id)
+        .await
+        .map_err(map_store_error)?;
+    let week_of = match &request.week_of {
+        Some(date) => week_beginning(parse_date(date)?, settings.week_start),
+        None => {
+            week_beginning(state.clock.now().date_naive(), settings.week_start)
+                + Days::new(7)
+        }
+    };
+    let range = DateRange::new(week_of, week_of + Days::new(6))?;
+
+    let project = project_store
+        .get_project(user_id, project_id)
+        .await
+        .map_err(map_store_error)?;
+    let absences = project_store
+        .get_absences(user_id, project_id, &range)
+        .await
+        .map_err(map_store_error)?;
+    drop(project_store);
+
+    let problems = publish_problems(&project, &settings, &absences);
+    if !problems.is_empty() {
+        return Err(ValidationError::new(format!(
+            "The rota can't be published yet: {}",
+            problems.join("; ")
+        ))
+        .into());
+    }
+
+    Ok((project, settings, week_of))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct PublishRotaRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    // Any date in the week, which defaults to next week
+    #[serde(rename = "weekOf")]
+    pub week_of: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaVersionResponse {
+    #[serde(rename = "versionId")]
+    pub version_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    #[serde(rename = "weekOf")]
+    pub week_of: String,
+    pub shifts: Vec<PublishedShift>,
+    pub automatic: bool,
+    #[serde(rename = "publishedAt")]
+    pub published_at: String,
+    // Published anyway, but worth a look, such as members scheduled well
+    // under their contracted hours
+    pub warnings: Vec<String>,
+}
+
+impl RotaVersionResponse {
+    pub fn new(version: RotaVersion, warnings: Vec<String>) -> Self {
+        Self {
+            version_id: *version.version_id.as_ref(),
+            project_id: *version.project_id.as_ref(),
+            week_of: version.week_of.to_string(),
+            shifts: version.shifts,
+            automatic: version.automatic,
+            published_at: version.published_at.to_rfc3339(),
+            warnings,
+        }
+    }
+}
@@ -0,0 +1,99 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        time::parse_date, Absence, AbsenceReason, ProjectAPIError,
+        ProjectStoreError, ShiftId,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Mark one occurrence of a shift as missed. The gap is covered by the
+// shift's standby member if it has one, otherwise it is left open for
+// another member to claim.
+#[tracing::instrument(name = "Report absence route handler", skip_all)]
+pub async fn report_absence(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<ReportAbsenceRequest>,
+) -> Result<(StatusCode, CookieJar, Json<AbsenceResponse>), ProjectAPIError> {
+    let user_id = claims.id;
+    let shift_id = ShiftId::new(request.shift_id);
+    let date = parse_date(&request.date)?;
+    let reason = match &request.reason {
+        Some(reason) => AbsenceReason::from_str(reason)?,
+        None => AbsenceReason::Sickness,
+    };
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ShiftIDNotFound => {
+            ProjectAPIError::ShiftNotFound(shift_id.clone())
+        }
+        ProjectStoreError::AbsenceExists => {
+            ProjectAPIError::IDExistsError(*shift_id.as_ref())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let shift = state
+        .project_store
+        .write()
+        .await
+        .get_shift(&user_id, &shift_id)
+        .await
+        .map_err(map_store_error)?;
+
+    let absence = Absence::new(&shift, date, reason)?;
+
+    state
+        .project_store
+        .write()
+        .await
+        .add_absence(&user_id, &absence)
+        .await
+        .map_err(map_store_error)?;
+
+    let response = Json(AbsenceResponse {
+        id: absence.id,
+        shift_id: *absence.shift_id.as_ref(),
+        member_id: *absence.member_id.as_ref(),
+        date: absence.date.to_string(),
+        reason: absence.reason,
+        open: absence.is_open(),
+        standby_member_id: absence.standby_member_id.map(|id| *id.as_ref()),
+    });
+
+    Ok((StatusCode::CREATED, jar, response))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct ReportAbsenceRequest {
+    #[serde(rename = "shiftId")]
+    pub shift_id: uuid::Uuid,
+    // YYYY-MM-DD, on the shift's day of the week
+    pub date: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct AbsenceResponse {
+    pub id: uuid::Uuid,
+    #[serde(rename = "shiftId")]
+    pub shift_id: uuid::Uuid,
+    #[serde(rename = "memberId")]
+    pub member_id: uuid::Uuid,
+    pub date: String,
+    pub reason: AbsenceReason,
+    // Nobody is lined up to cover, so the shift is up for grabs
+    pub open: bool,
+    #[serde(rename = "standbyMemberId")]
+    pub standby_member_id: Option<uuid::Uuid>,
+}
@@ -0,0 +1,88 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    domain::{
+        ProjectAPIError, ProjectId, ProjectStoreError, WeekTemplate,
+        WeekTemplateName,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Save the project's week of shifts as it stands under a name, replacing
+// any template already saved under that name
+#[tracing::instrument(name = "Save week template route handler", skip_all)]
+pub async fn save_week_template(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<SaveWeekTemplateRequest>,
+) -> Result<(StatusCode, CookieJar, Json<WeekTemplateResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+    let name = WeekTemplateName::parse(&request.name)?;
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let project = state
+        .project_store
+        .write()
+        .await
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+
+    let mut template = WeekTemplate::new(&project, name);
+    template.template_id = state
+        .project_store
+        .write()
+        .await
+        .save_week_template(&user_id, &template)
+        .await
+        .map_err(map_store_error)?;
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(WeekTemplateResponse::from(&template)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct SaveWeekTemplateRequest {
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub name: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct WeekTemplateResponse {
+    #[serde(rename = "templateId")]
+    pub template_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    pub name: String,
+    #[serde(rename = "shiftCount")]
+    pub shift_count: usize,
+}
+
+impl From<&WeekTemplate> for WeekTemplateResponse {
+    fn from(template: &WeekTemplate) -> Self {
+        Self {
+            template_id: *template.template_id.as_ref(),
+            project_id: *template.project_id.as_ref(),
+            name: template.name.as_ref().clone(),
+            shift_count: template.shifts.len(),
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+
+use super::publish_rota::{prepare_rota, PublishRotaRequest};
+use crate::{
+    domain::{
+        ApprovalStatus, ProjectAPIError, ProjectId, ProjectStoreError,
+        PublishedShift, RotaApproval, RotaChanges, ValidationError,
+    },
+    utils::{auth::Claims, json::Json},
+    AppState,
+};
+
+// Send the project's shifts for a week to the project's approver, who sees
+// how they differ from the rota last published. Anything already waiting
+// for approval is withdrawn.
+#[tracing::instrument(name = "Submit rota route handler", skip_all)]
+pub async fn submit_rota(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    Json(request): Json<PublishRotaRequest>,
+) -> Result<(StatusCode, CookieJar, Json<RotaApprovalResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let project_id = ProjectId::new(request.project_id);
+
+    let (project, settings, week_of) =
+        prepare_rota(&state, &user_id, &project_id, &request).await?;
+    let Some(approver_email) = settings.approver_email else {
+        return Err(ValidationError::new(String::from(
+            "This project has no approver; publish the rota instead",
+        ))
+        .into());
+    };
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    let mut project_store = state.project_store.write().await;
+    let published = project_store
+        .get_latest_rota_version(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+    let approval = RotaApproval::new(
+        &project,
+        week_of,
+        false,
+        published.as_ref(),
+        approver_email,
+        state.clock.now(),
+    );
+    project_store
+        .submit_rota(&user_id, &approval)
+        .await
+        .map_err(map_store_error)?;
+    drop(project_store);
+
+    Ok((
+        StatusCode::CREATED,
+        jar,
+        Json(RotaApprovalResponse::from(approval)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct RotaApprovalResponse {
+    #[serde(rename = "approvalId")]
+    pub approval_id: uuid::Uuid,
+    #[serde(rename = "projectId")]
+    pub project_id: uuid::Uuid,
+    #[serde(rename = "projectName")]
+    pub project_name: String,
+    #[serde(rename = "weekOf")]
+    pub week_of: String,
+    pub shifts: Vec<PublishedShift>,
+    // Against the rota last published when this was submitted
+    pub changes: RotaChanges,
+    #[serde(rename = "approverEmail")]
+    pub approver_email: String,
+    pub status: ApprovalStatus,
+    pub comment: Option<String>,
+    #[serde(rename = "submittedAt")]
+    pub submitted_at: String,
+    #[serde(rename = "reviewedAt")]
+    pub reviewed_at: Option<String>,
+}
+
+impl From<RotaApproval> for RotaApprovalResponse {
+    fn from(approval: RotaApproval) -> Self {
+        Self {
+            approval_id: *approval.approval_id.as_ref(),
+            project_id: *approval.version.project_id.as_ref(),
+            project_name: approval.project_name.as_ref().to_owned(),
+            week_of: approval.version.week_of.to_string(),
+            shifts: approval.version.shifts,
+            changes: approval.changes,
+            approver_email: approval
+                .approver_email
+                .as_ref()
+                .expose_secret()
+                .to_owned(),
+            status: approval.status,
+            comment: approval
+                .comment
+                .map(|comment| comment.as_ref().to_owned()),
+            submitted_at: approval.submitted_at.to_rfc3339(),
+            reviewed_at: approval.reviewed_at.map(|time| time.to_rfc3339()),
+        }
+    }
+}
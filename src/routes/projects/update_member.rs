@@ -1,15 +1,19 @@
-use axum::{
-    extract::{Query, State},
-    http::StatusCode,
-    Json,
-};
+use axum::{extract::State, http::StatusCode};
 use axum_extra::extract::CookieJar;
 use color_eyre::eyre::eyre;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    domain::{MemberId, MemberName, ProjectAPIError, ProjectStoreError},
-    utils::auth::get_claims,
+    domain::{
+        ContractedHours, DepartmentId, Member, MemberId, MemberName,
+        ProjectAPIError, ProjectStoreError, ShiftTag,
+    },
+    utils::{
+        auth::Claims,
+        json::{deserialize_present, Json},
+        query::ValidatedQuery,
+    },
     AppState,
 };
 
@@ -22,14 +26,24 @@ pub struct QueryParams {
 #[tracing::instrument(name = "Update member route handler", skip_all)]
 pub async fn update_member(
     State(state): State<AppState>,
+    claims: Claims,
     jar: CookieJar,
-    query_params: Query<QueryParams>,
+    query_params: ValidatedQuery<QueryParams>,
     Json(request): Json<UpdateMemberRequest>,
 ) -> Result<(StatusCode, CookieJar, Json<UpdateMemberResponse>), ProjectAPIError>
 {
-    let user_id = get_claims(&jar, &state.banned_token_store).await?.id;
+    let user_id = claims.id;
     let member_id = MemberId::new(query_params.member_id);
     let member_name = MemberName::parse(request.member_name)?;
+    let skills = request
+        .skills
+        .as_deref()
+        .map(Member::parse_skills)
+        .transpose()?;
+    let contracted_hours = request
+        .contracted_hours_per_week
+        .map(|hours| hours.map(ContractedHours::parse).transpose())
+        .transpose()?;
 
     let mut member = state
         .project_store
@@ -39,12 +53,21 @@ pub async fn update_member(
         .await
         .map_err(|e| match e {
             ProjectStoreError::MemberIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*member_id.as_ref())
+                ProjectAPIError::MemberNotFound(member_id.clone())
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
 
     member.member_name = member_name;
+    if let Some(contracted_hours) = contracted_hours {
+        member.contracted_hours = contracted_hours;
+    }
+    if let Some(department_id) = request.department_id {
+        member.department_id = department_id.map(DepartmentId::new);
+    }
+    if let Some(skills) = skills {
+        member.skills = skills;
+    }
 
     state
         .project_store
@@ -54,7 +77,13 @@ pub async fn update_member(
         .await
         .map_err(|e| match e {
             ProjectStoreError::ProjectIDNotFound => {
-                ProjectAPIError::IDNotFoundError(*member.project_id.as_ref())
+                ProjectAPIError::ProjectNotFound(member.project_id.clone())
+            }
+            e @ ProjectStoreError::DepartmentIDNotFound => {
+                match member.department_id.as_ref() {
+                    Some(id) => ProjectAPIError::DepartmentNotFound(id.clone()),
+                    None => ProjectAPIError::UnexpectedError(eyre!(e)),
+                }
             }
             e => ProjectAPIError::UnexpectedError(eyre!(e)),
         })?;
@@ -63,23 +92,57 @@ pub async fn update_member(
         project_id: *member.project_id.as_ref(),
         member_id: *member.member_id.as_ref(),
         member_name: member.member_name.as_ref().to_owned(),
+        contracted_hours_per_week: member
+            .contracted_hours
+            .map(|hours| *hours.as_ref()),
+        department_id: member.department_id.map(|id| *id.as_ref()),
+        skills: member.skills,
     });
 
     Ok((StatusCode::OK, jar, response))
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateMemberResponse {
     #[serde(rename = "projectId")]
     pub project_id: uuid::Uuid,
     #[serde(rename = "memberId")]
     pub member_id: uuid::Uuid,
     #[serde(rename = "memberName")]
+    #[schemars(length(min = 1, max = 255))]
     pub member_name: String,
+    // Left out for members not on a fixed-hours contract
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        skip_serializing_if = "Option::is_none"
+    )]
+    #[schemars(range(min = 0, max = 168))]
+    pub contracted_hours_per_week: Option<f64>,
+    // Left out for members not in a department
+    #[serde(rename = "departmentId", skip_serializing_if = "Option::is_none")]
+    pub department_id: Option<uuid::Uuid>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub skills: Vec<ShiftTag>,
 }
 
 #[derive(Debug, PartialEq, Deserialize)]
 pub struct UpdateMemberRequest {
     #[serde(rename = "memberName")]
     pub member_name: String,
+    // Left out to keep the current contract; null for no fixed hours
+    #[serde(
+        rename = "contractedHoursPerWeek",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub contracted_hours_per_week: Option<Option<f64>>,
+    // Left out to stay in the current department; null for none
+    #[serde(
+        rename = "departmentId",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub department_id: Option<Option<uuid::Uuid>>,
+    // Left out to keep the current skills
+    pub skills: Option<Vec<String>>,
 }
@@ -0,0 +1,100 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use super::{MemberContactQueryParams, MemberContactResponse};
+use crate::{
+    domain::{
+        Email, MemberId, PhoneNumber, ProjectAPIError, ProjectStoreError,
+    },
+    utils::{
+        auth::Claims,
+        json::{deserialize_present, Json},
+        query::ValidatedQuery,
+    },
+    AppState,
+};
+
+#[tracing::instrument(name = "Update member contact route handler", skip_all)]
+pub async fn update_member_contact(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<MemberContactQueryParams>,
+    Json(request): Json<UpdateMemberContactRequest>,
+) -> Result<(StatusCode, CookieJar, Json<MemberContactResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+    let member_id = MemberId::new(query_params.member_id);
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::MemberIDNotFound => {
+            ProjectAPIError::MemberNotFound(member_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    // Fields left out of the request keep their current values
+    let mut contact = state
+        .project_store
+        .write()
+        .await
+        .get_member_contact(&user_id, &member_id)
+        .await
+        .map_err(map_store_error)?;
+
+    if let Some(phone_number) = request.phone_number {
+        contact.phone_number =
+            phone_number.map(PhoneNumber::parse).transpose()?;
+        // A new number hasn't agreed to texts
+        contact.sms_opt_in = false;
+    }
+    if let Some(sms_opt_in) = request.sms_opt_in {
+        contact.sms_opt_in = sms_opt_in;
+    }
+    if let Some(email) = request.email {
+        contact.email = email
+            .map(|email| Email::parse(Secret::new(email)))
+            .transpose()?;
+        // Nor has a new address agreed to emails
+        contact.email_opt_in = false;
+    }
+    if let Some(email_opt_in) = request.email_opt_in {
+        contact.email_opt_in = email_opt_in;
+    }
+    contact.validate()?;
+
+    state
+        .project_store
+        .write()
+        .await
+        .update_member_contact(&user_id, &member_id, &contact)
+        .await
+        .map_err(map_store_error)?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(MemberContactResponse::new(member_id, contact)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct UpdateMemberContactRequest {
+    // Null removes the number
+    #[serde(
+        rename = "phoneNumber",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub phone_number: Option<Option<String>>,
+    #[serde(rename = "smsOptIn")]
+    pub sms_opt_in: Option<bool>,
+    // Null removes the address
+    #[serde(default, deserialize_with = "deserialize_present")]
+    pub email: Option<Option<String>>,
+    #[serde(rename = "emailOptIn")]
+    pub email_opt_in: Option<bool>,
+}
@@ -0,0 +1,191 @@
+use std::str::FromStr;
+
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use color_eyre::eyre::eyre;
+use secrecy::Secret;
+use serde::Deserialize;
+
+use super::{ProjectSettingsQueryParams, ProjectSettingsResponse};
+use crate::{
+    domain::{
+        BreakRule, ChatWebhookUrl, CronSchedule, Day, Email, EscalationChannel,
+        EscalationStep, PhoneNumber, ProjectAPIError, ProjectId,
+        ProjectStoreError, ShiftGranularity, ShiftLength,
+    },
+    utils::{
+        auth::Claims,
+        json::{deserialize_present, Json},
+        query::ValidatedQuery,
+        residency::data_region,
+    },
+    AppState,
+};
+
+#[tracing::instrument(name = "Update project settings route handler", skip_all)]
+pub async fn update_project_settings(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<ProjectSettingsQueryParams>,
+    Json(request): Json<UpdateProjectSettingsRequest>,
+) -> Result<
+    (StatusCode, CookieJar, Json<ProjectSettingsResponse>),
+    ProjectAPIError,
+> {
+    let user_id = claims.id;
+    let project_id = ProjectId::new(query_params.project_id);
+
+    let map_store_error = |e| match e {
+        ProjectStoreError::ProjectIDNotFound => {
+            ProjectAPIError::ProjectNotFound(project_id.clone())
+        }
+        e => ProjectAPIError::UnexpectedError(eyre!(e)),
+    };
+
+    // Settings left out of the request keep their current values
+    let mut settings = state
+        .project_store
+        .write()
+        .await
+        .get_project_settings(&user_id, &project_id)
+        .await
+        .map_err(map_store_error)?;
+
+    if let Some(shift_granularity) = request.shift_granularity {
+        settings.shift_granularity =
+            ShiftGranularity::parse(shift_granularity)?;
+    }
+    if let Some(min_shift_length) = request.min_shift_length {
+        settings.min_shift_length = ShiftLength::parse(min_shift_length)?;
+    }
+    if let Some(max_shift_length) = request.max_shift_length {
+        settings.max_shift_length = ShiftLength::parse(max_shift_length)?;
+    }
+    if let Some(break_rules) = request.break_rules {
+        settings.break_rules = break_rules
+            .into_iter()
+            .map(|rule| {
+                BreakRule::new(
+                    rule.shift_longer_than,
+                    rule.break_length,
+                    rule.paid,
+                )
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(week_start) = request.week_start {
+        settings.week_start = Day::from_str(&week_start)?;
+    }
+    if let Some(escalation_steps) = request.escalation_steps {
+        settings.escalation_steps = escalation_steps
+            .into_iter()
+            .map(|step| {
+                EscalationStep::new(step.channel, step.hours_before_start)
+            })
+            .collect::<Result<_, _>>()?;
+    }
+    if let Some(chat_webhook_url) = request.chat_webhook_url {
+        settings.chat_webhook_url =
+            chat_webhook_url.map(ChatWebhookUrl::parse).transpose()?;
+        if let Some(url) = &settings.chat_webhook_url {
+            let region = data_region(&state, &user_id)
+                .await
+                .map_err(|e| ProjectAPIError::UnexpectedError(eyre!(e)))?;
+            state.settings.residency_rules.check_webhook(region, url)?;
+        }
+    }
+    if let Some(escalation_phone) = request.escalation_phone {
+        settings.escalation_phone =
+            escalation_phone.map(PhoneNumber::parse).transpose()?;
+    }
+    if let Some(auto_publish) = request.auto_publish {
+        settings.auto_publish = auto_publish
+            .as_deref()
+            .map(CronSchedule::parse)
+            .transpose()?;
+    }
+    if let Some(approver_email) = request.approver_email {
+        settings.approver_email = approver_email
+            .map(|email| Email::parse(Secret::new(email)))
+            .transpose()?;
+    }
+    settings.validate()?;
+
+    state
+        .project_store
+        .write()
+        .await
+        .update_project_settings(&user_id, &project_id, &settings)
+        .await
+        .map_err(map_store_error)?;
+
+    Ok((
+        StatusCode::OK,
+        jar,
+        Json(ProjectSettingsResponse::new(project_id, settings)),
+    ))
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct UpdateProjectSettingsRequest {
+    #[serde(rename = "shiftGranularity")]
+    pub shift_granularity: Option<i16>,
+    #[serde(rename = "minShiftLength")]
+    pub min_shift_length: Option<i16>,
+    #[serde(rename = "maxShiftLength")]
+    pub max_shift_length: Option<i16>,
+    // Replaces all of the project's break rules
+    #[serde(rename = "breakRules")]
+    pub break_rules: Option<Vec<BreakRuleRequest>>,
+    #[serde(rename = "weekStartsOn")]
+    pub week_start: Option<String>,
+    // Replaces the project's escalation schedule; empty turns it off
+    #[serde(rename = "escalationSteps")]
+    pub escalation_steps: Option<Vec<EscalationStepRequest>>,
+    // Null removes the URL
+    #[serde(
+        rename = "chatWebhookUrl",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub chat_webhook_url: Option<Option<String>>,
+    // Null removes the number
+    #[serde(
+        rename = "escalationPhone",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub escalation_phone: Option<Option<String>>,
+    // Null stops publishing automatically
+    #[serde(
+        rename = "autoPublish",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub auto_publish: Option<Option<String>>,
+    // Null lets rotas be published without approval
+    #[serde(
+        rename = "approverEmail",
+        default,
+        deserialize_with = "deserialize_present"
+    )]
+    pub approver_email: Option<Option<String>>,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct BreakRuleRequest {
+    #[serde(rename = "shiftLongerThan")]
+    pub shift_longer_than: i16,
+    #[serde(rename = "breakLength")]
+    pub break_length: i16,
+    #[serde(default)]
+    pub paid: bool,
+}
+
+#[derive(Debug, PartialEq, Deserialize)]
+pub struct EscalationStepRequest {
+    pub channel: EscalationChannel,
+    #[serde(rename = "hoursBeforeStart")]
+    pub hours_before_start: i16,
+}
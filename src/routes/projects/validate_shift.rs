@@ -0,0 +1,67 @@
+use axum::{extract::State, http::StatusCode};
+use axum_extra::extract::CookieJar;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use super::add_shift::{build_shift, AddShiftQueryParams, AddShiftRequest};
+use crate::{
+    domain::{ProjectAPIError, ShiftBreak},
+    utils::{auth::Claims, json::Json, query::ValidatedQuery},
+    AppState,
+};
+
+// Pre-flight check for a shift, so clients can show rule violations before
+// submitting. Rule violations are reported in the body rather than as errors.
+#[tracing::instrument(name = "Validate shift route handler", skip_all)]
+pub async fn validate_shift(
+    State(state): State<AppState>,
+    claims: Claims,
+    jar: CookieJar,
+    query_params: ValidatedQuery<AddShiftQueryParams>,
+    Json(request): Json<AddShiftRequest>,
+) -> Result<(StatusCode, CookieJar, Json<ValidateShiftResponse>), ProjectAPIError>
+{
+    let user_id = claims.id;
+
+    let response = match build_shift(
+        &state,
+        &user_id,
+        &request,
+        query_params.snap,
+    )
+    .await
+    {
+        Ok(shift) => ValidateShiftResponse {
+            valid: true,
+            error: None,
+            start_time: Some(shift.start_time.value_of()),
+            end_time: Some(shift.end_time.value_of()),
+            breaks: shift.breaks,
+        },
+        Err(ProjectAPIError::ValidationError(e)) => ValidateShiftResponse {
+            valid: false,
+            error: Some(e.to_string()),
+            start_time: None,
+            end_time: None,
+            breaks: Vec::new(),
+        },
+        Err(e) => return Err(e),
+    };
+
+    Ok((StatusCode::OK, jar, Json(response)))
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct ValidateShiftResponse {
+    pub valid: bool,
+    pub error: Option<String>,
+    // The times the shift would be saved with, after any snapping
+    #[serde(rename = "startTime")]
+    #[schemars(range(min = 0, max = 1440))]
+    pub start_time: Option<i16>,
+    #[serde(rename = "endTime")]
+    #[schemars(range(min = 0, max = 1440))]
+    pub end_time: Option<i16>,
+    // Breaks the project's break rules would add
+    pub breaks: Vec<ShiftBreak>,
+}
@@ -0,0 +1,217 @@
+use axum::{
+    body::Bytes,
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Response},
+};
+use color_eyre::eyre::eyre;
+
+use crate::{
+    domain::{
+        time::at_minute, AuthAPIError, Locale, PhoneNumber, ReactedRota,
+        Reaction, RemindedShift, ShiftReminderStoreError, SmsReply,
+    },
+    utils::{
+        constants::APP_SERVICE_EXTERNAL_ADDRESS,
+        twilio::{verify_twilio_signature, TWILIO_SIGNATURE_HEADER},
+    },
+    AppState,
+};
+
+const HELP_MESSAGE: &str =
+    "Reply YES to confirm your next shift or SWAP to ask to swap it, or SEEN \
+     once you've seen the rota.";
+const NO_SHIFT_MESSAGE: &str =
+    "We couldn't find an upcoming shift for this number.";
+const NO_ROTA_MESSAGE: &str =
+    "We couldn't find a published rota for this number.";
+
+// Texts sent to our Twilio number, forwarded by Twilio as a form. Members
+// answer a shift reminder with YES to confirm the shift or SWAP to ask the
+// project owner to find someone to swap with, and react to the latest
+// published rota with an emoji or SEEN. The reply goes back to them as
+// TwiML.
+#[tracing::instrument(name = "Receive SMS route handler", skip_all)]
+pub async fn receive_sms(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Bytes,
+) -> Result<Twiml, AuthAPIError> {
+    let params = form_urlencoded::parse(&body)
+        .into_owned()
+        .collect::<Vec<_>>();
+
+    let signature = headers
+        .get(TWILIO_SIGNATURE_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .ok_or(AuthAPIError::MissingCredentials)?;
+    let auth_token = state
+        .settings
+        .twilio_auth_token
+        .as_ref()
+        .ok_or(AuthAPIError::IncorrectCredentials)?;
+    let url = format!("{}/sms/inbound", *APP_SERVICE_EXTERNAL_ADDRESS);
+    if !verify_twilio_signature(auth_token, &url, &params, signature) {
+        return Err(AuthAPIError::IncorrectCredentials);
+    }
+
+    let param = |name: &str| {
+        params
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or_default()
+    };
+    if let Some(reaction) = Reaction::from_sms(param("Body")) {
+        return react_to_rota(&state, param("From"), &reaction).await;
+    }
+    let Some(reply) = SmsReply::parse(param("Body")) else {
+        return Ok(Twiml::message(HELP_MESSAGE));
+    };
+    let Ok(phone_number) = PhoneNumber::parse(param("From").to_owned()) else {
+        return Ok(Twiml::message(NO_SHIFT_MESSAGE));
+    };
+
+    let result = state
+        .shift_reminder_store
+        .write()
+        .await
+        .record_reply(&phone_number, reply, state.clock.now().date_naive())
+        .await;
+    match result {
+        Ok(shift) => Ok(Twiml::message(&reply_message(reply, &shift))),
+        Err(ShiftReminderStoreError::ReminderNotFound) => {
+            Ok(Twiml::message(NO_SHIFT_MESSAGE))
+        }
+        Err(e) => Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    }
+}
+
+async fn react_to_rota(
+    state: &AppState,
+    from: &str,
+    reaction: &Reaction,
+) -> Result<Twiml, AuthAPIError> {
+    let Ok(phone_number) = PhoneNumber::parse(from.to_owned()) else {
+        return Ok(Twiml::message(NO_ROTA_MESSAGE));
+    };
+    let result = state
+        .shift_reminder_store
+        .write()
+        .await
+        .record_rota_reaction(&phone_number, reaction)
+        .await;
+    match result {
+        Ok(rota) => Ok(Twiml::message(&reaction_message(&rota))),
+        Err(ShiftReminderStoreError::RotaNotFound) => {
+            Ok(Twiml::message(NO_ROTA_MESSAGE))
+        }
+        Err(e) => Err(AuthAPIError::UnexpectedError(eyre!(e))),
+    }
+}
+
+pub fn reaction_message(rota: &ReactedRota) -> String {
+    format!(
+        "Thanks, we've let the {} rota manager know you've seen the rota for \
+         the week of {}.",
+        rota.project_name.as_ref(),
+        Locale::default().format_date(rota.week_of)
+    )
+}
+
+pub fn reply_message(reply: SmsReply, shift: &RemindedShift) -> String {
+    let start = Locale::default()
+        .format_date_time(at_minute(shift.shift_date, &shift.start_time));
+    match reply {
+        SmsReply::Confirm => format!(
+            "Thanks, you're confirmed for {} on {}.",
+            shift.project_name.as_ref(),
+            start
+        ),
+        SmsReply::Swap => format!(
+            "Thanks, we've asked the {} rota manager to find someone to swap \
+             your shift on {}.",
+            shift.project_name.as_ref(),
+            start
+        ),
+    }
+}
+
+// A TwiML reply telling Twilio to text `message` back to the sender
+#[derive(Debug, PartialEq)]
+pub struct Twiml(String);
+
+impl Twiml {
+    pub fn message(message: &str) -> Self {
+        Self(format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\
+             <Response><Message>{}</Message></Response>",
+            escape_xml(message)
+        ))
+    }
+}
+
+impl IntoResponse for Twiml {
+    fn into_response(self) -> Response {
+        (StatusCode::OK, [(header::CONTENT_TYPE, "text/xml")], self.0)
+            .into_response()
+    }
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+    use crate::domain::{MemberName, Minute, ProjectName, ShiftId};
+
+    #[test]
+    fn test_twiml_message_is_escaped() {
+        assert_eq!(
+            Twiml::message("Tom & Jerry's <shift>").0,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?><Response><Message>\
+             Tom &amp; Jerry&apos;s &lt;shift&gt;</Message></Response>"
+        );
+    }
+
+    #[test]
+    fn test_reply_message() {
+        let shift = RemindedShift {
+            shift_id: ShiftId::default(),
+            shift_date: NaiveDate::from_ymd_opt(2025, 10, 20).unwrap(),
+            start_time: Minute::parse(540).unwrap(),
+            member_name: MemberName::parse("Ted".to_owned()).unwrap(),
+            project_name: ProjectName::parse("Craggy Island").unwrap(),
+        };
+
+        assert_eq!(
+            reply_message(SmsReply::Confirm, &shift),
+            "Thanks, you're confirmed for Craggy Island on Monday 20/10/2025 \
+             at 09:00."
+        );
+        assert!(reply_message(SmsReply::Swap, &shift)
+            .starts_with("Thanks, we've asked the Craggy Island rota manager"));
+    }
+
+    #[test]
+    fn test_reaction_message() {
+        let rota = ReactedRota {
+            project_name: ProjectName::parse("Craggy Island").unwrap(),
+            week_of: NaiveDate::from_ymd_opt(2025, 10, 26).unwrap(),
+        };
+
+        assert_eq!(
+            reaction_message(&rota),
+            "Thanks, we've let the Craggy Island rota manager know you've \
+             seen the rota for the week of Sunday 26/10/2025."
+        );
+    }
+}
@@ -0,0 +1,36 @@
+use axum::{http::StatusCode, Json};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::constants::build_info;
+
+// Which build is running, so bug reports and deployed containers can be
+// matched to the source they came from
+#[tracing::instrument(name = "Get version route handler", skip_all)]
+pub async fn get_version() -> (StatusCode, Json<VersionResponse>) {
+    let response = Json(VersionResponse {
+        version: build_info::VERSION.to_owned(),
+        git_sha: build_info::GIT_SHA.to_owned(),
+        build_timestamp: build_info::BUILD_TIMESTAMP.to_owned(),
+        features: build_info::FEATURES
+            .iter()
+            .map(|feature| feature.to_string())
+            .collect(),
+    });
+
+    (StatusCode::OK, response)
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct VersionResponse {
+    // The crate version
+    pub version: String,
+    // The commit built from, or a placeholder when built outside git
+    #[serde(rename = "gitSha")]
+    pub git_sha: String,
+    // RFC 3339, in UTC
+    #[serde(rename = "buildTimestamp")]
+    pub build_timestamp: String,
+    // Optional features compiled in, e.g. "postmark"
+    pub features: Vec<String>,
+}
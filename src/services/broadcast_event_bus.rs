@@ -0,0 +1,57 @@
+use tokio::sync::broadcast;
+
+use crate::domain::{DomainEvent, EventBus, EventReceiver};
+
+pub struct BroadcastEventBus {
+    sender: broadcast::Sender<DomainEvent>,
+}
+
+impl BroadcastEventBus {
+    // Subscribers that fall more than `capacity` events behind skip the
+    // oldest ones rather than holding up publishers.
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        Self { sender }
+    }
+}
+
+impl EventBus for BroadcastEventBus {
+    #[tracing::instrument(name = "Publishing domain event", skip_all)]
+    fn publish(&self, event: DomainEvent) {
+        tracing::debug!(event = event.name(), "Publishing event");
+        let _ = self.sender.send(event);
+    }
+
+    fn subscribe(&self) -> EventReceiver {
+        self.sender.subscribe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserId;
+
+    #[tokio::test]
+    async fn test_every_subscriber_receives_event() {
+        let bus = BroadcastEventBus::new(8);
+        let mut first = bus.subscribe();
+        let mut second = bus.subscribe();
+
+        let event = DomainEvent::UserSignedUp {
+            user_id: UserId::default(),
+        };
+        bus.publish(event.clone());
+
+        assert_eq!(first.recv().await.unwrap(), event);
+        assert_eq!(second.recv().await.unwrap(), event);
+    }
+
+    #[test]
+    fn test_publish_without_subscribers() {
+        let bus = BroadcastEventBus::new(8);
+        bus.publish(DomainEvent::UserDeleted {
+            user_id: UserId::default(),
+        });
+    }
+}
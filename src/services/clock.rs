@@ -0,0 +1,69 @@
+use std::sync::{Arc, Mutex};
+
+use chrono::{DateTime, TimeDelta, Utc};
+
+use crate::domain::Clock;
+
+// The time as the system tells it
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+// A clock that only moves when it's told to. Clones share the same time, so
+// a test can keep one and move the time seen by the app it handed one to.
+#[derive(Debug, Clone)]
+pub struct ManualClock(Arc<Mutex<DateTime<Utc>>>);
+
+impl ManualClock {
+    pub fn new(now: DateTime<Utc>) -> Self {
+        Self(Arc::new(Mutex::new(now)))
+    }
+
+    pub fn set(&self, now: DateTime<Utc>) {
+        *self.0.lock().expect("Clock lock poisoned") = now;
+    }
+
+    pub fn advance(&self, by: TimeDelta) {
+        *self.0.lock().expect("Clock lock poisoned") += by;
+    }
+}
+
+impl Default for ManualClock {
+    // Starts at the real time, so anything stored by the app isn't far in
+    // the past or future to the database
+    fn default() -> Self {
+        Self::new(Utc::now())
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> DateTime<Utc> {
+        *self.0.lock().expect("Clock lock poisoned")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+
+    use super::*;
+
+    #[test]
+    fn test_manual_clock_only_moves_when_told() {
+        let start = Utc.with_ymd_and_hms(2024, 3, 4, 9, 0, 0).unwrap();
+        let clock = ManualClock::new(start);
+        let shared = clock.clone();
+        assert_eq!(shared.now(), start);
+
+        clock.advance(TimeDelta::minutes(90));
+        assert_eq!(shared.now(), start + TimeDelta::minutes(90));
+
+        clock.set(start);
+        assert_eq!(shared.now(), start);
+    }
+}
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::domain::{DeprecatedUsageStore, DeprecatedUsageStoreError};
+
+#[derive(Default)]
+pub struct HashmapDeprecatedUsageStore {
+    usage: HashMap<String, HashMap<String, u64>>,
+}
+
+#[async_trait::async_trait]
+impl DeprecatedUsageStore for HashmapDeprecatedUsageStore {
+    async fn record_usage(
+        &mut self,
+        path: &str,
+        client: &str,
+    ) -> Result<(), DeprecatedUsageStoreError> {
+        *self
+            .usage
+            .entry(path.to_owned())
+            .or_default()
+            .entry(client.to_owned())
+            .or_default() += 1;
+        Ok(())
+    }
+
+    async fn get_usage(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, u64)>, DeprecatedUsageStoreError> {
+        let mut usage: Vec<(String, u64)> = self
+            .usage
+            .get(path)
+            .map(|clients| clients.clone().into_iter().collect())
+            .unwrap_or_default();
+        usage.sort();
+        Ok(usage)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn counts_usage_per_client() {
+        let mut store = HashmapDeprecatedUsageStore::default();
+        let path = "/projects/add-member";
+
+        for client in ["curl/8.5.0", "rota-web/1.2", "curl/8.5.0"] {
+            store
+                .record_usage(path, client)
+                .await
+                .expect("Failed to record usage");
+        }
+
+        assert_eq!(
+            store.get_usage(path).await.expect("Failed to get usage"),
+            vec![("curl/8.5.0".to_owned(), 2), ("rota-web/1.2".to_owned(), 1)]
+        );
+    }
+
+    #[tokio::test]
+    async fn unused_path_has_no_usage() {
+        let store = HashmapDeprecatedUsageStore::default();
+        assert!(store
+            .get_usage("/projects/add-member")
+            .await
+            .expect("Failed to get usage")
+            .is_empty());
+    }
+}
@@ -0,0 +1,82 @@
+use std::{collections::HashMap, time::Duration};
+
+use tokio::time::Instant;
+use uuid::Uuid;
+
+use crate::domain::{JobLease, JobLockStore, JobLockStoreError};
+
+// Leases for a single instance, which has no one to share jobs with
+#[derive(Default)]
+pub struct HashmapJobLockStore {
+    leases: HashMap<String, (String, Instant)>,
+}
+
+#[async_trait::async_trait]
+impl JobLockStore for HashmapJobLockStore {
+    async fn acquire(
+        &mut self,
+        job: &str,
+        duration: Duration,
+    ) -> Result<Option<JobLease>, JobLockStoreError> {
+        let now = Instant::now();
+        if let Some((_, expires_at)) = self.leases.get(job) {
+            if *expires_at > now {
+                return Ok(None);
+            }
+        }
+
+        let token = Uuid::new_v4().to_string();
+        self.leases
+            .insert(job.to_owned(), (token.clone(), now + duration));
+        Ok(Some(JobLease {
+            job: job.to_owned(),
+            token,
+        }))
+    }
+
+    async fn release(
+        &mut self,
+        lease: JobLease,
+    ) -> Result<(), JobLockStoreError> {
+        if self
+            .leases
+            .get(&lease.job)
+            .is_some_and(|(token, _)| *token == lease.token)
+        {
+            self.leases.remove(&lease.job);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LEASE: Duration = Duration::from_secs(60);
+
+    #[tokio::test]
+    async fn test_only_one_holder_at_a_time() {
+        let mut store = HashmapJobLockStore::default();
+        let lease = store.acquire("job", LEASE).await.unwrap().unwrap();
+        assert_eq!(store.acquire("job", LEASE).await.unwrap(), None);
+        assert!(store.acquire("other_job", LEASE).await.unwrap().is_some());
+
+        store.release(lease).await.unwrap();
+        assert!(store.acquire("job", LEASE).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_lease_runs_out() {
+        let mut store = HashmapJobLockStore::default();
+        let stale =
+            store.acquire("job", Duration::ZERO).await.unwrap().unwrap();
+
+        let current = store.acquire("job", LEASE).await.unwrap().unwrap();
+        // Releasing the stale lease leaves the current holder's alone
+        store.release(stale).await.unwrap();
+        assert_eq!(store.acquire("job", LEASE).await.unwrap(), None);
+        store.release(current).await.unwrap();
+        assert!(store.acquire("job", LEASE).await.unwrap().is_some());
+    }
+}
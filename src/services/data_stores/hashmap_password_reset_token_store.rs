@@ -0,0 +1,79 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    PasswordReset, PasswordResetToken, PasswordResetTokenStore,
+    PasswordResetTokenStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapPasswordResetTokenStore {
+    tokens: HashMap<String, PasswordReset>,
+}
+
+#[async_trait::async_trait]
+impl PasswordResetTokenStore for HashmapPasswordResetTokenStore {
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        reset: PasswordReset,
+    ) -> Result<(), PasswordResetTokenStoreError> {
+        self.tokens
+            .insert(token.as_ref().expose_secret().to_owned(), reset);
+        Ok(())
+    }
+
+    async fn take_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<PasswordReset, PasswordResetTokenStoreError> {
+        self.tokens
+            .remove(token.as_ref().expose_secret())
+            .ok_or(PasswordResetTokenStoreError::TokenNotFound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::UserId;
+    use chrono::Utc;
+
+    fn get_test_data() -> (PasswordResetToken, PasswordReset) {
+        let reset = PasswordReset {
+            user_id: UserId::default(),
+            expires_at: Utc::now(),
+        };
+        (PasswordResetToken::default(), reset)
+    }
+
+    #[tokio::test]
+    async fn add_and_take_token() {
+        let (token, reset) = get_test_data();
+        let mut store = HashmapPasswordResetTokenStore::default();
+        assert_eq!(
+            store.add_token(token.clone(), reset.clone()).await,
+            Ok(()),
+            "Failed to add token to store"
+        );
+        assert_eq!(
+            store.take_token(&token).await,
+            Ok(reset),
+            "Taken reset does not match stored reset"
+        );
+    }
+
+    #[tokio::test]
+    async fn token_can_only_be_taken_once() {
+        let (token, reset) = get_test_data();
+        let mut store = HashmapPasswordResetTokenStore::default();
+        store.add_token(token.clone(), reset).await.unwrap();
+        store.take_token(&token).await.unwrap();
+        assert_eq!(
+            store.take_token(&token).await,
+            Err(PasswordResetTokenStoreError::TokenNotFound),
+            "Taken token should not be found again"
+        );
+    }
+}
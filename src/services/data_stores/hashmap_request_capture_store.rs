@@ -0,0 +1,111 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use crate::{
+    domain::{
+        CapturedRequest, RequestCaptureStore, RequestCaptureStoreError, UserId,
+    },
+    utils::constants::REQUEST_CAPTURE_LIMIT,
+};
+
+// Captures for a single instance. Nothing expires, as it only lives as long
+// as the process.
+#[derive(Default)]
+pub struct HashmapRequestCaptureStore {
+    capturing: HashSet<uuid::Uuid>,
+    captured: HashMap<uuid::Uuid, VecDeque<CapturedRequest>>,
+}
+
+#[async_trait::async_trait]
+impl RequestCaptureStore for HashmapRequestCaptureStore {
+    async fn start_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError> {
+        self.capturing.insert(*user_id.as_ref());
+        self.captured.remove(user_id.as_ref());
+        Ok(())
+    }
+
+    async fn stop_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError> {
+        self.capturing.remove(user_id.as_ref());
+        Ok(())
+    }
+
+    async fn is_capturing(
+        &self,
+        user_id: &UserId,
+    ) -> Result<bool, RequestCaptureStoreError> {
+        Ok(self.capturing.contains(user_id.as_ref()))
+    }
+
+    async fn record(
+        &mut self,
+        user_id: &UserId,
+        request: &CapturedRequest,
+    ) -> Result<(), RequestCaptureStoreError> {
+        let captured = self.captured.entry(*user_id.as_ref()).or_default();
+        captured.push_front(request.clone());
+        captured.truncate(REQUEST_CAPTURE_LIMIT);
+        Ok(())
+    }
+
+    async fn get_captured(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<CapturedRequest>, RequestCaptureStoreError> {
+        Ok(self
+            .captured
+            .get(user_id.as_ref())
+            .map(|captured| captured.iter().cloned().collect())
+            .unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use super::*;
+
+    fn request(path: &str) -> CapturedRequest {
+        CapturedRequest::new(Utc::now(), "GET", path, None, 200, b"", b"")
+    }
+
+    #[tokio::test]
+    async fn keeps_latest_requests_newest_first() {
+        let mut store = HashmapRequestCaptureStore::default();
+        let user_id = UserId::default();
+        store.start_capture(&user_id).await.unwrap();
+        assert!(store.is_capturing(&user_id).await.unwrap());
+
+        for i in 0..=REQUEST_CAPTURE_LIMIT {
+            store
+                .record(&user_id, &request(&format!("/{}", i)))
+                .await
+                .unwrap();
+        }
+
+        let captured = store.get_captured(&user_id).await.unwrap();
+        assert_eq!(captured.len(), REQUEST_CAPTURE_LIMIT);
+        assert_eq!(captured[0].uri, format!("/{}", REQUEST_CAPTURE_LIMIT));
+        assert_eq!(captured[REQUEST_CAPTURE_LIMIT - 1].uri, "/1");
+    }
+
+    #[tokio::test]
+    async fn stopping_keeps_captured_requests() {
+        let mut store = HashmapRequestCaptureStore::default();
+        let user_id = UserId::default();
+        store.start_capture(&user_id).await.unwrap();
+        store.record(&user_id, &request("/")).await.unwrap();
+
+        store.stop_capture(&user_id).await.unwrap();
+        assert!(!store.is_capturing(&user_id).await.unwrap());
+        assert_eq!(store.get_captured(&user_id).await.unwrap().len(), 1);
+
+        store.start_capture(&user_id).await.unwrap();
+        assert!(store.get_captured(&user_id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,127 @@
+use std::{cmp::Reverse, collections::HashMap};
+
+use chrono::{DateTime, Utc};
+
+use crate::domain::{
+    Session, SessionId, SessionStore, SessionStoreError, UserId,
+};
+
+// Sessions for a single instance. Expired ones are dropped when the user
+// next signs in.
+#[derive(Default)]
+pub struct HashmapSessionStore {
+    sessions: HashMap<uuid::Uuid, Vec<Session>>,
+}
+
+#[async_trait::async_trait]
+impl SessionStore for HashmapSessionStore {
+    async fn add_session(
+        &mut self,
+        session: &Session,
+    ) -> Result<(), SessionStoreError> {
+        let sessions =
+            self.sessions.entry(*session.user_id.as_ref()).or_default();
+        sessions.retain(|other| other.is_active(session.issued_at));
+        sessions.push(session.clone());
+        Ok(())
+    }
+
+    async fn get_sessions(
+        &self,
+        user_id: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, SessionStoreError> {
+        let mut sessions: Vec<Session> = self
+            .sessions
+            .get(user_id.as_ref())
+            .into_iter()
+            .flatten()
+            .filter(|session| session.is_active(now))
+            .cloned()
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.issued_at));
+        Ok(sessions)
+    }
+
+    async fn remove_session(
+        &mut self,
+        user_id: &UserId,
+        session_id: &SessionId,
+    ) -> Result<(), SessionStoreError> {
+        let sessions = self
+            .sessions
+            .get_mut(user_id.as_ref())
+            .ok_or(SessionStoreError::SessionNotFound)?;
+        let before = sessions.len();
+        sessions.retain(|session| session.session_id != *session_id);
+        match sessions.len() < before {
+            true => Ok(()),
+            false => Err(SessionStoreError::SessionNotFound),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeDelta;
+
+    use super::*;
+
+    fn session(user_id: &UserId, issued_at: DateTime<Utc>) -> Session {
+        Session {
+            session_id: SessionId::default(),
+            user_id: user_id.clone(),
+            device: Some(String::from("Firefox")),
+            ip: None,
+            issued_at,
+            expires_at: issued_at + TimeDelta::minutes(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn lists_active_sessions_newest_first() {
+        let mut store = HashmapSessionStore::default();
+        let user_id = UserId::default();
+        let now = Utc::now();
+        let expired = session(&user_id, now - TimeDelta::minutes(11));
+        let older = session(&user_id, now - TimeDelta::minutes(5));
+        let newer = session(&user_id, now);
+        for session in [&expired, &older, &newer] {
+            store.add_session(session).await.unwrap();
+        }
+        store
+            .add_session(&session(&UserId::default(), now))
+            .await
+            .unwrap();
+
+        assert_eq!(
+            store.get_sessions(&user_id, now).await.unwrap(),
+            vec![newer, older]
+        );
+    }
+
+    #[tokio::test]
+    async fn only_removes_the_users_own_sessions() {
+        let mut store = HashmapSessionStore::default();
+        let user_id = UserId::default();
+        let now = Utc::now();
+        let session = session(&user_id, now);
+        store.add_session(&session).await.unwrap();
+
+        assert!(matches!(
+            store
+                .remove_session(&UserId::default(), &session.session_id)
+                .await,
+            Err(SessionStoreError::SessionNotFound)
+        ));
+        store
+            .remove_session(&user_id, &session.session_id)
+            .await
+            .unwrap();
+        assert!(store.get_sessions(&user_id, now).await.unwrap().is_empty());
+        assert!(matches!(
+            store.remove_session(&user_id, &session.session_id).await,
+            Err(SessionStoreError::SessionNotFound)
+        ));
+    }
+}
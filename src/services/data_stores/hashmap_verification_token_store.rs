@@ -0,0 +1,115 @@
+use std::collections::HashMap;
+
+use secrecy::ExposeSecret;
+
+use crate::domain::{
+    PendingEmailChange, VerificationToken, VerificationTokenStore,
+    VerificationTokenStoreError,
+};
+
+#[derive(Default)]
+pub struct HashmapVerificationTokenStore {
+    tokens: HashMap<String, PendingEmailChange>,
+}
+
+#[async_trait::async_trait]
+impl VerificationTokenStore for HashmapVerificationTokenStore {
+    async fn add_token(
+        &mut self,
+        token: VerificationToken,
+        change: PendingEmailChange,
+    ) -> Result<(), VerificationTokenStoreError> {
+        self.tokens
+            .insert(token.as_ref().expose_secret().to_owned(), change);
+        Ok(())
+    }
+
+    async fn get_token(
+        &self,
+        token: &VerificationToken,
+    ) -> Result<PendingEmailChange, VerificationTokenStoreError> {
+        self.tokens
+            .get(token.as_ref().expose_secret())
+            .cloned()
+            .ok_or(VerificationTokenStoreError::TokenNotFound)
+    }
+
+    async fn remove_token(
+        &mut self,
+        token: &VerificationToken,
+    ) -> Result<(), VerificationTokenStoreError> {
+        self.tokens.remove(token.as_ref().expose_secret());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Email, UserId, VerificationTokenType};
+    use secrecy::Secret;
+
+    fn get_test_data() -> (VerificationToken, PendingEmailChange) {
+        let change = PendingEmailChange {
+            user_id: UserId::default(),
+            new_email: Email::parse(Secret::new(String::from("new@bar.com")))
+                .expect("Could not parse email"),
+            token_type: VerificationTokenType::ConfirmOldEmail,
+            counterpart: VerificationToken::default(),
+            confirmed: false,
+        };
+        (VerificationToken::default(), change)
+    }
+
+    #[tokio::test]
+    async fn add_and_get_token() {
+        let (token, change) = get_test_data();
+        let mut store = HashmapVerificationTokenStore::default();
+        assert_eq!(
+            store.add_token(token.clone(), change.clone()).await,
+            Ok(()),
+            "Failed to add token to store"
+        );
+        assert_eq!(
+            store.get_token(&token).await,
+            Ok(change),
+            "Retrieved change does not match stored change"
+        );
+    }
+
+    #[tokio::test]
+    async fn token_can_be_updated() {
+        let (token, mut change) = get_test_data();
+        let mut store = HashmapVerificationTokenStore::default();
+        store
+            .add_token(token.clone(), change.clone())
+            .await
+            .unwrap();
+
+        change.confirmed = true;
+        store
+            .add_token(token.clone(), change.clone())
+            .await
+            .unwrap();
+
+        assert!(store.get_token(&token).await.unwrap().confirmed);
+    }
+
+    #[tokio::test]
+    async fn remove_token() {
+        let (token, change) = get_test_data();
+        let mut store = HashmapVerificationTokenStore::default();
+        store.add_token(token.clone(), change).await.unwrap();
+        assert_eq!(store.remove_token(&token).await, Ok(()));
+        assert_eq!(
+            store.get_token(&token).await,
+            Err(VerificationTokenStoreError::TokenNotFound),
+            "Removed token should not be found"
+        );
+        assert_eq!(
+            store.remove_token(&token).await,
+            Ok(()),
+            "Removing a token twice should be idempotent"
+        );
+    }
+}
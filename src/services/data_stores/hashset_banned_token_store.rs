@@ -1,18 +1,31 @@
+use chrono::{DateTime, Utc};
 use color_eyre::eyre::Result;
 use secrecy::{ExposeSecret, Secret};
-use std::collections::HashSet;
+use std::collections::HashMap;
 
-use crate::domain::{BannedTokenStore, BannedTokenStoreError};
+use crate::domain::{BannedTokenStore, BannedTokenStoreError, SessionId};
 
+// Bans for a single instance. Like Redis's, they only last until the token
+// expires: expired ones are dropped whenever another is added.
 #[derive(Default)]
 pub struct HashsetBannedTokenStore {
-    banned_tokens: HashSet<String>,
+    banned_tokens: HashMap<String, DateTime<Utc>>,
+    banned_sessions: HashMap<SessionId, DateTime<Utc>>,
 }
 
 #[async_trait::async_trait]
 impl BannedTokenStore for HashsetBannedTokenStore {
-    async fn add_token(&mut self, token: &Secret<String>) -> Result<()> {
-        self.banned_tokens.insert(token.expose_secret().to_owned());
+    async fn add_token(
+        &mut self,
+        token: &Secret<String>,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        self.banned_tokens.retain(|_, expires_at| *expires_at > now);
+        if expires_at > now {
+            self.banned_tokens
+                .insert(token.expose_secret().to_owned(), expires_at);
+        }
         Ok(())
     }
 
@@ -20,29 +33,69 @@ impl BannedTokenStore for HashsetBannedTokenStore {
         &self,
         token: &Secret<String>,
     ) -> Result<(), BannedTokenStoreError> {
-        if self.banned_tokens.contains(token.expose_secret()) {
+        if self.banned_tokens.contains_key(token.expose_secret()) {
             Err(BannedTokenStoreError::BannedToken)
         } else {
             Ok(())
         }
     }
+
+    async fn ban_session(
+        &mut self,
+        session_id: &SessionId,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        self.banned_sessions
+            .retain(|_, expires_at| *expires_at > now);
+        if expires_at > now {
+            self.banned_sessions.insert(session_id.clone(), expires_at);
+        }
+        Ok(())
+    }
+
+    async fn check_session(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<(), BannedTokenStoreError> {
+        match self.banned_sessions.contains_key(session_id) {
+            true => Err(BannedTokenStoreError::BannedToken),
+            false => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use chrono::TimeDelta;
+
     use super::*;
 
+    fn now() -> DateTime<Utc> {
+        DateTime::from_timestamp(1_700_000_000, 0).unwrap()
+    }
+
+    fn in_ten_minutes() -> DateTime<Utc> {
+        now() + TimeDelta::minutes(10)
+    }
+
     #[tokio::test]
     async fn test_add_token() {
         let mut banned_tokens = HashsetBannedTokenStore::default();
         let token = Secret::new("token".to_owned());
 
         assert!(
-            banned_tokens.add_token(&token).await.is_ok(),
+            banned_tokens
+                .add_token(&token, in_ten_minutes(), now())
+                .await
+                .is_ok(),
             "Failed to add token to store"
         );
         assert!(
-            banned_tokens.add_token(&token).await.is_ok(),
+            banned_tokens
+                .add_token(&token, in_ten_minutes(), now())
+                .await
+                .is_ok(),
             "Failed to add token to store"
         );
     }
@@ -57,7 +110,10 @@ mod tests {
             "Token banned without existing in store"
         );
         assert!(
-            banned_tokens.add_token(&token).await.is_ok(),
+            banned_tokens
+                .add_token(&token, in_ten_minutes(), now())
+                .await
+                .is_ok(),
             "Failed to add token to store"
         );
         assert_eq!(
@@ -66,4 +122,68 @@ mod tests {
             "Token should be banned"
         );
     }
+
+    #[tokio::test]
+    async fn test_ban_session() {
+        let mut banned_tokens = HashsetBannedTokenStore::default();
+        let session_id = SessionId::default();
+
+        assert!(banned_tokens.check_session(&session_id).await.is_ok());
+        banned_tokens
+            .ban_session(&session_id, in_ten_minutes(), now())
+            .await
+            .unwrap();
+        assert_eq!(
+            banned_tokens.check_session(&session_id).await,
+            Err(BannedTokenStoreError::BannedToken)
+        );
+        assert!(banned_tokens
+            .check_session(&SessionId::default())
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_bans_are_dropped_once_expired() {
+        let mut banned_tokens = HashsetBannedTokenStore::default();
+        let token = Secret::new("token".to_owned());
+        let session_id = SessionId::default();
+        banned_tokens
+            .add_token(&token, in_ten_minutes(), now())
+            .await
+            .unwrap();
+        banned_tokens
+            .ban_session(&session_id, in_ten_minutes(), now())
+            .await
+            .unwrap();
+
+        let later = in_ten_minutes();
+        let other_token = Secret::new("other".to_owned());
+        banned_tokens
+            .add_token(&other_token, later + TimeDelta::minutes(10), later)
+            .await
+            .unwrap();
+        banned_tokens
+            .ban_session(
+                &SessionId::default(),
+                later + TimeDelta::minutes(10),
+                later,
+            )
+            .await
+            .unwrap();
+
+        assert!(banned_tokens.check_token(&token).await.is_ok());
+        assert!(banned_tokens.check_session(&session_id).await.is_ok());
+        assert!(banned_tokens.check_token(&other_token).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_expired_tokens_are_not_kept() {
+        let mut banned_tokens = HashsetBannedTokenStore::default();
+        let token = Secret::new("token".to_owned());
+
+        banned_tokens.add_token(&token, now(), now()).await.unwrap();
+
+        assert!(banned_tokens.banned_tokens.is_empty());
+    }
 }
@@ -1,13 +1,51 @@
+mod hashmap_deprecated_usage_store;
+mod hashmap_job_lock_store;
+mod hashmap_password_reset_token_store;
+mod hashmap_request_capture_store;
+mod hashmap_session_store;
 mod hashmap_two_fa_code_store;
+mod hashmap_verification_token_store;
 mod hashset_banned_token_store;
+mod postgres_email_outbox;
+mod postgres_event_outbox;
+mod postgres_integration_store;
+mod postgres_job_queue;
 mod postgres_project_store;
+mod postgres_shift_reminder_store;
+mod postgres_sms_outbox;
 mod postgres_user_store;
 mod redis_banned_token_store;
+mod redis_deprecated_usage_store;
+mod redis_job_lock_store;
+mod redis_password_reset_token_store;
+mod redis_pipeline;
+mod redis_request_capture_store;
+mod redis_session_store;
 mod redis_two_fa_code_store;
+mod redis_verification_token_store;
 
+pub use hashmap_deprecated_usage_store::*;
+pub use hashmap_job_lock_store::*;
+pub use hashmap_password_reset_token_store::*;
+pub use hashmap_request_capture_store::*;
+pub use hashmap_session_store::*;
 pub use hashmap_two_fa_code_store::*;
+pub use hashmap_verification_token_store::*;
 pub use hashset_banned_token_store::*;
+pub use postgres_email_outbox::*;
+pub use postgres_event_outbox::*;
+pub use postgres_integration_store::*;
+pub use postgres_job_queue::*;
 pub use postgres_project_store::*;
+pub use postgres_shift_reminder_store::*;
+pub use postgres_sms_outbox::*;
 pub use postgres_user_store::*;
 pub use redis_banned_token_store::*;
+pub use redis_deprecated_usage_store::*;
+pub use redis_job_lock_store::*;
+pub use redis_password_reset_token_store::*;
+pub use redis_pipeline::*;
+pub use redis_request_capture_store::*;
+pub use redis_session_store::*;
 pub use redis_two_fa_code_store::*;
+pub use redis_verification_token_store::*;
@@ -0,0 +1,288 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Result, WrapErr};
+use secrecy::ExposeSecret;
+use sqlx::PgConnection;
+
+use crate::{
+    domain::{Email, ProjectId},
+    utils::metrics::timed_query,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingEmail {
+    pub id: i64,
+    pub recipient: String,
+    pub subject: String,
+    pub content: String,
+    pub reply_to: Option<String>,
+}
+
+// Queue a notification email. Call this with the store's open transaction so
+// the email is only sent if the change that prompted it is committed.
+#[tracing::instrument(name = "Enqueueing email", skip_all)]
+pub async fn enqueue_email(
+    conn: &mut PgConnection,
+    recipient: &Email,
+    subject: &str,
+    content: &str,
+) -> Result<()> {
+    insert_email(conn, None, recipient, subject, content, None).await
+}
+
+// Queue an email about a project, which its owner can then see was sent and
+// whether it was opened
+#[tracing::instrument(name = "Enqueueing project email", skip_all)]
+pub async fn enqueue_project_email(
+    conn: &mut PgConnection,
+    project_id: &ProjectId,
+    recipient: &Email,
+    subject: &str,
+    content: &str,
+) -> Result<()> {
+    insert_email(conn, Some(project_id), recipient, subject, content, None)
+        .await
+}
+
+// Queue an email about a project whose replies go to `reply_to`, such as the
+// inbound address that acts on them
+#[tracing::instrument(
+    name = "Enqueueing project email with reply-to",
+    skip_all
+)]
+pub async fn enqueue_project_email_with_reply_to(
+    conn: &mut PgConnection,
+    project_id: &ProjectId,
+    recipient: &Email,
+    subject: &str,
+    content: &str,
+    reply_to: &str,
+) -> Result<()> {
+    insert_email(
+        conn,
+        Some(project_id),
+        recipient,
+        subject,
+        content,
+        Some(reply_to),
+    )
+    .await
+}
+
+async fn insert_email(
+    conn: &mut PgConnection,
+    project_id: Option<&ProjectId>,
+    recipient: &Email,
+    subject: &str,
+    content: &str,
+    reply_to: Option<&str>,
+) -> Result<()> {
+    timed_query(
+        "enqueue_email",
+        sqlx::query!(
+            r#"
+                INSERT INTO email_outbox
+                    (recipient, subject, content, project_id, reply_to)
+                VALUES ($1, $2, $3, $4, $5)
+            "#,
+            recipient.as_ref().expose_secret(),
+            subject,
+            content,
+            project_id.map(|project_id| *project_id.as_ref()),
+            reply_to
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to insert email into outbox")?;
+
+    Ok(())
+}
+
+// The oldest unsent emails, locked until the transaction ends so concurrent
+// relays never send the same email twice.
+#[tracing::instrument(name = "Fetching pending emails", skip_all)]
+pub async fn fetch_pending_emails(
+    conn: &mut PgConnection,
+    batch_size: i64,
+) -> Result<Vec<PendingEmail>> {
+    let rows = timed_query(
+        "fetch_pending_emails",
+        sqlx::query!(
+            r#"
+                SELECT id, recipient, subject, content, reply_to
+                FROM email_outbox
+                WHERE sent_at IS NULL AND NOT suppressed
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            "#,
+            batch_size
+        )
+        .fetch_all(conn),
+    )
+    .await
+    .wrap_err("failed to fetch pending emails")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PendingEmail {
+            id: row.id,
+            recipient: row.recipient,
+            subject: row.subject,
+            content: row.content,
+            reply_to: row.reply_to,
+        })
+        .collect())
+}
+
+// How many separate emails, counting a digest as one, the recipient has been
+// sent since `since`
+#[tracing::instrument(name = "Counting recent email deliveries", skip_all)]
+pub async fn count_recent_deliveries(
+    conn: &mut PgConnection,
+    recipient: &str,
+    since: DateTime<Utc>,
+) -> Result<i64> {
+    let row = timed_query(
+        "count_recent_deliveries",
+        sqlx::query!(
+            r#"
+                SELECT COUNT(DISTINCT delivery_id) AS "count!"
+                FROM email_outbox
+                WHERE recipient = $1 AND sent_at > $2
+            "#,
+            recipient,
+            since
+        )
+        .fetch_one(conn),
+    )
+    .await
+    .wrap_err("failed to count recent email deliveries")?;
+
+    Ok(row.count)
+}
+
+// Mark emails as sent together in a single delivery. Tracked deliveries
+// carried the image that records them being opened.
+#[tracing::instrument(name = "Marking emails sent", skip_all)]
+pub async fn mark_emails_sent(
+    conn: &mut PgConnection,
+    ids: &[i64],
+    tracked: bool,
+) -> Result<()> {
+    timed_query(
+        "mark_emails_sent",
+        sqlx::query!(
+            r#"
+                UPDATE email_outbox
+                SET sent_at = now(), delivery_id = $1, tracked = $3
+                WHERE id = ANY($2)
+            "#,
+            ids.first().copied(),
+            ids,
+            tracked
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to mark emails sent")?;
+
+    Ok(())
+}
+
+// Whether opening emails sent to `recipient` may be recorded. Only users can
+// agree to it, so anyone else's emails are never tracked.
+#[tracing::instrument(name = "Checking email open tracking", skip_all)]
+pub async fn email_open_tracking_allowed(
+    conn: &mut PgConnection,
+    recipient: &str,
+) -> Result<bool> {
+    let allowed = timed_query(
+        "email_open_tracking_allowed",
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM users
+                    WHERE email = $1 AND email_open_tracking
+                ) AS "allowed!"
+            "#,
+            recipient
+        )
+        .fetch_one(conn),
+    )
+    .await
+    .wrap_err("failed to check email open tracking")?;
+
+    Ok(allowed)
+}
+
+// Record the first time a tracked delivery was opened
+#[tracing::instrument(name = "Recording email opened", skip_all)]
+pub async fn record_email_opened(
+    conn: &mut PgConnection,
+    delivery_id: i64,
+) -> Result<()> {
+    timed_query(
+        "record_email_opened",
+        sqlx::query!(
+            r#"
+                UPDATE email_outbox SET opened_at = now()
+                WHERE delivery_id = $1 AND tracked AND opened_at IS NULL
+            "#,
+            delivery_id
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to record email opened")?;
+
+    Ok(())
+}
+
+// Whether `recipient` bounced or complained and hasn't been confirmed since
+#[tracing::instrument(name = "Checking email deliverability", skip_all)]
+pub async fn email_undeliverable(
+    conn: &mut PgConnection,
+    recipient: &str,
+) -> Result<bool> {
+    let undeliverable = timed_query(
+        "email_undeliverable",
+        sqlx::query_scalar!(
+            r#"
+                SELECT EXISTS (
+                    SELECT 1 FROM undeliverable_emails WHERE email = $1
+                ) AS "undeliverable!"
+            "#,
+            recipient
+        )
+        .fetch_one(conn),
+    )
+    .await
+    .wrap_err("failed to check email deliverability")?;
+
+    Ok(undeliverable)
+}
+
+// Take emails to an undeliverable address out of the queue without sending
+// them
+#[tracing::instrument(name = "Marking emails suppressed", skip_all)]
+pub async fn mark_emails_suppressed(
+    conn: &mut PgConnection,
+    ids: &[i64],
+) -> Result<()> {
+    timed_query(
+        "mark_emails_suppressed",
+        sqlx::query!(
+            r#"
+                UPDATE email_outbox SET suppressed = true
+                WHERE id = ANY($1)
+            "#,
+            ids
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to mark emails suppressed")?;
+
+    Ok(())
+}
@@ -0,0 +1,120 @@
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::{PgConnection, PgPool};
+
+use crate::{domain::DomainEvent, utils::metrics::timed_query};
+
+// Write an event alongside the change that caused it. Call this with the
+// store's open transaction so the event is committed if and only if the
+// change is. Events that touch a project are also appended to its history.
+#[tracing::instrument(name = "Enqueueing domain event", skip_all)]
+pub async fn enqueue_event(
+    conn: &mut PgConnection,
+    event: &DomainEvent,
+) -> Result<()> {
+    let value = serde_json::to_value(event)
+        .wrap_err("failed to serialise domain event")?;
+
+    timed_query(
+        "enqueue_event",
+        sqlx::query!(
+            r#"
+                INSERT INTO event_outbox (event) VALUES ($1)
+            "#,
+            value
+        )
+        .execute(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to insert event into outbox")?;
+
+    if let (Some(project_id), Some(entity_type)) =
+        (event.project_id(), event.entity_type())
+    {
+        timed_query(
+            "enqueue_event.history",
+            sqlx::query!(
+                r#"
+                INSERT INTO project_events (project_id, entity_type, event)
+                VALUES ($1, $2, $3)
+            "#,
+                project_id.as_ref(),
+                entity_type.as_str(),
+                value
+            )
+            .execute(&mut *conn),
+        )
+        .await
+        .wrap_err("failed to insert event into project history")?;
+    }
+
+    Ok(())
+}
+
+// Hand the oldest undelivered events to `deliver`, then mark them delivered.
+// Rows stay locked until commit so concurrent relays never deliver the same
+// event twice; a crash before commit means they are delivered again later.
+#[tracing::instrument(name = "Relaying outbox events", skip_all)]
+pub async fn relay_outbox_events<F>(
+    pool: &PgPool,
+    batch_size: i64,
+    mut deliver: F,
+) -> Result<usize>
+where
+    F: FnMut(DomainEvent),
+{
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let rows = timed_query(
+        "relay_outbox_events.fetch",
+        sqlx::query!(
+            r#"
+                SELECT id, event
+                FROM event_outbox
+                WHERE delivered_at IS NULL
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            "#,
+            batch_size
+        )
+        .fetch_all(&mut *tx),
+    )
+    .await
+    .wrap_err("failed to fetch undelivered events")?;
+
+    if rows.is_empty() {
+        return Ok(0);
+    }
+
+    let mut ids = Vec::with_capacity(rows.len());
+    for row in rows {
+        ids.push(row.id);
+        match serde_json::from_value::<DomainEvent>(row.event) {
+            Ok(event) => deliver(event),
+            // A row we can't read will never become readable, so don't retry it
+            Err(e) => tracing::error!(
+                outbox_id = row.id,
+                "Discarding unreadable outbox event: {:?}",
+                e
+            ),
+        }
+    }
+
+    timed_query(
+        "relay_outbox_events.mark_delivered",
+        sqlx::query!(
+            r#"
+                UPDATE event_outbox SET delivered_at = now()
+                WHERE id = ANY($1)
+            "#,
+            &ids
+        )
+        .execute(&mut *tx),
+    )
+    .await
+    .wrap_err("failed to mark events delivered")?;
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(ids.len())
+}
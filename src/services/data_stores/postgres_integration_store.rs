@@ -0,0 +1,393 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::eyre;
+use sqlx::{pool::PoolConnection, PgPool, Postgres, Transaction};
+
+use crate::{
+    domain::{
+        DomainEvent, ExternalId, IntegrationKey, IntegrationKeyId,
+        IntegrationKeyName, IntegrationStore, IntegrationStoreError,
+        IntegrationUpdate, UserId,
+    },
+    services::data_stores::enqueue_event,
+    utils::{database::retry_on_disconnect, metrics::timed_query},
+};
+
+pub struct PostgresIntegrationStore {
+    pool: PgPool,
+}
+
+impl PostgresIntegrationStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // A connection, once PostgreSQL can be reached
+    async fn acquire(
+        &self,
+    ) -> Result<PoolConnection<Postgres>, IntegrationStoreError> {
+        retry_on_disconnect(|| self.pool.acquire())
+            .await
+            .map_err(unexpected_error)
+    }
+
+    async fn begin(
+        &self,
+    ) -> Result<Transaction<'static, Postgres>, IntegrationStoreError> {
+        retry_on_disconnect(|| self.pool.begin())
+            .await
+            .map_err(unexpected_error)
+    }
+}
+
+#[async_trait::async_trait]
+impl IntegrationStore for PostgresIntegrationStore {
+    #[tracing::instrument(
+        name = "Adding integration key to PostgreSQL",
+        skip_all
+    )]
+    async fn add_integration_key(
+        &mut self,
+        key: &IntegrationKey,
+        key_hash: &str,
+    ) -> Result<(), IntegrationStoreError> {
+        let mut tx = self.begin().await?;
+        timed_query(
+            "add_integration_key",
+            sqlx::query!(
+                r#"
+                INSERT INTO integration_keys
+                    (key_id, user_id, key_name, key_hash, created_at)
+                VALUES ($1, $2, $3, $4, $5)
+                "#,
+                key.key_id.as_ref(),
+                key.user_id.as_ref(),
+                key.name.as_ref(),
+                key_hash,
+                key.created_at
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::IntegrationKeyCreated {
+                user_id: key.user_id.clone(),
+                key_id: key.key_id.clone(),
+                key_name: key.name.clone(),
+            },
+        )
+        .await
+        .map_err(IntegrationStoreError::UnexpectedError)?;
+
+        tx.commit().await.map_err(unexpected_error)
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving integration keys from PostgreSQL",
+        skip_all
+    )]
+    async fn get_integration_keys(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<IntegrationKey>, IntegrationStoreError> {
+        let rows = timed_query(
+            "get_integration_keys",
+            sqlx::query!(
+                r#"
+                SELECT
+                    key_id, user_id, key_name, last_event_id, created_at,
+                    last_used_at
+                FROM integration_keys
+                WHERE user_id = $1
+                ORDER BY created_at, key_id
+                "#,
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(IntegrationKey {
+                    key_id: IntegrationKeyId::new(row.key_id),
+                    user_id: UserId::new(row.user_id),
+                    name: IntegrationKeyName::parse(&row.key_name).map_err(
+                        |e| IntegrationStoreError::UnexpectedError(eyre!(e)),
+                    )?,
+                    last_event_id: row.last_event_id,
+                    created_at: row.created_at,
+                    last_used_at: row.last_used_at,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(
+        name = "Deleting integration key from PostgreSQL",
+        skip_all
+    )]
+    async fn delete_integration_key(
+        &mut self,
+        user_id: &UserId,
+        key_id: &IntegrationKeyId,
+    ) -> Result<(), IntegrationStoreError> {
+        let result = timed_query(
+            "delete_integration_key",
+            sqlx::query!(
+                r#"
+                DELETE FROM integration_keys
+                WHERE key_id = $1 AND user_id = $2
+                "#,
+                key_id.as_ref(),
+                user_id.as_ref()
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        match result.rows_affected() {
+            0 => Err(IntegrationStoreError::KeyNotFound),
+            _ => Ok(()),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "Authenticating integration key in PostgreSQL",
+        skip_all
+    )]
+    async fn authenticate_integration_key(
+        &mut self,
+        key_hash: &str,
+        now: DateTime<Utc>,
+    ) -> Result<IntegrationKey, IntegrationStoreError> {
+        let row = timed_query(
+            "authenticate_integration_key",
+            sqlx::query!(
+                r#"
+                UPDATE integration_keys SET last_used_at = $2
+                WHERE key_hash = $1
+                RETURNING
+                    key_id, user_id, key_name, last_event_id, created_at,
+                    last_used_at
+                "#,
+                key_hash,
+                now
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(IntegrationStoreError::KeyNotFound)?;
+
+        Ok(IntegrationKey {
+            key_id: IntegrationKeyId::new(row.key_id),
+            user_id: UserId::new(row.user_id),
+            name: IntegrationKeyName::parse(&row.key_name).map_err(|e| {
+                IntegrationStoreError::UnexpectedError(eyre!(e))
+            })?,
+            last_event_id: row.last_event_id,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving integration updates from PostgreSQL",
+        skip_all
+    )]
+    async fn get_integration_updates(
+        &self,
+        user_id: &UserId,
+        after: i64,
+        settled_before: DateTime<Utc>,
+        limit: i64,
+    ) -> Result<Vec<IntegrationUpdate>, IntegrationStoreError> {
+        // Runs outside the tenant role, since tools poll with a key rather
+        // than a session, and is scoped by the user each event belongs to
+        let rows = timed_query(
+            "get_integration_updates",
+            sqlx::query!(
+                r#"
+                SELECT id, event, created_at
+                FROM event_outbox
+                WHERE event->>'user_id' = $1 AND id > $2 AND created_at < $3
+                ORDER BY id
+                LIMIT $4
+                "#,
+                user_id.as_ref().to_string(),
+                after,
+                settled_before,
+                limit
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                IntegrationUpdate::new(row.id, row.created_at, row.event)
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(
+        name = "Saving integration cursor in PostgreSQL",
+        skip_all
+    )]
+    async fn save_integration_cursor(
+        &mut self,
+        key_id: &IntegrationKeyId,
+        last_event_id: i64,
+    ) -> Result<(), IntegrationStoreError> {
+        // Never moves back, so replaying old updates with a cursor doesn't
+        // make polling without one repeat them
+        timed_query(
+            "save_integration_cursor",
+            sqlx::query!(
+                r#"
+                UPDATE integration_keys
+                SET last_event_id = GREATEST(last_event_id, $2)
+                WHERE key_id = $1
+                "#,
+                key_id.as_ref(),
+                last_event_id
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving provisioned integration key from PostgreSQL",
+        skip_all
+    )]
+    async fn get_provisioned_integration_key(
+        &self,
+        external_id: &ExternalId,
+    ) -> Result<IntegrationKey, IntegrationStoreError> {
+        let row = timed_query(
+            "get_provisioned_integration_key",
+            sqlx::query!(
+                r#"
+                SELECT
+                    key_id, user_id, key_name, last_event_id, created_at,
+                    last_used_at
+                FROM integration_keys
+                WHERE external_id = $1
+                "#,
+                external_id.as_ref()
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(IntegrationStoreError::KeyNotFound)?;
+
+        Ok(IntegrationKey {
+            key_id: IntegrationKeyId::new(row.key_id),
+            user_id: UserId::new(row.user_id),
+            name: IntegrationKeyName::parse(&row.key_name).map_err(|e| {
+                IntegrationStoreError::UnexpectedError(eyre!(e))
+            })?,
+            last_event_id: row.last_event_id,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Adding provisioned integration key to PostgreSQL",
+        skip_all
+    )]
+    async fn add_provisioned_integration_key(
+        &mut self,
+        key: &IntegrationKey,
+        key_hash: &str,
+        external_id: &ExternalId,
+    ) -> Result<(), IntegrationStoreError> {
+        let mut tx = self.begin().await?;
+        timed_query(
+            "add_provisioned_integration_key",
+            sqlx::query!(
+                r#"
+                INSERT INTO integration_keys
+                    (
+                        key_id, user_id, key_name, key_hash, created_at,
+                        external_id
+                    )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+                key.key_id.as_ref(),
+                key.user_id.as_ref(),
+                key.name.as_ref(),
+                key_hash,
+                key.created_at,
+                external_id.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                IntegrationStoreError::KeyAlreadyExists
+            }
+            e => unexpected_error(e),
+        })?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::IntegrationKeyCreated {
+                user_id: key.user_id.clone(),
+                key_id: key.key_id.clone(),
+                key_name: key.name.clone(),
+            },
+        )
+        .await
+        .map_err(IntegrationStoreError::UnexpectedError)?;
+
+        tx.commit().await.map_err(unexpected_error)
+    }
+
+    #[tracing::instrument(
+        name = "Renaming integration key in PostgreSQL",
+        skip_all
+    )]
+    async fn rename_integration_key(
+        &mut self,
+        key_id: &IntegrationKeyId,
+        name: &IntegrationKeyName,
+    ) -> Result<(), IntegrationStoreError> {
+        let result = timed_query(
+            "rename_integration_key",
+            sqlx::query!(
+                r#"
+                UPDATE integration_keys SET key_name = $2 WHERE key_id = $1
+                "#,
+                key_id.as_ref(),
+                name.as_ref()
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        match result.rows_affected() {
+            0 => Err(IntegrationStoreError::KeyNotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+fn unexpected_error(e: sqlx::Error) -> IntegrationStoreError {
+    IntegrationStoreError::UnexpectedError(eyre!(e))
+}
@@ -0,0 +1,141 @@
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::PgConnection;
+
+use crate::{domain::Job, utils::metrics::timed_query};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedJob {
+    pub id: i64,
+    pub job: Job,
+    // How many times the job was scheduled while it was waiting
+    pub count: i32,
+    pub attempts: i32,
+}
+
+// Schedule a job to run at `run_at`, or bump the count of the waiting job
+// with the same key. A waiting job keeps its original run time, so repeats
+// within the window are handled together.
+#[tracing::instrument(name = "Scheduling job", skip_all)]
+pub async fn schedule_job(
+    conn: &mut PgConnection,
+    job: &Job,
+    run_at: DateTime<Utc>,
+) -> Result<()> {
+    let value =
+        serde_json::to_value(job).wrap_err("failed to serialise job")?;
+
+    timed_query(
+        "schedule_job",
+        sqlx::query!(
+            r#"
+                INSERT INTO job_queue (kind, key, job, run_at)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (kind, key) WHERE completed_at IS NULL
+                DO UPDATE SET
+                    job = EXCLUDED.job,
+                    count = job_queue.count + 1
+            "#,
+            job.name(),
+            job.key(),
+            value,
+            run_at
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to schedule job")?;
+
+    Ok(())
+}
+
+// Jobs that are due, locked until the transaction ends so concurrent workers
+// never run the same job twice
+#[tracing::instrument(name = "Claiming due jobs", skip_all)]
+pub async fn claim_due_jobs(
+    conn: &mut PgConnection,
+    batch_size: i64,
+) -> Result<Vec<QueuedJob>> {
+    let rows = timed_query(
+        "claim_due_jobs",
+        sqlx::query!(
+            r#"
+                SELECT id, job, count, attempts
+                FROM job_queue
+                WHERE completed_at IS NULL AND run_at <= now()
+                ORDER BY run_at
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            "#,
+            batch_size
+        )
+        .fetch_all(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to fetch due jobs")?;
+
+    let mut jobs = Vec::with_capacity(rows.len());
+    for row in rows {
+        match serde_json::from_value::<Job>(row.job) {
+            Ok(job) => jobs.push(QueuedJob {
+                id: row.id,
+                job,
+                count: row.count,
+                attempts: row.attempts,
+            }),
+            // A job we can't read will never become readable, so don't retry it
+            Err(e) => {
+                tracing::error!(
+                    job_id = row.id,
+                    "Discarding unreadable job: {:?}",
+                    e
+                );
+                complete_job(&mut *conn, row.id).await?;
+            }
+        }
+    }
+
+    Ok(jobs)
+}
+
+#[tracing::instrument(name = "Completing job", skip_all)]
+pub async fn complete_job(conn: &mut PgConnection, id: i64) -> Result<()> {
+    timed_query(
+        "complete_job",
+        sqlx::query!(
+            r#"
+                UPDATE job_queue SET completed_at = now() WHERE id = $1
+            "#,
+            id
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to complete job")?;
+
+    Ok(())
+}
+
+#[tracing::instrument(name = "Rescheduling failed job", skip_all)]
+pub async fn retry_job(
+    conn: &mut PgConnection,
+    id: i64,
+    run_at: DateTime<Utc>,
+) -> Result<()> {
+    timed_query(
+        "retry_job",
+        sqlx::query!(
+            r#"
+                UPDATE job_queue SET attempts = attempts + 1, run_at = $2
+                WHERE id = $1
+            "#,
+            id,
+            run_at
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to reschedule job")?;
+
+    Ok(())
+}
@@ -1,22 +1,738 @@
-use std::collections::HashMap;
+use std::{collections::HashMap, str::FromStr};
 
+use chrono::{DateTime, Days, NaiveDate, Utc};
 use color_eyre::eyre::{eyre, Result};
-use sqlx::PgPool;
+use secrecy::{ExposeSecret, Secret};
+use sqlx::{pool::PoolConnection, PgConnection, PgPool, Postgres, Transaction};
 use uuid::Uuid;
 
-use crate::domain::{
-    Day, Member, MemberId, MemberName, Minute, Project, ProjectId,
-    ProjectMember, ProjectName, ProjectStore, ProjectStoreError, Shift,
-    ShiftId, UserId,
+#[cfg(feature = "chaos")]
+use crate::utils::chaos::FaultInjector;
+use crate::{
+    domain::{
+        Absence, AbsenceReason, ApprovalStatus, ChatWebhookUrl, Comment,
+        CommentBody, CommentId, ContractedHours, CronSchedule, DateRange, Day,
+        Department, DepartmentId, DepartmentName, DomainEvent, Email,
+        EmailReceipt, EntityType, HistoryCursor, Member, MemberContact,
+        MemberId, MemberName, MemberShiftTotals, Minute, PhoneNumber, Project,
+        ProjectHistoryEntry, ProjectId, ProjectInclude, ProjectMember,
+        ProjectName, ProjectSettings, ProjectStore, ProjectStoreError,
+        ProjectSummary, ProjectWindow, QuotaUsage, Reaction, ReviewComment,
+        RotaApproval, RotaApprovalId, RotaReaction, RotaVersion, RotaVersionId,
+        Shift, ShiftBreak, ShiftGranularity, ShiftId, ShiftLength,
+        ShiftLocation, ShiftNote, ShiftSlot, ShiftSlotId, ShiftTag,
+        SlotCoverage, TemplateShift, UserId, ValidationError, WeekTemplate,
+        WeekTemplateId, WeekTemplateName,
+    },
+    services::data_stores::{
+        enqueue_event, enqueue_project_email, enqueue_sms,
+    },
+    utils::{
+        constants::PROJECT_EMAILS_LIMIT, database::retry_on_disconnect,
+        deadline::Deadline, metrics::timed_query,
+    },
 };
 
 pub struct PostgresProjectStore {
     pool: PgPool,
+    #[cfg(feature = "chaos")]
+    faults: FaultInjector,
 }
 
 impl PostgresProjectStore {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            #[cfg(feature = "chaos")]
+            faults: FaultInjector::default(),
+        }
+    }
+
+    #[cfg(feature = "chaos")]
+    pub fn with_faults(pool: PgPool, faults: FaultInjector) -> Self {
+        Self { pool, faults }
+    }
+
+    async fn begin(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Transaction<'static, Postgres>, ProjectStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults
+            .before_begin()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        retry_on_disconnect(|| begin_tenant_transaction(&self.pool, user_id))
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))
+    }
+
+    // For the few queries made outside the tenant role
+    async fn acquire(
+        &self,
+    ) -> Result<PoolConnection<Postgres>, ProjectStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults
+            .before_begin()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        retry_on_disconnect(|| self.pool.acquire())
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))
+    }
+
+    async fn commit(
+        &self,
+        tx: Transaction<'static, Postgres>,
+    ) -> Result<(), ProjectStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults
+            .before_commit()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        tx.commit()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))
+    }
+}
+
+// Project tables are protected by row-level security, so every query runs in
+// a transaction that assumes the tenant role and identifies the requesting
+// user. A missing ownership check then finds no rows instead of leaking
+// another user's rota. Statements are also cut off at the request deadline.
+#[tracing::instrument(name = "Beginning tenant transaction", skip_all)]
+pub async fn begin_tenant_transaction(
+    pool: &PgPool,
+    user_id: &UserId,
+) -> Result<Transaction<'static, Postgres>, sqlx::Error> {
+    // statement_timeout = 0 disables the timeout, so never go below 1ms
+    let statement_timeout = Deadline::current()
+        .map(|deadline| deadline.remaining().as_millis().max(1).to_string());
+
+    let mut tx = pool.begin().await?;
+    timed_query(
+        "begin_tenant_transaction",
+        sqlx::query!(
+            r#"
+            SELECT
+                set_config('role', 'rota_tenant', true) AS role,
+                set_config('app.user_id', $1, true) AS user_id,
+                set_config(
+                    'statement_timeout',
+                    COALESCE($2, current_setting('statement_timeout')),
+                    true
+                ) AS statement_timeout
+        "#,
+            user_id.as_ref().to_string(),
+            statement_timeout
+        )
+        .fetch_one(&mut *tx),
+    )
+    .await?;
+    Ok(tx)
+}
+
+fn parse_contracted_hours(
+    hours: Option<f64>,
+) -> Result<Option<ContractedHours>, ProjectStoreError> {
+    hours
+        .map(ContractedHours::parse)
+        .transpose()
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))
+}
+
+fn skill_names(skills: &[ShiftTag]) -> Vec<String> {
+    skills
+        .iter()
+        .map(|skill| skill.as_ref().to_owned())
+        .collect()
+}
+
+fn parse_skills(skills: &[String]) -> Result<Vec<ShiftTag>, ProjectStoreError> {
+    Member::parse_skills(skills)
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))
+}
+
+// A member or slot can only be put in a department of its own project
+async fn check_department(
+    tx: &mut Transaction<'static, Postgres>,
+    project_id: &ProjectId,
+    department_id: Option<&DepartmentId>,
+) -> Result<(), ProjectStoreError> {
+    let Some(department_id) = department_id else {
+        return Ok(());
+    };
+    let exists = timed_query(
+        "check_department",
+        sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS (
+                SELECT 1 FROM departments
+                WHERE department_id = $1 AND project_id = $2
+            ) AS "exists!"
+            "#,
+            department_id.as_ref(),
+            project_id.as_ref()
+        )
+        .fetch_one(&mut **tx),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    if exists {
+        Ok(())
+    } else {
+        Err(ProjectStoreError::DepartmentIDNotFound)
+    }
+}
+
+// The breaks in each of `shift_ids`, in order
+async fn fetch_breaks(
+    tx: &mut Transaction<'static, Postgres>,
+    shift_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<ShiftBreak>>, ProjectStoreError> {
+    let rows = timed_query(
+        "fetch_breaks",
+        sqlx::query!(
+            r#"
+            SELECT shift_id, start_time, end_time, paid, auto_inserted
+            FROM breaks
+            WHERE shift_id = ANY($1)
+            ORDER BY start_time
+            "#,
+            shift_ids
+        )
+        .fetch_all(&mut **tx),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+    let unexpected =
+        |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+    let mut breaks = HashMap::<Uuid, Vec<ShiftBreak>>::new();
+    for row in rows {
+        breaks.entry(row.shift_id).or_default().push(ShiftBreak {
+            start_time: Minute::parse(row.start_time).map_err(unexpected)?,
+            end_time: Minute::parse(row.end_time).map_err(unexpected)?,
+            paid: row.paid,
+            auto_inserted: row.auto_inserted,
+        });
+    }
+    Ok(breaks)
+}
+
+// The tags on each of `shift_ids`, in alphabetical order
+async fn fetch_tags(
+    tx: &mut Transaction<'static, Postgres>,
+    shift_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<ShiftTag>>, ProjectStoreError> {
+    let rows = timed_query(
+        "fetch_tags",
+        sqlx::query!(
+            r#"
+            SELECT shift_id, tag
+            FROM shift_tags
+            WHERE shift_id = ANY($1)
+            ORDER BY tag
+            "#,
+            shift_ids
+        )
+        .fetch_all(&mut **tx),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+    let mut tags = HashMap::<Uuid, Vec<ShiftTag>>::new();
+    for row in rows {
+        tags.entry(row.shift_id).or_default().push(
+            ShiftTag::parse(&row.tag)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+        );
+    }
+    Ok(tags)
+}
+
+// Insert `shifts` with their breaks and tags, a statement per table however
+// many shifts there are
+async fn insert_shifts(
+    tx: &mut Transaction<'static, Postgres>,
+    shifts: &[Shift],
+) -> Result<(), ProjectStoreError> {
+    let ids: Vec<Uuid> =
+        shifts.iter().map(|shift| *shift.id.as_ref()).collect();
+    let member_ids: Vec<Uuid> = shifts
+        .iter()
+        .map(|shift| *shift.member_id.as_ref())
+        .collect();
+    let days: Vec<i16> = shifts.iter().map(|shift| shift.day as i16).collect();
+    let start_times: Vec<i16> = shifts
+        .iter()
+        .map(|shift| shift.start_time.value_of())
+        .collect();
+    let end_times: Vec<i16> = shifts
+        .iter()
+        .map(|shift| shift.end_time.value_of())
+        .collect();
+    let standby_member_ids: Vec<Option<Uuid>> = shifts
+        .iter()
+        .map(|shift| shift.standby_member_id.as_ref().map(|id| *id.as_ref()))
+        .collect();
+    let notes: Vec<Option<String>> = shifts
+        .iter()
+        .map(|shift| shift.note.as_ref().map(|note| note.as_ref().clone()))
+        .collect();
+    let locations: Vec<Option<String>> = shifts
+        .iter()
+        .map(|shift| {
+            shift
+                .location
+                .as_ref()
+                .map(|location| location.as_ref().clone())
+        })
+        .collect();
+    timed_query(
+        "insert_shifts",
+        sqlx::query!(
+            r#"
+            INSERT INTO shifts (
+                id, member_id, day, in_time, out_time, standby_member_id,
+                note, location
+            )
+            SELECT * FROM UNNEST(
+                $1::UUID[], $2::UUID[], $3::SMALLINT[], $4::SMALLINT[],
+                $5::SMALLINT[], $6::UUID[], $7::VARCHAR[], $8::VARCHAR[]
+            )
+            "#,
+            &ids,
+            &member_ids,
+            &days,
+            &start_times,
+            &end_times,
+            // Arrays with NULLs in need their element type spelling out
+            &standby_member_ids as &[Option<Uuid>],
+            &notes as &[Option<String>],
+            &locations as &[Option<String>]
+        )
+        .execute(&mut **tx),
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+            ProjectStoreError::ShiftIdExists
+        }
+        e => ProjectStoreError::UnexpectedError(eyre!(e)),
+    })?;
+
+    let breaks: Vec<(&Uuid, &ShiftBreak)> = shifts
+        .iter()
+        .zip(&ids)
+        .flat_map(|(shift, id)| shift.breaks.iter().map(move |b| (id, b)))
+        .collect();
+    if !breaks.is_empty() {
+        timed_query(
+            "insert_shifts.breaks",
+            sqlx::query!(
+                r#"
+                INSERT INTO breaks (
+                    shift_id, start_time, end_time, paid, auto_inserted
+                )
+                SELECT * FROM UNNEST(
+                    $1::UUID[], $2::SMALLINT[], $3::SMALLINT[], $4::BOOLEAN[],
+                    $5::BOOLEAN[]
+                )
+                "#,
+                &breaks.iter().map(|(id, _)| **id).collect::<Vec<_>>(),
+                &breaks
+                    .iter()
+                    .map(|(_, b)| b.start_time.value_of())
+                    .collect::<Vec<_>>(),
+                &breaks
+                    .iter()
+                    .map(|(_, b)| b.end_time.value_of())
+                    .collect::<Vec<_>>(),
+                &breaks.iter().map(|(_, b)| b.paid).collect::<Vec<_>>(),
+                &breaks
+                    .iter()
+                    .map(|(_, b)| b.auto_inserted)
+                    .collect::<Vec<_>>()
+            )
+            .execute(&mut **tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    }
+
+    let tags: Vec<(Uuid, String)> = shifts
+        .iter()
+        .zip(&ids)
+        .flat_map(|(shift, id)| {
+            shift
+                .tags
+                .iter()
+                .map(move |tag| (*id, tag.as_ref().clone()))
+        })
+        .collect();
+    if !tags.is_empty() {
+        let (tag_shift_ids, tags): (Vec<Uuid>, Vec<String>) =
+            tags.into_iter().unzip();
+        timed_query(
+            "insert_shifts.tags",
+            sqlx::query!(
+                r#"
+                INSERT INTO shift_tags (shift_id, tag)
+                SELECT * FROM UNNEST($1::UUID[], $2::VARCHAR[])
+                "#,
+                &tag_shift_ids,
+                &tags
+            )
+            .execute(&mut **tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    }
+
+    Ok(())
+}
+
+// The shifts in each of `template_ids`, in week order so that clashes are
+// reported for the earliest shift
+async fn fetch_template_shifts(
+    tx: &mut Transaction<'static, Postgres>,
+    template_ids: &[Uuid],
+) -> Result<HashMap<Uuid, Vec<TemplateShift>>, ProjectStoreError> {
+    let rows = timed_query(
+        "fetch_template_shifts",
+        sqlx::query!(
+            r#"
+            SELECT
+                template_id, member_id, standby_member_id, day, in_time,
+                out_time, tags, note, location
+            FROM week_template_shifts
+            WHERE template_id = ANY($1)
+            ORDER BY day, in_time, member_id
+            "#,
+            template_ids
+        )
+        .fetch_all(&mut **tx),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+    let unexpected =
+        |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+    let mut shifts = HashMap::<Uuid, Vec<TemplateShift>>::new();
+    for row in rows {
+        let tags: Vec<String> = serde_json::from_value(row.tags)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        shifts
+            .entry(row.template_id)
+            .or_default()
+            .push(TemplateShift {
+                member_id: MemberId::new(row.member_id),
+                standby_member_id: row.standby_member_id.map(MemberId::new),
+                day: Day::try_from(row.day).map_err(unexpected)?,
+                start_time: Minute::parse(row.in_time).map_err(unexpected)?,
+                end_time: Minute::parse(row.out_time).map_err(unexpected)?,
+                tags: ShiftTag::parse_all(&tags).map_err(unexpected)?,
+                note: row
+                    .note
+                    .as_deref()
+                    .map(ShiftNote::parse)
+                    .transpose()
+                    .map_err(unexpected)?
+                    .flatten(),
+                location: row
+                    .location
+                    .as_deref()
+                    .map(ShiftLocation::parse)
+                    .transpose()
+                    .map_err(unexpected)?
+                    .flatten(),
+            });
+    }
+    Ok(shifts)
+}
+
+async fn insert_rota_version(
+    conn: &mut PgConnection,
+    version: &RotaVersion,
+) -> Result<(), ProjectStoreError> {
+    let shifts = serde_json::to_value(&version.shifts)
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+    timed_query(
+        "insert_rota_version",
+        sqlx::query!(
+            r#"
+            INSERT INTO rota_versions
+                (version_id, project_id, week_of, shifts, automatic,
+                published_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+            version.version_id.as_ref(),
+            version.project_id.as_ref(),
+            version.week_of,
+            shifts,
+            version.automatic,
+            version.published_at
+        )
+        .execute(&mut *conn),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    Ok(())
+}
+
+// Review one approval as part of a larger transaction
+async fn review_rota_in(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+    approval_id: &RotaApprovalId,
+    approve: bool,
+    comment: Option<&ReviewComment>,
+) -> Result<RotaApproval, ProjectStoreError> {
+    // Approvers don't own the projects, so this runs outside the tenant
+    // role and is scoped by the user's email instead
+    let row = timed_query(
+        "review_rota.approval",
+        sqlx::query_as!(
+            RotaApprovalRow,
+            r#"
+            SELECT rota_approvals.approval_id, rota_approvals.project_id,
+                projects_list.project_name, rota_approvals.version_id,
+                rota_approvals.week_of, rota_approvals.shifts,
+                rota_approvals.changes, rota_approvals.automatic,
+                rota_approvals.approver_email, rota_approvals.status,
+                rota_approvals.comment, rota_approvals.submitted_at,
+                rota_approvals.reviewed_at
+            FROM rota_approvals
+            INNER JOIN projects_list
+                ON projects_list.project_id = rota_approvals.project_id
+            WHERE rota_approvals.approval_id = $1
+            AND rota_approvals.approver_email =
+                (SELECT email FROM users WHERE id = $2)
+            FOR UPDATE OF rota_approvals
+            "#,
+            approval_id.as_ref(),
+            user_id.as_ref()
+        )
+        .fetch_optional(&mut *conn),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+    .ok_or(ProjectStoreError::ApprovalIDNotFound)?;
+
+    let mut approval = RotaApproval::try_from(row)?;
+    if approval.status != ApprovalStatus::Pending {
+        return Err(ProjectStoreError::AlreadyReviewed);
+    }
+    let now = Utc::now();
+    approval.status = if approve {
+        ApprovalStatus::Approved
+    } else {
+        ApprovalStatus::Rejected
+    };
+    approval.comment = comment.cloned();
+    approval.reviewed_at = Some(now);
+    approval.version.published_at = now;
+
+    timed_query(
+        "review_rota.update",
+        sqlx::query!(
+            r#"
+            UPDATE rota_approvals
+            SET status = $2, comment = $3, reviewed_at = $4
+            WHERE approval_id = $1
+            "#,
+            approval_id.as_ref(),
+            approval.status.as_str(),
+            comment.map(|comment| comment.as_ref().as_str()),
+            now
+        )
+        .execute(&mut *conn),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+    let owner = timed_query(
+        "review_rota.owner",
+        sqlx::query!(
+            r#"
+            SELECT projects_list.user_id, users.email
+            FROM projects_list
+            INNER JOIN users ON users.id = projects_list.user_id
+            WHERE projects_list.project_id = $1
+            "#,
+            approval.version.project_id.as_ref()
+        )
+        .fetch_one(&mut *conn),
+    )
+    .await
+    .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    let owner_id = UserId::new(owner.user_id);
+
+    let version = &approval.version;
+    enqueue_event(
+        conn,
+        &DomainEvent::RotaReviewed {
+            user_id: owner_id.clone(),
+            project_id: version.project_id.clone(),
+            week_of: version.week_of,
+            approved: approve,
+            comment: approval.comment.clone(),
+        },
+    )
+    .await
+    .map_err(ProjectStoreError::UnexpectedError)?;
+
+    if approve {
+        insert_rota_version(conn, version).await?;
+        enqueue_event(
+            conn,
+            &DomainEvent::RotaPublished {
+                user_id: owner_id,
+                project_id: version.project_id.clone(),
+                week_of: version.week_of,
+                shifts: version.shifts.len() as u64,
+                automatic: version.automatic,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+    }
+
+    let owner_email = Email::parse(Secret::new(owner.email))
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+    let (subject, content) = approval.review_email();
+    enqueue_project_email(
+        conn,
+        &version.project_id,
+        &owner_email,
+        &subject,
+        &content,
+    )
+    .await
+    .map_err(ProjectStoreError::UnexpectedError)?;
+
+    Ok(approval)
+}
+
+struct CommentRow {
+    comment_id: Uuid,
+    project_id: Uuid,
+    shift_id: Option<Uuid>,
+    author_id: Uuid,
+    body: String,
+    mentions: Vec<Uuid>,
+    posted_at: DateTime<Utc>,
+}
+
+impl TryFrom<CommentRow> for Comment {
+    type Error = ProjectStoreError;
+
+    fn try_from(row: CommentRow) -> Result<Self, Self::Error> {
+        Ok(Self {
+            comment_id: CommentId::new(row.comment_id),
+            project_id: ProjectId::new(row.project_id),
+            shift_id: row.shift_id.map(ShiftId::new),
+            author_id: UserId::new(row.author_id),
+            body: CommentBody::parse(&row.body)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            mentions: row.mentions.into_iter().map(MemberId::new).collect(),
+            posted_at: row.posted_at,
+        })
+    }
+}
+
+// A rota approval as stored, with its project's name
+struct RotaApprovalRow {
+    approval_id: Uuid,
+    project_id: Uuid,
+    project_name: String,
+    version_id: Uuid,
+    week_of: NaiveDate,
+    shifts: serde_json::Value,
+    changes: serde_json::Value,
+    automatic: bool,
+    approver_email: String,
+    status: String,
+    comment: Option<String>,
+    submitted_at: DateTime<Utc>,
+    reviewed_at: Option<DateTime<Utc>>,
+}
+
+impl TryFrom<RotaApprovalRow> for RotaApproval {
+    type Error = ProjectStoreError;
+
+    fn try_from(row: RotaApprovalRow) -> Result<Self, Self::Error> {
+        let unexpected =
+            |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+        Ok(Self {
+            approval_id: RotaApprovalId::new(row.approval_id),
+            project_name: ProjectName::parse(&row.project_name)
+                .map_err(unexpected)?,
+            version: RotaVersion {
+                version_id: RotaVersionId::new(row.version_id),
+                project_id: ProjectId::new(row.project_id),
+                week_of: row.week_of,
+                shifts: serde_json::from_value(row.shifts).map_err(|e| {
+                    ProjectStoreError::UnexpectedError(eyre!(e))
+                })?,
+                automatic: row.automatic,
+                published_at: row.reviewed_at.unwrap_or(row.submitted_at),
+            },
+            changes: serde_json::from_value(row.changes)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            approver_email: Email::parse(Secret::new(row.approver_email))
+                .map_err(unexpected)?,
+            status: ApprovalStatus::from_str(&row.status)
+                .map_err(unexpected)?,
+            comment: row
+                .comment
+                .as_deref()
+                .map(ReviewComment::parse)
+                .transpose()
+                .map_err(unexpected)?
+                .flatten(),
+            submitted_at: row.submitted_at,
+            reviewed_at: row.reviewed_at,
+        })
+    }
+}
+
+struct ShiftSlotRow {
+    slot_id: Uuid,
+    project_id: Uuid,
+    day: i16,
+    in_time: i16,
+    out_time: i16,
+    places: i16,
+    tag: Option<String>,
+    department_id: Option<Uuid>,
+}
+
+impl TryFrom<ShiftSlotRow> for ShiftSlot {
+    type Error = ProjectStoreError;
+
+    fn try_from(row: ShiftSlotRow) -> Result<Self, Self::Error> {
+        let unexpected =
+            |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+        let mut slot = ShiftSlot::new(
+            ShiftSlotId::new(row.slot_id),
+            ProjectId::new(row.project_id),
+            Day::try_from(row.day).map_err(unexpected)?,
+            Minute::parse(row.in_time).map_err(unexpected)?,
+            Minute::parse(row.out_time).map_err(unexpected)?,
+            row.places,
+        )
+        .map_err(unexpected)?;
+        slot.tag = row
+            .tag
+            .as_deref()
+            .map(ShiftTag::parse)
+            .transpose()
+            .map_err(unexpected)?;
+        slot.department_id = row.department_id.map(DepartmentId::new);
+        Ok(slot)
     }
 }
 
@@ -30,19 +746,24 @@ impl ProjectStore for PostgresProjectStore {
         &mut self,
         user_id: &UserId,
     ) -> Result<Vec<(ProjectId, ProjectName)>, ProjectStoreError> {
-        let rows = sqlx::query!(
-            r#"
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_project_list",
+            sqlx::query!(
+                r#"
                     SELECT project_id, project_name
                     FROM projects_list
                     WHERE user_id = $1
                     "#,
-            user_id.as_ref()
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
         )
-        .fetch_all(&self.pool)
         .await
         .map_err(|e| match e {
             err => ProjectStoreError::UnexpectedError(err.into()),
         })?;
+        self.commit(tx).await?;
 
         rows.into_iter()
             .map(|row| {
@@ -56,6 +777,190 @@ impl ProjectStore for PostgresProjectStore {
             .collect()
     }
 
+    #[tracing::instrument(
+        name = "Getting project summaries from PostgreSQL",
+        skip_all
+    )]
+    async fn get_project_summaries(
+        &mut self,
+        user_id: &UserId,
+        project_ids: &[ProjectId],
+    ) -> Result<Vec<ProjectSummary>, ProjectStoreError> {
+        let project_ids: Vec<Uuid> =
+            project_ids.iter().map(|id| *id.as_ref()).collect();
+
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_project_summaries",
+            sqlx::query!(
+                r#"
+                SELECT
+                    projects_list.project_id,
+                    projects_list.project_name,
+                    projects_list.week_start,
+                    (
+                        SELECT count(*) FROM members
+                        WHERE members.project_id = projects_list.project_id
+                    ) AS "members!",
+                    (
+                        SELECT count(*) FROM shifts
+                        INNER JOIN members
+                            ON members.member_id = shifts.member_id
+                        WHERE members.project_id = projects_list.project_id
+                    ) AS "shifts!"
+                FROM projects_list
+                WHERE projects_list.project_id = ANY($1)
+                AND projects_list.user_id = $2
+                "#,
+                &project_ids,
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ProjectSummary {
+                    project_id: ProjectId::new(row.project_id),
+                    project_name: ProjectName::parse(&row.project_name)
+                        .map_err(|e| {
+                            ProjectStoreError::UnexpectedError(eyre!(e))
+                        })?,
+                    week_start: Day::try_from(row.week_start).map_err(|e| {
+                        ProjectStoreError::UnexpectedError(eyre!(e))
+                    })?,
+                    members: row.members,
+                    shifts: row.shifts,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(
+        name = "Getting quota usage from PostgreSQL",
+        skip_all
+    )]
+    async fn get_quota_usage(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<QuotaUsage, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_quota_usage",
+            sqlx::query!(
+                r#"
+                SELECT
+                    (
+                        SELECT count(*) FROM projects_list
+                        WHERE projects_list.user_id = $1
+                    ) AS "projects!",
+                    (
+                        SELECT count(*) FROM members
+                        INNER JOIN projects_list
+                            ON projects_list.project_id = members.project_id
+                        WHERE projects_list.user_id = $1
+                    ) AS "members!",
+                    (
+                        SELECT count(*) FROM shifts
+                        INNER JOIN members
+                            ON members.member_id = shifts.member_id
+                        INNER JOIN projects_list
+                            ON projects_list.project_id = members.project_id
+                        WHERE projects_list.user_id = $1
+                    ) AS "shifts!"
+                "#,
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        Ok(QuotaUsage {
+            projects: row.projects,
+            members: row.members,
+            shifts: row.shifts,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Getting upcoming shift totals from PostgreSQL",
+        skip_all
+    )]
+    async fn get_upcoming_shift_totals(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        from: NaiveDate,
+    ) -> Result<Vec<MemberShiftTotals>, ProjectStoreError> {
+        let to = from + Days::new(6);
+
+        // A shift has at most one absence in any seven days, so joining
+        // them doesn't count any shift twice
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_upcoming_shift_totals",
+            sqlx::query!(
+                r#"
+                SELECT
+                    members.member_id,
+                    members.member_name,
+                    count(paid_shifts.id) - count(absences.id) AS "shifts!",
+                    COALESCE(
+                        sum(paid_shifts.minutes)
+                            FILTER (WHERE absences.id IS NULL),
+                        0
+                    )::BIGINT AS "minutes!",
+                    count(absences.id) AS "absences!"
+                FROM members
+                LEFT JOIN LATERAL (
+                    SELECT
+                        shifts.id,
+                        shifts.out_time - shifts.in_time - COALESCE((
+                            SELECT sum(breaks.end_time - breaks.start_time)
+                            FROM breaks
+                            WHERE breaks.shift_id = shifts.id
+                            AND NOT breaks.paid
+                        ), 0) AS minutes
+                    FROM shifts
+                    WHERE shifts.member_id = members.member_id
+                ) AS paid_shifts ON TRUE
+                LEFT JOIN absences
+                    ON absences.shift_id = paid_shifts.id
+                    AND absences.date BETWEEN $2 AND $3
+                WHERE members.project_id = $1
+                GROUP BY members.member_id, members.member_name
+                ORDER BY members.member_name, members.member_id
+                "#,
+                project_id.as_ref(),
+                from,
+                to
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(MemberShiftTotals {
+                    member_id: MemberId::new(row.member_id),
+                    member_name: MemberName::parse(row.member_name).map_err(
+                        |e| ProjectStoreError::UnexpectedError(eyre!(e)),
+                    )?,
+                    shifts: row.shifts,
+                    minutes: row.minutes,
+                    absences: row.absences,
+                })
+            })
+            .collect()
+    }
+
     #[tracing::instrument(name = "Adding project to PostgreSQL", skip_all)]
     async fn add_project(
         &mut self,
@@ -63,15 +968,19 @@ impl ProjectStore for PostgresProjectStore {
         project_id: &ProjectId,
         project_name: &ProjectName,
     ) -> Result<(), ProjectStoreError> {
-        sqlx::query!(
-            r#"
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "add_project",
+            sqlx::query!(
+                r#"
             INSERT INTO projects_list (user_id, project_id, project_name) VALUES ($1, $2, $3)
             "#,
-            user_id.as_ref() as &uuid::Uuid,
-            project_id.as_ref() as &uuid::Uuid,
-            project_name.as_ref(),
+                user_id.as_ref() as &uuid::Uuid,
+                project_id.as_ref() as &uuid::Uuid,
+                project_name.as_ref(),
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
@@ -79,7 +988,17 @@ impl ProjectStore for PostgresProjectStore {
             }
             err => ProjectStoreError::UnexpectedError(err.into()),
         })?;
-        Ok(())
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::ProjectCreated {
+                user_id: user_id.clone(),
+                project_id: project_id.clone(),
+                project_name: project_name.clone(),
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
     }
 
     #[tracing::instrument(name = "Deleting all projects for user", skip_all)]
@@ -87,17 +1006,21 @@ impl ProjectStore for PostgresProjectStore {
         &mut self,
         user_id: &UserId,
     ) -> Result<(), ProjectStoreError> {
-        sqlx::query!(
-            r#"
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "delete_projects",
+            sqlx::query!(
+                r#"
                    DELETE FROM projects_list WHERE user_id = $1
                    "#,
-            user_id.as_ref(),
+                user_id.as_ref(),
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
 
-        Ok(())
+        self.commit(tx).await
     }
 
     #[tracing::instrument(name = "Adding member to PostgreSQL", skip_all)]
@@ -113,15 +1036,35 @@ impl ProjectStore for PostgresProjectStore {
             .find(|(id, _)| id == &member.project_id)
             .ok_or(ProjectStoreError::ProjectIDNotFound)?;
 
-        sqlx::query!(
-            r#"
-            INSERT INTO members (member_id, project_id, member_name) VALUES ($1, $2, $3)
+        let mut tx = self.begin(user_id).await?;
+        check_department(
+            &mut tx,
+            &member.project_id,
+            member.department_id.as_ref(),
+        )
+        .await?;
+        timed_query(
+            "add_member",
+            sqlx::query!(
+                r#"
+            INSERT INTO members (
+                member_id, project_id, member_name, contracted_hours,
+                department_id, skills
+            )
+            VALUES ($1, $2, $3, $4, $5, $6)
             "#,
-            member.member_id.as_ref() as &uuid::Uuid,
-            member.project_id.as_ref() as &uuid::Uuid,
-            member.member_name.as_ref(),
+                member.member_id.as_ref() as &uuid::Uuid,
+                member.project_id.as_ref() as &uuid::Uuid,
+                member.member_name.as_ref(),
+                member
+                    .contracted_hours
+                    .as_ref()
+                    .map(|hours| *hours.as_ref()),
+                member.department_id.as_ref().map(|id| *id.as_ref()),
+                &skill_names(&member.skills),
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
@@ -129,7 +1072,18 @@ impl ProjectStore for PostgresProjectStore {
             }
             e => ProjectStoreError::UnexpectedError(eyre!(e)),
         })?;
-        Ok(())
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::MemberAdded {
+                user_id: user_id.clone(),
+                project_id: member.project_id.clone(),
+                member_id: member.member_id.clone(),
+                member_name: member.member_name.clone(),
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
     }
 
     #[tracing::instrument(name = "Getting member from PostgreSQL", skip_all)]
@@ -138,32 +1092,40 @@ impl ProjectStore for PostgresProjectStore {
         user_id: &UserId,
         member_id: &MemberId,
     ) -> Result<Member, ProjectStoreError> {
-        sqlx::query!(
-            r#"
-                SELECT members.project_id, members.member_id, members.member_name
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_member",
+            sqlx::query!(
+                r#"
+                SELECT
+                    members.project_id, members.member_id, members.member_name,
+                    members.contracted_hours, members.department_id,
+                    members.skills
                 FROM members
                 INNER JOIN projects_list ON members.project_id = projects_list.project_id
                 WHERE members.member_id = $1 AND projects_list.user_id = $2
             "#,
-            member_id.as_ref(),
-            user_id.as_ref()
+                member_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
         )
-        .fetch_one(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => ProjectStoreError::MemberIDNotFound,
             e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        self.commit(tx).await?;
+
+        Ok(Member {
+            project_id: ProjectId::new(row.project_id),
+            member_id: MemberId::new(row.member_id),
+            member_name: MemberName::parse(row.member_name.to_owned())
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            contracted_hours: parse_contracted_hours(row.contracted_hours)?,
+            department_id: row.department_id.map(DepartmentId::new),
+            skills: parse_skills(&row.skills)?,
         })
-        .map(|row| {
-            Ok(Member {
-                project_id: ProjectId::new(row.project_id),
-                member_id: MemberId::new(row.member_id),
-                member_name: MemberName::parse(row.member_name.to_owned())
-                    .map_err(|e| {
-                        ProjectStoreError::UnexpectedError(eyre!(e))
-                    })?,
-            })
-        })?
     }
 
     #[tracing::instrument(name = "Updating member in PostgreSQL", skip_all)]
@@ -179,21 +1141,188 @@ impl ProjectStore for PostgresProjectStore {
             .find(|(id, _)| id == &member.project_id)
             .ok_or(ProjectStoreError::ProjectIDNotFound)?;
 
-        sqlx::query!(
-            r#"
-            UPDATE members SET member_name = $2
+        let mut tx = self.begin(user_id).await?;
+        check_department(
+            &mut tx,
+            &member.project_id,
+            member.department_id.as_ref(),
+        )
+        .await?;
+        timed_query(
+            "update_member",
+            sqlx::query!(
+                r#"
+            UPDATE members
+            SET
+                member_name = $2, contracted_hours = $3, department_id = $4,
+                skills = $5
             WHERE member_id = $1
             "#,
-            member.member_id.as_ref() as &uuid::Uuid,
-            member.member_name.as_ref(),
+                member.member_id.as_ref() as &uuid::Uuid,
+                member.member_name.as_ref(),
+                member
+                    .contracted_hours
+                    .as_ref()
+                    .map(|hours| *hours.as_ref()),
+                member.department_id.as_ref().map(|id| *id.as_ref()),
+                &skill_names(&member.skills),
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => ProjectStoreError::MemberIDNotFound,
             e => ProjectStoreError::UnexpectedError(eyre!(e)),
         })?;
-        Ok(())
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::MemberUpdated {
+                user_id: user_id.clone(),
+                project_id: member.project_id.clone(),
+                member_id: member.member_id.clone(),
+                member_name: member.member_name.clone(),
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting member contact from PostgreSQL",
+        skip_all
+    )]
+    async fn get_member_contact(
+        &mut self,
+        user_id: &UserId,
+        member_id: &MemberId,
+    ) -> Result<MemberContact, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_member_contact",
+            sqlx::query!(
+                r#"
+                SELECT
+                    members.phone_number,
+                    members.sms_opt_in,
+                    members.email,
+                    members.email_opt_in
+                FROM members
+                INNER JOIN projects_list ON members.project_id = projects_list.project_id
+                WHERE members.member_id = $1 AND projects_list.user_id = $2
+            "#,
+                member_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::MemberIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        self.commit(tx).await?;
+
+        Ok(MemberContact {
+            phone_number: row
+                .phone_number
+                .map(PhoneNumber::parse)
+                .transpose()
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            sms_opt_in: row.sms_opt_in,
+            email: row
+                .email
+                .map(|email| Email::parse(Secret::new(email)))
+                .transpose()
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            email_opt_in: row.email_opt_in,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Updating member contact in PostgreSQL",
+        skip_all
+    )]
+    async fn update_member_contact(
+        &mut self,
+        user_id: &UserId,
+        member_id: &MemberId,
+        contact: &MemberContact,
+    ) -> Result<(), ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let result = timed_query(
+            "update_member_contact",
+            sqlx::query!(
+                r#"
+            UPDATE members
+            SET phone_number = $2, sms_opt_in = $3, email = $4,
+                email_opt_in = $5
+            WHERE member_id = $1
+            "#,
+                member_id.as_ref() as &uuid::Uuid,
+                contact.phone_number.as_ref().map(|number| number.as_ref()),
+                contact.sms_opt_in,
+                contact
+                    .email
+                    .as_ref()
+                    .map(|email| email.as_ref().expose_secret().as_str()),
+                contact.email_opt_in
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ProjectStoreError::MemberIDNotFound);
+        }
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Broadcasting SMS to project", skip_all)]
+    async fn broadcast_sms(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        content: &str,
+    ) -> Result<usize, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "broadcast_sms",
+            sqlx::query!(
+                r#"
+            SELECT projects_list.project_id, members.phone_number
+            FROM projects_list
+            LEFT JOIN members ON members.project_id = projects_list.project_id
+                AND members.sms_opt_in
+            WHERE projects_list.project_id = $1
+            AND projects_list.user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        if rows.is_empty() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        let mut queued = 0;
+        for phone_number in rows.into_iter().filter_map(|row| row.phone_number)
+        {
+            let recipient = PhoneNumber::parse(phone_number)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+            enqueue_sms(&mut tx, &recipient, content)
+                .await
+                .map_err(ProjectStoreError::UnexpectedError)?;
+            queued += 1;
+        }
+        self.commit(tx).await?;
+
+        Ok(queued)
     }
 
     #[tracing::instrument(name = "Getting members from PostgreSQL", skip_all)]
@@ -209,20 +1338,27 @@ impl ProjectStore for PostgresProjectStore {
             .find(|(id, _)| id == project_id)
             .ok_or(ProjectStoreError::ProjectIDNotFound)?;
 
-        let rows = sqlx::query!(
-            r#"
-                SELECT project_id, member_id, member_name
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_members",
+            sqlx::query!(
+                r#"
+                SELECT
+                    project_id, member_id, member_name, contracted_hours,
+                    department_id, skills
                 FROM members
                 WHERE project_id = $1
             "#,
-            project_id.as_ref()
+                project_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
         )
-        .fetch_all(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => ProjectStoreError::MemberIDNotFound,
             e => ProjectStoreError::UnexpectedError(eyre!(e)),
         })?;
+        self.commit(tx).await?;
 
         rows.into_iter()
             .map(|row| {
@@ -233,6 +1369,11 @@ impl ProjectStore for PostgresProjectStore {
                         .map_err(|e| {
                             ProjectStoreError::UnexpectedError(eyre!(e))
                         })?,
+                    contracted_hours: parse_contracted_hours(
+                        row.contracted_hours,
+                    )?,
+                    department_id: row.department_id.map(DepartmentId::new),
+                    skills: parse_skills(&row.skills)?,
                 };
                 Ok(member)
             })
@@ -252,17 +1393,21 @@ impl ProjectStore for PostgresProjectStore {
             .find(|(id, _)| id == project_id)
             .ok_or(ProjectStoreError::ProjectIDNotFound)?;
 
-        sqlx::query!(
-            r#"
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "delete_members",
+            sqlx::query!(
+                r#"
                 DELETE FROM members WHERE project_id = $1
             "#,
-            project_id.as_ref(),
+                project_id.as_ref(),
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
 
-        Ok(())
+        self.commit(tx).await
     }
 
     #[tracing::instrument(name = "Adding shift to PostgreSQL", skip_all)]
@@ -271,100 +1416,404 @@ impl ProjectStore for PostgresProjectStore {
         user_id: &UserId,
         shift: &Shift,
     ) -> Result<(), ProjectStoreError> {
-        let _member = self.get_member(&user_id, &shift.member_id).await?;
+        let member = self.get_member(user_id, &shift.member_id).await?;
+        let standby_member = match &shift.standby_member_id {
+            Some(standby_member_id) => {
+                Some(self.get_member(user_id, standby_member_id).await?)
+            }
+            None => None,
+        };
 
-        sqlx::query!(
-            r#"
-            INSERT INTO shifts (id, member_id, day, in_time, out_time) VALUES ($1, $2, $3, $4, $5)
+        let mut tx = self.begin(user_id).await?;
+        insert_shifts(&mut tx, std::slice::from_ref(shift)).await?;
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::ShiftCreated {
+                user_id: user_id.clone(),
+                project_id: member.project_id,
+                member_id: shift.member_id.clone(),
+                member_name: member.member_name,
+                shift_id: shift.id.clone(),
+                day: shift.day,
+                start_time: shift.start_time.clone(),
+                end_time: shift.end_time.clone(),
+                standby_member_id: shift.standby_member_id.clone(),
+                standby_member_name: standby_member
+                    .map(|standby| standby.member_name),
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Getting shift from PostgreSQL", skip_all)]
+    async fn get_shift(
+        &mut self,
+        user_id: &UserId,
+        shift_id: &ShiftId,
+    ) -> Result<Shift, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_shift",
+            sqlx::query!(
+                r#"
+            SELECT
+                id, member_id, day, in_time, out_time, standby_member_id,
+                note, location
+            FROM shifts
+            WHERE id = $1
+            "#,
+                shift_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ShiftIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        let mut breaks = fetch_breaks(&mut tx, &[row.id]).await?;
+        let mut tags = fetch_tags(&mut tx, &[row.id]).await?;
+        self.commit(tx).await?;
+
+        let unexpected =
+            |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+        Ok(Shift {
+            id: ShiftId::new(row.id),
+            member_id: MemberId::new(row.member_id),
+            day: Day::try_from(row.day).map_err(unexpected)?,
+            start_time: Minute::parse(row.in_time).map_err(unexpected)?,
+            end_time: Minute::parse(row.out_time).map_err(unexpected)?,
+            breaks: breaks.remove(&row.id).unwrap_or_default(),
+            standby_member_id: row.standby_member_id.map(MemberId::new),
+            tags: tags.remove(&row.id).unwrap_or_default(),
+            note: row
+                .note
+                .as_deref()
+                .map(ShiftNote::parse)
+                .transpose()
+                .map_err(unexpected)?
+                .flatten(),
+            location: row
+                .location
+                .as_deref()
+                .map(ShiftLocation::parse)
+                .transpose()
+                .map_err(unexpected)?
+                .flatten(),
+        })
+    }
+
+    #[tracing::instrument(name = "Clearing shifts in PostgreSQL", skip_all)]
+    async fn clear_shifts(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        day: Option<Day>,
+        dry_run: bool,
+    ) -> Result<u64, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let project = timed_query(
+            "clear_shifts.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT project_id FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        if dry_run {
+            let shifts = timed_query(
+                "clear_shifts.count",
+                sqlx::query_scalar!(
+                    r#"
+                SELECT COUNT(*) AS "count!"
+                FROM shifts
+                INNER JOIN members ON members.member_id = shifts.member_id
+                WHERE members.project_id = $1
+                AND ($2::SMALLINT IS NULL OR shifts.day = $2)
+                "#,
+                    project_id.as_ref(),
+                    day.map(|day| day as i16)
+                )
+                .fetch_one(&mut *tx),
+            )
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+            self.commit(tx).await?;
+            return Ok(shifts as u64);
+        }
+
+        // Breaks, tags, absences and reminders go with their shifts
+        let shifts = timed_query(
+            "clear_shifts.delete",
+            sqlx::query!(
+                r#"
+            DELETE FROM shifts
+            USING members
+            WHERE members.member_id = shifts.member_id
+            AND members.project_id = $1
+            AND ($2::SMALLINT IS NULL OR shifts.day = $2)
+            "#,
+                project_id.as_ref(),
+                day.map(|day| day as i16)
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+        .rows_affected();
+
+        if shifts > 0 {
+            enqueue_event(
+                &mut tx,
+                &DomainEvent::ShiftsCleared {
+                    user_id: user_id.clone(),
+                    project_id: project_id.clone(),
+                    day,
+                    shifts,
+                },
+            )
+            .await
+            .map_err(ProjectStoreError::UnexpectedError)?;
+        }
+        self.commit(tx).await?;
+
+        Ok(shifts)
+    }
+
+    #[tracing::instrument(name = "Adding absence to PostgreSQL", skip_all)]
+    async fn add_absence(
+        &mut self,
+        user_id: &UserId,
+        absence: &Absence,
+    ) -> Result<(), ProjectStoreError> {
+        let member = self.get_member(user_id, &absence.member_id).await?;
+        let standby_member = match &absence.standby_member_id {
+            Some(standby_member_id) => {
+                Some(self.get_member(user_id, standby_member_id).await?)
+            }
+            None => None,
+        };
+
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "add_absence",
+            sqlx::query!(
+                r#"
+            INSERT INTO absences (id, shift_id, member_id, date, reason, standby_member_id)
+            VALUES ($1, $2, $3, $4, $5, $6)
             "#,
-            shift.id.as_ref() as &uuid::Uuid,
-            shift.member_id.as_ref() as &uuid::Uuid,
-            shift.day as i16,
-            shift.start_time.value_of(),
-            shift.end_time.value_of()
+                absence.id,
+                absence.shift_id.as_ref(),
+                absence.member_id.as_ref(),
+                absence.date,
+                absence.reason.as_str(),
+                absence.standby_member_id.as_ref().map(|id| *id.as_ref())
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
-                ProjectStoreError::ShiftIdExists
+                ProjectStoreError::AbsenceExists
             }
             e => ProjectStoreError::UnexpectedError(eyre!(e)),
         })?;
-        Ok(())
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::AbsenceReported {
+                user_id: user_id.clone(),
+                project_id: member.project_id,
+                shift_id: absence.shift_id.clone(),
+                member_id: absence.member_id.clone(),
+                member_name: member.member_name,
+                date: absence.date,
+                reason: absence.reason,
+                standby_member_id: absence.standby_member_id.clone(),
+                standby_member_name: standby_member
+                    .map(|standby| standby.member_name),
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Getting absences from PostgreSQL", skip_all)]
+    async fn get_absences(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        range: &DateRange,
+    ) -> Result<Vec<Absence>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_absences",
+            sqlx::query!(
+                r#"
+            SELECT absences.id, absences.shift_id, absences.member_id,
+                absences.date, absences.reason, absences.standby_member_id
+            FROM absences
+            INNER JOIN members ON absences.member_id = members.member_id
+            WHERE members.project_id = $1
+            AND absences.date BETWEEN $2 AND $3
+            ORDER BY absences.date
+            "#,
+                project_id.as_ref(),
+                range.from(),
+                range.to()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(Absence {
+                    id: row.id,
+                    shift_id: ShiftId::new(row.shift_id),
+                    member_id: MemberId::new(row.member_id),
+                    date: row.date,
+                    reason: AbsenceReason::from_str(&row.reason).map_err(
+                        |e| ProjectStoreError::UnexpectedError(eyre!(e)),
+                    )?,
+                    standby_member_id: row.standby_member_id.map(MemberId::new),
+                })
+            })
+            .collect()
     }
 
     #[tracing::instrument(
         name = "Getting project details from PostreSQL",
         skip_all
     )]
-    async fn get_project(
+    async fn get_project_with(
         &mut self,
         user_id: &UserId,
         project_id: &ProjectId,
+        include: ProjectInclude,
+        window: &ProjectWindow,
     ) -> Result<Project, ProjectStoreError> {
-        let project_row = sqlx::query!(
-            r#"
-            SELECT project_id, project_name
+        let mut tx = self.begin(user_id).await?;
+        let project_row = timed_query(
+            "get_project.project",
+            sqlx::query!(
+                r#"
+            SELECT project_id, project_name, week_start
             FROM projects_list
             WHERE project_id = $1
             AND user_id = $2
             "#,
-            project_id.as_ref(),
-            user_id.as_ref()
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
         )
-        .fetch_one(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
             err => ProjectStoreError::UnexpectedError(eyre!(err)),
         })?;
 
-        let member_rows = sqlx::query!(
-            r#"
-                SELECT member_id, member_name
-                FROM members
-                WHERE project_id = $1
-            "#,
-            project_id.as_ref()
-        )
-        .fetch_all(&self.pool)
-        .await
-        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        let member_rows = if include >= ProjectInclude::Members {
+            timed_query(
+                "get_project.members",
+                sqlx::query!(
+                    r#"
+                    SELECT
+                        member_id, member_name, contracted_hours, department_id,
+                        skills
+                    FROM members
+                    WHERE project_id = $1
+                    AND ($2::uuid IS NULL OR department_id = $2)
+                    ORDER BY member_name, member_id
+                    LIMIT $3 OFFSET $4
+                "#,
+                    project_id.as_ref(),
+                    window.department_id.as_ref().map(|id| *id.as_ref()),
+                    window.limit,
+                    window.offset
+                )
+                .fetch_all(&mut *tx),
+            )
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+        } else {
+            Vec::new()
+        };
 
-        let mut member_map = HashMap::<uuid::Uuid, ProjectMember>::new();
+        // Kept in the order fetched, so the same project always comes back
+        // the same way
+        let mut members =
+            Vec::<ProjectMember>::with_capacity(member_rows.len());
+        let mut member_index = HashMap::<Uuid, usize>::new();
         for row in member_rows {
             let member_id = MemberId::new(row.member_id);
             let member_name = MemberName::parse(row.member_name)
                 .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
-            member_map.insert(
-                member_id.as_ref().to_owned(),
-                ProjectMember {
-                    member_id,
-                    member_name,
-                    shifts: Vec::new(),
-                },
-            );
+            member_index.insert(row.member_id, members.len());
+            members.push(ProjectMember {
+                member_id,
+                member_name,
+                contracted_hours: parse_contracted_hours(row.contracted_hours)?,
+                department_id: row.department_id.map(DepartmentId::new),
+                skills: parse_skills(&row.skills)?,
+                shifts: Vec::new(),
+            });
         }
 
-        let member_ids: Vec<Uuid> =
-            member_map.keys().map(|id| *id.as_ref()).collect();
-        if !member_ids.is_empty() {
-            let shift_rows = sqlx::query!(
-                r#"
-                    SELECT id, member_id, day, in_time, out_time
+        let member_ids: Vec<Uuid> = member_index.keys().copied().collect();
+        if include == ProjectInclude::MembersAndShifts && !member_ids.is_empty()
+        {
+            let days: Option<Vec<i16>> = window
+                .days
+                .as_ref()
+                .map(|days| days.iter().map(|day| *day as i16).collect());
+            let shift_rows = timed_query(
+                "get_project.shifts",
+                sqlx::query!(
+                    r#"
+                    SELECT
+                        id, member_id, day, in_time, out_time,
+                        standby_member_id, note, location
                     FROM shifts
                     WHERE member_id = ANY($1)
+                    AND ($2::smallint[] IS NULL OR day = ANY($2))
+                    ORDER BY id
                "#,
-                &member_ids
+                    &member_ids,
+                    days.as_deref()
+                )
+                .fetch_all(&mut *tx),
             )
-            .fetch_all(&self.pool)
             .await
             .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
 
+            let shift_ids: Vec<Uuid> =
+                shift_rows.iter().map(|row| row.id).collect();
+            let mut break_map = fetch_breaks(&mut tx, &shift_ids).await?;
+            let mut tag_map = fetch_tags(&mut tx, &shift_ids).await?;
+
             for row in shift_rows {
                 let member_id = MemberId::new(row.member_id);
-                if let Some(member) = member_map.get_mut(&member_id.as_ref()) {
+                if let Some(member) = member_index
+                    .get(&row.member_id)
+                    .map(|&index| &mut members[index])
+                {
                     let shift = Shift {
                         id: ShiftId::new(row.id),
                         member_id: member_id.clone(),
@@ -377,19 +1826,1723 @@ impl ProjectStore for PostgresProjectStore {
                         end_time: Minute::parse(row.out_time).map_err(|e| {
                             ProjectStoreError::UnexpectedError(eyre!(e))
                         })?,
+                        breaks: break_map.remove(&row.id).unwrap_or_default(),
+                        standby_member_id: row
+                            .standby_member_id
+                            .map(MemberId::new),
+                        tags: tag_map.remove(&row.id).unwrap_or_default(),
+                        note: row
+                            .note
+                            .as_deref()
+                            .map(ShiftNote::parse)
+                            .transpose()
+                            .map_err(|e| {
+                                ProjectStoreError::UnexpectedError(eyre!(e))
+                            })?
+                            .flatten(),
+                        location: row
+                            .location
+                            .as_deref()
+                            .map(ShiftLocation::parse)
+                            .transpose()
+                            .map_err(|e| {
+                                ProjectStoreError::UnexpectedError(eyre!(e))
+                            })?
+                            .flatten(),
                     };
                     member.shifts.push(shift);
                 }
             }
         }
 
-        let project = Project {
-            project_id: ProjectId::new(project_row.project_id),
-            project_name: ProjectName::parse(&project_row.project_name)
+        self.commit(tx).await?;
+
+        let project = Project::new(
+            ProjectId::new(project_row.project_id),
+            ProjectName::parse(&project_row.project_name)
                 .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
-            members: member_map.into_values().collect(),
-        };
+            Day::try_from(project_row.week_start)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            members,
+        );
 
         Ok(project)
     }
+
+    #[tracing::instrument(
+        name = "Getting project settings from PostgreSQL",
+        skip_all
+    )]
+    async fn get_project_settings(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<ProjectSettings, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_project_settings",
+            sqlx::query!(
+                r#"
+            SELECT shift_granularity, min_shift_length, max_shift_length, break_rules,
+                week_start, escalation_steps, chat_webhook_url, escalation_phone,
+                auto_publish, approver_email
+            FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            err => ProjectStoreError::UnexpectedError(eyre!(err)),
+        })?;
+        self.commit(tx).await?;
+
+        let unexpected =
+            |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+        Ok(ProjectSettings {
+            shift_granularity: ShiftGranularity::parse(row.shift_granularity)
+                .map_err(unexpected)?,
+            min_shift_length: ShiftLength::parse(row.min_shift_length)
+                .map_err(unexpected)?,
+            max_shift_length: ShiftLength::parse(row.max_shift_length)
+                .map_err(unexpected)?,
+            break_rules: serde_json::from_value(row.break_rules)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            week_start: Day::try_from(row.week_start).map_err(unexpected)?,
+            escalation_steps: serde_json::from_value(row.escalation_steps)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            chat_webhook_url: row
+                .chat_webhook_url
+                .map(ChatWebhookUrl::parse)
+                .transpose()
+                .map_err(unexpected)?,
+            escalation_phone: row
+                .escalation_phone
+                .map(PhoneNumber::parse)
+                .transpose()
+                .map_err(unexpected)?,
+            auto_publish: row
+                .auto_publish
+                .as_deref()
+                .map(CronSchedule::parse)
+                .transpose()
+                .map_err(unexpected)?,
+            approver_email: row
+                .approver_email
+                .map(|email| Email::parse(Secret::new(email)))
+                .transpose()
+                .map_err(unexpected)?,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Updating project settings in PostgreSQL",
+        skip_all
+    )]
+    async fn update_project_settings(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        settings: &ProjectSettings,
+    ) -> Result<(), ProjectStoreError> {
+        let break_rules = serde_json::to_value(&settings.break_rules)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        let escalation_steps = serde_json::to_value(&settings.escalation_steps)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let mut tx = self.begin(user_id).await?;
+        let result = timed_query(
+            "update_project_settings",
+            sqlx::query!(
+                r#"
+            UPDATE projects_list
+            SET shift_granularity = $3, min_shift_length = $4, max_shift_length = $5,
+                break_rules = $6, escalation_steps = $7, chat_webhook_url = $8,
+                escalation_phone = $9, week_start = $10, auto_publish = $11,
+                -- The publisher works out when a new schedule next runs
+                auto_publish_at = CASE
+                    WHEN auto_publish IS DISTINCT FROM $11 THEN NULL
+                    ELSE auto_publish_at
+                END,
+                approver_email = $12
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref(),
+                settings.shift_granularity.value_of(),
+                settings.min_shift_length.value_of(),
+                settings.max_shift_length.value_of(),
+                break_rules,
+                escalation_steps,
+                settings.chat_webhook_url.as_ref().map(|url| url.as_ref()),
+                settings
+                    .escalation_phone
+                    .as_ref()
+                    .map(|number| number.as_ref()),
+                i16::from(settings.week_start),
+                settings
+                    .auto_publish
+                    .as_ref()
+                    .map(|schedule| schedule.as_ref()),
+                settings
+                    .approver_email
+                    .as_ref()
+                    .map(|email| email.as_ref().expose_secret())
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Saving week template to PostgreSQL",
+        skip_all
+    )]
+    async fn save_week_template(
+        &mut self,
+        user_id: &UserId,
+        template: &WeekTemplate,
+    ) -> Result<WeekTemplateId, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        // Foreign keys aren't checked against row-level security, so make
+        // sure the project is the user's own
+        let project = timed_query(
+            "save_week_template.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT project_id FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                template.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        let template_id = timed_query(
+            "save_week_template.template",
+            sqlx::query_scalar!(
+                r#"
+            INSERT INTO week_templates (template_id, project_id, template_name)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (project_id, template_name)
+            DO UPDATE SET saved_at = now()
+            RETURNING template_id
+            "#,
+                template.template_id.as_ref(),
+                template.project_id.as_ref(),
+                template.name.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        timed_query(
+            "save_week_template.clear",
+            sqlx::query!(
+                r#"
+            DELETE FROM week_template_shifts WHERE template_id = $1
+            "#,
+                template_id
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let shifts = &template.shifts;
+        let member_ids: Vec<Uuid> = shifts
+            .iter()
+            .map(|shift| *shift.member_id.as_ref())
+            .collect();
+        let standby_member_ids: Vec<Option<Uuid>> = shifts
+            .iter()
+            .map(|shift| {
+                shift.standby_member_id.as_ref().map(|id| *id.as_ref())
+            })
+            .collect();
+        let days: Vec<i16> =
+            shifts.iter().map(|shift| shift.day as i16).collect();
+        let start_times: Vec<i16> = shifts
+            .iter()
+            .map(|shift| shift.start_time.value_of())
+            .collect();
+        let end_times: Vec<i16> = shifts
+            .iter()
+            .map(|shift| shift.end_time.value_of())
+            .collect();
+        let tags: Vec<serde_json::Value> = shifts
+            .iter()
+            .map(|shift| {
+                serde_json::Value::from_iter(
+                    shift.tags.iter().map(|tag| tag.as_ref().clone()),
+                )
+            })
+            .collect();
+        let notes: Vec<Option<String>> = shifts
+            .iter()
+            .map(|shift| shift.note.as_ref().map(|note| note.as_ref().clone()))
+            .collect();
+        let locations: Vec<Option<String>> = shifts
+            .iter()
+            .map(|shift| {
+                shift
+                    .location
+                    .as_ref()
+                    .map(|location| location.as_ref().clone())
+            })
+            .collect();
+        timed_query(
+            "save_week_template.shifts",
+            sqlx::query!(
+                r#"
+            INSERT INTO week_template_shifts (
+                template_id, member_id, standby_member_id, day, in_time,
+                out_time, tags, note, location
+            )
+            SELECT $1, * FROM UNNEST(
+                $2::UUID[], $3::UUID[], $4::SMALLINT[], $5::SMALLINT[],
+                $6::SMALLINT[], $7::JSONB[], $8::VARCHAR[], $9::VARCHAR[]
+            )
+            "#,
+                template_id,
+                &member_ids,
+                &standby_member_ids as &[Option<Uuid>],
+                &days,
+                &start_times,
+                &end_times,
+                &tags,
+                &notes as &[Option<String>],
+                &locations as &[Option<String>]
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        self.commit(tx).await?;
+        Ok(WeekTemplateId::new(template_id))
+    }
+
+    #[tracing::instrument(
+        name = "Getting week templates from PostgreSQL",
+        skip_all
+    )]
+    async fn get_week_templates(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<WeekTemplate>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_week_templates",
+            sqlx::query!(
+                r#"
+            SELECT projects_list.project_id, template_id AS "template_id?",
+                template_name AS "template_name?"
+            FROM projects_list
+            LEFT JOIN week_templates
+                ON week_templates.project_id = projects_list.project_id
+            WHERE projects_list.project_id = $1
+            AND projects_list.user_id = $2
+            ORDER BY template_name
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if rows.is_empty() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        let template_ids: Vec<Uuid> =
+            rows.iter().filter_map(|row| row.template_id).collect();
+        let mut shifts = fetch_template_shifts(&mut tx, &template_ids).await?;
+        self.commit(tx).await?;
+
+        rows.into_iter()
+            .filter_map(|row| Some((row.template_id?, row.template_name?)))
+            .map(|(template_id, template_name)| {
+                Ok(WeekTemplate {
+                    template_id: WeekTemplateId::new(template_id),
+                    project_id: project_id.clone(),
+                    name: WeekTemplateName::parse(&template_name).map_err(
+                        |e| ProjectStoreError::UnexpectedError(eyre!(e)),
+                    )?,
+                    shifts: shifts.remove(&template_id).unwrap_or_default(),
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(
+        name = "Getting week template from PostgreSQL",
+        skip_all
+    )]
+    async fn get_week_template(
+        &mut self,
+        user_id: &UserId,
+        template_id: &WeekTemplateId,
+    ) -> Result<WeekTemplate, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_week_template",
+            sqlx::query!(
+                r#"
+            SELECT template_id, project_id, template_name
+            FROM week_templates
+            WHERE template_id = $1
+            "#,
+                template_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::TemplateIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        let mut shifts =
+            fetch_template_shifts(&mut tx, &[row.template_id]).await?;
+        self.commit(tx).await?;
+
+        Ok(WeekTemplate {
+            template_id: WeekTemplateId::new(row.template_id),
+            project_id: ProjectId::new(row.project_id),
+            name: WeekTemplateName::parse(&row.template_name)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            shifts: shifts.remove(&row.template_id).unwrap_or_default(),
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Applying week template in PostgreSQL",
+        skip_all
+    )]
+    async fn apply_week_template(
+        &mut self,
+        user_id: &UserId,
+        template: &WeekTemplate,
+        shifts: &[Shift],
+        replace: bool,
+    ) -> Result<u64, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let replaced = if replace {
+            timed_query(
+                "apply_week_template.clear",
+                sqlx::query!(
+                    r#"
+                DELETE FROM shifts
+                USING members
+                WHERE members.member_id = shifts.member_id
+                AND members.project_id = $1
+                "#,
+                    template.project_id.as_ref()
+                )
+                .execute(&mut *tx),
+            )
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+            .rows_affected()
+        } else {
+            0
+        };
+        insert_shifts(&mut tx, shifts).await?;
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::WeekTemplateApplied {
+                user_id: user_id.clone(),
+                project_id: template.project_id.clone(),
+                template_name: template.name.clone(),
+                shifts: shifts.len() as u64,
+                replaced,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await?;
+
+        Ok(replaced)
+    }
+
+    #[tracing::instrument(name = "Importing rota to PostgreSQL", skip_all)]
+    async fn import_rota(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        members: &[Member],
+        shifts: &[Shift],
+        replace: bool,
+    ) -> Result<u64, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let project = timed_query(
+            "import_rota.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT project_id FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        let replaced = if replace {
+            timed_query(
+                "import_rota.clear",
+                sqlx::query!(
+                    r#"
+                DELETE FROM shifts
+                USING members
+                WHERE members.member_id = shifts.member_id
+                AND members.project_id = $1
+                "#,
+                    project_id.as_ref()
+                )
+                .execute(&mut *tx),
+            )
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+            .rows_affected()
+        } else {
+            0
+        };
+        for member in members {
+            timed_query(
+                "import_rota.member",
+                sqlx::query!(
+                    r#"
+                INSERT INTO members (member_id, project_id, member_name)
+                VALUES ($1, $2, $3)
+                "#,
+                    member.member_id.as_ref() as &uuid::Uuid,
+                    project_id.as_ref() as &uuid::Uuid,
+                    member.member_name.as_ref(),
+                )
+                .execute(&mut *tx),
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::Database(db_err)
+                    if db_err.is_unique_violation() =>
+                {
+                    ProjectStoreError::MemberIDExists
+                }
+                e => ProjectStoreError::UnexpectedError(eyre!(e)),
+            })?;
+            enqueue_event(
+                &mut tx,
+                &DomainEvent::MemberAdded {
+                    user_id: user_id.clone(),
+                    project_id: project_id.clone(),
+                    member_id: member.member_id.clone(),
+                    member_name: member.member_name.clone(),
+                },
+            )
+            .await
+            .map_err(ProjectStoreError::UnexpectedError)?;
+        }
+        insert_shifts(&mut tx, shifts).await?;
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::RotaImported {
+                user_id: user_id.clone(),
+                project_id: project_id.clone(),
+                members: members.len() as u64,
+                shifts: shifts.len() as u64,
+                replaced,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await?;
+
+        Ok(replaced)
+    }
+
+    #[tracing::instrument(name = "Adding department to PostgreSQL", skip_all)]
+    async fn add_department(
+        &mut self,
+        user_id: &UserId,
+        department: &Department,
+    ) -> Result<(), ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let project = timed_query(
+            "add_department.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT project_id FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                department.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        timed_query(
+            "add_department.department",
+            sqlx::query!(
+                r#"
+            INSERT INTO departments (department_id, project_id, department_name)
+            VALUES ($1, $2, $3)
+            "#,
+                department.department_id.as_ref(),
+                department.project_id.as_ref(),
+                department.department_name.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                ProjectStoreError::DepartmentNameExists
+            }
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting departments from PostgreSQL",
+        skip_all
+    )]
+    async fn get_departments(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<Department>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_departments",
+            sqlx::query!(
+                r#"
+            SELECT department_id AS "department_id?",
+                department_name AS "department_name?"
+            FROM projects_list
+            LEFT JOIN departments
+                ON departments.project_id = projects_list.project_id
+            WHERE projects_list.project_id = $1
+            AND projects_list.user_id = $2
+            ORDER BY department_name
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+        if rows.is_empty() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        rows.into_iter()
+            .filter_map(|row| Some((row.department_id?, row.department_name?)))
+            .map(|(department_id, department_name)| {
+                Ok(Department {
+                    department_id: DepartmentId::new(department_id),
+                    project_id: project_id.clone(),
+                    department_name: DepartmentName::parse(&department_name)
+                        .map_err(|e| {
+                            ProjectStoreError::UnexpectedError(eyre!(e))
+                        })?,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(name = "Adding shift slot to PostgreSQL", skip_all)]
+    async fn add_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot: &ShiftSlot,
+    ) -> Result<(), ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        // Foreign keys aren't checked against row-level security, so make
+        // sure the project is the user's own
+        let project = timed_query(
+            "add_shift_slot.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT project_id FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                slot.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+        check_department(
+            &mut tx,
+            &slot.project_id,
+            slot.department_id.as_ref(),
+        )
+        .await?;
+
+        timed_query(
+            "add_shift_slot.slot",
+            sqlx::query!(
+                r#"
+            INSERT INTO shift_slots (
+                slot_id, project_id, day, in_time, out_time, places, tag,
+                department_id
+            )
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+                slot.slot_id.as_ref(),
+                slot.project_id.as_ref(),
+                slot.day as i16,
+                slot.start_time.value_of(),
+                slot.end_time.value_of(),
+                slot.places,
+                slot.tag.as_ref().map(|tag| tag.as_ref().as_str()),
+                slot.department_id.as_ref().map(|id| *id.as_ref())
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting shift slot from PostgreSQL",
+        skip_all
+    )]
+    async fn get_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot_id: &ShiftSlotId,
+    ) -> Result<ShiftSlot, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let row = timed_query(
+            "get_shift_slot",
+            sqlx::query_as!(
+                ShiftSlotRow,
+                r#"
+            SELECT
+                slot_id, project_id, day, in_time, out_time, places, tag,
+                department_id
+            FROM shift_slots
+            WHERE slot_id = $1
+            "#,
+                slot_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+        .ok_or(ProjectStoreError::SlotIDNotFound)?;
+        self.commit(tx).await?;
+
+        ShiftSlot::try_from(row)
+    }
+
+    #[tracing::instrument(
+        name = "Getting slot coverage from PostgreSQL",
+        skip_all
+    )]
+    async fn get_slot_coverage(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<SlotCoverage>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let week_start = timed_query(
+            "get_slot_coverage.project",
+            sqlx::query_scalar!(
+                r#"
+            SELECT week_start FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+        .ok_or(ProjectStoreError::ProjectIDNotFound)?;
+        let week_start = Day::try_from(week_start)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let slot_rows = timed_query(
+            "get_slot_coverage.slots",
+            sqlx::query_as!(
+                ShiftSlotRow,
+                r#"
+            SELECT
+                slot_id, project_id, day, in_time, out_time, places, tag,
+                department_id
+            FROM shift_slots
+            WHERE project_id = $1
+            "#,
+                project_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let slot_ids: Vec<Uuid> =
+            slot_rows.iter().map(|row| row.slot_id).collect();
+        let assignment_rows = timed_query(
+            "get_slot_coverage.assignments",
+            sqlx::query!(
+                r#"
+            SELECT slot_id AS "slot_id!", member_id
+            FROM shifts
+            WHERE slot_id = ANY($1)
+            ORDER BY member_id
+            "#,
+                &slot_ids
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        let mut assigned = HashMap::<Uuid, Vec<MemberId>>::new();
+        for row in assignment_rows {
+            assigned
+                .entry(row.slot_id)
+                .or_default()
+                .push(MemberId::new(row.member_id));
+        }
+
+        let mut coverage = slot_rows
+            .into_iter()
+            .map(|row| {
+                let assigned =
+                    assigned.remove(&row.slot_id).unwrap_or_default();
+                Ok(SlotCoverage {
+                    slot: ShiftSlot::try_from(row)?,
+                    assigned,
+                })
+            })
+            .collect::<Result<Vec<_>, ProjectStoreError>>()?;
+        coverage.sort_by_key(|coverage| {
+            (
+                coverage.slot.day.days_after(week_start),
+                coverage.slot.start_time.value_of(),
+            )
+        });
+        Ok(coverage)
+    }
+
+    #[tracing::instrument(name = "Filling shift slot in PostgreSQL", skip_all)]
+    async fn fill_shift_slot(
+        &mut self,
+        user_id: &UserId,
+        slot_id: &ShiftSlotId,
+        shift: &Shift,
+    ) -> Result<(), ProjectStoreError> {
+        let member = self.get_member(user_id, &shift.member_id).await?;
+
+        let mut tx = self.begin(user_id).await?;
+        // Locked so two members can't take the last place at once
+        let places = timed_query(
+            "fill_shift_slot.slot",
+            sqlx::query_scalar!(
+                r#"
+            SELECT places FROM shift_slots
+            WHERE slot_id = $1 AND project_id = $2
+            FOR UPDATE
+            "#,
+                slot_id.as_ref(),
+                member.project_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?
+        .ok_or(ProjectStoreError::SlotIDNotFound)?;
+
+        let assigned = timed_query(
+            "fill_shift_slot.assigned",
+            sqlx::query_scalar!(
+                r#"
+            SELECT member_id FROM shifts WHERE slot_id = $1
+            "#,
+                slot_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if assigned.contains(shift.member_id.as_ref()) {
+            return Err(ProjectStoreError::AlreadyInSlot);
+        }
+        if assigned.len() >= places as usize {
+            return Err(ProjectStoreError::SlotFull);
+        }
+
+        insert_shifts(&mut tx, std::slice::from_ref(shift)).await?;
+        timed_query(
+            "fill_shift_slot.shift",
+            sqlx::query!(
+                r#"
+            UPDATE shifts SET slot_id = $2 WHERE id = $1
+            "#,
+                shift.id.as_ref(),
+                slot_id.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::ShiftCreated {
+                user_id: user_id.clone(),
+                project_id: member.project_id,
+                member_id: shift.member_id.clone(),
+                member_name: member.member_name,
+                shift_id: shift.id.clone(),
+                day: shift.day,
+                start_time: shift.start_time.clone(),
+                end_time: shift.end_time.clone(),
+                standby_member_id: None,
+                standby_member_name: None,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Publishing rota to PostgreSQL", skip_all)]
+    async fn publish_rota(
+        &mut self,
+        user_id: &UserId,
+        version: &RotaVersion,
+    ) -> Result<(), ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        // Foreign keys aren't checked against row-level security, so make
+        // sure the project is the user's own
+        timed_query(
+            "publish_rota.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                version.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        insert_rota_version(&mut tx, version).await?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::RotaPublished {
+                user_id: user_id.clone(),
+                project_id: version.project_id.clone(),
+                week_of: version.week_of,
+                shifts: version.shifts.len() as u64,
+                automatic: version.automatic,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting rota version from PostgreSQL",
+        skip_all
+    )]
+    async fn get_rota_version(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        version_id: &RotaVersionId,
+    ) -> Result<RotaVersion, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "get_rota_version.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        let row = timed_query(
+            "get_rota_version.version",
+            sqlx::query!(
+                r#"
+                SELECT week_of, shifts, automatic, published_at
+                FROM rota_versions
+                WHERE version_id = $1 AND project_id = $2
+                "#,
+                version_id.as_ref(),
+                project_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::VersionIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        self.commit(tx).await?;
+
+        Ok(RotaVersion {
+            version_id: version_id.clone(),
+            project_id: project_id.clone(),
+            week_of: row.week_of,
+            shifts: serde_json::from_value(row.shifts)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?,
+            automatic: row.automatic,
+            published_at: row.published_at,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Getting latest rota version from PostgreSQL",
+        skip_all
+    )]
+    async fn get_latest_rota_version(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Option<RotaVersion>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "get_latest_rota_version.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        let row = timed_query(
+            "get_latest_rota_version.version",
+            sqlx::query!(
+                r#"
+                SELECT version_id, week_of, shifts, automatic, published_at
+                FROM rota_versions
+                WHERE project_id = $1
+                ORDER BY published_at DESC, seq DESC
+                LIMIT 1
+                "#,
+                project_id.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        row.map(|row| {
+            Ok(RotaVersion {
+                version_id: RotaVersionId::new(row.version_id),
+                project_id: project_id.clone(),
+                week_of: row.week_of,
+                shifts: serde_json::from_value(row.shifts).map_err(|e| {
+                    ProjectStoreError::UnexpectedError(eyre!(e))
+                })?,
+                automatic: row.automatic,
+                published_at: row.published_at,
+            })
+        })
+        .transpose()
+    }
+
+    #[tracing::instrument(
+        name = "Getting rota reactions from PostgreSQL",
+        skip_all
+    )]
+    async fn get_rota_reactions(
+        &mut self,
+        user_id: &UserId,
+        version_id: &RotaVersionId,
+    ) -> Result<Vec<RotaReaction>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let rows = timed_query(
+            "get_rota_reactions",
+            sqlx::query!(
+                r#"
+                SELECT
+                    rota_reactions.member_id, members.member_name,
+                    rota_reactions.reaction, rota_reactions.reacted_at
+                FROM rota_reactions
+                INNER JOIN members
+                    ON members.member_id = rota_reactions.member_id
+                WHERE rota_reactions.version_id = $1
+                ORDER BY rota_reactions.reacted_at, members.member_name
+                "#,
+                version_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        let unexpected =
+            |e: ValidationError| ProjectStoreError::UnexpectedError(eyre!(e));
+        rows.into_iter()
+            .map(|row| {
+                Ok(RotaReaction {
+                    member_id: MemberId::new(row.member_id),
+                    member_name: MemberName::parse(row.member_name)
+                        .map_err(unexpected)?,
+                    reaction: Reaction::parse(&row.reaction)
+                        .map_err(unexpected)?,
+                    reacted_at: row.reacted_at,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(
+        name = "Getting project emails from PostgreSQL",
+        skip_all
+    )]
+    async fn get_project_emails(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<EmailReceipt>, ProjectStoreError> {
+        // The tenant role can only queue emails, so this runs outside it and
+        // is scoped by the project's owner instead
+        let project = timed_query(
+            "get_project_emails.project",
+            sqlx::query_scalar!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1
+                AND user_id = $2
+                "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        if project.is_none() {
+            return Err(ProjectStoreError::ProjectIDNotFound);
+        }
+
+        let rows = timed_query(
+            "get_project_emails",
+            sqlx::query!(
+                r#"
+                SELECT recipient, subject, created_at, sent_at, suppressed,
+                    tracked, opened_at
+                FROM email_outbox
+                WHERE project_id = $1
+                ORDER BY id DESC
+                LIMIT $2
+                "#,
+                project_id.as_ref(),
+                PROJECT_EMAILS_LIMIT
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| EmailReceipt {
+                recipient: row.recipient,
+                subject: row.subject,
+                queued_at: row.created_at,
+                sent_at: row.sent_at,
+                suppressed: row.suppressed,
+                tracked: row.tracked,
+                opened_at: row.opened_at,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(
+        name = "Submitting rota for approval in PostgreSQL",
+        skip_all
+    )]
+    async fn submit_rota(
+        &mut self,
+        user_id: &UserId,
+        approval: &RotaApproval,
+    ) -> Result<(), ProjectStoreError> {
+        let version = &approval.version;
+        let shifts = serde_json::to_value(&version.shifts)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        let changes = serde_json::to_value(&approval.changes)
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let mut tx = self.begin(user_id).await?;
+        // Foreign keys aren't checked against row-level security, so make
+        // sure the project is the user's own
+        timed_query(
+            "submit_rota.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                version.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        // A resubmitted week replaces the one waiting, but other weeks
+        // stay pending
+        timed_query(
+            "submit_rota.withdraw",
+            sqlx::query!(
+                r#"
+                UPDATE rota_approvals SET status = 'withdrawn'
+                WHERE project_id = $1 AND week_of = $2 AND status = 'pending'
+                "#,
+                version.project_id.as_ref(),
+                version.week_of
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        timed_query(
+            "submit_rota.insert",
+            sqlx::query!(
+                r#"
+                INSERT INTO rota_approvals
+                    (approval_id, project_id, version_id, week_of, shifts,
+                    changes, automatic, approver_email, status, submitted_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                "#,
+                approval.approval_id.as_ref(),
+                version.project_id.as_ref(),
+                version.version_id.as_ref(),
+                version.week_of,
+                shifts,
+                changes,
+                version.automatic,
+                approval.approver_email.as_ref().expose_secret(),
+                approval.status.as_str(),
+                approval.submitted_at
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::RotaSubmitted {
+                user_id: user_id.clone(),
+                project_id: version.project_id.clone(),
+                week_of: version.week_of,
+                shifts: version.shifts.len() as u64,
+                automatic: version.automatic,
+            },
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+
+        let (subject, content) = approval.review_request_email();
+        enqueue_project_email(
+            &mut tx,
+            &version.project_id,
+            &approval.approver_email,
+            &subject,
+            &content,
+        )
+        .await
+        .map_err(ProjectStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting rota approvals from PostgreSQL",
+        skip_all
+    )]
+    async fn get_rota_approvals(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+    ) -> Result<Vec<RotaApproval>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "get_rota_approvals.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        let rows = timed_query(
+            "get_rota_approvals.approvals",
+            sqlx::query_as!(
+                RotaApprovalRow,
+                r#"
+                SELECT rota_approvals.approval_id, rota_approvals.project_id,
+                    projects_list.project_name, rota_approvals.version_id,
+                    rota_approvals.week_of, rota_approvals.shifts,
+                    rota_approvals.changes, rota_approvals.automatic,
+                    rota_approvals.approver_email, rota_approvals.status,
+                    rota_approvals.comment, rota_approvals.submitted_at,
+                    rota_approvals.reviewed_at
+                FROM rota_approvals
+                INNER JOIN projects_list
+                    ON projects_list.project_id = rota_approvals.project_id
+                WHERE rota_approvals.project_id = $1
+                ORDER BY rota_approvals.submitted_at DESC,
+                    rota_approvals.seq DESC
+                "#,
+                project_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter().map(RotaApproval::try_from).collect()
+    }
+
+    #[tracing::instrument(
+        name = "Getting pending approvals from PostgreSQL",
+        skip_all
+    )]
+    async fn get_pending_approvals(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<Vec<RotaApproval>, ProjectStoreError> {
+        // Approvers don't own the projects, so this runs outside the tenant
+        // role and is scoped by the user's email instead
+        let rows = timed_query(
+            "get_pending_approvals",
+            sqlx::query_as!(
+                RotaApprovalRow,
+                r#"
+                SELECT rota_approvals.approval_id, rota_approvals.project_id,
+                    projects_list.project_name, rota_approvals.version_id,
+                    rota_approvals.week_of, rota_approvals.shifts,
+                    rota_approvals.changes, rota_approvals.automatic,
+                    rota_approvals.approver_email, rota_approvals.status,
+                    rota_approvals.comment, rota_approvals.submitted_at,
+                    rota_approvals.reviewed_at
+                FROM rota_approvals
+                INNER JOIN projects_list
+                    ON projects_list.project_id = rota_approvals.project_id
+                WHERE rota_approvals.approver_email =
+                    (SELECT email FROM users WHERE id = $1)
+                AND rota_approvals.status = 'pending'
+                ORDER BY rota_approvals.submitted_at, rota_approvals.seq
+                "#,
+                user_id.as_ref()
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        rows.into_iter().map(RotaApproval::try_from).collect()
+    }
+
+    #[tracing::instrument(name = "Reviewing rota in PostgreSQL", skip_all)]
+    async fn review_rota(
+        &mut self,
+        user_id: &UserId,
+        approval_id: &RotaApprovalId,
+        approve: bool,
+        comment: Option<&ReviewComment>,
+    ) -> Result<RotaApproval, ProjectStoreError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        let approval =
+            review_rota_in(&mut tx, user_id, approval_id, approve, comment)
+                .await?;
+        self.commit(tx).await?;
+        Ok(approval)
+    }
+
+    #[tracing::instrument(name = "Reviewing rotas in PostgreSQL", skip_all)]
+    async fn review_rotas(
+        &mut self,
+        user_id: &UserId,
+        approval_ids: &[RotaApprovalId],
+        approve: bool,
+        comment: Option<&ReviewComment>,
+    ) -> Result<Vec<Result<RotaApproval, ProjectStoreError>>, ProjectStoreError>
+    {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        // An approval that can't be reviewed is turned down before anything
+        // is written for it, so the others carry on in the same transaction
+        let mut results = Vec::with_capacity(approval_ids.len());
+        for approval_id in approval_ids {
+            match review_rota_in(
+                &mut tx,
+                user_id,
+                approval_id,
+                approve,
+                comment,
+            )
+            .await
+            {
+                Err(
+                    e @ (ProjectStoreError::ApprovalIDNotFound
+                    | ProjectStoreError::AlreadyReviewed),
+                ) => results.push(Err(e)),
+                result => results.push(Ok(result?)),
+            }
+        }
+
+        self.commit(tx).await?;
+        Ok(results)
+    }
+
+    #[tracing::instrument(name = "Adding comment to PostgreSQL", skip_all)]
+    async fn add_comment(
+        &mut self,
+        user_id: &UserId,
+        comment: &Comment,
+        mention_message: &str,
+    ) -> Result<usize, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        // Foreign keys aren't checked against row-level security, so make
+        // sure the project, and the shift, are the user's own
+        timed_query(
+            "add_comment.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                comment.project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+        if let Some(shift_id) = &comment.shift_id {
+            timed_query(
+                "add_comment.shift",
+                sqlx::query!(
+                    r#"
+                    SELECT shifts.id FROM shifts
+                    JOIN members ON members.member_id = shifts.member_id
+                    WHERE shifts.id = $1 AND members.project_id = $2
+                    "#,
+                    shift_id.as_ref(),
+                    comment.project_id.as_ref()
+                )
+                .fetch_one(&mut *tx),
+            )
+            .await
+            .map_err(|e| match e {
+                sqlx::Error::RowNotFound => ProjectStoreError::ShiftIDNotFound,
+                e => ProjectStoreError::UnexpectedError(eyre!(e)),
+            })?;
+        }
+
+        let mentions: Vec<Uuid> = comment
+            .mentions
+            .iter()
+            .map(|member_id| *member_id.as_ref())
+            .collect();
+        timed_query(
+            "add_comment.insert",
+            sqlx::query!(
+                r#"
+                INSERT INTO comments
+                    (comment_id, project_id, shift_id, author_id, body,
+                    mentions, posted_at)
+                VALUES ($1, $2, $3, $4, $5, $6, $7)
+                "#,
+                comment.comment_id.as_ref(),
+                comment.project_id.as_ref(),
+                comment.shift_id.as_ref().map(|shift_id| *shift_id.as_ref()),
+                user_id.as_ref(),
+                comment.body.as_ref(),
+                &mentions,
+                comment.posted_at
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let phone_numbers = timed_query(
+            "add_comment.mentioned",
+            sqlx::query_scalar!(
+                r#"
+                SELECT phone_number AS "phone_number!" FROM members
+                WHERE member_id = ANY($1) AND project_id = $2
+                AND sms_opt_in AND phone_number IS NOT NULL
+                "#,
+                &mentions,
+                comment.project_id.as_ref()
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        let notified = phone_numbers.len();
+        for phone_number in phone_numbers {
+            let recipient = PhoneNumber::parse(phone_number)
+                .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+            enqueue_sms(&mut tx, &recipient, mention_message)
+                .await
+                .map_err(ProjectStoreError::UnexpectedError)?;
+        }
+        self.commit(tx).await?;
+
+        Ok(notified)
+    }
+
+    #[tracing::instrument(name = "Getting comments from PostgreSQL", skip_all)]
+    async fn get_comments(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        shift_id: Option<&ShiftId>,
+    ) -> Result<Vec<Comment>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "get_comments.project",
+            sqlx::query!(
+                r#"
+                SELECT project_id FROM projects_list
+                WHERE project_id = $1 AND user_id = $2
+                "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            e => ProjectStoreError::UnexpectedError(eyre!(e)),
+        })?;
+
+        let rows = timed_query(
+            "get_comments",
+            sqlx::query_as!(
+                CommentRow,
+                r#"
+                SELECT
+                    comment_id, project_id, shift_id, author_id, body,
+                    mentions, posted_at
+                FROM comments
+                WHERE project_id = $1
+                AND ($2::UUID IS NULL OR shift_id = $2)
+                ORDER BY posted_at, comment_id
+                "#,
+                project_id.as_ref(),
+                shift_id.map(|shift_id| *shift_id.as_ref())
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter().map(Comment::try_from).collect()
+    }
+
+    #[tracing::instrument(name = "Deleting comment from PostgreSQL", skip_all)]
+    async fn delete_comment(
+        &mut self,
+        user_id: &UserId,
+        comment_id: &CommentId,
+    ) -> Result<(), ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        let result = timed_query(
+            "delete_comment",
+            sqlx::query!(
+                "DELETE FROM comments WHERE comment_id = $1",
+                comment_id.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(ProjectStoreError::CommentIDNotFound);
+        }
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Getting project history from PostgreSQL",
+        skip_all
+    )]
+    async fn get_project_history(
+        &mut self,
+        user_id: &UserId,
+        project_id: &ProjectId,
+        entity_type: Option<EntityType>,
+        cursor: Option<HistoryCursor>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<Vec<ProjectHistoryEntry>, ProjectStoreError> {
+        let mut tx = self.begin(user_id).await?;
+        timed_query(
+            "get_project_history.project",
+            sqlx::query!(
+                r#"
+            SELECT project_id
+            FROM projects_list
+            WHERE project_id = $1
+            AND user_id = $2
+            "#,
+                project_id.as_ref(),
+                user_id.as_ref()
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => ProjectStoreError::ProjectIDNotFound,
+            err => ProjectStoreError::UnexpectedError(eyre!(err)),
+        })?;
+
+        let rows = timed_query(
+            "get_project_history.events",
+            sqlx::query!(
+                r#"
+                SELECT id, event, occurred_at
+                FROM project_events
+                WHERE project_id = $1
+                AND ($2::TEXT IS NULL OR entity_type = $2)
+                AND ($3::BIGINT IS NULL OR id < $3)
+                ORDER BY id DESC
+                LIMIT $4
+                OFFSET $5
+            "#,
+                project_id.as_ref(),
+                entity_type.map(|entity_type| entity_type.as_str()),
+                cursor.as_ref().map(|cursor| *cursor.as_ref()),
+                limit,
+                offset
+            )
+            .fetch_all(&mut *tx),
+        )
+        .await
+        .map_err(|e| ProjectStoreError::UnexpectedError(eyre!(e)))?;
+        self.commit(tx).await?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ProjectHistoryEntry {
+                    id: row.id,
+                    occurred_at: row.occurred_at,
+                    event: serde_json::from_value(row.event).map_err(|e| {
+                        ProjectStoreError::UnexpectedError(eyre!(e))
+                    })?,
+                })
+            })
+            .collect()
+    }
 }
@@ -0,0 +1,344 @@
+use chrono::NaiveDate;
+use color_eyre::eyre::eyre;
+use secrecy::Secret;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use uuid::Uuid;
+
+use crate::{
+    domain::{
+        DomainEvent, Email, MemberId, MemberName, Minute, PhoneNumber,
+        ProjectId, ProjectName, ReactedRota, Reaction, RemindedShift, ShiftId,
+        ShiftReminderStore, ShiftReminderStoreError, SmsReply, UserId,
+    },
+    services::data_stores::{enqueue_event, enqueue_project_email},
+    utils::{database::retry_on_disconnect, metrics::timed_query},
+};
+
+pub struct PostgresShiftReminderStore {
+    pool: PgPool,
+}
+
+impl PostgresShiftReminderStore {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    // A transaction, once PostgreSQL can be reached
+    async fn begin(
+        &self,
+    ) -> Result<Transaction<'static, Postgres>, ShiftReminderStoreError> {
+        retry_on_disconnect(|| self.pool.begin())
+            .await
+            .map_err(unexpected_error)
+    }
+}
+
+#[async_trait::async_trait]
+impl ShiftReminderStore for PostgresShiftReminderStore {
+    #[tracing::instrument(name = "Recording SMS reply in PostgreSQL", skip_all)]
+    async fn record_reply(
+        &mut self,
+        phone_number: &PhoneNumber,
+        reply: SmsReply,
+        today: NaiveDate,
+    ) -> Result<RemindedShift, ShiftReminderStoreError> {
+        let mut tx = self.begin().await?;
+
+        // Replies don't come from a signed-in user, so this runs outside the
+        // tenant role and is scoped by phone number instead
+        let row = timed_query(
+            "record_reply.reminder",
+            sqlx::query!(
+                r#"
+                SELECT
+                    shift_reminders.shift_id,
+                    shift_reminders.shift_date,
+                    shifts.in_time,
+                    members.member_id,
+                    members.member_name,
+                    projects_list.project_id,
+                    projects_list.project_name,
+                    projects_list.user_id,
+                    users.email
+                FROM shift_reminders
+                INNER JOIN shifts ON shifts.id = shift_reminders.shift_id
+                INNER JOIN members ON members.member_id = shifts.member_id
+                INNER JOIN projects_list
+                    ON projects_list.project_id = members.project_id
+                INNER JOIN users ON users.id = projects_list.user_id
+                WHERE members.phone_number = $1
+                AND members.sms_opt_in
+                AND shift_reminders.shift_date >= $2
+                ORDER BY shift_reminders.sent_at DESC
+                LIMIT 1
+                FOR UPDATE OF shift_reminders
+                "#,
+                phone_number.as_ref(),
+                today
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(ShiftReminderStoreError::ReminderNotFound)?;
+
+        let reminder = Reminder {
+            shift_id: row.shift_id,
+            shift_date: row.shift_date,
+            in_time: row.in_time,
+            member_id: row.member_id,
+            member_name: row.member_name,
+            project_id: row.project_id,
+            project_name: row.project_name,
+            user_id: row.user_id,
+            owner_email: row.email,
+        };
+        let shift = apply_reply(&mut tx, reminder, reply).await?;
+
+        tx.commit().await.map_err(unexpected_error)?;
+
+        Ok(shift)
+    }
+
+    #[tracing::instrument(
+        name = "Recording email reply in PostgreSQL",
+        skip_all
+    )]
+    async fn record_email_reply(
+        &mut self,
+        reminder_id: i64,
+        reply: SmsReply,
+        today: NaiveDate,
+    ) -> Result<RemindedShift, ShiftReminderStoreError> {
+        let mut tx = self.begin().await?;
+
+        // Scoped by the reminder named in the signed reply-to address, and
+        // only while the member still gets reminders by email
+        let row = timed_query(
+            "record_email_reply.reminder",
+            sqlx::query!(
+                r#"
+                SELECT
+                    shift_reminders.shift_id,
+                    shift_reminders.shift_date,
+                    shifts.in_time,
+                    members.member_id,
+                    members.member_name,
+                    projects_list.project_id,
+                    projects_list.project_name,
+                    projects_list.user_id,
+                    users.email
+                FROM shift_reminders
+                INNER JOIN shifts ON shifts.id = shift_reminders.shift_id
+                INNER JOIN members ON members.member_id = shifts.member_id
+                INNER JOIN projects_list
+                    ON projects_list.project_id = members.project_id
+                INNER JOIN users ON users.id = projects_list.user_id
+                WHERE shift_reminders.reminder_id = $1
+                AND members.email_opt_in
+                AND shift_reminders.shift_date >= $2
+                FOR UPDATE OF shift_reminders
+                "#,
+                reminder_id,
+                today
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(ShiftReminderStoreError::ReminderNotFound)?;
+
+        let reminder = Reminder {
+            shift_id: row.shift_id,
+            shift_date: row.shift_date,
+            in_time: row.in_time,
+            member_id: row.member_id,
+            member_name: row.member_name,
+            project_id: row.project_id,
+            project_name: row.project_name,
+            user_id: row.user_id,
+            owner_email: row.email,
+        };
+        let shift = apply_reply(&mut tx, reminder, reply).await?;
+
+        tx.commit().await.map_err(unexpected_error)?;
+
+        Ok(shift)
+    }
+
+    #[tracing::instrument(
+        name = "Recording rota reaction in PostgreSQL",
+        skip_all
+    )]
+    async fn record_rota_reaction(
+        &mut self,
+        phone_number: &PhoneNumber,
+        reaction: &Reaction,
+    ) -> Result<ReactedRota, ShiftReminderStoreError> {
+        let mut tx = self.begin().await?;
+
+        // Like replies, reactions are scoped by phone number rather than by
+        // the tenant role
+        let row = timed_query(
+            "record_rota_reaction.version",
+            sqlx::query!(
+                r#"
+                SELECT
+                    rota_versions.version_id,
+                    rota_versions.project_id,
+                    rota_versions.week_of,
+                    members.member_id,
+                    projects_list.project_name
+                FROM members
+                INNER JOIN rota_versions
+                    ON rota_versions.project_id = members.project_id
+                INNER JOIN projects_list
+                    ON projects_list.project_id = members.project_id
+                WHERE members.phone_number = $1
+                AND members.sms_opt_in
+                ORDER BY rota_versions.published_at DESC,
+                    rota_versions.seq DESC
+                LIMIT 1
+                "#,
+                phone_number.as_ref()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(ShiftReminderStoreError::RotaNotFound)?;
+
+        timed_query(
+            "record_rota_reaction.upsert",
+            sqlx::query!(
+                r#"
+                INSERT INTO rota_reactions
+                    (version_id, member_id, project_id, reaction)
+                VALUES ($1, $2, $3, $4)
+                ON CONFLICT (version_id, member_id) DO UPDATE
+                SET reaction = EXCLUDED.reaction, reacted_at = now()
+                "#,
+                row.version_id,
+                row.member_id,
+                row.project_id,
+                reaction.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        tx.commit().await.map_err(unexpected_error)?;
+
+        Ok(ReactedRota {
+            project_name: ProjectName::parse(&row.project_name).map_err(
+                |e| ShiftReminderStoreError::UnexpectedError(eyre!(e)),
+            )?,
+            week_of: row.week_of,
+        })
+    }
+}
+
+// The reminder a reply answers, with what's needed to act on it
+struct Reminder {
+    shift_id: Uuid,
+    shift_date: NaiveDate,
+    in_time: i16,
+    member_id: Uuid,
+    member_name: String,
+    project_id: Uuid,
+    project_name: String,
+    user_id: Uuid,
+    owner_email: String,
+}
+
+// Confirm the reminded shift, or ask the project owner to find someone to
+// swap with, however the member replied
+async fn apply_reply(
+    conn: &mut PgConnection,
+    reminder: Reminder,
+    reply: SmsReply,
+) -> Result<RemindedShift, ShiftReminderStoreError> {
+    let shift = RemindedShift {
+        shift_id: ShiftId::new(reminder.shift_id),
+        shift_date: reminder.shift_date,
+        start_time: Minute::parse(reminder.in_time)
+            .map_err(|e| ShiftReminderStoreError::UnexpectedError(eyre!(e)))?,
+        member_name: MemberName::parse(reminder.member_name)
+            .map_err(|e| ShiftReminderStoreError::UnexpectedError(eyre!(e)))?,
+        project_name: ProjectName::parse(&reminder.project_name)
+            .map_err(|e| ShiftReminderStoreError::UnexpectedError(eyre!(e)))?,
+    };
+
+    match reply {
+        SmsReply::Confirm => {
+            timed_query(
+                "record_reply.confirm",
+                sqlx::query!(
+                    r#"
+                        UPDATE shift_reminders SET confirmed_at = now()
+                        WHERE shift_id = $1 AND shift_date = $2
+                        "#,
+                    shift.shift_id.as_ref(),
+                    shift.shift_date
+                )
+                .execute(&mut *conn),
+            )
+            .await
+            .map_err(unexpected_error)?;
+        }
+        SmsReply::Swap => {
+            timed_query(
+                "record_reply.swap",
+                sqlx::query!(
+                    r#"
+                        UPDATE shift_reminders
+                        SET confirmed_at = NULL, swap_requested_at = now()
+                        WHERE shift_id = $1 AND shift_date = $2
+                        "#,
+                    shift.shift_id.as_ref(),
+                    shift.shift_date
+                )
+                .execute(&mut *conn),
+            )
+            .await
+            .map_err(unexpected_error)?;
+
+            let owner = Email::parse(Secret::new(reminder.owner_email))
+                .map_err(|e| {
+                    ShiftReminderStoreError::UnexpectedError(eyre!(e))
+                })?;
+            let project_id = ProjectId::new(reminder.project_id);
+            let (subject, content) = shift.swap_request_email();
+            enqueue_project_email(
+                &mut *conn,
+                &project_id,
+                &owner,
+                &subject,
+                &content,
+            )
+            .await
+            .map_err(ShiftReminderStoreError::UnexpectedError)?;
+
+            enqueue_event(
+                &mut *conn,
+                &DomainEvent::SwapRequested {
+                    user_id: UserId::new(reminder.user_id),
+                    project_id,
+                    shift_id: shift.shift_id.clone(),
+                    member_id: MemberId::new(reminder.member_id),
+                    member_name: shift.member_name.clone(),
+                    shift_date: shift.shift_date,
+                },
+            )
+            .await
+            .map_err(ShiftReminderStoreError::UnexpectedError)?;
+        }
+    }
+
+    Ok(shift)
+}
+
+fn unexpected_error(e: sqlx::Error) -> ShiftReminderStoreError {
+    ShiftReminderStoreError::UnexpectedError(eyre!(e))
+}
@@ -0,0 +1,90 @@
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::PgConnection;
+
+use crate::{domain::PhoneNumber, utils::metrics::timed_query};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingSms {
+    pub id: i64,
+    pub recipient: String,
+    pub content: String,
+}
+
+// Queue a text message. As with emails, call this with the open transaction
+// so the message is only sent if the change that prompted it is committed.
+#[tracing::instrument(name = "Enqueueing SMS", skip_all)]
+pub async fn enqueue_sms(
+    conn: &mut PgConnection,
+    recipient: &PhoneNumber,
+    content: &str,
+) -> Result<()> {
+    timed_query(
+        "enqueue_sms",
+        sqlx::query!(
+            r#"
+                INSERT INTO sms_outbox (recipient, content)
+                VALUES ($1, $2)
+            "#,
+            recipient.as_ref(),
+            content
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to insert SMS into outbox")?;
+
+    Ok(())
+}
+
+// The oldest unsent messages, locked until the transaction ends so concurrent
+// relays never send the same message twice.
+#[tracing::instrument(name = "Fetching pending SMS", skip_all)]
+pub async fn fetch_pending_sms(
+    conn: &mut PgConnection,
+    batch_size: i64,
+) -> Result<Vec<PendingSms>> {
+    let rows = timed_query(
+        "fetch_pending_sms",
+        sqlx::query!(
+            r#"
+                SELECT id, recipient, content
+                FROM sms_outbox
+                WHERE sent_at IS NULL
+                ORDER BY id
+                LIMIT $1
+                FOR UPDATE SKIP LOCKED
+            "#,
+            batch_size
+        )
+        .fetch_all(conn),
+    )
+    .await
+    .wrap_err("failed to fetch pending SMS")?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| PendingSms {
+            id: row.id,
+            recipient: row.recipient,
+            content: row.content,
+        })
+        .collect())
+}
+
+#[tracing::instrument(name = "Marking SMS sent", skip_all)]
+pub async fn mark_sms_sent(conn: &mut PgConnection, id: i64) -> Result<()> {
+    timed_query(
+        "mark_sms_sent",
+        sqlx::query!(
+            r#"
+                UPDATE sms_outbox SET sent_at = now() WHERE id = $1
+            "#,
+            id
+        )
+        .execute(conn),
+    )
+    .await
+    .wrap_err("failed to mark SMS sent")?;
+
+    Ok(())
+}
@@ -1,36 +1,127 @@
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
 use color_eyre::eyre::{eyre, Result};
 use secrecy::{ExposeSecret, Secret};
-use sqlx::PgPool;
+use sqlx::{pool::PoolConnection, PgConnection, PgPool, Postgres, Transaction};
 
-use crate::domain::{
-    verify_password_hash, Email, Password, User, UserId, UserPasswordHash,
-    UserStore, UserStoreError,
+#[cfg(feature = "chaos")]
+use crate::utils::chaos::FaultInjector;
+use crate::{
+    domain::{
+        time::parse_timezone, verify_password_hash, BackupCodeHash, DataRegion,
+        DisplayName, DomainEvent, Email, ExternalId, Invitation,
+        InvitationCode, LegalHold, Locale, Notification, NotificationId,
+        Password, ProjectId, TotpSecret, TwoFACode, UndeliverableEmail,
+        UndeliverableReason, User, UserId, UserPasswordHash, UserStore,
+        UserStoreError,
+    },
+    services::data_stores::{enqueue_event, record_email_opened},
+    utils::{
+        constants::NOTIFICATIONS_LIMIT, database::retry_on_disconnect,
+        hashing_pool::is_hashing_overloaded, metrics::timed_query,
+    },
 };
 
+// The .invalid domain can never be a user's address
+const WARM_UP_EMAIL: &str = "warm-up@warm-up.invalid";
+
 pub struct PostgresUserStore {
     pool: PgPool,
+    #[cfg(feature = "chaos")]
+    faults: FaultInjector,
 }
 
 impl PostgresUserStore {
     pub fn new(pool: PgPool) -> Self {
-        Self { pool }
+        Self {
+            pool,
+            #[cfg(feature = "chaos")]
+            faults: FaultInjector::default(),
+        }
+    }
+
+    #[cfg(feature = "chaos")]
+    pub fn with_faults(pool: PgPool, faults: FaultInjector) -> Self {
+        Self { pool, faults }
+    }
+
+    // A connection, once PostgreSQL can be reached
+    async fn acquire(
+        &self,
+    ) -> Result<PoolConnection<Postgres>, UserStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults.before_begin().await.map_err(unexpected_error)?;
+        retry_on_disconnect(|| self.pool.acquire())
+            .await
+            .map_err(unexpected_error)
+    }
+
+    async fn begin(
+        &self,
+    ) -> Result<Transaction<'static, Postgres>, UserStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults.before_begin().await.map_err(unexpected_error)?;
+        retry_on_disconnect(|| self.pool.begin())
+            .await
+            .map_err(unexpected_error)
+    }
+
+    async fn commit(
+        &self,
+        tx: Transaction<'static, Postgres>,
+    ) -> Result<(), UserStoreError> {
+        #[cfg(feature = "chaos")]
+        self.faults
+            .before_commit()
+            .await
+            .map_err(unexpected_error)?;
+        tx.commit().await.map_err(unexpected_error)
     }
 }
 
 #[async_trait::async_trait]
 impl UserStore for PostgresUserStore {
     #[tracing::instrument(name = "Adding user to PostgreSQL", skip_all)]
-    async fn add_user(&mut self, user: User) -> Result<(), UserStoreError> {
-        sqlx::query!(
-            r#"
-            INSERT INTO users (id, email, password_hash, requires_2fa) VALUES ($1, $2, $3, $4)
+    async fn add_user(
+        &mut self,
+        user: User,
+        invitation: Option<&InvitationCode>,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        if let Some(code) = invitation {
+            // Rolled back with the rest if the user can't be added
+            timed_query(
+                "redeem_invitation",
+                sqlx::query!(
+                    r#"
+                    UPDATE invitations SET uses = uses + 1
+                    WHERE code = $1 AND uses < max_uses AND expires_at > NOW()
+                    RETURNING code
+                    "#,
+                    code.as_ref().expose_secret()
+                )
+                .fetch_optional(&mut *tx),
+            )
+            .await
+            .map_err(unexpected_error)?
+            .ok_or(UserStoreError::InvalidInvitation)?;
+        }
+
+        timed_query(
+            "add_user",
+            sqlx::query!(
+                r#"
+            INSERT INTO users (id, email, password_hash, requires_2fa, accepted_policy_version)
+            VALUES ($1, $2, $3, $4, $5)
             "#,
-            user.id.as_ref() as &uuid::Uuid,
-            user.email.as_ref().expose_secret(),
-            user.hash.as_ref().expose_secret(),
-            user.requires_2fa
+                user.id.as_ref() as &uuid::Uuid,
+                user.email.as_ref().expose_secret(),
+                user.hash.as_ref().expose_secret(),
+                user.requires_2fa,
+                user.accepted_policy_version
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| match e {
             sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
@@ -38,35 +129,38 @@ impl UserStore for PostgresUserStore {
             }
             err => UserStoreError::UnexpectedError(eyre!(err)),
         })?;
-        Ok(())
+
+        enqueue_event(&mut tx, &DomainEvent::UserSignedUp { user_id: user.id })
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
     }
 
     #[tracing::instrument(name = "Retrieving user from PostgreSQL", skip_all)]
     async fn get_user(&self, email: &Email) -> Result<User, UserStoreError> {
-        sqlx::query!(
-            r#"
-                    SELECT id, email, password_hash, requires_2fa
-                    FROM users
-                    WHERE email = $1
-                    "#,
-            email.as_ref().expose_secret()
-        )
-        .fetch_one(&self.pool)
-        .await
-        .map_err(|e| match e {
-            sqlx::Error::RowNotFound => UserStoreError::UserNotFound,
-            err => UserStoreError::UnexpectedError(eyre!(err)),
-        })
-        .map(|row| {
-            Ok(User {
-                id: UserId::new(row.id),
-                email: Email::parse(Secret::new(row.email))
-                    .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
-                hash: UserPasswordHash::parse(Secret::new(row.password_hash))
-                    .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
-                requires_2fa: row.requires_2fa,
-            })
-        })?
+        select_user(&mut *self.acquire().await?, email).await
+    }
+
+    #[tracing::instrument(name = "Warming up PostgreSQL connections", skip_all)]
+    async fn warm_up(&self) -> Result<usize, UserStoreError> {
+        // Looking up an address that can't exist prepares the statement
+        // logins use without touching any user
+        let email = Email::parse(Secret::new(WARM_UP_EMAIL.to_owned()))
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?;
+
+        // Held until the end, so each acquire opens another connection
+        let mut connections = Vec::new();
+        for _ in 0..self.pool.options().get_max_connections() {
+            let mut conn = self.acquire().await?;
+            match select_user(&mut conn, &email).await {
+                Ok(_) | Err(UserStoreError::UserNotFound) => {}
+                Err(e) => return Err(e),
+            }
+            connections.push(conn);
+        }
+
+        Ok(connections.len())
     }
 
     #[tracing::instrument(
@@ -84,27 +178,1327 @@ impl UserStore for PostgresUserStore {
             password.as_ref().to_owned(),
         )
         .await
-        .map_err(|_| UserStoreError::InvalidCredentials)
+        .map_err(|e| match is_hashing_overloaded(e.as_ref()) {
+            // Not a wrong password, so the client can try again
+            true => UserStoreError::UnexpectedError(e),
+            false => UserStoreError::InvalidCredentials,
+        })
     }
 
     async fn delete_user(
         &mut self,
         email: &Email,
     ) -> Result<(), UserStoreError> {
-        let result = sqlx::query!(
-            r#"
-               DELETE FROM users WHERE email = $1
+        let mut tx = self.begin().await?;
+        let row = timed_query(
+            "delete_user.legal_hold",
+            sqlx::query!(
+                r#"
+                SELECT id, legal_hold_at FROM users WHERE email = $1
+                FOR UPDATE
+                "#,
+                email.as_ref().expose_secret()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+        if row.legal_hold_at.is_some() {
+            return Err(UserStoreError::LegalHold);
+        }
+
+        timed_query(
+            "delete_user",
+            sqlx::query!(
+                r#"
+               DELETE FROM users WHERE id = $1
                "#,
-            email.as_ref().expose_secret()
+                row.id
+            )
+            .execute(&mut *tx),
         )
-        .execute(&self.pool)
         .await
         .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?;
 
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::UserDeleted {
+                user_id: UserId::new(row.id),
+            },
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Anonymizing user in PostgreSQL", skip_all)]
+    async fn anonymize_user(
+        &mut self,
+        user_id: &UserId,
+        hash: &UserPasswordHash,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        let row = timed_query(
+            "anonymize_user.previous",
+            sqlx::query!(
+                r#"
+                SELECT email, legal_hold_at FROM users WHERE id = $1
+                FOR UPDATE
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+        if row.legal_hold_at.is_some() {
+            return Err(UserStoreError::LegalHold);
+        }
+        let previous = row.email;
+
+        // The .invalid domain can never receive email
+        timed_query(
+            "anonymize_user",
+            sqlx::query!(
+                r#"
+                UPDATE users
+                SET email = 'anonymized-' || id || '@anonymized.invalid',
+                    password_hash = $2,
+                    requires_2fa = false,
+                    security_emails = false,
+                    email_open_tracking = false,
+                    locale = NULL,
+                    display_name = NULL,
+                    timezone = NULL,
+                    totp_secret = NULL,
+                    totp_last_step = NULL,
+                    external_id = NULL
+                WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                hash.as_ref().expose_secret()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        timed_query(
+            "anonymize_user.backup_codes",
+            sqlx::query!(
+                r#"
+                DELETE FROM backup_codes WHERE user_id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        timed_query(
+            "anonymize_user.notifications",
+            sqlx::query!(
+                r#"
+                DELETE FROM notifications WHERE user_id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        // Tools polling with the account's keys would otherwise keep seeing
+        // its updates
+        timed_query(
+            "anonymize_user.integration_keys",
+            sqlx::query!(
+                r#"
+                DELETE FROM integration_keys WHERE user_id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        // Emails to the old address hold it and whatever they said, and
+        // those not sent yet would go to someone no longer here
+        timed_query(
+            "anonymize_user.email_outbox",
+            sqlx::query!(
+                r#"
+                DELETE FROM email_outbox WHERE recipient = $1
+                "#,
+                previous
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        timed_query(
+            "anonymize_user.undeliverable",
+            sqlx::query!(
+                r#"
+                DELETE FROM undeliverable_emails WHERE email = $1
+                "#,
+                previous
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::UserAnonymized {
+                user_id: user_id.clone(),
+            },
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Updating user email in PostgreSQL", skip_all)]
+    async fn update_email(
+        &mut self,
+        user_id: &UserId,
+        email: &Email,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        let previous = timed_query(
+            "update_user_email.previous",
+            sqlx::query_scalar!(
+                r#"
+                SELECT email FROM users WHERE id = $1 FOR UPDATE
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        timed_query(
+            "update_user_email",
+            sqlx::query!(
+                r#"
+                UPDATE users SET email = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                email.as_ref().expose_secret()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                UserStoreError::UserAlreadyExists
+            }
+            err => UserStoreError::UnexpectedError(eyre!(err)),
+        })?;
+
+        timed_query(
+            "update_user_email.deliverable",
+            sqlx::query!(
+                r#"
+                DELETE FROM undeliverable_emails WHERE email = $1
+                "#,
+                email.as_ref().expose_secret()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        // Confirming the same address again only re-verifies it
+        if previous != *email.as_ref().expose_secret() {
+            enqueue_event(
+                &mut tx,
+                &DomainEvent::UserEmailChanged {
+                    user_id: user_id.clone(),
+                },
+            )
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+        }
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Updating user password in PostgreSQL",
+        skip_all
+    )]
+    async fn update_password(
+        &mut self,
+        user_id: &UserId,
+        hash: &UserPasswordHash,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        let result = timed_query(
+            "update_user_password",
+            sqlx::query!(
+                r#"
+                UPDATE users SET password_hash = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                hash.as_ref().expose_secret()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::UserPasswordChanged {
+                user_id: user_id.clone(),
+            },
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Setting backup codes in PostgreSQL",
+        skip_all
+    )]
+    async fn set_backup_codes(
+        &mut self,
+        user_id: &UserId,
+        hashes: &[BackupCodeHash],
+    ) -> Result<(), UserStoreError> {
+        let hashes = hashes
+            .iter()
+            .map(|hash| hash.as_ref().expose_secret().to_owned())
+            .collect::<Vec<_>>();
+
+        let mut tx = self.begin().await?;
+        timed_query(
+            "delete_backup_codes",
+            sqlx::query!(
+                r#"
+                DELETE FROM backup_codes WHERE user_id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        timed_query(
+            "add_backup_codes",
+            sqlx::query!(
+                r#"
+                INSERT INTO backup_codes (user_id, code_hash)
+                SELECT $1, UNNEST($2::TEXT[])
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                &hashes
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Using backup code in PostgreSQL", skip_all)]
+    async fn use_backup_code(
+        &mut self,
+        user_id: &UserId,
+        hash: &BackupCodeHash,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        // Marking the code used in the same statement that finds it means
+        // the same code can't be spent by two logins at once
+        timed_query(
+            "use_backup_code",
+            sqlx::query!(
+                r#"
+                UPDATE backup_codes SET used_at = NOW()
+                WHERE user_id = $1 AND code_hash = $2 AND used_at IS NULL
+                RETURNING id
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                hash.as_ref().expose_secret()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::InvalidCredentials)?;
+
+        let remaining = timed_query(
+            "count_backup_codes",
+            sqlx::query_scalar!(
+                r#"
+                SELECT COUNT(*) AS "count!" FROM backup_codes
+                WHERE user_id = $1 AND used_at IS NULL
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_one(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::BackupCodeUsed {
+                user_id: user_id.clone(),
+                remaining,
+            },
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Setting TOTP secret in PostgreSQL", skip_all)]
+    async fn set_totp_secret(
+        &mut self,
+        user_id: &UserId,
+        secret: &TotpSecret,
+    ) -> Result<(), UserStoreError> {
+        let encoded = secret.to_base32();
+        let mut tx = self.begin().await?;
+        let result = timed_query(
+            "set_totp_secret",
+            sqlx::query!(
+                r#"
+                UPDATE users SET totp_secret = $2, totp_last_step = NULL
+                WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                encoded.expose_secret()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+
+        enqueue_event(
+            &mut tx,
+            &DomainEvent::AuthenticatorAppSetUp {
+                user_id: user_id.clone(),
+            },
+        )
+        .await
+        .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Using TOTP code in PostgreSQL", skip_all)]
+    async fn use_totp_code(
+        &mut self,
+        user_id: &UserId,
+        code: &TwoFACode,
+        now: DateTime<Utc>,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        // Locked so the same code can't be used by two logins at once
+        let row = timed_query(
+            "get_totp_secret",
+            sqlx::query!(
+                r#"
+                SELECT totp_secret, totp_last_step
+                FROM users
+                WHERE id = $1
+                FOR UPDATE
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        let secret = row
+            .totp_secret
+            .as_deref()
+            .map(TotpSecret::from_base32)
+            .transpose()
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?
+            .ok_or(UserStoreError::InvalidCredentials)?;
+        let step = secret
+            .matching_step(code, now)
+            .filter(|&step| row.totp_last_step.is_none_or(|last| step > last))
+            .ok_or(UserStoreError::InvalidCredentials)?;
+
+        timed_query(
+            "use_totp_code",
+            sqlx::query!(
+                r#"
+                UPDATE users SET totp_last_step = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                step
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Setting 2FA requirement in PostgreSQL",
+        skip_all
+    )]
+    async fn set_requires_2fa(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        let previous = timed_query(
+            "set_requires_2fa.previous",
+            sqlx::query_scalar!(
+                r#"
+                SELECT requires_2fa FROM users WHERE id = $1 FOR UPDATE
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        timed_query(
+            "set_requires_2fa",
+            sqlx::query!(
+                r#"
+                UPDATE users SET requires_2fa = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                enabled
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        // Setting it to what it already was isn't worth an alert
+        if previous != enabled {
+            enqueue_event(
+                &mut tx,
+                &DomainEvent::TwoFactorChanged {
+                    user_id: user_id.clone(),
+                    enabled,
+                },
+            )
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+        }
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(
+        name = "Setting security email preference in PostgreSQL",
+        skip_all
+    )]
+    async fn set_security_emails(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_security_emails",
+            sqlx::query!(
+                r#"
+                UPDATE users SET security_emails = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                enabled
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Setting email open tracking preference in PostgreSQL",
+        skip_all
+    )]
+    async fn set_email_open_tracking(
+        &mut self,
+        user_id: &UserId,
+        enabled: bool,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_email_open_tracking",
+            sqlx::query!(
+                r#"
+                UPDATE users SET email_open_tracking = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                enabled
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Recording email opened in PostgreSQL",
+        skip_all
+    )]
+    async fn record_email_opened(
+        &mut self,
+        delivery_id: i64,
+    ) -> Result<(), UserStoreError> {
+        let mut conn = self.acquire().await?;
+        record_email_opened(&mut conn, delivery_id)
+            .await
+            .map_err(UserStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Marking email undeliverable in PostgreSQL",
+        skip_all
+    )]
+    async fn mark_email_undeliverable(
+        &mut self,
+        email: &Email,
+        reason: UndeliverableReason,
+    ) -> Result<(), UserStoreError> {
+        timed_query(
+            "mark_email_undeliverable",
+            sqlx::query!(
+                r#"
+                INSERT INTO undeliverable_emails (email, reason)
+                VALUES ($1, $2)
+                ON CONFLICT (email)
+                DO UPDATE SET reason = $2, marked_at = now()
+                "#,
+                email.as_ref().expose_secret(),
+                reason.as_str()
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving undeliverable email from PostgreSQL",
+        skip_all
+    )]
+    async fn get_undeliverable_email(
+        &self,
+        email: &Email,
+    ) -> Result<Option<UndeliverableEmail>, UserStoreError> {
+        let row = timed_query(
+            "get_undeliverable_email",
+            sqlx::query!(
+                r#"
+                SELECT reason, marked_at FROM undeliverable_emails
+                WHERE email = $1
+                "#,
+                email.as_ref().expose_secret()
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        row.map(|row| {
+            Ok(UndeliverableEmail {
+                reason: row
+                    .reason
+                    .parse::<UndeliverableReason>()
+                    .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+                marked_at: row.marked_at,
+            })
+        })
+        .transpose()
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving notifications from PostgreSQL",
+        skip_all
+    )]
+    async fn get_notifications(
+        &self,
+        user_id: &UserId,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, UserStoreError> {
+        let rows = timed_query(
+            "get_notifications",
+            sqlx::query!(
+                r#"
+                SELECT id, seq, project_id, message, created_at, read_at
+                FROM notifications
+                WHERE user_id = $1 AND (NOT $2 OR read_at IS NULL)
+                ORDER BY created_at DESC, id
+                LIMIT $3
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                unread_only,
+                NOTIFICATIONS_LIMIT
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Notification {
+                notification_id: NotificationId::new(row.id),
+                cursor: row.seq,
+                project_id: row.project_id.map(ProjectId::new),
+                message: row.message,
+                created_at: row.created_at,
+                read_at: row.read_at,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving new notifications from PostgreSQL",
+        skip_all
+    )]
+    async fn get_notifications_since(
+        &self,
+        user_id: &UserId,
+        cursor: i64,
+    ) -> Result<Vec<Notification>, UserStoreError> {
+        let rows = timed_query(
+            "get_notifications_since",
+            sqlx::query!(
+                r#"
+                SELECT id, seq, project_id, message, created_at, read_at
+                FROM notifications
+                WHERE user_id = $1 AND seq > $2
+                ORDER BY seq
+                LIMIT $3
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                cursor,
+                NOTIFICATIONS_LIMIT
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Notification {
+                notification_id: NotificationId::new(row.id),
+                cursor: row.seq,
+                project_id: row.project_id.map(ProjectId::new),
+                message: row.message,
+                created_at: row.created_at,
+                read_at: row.read_at,
+            })
+            .collect())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving notification cursor from PostgreSQL",
+        skip_all
+    )]
+    async fn get_notification_cursor(
+        &self,
+        user_id: &UserId,
+    ) -> Result<i64, UserStoreError> {
+        timed_query(
+            "get_notification_cursor",
+            sqlx::query_scalar!(
+                r#"
+                SELECT coalesce(max(seq), 0) AS "cursor!" FROM notifications
+                WHERE user_id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_one(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)
+    }
+
+    #[tracing::instrument(
+        name = "Counting unread notifications in PostgreSQL",
+        skip_all
+    )]
+    async fn count_unread_notifications(
+        &self,
+        user_id: &UserId,
+    ) -> Result<i64, UserStoreError> {
+        timed_query(
+            "count_unread_notifications",
+            sqlx::query_scalar!(
+                r#"
+                SELECT count(*) AS "count!" FROM notifications
+                WHERE user_id = $1 AND read_at IS NULL
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_one(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)
+    }
+
+    #[tracing::instrument(
+        name = "Marking notifications read in PostgreSQL",
+        skip_all
+    )]
+    async fn mark_notifications_read(
+        &mut self,
+        user_id: &UserId,
+        notification_ids: Option<&[NotificationId]>,
+    ) -> Result<u64, UserStoreError> {
+        let ids: Option<Vec<uuid::Uuid>> = notification_ids
+            .map(|ids| ids.iter().map(|id| *id.as_ref()).collect());
+        let result = timed_query(
+            "mark_notifications_read",
+            sqlx::query!(
+                r#"
+                UPDATE notifications SET read_at = now()
+                WHERE user_id = $1
+                AND read_at IS NULL
+                AND ($2::UUID[] IS NULL OR id = ANY($2))
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                ids.as_deref()
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        Ok(result.rows_affected())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving accepted policy version from PostgreSQL",
+        skip_all
+    )]
+    async fn get_accepted_policy_version(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<i32>, UserStoreError> {
+        timed_query(
+            "get_accepted_policy_version",
+            sqlx::query_scalar!(
+                r#"
+                SELECT accepted_policy_version FROM users WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)
+    }
+
+    #[tracing::instrument(name = "Accepting policy in PostgreSQL", skip_all)]
+    async fn accept_policy(
+        &mut self,
+        user_id: &UserId,
+        version: i32,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "accept_policy",
+            sqlx::query!(
+                r#"
+                UPDATE users SET accepted_policy_version = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                version
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Retrieving locale from PostgreSQL", skip_all)]
+    async fn get_locale(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<Locale>, UserStoreError> {
+        let locale = timed_query(
+            "get_locale",
+            sqlx::query_scalar!(
+                r#"
+                SELECT locale FROM users WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        locale
+            .map(|tag| Locale::parse(&tag))
+            .transpose()
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))
+    }
+
+    #[tracing::instrument(name = "Setting locale in PostgreSQL", skip_all)]
+    async fn set_locale(
+        &mut self,
+        user_id: &UserId,
+        locale: Option<Locale>,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_locale",
+            sqlx::query!(
+                r#"
+                UPDATE users SET locale = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                locale.map(|locale| locale.tag())
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Setting profile in PostgreSQL", skip_all)]
+    async fn set_profile(
+        &mut self,
+        user_id: &UserId,
+        display_name: Option<&DisplayName>,
+        timezone: Option<Tz>,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_profile",
+            sqlx::query!(
+                r#"
+                UPDATE users SET display_name = $2, timezone = $3
+                WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                display_name.map(|name| name.as_ref().as_str()),
+                timezone.map(|timezone| timezone.name())
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving data region from PostgreSQL",
+        skip_all
+    )]
+    async fn get_data_region(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<DataRegion>, UserStoreError> {
+        let region = timed_query(
+            "get_data_region",
+            sqlx::query_scalar!(
+                r#"
+                SELECT data_region FROM users WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        region
+            .map(|region| DataRegion::parse(&region))
+            .transpose()
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))
+    }
+
+    #[tracing::instrument(name = "Setting data region in PostgreSQL", skip_all)]
+    async fn set_data_region(
+        &mut self,
+        user_id: &UserId,
+        region: Option<DataRegion>,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_data_region",
+            sqlx::query!(
+                r#"
+                UPDATE users SET data_region = $2 WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                region.map(|region| region.as_str())
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::UserNotFound);
+        }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving legal hold from PostgreSQL",
+        skip_all
+    )]
+    async fn get_legal_hold(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Option<DateTime<Utc>>, UserStoreError> {
+        timed_query(
+            "get_legal_hold",
+            sqlx::query_scalar!(
+                r#"
+                SELECT legal_hold_at FROM users WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)
+    }
+
+    #[tracing::instrument(name = "Setting legal hold in PostgreSQL", skip_all)]
+    async fn set_legal_hold(
+        &mut self,
+        user_id: &UserId,
+        placed_at: Option<DateTime<Utc>>,
+    ) -> Result<(), UserStoreError> {
+        let result = timed_query(
+            "set_legal_hold",
+            sqlx::query!(
+                r#"
+                UPDATE users
+                SET legal_hold_at = CASE
+                    WHEN $2::TIMESTAMPTZ IS NULL THEN NULL
+                    ELSE COALESCE(legal_hold_at, $2)
+                END
+                WHERE id = $1
+                "#,
+                user_id.as_ref() as &uuid::Uuid,
+                placed_at
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
         if result.rows_affected() == 0 {
             return Err(UserStoreError::UserNotFound);
         }
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving legal holds from PostgreSQL",
+        skip_all
+    )]
+    async fn get_legal_holds(&self) -> Result<Vec<LegalHold>, UserStoreError> {
+        let rows = timed_query(
+            "get_legal_holds",
+            sqlx::query!(
+                r#"
+                SELECT id, email, legal_hold_at AS "legal_hold_at!"
+                FROM users
+                WHERE legal_hold_at IS NOT NULL
+                ORDER BY legal_hold_at
+                "#
+            )
+            .fetch_all(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
 
+        rows.into_iter()
+            .map(|row| {
+                Ok(LegalHold {
+                    user_id: UserId::new(row.id),
+                    email: Email::parse(Secret::new(row.email)).map_err(
+                        |e| UserStoreError::UnexpectedError(eyre!(e)),
+                    )?,
+                    placed_at: row.legal_hold_at,
+                })
+            })
+            .collect()
+    }
+
+    #[tracing::instrument(name = "Adding invitation to PostgreSQL", skip_all)]
+    async fn add_invitation(
+        &mut self,
+        invitation: &Invitation,
+    ) -> Result<(), UserStoreError> {
+        timed_query(
+            "add_invitation",
+            sqlx::query!(
+                r#"
+                INSERT INTO invitations (code, max_uses, expires_at)
+                VALUES ($1, $2, $3)
+                "#,
+                invitation.code.as_ref().expose_secret(),
+                invitation.max_uses,
+                invitation.expires_at
+            )
+            .execute(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?;
         Ok(())
     }
+
+    #[tracing::instrument(name = "Checking for users in PostgreSQL", skip_all)]
+    async fn has_users(&self) -> Result<bool, UserStoreError> {
+        timed_query(
+            "has_users",
+            sqlx::query_scalar!(
+                r#"SELECT EXISTS (SELECT 1 FROM users) AS "exists!""#
+            )
+            .fetch_one(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)
+    }
+
+    #[tracing::instrument(
+        name = "Retrieving provisioned user from PostgreSQL",
+        skip_all
+    )]
+    async fn get_provisioned_user(
+        &self,
+        external_id: &ExternalId,
+    ) -> Result<User, UserStoreError> {
+        let row = timed_query(
+            "get_provisioned_user",
+            sqlx::query!(
+                r#"
+                SELECT id, email, password_hash, requires_2fa,
+                    accepted_policy_version, display_name, timezone
+                FROM users
+                WHERE external_id = $1
+                "#,
+                external_id.as_ref()
+            )
+            .fetch_optional(&mut *self.acquire().await?),
+        )
+        .await
+        .map_err(unexpected_error)?
+        .ok_or(UserStoreError::UserNotFound)?;
+
+        Ok(User {
+            id: UserId::new(row.id),
+            email: Email::parse(Secret::new(row.email))
+                .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+            hash: UserPasswordHash::parse(Secret::new(row.password_hash))
+                .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+            requires_2fa: row.requires_2fa,
+            accepted_policy_version: row.accepted_policy_version,
+            display_name: row
+                .display_name
+                .as_deref()
+                .map(DisplayName::parse)
+                .transpose()
+                .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+            timezone: row
+                .timezone
+                .as_deref()
+                .map(parse_timezone)
+                .transpose()
+                .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Adding provisioned user to PostgreSQL",
+        skip_all
+    )]
+    async fn add_provisioned_user(
+        &mut self,
+        user: User,
+        external_id: &ExternalId,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        timed_query(
+            "add_provisioned_user",
+            sqlx::query!(
+                r#"
+                INSERT INTO users (
+                    id, email, password_hash, requires_2fa,
+                    accepted_policy_version, external_id
+                )
+                VALUES ($1, $2, $3, $4, $5, $6)
+                "#,
+                user.id.as_ref() as &uuid::Uuid,
+                user.email.as_ref().expose_secret(),
+                user.hash.as_ref().expose_secret(),
+                user.requires_2fa,
+                user.accepted_policy_version,
+                external_id.as_ref()
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::Database(db_err) if db_err.is_unique_violation() => {
+                UserStoreError::UserAlreadyExists
+            }
+            err => UserStoreError::UnexpectedError(eyre!(err)),
+        })?;
+
+        enqueue_event(&mut tx, &DomainEvent::UserSignedUp { user_id: user.id })
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+
+    #[tracing::instrument(name = "Adding first admin to PostgreSQL", skip_all)]
+    async fn add_first_admin(
+        &mut self,
+        user: User,
+    ) -> Result<(), UserStoreError> {
+        let mut tx = self.begin().await?;
+        // Held until commit, so concurrent attempts can't both see no users.
+        // This mode still allows reads.
+        timed_query(
+            "lock_users",
+            sqlx::query!("LOCK TABLE users IN SHARE ROW EXCLUSIVE MODE")
+                .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+
+        let result = timed_query(
+            "add_first_admin",
+            sqlx::query!(
+                r#"
+                INSERT INTO users (
+                    id, email, password_hash, requires_2fa,
+                    accepted_policy_version, is_admin
+                )
+                SELECT $1, $2, $3, $4, $5, TRUE
+                WHERE NOT EXISTS (SELECT 1 FROM users)
+                "#,
+                user.id.as_ref() as &uuid::Uuid,
+                user.email.as_ref().expose_secret(),
+                user.hash.as_ref().expose_secret(),
+                user.requires_2fa,
+                user.accepted_policy_version
+            )
+            .execute(&mut *tx),
+        )
+        .await
+        .map_err(unexpected_error)?;
+        if result.rows_affected() == 0 {
+            return Err(UserStoreError::AlreadyBootstrapped);
+        }
+
+        enqueue_event(&mut tx, &DomainEvent::UserSignedUp { user_id: user.id })
+            .await
+            .map_err(UserStoreError::UnexpectedError)?;
+
+        self.commit(tx).await
+    }
+}
+
+async fn select_user(
+    conn: &mut PgConnection,
+    email: &Email,
+) -> Result<User, UserStoreError> {
+    let row = timed_query(
+        "get_user",
+        sqlx::query!(
+            r#"
+                SELECT id, email, password_hash, requires_2fa,
+                    accepted_policy_version, display_name, timezone
+                FROM users
+                WHERE email = $1
+                "#,
+            email.as_ref().expose_secret()
+        )
+        .fetch_one(conn),
+    )
+    .await
+    .map_err(|e| match e {
+        sqlx::Error::RowNotFound => UserStoreError::UserNotFound,
+        err => UserStoreError::UnexpectedError(eyre!(err)),
+    })?;
+
+    Ok(User {
+        id: UserId::new(row.id),
+        email: Email::parse(Secret::new(row.email))
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+        hash: UserPasswordHash::parse(Secret::new(row.password_hash))
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+        requires_2fa: row.requires_2fa,
+        accepted_policy_version: row.accepted_policy_version,
+        display_name: row
+            .display_name
+            .as_deref()
+            .map(DisplayName::parse)
+            .transpose()
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+        timezone: row
+            .timezone
+            .as_deref()
+            .map(parse_timezone)
+            .transpose()
+            .map_err(|e| UserStoreError::UnexpectedError(eyre!(e)))?,
+    })
+}
+
+fn unexpected_error(e: sqlx::Error) -> UserStoreError {
+    UserStoreError::UnexpectedError(eyre!(e))
 }
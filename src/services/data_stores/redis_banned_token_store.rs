@@ -1,3 +1,4 @@
+use chrono::{DateTime, Utc};
 use color_eyre::eyre::{eyre, Result, WrapErr};
 use redis::{Commands, Connection};
 use secrecy::{ExposeSecret, Secret};
@@ -5,8 +6,8 @@ use std::sync::Arc;
 use tokio::sync::RwLock;
 
 use crate::{
-    domain::{BannedTokenStore, BannedTokenStoreError},
-    utils::auth::TOKEN_TTL_SECONDS,
+    domain::{BannedTokenStore, BannedTokenStoreError, SessionId},
+    services::data_stores::RedisPipeline,
 };
 
 pub struct RedisBannedTokenStore {
@@ -25,17 +26,20 @@ impl BannedTokenStore for RedisBannedTokenStore {
         name = "Adding token to Redis banned token store",
         skip_all
     )]
-    async fn add_token(&mut self, token: &Secret<String>) -> Result<()> {
-        let key = get_key(token);
-        let token_ttl_seconds: u64 = TOKEN_TTL_SECONDS
-            .try_into()
-            .wrap_err("failed to cast TOKEN_TTL_SECONDS to u64")
-            .map_err(BannedTokenStoreError::UnexpectedError)?;
+    async fn add_token(
+        &mut self,
+        token: &Secret<String>,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let Some(ttl_seconds) = remaining_seconds(expires_at, now) else {
+            return Ok(());
+        };
 
         self.conn
             .write()
             .await
-            .set_ex::<_, _, ()>(key, true, token_ttl_seconds)
+            .set_ex::<_, _, ()>(get_key(token), true, ttl_seconds)
             .wrap_err("failed to set banned token in Redis")
             .map_err(BannedTokenStoreError::UnexpectedError)?;
 
@@ -47,18 +51,94 @@ impl BannedTokenStore for RedisBannedTokenStore {
         &self,
         token: &Secret<String>,
     ) -> Result<(), BannedTokenStoreError> {
-        let key = get_key(&token);
-        match self.conn.write().await.exists(&key) {
+        let key = get_key(token);
+        match self.conn.write().await.exists::<_, bool>(&key) {
+            Ok(true) => Err(BannedTokenStoreError::BannedToken),
+            Ok(false) => Ok(()),
+            Err(e) => Err(BannedTokenStoreError::UnexpectedError(eyre!(e))),
+        }
+    }
+
+    #[tracing::instrument(
+        name = "Adding session to Redis banned token store",
+        skip_all
+    )]
+    async fn ban_session(
+        &mut self,
+        session_id: &SessionId,
+        expires_at: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<()> {
+        let Some(ttl_seconds) = remaining_seconds(expires_at, now) else {
+            return Ok(());
+        };
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(get_session_key(session_id), true, ttl_seconds)
+            .wrap_err("failed to set banned session in Redis")
+            .map_err(BannedTokenStoreError::UnexpectedError)?;
+
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Checking Redis banned token store for session",
+        skip_all
+    )]
+    async fn check_session(
+        &self,
+        session_id: &SessionId,
+    ) -> Result<(), BannedTokenStoreError> {
+        let key = get_session_key(session_id);
+        match self.conn.write().await.exists::<_, bool>(&key) {
             Ok(true) => Err(BannedTokenStoreError::BannedToken),
             Ok(false) => Ok(()),
             Err(e) => Err(BannedTokenStoreError::UnexpectedError(eyre!(e))),
         }
     }
+
+    #[tracing::instrument(
+        name = "Checking Redis banned token store for token and session",
+        skip_all
+    )]
+    async fn check_token_and_session(
+        &self,
+        token: &Secret<String>,
+        session_id: &SessionId,
+    ) -> Result<(), BannedTokenStoreError> {
+        match RedisPipeline::new()
+            .exists(get_key(token))
+            .exists(get_session_key(session_id))
+            .query::<(bool, bool)>(&self.conn)
+            .await
+        {
+            Ok((false, false)) => Ok(()),
+            Ok(_) => Err(BannedTokenStoreError::BannedToken),
+            Err(e) => Err(BannedTokenStoreError::UnexpectedError(eyre!(e))),
+        }
+    }
 }
 
 // We are using a key prefix to prevent collisions and organize data!
 const BANNED_TOKEN_KEY_PREFIX: &str = "banned_token:";
+const BANNED_SESSION_KEY_PREFIX: &str = "banned_session:";
+
+// Rounded up, so a ban never lapses before the token does. None once it
+// has.
+fn remaining_seconds(
+    expires_at: DateTime<Utc>,
+    now: DateTime<Utc>,
+) -> Option<u64> {
+    let remaining = (expires_at - now).num_milliseconds();
+    (remaining > 0).then(|| (remaining as u64).div_ceil(1000))
+}
 
 fn get_key(token: &Secret<String>) -> String {
     format!("{}{}", BANNED_TOKEN_KEY_PREFIX, token.expose_secret())
 }
+
+fn get_session_key(session_id: &SessionId) -> String {
+    format!("{}{}", BANNED_SESSION_KEY_PREFIX, session_id.as_ref())
+}
@@ -0,0 +1,62 @@
+use std::{collections::HashMap, sync::Arc};
+
+use color_eyre::eyre::WrapErr;
+use redis::{Commands, Connection};
+use tokio::sync::RwLock;
+
+use crate::domain::{DeprecatedUsageStore, DeprecatedUsageStoreError};
+
+pub struct RedisDeprecatedUsageStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisDeprecatedUsageStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl DeprecatedUsageStore for RedisDeprecatedUsageStore {
+    #[tracing::instrument(
+        name = "Recording deprecated endpoint usage",
+        skip_all
+    )]
+    async fn record_usage(
+        &mut self,
+        path: &str,
+        client: &str,
+    ) -> Result<(), DeprecatedUsageStoreError> {
+        self.conn
+            .write()
+            .await
+            .hincr::<_, _, _, ()>(get_key(path), client, 1)
+            .wrap_err("failed to increment deprecated usage in Redis")
+            .map_err(DeprecatedUsageStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Getting deprecated endpoint usage", skip_all)]
+    async fn get_usage(
+        &self,
+        path: &str,
+    ) -> Result<Vec<(String, u64)>, DeprecatedUsageStoreError> {
+        let usage = self
+            .conn
+            .write()
+            .await
+            .hgetall::<_, HashMap<String, u64>>(get_key(path))
+            .wrap_err("failed to get deprecated usage from Redis")
+            .map_err(DeprecatedUsageStoreError::UnexpectedError)?;
+
+        let mut usage: Vec<(String, u64)> = usage.into_iter().collect();
+        usage.sort();
+        Ok(usage)
+    }
+}
+
+const DEPRECATED_USAGE_KEY_PREFIX: &str = "deprecated_usage:";
+
+fn get_key(path: &str) -> String {
+    format!("{}{}", DEPRECATED_USAGE_KEY_PREFIX, path)
+}
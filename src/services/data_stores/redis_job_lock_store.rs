@@ -0,0 +1,75 @@
+use std::{sync::Arc, time::Duration};
+
+use color_eyre::eyre::WrapErr;
+use redis::{Connection, Script};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::domain::{JobLease, JobLockStore, JobLockStoreError};
+
+pub struct RedisJobLockStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisJobLockStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl JobLockStore for RedisJobLockStore {
+    #[tracing::instrument(name = "Acquiring job lease in Redis", skip_all)]
+    async fn acquire(
+        &mut self,
+        job: &str,
+        duration: Duration,
+    ) -> Result<Option<JobLease>, JobLockStoreError> {
+        let token = Uuid::new_v4().to_string();
+
+        // Only set when no lease is running, and gone once it runs out
+        let set: Option<String> = redis::cmd("SET")
+            .arg(get_key(job))
+            .arg(&token)
+            .arg("NX")
+            .arg("PX")
+            .arg(duration.as_millis() as u64)
+            .query(&mut *self.conn.write().await)
+            .wrap_err("failed to acquire job lease in Redis")
+            .map_err(JobLockStoreError::UnexpectedError)?;
+
+        Ok(set.map(|_| JobLease {
+            job: job.to_owned(),
+            token,
+        }))
+    }
+
+    #[tracing::instrument(name = "Releasing job lease in Redis", skip_all)]
+    async fn release(
+        &mut self,
+        lease: JobLease,
+    ) -> Result<(), JobLockStoreError> {
+        // Checked and deleted in one step, so a lease that ran out and was
+        // taken by another instance is left alone
+        Script::new(RELEASE_SCRIPT)
+            .key(get_key(&lease.job))
+            .arg(&lease.token)
+            .invoke::<()>(&mut *self.conn.write().await)
+            .wrap_err("failed to release job lease in Redis")
+            .map_err(JobLockStoreError::UnexpectedError)?;
+        Ok(())
+    }
+}
+
+const JOB_LEASE_KEY_PREFIX: &str = "job_lease:";
+
+const RELEASE_SCRIPT: &str = r#"
+if redis.call("GET", KEYS[1]) == ARGV[1] then
+    return redis.call("DEL", KEYS[1])
+end
+return 0
+"#;
+
+fn get_key(job: &str) -> String {
+    format!("{}{}", JOB_LEASE_KEY_PREFIX, job)
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::WrapErr;
+use redis::{Commands, Connection};
+use secrecy::ExposeSecret;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        PasswordReset, PasswordResetToken, PasswordResetTokenStore,
+        PasswordResetTokenStoreError, UserId,
+    },
+    utils::auth::PASSWORD_RESET_TTL_SECONDS,
+};
+
+pub struct RedisPasswordResetTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisPasswordResetTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl PasswordResetTokenStore for RedisPasswordResetTokenStore {
+    #[tracing::instrument(
+        name = "Adding token to Redis password reset token store",
+        skip_all
+    )]
+    async fn add_token(
+        &mut self,
+        token: PasswordResetToken,
+        reset: PasswordReset,
+    ) -> Result<(), PasswordResetTokenStoreError> {
+        let key = get_key(&token);
+
+        let reset = serde_json::to_string(&StoredPasswordReset {
+            user_id: reset.user_id,
+            expires_at: reset.expires_at,
+        })
+        .wrap_err("failed to serialise password reset")
+        .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+
+        // Expiry is checked when the token is used; this only clears it out
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(key, reset, PASSWORD_RESET_TTL_SECONDS as u64)
+            .wrap_err("failed to set password reset token in Redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Taking token from Redis password reset token store",
+        skip_all
+    )]
+    async fn take_token(
+        &mut self,
+        token: &PasswordResetToken,
+    ) -> Result<PasswordReset, PasswordResetTokenStoreError> {
+        let key = get_key(token);
+
+        let reset = self
+            .conn
+            .write()
+            .await
+            .get_del::<_, Option<String>>(key)
+            .wrap_err("failed to take password reset token from Redis")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?
+            .ok_or(PasswordResetTokenStoreError::TokenNotFound)?;
+
+        let reset = serde_json::from_str::<StoredPasswordReset>(&reset)
+            .wrap_err("failed to deserialise password reset")
+            .map_err(PasswordResetTokenStoreError::UnexpectedError)?;
+
+        Ok(PasswordReset {
+            user_id: reset.user_id,
+            expires_at: reset.expires_at,
+        })
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredPasswordReset {
+    user_id: UserId,
+    expires_at: DateTime<Utc>,
+}
+
+const PASSWORD_RESET_TOKEN_PREFIX: &str = "password_reset_token:";
+
+fn get_key(token: &PasswordResetToken) -> String {
+    format!(
+        "{}{}",
+        PASSWORD_RESET_TOKEN_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
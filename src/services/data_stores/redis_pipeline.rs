@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use redis::{Connection, FromRedisValue, Pipeline, RedisResult, ToRedisArgs};
+use tokio::sync::RwLock;
+
+// Authenticating a request touches several Redis keys, such as the banned
+// token and banned session checks. Queue the commands here and send them in
+// one round trip rather than one per store call; a single command gains
+// nothing from it. Replies come back in the order the commands were queued.
+#[derive(Default)]
+pub struct RedisPipeline {
+    pipe: Pipeline,
+}
+
+impl RedisPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn exists<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.pipe.exists(key);
+        self
+    }
+
+    pub fn get<K: ToRedisArgs>(&mut self, key: K) -> &mut Self {
+        self.pipe.get(key);
+        self
+    }
+
+    pub fn set_ex<K: ToRedisArgs, V: ToRedisArgs>(
+        &mut self,
+        key: K,
+        value: V,
+        seconds: u64,
+    ) -> &mut Self {
+        self.pipe.set_ex(key, value, seconds).ignore();
+        self
+    }
+
+    pub fn len(&self) -> usize {
+        self.pipe.cmd_iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[tracing::instrument(name = "Running Redis pipeline", skip_all)]
+    pub async fn query<T: FromRedisValue>(
+        &self,
+        conn: &Arc<RwLock<Connection>>,
+    ) -> RedisResult<T> {
+        self.pipe.query(&mut *conn.write().await)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commands_are_queued_in_order() {
+        let mut pipeline = RedisPipeline::new();
+        assert!(pipeline.is_empty());
+
+        pipeline.exists("banned_token:a").get("session:a").set_ex(
+            "rate_limit:a",
+            1,
+            60,
+        );
+
+        let commands: Vec<Vec<u8>> = pipeline
+            .pipe
+            .cmd_iter()
+            .map(|cmd| cmd.get_packed_command())
+            .collect();
+
+        assert_eq!(pipeline.len(), 3);
+        assert!(String::from_utf8_lossy(&commands[0]).contains("EXISTS"));
+        assert!(String::from_utf8_lossy(&commands[1]).contains("GET"));
+        assert!(String::from_utf8_lossy(&commands[2]).contains("SETEX"));
+    }
+}
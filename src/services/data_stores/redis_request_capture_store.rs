@@ -0,0 +1,199 @@
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Instant,
+};
+
+use chrono::Utc;
+use color_eyre::eyre::WrapErr;
+use redis::{Commands, Connection};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{
+        CapturedRequest, RequestCaptureStore, RequestCaptureStoreError, UserId,
+    },
+    utils::constants::{
+        REQUEST_CAPTURE_LIMIT, REQUEST_CAPTURE_REFRESH, REQUEST_CAPTURE_TTL,
+    },
+};
+
+// Every request asks whether its user is being captured, so the users
+// being captured are kept here and only read from Redis again once
+// REQUEST_CAPTURE_REFRESH has passed. Captures started or stopped through
+// this store take effect straight away; those started through another
+// instance's store are picked up at the next refresh.
+pub struct RedisRequestCaptureStore {
+    conn: Arc<RwLock<Connection>>,
+    capturing: Mutex<CapturingUsers>,
+}
+
+#[derive(Default)]
+struct CapturingUsers {
+    // When each user's capture ends, as a Unix timestamp
+    expires_at: HashMap<String, i64>,
+    refreshed_at: Option<Instant>,
+}
+
+impl RedisRequestCaptureStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self {
+            conn,
+            capturing: Mutex::new(CapturingUsers::default()),
+        }
+    }
+
+    fn capturing(&self) -> std::sync::MutexGuard<'_, CapturingUsers> {
+        self.capturing.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[async_trait::async_trait]
+impl RequestCaptureStore for RedisRequestCaptureStore {
+    #[tracing::instrument(name = "Starting request capture in Redis", skip_all)]
+    async fn start_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError> {
+        let now = Utc::now().timestamp();
+        let expires_at = now + REQUEST_CAPTURE_TTL.as_secs() as i64;
+        redis::pipe()
+            .atomic()
+            .zrembyscore(CAPTURING_KEY, "-inf", now)
+            .ignore()
+            .zadd(CAPTURING_KEY, user_id.as_ref().to_string(), expires_at)
+            .ignore()
+            .del(get_captured_key(user_id))
+            .ignore()
+            .query::<()>(&mut *self.conn.write().await)
+            .wrap_err("failed to start request capture in Redis")
+            .map_err(RequestCaptureStoreError::UnexpectedError)?;
+
+        self.capturing()
+            .expires_at
+            .insert(user_id.as_ref().to_string(), expires_at);
+        Ok(())
+    }
+
+    #[tracing::instrument(name = "Stopping request capture in Redis", skip_all)]
+    async fn stop_capture(
+        &mut self,
+        user_id: &UserId,
+    ) -> Result<(), RequestCaptureStoreError> {
+        self.conn
+            .write()
+            .await
+            .zrem::<_, _, ()>(CAPTURING_KEY, user_id.as_ref().to_string())
+            .wrap_err("failed to stop request capture in Redis")
+            .map_err(RequestCaptureStoreError::UnexpectedError)?;
+
+        self.capturing()
+            .expires_at
+            .remove(&user_id.as_ref().to_string());
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Checking for request capture in Redis",
+        skip_all
+    )]
+    async fn is_capturing(
+        &self,
+        user_id: &UserId,
+    ) -> Result<bool, RequestCaptureStoreError> {
+        let now = Utc::now().timestamp();
+        let is_capturing = |capturing: &CapturingUsers| {
+            capturing
+                .expires_at
+                .get(&user_id.as_ref().to_string())
+                .is_some_and(|expires_at| *expires_at > now)
+        };
+        {
+            let capturing = self.capturing();
+            let fresh = capturing.refreshed_at.is_some_and(|refreshed_at| {
+                refreshed_at.elapsed() < REQUEST_CAPTURE_REFRESH
+            });
+            if fresh {
+                return Ok(is_capturing(&capturing));
+            }
+        }
+
+        let expires_at = self
+            .conn
+            .write()
+            .await
+            .zrangebyscore_withscores::<_, _, _, Vec<(String, i64)>>(
+                CAPTURING_KEY,
+                now,
+                "+inf",
+            )
+            .wrap_err("failed to check for request capture in Redis")
+            .map_err(RequestCaptureStoreError::UnexpectedError)?;
+
+        let mut capturing = self.capturing();
+        capturing.expires_at = expires_at.into_iter().collect();
+        capturing.refreshed_at = Some(Instant::now());
+        Ok(is_capturing(&capturing))
+    }
+
+    #[tracing::instrument(
+        name = "Recording captured request in Redis",
+        skip_all
+    )]
+    async fn record(
+        &mut self,
+        user_id: &UserId,
+        request: &CapturedRequest,
+    ) -> Result<(), RequestCaptureStoreError> {
+        let request = serde_json::to_string(request)
+            .wrap_err("failed to serialise captured request")
+            .map_err(RequestCaptureStoreError::UnexpectedError)?;
+        let key = get_captured_key(user_id);
+
+        redis::pipe()
+            .atomic()
+            .lpush(&key, request)
+            .ignore()
+            .ltrim(&key, 0, REQUEST_CAPTURE_LIMIT as isize - 1)
+            .ignore()
+            .expire(&key, REQUEST_CAPTURE_TTL.as_secs() as i64)
+            .ignore()
+            .query::<()>(&mut *self.conn.write().await)
+            .wrap_err("failed to record captured request in Redis")
+            .map_err(RequestCaptureStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(
+        name = "Getting captured requests from Redis",
+        skip_all
+    )]
+    async fn get_captured(
+        &self,
+        user_id: &UserId,
+    ) -> Result<Vec<CapturedRequest>, RequestCaptureStoreError> {
+        let captured = self
+            .conn
+            .write()
+            .await
+            .lrange::<_, Vec<String>>(get_captured_key(user_id), 0, -1)
+            .wrap_err("failed to get captured requests from Redis")
+            .map_err(RequestCaptureStoreError::UnexpectedError)?;
+
+        captured
+            .iter()
+            .map(|request| {
+                serde_json::from_str(request)
+                    .wrap_err("failed to deserialise captured request")
+                    .map_err(RequestCaptureStoreError::UnexpectedError)
+            })
+            .collect()
+    }
+}
+
+// The users being captured, scored by when their capture ends
+const CAPTURING_KEY: &str = "request_capture:capturing";
+const CAPTURED_KEY_PREFIX: &str = "request_capture:captured:";
+
+fn get_captured_key(user_id: &UserId) -> String {
+    format!("{}{}", CAPTURED_KEY_PREFIX, user_id.as_ref())
+}
@@ -0,0 +1,119 @@
+use std::{cmp::Reverse, collections::HashMap, sync::Arc};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::WrapErr;
+use redis::{Commands, Connection};
+use tokio::sync::RwLock;
+
+use crate::{
+    domain::{Session, SessionId, SessionStore, SessionStoreError, UserId},
+    utils::auth::TOKEN_TTL_SECONDS,
+};
+
+// Each user's sessions are kept in one hash, which expires along with the
+// newest of them
+pub struct RedisSessionStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisSessionStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl SessionStore for RedisSessionStore {
+    #[tracing::instrument(name = "Adding session to Redis", skip_all)]
+    async fn add_session(
+        &mut self,
+        session: &Session,
+    ) -> Result<(), SessionStoreError> {
+        let key = get_key(&session.user_id);
+        let expired: Vec<String> = get_all(&self.conn, &session.user_id)
+            .await?
+            .into_iter()
+            .filter(|(_, other)| !other.is_active(session.issued_at))
+            .map(|(field, _)| field)
+            .collect();
+        let value = serde_json::to_string(session)
+            .wrap_err("failed to serialise session")
+            .map_err(SessionStoreError::UnexpectedError)?;
+
+        let mut pipe = redis::pipe();
+        pipe.atomic();
+        if !expired.is_empty() {
+            pipe.hdel(&key, expired).ignore();
+        }
+        pipe.hset(&key, session.session_id.as_ref().to_string(), value)
+            .ignore()
+            .expire(&key, TOKEN_TTL_SECONDS)
+            .ignore()
+            .query::<()>(&mut *self.conn.write().await)
+            .wrap_err("failed to add session to Redis")
+            .map_err(SessionStoreError::UnexpectedError)
+    }
+
+    #[tracing::instrument(name = "Getting sessions from Redis", skip_all)]
+    async fn get_sessions(
+        &self,
+        user_id: &UserId,
+        now: DateTime<Utc>,
+    ) -> Result<Vec<Session>, SessionStoreError> {
+        let mut sessions: Vec<Session> = get_all(&self.conn, user_id)
+            .await?
+            .into_values()
+            .filter(|session| session.is_active(now))
+            .collect();
+        sessions.sort_by_key(|session| Reverse(session.issued_at));
+        Ok(sessions)
+    }
+
+    #[tracing::instrument(name = "Removing session from Redis", skip_all)]
+    async fn remove_session(
+        &mut self,
+        user_id: &UserId,
+        session_id: &SessionId,
+    ) -> Result<(), SessionStoreError> {
+        let removed = self
+            .conn
+            .write()
+            .await
+            .hdel::<_, _, u64>(
+                get_key(user_id),
+                session_id.as_ref().to_string(),
+            )
+            .wrap_err("failed to remove session from Redis")
+            .map_err(SessionStoreError::UnexpectedError)?;
+        match removed {
+            0 => Err(SessionStoreError::SessionNotFound),
+            _ => Ok(()),
+        }
+    }
+}
+
+// The user's sessions by hash field, expired or not
+async fn get_all(
+    conn: &RwLock<Connection>,
+    user_id: &UserId,
+) -> Result<HashMap<String, Session>, SessionStoreError> {
+    conn.write()
+        .await
+        .hgetall::<_, HashMap<String, String>>(get_key(user_id))
+        .wrap_err("failed to get sessions from Redis")
+        .map_err(SessionStoreError::UnexpectedError)?
+        .into_iter()
+        .map(|(field, session)| {
+            serde_json::from_str(&session)
+                .map(|session| (field, session))
+                .wrap_err("failed to deserialise session")
+                .map_err(SessionStoreError::UnexpectedError)
+        })
+        .collect()
+}
+
+const SESSIONS_KEY_PREFIX: &str = "sessions:";
+
+fn get_key(user_id: &UserId) -> String {
+    format!("{}{}", SESSIONS_KEY_PREFIX, user_id.as_ref())
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+
+use color_eyre::eyre::{eyre, WrapErr};
+use redis::{Commands, Connection};
+use secrecy::{ExposeSecret, Secret};
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+
+use crate::domain::{
+    Email, PendingEmailChange, UserId, VerificationToken,
+    VerificationTokenStore, VerificationTokenStoreError, VerificationTokenType,
+};
+
+pub struct RedisVerificationTokenStore {
+    conn: Arc<RwLock<Connection>>,
+}
+
+impl RedisVerificationTokenStore {
+    pub fn new(conn: Arc<RwLock<Connection>>) -> Self {
+        Self { conn }
+    }
+}
+
+#[async_trait::async_trait]
+impl VerificationTokenStore for RedisVerificationTokenStore {
+    #[tracing::instrument(
+        name = "Adding token to Redis verification token store",
+        skip_all
+    )]
+    async fn add_token(
+        &mut self,
+        token: VerificationToken,
+        change: PendingEmailChange,
+    ) -> Result<(), VerificationTokenStoreError> {
+        let key = get_key(&token);
+
+        let change = serde_json::to_string(&StoredEmailChange {
+            user_id: change.user_id,
+            new_email: change.new_email.as_ref().expose_secret().to_owned(),
+            token_type: change.token_type,
+            counterpart: change.counterpart.as_ref().expose_secret().to_owned(),
+            confirmed: change.confirmed,
+        })
+        .wrap_err("failed to serialise pending email change")
+        .map_err(VerificationTokenStoreError::UnexpectedError)?;
+
+        self.conn
+            .write()
+            .await
+            .set_ex::<_, _, ()>(key, change, ONE_HOUR_IN_SECONDS)
+            .wrap_err("failed to set verification token in Redis")
+            .map_err(VerificationTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+
+    #[tracing::instrument(
+        name = "Getting token from Redis verification token store",
+        skip_all
+    )]
+    async fn get_token(
+        &self,
+        token: &VerificationToken,
+    ) -> Result<PendingEmailChange, VerificationTokenStoreError> {
+        let key = get_key(token);
+
+        let change = self
+            .conn
+            .write()
+            .await
+            .get::<_, Option<String>>(key)
+            .wrap_err("failed to get verification token from Redis")
+            .map_err(VerificationTokenStoreError::UnexpectedError)?
+            .ok_or(VerificationTokenStoreError::TokenNotFound)?;
+
+        let change = serde_json::from_str::<StoredEmailChange>(&change)
+            .wrap_err("failed to deserialise pending email change")
+            .map_err(VerificationTokenStoreError::UnexpectedError)?;
+
+        Ok(PendingEmailChange {
+            user_id: change.user_id,
+            new_email: Email::parse(Secret::new(change.new_email)).map_err(
+                |e| VerificationTokenStoreError::UnexpectedError(eyre!(e)),
+            )?,
+            token_type: change.token_type,
+            counterpart: VerificationToken::parse(Secret::new(
+                change.counterpart,
+            ))
+            .map_err(|e| {
+                VerificationTokenStoreError::UnexpectedError(eyre!(e))
+            })?,
+            confirmed: change.confirmed,
+        })
+    }
+
+    #[tracing::instrument(
+        name = "Removing token from Redis verification token store",
+        skip_all
+    )]
+    async fn remove_token(
+        &mut self,
+        token: &VerificationToken,
+    ) -> Result<(), VerificationTokenStoreError> {
+        let key = get_key(token);
+
+        self.conn
+            .write()
+            .await
+            .del::<_, ()>(key)
+            .wrap_err("failed to delete verification token from Redis")
+            .map_err(VerificationTokenStoreError::UnexpectedError)?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoredEmailChange {
+    user_id: UserId,
+    new_email: String,
+    token_type: VerificationTokenType,
+    counterpart: String,
+    confirmed: bool,
+}
+
+const ONE_HOUR_IN_SECONDS: u64 = 60 * 60;
+const VERIFICATION_TOKEN_PREFIX: &str = "verification_token:";
+
+fn get_key(token: &VerificationToken) -> String {
+    format!(
+        "{}{}",
+        VERIFICATION_TOKEN_PREFIX,
+        token.as_ref().expose_secret()
+    )
+}
@@ -0,0 +1,286 @@
+use std::{collections::BTreeMap, time::Duration};
+
+use chrono::Utc;
+use color_eyre::eyre::{Result, WrapErr};
+use secrecy::Secret;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{EmailClientType, JobLockStoreType},
+    domain::Email,
+    services::{
+        data_stores::{
+            count_recent_deliveries, email_open_tracking_allowed,
+            email_undeliverable, fetch_pending_emails, mark_emails_sent,
+            mark_emails_suppressed, PendingEmail,
+        },
+        job_lock::run_exclusively,
+    },
+    utils::{
+        constants::{EMAIL_OUTBOX_BATCH_SIZE, EMAIL_RATE_LIMIT_WINDOW},
+        email_tracking::open_pixel_url,
+    },
+};
+
+// One email to send, made from one or more queued emails
+#[derive(Debug, PartialEq)]
+pub struct Delivery {
+    pub ids: Vec<i64>,
+    pub subject: String,
+    pub content: String,
+    // Only kept for an email sent on its own, since a digest's replies
+    // can't be matched to any one of its emails
+    pub reply_to: Option<String>,
+}
+
+// Decide how to send a recipient's queued emails when they may be sent
+// `allowance` more this hour. If they all fit they go out as they are,
+// otherwise they are collapsed into a single digest. Nothing is sent once the
+// allowance is used up; the emails wait for the window to move on.
+pub fn plan_deliveries(
+    pending: Vec<PendingEmail>,
+    allowance: i64,
+) -> Vec<Delivery> {
+    if pending.is_empty() || allowance <= 0 {
+        return Vec::new();
+    }
+
+    if pending.len() as i64 <= allowance {
+        return pending
+            .into_iter()
+            .map(|email| Delivery {
+                ids: vec![email.id],
+                subject: email.subject,
+                content: email.content,
+                reply_to: email.reply_to,
+            })
+            .collect();
+    }
+
+    let subject = format!("You have {} new notifications", pending.len());
+    let content = pending
+        .iter()
+        .map(|email| format!("{}\n\n{}", email.subject, email.content))
+        .collect::<Vec<_>>()
+        .join("\n\n---\n\n");
+
+    vec![Delivery {
+        ids: pending.into_iter().map(|email| email.id).collect(),
+        subject,
+        content,
+        reply_to: None,
+    }]
+}
+
+// Send a batch of queued emails, holding back any recipient who has already
+// had `max_per_recipient` emails in the last hour. Emails to addresses that
+// bounced or complained are suppressed instead. Returns how many queued
+// emails were sent. A failed send is logged and retried on the next poll.
+#[tracing::instrument(name = "Relaying email outbox", skip_all)]
+pub async fn relay_email_outbox(
+    pool: &PgPool,
+    email_client: &EmailClientType,
+    max_per_recipient: i64,
+) -> Result<usize> {
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let pending =
+        fetch_pending_emails(&mut tx, EMAIL_OUTBOX_BATCH_SIZE).await?;
+    if pending.is_empty() {
+        return Ok(0);
+    }
+
+    let mut by_recipient = BTreeMap::<String, Vec<PendingEmail>>::new();
+    for email in pending {
+        by_recipient
+            .entry(email.recipient.clone())
+            .or_default()
+            .push(email);
+    }
+
+    let window_start = Utc::now() - EMAIL_RATE_LIMIT_WINDOW;
+    let mut sent = 0;
+    for (recipient, emails) in by_recipient {
+        let ids: Vec<i64> = emails.iter().map(|email| email.id).collect();
+        let email = match Email::parse(Secret::new(recipient.clone())) {
+            Ok(email) => email,
+            // An address we can't parse will never become valid, so don't
+            // retry it
+            Err(e) => {
+                tracing::error!(
+                    "Discarding emails to unreadable recipient: {:?}",
+                    e
+                );
+                mark_emails_sent(&mut tx, &ids, false).await?;
+                continue;
+            }
+        };
+
+        if email_undeliverable(&mut tx, &recipient).await? {
+            tracing::debug!(
+                suppressed = ids.len(),
+                "Suppressing emails to undeliverable recipient"
+            );
+            mark_emails_suppressed(&mut tx, &ids).await?;
+            continue;
+        }
+
+        let recent =
+            count_recent_deliveries(&mut tx, &recipient, window_start).await?;
+        let deliveries = plan_deliveries(emails, max_per_recipient - recent);
+        if deliveries.is_empty() {
+            tracing::debug!(
+                held = ids.len(),
+                "Holding emails for rate-limited recipient"
+            );
+        }
+
+        let tracked = !deliveries.is_empty()
+            && email_open_tracking_allowed(&mut tx, &recipient).await?;
+        for delivery in deliveries {
+            // Emails that take replies aren't tracked, as a reply shows
+            // they were read anyway
+            let tracked = tracked && delivery.reply_to.is_none();
+            let outcome = match &delivery.reply_to {
+                Some(reply_to) => {
+                    email_client
+                        .send_email_with_reply_to(
+                            &email,
+                            &delivery.subject,
+                            &delivery.content,
+                            reply_to,
+                        )
+                        .await
+                }
+                None if tracked => {
+                    email_client
+                        .send_tracked_email(
+                            &email,
+                            &delivery.subject,
+                            &delivery.content,
+                            &open_pixel_url(delivery.ids[0]),
+                        )
+                        .await
+                }
+                None => {
+                    email_client
+                        .send_email(
+                            &email,
+                            &delivery.subject,
+                            &delivery.content,
+                        )
+                        .await
+                }
+            };
+            match outcome {
+                Ok(()) => {
+                    mark_emails_sent(&mut tx, &delivery.ids, tracked).await?;
+                    sent += delivery.ids.len();
+                }
+                Err(e) => tracing::error!("Failed to send email: {:?}", e),
+            }
+        }
+    }
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(sent)
+}
+
+pub fn spawn_email_outbox_relay(
+    pool: PgPool,
+    email_client: EmailClientType,
+    job_lock: JobLockStoreType,
+    poll_interval: Duration,
+    max_per_recipient: i64,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let relayed = run_exclusively(
+                &job_lock,
+                "email_outbox_relay",
+                relay_email_outbox(&pool, &email_client, max_per_recipient),
+            )
+            .await;
+            if let Some(Err(e)) = relayed {
+                tracing::error!("Failed to relay email outbox: {:?}", e);
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pending(id: i64) -> PendingEmail {
+        PendingEmail {
+            id,
+            recipient: "ted@example.com".to_owned(),
+            subject: format!("Subject {}", id),
+            content: format!("Content {}", id),
+            reply_to: None,
+        }
+    }
+
+    #[test]
+    fn test_sends_individually_within_allowance() {
+        let deliveries = plan_deliveries(vec![pending(1), pending(2)], 2);
+
+        assert_eq!(
+            deliveries,
+            vec![
+                Delivery {
+                    ids: vec![1],
+                    subject: "Subject 1".to_owned(),
+                    content: "Content 1".to_owned(),
+                    reply_to: None,
+                },
+                Delivery {
+                    ids: vec![2],
+                    subject: "Subject 2".to_owned(),
+                    content: "Content 2".to_owned(),
+                    reply_to: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collapses_burst_into_digest() {
+        let deliveries =
+            plan_deliveries(vec![pending(1), pending(2), pending(3)], 2);
+
+        assert_eq!(deliveries.len(), 1);
+        assert_eq!(deliveries[0].ids, vec![1, 2, 3]);
+        assert_eq!(deliveries[0].subject, "You have 3 new notifications");
+        assert!(deliveries[0].content.contains("Subject 2\n\nContent 2"));
+    }
+
+    #[test]
+    fn test_digests_drop_reply_to() {
+        let with_reply_to = |id| PendingEmail {
+            reply_to: Some(format!("inbound+{}@example.com", id)),
+            ..pending(id)
+        };
+
+        let deliveries = plan_deliveries(vec![with_reply_to(1)], 1);
+        assert_eq!(
+            deliveries[0].reply_to.as_deref(),
+            Some("inbound+1@example.com")
+        );
+
+        let deliveries =
+            plan_deliveries(vec![with_reply_to(1), with_reply_to(2)], 1);
+        assert_eq!(deliveries[0].reply_to, None);
+    }
+
+    #[test]
+    fn test_holds_emails_once_allowance_used() {
+        assert!(plan_deliveries(vec![pending(1)], 0).is_empty());
+        assert!(plan_deliveries(vec![pending(1)], -1).is_empty());
+    }
+}
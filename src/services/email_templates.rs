@@ -0,0 +1,78 @@
+use askama::Template;
+use chrono::NaiveDateTime;
+use color_eyre::eyre::{Result, WrapErr};
+
+use crate::domain::{Locale, SecurityAlert};
+
+// Email bodies are rendered from the templates directory. Each email is a
+// template struct holding what its template needs.
+
+#[derive(Template)]
+#[template(path = "emails/security_alert.txt")]
+struct SecurityAlertEmail<'a> {
+    summary: &'a str,
+    occurred_at: &'a str,
+    detail: &'a str,
+    can_opt_out: bool,
+}
+
+// The subject and body of the email for `alert`, with the time it happened
+// formatted for the recipient
+pub fn render_security_alert(
+    alert: &SecurityAlert,
+    locale: &Locale,
+    occurred_at: NaiveDateTime,
+) -> Result<(String, String)> {
+    let content = SecurityAlertEmail {
+        summary: alert.summary(),
+        occurred_at: &locale.format_date_time(occurred_at),
+        detail: &alert.detail(),
+        can_opt_out: !alert.is_critical(),
+    }
+    .render()
+    .wrap_err("failed to render security alert email")?;
+
+    Ok((format!("Security alert: {}", alert.summary()), content))
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    fn occurred_at() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 10, 21)
+            .unwrap()
+            .and_hms_opt(21, 30, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_render_security_alert() {
+        let (subject, content) = render_security_alert(
+            &SecurityAlert::EmailChanged,
+            &Locale::EnUs,
+            occurred_at(),
+        )
+        .unwrap();
+        assert_eq!(subject, "Security alert: Your email address was changed");
+        assert!(content.starts_with(
+            "Your email address was changed on Tuesday 10/21/2025 at 9:30 PM \
+             (UTC).\n\n"
+        ));
+        assert!(!content.contains("turn off"));
+
+        let (_, content) = render_security_alert(
+            &SecurityAlert::BackupCodeUsed { remaining: 9 },
+            &Locale::EnGb,
+            occurred_at(),
+        )
+        .unwrap();
+        assert!(content.contains("on Tuesday 21/10/2025 at 21:30 (UTC)."));
+        assert!(content.contains("You have 9 backup codes left."));
+        assert!(content.ends_with(
+            "You can turn off emails like this one in your account settings."
+        ));
+    }
+}
@@ -0,0 +1,88 @@
+use std::future::Future;
+
+use color_eyre::eyre::Result;
+use tokio::{sync::broadcast::error::RecvError, task::JoinHandle};
+
+use crate::{app_state::EventBusType, domain::DomainEvent};
+
+// Run `handler` for every event published on the bus, in its own task so a
+// slow side effect never holds up the request that caused it.
+pub fn spawn_subscriber<F, Fut>(
+    event_bus: &EventBusType,
+    name: &'static str,
+    handler: F,
+) -> JoinHandle<()>
+where
+    F: Fn(DomainEvent) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<()>> + Send,
+{
+    let mut receiver = event_bus.subscribe();
+    tokio::spawn(async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) => {
+                    let event_name = event.name();
+                    if let Err(e) = handler(event).await {
+                        tracing::error!(
+                            subscriber = name,
+                            event = event_name,
+                            "Failed to handle event: {:?}",
+                            e
+                        );
+                    }
+                }
+                Err(RecvError::Lagged(missed)) => {
+                    tracing::warn!(
+                        subscriber = name,
+                        "Subscriber fell behind and missed {} events",
+                        missed
+                    );
+                }
+                Err(RecvError::Closed) => break,
+            }
+        }
+    })
+}
+
+#[tracing::instrument(name = "Audit log subscriber", skip_all)]
+pub async fn audit_log(event: DomainEvent) -> Result<()> {
+    tracing::info!(event = event.name(), "[AUDIT] {:?}", event);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use tokio::sync::mpsc;
+
+    use super::*;
+    use crate::{
+        domain::UserId, services::broadcast_event_bus::BroadcastEventBus,
+    };
+
+    #[tokio::test]
+    async fn test_subscriber_handles_published_events() {
+        let event_bus: EventBusType = Arc::new(BroadcastEventBus::new(8));
+        let (sender, mut handled) = mpsc::unbounded_channel();
+
+        spawn_subscriber(&event_bus, "test", move |event| {
+            let sender = sender.clone();
+            async move {
+                sender.send(event)?;
+                Ok(())
+            }
+        });
+
+        let event = DomainEvent::UserSignedUp {
+            user_id: UserId::default(),
+        };
+        event_bus.publish(event.clone());
+
+        let received =
+            tokio::time::timeout(Duration::from_secs(1), handled.recv())
+                .await
+                .expect("Subscriber did not handle event");
+        assert_eq!(received, Some(event));
+    }
+}
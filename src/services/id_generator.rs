@@ -0,0 +1,63 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use uuid::Uuid;
+
+use crate::domain::IdGenerator;
+
+// Random version 4 UUIDs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIdGenerator;
+
+impl IdGenerator for RandomIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+// The same IDs in the same order every time: 00000000-0000-4000-8000-
+// 000000000001, then ...0002 and so on. Clones share the count, so a test
+// can keep one to see what the app it handed one to has used.
+#[derive(Debug, Clone, Default)]
+pub struct SequentialIdGenerator(Arc<AtomicU64>);
+
+// Laid out as a version 4 UUID, so the IDs pass for generated ones
+const SEQUENTIAL_ID_BASE: u128 = 0x0000_0000_0000_4000_8000_0000_0000_0000;
+
+impl SequentialIdGenerator {
+    // The `n`th ID handed out, counting from 1
+    pub fn nth(n: u64) -> Uuid {
+        Uuid::from_u128(SEQUENTIAL_ID_BASE | n as u128)
+    }
+
+    // How many IDs have been handed out
+    pub fn count(&self) -> u64 {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn new_id(&self) -> Uuid {
+        Self::nth(self.0.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequential_ids_are_the_same_every_time() {
+        let ids = SequentialIdGenerator::default();
+        let shared = ids.clone();
+        assert_eq!(
+            ids.new_id().to_string(),
+            "00000000-0000-4000-8000-000000000001"
+        );
+        assert_eq!(shared.new_id(), SequentialIdGenerator::nth(2));
+        assert_eq!(ids.count(), 2);
+        assert_eq!(SequentialIdGenerator::nth(2).get_version_num(), 4);
+    }
+}
@@ -0,0 +1,90 @@
+use std::future::Future;
+
+use crate::{
+    app_state::JobLockStoreType,
+    utils::{
+        constants::JOB_LEASE_DURATION,
+        metrics::{JOB_LEASE_ACQUIRED_METRIC, JOB_LEASE_CONTENDED_METRIC},
+    },
+};
+
+// Run a pass of a background job if no other instance is running it, so
+// polling from several instances doesn't do the work twice. Returns None
+// when the pass was skipped. A pass is skipped, not run unguarded, when the
+// lease can't be taken at all.
+pub async fn run_exclusively<F: Future>(
+    job_lock: &JobLockStoreType,
+    job: &'static str,
+    pass: F,
+) -> Option<F::Output> {
+    let acquired = job_lock
+        .write()
+        .await
+        .acquire(job, JOB_LEASE_DURATION)
+        .await;
+    let lease = match acquired {
+        Ok(Some(lease)) => lease,
+        Ok(None) => {
+            metrics::counter!(JOB_LEASE_CONTENDED_METRIC, "job" => job)
+                .increment(1);
+            return None;
+        }
+        Err(e) => {
+            tracing::error!("Failed to acquire {} lease: {:?}", job, e);
+            return None;
+        }
+    };
+    metrics::counter!(JOB_LEASE_ACQUIRED_METRIC, "job" => job).increment(1);
+
+    let output = pass.await;
+
+    // Otherwise the lease runs out on its own
+    if let Err(e) = job_lock.write().await.release(lease).await {
+        tracing::error!("Failed to release {} lease: {:?}", job, e);
+    }
+    Some(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, LazyLock};
+
+    use tokio::sync::RwLock;
+
+    use super::*;
+    use crate::{
+        services::data_stores::HashmapJobLockStore,
+        utils::metrics::METRICS_HANDLE,
+    };
+
+    #[tokio::test]
+    async fn test_pass_is_skipped_while_another_holds_the_lease() {
+        LazyLock::force(&METRICS_HANDLE);
+        let job_lock: JobLockStoreType =
+            Arc::new(RwLock::new(HashmapJobLockStore::default()));
+
+        let held = job_lock
+            .write()
+            .await
+            .acquire("test_job", JOB_LEASE_DURATION)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            run_exclusively(&job_lock, "test_job", async { 1 }).await,
+            None
+        );
+        assert!(METRICS_HANDLE.render().contains(JOB_LEASE_CONTENDED_METRIC));
+
+        job_lock.write().await.release(held).await.unwrap();
+        assert_eq!(
+            run_exclusively(&job_lock, "test_job", async { 1 }).await,
+            Some(1)
+        );
+        // Released once the pass is done
+        assert_eq!(
+            run_exclusively(&job_lock, "test_job", async { 2 }).await,
+            Some(2)
+        );
+    }
+}
@@ -0,0 +1,122 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::{Connection, PgConnection, PgPool};
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::JobLockStoreType,
+    domain::Job,
+    services::{
+        data_stores::{claim_due_jobs, complete_job, retry_job, QueuedJob},
+        job_lock::run_exclusively,
+        notification_digest::send_rota_change_digest,
+    },
+    utils::constants::{
+        JOB_RETRY_DELAY, JOB_WORKER_BATCH_SIZE, MAX_JOB_ATTEMPTS,
+    },
+};
+
+// Run due jobs in the background. A job that fails is retried after a delay,
+// and given up on once it has failed MAX_JOB_ATTEMPTS times.
+pub fn spawn_job_worker(
+    pool: PgPool,
+    job_lock: JobLockStoreType,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            run_exclusively(&job_lock, "job_worker", async {
+                loop {
+                    match run_due_jobs(&pool).await {
+                        // A full batch means there may be more waiting
+                        Ok(ran) if ran as i64 == JOB_WORKER_BATCH_SIZE => {
+                            continue
+                        }
+                        Ok(_) => break,
+                        Err(e) => {
+                            tracing::error!("Failed to run due jobs: {:?}", e);
+                            break;
+                        }
+                    }
+                }
+            })
+            .await;
+        }
+    })
+}
+
+#[tracing::instrument(name = "Running due jobs", skip_all)]
+pub async fn run_due_jobs(pool: &PgPool) -> Result<usize> {
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let jobs = claim_due_jobs(&mut tx, JOB_WORKER_BATCH_SIZE).await?;
+    for job in jobs.iter() {
+        // Each job gets a savepoint so one failing doesn't undo the others
+        let mut savepoint = Connection::begin(&mut *tx)
+            .await
+            .wrap_err("failed to begin savepoint")?;
+
+        match run_job(&mut savepoint, job).await {
+            Ok(()) => {
+                savepoint
+                    .commit()
+                    .await
+                    .wrap_err("failed to release savepoint")?;
+                complete_job(&mut tx, job.id).await?;
+            }
+            Err(e) => {
+                savepoint
+                    .rollback()
+                    .await
+                    .wrap_err("failed to roll back savepoint")?;
+
+                if job.attempts + 1 >= MAX_JOB_ATTEMPTS {
+                    tracing::error!(
+                        job = job.job.name(),
+                        job_id = job.id,
+                        "Giving up on job: {:?}",
+                        e
+                    );
+                    complete_job(&mut tx, job.id).await?;
+                } else {
+                    tracing::warn!(
+                        job = job.job.name(),
+                        job_id = job.id,
+                        "Job failed, will retry: {:?}",
+                        e
+                    );
+                    retry_job(&mut tx, job.id, Utc::now() + JOB_RETRY_DELAY)
+                        .await?;
+                }
+            }
+        }
+    }
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(jobs.len())
+}
+
+async fn run_job(conn: &mut PgConnection, queued: &QueuedJob) -> Result<()> {
+    match &queued.job {
+        Job::RotaChangeDigest {
+            user_id,
+            project_id,
+            member_name,
+            ..
+        } => {
+            send_rota_change_digest(
+                conn,
+                user_id,
+                project_id,
+                member_name,
+                queued.count,
+            )
+            .await
+        }
+    }
+}
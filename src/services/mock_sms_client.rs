@@ -0,0 +1,20 @@
+use crate::domain::{PhoneNumber, SmsClient};
+use color_eyre::eyre::Result;
+
+pub struct MockSmsClient;
+
+#[async_trait::async_trait]
+impl SmsClient for MockSmsClient {
+    async fn send_sms(
+        &self,
+        recipient: &PhoneNumber,
+        content: &str,
+    ) -> Result<()> {
+        tracing::info!(
+            "Sending SMS to {} with content: {}",
+            recipient.as_ref(),
+            content
+        );
+        Ok(())
+    }
+}
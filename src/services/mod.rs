@@ -1,3 +1,25 @@
+pub mod broadcast_event_bus;
+pub mod clock;
 pub mod data_stores;
+pub mod email_outbox_relay;
+pub mod email_templates;
+pub mod event_subscribers;
+pub mod id_generator;
+pub mod job_lock;
+pub mod job_worker;
 pub mod mock_email_client;
+pub mod mock_sms_client;
+pub mod notification_digest;
+pub mod notification_inbox;
+pub mod outbox_relay;
+#[cfg(feature = "postmark")]
 pub mod postmark_email_client;
+#[cfg(feature = "pwned-passwords")]
+pub mod pwned_passwords;
+pub mod retention;
+pub mod rota_publisher;
+pub mod security_emails;
+pub mod shift_escalations;
+pub mod shift_reminders;
+pub mod sms_outbox_relay;
+pub mod twilio_sms_client;
@@ -0,0 +1,148 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use color_eyre::eyre::{Result, WrapErr};
+use secrecy::Secret;
+use sqlx::{PgConnection, PgPool};
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::EventBusType,
+    domain::{DomainEvent, Email, Job, MemberName, ProjectId, UserId},
+    services::{
+        data_stores::{enqueue_email, schedule_job},
+        event_subscribers::spawn_subscriber,
+    },
+    utils::metrics::timed_query,
+};
+
+// Tell project owners when a member's rota changes. Edits made within
+// `window` of the first are batched into a single notification, so editing
+// a member's week shift by shift sends one email rather than one per shift.
+pub fn spawn_rota_change_digests(
+    event_bus: &EventBusType,
+    pool: PgPool,
+    window: Duration,
+) -> JoinHandle<()> {
+    spawn_subscriber(event_bus, "rota_change_digest", move |event| {
+        schedule_rota_change_digest(pool.clone(), window, event)
+    })
+}
+
+#[tracing::instrument(name = "Scheduling rota change digest", skip_all)]
+async fn schedule_rota_change_digest(
+    pool: PgPool,
+    window: Duration,
+    event: DomainEvent,
+) -> Result<()> {
+    let DomainEvent::ShiftCreated {
+        user_id,
+        project_id,
+        member_id,
+        member_name,
+        standby_member_id,
+        standby_member_name,
+        ..
+    } = event
+    else {
+        return Ok(());
+    };
+
+    // Being put on standby changes the standby member's rota too
+    let mut members = vec![(member_id, member_name)];
+    if let (Some(member_id), Some(member_name)) =
+        (standby_member_id, standby_member_name)
+    {
+        members.push((member_id, member_name));
+    }
+
+    let mut conn = pool.acquire().await.wrap_err("failed to get connection")?;
+    for (member_id, member_name) in members {
+        let job = Job::RotaChangeDigest {
+            user_id: user_id.clone(),
+            project_id: project_id.clone(),
+            member_id,
+            member_name,
+        };
+        schedule_job(&mut conn, &job, Utc::now() + window).await?;
+    }
+    Ok(())
+}
+
+// Queue the digest email for a batch of rota changes. The project or its
+// owner may have been deleted since the changes were made, in which case
+// there is nobody to tell.
+#[tracing::instrument(name = "Sending rota change digest", skip_all)]
+pub async fn send_rota_change_digest(
+    conn: &mut PgConnection,
+    user_id: &UserId,
+    project_id: &ProjectId,
+    member_name: &MemberName,
+    count: i32,
+) -> Result<()> {
+    let row = timed_query(
+        "send_rota_change_digest.owner",
+        sqlx::query!(
+            r#"
+                SELECT users.email, projects_list.project_name
+                FROM projects_list
+                INNER JOIN users ON users.id = projects_list.user_id
+                WHERE projects_list.project_id = $1 AND users.id = $2
+            "#,
+            project_id.as_ref(),
+            user_id.as_ref()
+        )
+        .fetch_optional(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to fetch project owner")?;
+
+    let Some(row) = row else {
+        tracing::debug!("Project no longer exists, skipping rota digest");
+        return Ok(());
+    };
+
+    let recipient = Email::parse(Secret::new(row.email))
+        .wrap_err("failed to parse project owner email")?;
+    let (subject, content) =
+        rota_change_message(count, member_name.as_ref(), &row.project_name);
+
+    enqueue_email(conn, &recipient, &subject, &content).await
+}
+
+pub fn rota_change_message(
+    count: i32,
+    member_name: &str,
+    project_name: &str,
+) -> (String, String) {
+    let shifts = if count == 1 { "shift" } else { "shifts" };
+    (
+        format!("{} {} changed for {}", count, shifts, member_name),
+        format!(
+            "{} {} for {} in {} changed in the last few minutes.",
+            count, shifts, member_name, project_name
+        ),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rota_change_message() {
+        assert_eq!(
+            rota_change_message(3, "Ted", "Craggy Island"),
+            (
+                "3 shifts changed for Ted".to_owned(),
+                "3 shifts for Ted in Craggy Island changed in the last few \
+                 minutes."
+                    .to_owned()
+            )
+        );
+        assert_eq!(
+            rota_change_message(1, "Ted", "Craggy Island").0,
+            "1 shift changed for Ted"
+        );
+    }
+}
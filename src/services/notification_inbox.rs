@@ -0,0 +1,102 @@
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::EventBusType,
+    domain::{DomainEvent, InboxNotice, NotificationId, NotificationRecipient},
+    services::event_subscribers::spawn_subscriber,
+    utils::metrics::timed_query,
+};
+
+// Fill users' in-app inboxes with the events that are news to them, so they
+// see them when they next log in even if they ignore their email
+pub fn spawn_notification_inbox(
+    event_bus: &EventBusType,
+    pool: PgPool,
+) -> JoinHandle<()> {
+    spawn_subscriber(event_bus, "notification_inbox", move |event| {
+        add_notification(pool.clone(), event)
+    })
+}
+
+#[tracing::instrument(name = "Adding notification", skip_all)]
+async fn add_notification(pool: PgPool, event: DomainEvent) -> Result<()> {
+    let Some(project_id) = event.project_id() else {
+        return Ok(());
+    };
+
+    let mut conn = pool.acquire().await.wrap_err("failed to get connection")?;
+    let project_name = timed_query(
+        "add_notification.project",
+        sqlx::query_scalar!(
+            r#"
+                SELECT project_name FROM projects_list WHERE project_id = $1
+            "#,
+            project_id.as_ref()
+        )
+        .fetch_optional(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to fetch project")?;
+    let Some(project_name) = project_name else {
+        tracing::debug!("Project no longer exists, skipping notification");
+        return Ok(());
+    };
+    let Some(notice) = InboxNotice::from_event(&event, &project_name) else {
+        return Ok(());
+    };
+
+    let recipient = match (&notice.recipient, &event) {
+        (NotificationRecipient::Owner, _) => Some(*event.user_id().as_ref()),
+        (
+            NotificationRecipient::Approver,
+            DomainEvent::RotaSubmitted { week_of, .. },
+        ) => {
+            // Approvers without an account still get the email
+            timed_query(
+                "add_notification.approver",
+                sqlx::query_scalar!(
+                    r#"
+                        SELECT users.id
+                        FROM rota_approvals
+                        INNER JOIN users
+                            ON users.email = rota_approvals.approver_email
+                        WHERE rota_approvals.project_id = $1
+                        AND rota_approvals.week_of = $2
+                        AND rota_approvals.status = 'pending'
+                    "#,
+                    project_id.as_ref(),
+                    week_of
+                )
+                .fetch_optional(&mut *conn),
+            )
+            .await
+            .wrap_err("failed to fetch approver")?
+        }
+        (NotificationRecipient::Approver, _) => None,
+    };
+    let Some(recipient) = recipient else {
+        return Ok(());
+    };
+
+    let notification_id = NotificationId::default();
+    timed_query(
+        "add_notification.insert",
+        sqlx::query!(
+            r#"
+                INSERT INTO notifications (id, user_id, project_id, message)
+                VALUES ($1, $2, $3, $4)
+            "#,
+            notification_id.as_ref(),
+            recipient,
+            project_id.as_ref(),
+            notice.message
+        )
+        .execute(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to add notification")?;
+
+    Ok(())
+}
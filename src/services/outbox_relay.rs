@@ -0,0 +1,54 @@
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{EventBusType, JobLockStoreType},
+    services::{data_stores::relay_outbox_events, job_lock::run_exclusively},
+    utils::constants::OUTBOX_RELAY_BATCH_SIZE,
+};
+
+// Poll the outbox and publish committed events to the bus. Subscribers may
+// see an event more than once if the process stops mid-batch, but never miss
+// one that was committed.
+pub fn spawn_outbox_relay(
+    pool: PgPool,
+    event_bus: EventBusType,
+    job_lock: JobLockStoreType,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            run_exclusively(&job_lock, "outbox_relay", async {
+                loop {
+                    match relay_outbox_events(
+                        &pool,
+                        OUTBOX_RELAY_BATCH_SIZE,
+                        |event| event_bus.publish(event),
+                    )
+                    .await
+                    {
+                        // A full batch means there may be more waiting
+                        Ok(relayed)
+                            if relayed as i64 == OUTBOX_RELAY_BATCH_SIZE =>
+                        {
+                            continue
+                        }
+                        Ok(_) => break,
+                        Err(e) => {
+                            tracing::error!(
+                                "Failed to relay outbox events: {:?}",
+                                e
+                            );
+                            break;
+                        }
+                    }
+                }
+            })
+            .await;
+        }
+    })
+}
@@ -27,14 +27,14 @@ impl PostmarkEmailClient {
     }
 }
 
-#[async_trait::async_trait]
-impl EmailClient for PostmarkEmailClient {
-    #[tracing::instrument(name = "Sending email", skip_all)] 
-    async fn send_email(
+impl PostmarkEmailClient {
+    async fn send(
         &self,
         recipient: &Email,
         subject: &str,
-        content: &str,
+        html_body: &str,
+        text_body: &str,
+        reply_to: Option<&str>,
     ) -> Result<()> {
         let base = Url::parse(&self.base_url)?;
         let url = base.join("/email")?;
@@ -43,8 +43,9 @@ impl EmailClient for PostmarkEmailClient {
             from: self.sender.as_ref().expose_secret(),
             to: recipient.as_ref().expose_secret(),
             subject,
-            html_body: content,
-            text_body: content,
+            html_body,
+            text_body,
+            reply_to,
             message_stream: MESSAGE_STREAM,
         };
 
@@ -63,6 +64,48 @@ impl EmailClient for PostmarkEmailClient {
     }
 }
 
+#[async_trait::async_trait]
+impl EmailClient for PostmarkEmailClient {
+    #[tracing::instrument(name = "Sending email", skip_all)] 
+    async fn send_email(
+        &self,
+        recipient: &Email,
+        subject: &str,
+        content: &str,
+    ) -> Result<()> {
+        self.send(recipient, subject, content, content, None).await
+    }
+
+    #[tracing::instrument(name = "Sending tracked email", skip_all)]
+    async fn send_tracked_email(
+        &self,
+        recipient: &Email,
+        subject: &str,
+        content: &str,
+        pixel_url: &str,
+    ) -> Result<()> {
+        // Only the HTML body can carry the image
+        let html_body = format!(
+            "{}<img src=\"{}\" width=\"1\" height=\"1\" alt=\"\">",
+            content, pixel_url
+        );
+        self.send(recipient, subject, &html_body, content, None)
+            .await
+    }
+
+    #[tracing::instrument(name = "Sending email with reply-to", skip_all)]
+    async fn send_email_with_reply_to(
+        &self,
+        recipient: &Email,
+        subject: &str,
+        content: &str,
+        reply_to: &str,
+    ) -> Result<()> {
+        self.send(recipient, subject, content, content, Some(reply_to))
+            .await
+    }
+}
+
 const MESSAGE_STREAM: &str = "outbound";
 const POSTMARK_AUTH_HEADER: &str = "X-Postmark-Server-Token";
 
@@ -76,6 +119,8 @@ struct SendEmailRequest<'a> {
     subject: &'a str,
     html_body: &'a str,
     text_body: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reply_to: Option<&'a str>,
     message_stream: &'a str,
 }
 
@@ -87,7 +132,9 @@ mod tests {
     use fake::faker::internet::en::SafeEmail;
     use fake::faker::lorem::en::{Paragraph, Sentence};
     use fake::{Fake, Faker};
-    use wiremock::matchers::{any, header, header_exists, method, path};
+    use wiremock::matchers::{
+        any, body_partial_json, header, header_exists, method, path,
+    };
     use wiremock::{Mock, MockServer, Request, ResponseTemplate};
 
     use super::PostmarkEmailClient;
@@ -166,6 +213,60 @@ mod tests {
         assert!(outcome.is_ok());
     }
 
+    // Test to ensure only the HTML body carries the tracking image
+    #[tokio::test]
+    async fn send_tracked_email_adds_image_to_html_body() {
+        let mock_server = MockServer::start().await;
+        let email_client = email_client(mock_server.uri());
+        let pixel_url = "https://example.com/email/open?token=1.abc";
+
+        Mock::given(path("/email"))
+            .and(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "HtmlBody": format!(
+                    "Hello<img src=\"{}\" width=\"1\" height=\"1\" \
+                     alt=\"\">",
+                    pixel_url
+                ),
+                "TextBody": "Hello"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let outcome = email_client
+            .send_tracked_email(&email(), &subject(), "Hello", pixel_url)
+            .await;
+
+        assert!(outcome.is_ok());
+    }
+
+    // Test to ensure replies are sent to the address given
+    #[tokio::test]
+    async fn send_email_with_reply_to_sets_reply_to() {
+        let mock_server = MockServer::start().await;
+        let email_client = email_client(mock_server.uri());
+        let reply_to = "inbound+1-abc@example.com";
+
+        Mock::given(path("/email"))
+            .and(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "ReplyTo": reply_to,
+                "TextBody": "Hello"
+            })))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let outcome = email_client
+            .send_email_with_reply_to(&email(), &subject(), "Hello", reply_to)
+            .await;
+
+        assert!(outcome.is_ok());
+    }
+
     // Test to handle server error responses
     #[tokio::test]
     async fn send_email_fails_if_the_server_returns_500() {
@@ -0,0 +1,135 @@
+use color_eyre::eyre::Result;
+use reqwest::{Client, Url};
+use secrecy::ExposeSecret;
+use sha1::{Digest, Sha1};
+
+use crate::domain::Password;
+
+// Asks Have I Been Pwned whether a password has been in a breach, using its
+// k-anonymity range API: only the first five characters of the password's
+// SHA-1 hash leave the app, and the match is made here against the
+// suffixes sent back.
+pub struct PwnedPasswordsClient {
+    http_client: Client,
+    base_url: String,
+}
+
+impl PwnedPasswordsClient {
+    pub fn new(base_url: String, http_client: Client) -> Self {
+        Self {
+            http_client,
+            base_url,
+        }
+    }
+
+    #[tracing::instrument(name = "Checking password with HIBP", skip_all)]
+    pub async fn is_pwned(&self, password: &Password) -> Result<bool> {
+        let hash = Sha1::digest(password.as_ref().expose_secret().as_bytes())
+            .iter()
+            .map(|byte| format!("{:02X}", byte))
+            .collect::<String>();
+        let (prefix, suffix) = hash.split_at(5);
+
+        let url =
+            Url::parse(&self.base_url)?.join(&format!("range/{}", prefix))?;
+        let body = self
+            .http_client
+            .get(url)
+            // Pads the response so its size doesn't give the prefix away
+            .header(PADDING_HEADER, "true")
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        Ok(body.lines().any(|line| match line.trim().split_once(':') {
+            // Padding entries have a count of 0
+            Some((candidate, count)) => {
+                candidate.eq_ignore_ascii_case(suffix) && count != "0"
+            }
+            None => false,
+        }))
+    }
+}
+
+const PADDING_HEADER: &str = "Add-Padding";
+
+#[cfg(test)]
+mod tests {
+    use secrecy::Secret;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    use super::*;
+    use crate::utils::constants::test;
+
+    fn client(base_url: String) -> PwnedPasswordsClient {
+        let http_client = Client::builder()
+            .timeout(test::pwned_passwords::TIMEOUT)
+            .build()
+            .unwrap();
+        PwnedPasswordsClient::new(base_url, http_client)
+    }
+
+    fn password(s: &str) -> Password {
+        Password::parse(Secret::new(s.to_owned())).unwrap()
+    }
+
+    // SHA-1 of "password1" is E38AD214943DAAD1D64C102FAEC29DE4AFE9DA3D
+    #[tokio::test]
+    async fn test_finds_breached_password_by_prefix() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/range/E38AD"))
+            .and(header(PADDING_HEADER, "true"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n\
+                 214943DAAD1D64C102FAEC29DE4AFE9DA3D:2413945\r\n",
+            ))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let pwned = client(format!("{}/", mock_server.uri()))
+            .is_pwned(&password("password1"))
+            .await
+            .unwrap();
+
+        assert!(pwned);
+    }
+
+    #[tokio::test]
+    async fn test_ignores_padding_and_other_suffixes() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(
+                "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n\
+                 214943DAAD1D64C102FAEC29DE4AFE9DA3D:0\r\n",
+            ))
+            .mount(&mock_server)
+            .await;
+
+        let pwned = client(format!("{}/", mock_server.uri()))
+            .is_pwned(&password("password1"))
+            .await
+            .unwrap();
+
+        assert!(!pwned);
+    }
+
+    #[tokio::test]
+    async fn test_fails_if_the_server_returns_500() {
+        let mock_server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(500))
+            .mount(&mock_server)
+            .await;
+
+        let outcome = client(format!("{}/", mock_server.uri()))
+            .is_pwned(&password("password1"))
+            .await;
+
+        assert!(outcome.is_err());
+    }
+}
@@ -0,0 +1,280 @@
+use std::{future::Future, time::Duration};
+
+use chrono::{DateTime, Utc};
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::{postgres::PgQueryResult, PgPool};
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{ClockType, JobLockStoreType},
+    domain::RetentionPolicy,
+    services::job_lock::run_exclusively,
+    utils::{
+        constants::RETENTION_BATCH_SIZE,
+        metrics::{timed_query, RETENTION_ROWS_DELETED_METRIC},
+    },
+};
+
+// Delete records older than the retention policy keeps, so the database
+// doesn't grow without bound
+pub fn spawn_retention_pruning(
+    pool: PgPool,
+    job_lock: JobLockStoreType,
+    clock: ClockType,
+    policy: RetentionPolicy,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let pruned = run_exclusively(
+                &job_lock,
+                "retention",
+                prune_expired_records(&pool, &policy, clock.now()),
+            )
+            .await;
+            if let Some(Err(e)) = pruned {
+                tracing::error!("Failed to prune expired records: {:?}", e);
+            }
+        }
+    })
+}
+
+// Delete every record the policy no longer keeps, a batch at a time so no
+// one statement holds its locks for long. Returns how many rows were
+// deleted, which are also counted per table in RETENTION_ROWS_DELETED_METRIC.
+// Records belonging to an account under legal hold are kept, however old.
+#[tracing::instrument(name = "Pruning expired records", skip_all)]
+pub async fn prune_expired_records(
+    pool: &PgPool,
+    policy: &RetentionPolicy,
+    now: DateTime<Utc>,
+) -> Result<u64> {
+    let audit_log_cutoff = now - policy.audit_log;
+    let notification_log_cutoff = now - policy.notification_log;
+    let rota_history_cutoff = (now - policy.rota_history).date_naive();
+
+    let mut deleted = 0;
+    deleted += prune_table("project_events", || {
+        timed_query(
+            "prune_expired_records.project_events",
+            sqlx::query!(
+                r#"
+                    DELETE FROM project_events
+                    WHERE id IN (
+                        SELECT id
+                        FROM project_events
+                        WHERE occurred_at < $1
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM projects_list
+                            JOIN users ON users.id = projects_list.user_id
+                            WHERE projects_list.project_id = project_events.project_id
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                audit_log_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    deleted += prune_table("event_outbox", || {
+        timed_query(
+            "prune_expired_records.event_outbox",
+            sqlx::query!(
+                r#"
+                    DELETE FROM event_outbox
+                    WHERE id IN (
+                        SELECT id
+                        FROM event_outbox
+                        WHERE delivered_at < $1
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM users
+                            WHERE users.id::TEXT = event_outbox.event->>'user_id'
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                audit_log_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    deleted += prune_table("email_outbox", || {
+        timed_query(
+            "prune_expired_records.email_outbox",
+            sqlx::query!(
+                r#"
+                    DELETE FROM email_outbox
+                    WHERE id IN (
+                        SELECT id
+                        FROM email_outbox
+                        WHERE sent_at < $1
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM projects_list
+                            JOIN users ON users.id = projects_list.user_id
+                            WHERE projects_list.project_id = email_outbox.project_id
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM users
+                            WHERE users.email = email_outbox.recipient
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                notification_log_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    // Texts go to members' phones and aren't tied to an account, so a legal
+    // hold doesn't keep them
+    deleted += prune_table("sms_outbox", || {
+        timed_query(
+            "prune_expired_records.sms_outbox",
+            sqlx::query!(
+                r#"
+                    DELETE FROM sms_outbox
+                    WHERE id IN (
+                        SELECT id
+                        FROM sms_outbox
+                        WHERE sent_at < $1
+                        LIMIT $2
+                    )
+                "#,
+                notification_log_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    deleted += prune_table("notifications", || {
+        timed_query(
+            "prune_expired_records.notifications",
+            sqlx::query!(
+                r#"
+                    DELETE FROM notifications
+                    WHERE id IN (
+                        SELECT id
+                        FROM notifications
+                        WHERE created_at < $1
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM users
+                            WHERE users.id = notifications.user_id
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                notification_log_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    // Reactions to a rota go with it
+    deleted += prune_table("rota_versions", || {
+        timed_query(
+            "prune_expired_records.rota_versions",
+            sqlx::query!(
+                r#"
+                    DELETE FROM rota_versions
+                    WHERE version_id IN (
+                        SELECT version_id
+                        FROM rota_versions
+                        WHERE week_of < $1
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM projects_list
+                            JOIN users ON users.id = projects_list.user_id
+                            WHERE projects_list.project_id = rota_versions.project_id
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                rota_history_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+    // Pending approvals are left for the approver, however old
+    deleted += prune_table("rota_approvals", || {
+        timed_query(
+            "prune_expired_records.rota_approvals",
+            sqlx::query!(
+                r#"
+                    DELETE FROM rota_approvals
+                    WHERE approval_id IN (
+                        SELECT approval_id
+                        FROM rota_approvals
+                        WHERE week_of < $1
+                        AND status <> 'pending'
+                        AND NOT EXISTS (
+                            SELECT 1
+                            FROM projects_list
+                            JOIN users ON users.id = projects_list.user_id
+                            WHERE projects_list.project_id = rota_approvals.project_id
+                            AND users.legal_hold_at IS NOT NULL
+                        )
+                        LIMIT $2
+                    )
+                "#,
+                rota_history_cutoff,
+                RETENTION_BATCH_SIZE
+            )
+            .execute(pool),
+        )
+    })
+    .await?;
+
+    Ok(deleted)
+}
+
+// Run a batch delete until it comes back short of a full batch
+async fn prune_table<F, Fut>(
+    table: &'static str,
+    delete_batch: F,
+) -> Result<u64>
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<PgQueryResult, sqlx::Error>>,
+{
+    let mut deleted = 0;
+    loop {
+        let batch = delete_batch()
+            .await
+            .wrap_err_with(|| format!("failed to prune {}", table))?
+            .rows_affected();
+        deleted += batch;
+        if batch < RETENTION_BATCH_SIZE as u64 {
+            break;
+        }
+    }
+    metrics::counter!(RETENTION_ROWS_DELETED_METRIC, "table" => table)
+        .increment(deleted);
+    if deleted > 0 {
+        tracing::info!(table, deleted, "Pruned expired records");
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,232 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Days, NaiveDate, NaiveDateTime, Utc};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use secrecy::Secret;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{ClockType, JobLockStoreType},
+    domain::{
+        publish_problems, publish_warnings, time::week_beginning, CronSchedule,
+        DateRange, Email, ProjectId, ProjectStore, RotaApproval, RotaVersion,
+        UserId,
+    },
+    services::{
+        data_stores::{enqueue_project_email, PostgresProjectStore},
+        job_lock::run_exclusively,
+    },
+    utils::metrics::timed_query,
+};
+
+// Publish next week's rota for each project that has an auto-publish
+// schedule, as each schedule comes due. Rotas have no timezone, so
+// schedules are read as UTC.
+pub fn spawn_rota_publisher(
+    pool: PgPool,
+    job_lock: JobLockStoreType,
+    clock: ClockType,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let now = clock.now().naive_utc();
+            let published = run_exclusively(
+                &job_lock,
+                "rota_publisher",
+                publish_due_rotas(&pool, now),
+            )
+            .await;
+            if let Some(Err(e)) = published {
+                tracing::error!("Failed to publish rotas: {:?}", e);
+            }
+        }
+    })
+}
+
+// Run every auto-publish schedule that has come due. A schedule that has
+// only just been set is first worked out, so it can't run for a time that
+// had already passed. Each run is claimed before it's made, so it happens
+// at most once even with several instances polling; a run that fails is
+// logged and not retried. Returns how many rotas were published, or
+// submitted for approval where a project needs it.
+#[tracing::instrument(name = "Publishing due rotas", skip_all)]
+pub async fn publish_due_rotas(
+    pool: &PgPool,
+    now: NaiveDateTime,
+) -> Result<usize> {
+    let rows = timed_query(
+        "publish_due_rotas.projects",
+        sqlx::query!(
+            r#"
+                SELECT
+                    projects_list.project_id,
+                    projects_list.user_id,
+                    projects_list.project_name,
+                    projects_list.auto_publish AS "auto_publish!",
+                    projects_list.auto_publish_at,
+                    users.email
+                FROM projects_list
+                INNER JOIN users ON users.id = projects_list.user_id
+                WHERE projects_list.auto_publish IS NOT NULL
+                AND (
+                    projects_list.auto_publish_at IS NULL
+                    OR projects_list.auto_publish_at <= $1
+                )
+            "#,
+            now
+        )
+        .fetch_all(pool),
+    )
+    .await
+    .wrap_err("failed to fetch due rotas")?;
+
+    let mut published = 0;
+    for row in rows {
+        let schedule = CronSchedule::parse(&row.auto_publish)
+            .wrap_err("invalid auto-publish schedule")?;
+        let claimed = timed_query(
+            "publish_due_rotas.claim",
+            sqlx::query!(
+                r#"
+                    UPDATE projects_list
+                    SET auto_publish_at = $2
+                    WHERE project_id = $1
+                    AND auto_publish_at IS NOT DISTINCT FROM $3
+                "#,
+                row.project_id,
+                schedule.next_after(now),
+                row.auto_publish_at
+            )
+            .execute(pool),
+        )
+        .await
+        .wrap_err("failed to claim rota publication")?;
+        if claimed.rows_affected() == 0 || row.auto_publish_at.is_none() {
+            continue;
+        }
+
+        let owner = Email::parse(Secret::new(row.email))
+            .wrap_err("invalid owner email")?;
+        match publish_next_week(
+            pool,
+            UserId::new(row.user_id),
+            ProjectId::new(row.project_id),
+            &row.project_name,
+            &owner,
+            now,
+        )
+        .await
+        {
+            Ok(true) => published += 1,
+            Ok(false) => {}
+            Err(e) => tracing::error!(
+                project_id = %row.project_id,
+                "Failed to publish rota: {:?}",
+                e
+            ),
+        }
+    }
+
+    Ok(published)
+}
+
+// Publish the week after the one `now` is in, or submit it to the project's
+// approver, unless there is something wrong with it, in which case the owner
+// is told what instead. Returns whether it was published or submitted.
+async fn publish_next_week(
+    pool: &PgPool,
+    user_id: UserId,
+    project_id: ProjectId,
+    project_name: &str,
+    owner: &Email,
+    now: NaiveDateTime,
+) -> Result<bool> {
+    let mut project_store = PostgresProjectStore::new(pool.clone());
+    let settings = project_store
+        .get_project_settings(&user_id, &project_id)
+        .await
+        .map_err(|e| eyre!(e))?;
+    let week_of =
+        week_beginning(now.date(), settings.week_start) + Days::new(7);
+    let range = DateRange::new(week_of, week_of + Days::new(6))
+        .map_err(|e| eyre!(e))?;
+
+    let project = project_store
+        .get_project(&user_id, &project_id)
+        .await
+        .map_err(|e| eyre!(e))?;
+    let absences = project_store
+        .get_absences(&user_id, &project_id, &range)
+        .await
+        .map_err(|e| eyre!(e))?;
+
+    let problems = publish_problems(&project, &settings, &absences);
+    if !problems.is_empty() {
+        alert_owner(pool, &project_id, owner, project_name, week_of, &problems)
+            .await?;
+        return Ok(false);
+    }
+
+    let now = DateTime::from_naive_utc_and_offset(now, Utc);
+    if let Some(approver_email) = settings.approver_email {
+        let published = project_store
+            .get_latest_rota_version(&user_id, &project_id)
+            .await
+            .map_err(|e| eyre!(e))?;
+        let approval = RotaApproval::new(
+            &project,
+            week_of,
+            true,
+            published.as_ref(),
+            approver_email,
+            now,
+        );
+        project_store
+            .submit_rota(&user_id, &approval)
+            .await
+            .map_err(|e| eyre!(e))?;
+        return Ok(true);
+    }
+
+    for warning in publish_warnings(&project) {
+        tracing::warn!("Publishing {} anyway: {}", project_name, warning);
+    }
+    let version = RotaVersion::new(&project, week_of, true, now);
+    project_store
+        .publish_rota(&user_id, &version)
+        .await
+        .map_err(|e| eyre!(e))?;
+
+    Ok(true)
+}
+
+async fn alert_owner(
+    pool: &PgPool,
+    project_id: &ProjectId,
+    owner: &Email,
+    project_name: &str,
+    week_of: NaiveDate,
+    problems: &[String],
+) -> Result<()> {
+    let subject = format!("Your {} rota wasn't published", project_name);
+    let content = format!(
+        "The {} rota for the week of {} was due to be published, but it \
+         wasn't because:\n\n{}\n\nIt will be tried again at the next \
+         scheduled time.",
+        project_name,
+        week_of,
+        problems
+            .iter()
+            .map(|problem| format!("- {}", problem))
+            .collect::<Vec<_>>()
+            .join("\n")
+    );
+
+    let mut conn = pool.acquire().await.wrap_err("failed to get connection")?;
+    enqueue_project_email(&mut conn, project_id, owner, &subject, &content)
+        .await
+}
@@ -0,0 +1,70 @@
+use chrono::Utc;
+use color_eyre::eyre::{Result, WrapErr};
+use secrecy::Secret;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::EventBusType,
+    domain::{DomainEvent, Email, Locale, SecurityAlert},
+    services::{
+        data_stores::enqueue_email, email_templates::render_security_alert,
+        event_subscribers::spawn_subscriber,
+    },
+    utils::metrics::timed_query,
+};
+
+// Email users about changes to their account's security. Users can turn
+// these off, apart from critical alerts.
+pub fn spawn_security_emails(
+    event_bus: &EventBusType,
+    pool: PgPool,
+) -> JoinHandle<()> {
+    spawn_subscriber(event_bus, "security_emails", move |event| {
+        send_security_alert(pool.clone(), event)
+    })
+}
+
+#[tracing::instrument(name = "Sending security alert", skip_all)]
+async fn send_security_alert(pool: PgPool, event: DomainEvent) -> Result<()> {
+    let Some(alert) = SecurityAlert::from_event(&event) else {
+        return Ok(());
+    };
+
+    let mut conn = pool.acquire().await.wrap_err("failed to get connection")?;
+    let row = timed_query(
+        "send_security_alert.user",
+        sqlx::query!(
+            r#"
+                SELECT email, security_emails, locale
+                FROM users
+                WHERE id = $1
+            "#,
+            event.user_id().as_ref()
+        )
+        .fetch_optional(&mut *conn),
+    )
+    .await
+    .wrap_err("failed to fetch user")?;
+
+    let Some(row) = row else {
+        tracing::debug!("User no longer exists, skipping security alert");
+        return Ok(());
+    };
+    if !row.security_emails && !alert.is_critical() {
+        return Ok(());
+    }
+
+    let recipient = Email::parse(Secret::new(row.email))
+        .wrap_err("failed to parse user email")?;
+    // Unrecognised locales fall back to the default rather than losing the
+    // alert
+    let locale = row
+        .locale
+        .and_then(|tag| Locale::parse(&tag).ok())
+        .unwrap_or_default();
+    let (subject, content) =
+        render_security_alert(&alert, &locale, Utc::now().naive_utc())?;
+
+    enqueue_email(&mut conn, &recipient, &subject, &content).await
+}
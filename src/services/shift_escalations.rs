@@ -0,0 +1,356 @@
+use std::time::Duration;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use color_eyre::eyre::{eyre, Result, WrapErr};
+use reqwest::Client;
+use secrecy::Secret;
+use serde_json::json;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+use crate::{
+    app_state::{ClockType, JobLockStoreType},
+    domain::{
+        escalation_message, ChatWebhookUrl, DataRegion, Day, Email,
+        EscalationChannel, EscalationStep, MemberName, Minute, PhoneNumber,
+        ResidencyRules, ShiftCoverage, CRITICAL_SHIFT_TAG,
+    },
+    services::{
+        data_stores::{enqueue_email, enqueue_sms},
+        job_lock::run_exclusively,
+        shift_reminders::upcoming_start,
+    },
+    utils::{constants::DATA_REGION_HEADER, metrics::timed_query},
+};
+
+// Escalate critical shifts that nobody has covered as they get closer,
+// following each project's escalation steps. Rotas have no timezone, so
+// shift times are read as UTC.
+pub fn spawn_shift_escalations(
+    pool: PgPool,
+    http_client: Client,
+    job_lock: JobLockStoreType,
+    clock: ClockType,
+    residency_rules: ResidencyRules,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let now = clock.now().naive_utc();
+            let escalated = run_exclusively(
+                &job_lock,
+                "shift_escalations",
+                escalate_uncovered_shifts(
+                    &pool,
+                    &http_client,
+                    &residency_rules,
+                    now,
+                ),
+            )
+            .await;
+            if let Some(Err(e)) = escalated {
+                tracing::error!("Failed to escalate shifts: {:?}", e);
+            }
+        }
+    })
+}
+
+// A critical shift in a project that escalates
+struct CriticalShift {
+    shift_id: Uuid,
+    member_id: Uuid,
+    member_name: MemberName,
+    project_id: Uuid,
+    project_name: String,
+    owner: Email,
+    // The owner's
+    data_region: Option<DataRegion>,
+    chat_webhook_url: Option<ChatWebhookUrl>,
+    escalation_phone: Option<PhoneNumber>,
+}
+
+// Take every escalation step that has come due for the next occurrence of
+// each uncovered critical shift. Each step is only taken once per
+// occurrence; a step that fails is tried again on the next poll. Returns
+// how many steps were taken. Chat posts are only sent where the owner's
+// data region allows.
+#[tracing::instrument(name = "Escalating uncovered shifts", skip_all)]
+pub async fn escalate_uncovered_shifts(
+    pool: &PgPool,
+    http_client: &Client,
+    residency_rules: &ResidencyRules,
+    now: NaiveDateTime,
+) -> Result<usize> {
+    let rows = timed_query(
+        "escalate_uncovered_shifts.shifts",
+        sqlx::query!(
+            r#"
+                SELECT
+                    shifts.id,
+                    shifts.day,
+                    shifts.in_time,
+                    members.member_id,
+                    members.member_name,
+                    projects_list.project_id,
+                    projects_list.project_name,
+                    projects_list.escalation_steps,
+                    projects_list.chat_webhook_url,
+                    projects_list.escalation_phone,
+                    users.email,
+                    users.data_region
+                FROM shifts
+                INNER JOIN shift_tags ON shift_tags.shift_id = shifts.id
+                INNER JOIN members ON members.member_id = shifts.member_id
+                INNER JOIN projects_list
+                    ON projects_list.project_id = members.project_id
+                INNER JOIN users ON users.id = projects_list.user_id
+                WHERE shift_tags.tag = $1
+                AND projects_list.escalation_steps <> '[]'
+            "#,
+            CRITICAL_SHIFT_TAG
+        )
+        .fetch_all(pool),
+    )
+    .await
+    .wrap_err("failed to fetch critical shifts")?;
+
+    let mut taken = 0;
+    for row in rows {
+        let steps: Vec<EscalationStep> =
+            serde_json::from_value(row.escalation_steps)
+                .wrap_err("invalid escalation steps")?;
+        let Some(lead_time) = steps.iter().map(|step| step.lead_time()).max()
+        else {
+            continue;
+        };
+        let day = Day::try_from(row.day).wrap_err("invalid shift day")?;
+        let start_time =
+            Minute::parse(row.in_time).wrap_err("invalid shift start")?;
+        let Some(start) = upcoming_start(now, lead_time, day, &start_time)
+        else {
+            continue;
+        };
+        let due: Vec<&EscalationStep> = steps
+            .iter()
+            .filter(|step| step.is_due(now, start))
+            .collect();
+        if due.is_empty() {
+            continue;
+        }
+
+        let coverage = shift_coverage(pool, row.id, start.date()).await?;
+        if coverage == ShiftCoverage::Covered {
+            continue;
+        }
+
+        let shift = CriticalShift {
+            shift_id: row.id,
+            member_id: row.member_id,
+            member_name: MemberName::parse(row.member_name)
+                .wrap_err("invalid member name")?,
+            project_id: row.project_id,
+            project_name: row.project_name,
+            owner: Email::parse(Secret::new(row.email))
+                .wrap_err("invalid owner email")?,
+            data_region: row
+                .data_region
+                .as_deref()
+                .map(DataRegion::parse)
+                .transpose()
+                .wrap_err("invalid data region")?,
+            chat_webhook_url: row
+                .chat_webhook_url
+                .map(ChatWebhookUrl::parse)
+                .transpose()
+                .wrap_err("invalid chat webhook URL")?,
+            escalation_phone: row
+                .escalation_phone
+                .map(PhoneNumber::parse)
+                .transpose()
+                .wrap_err("invalid escalation phone")?,
+        };
+        let message = escalation_message(
+            coverage,
+            &shift.member_name,
+            &shift.project_name,
+            start,
+        );
+        for step in due {
+            match escalate(
+                pool,
+                http_client,
+                residency_rules,
+                &shift,
+                step.channel(),
+                coverage,
+                start.date(),
+                &message,
+            )
+            .await
+            {
+                Ok(true) => taken += 1,
+                Ok(false) => {}
+                Err(e) => tracing::error!(
+                    channel = step.channel().as_str(),
+                    "Failed to escalate shift: {:?}",
+                    e
+                ),
+            }
+        }
+    }
+
+    Ok(taken)
+}
+
+async fn shift_coverage(
+    pool: &PgPool,
+    shift_id: Uuid,
+    shift_date: NaiveDate,
+) -> Result<ShiftCoverage> {
+    let row = timed_query(
+        "escalate_uncovered_shifts.coverage",
+        sqlx::query!(
+            r#"
+                SELECT
+                    EXISTS (
+                        SELECT 1 FROM absences
+                        WHERE shift_id = $1 AND date = $2
+                    ) AS "absent!",
+                    EXISTS (
+                        SELECT 1 FROM absences
+                        WHERE shift_id = $1 AND date = $2
+                        AND standby_member_id IS NOT NULL
+                    ) AS "standby!",
+                    EXISTS (
+                        SELECT 1 FROM shift_reminders
+                        WHERE shift_id = $1 AND shift_date = $2
+                        AND confirmed_at IS NOT NULL
+                    ) AS "confirmed!"
+            "#,
+            shift_id,
+            shift_date
+        )
+        .fetch_one(pool),
+    )
+    .await
+    .wrap_err("failed to fetch shift coverage")?;
+
+    Ok(ShiftCoverage::new(row.absent, row.standby, row.confirmed))
+}
+
+// Take one escalation step, unless it has been taken already. Returns
+// whether it was taken.
+#[allow(clippy::too_many_arguments)]
+async fn escalate(
+    pool: &PgPool,
+    http_client: &Client,
+    residency_rules: &ResidencyRules,
+    shift: &CriticalShift,
+    channel: EscalationChannel,
+    coverage: ShiftCoverage,
+    shift_date: NaiveDate,
+    message: &str,
+) -> Result<bool> {
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let claimed = timed_query(
+        "escalate_uncovered_shifts.claim",
+        sqlx::query!(
+            r#"
+                INSERT INTO shift_escalations (shift_id, shift_date, channel)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING
+            "#,
+            shift.shift_id,
+            shift_date,
+            channel.as_str()
+        )
+        .execute(&mut *tx),
+    )
+    .await
+    .wrap_err("failed to record shift escalation")?;
+    if claimed.rows_affected() == 0 {
+        return Ok(false);
+    }
+
+    match channel {
+        EscalationChannel::Email => {
+            let subject =
+                format!("A critical {} shift needs cover", shift.project_name);
+            enqueue_email(&mut tx, &shift.owner, &subject, message).await?;
+        }
+        EscalationChannel::Sms => {
+            // Everyone on the team who takes texts, apart from a member who
+            // has said they can't make it
+            let recipients = timed_query(
+                "escalate_uncovered_shifts.team",
+                sqlx::query_scalar!(
+                    r#"
+                        SELECT phone_number AS "phone_number!"
+                        FROM members
+                        WHERE project_id = $1
+                        AND sms_opt_in AND phone_number IS NOT NULL
+                        AND NOT ($2 AND member_id = $3)
+                    "#,
+                    shift.project_id,
+                    coverage == ShiftCoverage::Open,
+                    shift.member_id
+                )
+                .fetch_all(&mut *tx),
+            )
+            .await
+            .wrap_err("failed to fetch team phone numbers")?;
+            for recipient in recipients {
+                let recipient = PhoneNumber::parse(recipient)
+                    .wrap_err("failed to parse member phone number")?;
+                enqueue_sms(&mut tx, &recipient, message).await?;
+            }
+        }
+        EscalationChannel::Chat => {
+            let Some(url) = &shift.chat_webhook_url else {
+                return Err(eyre!("project has no chat webhook URL"));
+            };
+            residency_rules
+                .check_webhook(shift.data_region, url)
+                .wrap_err("chat webhook blocked by data residency rules")?;
+            post_chat_message(http_client, url, shift.data_region, message)
+                .await?;
+        }
+        EscalationChannel::OwnerPhone => {
+            let Some(phone) = &shift.escalation_phone else {
+                return Err(eyre!("project has no escalation phone"));
+            };
+            enqueue_sms(&mut tx, phone, message).await?;
+        }
+    }
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(true)
+}
+
+// Post in the format Slack and most chat tools' incoming webhooks accept,
+// tagged with the owner's data region if they have one
+async fn post_chat_message(
+    http_client: &Client,
+    url: &ChatWebhookUrl,
+    data_region: Option<DataRegion>,
+    message: &str,
+) -> Result<()> {
+    let mut request = http_client.post(url.as_ref());
+    if let Some(region) = data_region {
+        request = request.header(DATA_REGION_HEADER, region.as_str());
+    }
+    request
+        .json(&json!({ "text": message }))
+        .send()
+        .await
+        .wrap_err("failed to post chat message")?
+        .error_for_status()
+        .wrap_err("chat webhook rejected the message")?;
+
+    Ok(())
+}
@@ -0,0 +1,305 @@
+use std::time::Duration;
+
+use chrono::{NaiveDateTime, TimeDelta};
+use color_eyre::eyre::{Result, WrapErr};
+use secrecy::Secret;
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{ClockType, JobLockStoreType},
+    domain::{
+        time::{at_minute, date_of_day},
+        Day, Email, Locale, Minute, PhoneNumber, ProjectId,
+    },
+    services::{
+        data_stores::{
+            enqueue_project_email, enqueue_project_email_with_reply_to,
+            enqueue_sms,
+        },
+        job_lock::run_exclusively,
+    },
+    utils::{email_replies::reply_address, metrics::timed_query},
+};
+
+// Text or email members who have opted in before each of their shifts.
+// Rotas have no timezone, so shift times are read as UTC. With an
+// `inbound_address`, reminder emails can be answered by replying.
+pub fn spawn_shift_reminders(
+    pool: PgPool,
+    job_lock: JobLockStoreType,
+    clock: ClockType,
+    poll_interval: Duration,
+    lead_time: Duration,
+    inbound_address: Option<String>,
+) -> JoinHandle<()> {
+    let lead_time =
+        TimeDelta::from_std(lead_time).expect("Lead time is out of range");
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let now = clock.now().naive_utc();
+            let sent = run_exclusively(
+                &job_lock,
+                "shift_reminders",
+                send_shift_reminders(
+                    &pool,
+                    now,
+                    lead_time,
+                    inbound_address.as_deref(),
+                ),
+            )
+            .await;
+            if let Some(Err(e)) = sent {
+                tracing::error!("Failed to send shift reminders: {:?}", e);
+            }
+        }
+    })
+}
+
+// Queue a reminder for every shift starting within `lead_time` of `now` that
+// hasn't had one yet. Returns how many shifts were reminded.
+#[tracing::instrument(name = "Sending shift reminders", skip_all)]
+pub async fn send_shift_reminders(
+    pool: &PgPool,
+    now: NaiveDateTime,
+    lead_time: TimeDelta,
+    inbound_address: Option<&str>,
+) -> Result<usize> {
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let shifts = timed_query(
+        "send_shift_reminders.shifts",
+        sqlx::query!(
+            r#"
+                SELECT
+                    shifts.id,
+                    shifts.day,
+                    shifts.in_time,
+                    members.phone_number,
+                    members.sms_opt_in,
+                    members.email,
+                    members.email_opt_in,
+                    projects_list.project_id,
+                    projects_list.project_name
+                FROM shifts
+                INNER JOIN members ON members.member_id = shifts.member_id
+                INNER JOIN projects_list
+                    ON projects_list.project_id = members.project_id
+                WHERE (members.sms_opt_in AND members.phone_number IS NOT NULL)
+                OR (members.email_opt_in AND members.email IS NOT NULL)
+            "#
+        )
+        .fetch_all(&mut *tx),
+    )
+    .await
+    .wrap_err("failed to fetch shifts")?;
+
+    let mut queued = 0;
+    for shift in shifts {
+        let day = Day::try_from(shift.day).wrap_err("invalid shift day")?;
+        let start_time =
+            Minute::parse(shift.in_time).wrap_err("invalid shift start")?;
+        let Some(start) = upcoming_start(now, lead_time, day, &start_time)
+        else {
+            continue;
+        };
+
+        let claimed = timed_query(
+            "send_shift_reminders.claim",
+            sqlx::query_scalar!(
+                r#"
+                    INSERT INTO shift_reminders (shift_id, shift_date)
+                    VALUES ($1, $2)
+                    ON CONFLICT DO NOTHING
+                    RETURNING reminder_id
+                "#,
+                shift.id,
+                start.date()
+            )
+            .fetch_optional(&mut *tx),
+        )
+        .await
+        .wrap_err("failed to record shift reminder")?;
+        let Some(reminder_id) = claimed else {
+            continue;
+        };
+
+        if let (true, Some(phone_number)) =
+            (shift.sms_opt_in, shift.phone_number)
+        {
+            let recipient = PhoneNumber::parse(phone_number)
+                .wrap_err("failed to parse member phone number")?;
+            let content = shift_reminder_message(&shift.project_name, start);
+            enqueue_sms(&mut tx, &recipient, &content).await?;
+        }
+
+        if let (true, Some(email)) = (shift.email_opt_in, shift.email) {
+            let recipient = Email::parse(Secret::new(email))
+                .wrap_err("failed to parse member email")?;
+            let project_id = ProjectId::new(shift.project_id);
+            let reply_to = inbound_address
+                .and_then(|address| reply_address(address, reminder_id));
+            let (subject, content) = shift_reminder_email(
+                &shift.project_name,
+                start,
+                reply_to.is_some(),
+            );
+            match reply_to {
+                Some(reply_to) => {
+                    enqueue_project_email_with_reply_to(
+                        &mut tx,
+                        &project_id,
+                        &recipient,
+                        &subject,
+                        &content,
+                        &reply_to,
+                    )
+                    .await?
+                }
+                None => {
+                    enqueue_project_email(
+                        &mut tx,
+                        &project_id,
+                        &recipient,
+                        &subject,
+                        &content,
+                    )
+                    .await?
+                }
+            }
+        }
+        queued += 1;
+    }
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(queued)
+}
+
+// When a weekly shift next starts, if that is after `now` and no more than
+// `lead_time` away
+pub fn upcoming_start(
+    now: NaiveDateTime,
+    lead_time: TimeDelta,
+    day: Day,
+    start_time: &Minute,
+) -> Option<NaiveDateTime> {
+    let start = at_minute(date_of_day(now.date(), day), start_time);
+    let start = if start <= now {
+        start + TimeDelta::weeks(1)
+    } else {
+        start
+    };
+    (start - now <= lead_time).then_some(start)
+}
+
+pub fn shift_reminder_message(
+    project_name: &str,
+    start: NaiveDateTime,
+) -> String {
+    format!(
+        "Reminder: you're on the {} rota on {}. Reply YES to confirm or SWAP \
+         to ask to swap.",
+        project_name,
+        Locale::default().format_date_time(start)
+    )
+}
+
+// The subject and body of a reminder email. Replying is only offered when
+// the reply can be read.
+pub fn shift_reminder_email(
+    project_name: &str,
+    start: NaiveDateTime,
+    replies: bool,
+) -> (String, String) {
+    let start = Locale::default().format_date_time(start);
+    let subject = format!("Reminder: {} shift on {}", project_name, start);
+    let content = match replies {
+        true => format!(
+            "You're on the {} rota on {}. Reply ACCEPT to confirm or DECLINE \
+             if you can't make it.",
+            project_name, start
+        ),
+        false => format!("You're on the {} rota on {}.", project_name, start),
+    };
+    (subject, content)
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::NaiveDate;
+
+    use super::*;
+
+    // A Tuesday
+    fn now() -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2025, 10, 21)
+            .unwrap()
+            .and_hms_opt(20, 0, 0)
+            .unwrap()
+    }
+
+    fn upcoming(day: Day, minute: i16) -> Option<NaiveDateTime> {
+        upcoming_start(
+            now(),
+            TimeDelta::hours(12),
+            day,
+            &Minute::parse(minute).unwrap(),
+        )
+    }
+
+    #[test]
+    fn test_upcoming_start_within_lead_time() {
+        let wednesday = NaiveDate::from_ymd_opt(2025, 10, 22).unwrap();
+
+        assert_eq!(
+            upcoming(Day::Tuesday, 1260),
+            Some(now() + TimeDelta::hours(1))
+        );
+        assert_eq!(
+            upcoming(Day::Wednesday, 480),
+            Some(wednesday.and_hms_opt(8, 0, 0).unwrap())
+        );
+        // Exactly at the edge of the lead time
+        assert_eq!(
+            upcoming(Day::Wednesday, 480),
+            Some(now() + TimeDelta::hours(12))
+        );
+    }
+
+    #[test]
+    fn test_upcoming_start_outside_lead_time() {
+        // Started already, so next week's is too far off
+        assert_eq!(upcoming(Day::Tuesday, 1200), None);
+        assert_eq!(upcoming(Day::Tuesday, 540), None);
+        assert_eq!(upcoming(Day::Wednesday, 481), None);
+        assert_eq!(upcoming(Day::Friday, 540), None);
+    }
+
+    #[test]
+    fn test_shift_reminder_message() {
+        assert_eq!(
+            shift_reminder_message("Craggy Island", now()),
+            "Reminder: you're on the Craggy Island rota on Tuesday 21/10/2025 \
+             at 20:00. Reply YES to confirm or SWAP to ask to swap."
+        );
+    }
+
+    #[test]
+    fn test_shift_reminder_email() {
+        let (subject, content) =
+            shift_reminder_email("Craggy Island", now(), true);
+        assert_eq!(
+            subject,
+            "Reminder: Craggy Island shift on Tuesday 21/10/2025 at 20:00"
+        );
+        assert!(content.ends_with(
+            "Reply ACCEPT to confirm or DECLINE if you can't make it."
+        ));
+
+        let (_, content) = shift_reminder_email("Craggy Island", now(), false);
+        assert!(!content.contains("ACCEPT"));
+    }
+}
@@ -0,0 +1,78 @@
+use std::time::Duration;
+
+use color_eyre::eyre::{Result, WrapErr};
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+
+use crate::{
+    app_state::{JobLockStoreType, SmsClientType},
+    domain::PhoneNumber,
+    services::{
+        data_stores::{fetch_pending_sms, mark_sms_sent},
+        job_lock::run_exclusively,
+    },
+    utils::constants::SMS_OUTBOX_BATCH_SIZE,
+};
+
+// Send a batch of queued text messages. Returns how many were sent. Like the
+// email relay, a failed send is logged and retried on the next poll.
+#[tracing::instrument(name = "Relaying SMS outbox", skip_all)]
+pub async fn relay_sms_outbox(
+    pool: &PgPool,
+    sms_client: &SmsClientType,
+) -> Result<usize> {
+    let mut tx = pool.begin().await.wrap_err("failed to begin transaction")?;
+
+    let pending = fetch_pending_sms(&mut tx, SMS_OUTBOX_BATCH_SIZE).await?;
+    let mut sent = 0;
+    for sms in pending {
+        let recipient = match PhoneNumber::parse(sms.recipient) {
+            Ok(recipient) => recipient,
+            // A number we can't parse will never become valid, so don't
+            // retry it
+            Err(e) => {
+                tracing::error!(
+                    "Discarding SMS to unreadable recipient: {:?}",
+                    e
+                );
+                mark_sms_sent(&mut tx, sms.id).await?;
+                continue;
+            }
+        };
+
+        match sms_client.send_sms(&recipient, &sms.content).await {
+            Ok(()) => {
+                mark_sms_sent(&mut tx, sms.id).await?;
+                sent += 1;
+            }
+            Err(e) => tracing::error!("Failed to send SMS: {:?}", e),
+        }
+    }
+
+    tx.commit().await.wrap_err("failed to commit transaction")?;
+
+    Ok(sent)
+}
+
+pub fn spawn_sms_outbox_relay(
+    pool: PgPool,
+    sms_client: SmsClientType,
+    job_lock: JobLockStoreType,
+    poll_interval: Duration,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(poll_interval);
+        loop {
+            interval.tick().await;
+            let relayed = run_exclusively(
+                &job_lock,
+                "sms_outbox_relay",
+                relay_sms_outbox(&pool, &sms_client),
+            )
+            .await;
+            if let Some(Err(e)) = relayed {
+                tracing::error!("Failed to relay SMS outbox: {:?}", e);
+            }
+        }
+    })
+}
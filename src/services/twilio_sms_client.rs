@@ -0,0 +1,149 @@
+use color_eyre::eyre::Result;
+use reqwest::{Client, Url};
+use secrecy::{ExposeSecret, Secret};
+
+use crate::domain::{PhoneNumber, SmsClient};
+
+pub struct TwilioSmsClient {
+    http_client: Client,
+    base_url: String,
+    account_sid: String,
+    auth_token: Secret<String>,
+    sender: PhoneNumber,
+}
+
+impl TwilioSmsClient {
+    pub fn new(
+        base_url: String,
+        account_sid: String,
+        auth_token: Secret<String>,
+        sender: PhoneNumber,
+        http_client: Client,
+    ) -> Self {
+        Self {
+            http_client,
+            base_url,
+            account_sid,
+            auth_token,
+            sender,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SmsClient for TwilioSmsClient {
+    #[tracing::instrument(name = "Sending SMS", skip_all)]
+    async fn send_sms(
+        &self,
+        recipient: &PhoneNumber,
+        content: &str,
+    ) -> Result<()> {
+        let base = Url::parse(&self.base_url)?;
+        let url = base.join(&format!(
+            "/2010-04-01/Accounts/{}/Messages.json",
+            self.account_sid
+        ))?;
+
+        let request_body = SendSmsRequest {
+            from: self.sender.as_ref(),
+            to: recipient.as_ref(),
+            body: content,
+        };
+
+        self.http_client
+            .post(url)
+            .basic_auth(
+                &self.account_sid,
+                Some(self.auth_token.expose_secret()),
+            )
+            .form(&request_body)
+            .send()
+            .await?
+            .error_for_status()?;
+
+        Ok(())
+    }
+}
+
+// Twilio takes a form-encoded body. For more information, see the API docs:
+// https://www.twilio.com/docs/messaging/api/message-resource#create-a-message-resource
+#[derive(serde::Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct SendSmsRequest<'a> {
+    from: &'a str,
+    to: &'a str,
+    body: &'a str,
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::constants::test;
+
+    use super::*;
+    use fake::{Fake, Faker};
+    use wiremock::matchers::{
+        any, body_string_contains, header_exists, method, path,
+    };
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ACCOUNT_SID: &str = "AC0123456789";
+
+    fn phone_number() -> PhoneNumber {
+        PhoneNumber::parse("+447700900123".to_owned()).unwrap()
+    }
+
+    fn sms_client(base_url: String) -> TwilioSmsClient {
+        let http_client = Client::builder()
+            .timeout(test::sms_client::TIMEOUT)
+            .build()
+            .unwrap();
+        TwilioSmsClient::new(
+            base_url,
+            ACCOUNT_SID.to_owned(),
+            Secret::new(Faker.fake()),
+            phone_number(),
+            http_client,
+        )
+    }
+
+    #[tokio::test]
+    async fn send_sms_sends_the_expected_request() {
+        let mock_server = MockServer::start().await;
+        let sms_client = sms_client(mock_server.uri());
+
+        Mock::given(header_exists("Authorization"))
+            .and(path(format!(
+                "/2010-04-01/Accounts/{}/Messages.json",
+                ACCOUNT_SID
+            )))
+            .and(method("POST"))
+            .and(body_string_contains("To=%2B447700900123"))
+            .and(body_string_contains("Body=Shift+reminder"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let outcome =
+            sms_client.send_sms(&phone_number(), "Shift reminder").await;
+
+        assert!(outcome.is_ok());
+    }
+
+    #[tokio::test]
+    async fn send_sms_fails_if_the_server_returns_500() {
+        let mock_server = MockServer::start().await;
+        let sms_client = sms_client(mock_server.uri());
+
+        Mock::given(any())
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&mock_server)
+            .await;
+
+        let outcome =
+            sms_client.send_sms(&phone_number(), "Shift reminder").await;
+
+        assert!(outcome.is_err());
+    }
+}
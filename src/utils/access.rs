@@ -0,0 +1,256 @@
+use axum::{
+    extract::{MatchedPath, Request, State},
+    http::{Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+
+use crate::{
+    domain::{AuthAPIError, ProjectAPIError},
+    utils::{admin::check_admin_key, auth::get_claims},
+    AppState,
+};
+
+// Who is calling, as far as authorization is concerned
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Role {
+    // No credentials
+    Anonymous,
+    // Signed in with a session cookie
+    User,
+    // Holding the admin key
+    Admin,
+}
+
+pub const EVERYONE: &[Role] = &[Role::Anonymous, Role::User, Role::Admin];
+pub const USERS: &[Role] = &[Role::User];
+pub const ADMINS: &[Role] = &[Role::Admin];
+
+pub struct RouteAccess {
+    pub method: Method,
+    pub path: &'static str,
+    pub roles: &'static [Role],
+}
+
+impl RouteAccess {
+    const fn new(
+        method: Method,
+        path: &'static str,
+        roles: &'static [Role],
+    ) -> Self {
+        Self {
+            method,
+            path,
+            roles,
+        }
+    }
+}
+
+// Which roles may call each route. Every route in the router needs an entry
+// here, or the guard refuses to serve it. Routes open to everyone may still
+// check a token of their own, such as a 2FA code or a bootstrap token. Those
+// taking a key or signature in place of a session turn callers away with
+// credential errors, not the session's token errors.
+pub const ROUTE_ACCESS: &[RouteAccess] = &[
+    RouteAccess::new(Method::POST, "/auth/signup", EVERYONE),
+    RouteAccess::new(Method::POST, "/auth/login", EVERYONE),
+    RouteAccess::new(Method::POST, "/auth/verify-2fa", EVERYONE),
+    RouteAccess::new(Method::PUT, "/auth/2fa", USERS),
+    RouteAccess::new(Method::POST, "/auth/2fa/setup", USERS),
+    RouteAccess::new(Method::POST, "/auth/logout", USERS),
+    RouteAccess::new(Method::POST, "/auth/verify-token", EVERYONE),
+    RouteAccess::new(Method::DELETE, "/auth/delete-user", USERS),
+    RouteAccess::new(Method::PUT, "/auth/email", USERS),
+    RouteAccess::new(Method::POST, "/auth/change-email", USERS),
+    RouteAccess::new(Method::POST, "/auth/confirm-email-change", EVERYONE),
+    // The reset token stands in for a session
+    RouteAccess::new(Method::POST, "/auth/forgot-password", EVERYONE),
+    RouteAccess::new(Method::POST, "/auth/reset-password", EVERYONE),
+    // As is the magic link's signed token
+    RouteAccess::new(Method::POST, "/auth/magic-link", EVERYONE),
+    RouteAccess::new(Method::GET, "/auth/magic-link/verify", EVERYONE),
+    RouteAccess::new(Method::PUT, "/auth/security-emails", USERS),
+    RouteAccess::new(Method::PUT, "/auth/email-tracking", USERS),
+    RouteAccess::new(Method::POST, "/auth/accept-policy", USERS),
+    RouteAccess::new(Method::PUT, "/auth/locale", USERS),
+    RouteAccess::new(Method::PUT, "/auth/profile", USERS),
+    RouteAccess::new(Method::GET, "/auth/quota", USERS),
+    RouteAccess::new(Method::GET, "/auth/sessions", USERS),
+    RouteAccess::new(Method::DELETE, "/auth/sessions/:id", USERS),
+    RouteAccess::new(Method::GET, "/policies", EVERYONE),
+    RouteAccess::new(Method::GET, "/me/calendar.ics", USERS),
+    RouteAccess::new(Method::GET, "/me/email", USERS),
+    RouteAccess::new(Method::GET, "/me/approvals", USERS),
+    RouteAccess::new(Method::POST, "/me/approvals/review", USERS),
+    RouteAccess::new(Method::POST, "/me/approvals/bulk-review", USERS),
+    RouteAccess::new(Method::GET, "/notifications", USERS),
+    RouteAccess::new(Method::POST, "/notifications/mark-read", USERS),
+    RouteAccess::new(Method::GET, "/notifications/poll", USERS),
+    RouteAccess::new(Method::GET, "/integrations/keys", USERS),
+    RouteAccess::new(Method::POST, "/integrations/keys", USERS),
+    RouteAccess::new(Method::DELETE, "/integrations/keys/:id", USERS),
+    // The integration key stands in for a session
+    RouteAccess::new(Method::GET, "/integrations/updates", EVERYONE),
+    RouteAccess::new(Method::POST, "/projects/new", USERS),
+    RouteAccess::new(Method::GET, "/projects/list", USERS),
+    RouteAccess::new(Method::GET, "/projects/departments", USERS),
+    RouteAccess::new(Method::POST, "/projects/departments", USERS),
+    RouteAccess::new(Method::POST, "/projects/add-member", USERS),
+    RouteAccess::new(Method::GET, "/projects/get-members", USERS),
+    RouteAccess::new(Method::GET, "/projects/get-member", USERS),
+    RouteAccess::new(Method::PUT, "/projects/update-member", USERS),
+    RouteAccess::new(Method::GET, "/projects/member-contact", USERS),
+    RouteAccess::new(Method::PUT, "/projects/member-contact", USERS),
+    RouteAccess::new(Method::POST, "/projects/shifts", USERS),
+    RouteAccess::new(Method::POST, "/projects/shifts/validate", USERS),
+    RouteAccess::new(Method::POST, "/projects/shifts/clear", USERS),
+    RouteAccess::new(Method::GET, "/projects/slots", USERS),
+    RouteAccess::new(Method::POST, "/projects/slots", USERS),
+    RouteAccess::new(Method::POST, "/projects/slots/fill", USERS),
+    RouteAccess::new(Method::GET, "/projects/slots/borrow-suggestions", USERS),
+    RouteAccess::new(Method::GET, "/projects/templates", USERS),
+    RouteAccess::new(Method::POST, "/projects/templates", USERS),
+    RouteAccess::new(Method::POST, "/projects/templates/apply", USERS),
+    RouteAccess::new(Method::POST, "/projects/import", USERS),
+    RouteAccess::new(Method::POST, "/projects/rota/publish", USERS),
+    RouteAccess::new(Method::POST, "/projects/rota/submit", USERS),
+    RouteAccess::new(Method::GET, "/projects/rota/approvals", USERS),
+    RouteAccess::new(Method::GET, "/projects/rota/diff", USERS),
+    RouteAccess::new(Method::GET, "/projects/rota/reactions", USERS),
+    RouteAccess::new(Method::POST, "/projects/absences", USERS),
+    RouteAccess::new(Method::GET, "/projects/comments", USERS),
+    RouteAccess::new(Method::POST, "/projects/comments", USERS),
+    RouteAccess::new(Method::DELETE, "/projects/comments", USERS),
+    RouteAccess::new(Method::POST, "/projects/open-shifts/broadcast", USERS),
+    RouteAccess::new(Method::GET, "/projects/attendance-report", USERS),
+    RouteAccess::new(Method::GET, "/projects/print", USERS),
+    RouteAccess::new(Method::GET, "/projects/project", USERS),
+    RouteAccess::new(Method::GET, "/projects/batch", USERS),
+    RouteAccess::new(Method::GET, "/projects/summary", USERS),
+    RouteAccess::new(Method::GET, "/projects/history", USERS),
+    RouteAccess::new(Method::GET, "/projects/emails", USERS),
+    RouteAccess::new(Method::GET, "/projects/settings", USERS),
+    RouteAccess::new(Method::PUT, "/projects/settings", USERS),
+    // Checked against Twilio's signature instead
+    RouteAccess::new(Method::POST, "/sms/inbound", EVERYONE),
+    // Loaded by mail clients; the token names and signs the delivery
+    RouteAccess::new(Method::GET, "/email/open", EVERYONE),
+    // Checked against POSTMARK_WEBHOOK_TOKEN instead
+    RouteAccess::new(Method::POST, "/email/webhook", EVERYONE),
+    // Also checked against POSTMARK_WEBHOOK_TOKEN; the reply-to token names
+    // and signs the reminder
+    RouteAccess::new(Method::POST, "/email/inbound", EVERYONE),
+    RouteAccess::new(Method::GET, "/api-docs/schemas", EVERYONE),
+    RouteAccess::new(Method::GET, "/metrics", ADMINS),
+    RouteAccess::new(Method::GET, "/version", EVERYONE),
+    RouteAccess::new(Method::GET, "/admin/deprecated-usage", ADMINS),
+    RouteAccess::new(Method::POST, "/admin/invitations", ADMINS),
+    RouteAccess::new(Method::POST, "/admin/bootstrap", EVERYONE),
+    RouteAccess::new(Method::GET, "/admin/log-filter", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/log-filter", ADMINS),
+    RouteAccess::new(Method::GET, "/admin/request-capture", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/request-capture", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/data-region", ADMINS),
+    RouteAccess::new(Method::GET, "/admin/legal-holds", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/legal-holds", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/provisioning/users/:id", ADMINS),
+    RouteAccess::new(Method::PUT, "/admin/provisioning/api-keys/:id", ADMINS),
+];
+
+pub fn find_route_access(
+    method: &Method,
+    path: &str,
+) -> Option<&'static RouteAccess> {
+    // axum answers HEAD with the GET handler
+    let method = match *method {
+        Method::HEAD => &Method::GET,
+        ref method => method,
+    };
+    ROUTE_ACCESS
+        .iter()
+        .find(|route| route.path == path && route.method == *method)
+}
+
+// Turn away callers whose role may not use the route, before its handler
+// runs. A session's claims are left in the request's extensions for the
+// handler, so it needn't validate the token again.
+pub async fn enforce_route_access(
+    State(state): State<AppState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    // Unmatched requests fall through to the 404 fallback
+    let path = match request.extensions().get::<MatchedPath>() {
+        Some(path) => path.as_str().to_owned(),
+        None => return next.run(request).await,
+    };
+    let route = match find_route_access(request.method(), &path) {
+        Some(route) => route,
+        None if ROUTE_ACCESS.iter().any(|route| route.path == path) => {
+            return StatusCode::METHOD_NOT_ALLOWED.into_response();
+        }
+        None => {
+            tracing::error!("No access rule for route {}", path);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    if route.roles.contains(&Role::Anonymous) {
+        return next.run(request).await;
+    }
+
+    let mut denied = AuthAPIError::MissingToken;
+    if route.roles.contains(&Role::Admin) {
+        match check_admin_key(request.headers()) {
+            Ok(()) => return next.run(request).await,
+            Err(e) => denied = e,
+        }
+    }
+    if route.roles.contains(&Role::User) {
+        let jar = CookieJar::from_headers(request.headers());
+        match get_claims(&jar, &state.banned_token_store, &state.clock).await {
+            Ok(claims) => {
+                request.extensions_mut().insert(claims);
+                return next.run(request).await;
+            }
+            Err(e) => denied = e,
+        }
+    }
+
+    // Answer as the route's own handler would have
+    match path.starts_with("/auth/")
+        || path.starts_with("/admin/")
+        || path == "/metrics"
+    {
+        true => denied.into_response(),
+        false => ProjectAPIError::AuthenticationError(denied).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_routes_are_listed_once() {
+        for (i, route) in ROUTE_ACCESS.iter().enumerate() {
+            assert!(
+                !ROUTE_ACCESS[i + 1..].iter().any(|other| {
+                    other.method == route.method && other.path == route.path
+                }),
+                "{} {} is listed twice",
+                route.method,
+                route.path
+            );
+            assert!(!route.roles.is_empty());
+        }
+    }
+
+    #[test]
+    fn test_find_route_access() {
+        let route = find_route_access(&Method::HEAD, "/projects/list")
+            .expect("HEAD should match GET");
+        assert_eq!(route.roles, USERS);
+        assert!(find_route_access(&Method::POST, "/projects/list").is_none());
+    }
+}
@@ -0,0 +1,54 @@
+use axum::http::HeaderMap;
+use secrecy::ExposeSecret;
+
+use crate::{
+    utils::constants::{ADMIN_API_KEY, ADMIN_KEY_HEADER},
+    AuthAPIError,
+};
+
+// Admin endpoints authenticate with a shared key rather than a user session
+#[tracing::instrument(name = "Checking admin key", skip_all)]
+pub fn check_admin_key(headers: &HeaderMap) -> Result<(), AuthAPIError> {
+    let expected = ADMIN_API_KEY.as_ref().ok_or(AuthAPIError::InvalidToken)?;
+
+    let provided = headers
+        .get(ADMIN_KEY_HEADER)
+        .ok_or(AuthAPIError::MissingToken)?
+        .to_str()
+        .map_err(|_| AuthAPIError::InvalidToken)?;
+
+    match constant_time_eq(
+        provided.as_bytes(),
+        expected.expose_secret().as_bytes(),
+    ) {
+        true => Ok(()),
+        false => Err(AuthAPIError::InvalidToken),
+    }
+}
+
+// Compare every byte so response timing doesn't reveal the matching prefix
+pub fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret!"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn test_missing_admin_key_header() {
+        let result = check_admin_key(&HeaderMap::new());
+        assert!(result.is_err());
+    }
+}
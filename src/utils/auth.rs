@@ -1,28 +1,167 @@
+use std::{convert::Infallible, net::SocketAddr, sync::LazyLock};
+
+use axum::{
+    extract::{ConnectInfo, FromRequestParts},
+    http::{header::USER_AGENT, request::Parts},
+};
 use axum_extra::extract::{
     cookie::{Cookie, SameSite},
     CookieJar,
 };
-use chrono::Utc;
+use chrono::{DateTime, TimeDelta, Utc};
 use color_eyre::eyre::{eyre, Context, ContextCompat, Result};
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Validation};
 use secrecy::{ExposeSecret, Secret};
 use serde::{Deserialize, Serialize};
 
 use crate::{
-    app_state::BannedTokenStoreType,
-    domain::{BannedTokenStoreError, Email, UserId},
-    AuthAPIError,
+    app_state::{BannedTokenStoreType, ClockType, SessionStoreType},
+    domain::{
+        BannedTokenStoreError, Email, PasswordReset, PasswordResetToken,
+        Session, SessionId, SessionStoreError, UserId,
+    },
+    AppState, AuthAPIError,
 };
 
 use super::constants::{JWT_COOKIE_NAME, JWT_SECRET};
 
-// Create cookie with a new JWT auth token
+// Derived from JWT_SECRET once rather than for every token, and forced
+// while warming up so the first sign-in doesn't pay for it
+pub static JWT_ENCODING_KEY: LazyLock<EncodingKey> = LazyLock::new(|| {
+    EncodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes())
+});
+pub static JWT_DECODING_KEY: LazyLock<DecodingKey> = LazyLock::new(|| {
+    DecodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes())
+});
+
+// Longer User-Agents are cut short before being kept with a session
+const MAX_DEVICE_LENGTH: usize = 256;
+
+// Where a sign-in came from, as far as its request says
+#[derive(Debug, Default)]
+pub struct SessionClient {
+    pub device: Option<String>,
+    pub ip: Option<String>,
+}
+
+#[async_trait::async_trait]
+impl FromRequestParts<AppState> for SessionClient {
+    type Rejection = Infallible;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        state: &AppState,
+    ) -> Result<Self, Self::Rejection> {
+        let header = |name: &str| {
+            parts
+                .headers
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+        };
+        let device = header(USER_AGENT.as_str())
+            .map(|device| device.chars().take(MAX_DEVICE_LENGTH).collect());
+        // Behind a proxy the peer is the proxy, and the client is the address
+        // it added to the header last. Earlier ones came from the client, so
+        // could be anything, as could the header when there's no proxy.
+        let forwarded = header("x-forwarded-for")
+            .filter(|_| state.settings.trust_proxy_headers)
+            .and_then(|addresses| addresses.rsplit(',').next())
+            .map(|address| address.trim().to_owned())
+            .filter(|address| !address.is_empty());
+        let peer = parts
+            .extensions
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(address)| address.ip().to_string());
+
+        Ok(Self {
+            device,
+            ip: forwarded.or(peer),
+        })
+    }
+}
+
+// Start a session for a user who has just signed in, and create the cookie
+// that carries it
+#[tracing::instrument(name = "Starting session", skip_all)]
+pub async fn start_session(
+    email: &Email,
+    user_id: &UserId,
+    client: SessionClient,
+    session_store: &SessionStoreType,
+    clock: &ClockType,
+) -> Result<Cookie<'static>> {
+    let delta = chrono::Duration::try_seconds(TOKEN_TTL_SECONDS)
+        .wrap_err("Failed to create 10 minute time delta")?;
+    let issued_at = clock.now();
+    let session = Session {
+        session_id: SessionId::default(),
+        user_id: user_id.clone(),
+        device: client.device,
+        ip: client.ip,
+        issued_at,
+        expires_at: issued_at
+            .checked_add_signed(delta)
+            .ok_or(eyre!("failed to add to current time"))?,
+    };
+
+    session_store
+        .write()
+        .await
+        .add_session(&session)
+        .await
+        .wrap_err("failed to store session")?;
+
+    generate_auth_cookie(email, &session)
+}
+
+// Sign out every one of a user's sessions, banning each for as long as its
+// tokens would still be accepted
+#[tracing::instrument(name = "Ending user's sessions", skip_all)]
+pub async fn end_sessions(
+    user_id: &UserId,
+    session_store: &SessionStoreType,
+    banned_token_store: &BannedTokenStoreType,
+    clock: &ClockType,
+) -> Result<()> {
+    let now = clock.now();
+    let sessions = session_store
+        .read()
+        .await
+        .get_sessions(user_id, now)
+        .await
+        .wrap_err("failed to get sessions")?;
+
+    for session in sessions {
+        banned_token_store
+            .write()
+            .await
+            .ban_session(&session.session_id, session_accepted_until(now), now)
+            .await?;
+
+        match session_store
+            .write()
+            .await
+            .remove_session(user_id, &session.session_id)
+            .await
+        {
+            // Already signed out elsewhere
+            Ok(()) | Err(SessionStoreError::SessionNotFound) => (),
+            Err(e) => return Err(eyre!(e)),
+        }
+    }
+
+    Ok(())
+}
+
+// Create cookie with a new JWT auth token for the session
 #[tracing::instrument(name = "Generating auth cookie", skip_all)]
 pub fn generate_auth_cookie(
     email: &Email,
-    user_id: &UserId,
+    session: &Session,
 ) -> Result<Cookie<'static>> {
-    let token = generate_auth_token(email, user_id)?;
+    let token = generate_auth_token(email, session)?;
     Ok(create_auth_cookie(token))
 }
 
@@ -39,23 +178,20 @@ fn create_auth_cookie(token: Secret<String>) -> Cookie<'static> {
     cookie
 }
 
-// This value determines how long the JWT auth token is valid for
+// This value determines how long the JWT auth token, and so its session, is
+// valid for
 pub const TOKEN_TTL_SECONDS: i64 = 600; // 10 minutes
 
-// Create JWT auth token
+// Tokens are given this long after they expire, for clocks that disagree
+pub const TOKEN_LEEWAY_SECONDS: i64 = 60;
+
+// Create JWT auth token, expiring with the session
 #[tracing::instrument(name = "Generating auth token", skip_all)]
 fn generate_auth_token(
     email: &Email,
-    user_id: &UserId,
+    session: &Session,
 ) -> Result<Secret<String>> {
-    let delta = chrono::Duration::try_seconds(TOKEN_TTL_SECONDS)
-        .wrap_err("Failed to create 10 minute time delta")?;
-
-    // Create JWT expiration time
-    let exp = Utc::now()
-        .checked_add_signed(delta)
-        .ok_or(eyre!("failed to add to current time"))?
-        .timestamp();
+    let exp = session.expires_at.timestamp();
 
     // Cast exp to a usize, which is what Claims expects
     let exp: usize = exp.try_into().wrap_err(format!(
@@ -64,9 +200,10 @@ fn generate_auth_token(
     ))?;
 
     let sub = email.as_ref().expose_secret().to_owned();
-    let id = user_id.clone();
+    let id = session.user_id.clone();
+    let sid = session.session_id.clone();
 
-    let claims = Claims { sub, exp, id };
+    let claims = Claims { sub, exp, id, sid };
 
     create_token(&claims)
 }
@@ -76,36 +213,54 @@ fn generate_auth_token(
 pub async fn validate_token(
     token: &Secret<String>,
     banned_token_store: BannedTokenStoreType,
+    clock: &ClockType,
 ) -> Result<Claims, AuthAPIError> {
+    let claims = decode_claims(token, clock)?;
+
+    // The session may have been revoked from another device
     banned_token_store
         .read()
         .await
-        .check_token(token)
+        .check_token_and_session(token, &claims.sid)
         .await
         .map_err(|e| match e {
             BannedTokenStoreError::BannedToken => AuthAPIError::InvalidToken,
             e => AuthAPIError::UnexpectedError(eyre!(e)),
         })?;
 
-    decode::<Claims>(
-        token.expose_secret(),
-        &DecodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
-        &Validation::default(),
-    )
-    .map(|data| data.claims)
-    .map_err(|_| AuthAPIError::InvalidToken)
-    // .wrap_err("failed to decode token")
+    Ok(claims)
+}
+
+// The claims of a token that hasn't expired, without checking it hasn't
+// been banned. Only for deciding how to handle a request, never for letting
+// it through.
+pub fn decode_claims(
+    token: &Secret<String>,
+    clock: &ClockType,
+) -> Result<Claims, AuthAPIError> {
+    // Expiry is checked against the app's clock rather than the system's
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+
+    let claims =
+        decode::<Claims>(token.expose_secret(), &JWT_DECODING_KEY, &validation)
+            .map(|data| data.claims)
+            .map_err(|_| AuthAPIError::InvalidToken)?;
+
+    let now = clock.now().timestamp();
+    if (claims.exp as i64) < now - TOKEN_LEEWAY_SECONDS {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    Ok(claims)
 }
 
 // Create JWT auth token by encoding claims using the JWT secret
 #[tracing::instrument(name = "Creating auth token", skip_all)]
-fn create_token(claims: &Claims) -> Result<Secret<String>> {
-    let token_string = encode(
-        &jsonwebtoken::Header::default(),
-        &claims,
-        &EncodingKey::from_secret(JWT_SECRET.expose_secret().as_bytes()),
-    )
-    .map_err(|e| eyre!(AuthAPIError::UnexpectedError(e.into())))?;
+fn create_token<T: Serialize>(claims: &T) -> Result<Secret<String>> {
+    let token_string =
+        encode(&jsonwebtoken::Header::default(), &claims, &JWT_ENCODING_KEY)
+            .map_err(|e| eyre!(AuthAPIError::UnexpectedError(e.into())))?;
 
     Ok(Secret::new(token_string))
 }
@@ -115,6 +270,7 @@ fn create_token(claims: &Claims) -> Result<Secret<String>> {
 pub async fn get_claims(
     jar: &CookieJar,
     banned_token_store: &BannedTokenStoreType,
+    clock: &ClockType,
 ) -> Result<Claims, AuthAPIError> {
     let cookie = match jar.get(JWT_COOKIE_NAME) {
         Some(cookie) => cookie,
@@ -122,34 +278,215 @@ pub async fn get_claims(
     };
 
     let token = Secret::new(cookie.value().to_string());
-    validate_token(&token, banned_token_store.clone()).await
+    validate_token(&token, banned_token_store.clone(), clock).await
+}
+
+// The claims of the session the access guard let the request in with.
+// Only routes open to users alone have them; anywhere else it's a mistake to
+// ask for them.
+#[async_trait::async_trait]
+impl<S> FromRequestParts<S> for Claims
+where
+    S: Send + Sync,
+{
+    type Rejection = AuthAPIError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<Claims>().cloned().ok_or_else(|| {
+            AuthAPIError::UnexpectedError(eyre!(
+                "no session claims for {}; is the route only open to users?",
+                parts.uri.path()
+            ))
+        })
+    }
+}
+
+// This value determines how long a password reset link can be used for
+pub const PASSWORD_RESET_TTL_SECONDS: i64 = 3600; // 1 hour
+
+// Create a token to email a user who has forgotten their password
+#[tracing::instrument(name = "Generating password reset token", skip_all)]
+pub fn generate_password_reset_token(
+    user_id: &UserId,
+    clock: &ClockType,
+) -> Result<(PasswordResetToken, PasswordReset)> {
+    let delta = chrono::Duration::try_seconds(PASSWORD_RESET_TTL_SECONDS)
+        .wrap_err("Failed to create 1 hour time delta")?;
+    let expires_at = clock
+        .now()
+        .checked_add_signed(delta)
+        .ok_or(eyre!("failed to add to current time"))?;
+
+    let reset = PasswordReset {
+        user_id: user_id.clone(),
+        expires_at,
+    };
+    Ok((PasswordResetToken::default(), reset))
+}
+
+// Check a password reset can still be used
+pub fn validate_password_reset(
+    reset: &PasswordReset,
+    clock: &ClockType,
+) -> Result<(), AuthAPIError> {
+    if reset.expires_at <= clock.now() {
+        return Err(AuthAPIError::InvalidToken);
+    }
+    Ok(())
+}
+
+// How long a magic link can be used for, and so how long a used link stays
+// banned
+pub const MAGIC_LINK_TTL_SECONDS: i64 = 600; // 10 minutes
+
+// Create a signed token for a link that signs the user in without their
+// password
+#[tracing::instrument(name = "Generating magic link token", skip_all)]
+pub fn generate_magic_link_token(
+    email: &Email,
+    user_id: &UserId,
+    clock: &ClockType,
+) -> Result<Secret<String>> {
+    let delta = chrono::Duration::try_seconds(MAGIC_LINK_TTL_SECONDS)
+        .wrap_err("Failed to create 10 minute time delta")?;
+    let exp = clock
+        .now()
+        .checked_add_signed(delta)
+        .ok_or(eyre!("failed to add to current time"))?
+        .timestamp();
+    let exp: usize = exp.try_into().wrap_err(format!(
+        "failed to cast exp time to usize. exp time: {}",
+        exp
+    ))?;
+
+    create_token(&MagicLinkClaims {
+        sub: email.as_ref().expose_secret().to_owned(),
+        exp,
+        id: user_id.clone(),
+        jti: uuid::Uuid::new_v4().to_string(),
+        purpose: MAGIC_LINK_PURPOSE.to_owned(),
+    })
+}
+
+// Check a magic link's token and use it up, so the link only works once.
+// Unlike session tokens, links get no grace once they've expired.
+#[tracing::instrument(name = "Using magic link token", skip_all)]
+pub async fn use_magic_link_token(
+    token: &Secret<String>,
+    banned_token_store: &BannedTokenStoreType,
+    clock: &ClockType,
+) -> Result<MagicLinkClaims, AuthAPIError> {
+    let mut validation = Validation::default();
+    validation.validate_exp = false;
+    let claims = decode::<MagicLinkClaims>(
+        token.expose_secret(),
+        &JWT_DECODING_KEY,
+        &validation,
+    )
+    .map(|data| data.claims)
+    .map_err(|_| AuthAPIError::InvalidToken)?;
+    if claims.purpose != MAGIC_LINK_PURPOSE
+        || (claims.exp as i64) <= clock.now().timestamp()
+    {
+        return Err(AuthAPIError::InvalidToken);
+    }
+
+    // Held across the check and the ban, so a link clicked twice at once
+    // only signs in once
+    let mut banned_token_store = banned_token_store.write().await;
+    banned_token_store
+        .check_token(token)
+        .await
+        .map_err(|e| match e {
+            BannedTokenStoreError::BannedToken => AuthAPIError::InvalidToken,
+            e => AuthAPIError::UnexpectedError(eyre!(e)),
+        })?;
+    // Links get no grace, so it only needs banning until it expires
+    let expires_at = DateTime::from_timestamp(claims.exp as i64, 0)
+        .ok_or(AuthAPIError::InvalidToken)?;
+    banned_token_store
+        .add_token(token, expires_at, clock.now())
+        .await
+        .map_err(AuthAPIError::UnexpectedError)?;
+
+    Ok(claims)
 }
 
+const MAGIC_LINK_PURPOSE: &str = "magic-link";
+
 #[derive(Debug, Serialize, Deserialize)]
+pub struct MagicLinkClaims {
+    pub sub: String,
+    pub exp: usize,
+    pub id: UserId,
+    // Tells links sent in the same second apart
+    pub jti: String,
+    // Stops the token being mistaken for any other signed with JWT_SECRET
+    pub purpose: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Claims {
     pub sub: String,
     pub exp: usize,
     pub id: UserId,
+    pub sid: SessionId,
+}
+
+impl Claims {
+    // When the token stops being accepted, grace included, and so how long
+    // it needs banning for
+    pub fn accepted_until(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp(self.exp as i64 + TOKEN_LEEWAY_SECONDS, 0)
+            .unwrap_or(DateTime::<Utc>::MAX_UTC)
+    }
+}
+
+// When the last token issued for a session by `now` stops being accepted,
+// and so how long the session needs banning for
+pub fn session_accepted_until(now: DateTime<Utc>) -> DateTime<Utc> {
+    now + TimeDelta::seconds(TOKEN_TTL_SECONDS + TOKEN_LEEWAY_SECONDS)
 }
 
 #[cfg(test)]
 mod tests {
     use crate::{
         domain::BannedTokenStore,
-        services::data_stores::HashsetBannedTokenStore,
+        services::{
+            clock::ManualClock,
+            data_stores::{HashmapSessionStore, HashsetBannedTokenStore},
+        },
     };
+    use chrono::TimeDelta;
     use secrecy::Secret;
     use std::sync::Arc;
     use tokio::sync::RwLock;
 
     use super::*;
 
+    fn clock() -> ClockType {
+        Arc::new(ManualClock::default())
+    }
+
+    fn session(clock: &ClockType) -> Session {
+        Session {
+            session_id: SessionId::default(),
+            user_id: UserId::default(),
+            device: None,
+            ip: None,
+            issued_at: clock.now(),
+            expires_at: clock.now() + TimeDelta::seconds(TOKEN_TTL_SECONDS),
+        }
+    }
+
     #[tokio::test]
     async fn test_generate_auth_cookie() {
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let user_id = UserId::default();
-        let cookie = generate_auth_cookie(&email, &user_id).unwrap();
+        let cookie = generate_auth_cookie(&email, &session(&clock())).unwrap();
         assert_eq!(cookie.name(), JWT_COOKIE_NAME);
         assert_eq!(cookie.value().split('.').count(), 3);
         assert_eq!(cookie.path(), Some("/"));
@@ -170,26 +507,31 @@ mod tests {
 
     #[tokio::test]
     async fn test_generate_auth_token() {
+        let clock = clock();
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let user_id = UserId::default();
-        let result = generate_auth_token(&email, &user_id).unwrap();
+        let result = generate_auth_token(&email, &session(&clock)).unwrap();
         assert_eq!(result.expose_secret().split('.').count(), 3);
     }
 
     #[tokio::test]
     async fn test_validate_token_with_valid_token() {
+        let clock = clock();
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let user_id = UserId::default();
-        let token = generate_auth_token(&email, &user_id).unwrap();
+        let session = session(&clock);
+        let user_id = session.user_id.clone();
+        let token = generate_auth_token(&email, &session).unwrap();
         let banned_token_store =
             Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
-        let result = validate_token(&token, banned_token_store).await.unwrap();
+        let result = validate_token(&token, banned_token_store, &clock)
+            .await
+            .unwrap();
         assert_eq!(result.sub, "test@example.com");
         assert_eq!(result.id, user_id);
 
-        let exp = Utc::now()
+        let exp = clock
+            .now()
             .checked_add_signed(
                 chrono::Duration::try_minutes(9).expect("valid duration"),
             )
@@ -201,31 +543,193 @@ mod tests {
 
     #[tokio::test]
     async fn test_validate_token_with_invalid_token() {
+        let clock = clock();
         let token = Secret::new("invalid_token".to_owned());
         let banned_token_store =
             Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
-        let result = validate_token(&token, banned_token_store).await;
+        let result = validate_token(&token, banned_token_store, &clock).await;
         assert!(result.is_err());
     }
 
     #[tokio::test]
     async fn test_validate_token_with_banned_token() {
+        let clock = clock();
         let email =
             Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
-        let user_id = UserId::default();
-        let token = generate_auth_token(&email, &user_id).unwrap();
+        let token = generate_auth_token(&email, &session(&clock)).unwrap();
         let banned_token_store =
             Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        let claims = decode_claims(&token, &clock).unwrap();
         banned_token_store
             .write()
             .await
-            .add_token(&token)
+            .add_token(&token, claims.accepted_until(), clock.now())
             .await
             .unwrap();
 
         assert!(
-            validate_token(&token, banned_token_store).await.is_err(),
+            validate_token(&token, banned_token_store, &clock)
+                .await
+                .is_err(),
             "token should be banned"
         );
     }
+
+    #[tokio::test]
+    async fn test_validate_token_with_banned_session() {
+        let clock = clock();
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let session = session(&clock);
+        let token = generate_auth_token(&email, &session).unwrap();
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+        banned_token_store
+            .write()
+            .await
+            .ban_session(
+                &session.session_id,
+                session_accepted_until(clock.now()),
+                clock.now(),
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            validate_token(&token, banned_token_store, &clock).await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_start_session() {
+        let clock = clock();
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let user_id = UserId::default();
+        let session_store: SessionStoreType =
+            Arc::new(RwLock::new(HashmapSessionStore::default()));
+        let client = SessionClient {
+            device: Some("Firefox".to_owned()),
+            ip: Some("203.0.113.7".to_owned()),
+        };
+
+        let cookie =
+            start_session(&email, &user_id, client, &session_store, &clock)
+                .await
+                .unwrap();
+        let claims =
+            decode_claims(&Secret::new(cookie.value().to_owned()), &clock)
+                .unwrap();
+
+        let sessions = session_store
+            .read()
+            .await
+            .get_sessions(&user_id, clock.now())
+            .await
+            .unwrap();
+        assert_eq!(sessions.len(), 1);
+        assert_eq!(sessions[0].session_id, claims.sid);
+        assert_eq!(sessions[0].device.as_deref(), Some("Firefox"));
+        assert_eq!(sessions[0].ip.as_deref(), Some("203.0.113.7"));
+        assert_eq!(sessions[0].expires_at.timestamp() as usize, claims.exp);
+    }
+
+    #[tokio::test]
+    async fn test_validate_token_once_expired() {
+        let manual_clock = ManualClock::default();
+        let clock: ClockType = Arc::new(manual_clock.clone());
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token = generate_auth_token(&email, &session(&clock)).unwrap();
+        let banned_token_store =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        // Tokens are given a minute's grace for clocks that disagree
+        manual_clock.advance(TimeDelta::seconds(TOKEN_TTL_SECONDS + 60));
+        assert!(validate_token(&token, banned_token_store.clone(), &clock)
+            .await
+            .is_ok());
+
+        manual_clock.advance(TimeDelta::seconds(1));
+        assert!(matches!(
+            validate_token(&token, banned_token_store, &clock).await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_magic_link_token_works_once() {
+        let clock = clock();
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let user_id = UserId::default();
+        let token =
+            generate_magic_link_token(&email, &user_id, &clock).unwrap();
+        let banned_token_store: BannedTokenStoreType =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        // Not a session token
+        assert!(validate_token(&token, banned_token_store.clone(), &clock)
+            .await
+            .is_err());
+
+        let claims = use_magic_link_token(&token, &banned_token_store, &clock)
+            .await
+            .unwrap();
+        assert_eq!(claims.sub, "test@example.com");
+        assert_eq!(claims.id, user_id);
+
+        assert!(matches!(
+            use_magic_link_token(&token, &banned_token_store, &clock).await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_magic_link_token_expires() {
+        let manual_clock = ManualClock::default();
+        let clock: ClockType = Arc::new(manual_clock.clone());
+        let email =
+            Email::parse(Secret::new("test@example.com".to_owned())).unwrap();
+        let token =
+            generate_magic_link_token(&email, &UserId::default(), &clock)
+                .unwrap();
+        let banned_token_store: BannedTokenStoreType =
+            Arc::new(RwLock::new(HashsetBannedTokenStore::default()));
+
+        manual_clock.advance(TimeDelta::seconds(MAGIC_LINK_TTL_SECONDS));
+        assert!(matches!(
+            use_magic_link_token(&token, &banned_token_store, &clock).await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+
+        // Session tokens aren't magic links
+        let session_token =
+            generate_auth_token(&email, &session(&clock)).unwrap();
+        assert!(matches!(
+            use_magic_link_token(&session_token, &banned_token_store, &clock)
+                .await,
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
+
+    #[test]
+    fn test_password_reset_expires() {
+        let manual_clock = ManualClock::default();
+        let clock: ClockType = Arc::new(manual_clock.clone());
+        let (_, reset) =
+            generate_password_reset_token(&UserId::default(), &clock).unwrap();
+        assert!(validate_password_reset(&reset, &clock).is_ok());
+
+        manual_clock
+            .advance(TimeDelta::seconds(PASSWORD_RESET_TTL_SECONDS - 1));
+        assert!(validate_password_reset(&reset, &clock).is_ok());
+
+        manual_clock.advance(TimeDelta::seconds(1));
+        assert!(matches!(
+            validate_password_reset(&reset, &clock),
+            Err(AuthAPIError::InvalidToken)
+        ));
+    }
 }
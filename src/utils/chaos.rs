@@ -0,0 +1,56 @@
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+
+// Faults for the PostgreSQL stores to run into, for testing that handlers
+// fail cleanly. Only built with the "chaos" feature.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Faults {
+    // Added before each connection or transaction is taken, and each commit
+    pub latency: Duration,
+    // Chance, from 0 to 1, of failing to take a connection or transaction
+    pub begin_error_rate: f64,
+    // Chance of failing in place of a commit, once a transaction's writes
+    // are made, so they're rolled back
+    pub commit_error_rate: f64,
+}
+
+// Shared by the stores it's handed to, so faults can be changed while the
+// app is running
+#[derive(Debug, Clone, Default)]
+pub struct FaultInjector(Arc<Mutex<Faults>>);
+
+impl FaultInjector {
+    pub fn set(&self, faults: Faults) {
+        *self.0.lock().expect("Fault injector lock poisoned") = faults;
+    }
+
+    pub fn clear(&self) {
+        self.set(Faults::default());
+    }
+
+    pub async fn before_begin(&self) -> Result<(), sqlx::Error> {
+        let faults = self.faults();
+        inject(faults.latency, faults.begin_error_rate).await
+    }
+
+    pub async fn before_commit(&self) -> Result<(), sqlx::Error> {
+        let faults = self.faults();
+        inject(faults.latency, faults.commit_error_rate).await
+    }
+
+    fn faults(&self) -> Faults {
+        *self.0.lock().expect("Fault injector lock poisoned")
+    }
+}
+
+async fn inject(latency: Duration, error_rate: f64) -> Result<(), sqlx::Error> {
+    if !latency.is_zero() {
+        tokio::time::sleep(latency).await;
+    }
+    if rand::random::<f64>() < error_rate {
+        return Err(sqlx::Error::Protocol("injected fault".to_owned()));
+    }
+    Ok(())
+}
@@ -1,8 +1,15 @@
+use argon2::Params;
+use chrono::TimeDelta;
 use dotenvy::dotenv;
 use lazy_static::lazy_static;
 use regex::Regex;
 use secrecy::Secret;
-use std::{env as std_env, sync::LazyLock};
+use std::{env as std_env, sync::LazyLock, time::Duration};
+
+use crate::{
+    app_state::InstanceRole,
+    domain::{ResidencyRules, RetentionPolicy},
+};
 
 pub static TWO_FA_CODE_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\d{6}$").expect("2FA regex is invalid"));
@@ -23,6 +30,38 @@ lazy_static! {
     pub static ref POSTMARK_EMAIL_SENDER_ADDRESS: Secret<String> =
         set_postmark_email_sender_address();
     pub static ref REDIS_HOST_NAME: String = set_redis_host();
+    pub static ref ADMIN_API_KEY: Option<Secret<String>> = set_admin_api_key();
+    pub static ref SLOW_QUERY_THRESHOLD: Duration = set_slow_query_threshold();
+    pub static ref EMAILS_PER_RECIPIENT_PER_HOUR: i64 =
+        set_emails_per_recipient_per_hour();
+    pub static ref INVITE_ONLY_SIGNUP: bool = set_invite_only_signup();
+    pub static ref PGBOUNCER_MODE: bool = set_pgbouncer_mode();
+    pub static ref REUSE_PORT: bool = set_reuse_port();
+    pub static ref TRUST_PROXY_HEADERS: bool = set_trust_proxy_headers();
+    pub static ref AUTH_CONCURRENCY_LIMIT: usize = set_auth_concurrency_limit();
+    pub static ref PASSWORD_HASHING_THREADS: usize =
+        set_password_hashing_threads();
+    pub static ref PASSWORD_HASHING_QUEUE: usize = set_password_hashing_queue();
+    pub static ref ARGON2_PARAMS: Params = set_argon2_params();
+    pub static ref INSTANCE_ROLE: InstanceRole = set_instance_role();
+    pub static ref REQUIRED_POLICY_VERSION: Option<i32> =
+        set_required_policy_version();
+    pub static ref TWILIO_ACCOUNT_SID: Option<String> =
+        load_optional(env::TWILIO_ACCOUNT_SID_ENV_VAR);
+    pub static ref TWILIO_AUTH_TOKEN: Option<Secret<String>> =
+        load_optional(env::TWILIO_AUTH_TOKEN_ENV_VAR).map(Secret::new);
+    pub static ref TWILIO_SENDER_NUMBER: Option<String> =
+        load_optional(env::TWILIO_SENDER_NUMBER_ENV_VAR);
+    pub static ref POSTMARK_WEBHOOK_TOKEN: Option<Secret<String>> =
+        load_optional(env::POSTMARK_WEBHOOK_TOKEN_ENV_VAR).map(Secret::new);
+    pub static ref POSTMARK_INBOUND_ADDRESS: Option<String> =
+        load_optional(env::POSTMARK_INBOUND_ADDRESS_ENV_VAR);
+    pub static ref RESIDENCY_RULES: ResidencyRules = set_residency_rules();
+    pub static ref RETENTION_POLICY: RetentionPolicy = set_retention_policy();
+    pub static ref PWNED_PASSWORDS_URL: String = load_or_default(
+        env::PWNED_PASSWORDS_URL_ENV_VAR,
+        prod::pwned_passwords::BASE_URL
+    );
 }
 
 fn load_env() {
@@ -80,12 +119,200 @@ fn load_or_default(variable_name: &str, default_value: &str) -> String {
     }
 }
 
+// Unset and empty are both treated as not configured
+fn load_optional(variable_name: &str) -> Option<String> {
+    load_env();
+    std_env::var(variable_name)
+        .ok()
+        .filter(|value| !value.is_empty())
+}
+
 fn set_redis_host() -> String {
     load_env();
     std_env::var(env::REDIS_HOST_NAME_ENV_VAR)
         .unwrap_or(DEFAULT_REDIS_HOSTNAME.to_owned())
 }
 
+// Admin endpoints are disabled unless a key is configured
+fn set_admin_api_key() -> Option<Secret<String>> {
+    load_env();
+    std_env::var(env::ADMIN_API_KEY_ENV_VAR)
+        .ok()
+        .filter(|key| !key.is_empty())
+        .map(Secret::new)
+}
+
+fn set_slow_query_threshold() -> Duration {
+    load_env();
+    let millis = std_env::var(env::SLOW_QUERY_THRESHOLD_MS_ENV_VAR)
+        .ok()
+        .and_then(|millis| millis.parse().ok())
+        .unwrap_or(DEFAULT_SLOW_QUERY_THRESHOLD_MS);
+    Duration::from_millis(millis)
+}
+
+fn set_emails_per_recipient_per_hour() -> i64 {
+    load_env();
+    std_env::var(env::EMAILS_PER_RECIPIENT_PER_HOUR_ENV_VAR)
+        .ok()
+        .and_then(|limit| limit.parse().ok())
+        .unwrap_or(DEFAULT_EMAILS_PER_RECIPIENT_PER_HOUR)
+}
+
+// Signup is open unless this is set to "true"
+fn set_invite_only_signup() -> bool {
+    load_env();
+    std_env::var(env::INVITE_ONLY_SIGNUP_ENV_VAR)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+// Prepared statements are cached per connection unless this is set to "true"
+fn set_pgbouncer_mode() -> bool {
+    load_env();
+    std_env::var(env::PGBOUNCER_MODE_ENV_VAR)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+// Another process can't bind the app's address unless this is set to "true"
+fn set_reuse_port() -> bool {
+    load_env();
+    std_env::var(env::REUSE_PORT_ENV_VAR)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+// X-Forwarded-For is ignored unless this is set to "true"
+fn set_trust_proxy_headers() -> bool {
+    load_env();
+    std_env::var(env::TRUST_PROXY_HEADERS_ENV_VAR)
+        .map(|value| value.eq_ignore_ascii_case("true"))
+        .unwrap_or(false)
+}
+
+// Password hashing requests beyond the default number at once are turned
+// away unless this is set to a positive number
+fn set_auth_concurrency_limit() -> usize {
+    load_env();
+    std_env::var(env::AUTH_CONCURRENCY_LIMIT_ENV_VAR)
+        .ok()
+        .and_then(|limit| limit.parse().ok())
+        .filter(|limit| *limit > 0)
+        .unwrap_or(DEFAULT_AUTH_CONCURRENCY_LIMIT)
+}
+
+// Passwords are hashed on one thread per CPU unless this is set to a
+// positive number
+fn set_password_hashing_threads() -> usize {
+    load_env();
+    std_env::var(env::PASSWORD_HASHING_THREADS_ENV_VAR)
+        .ok()
+        .and_then(|threads| threads.parse().ok())
+        .filter(|threads| *threads > 0)
+        .unwrap_or_else(|| {
+            std::thread::available_parallelism().map_or(1, |n| n.get())
+        })
+}
+
+fn set_password_hashing_queue() -> usize {
+    load_env();
+    std_env::var(env::PASSWORD_HASHING_QUEUE_ENV_VAR)
+        .ok()
+        .and_then(|queue| queue.parse().ok())
+        .unwrap_or(DEFAULT_PASSWORD_HASHING_QUEUE)
+}
+
+// New password hashes use the default Argon2 cost unless these are set.
+// Existing hashes carry their own parameters, so still verify after a
+// change. Values Argon2 won't accept stop the app from starting.
+fn set_argon2_params() -> Params {
+    load_env();
+    let number = |name: &str, default: u32| {
+        load_optional(name)
+            .map(|value| {
+                value
+                    .parse()
+                    .unwrap_or_else(|_| panic!("{} must be a number", name))
+            })
+            .unwrap_or(default)
+    };
+    Params::new(
+        number(env::ARGON2_MEMORY_KIB_ENV_VAR, DEFAULT_ARGON2_MEMORY_KIB),
+        number(env::ARGON2_ITERATIONS_ENV_VAR, DEFAULT_ARGON2_ITERATIONS),
+        number(env::ARGON2_PARALLELISM_ENV_VAR, DEFAULT_ARGON2_PARALLELISM),
+        None,
+    )
+    .unwrap_or_else(|e| panic!("Argon2 parameters are not valid: {}", e))
+}
+
+// Instances serve the API and run the background jobs unless this is set to
+// "api" or "worker"
+fn set_instance_role() -> InstanceRole {
+    load_env();
+    std_env::var(env::INSTANCE_ROLE_ENV_VAR)
+        .ok()
+        .filter(|role| !role.is_empty())
+        .map(|role| {
+            InstanceRole::parse(&role)
+                .expect("INSTANCE_ROLE must be api, worker or both")
+        })
+        .unwrap_or_default()
+}
+
+// Users don't have to accept any policy version unless this is set
+fn set_required_policy_version() -> Option<i32> {
+    load_env();
+    std_env::var(env::REQUIRED_POLICY_VERSION_ENV_VAR)
+        .ok()
+        .filter(|version| !version.is_empty())
+        .map(|version| {
+            version
+                .parse()
+                .expect("REQUIRED_POLICY_VERSION must be a number")
+        })
+}
+
+// Webhooks aren't restricted by data region unless this is set
+fn set_residency_rules() -> ResidencyRules {
+    load_optional(env::DATA_REGION_WEBHOOK_HOSTS_ENV_VAR)
+        .map(|rules| {
+            ResidencyRules::parse(&rules).unwrap_or_else(|e| {
+                panic!("DATA_REGION_WEBHOOK_HOSTS is not valid: {}", e.as_ref())
+            })
+        })
+        .unwrap_or_default()
+}
+
+// Each kind of record is kept for its default number of days unless its
+// variable is set to a positive number of days
+fn set_retention_policy() -> RetentionPolicy {
+    load_env();
+    let days = |name: &str, default: TimeDelta| {
+        std_env::var(name)
+            .ok()
+            .and_then(|days| days.parse::<i64>().ok())
+            .filter(|days| *days > 0)
+            .and_then(TimeDelta::try_days)
+            .unwrap_or(default)
+    };
+    let defaults = RetentionPolicy::default();
+    RetentionPolicy {
+        audit_log: days(
+            env::AUDIT_LOG_RETENTION_DAYS_ENV_VAR,
+            defaults.audit_log,
+        ),
+        notification_log: days(
+            env::NOTIFICATION_LOG_RETENTION_DAYS_ENV_VAR,
+            defaults.notification_log,
+        ),
+        rota_history: days(
+            env::ROTA_HISTORY_RETENTION_DAYS_ENV_VAR,
+            defaults.rota_history,
+        ),
+    }
+}
+
 pub mod env {
     pub const DATABASE_URL_ENV_VAR: &str = "DATABASE_URL";
     pub const JWT_SECRET_ENV_VAR: &str = "JWT_SECRET";
@@ -93,27 +320,188 @@ pub mod env {
     pub const POSTMARK_EMAIL_SENDER_ADDRESS_ENV_VAR: &str =
         "POSTMARK_EMAIL_SENDER_ADDRESS";
     pub const REDIS_HOST_NAME_ENV_VAR: &str = "REDIS_HOST_NAME";
+    pub const ADMIN_API_KEY_ENV_VAR: &str = "ADMIN_API_KEY";
+    pub const SLOW_QUERY_THRESHOLD_MS_ENV_VAR: &str = "SLOW_QUERY_THRESHOLD_MS";
+    pub const EMAILS_PER_RECIPIENT_PER_HOUR_ENV_VAR: &str =
+        "EMAILS_PER_RECIPIENT_PER_HOUR";
+    pub const INVITE_ONLY_SIGNUP_ENV_VAR: &str = "INVITE_ONLY_SIGNUP";
+    pub const PGBOUNCER_MODE_ENV_VAR: &str = "PGBOUNCER_MODE";
+    pub const REUSE_PORT_ENV_VAR: &str = "REUSE_PORT";
+    pub const TRUST_PROXY_HEADERS_ENV_VAR: &str = "TRUST_PROXY_HEADERS";
+    pub const AUTH_CONCURRENCY_LIMIT_ENV_VAR: &str = "AUTH_CONCURRENCY_LIMIT";
+    pub const PASSWORD_HASHING_THREADS_ENV_VAR: &str =
+        "PASSWORD_HASHING_THREADS";
+    pub const PASSWORD_HASHING_QUEUE_ENV_VAR: &str = "PASSWORD_HASHING_QUEUE";
+    pub const ARGON2_MEMORY_KIB_ENV_VAR: &str = "ARGON2_MEMORY_KIB";
+    pub const ARGON2_ITERATIONS_ENV_VAR: &str = "ARGON2_ITERATIONS";
+    pub const ARGON2_PARALLELISM_ENV_VAR: &str = "ARGON2_PARALLELISM";
+    pub const INSTANCE_ROLE_ENV_VAR: &str = "INSTANCE_ROLE";
+    pub const REQUIRED_POLICY_VERSION_ENV_VAR: &str = "REQUIRED_POLICY_VERSION";
+    pub const TWILIO_ACCOUNT_SID_ENV_VAR: &str = "TWILIO_ACCOUNT_SID";
+    pub const TWILIO_AUTH_TOKEN_ENV_VAR: &str = "TWILIO_AUTH_TOKEN";
+    pub const TWILIO_SENDER_NUMBER_ENV_VAR: &str = "TWILIO_SENDER_NUMBER";
+    pub const POSTMARK_WEBHOOK_TOKEN_ENV_VAR: &str = "POSTMARK_WEBHOOK_TOKEN";
+    pub const POSTMARK_INBOUND_ADDRESS_ENV_VAR: &str =
+        "POSTMARK_INBOUND_ADDRESS";
+    pub const PWNED_PASSWORDS_URL_ENV_VAR: &str = "PWNED_PASSWORDS_URL";
+    pub const DATA_REGION_WEBHOOK_HOSTS_ENV_VAR: &str =
+        "DATA_REGION_WEBHOOK_HOSTS";
+    pub const AUDIT_LOG_RETENTION_DAYS_ENV_VAR: &str =
+        "AUDIT_LOG_RETENTION_DAYS";
+    pub const NOTIFICATION_LOG_RETENTION_DAYS_ENV_VAR: &str =
+        "NOTIFICATION_LOG_RETENTION_DAYS";
+    pub const ROTA_HISTORY_RETENTION_DAYS_ENV_VAR: &str =
+        "ROTA_HISTORY_RETENTION_DAYS";
 }
 
 pub const JWT_COOKIE_NAME: &str = "jwt";
+pub const ADMIN_KEY_HEADER: &str = "X-Admin-Key";
+// Carries the key no-code tools poll for updates with
+pub const INTEGRATION_KEY_HEADER: &str = "X-Api-Key";
+pub const POSTMARK_WEBHOOK_TOKEN_HEADER: &str = "X-Postmark-Webhook-Token";
+// Sent with exports and webhooks for accounts with a data region
+pub const DATA_REGION_HEADER: &str = "X-Data-Region";
+// Sent when creating projects, members and shifts
+pub const QUOTA_LIMIT_HEADER: &str = "X-Quota-Limit";
+pub const QUOTA_REMAINING_HEADER: &str = "X-Quota-Remaining";
 pub const DEFAULT_REDIS_HOSTNAME: &str = "127.0.0.1";
+pub const DEFAULT_SLOW_QUERY_THRESHOLD_MS: u64 = 200;
+pub const EVENT_BUS_CAPACITY: usize = 1024;
+pub const OUTBOX_RELAY_BATCH_SIZE: i64 = 100;
+pub const EMAIL_OUTBOX_BATCH_SIZE: i64 = 100;
+pub const DEFAULT_EMAILS_PER_RECIPIENT_PER_HOUR: i64 = 5;
+pub const EMAIL_RATE_LIMIT_WINDOW: Duration = Duration::from_secs(60 * 60);
+// How many of a project's emails its owner can look back over
+pub const PROJECT_EMAILS_LIMIT: i64 = 100;
+// How far back a user's notification inbox goes
+pub const NOTIFICATIONS_LIMIT: i64 = 50;
+// How long a long-polling client waits for notifications by default, and at
+// most. The wait also ends in time to answer before the request deadline.
+pub const DEFAULT_NOTIFICATION_WAIT: Duration = Duration::from_secs(25);
+pub const MAX_NOTIFICATION_WAIT: Duration = Duration::from_secs(60);
+// How often a waiting long poll checks for new notifications
+pub const NOTIFICATION_POLL_INTERVAL: Duration = Duration::from_millis(500);
+pub const SMS_OUTBOX_BATCH_SIZE: i64 = 100;
+pub const JOB_WORKER_BATCH_SIZE: i64 = 100;
+pub const MAX_JOB_ATTEMPTS: i32 = 5;
+pub const JOB_RETRY_DELAY: Duration = Duration::from_secs(30);
+// How often, and how soon, taking a connection or starting a transaction is
+// retried while PostgreSQL can't be reached. The backoff doubles each time.
+pub const DATABASE_RETRIES: u32 = 3;
+pub const DATABASE_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+// How long to wait for a free connection, or a new one, before giving up
+pub const DATABASE_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(3);
+// When clients are told to try again while PostgreSQL is unavailable
+pub const DATABASE_RETRY_AFTER: Duration = Duration::from_secs(5);
+// Argon2 hashing is CPU-bound, so this many at once keeps a burst of logins
+// from starving the rest of the API
+pub const DEFAULT_AUTH_CONCURRENCY_LIMIT: usize = 16;
+// Hashes take a fraction of a second, so permits free up quickly
+pub const AUTH_RETRY_AFTER: Duration = Duration::from_secs(1);
+// How many hashes may wait for a thread before more are turned away
+pub const DEFAULT_PASSWORD_HASHING_QUEUE: usize = 64;
+// Argon2id cost of new password hashes: memory in KiB, passes over it, and
+// lanes. Raising them slows brute forcing and logins alike.
+pub const DEFAULT_ARGON2_MEMORY_KIB: u32 = 15000;
+pub const DEFAULT_ARGON2_ITERATIONS: u32 = 2;
+pub const DEFAULT_ARGON2_PARALLELISM: u32 = 1;
+// How long a background job's lease lasts if its holder never releases it.
+// Longer than any one pass should take.
+pub const JOB_LEASE_DURATION: Duration = Duration::from_secs(5 * 60);
+// How many of a user's requests are kept while capturing them, and how long
+// capturing lasts if it's never stopped
+pub const REQUEST_CAPTURE_LIMIT: usize = 50;
+// How long an event waits before it's given to tools polling for updates.
+// Events are numbered when they're written, not when their transaction
+// commits, so a later event can appear before an earlier one; waiting out
+// the longest a transaction could be open for keeps a cursor from moving
+// past events that haven't appeared yet.
+pub const INTEGRATION_UPDATE_SETTLE_TIME: Duration = Duration::from_secs(60);
+pub const REQUEST_CAPTURE_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+// How stale an instance's list of the users being captured can get before
+// it's read again
+pub const REQUEST_CAPTURE_REFRESH: Duration = Duration::from_secs(10);
+// How many expired rows the retention job deletes in one statement
+pub const RETENTION_BATCH_SIZE: i64 = 1000;
+
+// Where the running binary came from, set at compile time by build.rs
+pub mod build_info {
+    pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+    pub const GIT_SHA: &str = env!("VERGEN_GIT_SHA");
+    pub const BUILD_TIMESTAMP: &str = env!("VERGEN_BUILD_TIMESTAMP");
+    // The optional features compiled in
+    pub const FEATURES: &[&str] = &[
+        #[cfg(feature = "chaos")]
+        "chaos",
+        #[cfg(feature = "postmark")]
+        "postmark",
+        #[cfg(feature = "pwned-passwords")]
+        "pwned-passwords",
+    ];
+}
 
 pub mod prod {
+    use std::time::Duration;
+
     pub const APP_ADDRESS: &str = "0.0.0.0:3000";
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+    pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+    pub const JOB_POLL_INTERVAL: Duration = Duration::from_secs(1);
+    pub const ROTA_CHANGE_DIGEST_WINDOW: Duration = Duration::from_secs(5 * 60);
+    pub const SHIFT_REMINDER_POLL_INTERVAL: Duration = Duration::from_secs(60);
+    pub const SHIFT_REMINDER_LEAD_TIME: Duration =
+        Duration::from_secs(12 * 60 * 60);
+    pub const SHIFT_ESCALATION_POLL_INTERVAL: Duration =
+        Duration::from_secs(60);
+    pub const CHAT_WEBHOOK_TIMEOUT: Duration = Duration::from_secs(10);
+    pub const ROTA_PUBLISH_POLL_INTERVAL: Duration = Duration::from_secs(60);
+    pub const RETENTION_POLL_INTERVAL: Duration = Duration::from_secs(60 * 60);
     pub mod email_client {
         use std::time::Duration;
 
         pub const BASE_URL: &str = "https://api.postmarkapp.com/email";
         pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
     }
+    pub mod sms_client {
+        use std::time::Duration;
+
+        pub const BASE_URL: &str = "https://api.twilio.com";
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(10);
+    }
+    pub mod pwned_passwords {
+        use std::time::Duration;
+
+        pub const BASE_URL: &str = "https://api.pwnedpasswords.com/";
+        // Short, as signups wait on it and go ahead without it
+        pub const TIMEOUT: Duration = std::time::Duration::from_secs(2);
+    }
 }
 
 pub mod test {
+    use std::time::Duration;
+
     pub const APP_ADDRESS: &str = "127.0.0.1:0";
+    pub const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+    pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_millis(50);
+    pub const EMAILS_PER_RECIPIENT_PER_HOUR: i64 = 3;
+    pub const JOB_POLL_INTERVAL: Duration = Duration::from_millis(50);
+    pub const ROTA_CHANGE_DIGEST_WINDOW: Duration = Duration::from_secs(1);
     pub mod email_client {
         use std::time::Duration;
 
         // pub const SENDER: &str = "test@email.com";
         pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
     }
+    pub mod sms_client {
+        use std::time::Duration;
+
+        pub const ACCOUNT_SID: &str = "AC00000000000000000000000000000000";
+        pub const SENDER: &str = "+15005550006";
+        pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
+    }
+    pub mod pwned_passwords {
+        use std::time::Duration;
+
+        pub const TIMEOUT: Duration = std::time::Duration::from_millis(200);
+    }
 }
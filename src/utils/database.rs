@@ -0,0 +1,156 @@
+use std::{
+    error::Error,
+    future::Future,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use crate::utils::{
+    constants::{DATABASE_RETRIES, DATABASE_RETRY_BACKOFF},
+    deadline::Deadline,
+};
+
+// Set while connecting to PostgreSQL is failing, so losing and regaining
+// the connection are each logged once rather than on every request
+static DATABASE_UNAVAILABLE: AtomicBool = AtomicBool::new(false);
+
+// Errors that say nothing about the query, only that PostgreSQL couldn't be
+// reached: a dead socket, no connection free in time, or a server that is
+// shutting down, starting up or no longer the primary
+pub fn is_connection_error(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed => true,
+        sqlx::Error::Database(e) => e.code().is_some_and(|code| {
+            // connection_exception, admin_shutdown, crash_shutdown,
+            // cannot_connect_now and read_only_sql_transaction
+            code.starts_with("08")
+                || matches!(
+                    code.as_ref(),
+                    "57P01" | "57P02" | "57P03" | "25006"
+                )
+        }),
+        _ => false,
+    }
+}
+
+// Whether an error was caused, anywhere down its chain, by PostgreSQL being
+// out of reach. Such errors are answered with a 503 for the client to retry.
+pub fn is_database_unavailable(e: &(dyn Error + 'static)) -> bool {
+    let mut current = Some(e);
+    while let Some(e) = current {
+        if e.downcast_ref::<sqlx::Error>()
+            .is_some_and(is_connection_error)
+        {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+// Run `attempt` again, backing off, while it fails to reach PostgreSQL, as
+// it does while a replica is promoted. Only for work that is safe to repeat,
+// such as taking a connection or starting a transaction. Gives up early
+// rather than wait past the request deadline.
+pub async fn retry_on_disconnect<T, F, Fut>(
+    mut attempt: F,
+) -> Result<T, sqlx::Error>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, sqlx::Error>>,
+{
+    let mut backoff = DATABASE_RETRY_BACKOFF;
+    let mut retries = 0;
+    loop {
+        match attempt().await {
+            Ok(output) => {
+                if DATABASE_UNAVAILABLE.swap(false, Ordering::Relaxed) {
+                    tracing::info!("Connection to PostgreSQL recovered");
+                }
+                return Ok(output);
+            }
+            Err(e) if is_connection_error(&e) => {
+                if !DATABASE_UNAVAILABLE.swap(true, Ordering::Relaxed) {
+                    tracing::warn!("Lost connection to PostgreSQL: {:?}", e);
+                }
+                let out_of_time = Deadline::current()
+                    .is_some_and(|deadline| deadline.remaining() <= backoff);
+                if retries == DATABASE_RETRIES || out_of_time {
+                    return Err(e);
+                }
+                tokio::time::sleep(backoff).await;
+                backoff *= 2;
+                retries += 1;
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io, sync::atomic::AtomicU32};
+
+    use color_eyre::eyre::{eyre, WrapErr};
+
+    use super::*;
+
+    fn disconnected() -> sqlx::Error {
+        sqlx::Error::Io(io::Error::from(io::ErrorKind::ConnectionReset))
+    }
+
+    #[test]
+    fn test_unavailable_anywhere_in_the_chain() {
+        let report = Err::<(), _>(disconnected())
+            .wrap_err("failed to fetch user")
+            .unwrap_err();
+        let wrapped =
+            eyre!(crate::domain::UserStoreError::UnexpectedError(report));
+        assert!(is_database_unavailable(wrapped.as_ref()));
+
+        let report = Err::<(), _>(sqlx::Error::RowNotFound)
+            .wrap_err("failed to fetch user")
+            .unwrap_err();
+        assert!(!is_database_unavailable(report.as_ref()));
+    }
+
+    #[tokio::test]
+    async fn test_retries_until_connected() {
+        let attempts = AtomicU32::new(0);
+        let result = retry_on_disconnect(|| async {
+            match attempts.fetch_add(1, Ordering::Relaxed) {
+                0 | 1 => Err(disconnected()),
+                _ => Ok(()),
+            }
+        })
+        .await;
+        assert!(result.is_ok());
+        assert_eq!(attempts.load(Ordering::Relaxed), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_the_last_retry() {
+        let attempts = AtomicU32::new(0);
+        let result = retry_on_disconnect(|| async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>(disconnected())
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), DATABASE_RETRIES + 1);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_other_errors() {
+        let attempts = AtomicU32::new(0);
+        let result = retry_on_disconnect(|| async {
+            attempts.fetch_add(1, Ordering::Relaxed);
+            Err::<(), _>(sqlx::Error::RowNotFound)
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::Relaxed), 1);
+    }
+}
@@ -0,0 +1,100 @@
+use std::{future::Future, time::Duration};
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use tokio::time::Instant;
+
+use crate::{AppState, ErrorResponse};
+
+tokio::task_local! {
+    static REQUEST_DEADLINE: Deadline;
+}
+
+// The point after which nobody is waiting for the result of a request
+#[derive(Debug, Clone, Copy)]
+pub struct Deadline(Instant);
+
+impl Deadline {
+    pub fn after(timeout: Duration) -> Self {
+        Self(Instant::now() + timeout)
+    }
+
+    pub fn remaining(&self) -> Duration {
+        self.0.saturating_duration_since(Instant::now())
+    }
+
+    // The deadline of the request being handled, if any
+    pub fn current() -> Option<Self> {
+        REQUEST_DEADLINE.try_with(|deadline| *deadline).ok()
+    }
+
+    pub async fn scope<F: Future>(self, f: F) -> F::Output {
+        REQUEST_DEADLINE.scope(self, f).await
+    }
+}
+
+// Give up on requests that outlive the configured timeout. Store calls made
+// while handling the request can read the deadline via Deadline::current and
+// bound their own work, so a slow query doesn't keep a connection busy after
+// the client has gone.
+pub async fn enforce_deadline(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let deadline = Deadline::after(state.request_timeout);
+
+    match deadline
+        .scope(tokio::time::timeout_at(deadline.0, next.run(request)))
+        .await
+    {
+        Ok(response) => response,
+        Err(_) => {
+            tracing::warn!(
+                "Request exceeded deadline of {:?}",
+                state.request_timeout
+            );
+            (
+                StatusCode::SERVICE_UNAVAILABLE,
+                Json(ErrorResponse::new(
+                    "request_timeout",
+                    "Request timed out".to_string(),
+                )),
+            )
+                .into_response()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_no_deadline_outside_scope() {
+        assert!(Deadline::current().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_deadline_visible_inside_scope() {
+        let remaining = Deadline::after(Duration::from_secs(5))
+            .scope(async { Deadline::current().map(|d| d.remaining()) })
+            .await
+            .expect("Deadline should be set");
+
+        assert!(remaining <= Duration::from_secs(5));
+        assert!(remaining > Duration::from_secs(4));
+    }
+
+    #[tokio::test]
+    async fn test_remaining_saturates_at_zero() {
+        let deadline = Deadline::after(Duration::ZERO);
+        tokio::time::sleep(Duration::from_millis(5)).await;
+        assert_eq!(deadline.remaining(), Duration::ZERO);
+    }
+}
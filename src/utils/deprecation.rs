@@ -0,0 +1,166 @@
+use axum::{
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderValue},
+    middleware::Next,
+    response::Response,
+};
+
+use crate::AppState;
+
+pub struct DeprecatedEndpoint {
+    pub path: &'static str,
+    // HTTP-date, as required by the Sunset header (RFC 8594)
+    pub sunset: &'static str,
+    pub successor: Option<&'static str>,
+}
+
+pub const DEPRECATED_ENDPOINTS: &[DeprecatedEndpoint] = &[
+    DeprecatedEndpoint {
+        path: "/projects/add-member",
+        sunset: "Fri, 01 Jan 2027 00:00:00 GMT",
+        successor: None,
+    },
+    DeprecatedEndpoint {
+        path: "/auth/change-email",
+        sunset: "Fri, 01 Jan 2027 00:00:00 GMT",
+        successor: Some("/auth/email"),
+    },
+];
+
+pub const DEPRECATION_HEADER: &str = "Deprecation";
+pub const SUNSET_HEADER: &str = "Sunset";
+const UNKNOWN_CLIENT: &str = "unknown";
+const OTHER_CLIENT: &str = "other";
+// The clients usage is counted for by name. Anything else is counted as
+// "other", so made-up User-Agents can't grow the counts without bound.
+const KNOWN_CLIENTS: &[&str] = &[
+    "rota-web",
+    "curl",
+    "Wget",
+    "PostmanRuntime",
+    "python-requests",
+    "axios",
+    "okhttp",
+    "Mozilla",
+];
+
+pub fn find_deprecated_endpoint(
+    path: &str,
+) -> Option<&'static DeprecatedEndpoint> {
+    DEPRECATED_ENDPOINTS
+        .iter()
+        .find(|endpoint| endpoint.path == path)
+}
+
+// Record which clients still call deprecated endpoints and tell them when the
+// endpoint goes away. Telemetry failures never fail the request itself.
+pub async fn deprecation_telemetry(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let endpoint = match find_deprecated_endpoint(request.uri().path()) {
+        Some(endpoint) => endpoint,
+        None => return next.run(request).await,
+    };
+
+    let client = client_identifier(request.headers());
+    if let Err(e) = state
+        .deprecated_usage_store
+        .write()
+        .await
+        .record_usage(endpoint.path, &client)
+        .await
+    {
+        tracing::warn!("Failed to record deprecated endpoint usage: {:?}", e);
+    }
+
+    let mut response = next.run(request).await;
+    let headers = response.headers_mut();
+    headers.insert(DEPRECATION_HEADER, HeaderValue::from_static("true"));
+    headers.insert(SUNSET_HEADER, HeaderValue::from_static(endpoint.sunset));
+    if let Some(successor) = endpoint.successor {
+        if let Ok(link) = HeaderValue::from_str(&format!(
+            "<{}>; rel=\"successor-version\"",
+            successor
+        )) {
+            headers.insert(header::LINK, link);
+        }
+    }
+
+    response
+}
+
+// The known client named by the first product token of the User-Agent,
+// without its version
+fn client_identifier(headers: &HeaderMap) -> String {
+    let agent = match headers
+        .get(header::USER_AGENT)
+        .and_then(|agent| agent.to_str().ok())
+        .filter(|agent| !agent.trim().is_empty())
+    {
+        Some(agent) => agent,
+        None => return UNKNOWN_CLIENT.to_owned(),
+    };
+    let product = agent
+        .split_whitespace()
+        .next()
+        .and_then(|token| token.split('/').next())
+        .unwrap_or_default();
+    KNOWN_CLIENTS
+        .iter()
+        .find(|client| client.eq_ignore_ascii_case(product))
+        .unwrap_or(&OTHER_CLIENT)
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_deprecated_endpoint() {
+        assert!(find_deprecated_endpoint("/projects/add-member").is_some());
+        assert!(find_deprecated_endpoint("/auth/change-email").is_some());
+        assert!(find_deprecated_endpoint("/projects/new").is_none());
+        assert!(find_deprecated_endpoint("/auth/email").is_none());
+    }
+
+    #[test]
+    fn test_sunset_dates_are_valid_header_values() {
+        for endpoint in DEPRECATED_ENDPOINTS {
+            assert!(
+                chrono::DateTime::parse_from_rfc2822(endpoint.sunset).is_ok(),
+                "Invalid sunset date for {}",
+                endpoint.path
+            );
+        }
+    }
+
+    #[test]
+    fn test_client_identifier() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(client_identifier(&headers), UNKNOWN_CLIENT);
+
+        headers.insert(header::USER_AGENT, HeaderValue::from_static(""));
+        assert_eq!(client_identifier(&headers), UNKNOWN_CLIENT);
+
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_static("rota-web/1.2"),
+        );
+        assert_eq!(client_identifier(&headers), "rota-web");
+
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_static("curl/8.5.0 (x86_64-pc-linux-gnu)"),
+        );
+        assert_eq!(client_identifier(&headers), "curl");
+
+        headers.insert(
+            header::USER_AGENT,
+            HeaderValue::from_static("made-up-client/7f3e"),
+        );
+        assert_eq!(client_identifier(&headers), OTHER_CLIENT);
+    }
+}
@@ -0,0 +1,101 @@
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+
+use super::{admin::constant_time_eq, constants::JWT_SECRET};
+
+// Enough of the signature that guessing one is hopeless, while keeping the
+// reply-to address within the 64 characters an address's local part allows
+const SIGNATURE_BYTES: usize = 10;
+
+// Replies to reminder emails come from the member's mail server, not a
+// signed-in user, so the reply-to address names the reminder and is signed.
+// Nobody can answer other reminders by guessing their IDs. The token is
+// lowercase hex, as some mail servers change the case of addresses.
+pub fn reply_token(secret: &Secret<String>, reminder_id: i64) -> String {
+    format!("{}-{}", reminder_id, signature(secret, reminder_id))
+}
+
+// The reminder a token is for, if its signature checks out
+pub fn verify_reply_token(secret: &Secret<String>, token: &str) -> Option<i64> {
+    let token = token.to_ascii_lowercase();
+    let (reminder_id, signature_part) = token.split_once('-')?;
+    let reminder_id = reminder_id.parse().ok()?;
+    constant_time_eq(
+        signature(secret, reminder_id).as_bytes(),
+        signature_part.as_bytes(),
+    )
+    .then_some(reminder_id)
+}
+
+// `inbound_address` with the reminder's token after a plus sign, which
+// Postmark passes on as the reply's MailboxHash
+pub fn reply_address(
+    inbound_address: &str,
+    reminder_id: i64,
+) -> Option<String> {
+    let (mailbox, domain) = inbound_address.split_once('@')?;
+    Some(format!(
+        "{}+{}@{}",
+        mailbox,
+        reply_token(&JWT_SECRET, reminder_id),
+        domain
+    ))
+}
+
+fn signature(secret: &Secret<String>, reminder_id: i64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC takes keys of any length");
+    mac.update(b"email-reply:");
+    mac.update(reminder_id.to_string().as_bytes());
+    mac.finalize().into_bytes()[..SIGNATURE_BYTES]
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret() -> Secret<String> {
+        Secret::new("secret".to_owned())
+    }
+
+    #[test]
+    fn test_reply_token_round_trip() {
+        let token = reply_token(&secret(), 42);
+        assert!(token.starts_with("42-"));
+        assert_eq!(token.len(), 3 + SIGNATURE_BYTES * 2);
+        assert_eq!(verify_reply_token(&secret(), &token), Some(42));
+        assert_eq!(
+            verify_reply_token(&secret(), &token.to_ascii_uppercase()),
+            Some(42)
+        );
+    }
+
+    #[test]
+    fn test_reply_token_rejects_tampering() {
+        let token = reply_token(&secret(), 42);
+        let (_, signature) = token.split_once('-').unwrap();
+
+        for token in [
+            format!("43-{}", signature),
+            format!("42-{}0", signature),
+            "42".to_owned(),
+            "forty-two".to_owned(),
+        ] {
+            assert_eq!(
+                verify_reply_token(&secret(), &token),
+                None,
+                "{}",
+                token
+            );
+        }
+        assert_eq!(
+            verify_reply_token(&Secret::new("other".to_owned()), &token),
+            None
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use hmac::{Hmac, Mac};
+use secrecy::{ExposeSecret, Secret};
+use sha2::Sha256;
+
+use super::{
+    admin::constant_time_eq,
+    constants::{APP_SERVICE_EXTERNAL_ADDRESS, JWT_SECRET},
+};
+
+// The image recording that an email was opened is fetched by the recipient's
+// mail client, not a signed-in user, so its link names the delivery and is
+// signed. Nobody can mark other deliveries opened by guessing their IDs.
+pub fn open_token(secret: &Secret<String>, delivery_id: i64) -> String {
+    format!("{}.{}", delivery_id, signature(secret, delivery_id))
+}
+
+// The delivery a token is for, if its signature checks out
+pub fn verify_open_token(secret: &Secret<String>, token: &str) -> Option<i64> {
+    let (delivery_id, signature_part) = token.split_once('.')?;
+    let delivery_id = delivery_id.parse().ok()?;
+    constant_time_eq(
+        signature(secret, delivery_id).as_bytes(),
+        signature_part.as_bytes(),
+    )
+    .then_some(delivery_id)
+}
+
+pub fn open_pixel_url(delivery_id: i64) -> String {
+    format!(
+        "{}/email/open?token={}",
+        *APP_SERVICE_EXTERNAL_ADDRESS,
+        open_token(&JWT_SECRET, delivery_id)
+    )
+}
+
+fn signature(secret: &Secret<String>, delivery_id: i64) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.expose_secret().as_bytes())
+            .expect("HMAC takes keys of any length");
+    mac.update(b"email-open:");
+    mac.update(delivery_id.to_string().as_bytes());
+    URL_SAFE_NO_PAD.encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn secret() -> Secret<String> {
+        Secret::new("secret".to_owned())
+    }
+
+    #[test]
+    fn test_open_token_round_trip() {
+        let token = open_token(&secret(), 42);
+        assert!(token.starts_with("42."));
+        assert_eq!(verify_open_token(&secret(), &token), Some(42));
+    }
+
+    #[test]
+    fn test_open_token_rejects_tampering() {
+        let token = open_token(&secret(), 42);
+        let (_, signature) = token.split_once('.').unwrap();
+
+        for token in [
+            format!("43.{}", signature),
+            format!("42.{}x", signature),
+            "42".to_owned(),
+            "forty-two.abc".to_owned(),
+        ] {
+            assert_eq!(verify_open_token(&secret(), &token), None, "{}", token);
+        }
+        assert_eq!(
+            verify_open_token(&Secret::new("other".to_owned()), &token),
+            None
+        );
+    }
+}
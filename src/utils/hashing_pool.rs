@@ -0,0 +1,193 @@
+use std::{
+    error::Error,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, SyncSender, TrySendError},
+        Arc, LazyLock, Mutex,
+    },
+    thread,
+};
+
+use thiserror::Error;
+use tokio::sync::oneshot;
+
+use crate::utils::{
+    constants::{PASSWORD_HASHING_QUEUE, PASSWORD_HASHING_THREADS},
+    metrics::{PASSWORD_HASH_QUEUE_DEPTH_METRIC, PASSWORD_HASH_SHED_METRIC},
+};
+
+// Password hashing runs on its own threads rather than tokio's blocking
+// pool, so a burst of signups can't take the threads other blocking work
+// relies on
+pub static HASHING_POOL: LazyLock<HashingPool> = LazyLock::new(|| {
+    HashingPool::new(*PASSWORD_HASHING_THREADS, *PASSWORD_HASHING_QUEUE)
+});
+
+type Job = Box<dyn FnOnce() + Send + 'static>;
+
+#[derive(Debug, Error)]
+pub enum HashingPoolError {
+    #[error("Too many passwords are waiting to be hashed")]
+    Overloaded,
+    #[error("The password hashing threads have stopped")]
+    Stopped,
+}
+
+pub struct HashingPool {
+    sender: SyncSender<Job>,
+    // Jobs waiting for a thread, not counting the ones being run
+    queued: Arc<AtomicUsize>,
+}
+
+impl HashingPool {
+    pub fn new(threads: usize, queue: usize) -> Self {
+        let (sender, receiver) = mpsc::sync_channel::<Job>(queue);
+        let receiver = Arc::new(Mutex::new(receiver));
+        let queued = Arc::new(AtomicUsize::new(0));
+
+        for i in 0..threads.max(1) {
+            let receiver = receiver.clone();
+            let queued = queued.clone();
+            thread::Builder::new()
+                .name(format!("password-hashing-{}", i))
+                .spawn(move || run_jobs(&receiver, &queued))
+                .expect("Failed to start password hashing thread");
+        }
+
+        Self { sender, queued }
+    }
+
+    // Run CPU-heavy work on the pool. When the queue is already full the
+    // work is turned away at once rather than left to wait behind it.
+    pub async fn run<T, F>(&self, work: F) -> Result<T, HashingPoolError>
+    where
+        T: Send + 'static,
+        F: FnOnce() -> T + Send + 'static,
+    {
+        let (result_sender, result_receiver) = oneshot::channel();
+        let job: Job = Box::new(move || {
+            // The caller may have given up waiting, which is fine
+            let _ = result_sender.send(work());
+        });
+
+        let depth = self.queued.fetch_add(1, Ordering::SeqCst) + 1;
+        match self.sender.try_send(job) {
+            Ok(()) => {
+                metrics::gauge!(PASSWORD_HASH_QUEUE_DEPTH_METRIC)
+                    .set(depth as f64);
+            }
+            Err(e) => {
+                self.queued.fetch_sub(1, Ordering::SeqCst);
+                return Err(match e {
+                    TrySendError::Full(_) => {
+                        metrics::counter!(PASSWORD_HASH_SHED_METRIC)
+                            .increment(1);
+                        HashingPoolError::Overloaded
+                    }
+                    TrySendError::Disconnected(_) => HashingPoolError::Stopped,
+                });
+            }
+        }
+
+        result_receiver.await.map_err(|_| HashingPoolError::Stopped)
+    }
+}
+
+fn run_jobs(receiver: &Mutex<Receiver<Job>>, queued: &AtomicUsize) {
+    loop {
+        // Only held while taking a job, so the threads hash in parallel
+        let job = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let job = match job {
+            Ok(job) => job,
+            Err(_) => return,
+        };
+
+        let depth = queued.fetch_sub(1, Ordering::SeqCst) - 1;
+        metrics::gauge!(PASSWORD_HASH_QUEUE_DEPTH_METRIC).set(depth as f64);
+        job();
+    }
+}
+
+// Whether an error was caused, anywhere down its chain, by the pool turning
+// work away. Such errors are answered with a 503 for the client to retry.
+pub fn is_hashing_overloaded(e: &(dyn Error + 'static)) -> bool {
+    let mut current = Some(e);
+    while let Some(e) = current {
+        if matches!(
+            e.downcast_ref::<HashingPoolError>(),
+            Some(HashingPoolError::Overloaded)
+        ) {
+            return true;
+        }
+        current = e.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Barrier, time::Duration};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_runs_work_on_pool_threads() {
+        let pool = HashingPool::new(2, 4);
+
+        let name = pool
+            .run(|| thread::current().name().map(str::to_owned))
+            .await
+            .unwrap();
+
+        assert!(name.unwrap().starts_with("password-hashing-"));
+    }
+
+    #[tokio::test]
+    async fn test_sheds_work_when_queue_is_full() {
+        let pool = Arc::new(HashingPool::new(1, 1));
+        let started = Arc::new(Barrier::new(2));
+        let release = Arc::new(Barrier::new(2));
+
+        // Keep the only thread busy
+        let busy = tokio::spawn({
+            let pool = pool.clone();
+            let started = started.clone();
+            let release = release.clone();
+            async move {
+                pool.run(move || {
+                    started.wait();
+                    release.wait();
+                })
+                .await
+            }
+        });
+        tokio::task::spawn_blocking({
+            let started = started.clone();
+            move || started.wait()
+        })
+        .await
+        .unwrap();
+
+        // Fill the queue
+        let queued = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(|| 1).await }
+        });
+        while pool.queued.load(Ordering::SeqCst) == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        let shed = pool.run(|| 2).await;
+        assert!(matches!(shed, Err(HashingPoolError::Overloaded)));
+        assert!(is_hashing_overloaded(&shed.unwrap_err()));
+
+        tokio::task::spawn_blocking(move || release.wait())
+            .await
+            .unwrap();
+        assert!(busy.await.unwrap().is_ok());
+        assert_eq!(queued.await.unwrap().unwrap(), 1);
+    }
+}
@@ -0,0 +1,130 @@
+use axum::{
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::ErrorResponse;
+
+// Drop-in for axum's Json that reports a body it can't read as an
+// ErrorResponse, like every other client error, rather than as plain text.
+// Statuses stay as axum sets them: 400 for broken JSON, 415 without a JSON
+// content type and 422 for JSON that doesn't match the request type.
+#[derive(Debug)]
+pub struct Json<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, S> FromRequest<S> for Json<T>
+where
+    axum::Json<T>: FromRequest<S, Rejection = JsonRejection>,
+    S: Send + Sync,
+{
+    type Rejection = JsonError;
+
+    async fn from_request(
+        request: Request,
+        state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(request, state)
+            .await
+            .map(|axum::Json(value)| Json(value))
+            .map_err(JsonError)
+    }
+}
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        axum::Json(self.0).into_response()
+    }
+}
+
+#[derive(Debug)]
+pub struct JsonError(JsonRejection);
+
+impl JsonError {
+    pub fn code(&self) -> &'static str {
+        match self.0 {
+            JsonRejection::JsonDataError(_) => "invalid_body",
+            JsonRejection::JsonSyntaxError(_) => "malformed_json",
+            JsonRejection::MissingJsonContentType(_) => {
+                "unsupported_media_type"
+            }
+            _ => "unreadable_body",
+        }
+    }
+}
+
+impl IntoResponse for JsonError {
+    fn into_response(self) -> Response {
+        tracing::debug!("Rejected request body: {}", self.0.body_text());
+        let body = ErrorResponse::new(self.code(), self.0.body_text());
+        (self.0.status(), axum::Json(body)).into_response()
+    }
+}
+
+// For `Option<Option<T>>` request fields, with `#[serde(default)]`: tells a
+// null field, Some(None), apart from a missing one, None
+pub fn deserialize_present<'de, D, T>(
+    deserializer: D,
+) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Deserialize<'de>,
+{
+    T::deserialize(deserializer).map(Some)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::header};
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Named {
+        #[allow(dead_code)]
+        name: String,
+    }
+
+    async fn extract(
+        content_type: Option<&str>,
+        body: &'static str,
+    ) -> Result<Json<Named>, JsonError> {
+        let mut request = Request::builder().method("POST").uri("/");
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        let request = request.body(Body::from(body)).unwrap();
+        Json::<Named>::from_request(request, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_accepts_valid_body() {
+        assert!(extract(Some("application/json"), r#"{"name": "Ted"}"#)
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejections_have_codes() {
+        for (content_type, body, status, code) in [
+            (
+                Some("application/json"),
+                r#"{"name": 1}"#,
+                422,
+                "invalid_body",
+            ),
+            (
+                Some("application/json"),
+                r#"{"name": "#,
+                400,
+                "malformed_json",
+            ),
+            (None, r#"{"name": "Ted"}"#, 415, "unsupported_media_type"),
+        ] {
+            let error = extract(content_type, body).await.expect_err(body);
+            assert_eq!(error.code(), code);
+            assert_eq!(error.into_response().status().as_u16(), status);
+        }
+    }
+}
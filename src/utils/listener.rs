@@ -0,0 +1,109 @@
+use std::io;
+
+use tokio::net::{TcpListener, TcpSocket};
+
+// systemd passes sockets from this descriptor on
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: i32 = 3;
+
+// The socket to serve on. One passed in by systemd socket activation is used
+// as it is, so the socket outlives restarts. Otherwise the address is bound,
+// with SO_REUSEPORT if asked, so a new instance can bind it while the old
+// one is still draining.
+pub async fn bind_listener(
+    address: &str,
+    reuse_port: bool,
+) -> io::Result<TcpListener> {
+    #[cfg(unix)]
+    if let Some(listener) = systemd_listener()? {
+        return TcpListener::from_std(listener);
+    }
+
+    if !reuse_port {
+        return TcpListener::bind(address).await;
+    }
+
+    let address =
+        tokio::net::lookup_host(address)
+            .await?
+            .next()
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "No address to bind",
+                )
+            })?;
+    let socket = if address.is_ipv4() {
+        TcpSocket::new_v4()?
+    } else {
+        TcpSocket::new_v6()?
+    };
+    socket.set_reuseaddr(true)?;
+    #[cfg(unix)]
+    socket.set_reuseport(true)?;
+    socket.bind(address)?;
+    socket.listen(1024)
+}
+
+// The first socket systemd passed this process, if it passed any
+#[cfg(unix)]
+fn systemd_listener() -> io::Result<Option<std::net::TcpListener>> {
+    use std::os::fd::FromRawFd;
+
+    let listen_pid = std::env::var("LISTEN_PID").ok();
+    let listen_fds = std::env::var("LISTEN_FDS").ok();
+    if passed_fds(
+        listen_pid.as_deref(),
+        listen_fds.as_deref(),
+        std::process::id(),
+    ) == 0
+    {
+        return Ok(None);
+    }
+
+    // Safety: systemd hands over the descriptors from SD_LISTEN_FDS_START
+    // for this process alone, and nothing else takes ownership of them
+    let listener =
+        unsafe { std::net::TcpListener::from_raw_fd(SD_LISTEN_FDS_START) };
+    listener.set_nonblocking(true)?;
+    Ok(Some(listener))
+}
+
+// How many sockets systemd passed. They're only ours if LISTEN_PID names
+// this process, as the variables are inherited by anything it starts.
+#[cfg(unix)]
+fn passed_fds(
+    listen_pid: Option<&str>,
+    listen_fds: Option<&str>,
+    pid: u32,
+) -> u32 {
+    match listen_pid.and_then(|listen_pid| listen_pid.parse::<u32>().ok()) {
+        Some(listen_pid) if listen_pid == pid => listen_fds
+            .and_then(|listen_fds| listen_fds.parse().ok())
+            .unwrap_or(0),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reuse_port_lets_two_listeners_share_an_address() {
+        let first = bind_listener("127.0.0.1:0", true).await.unwrap();
+        let address = first.local_addr().unwrap().to_string();
+
+        assert!(bind_listener(&address, true).await.is_ok());
+        assert!(bind_listener(&address, false).await.is_err());
+    }
+
+    #[test]
+    fn test_passed_fds_only_counts_sockets_for_this_process() {
+        assert_eq!(passed_fds(Some("42"), Some("1"), 42), 1);
+        assert_eq!(passed_fds(Some("41"), Some("1"), 42), 0);
+        assert_eq!(passed_fds(None, Some("1"), 42), 0);
+        assert_eq!(passed_fds(Some("42"), None, 42), 0);
+        assert_eq!(passed_fds(Some("42"), Some("none"), 42), 0);
+    }
+}
@@ -0,0 +1,32 @@
+use axum::http::{header::ACCEPT_LANGUAGE, HeaderMap};
+
+use crate::{
+    domain::{Locale, UserId, UserStoreError},
+    AppState,
+};
+
+// The locale to format a response in: the user's own preference, then the
+// request's Accept-Language header, then the default
+#[tracing::instrument(name = "Resolve locale", skip_all)]
+pub async fn resolve_locale(
+    state: &AppState,
+    user_id: &UserId,
+    headers: &HeaderMap,
+) -> Result<Locale, UserStoreError> {
+    let preferred =
+        match state.user_store.read().await.get_locale(user_id).await {
+            Ok(locale) => locale,
+            // The caller decides what a missing user means
+            Err(UserStoreError::UserNotFound) => None,
+            Err(e) => return Err(e),
+        };
+
+    Ok(preferred
+        .or_else(|| {
+            headers
+                .get(ACCEPT_LANGUAGE)
+                .and_then(|header| header.to_str().ok())
+                .and_then(Locale::from_accept_language)
+        })
+        .unwrap_or_default())
+}
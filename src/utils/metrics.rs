@@ -0,0 +1,65 @@
+use std::{future::Future, sync::LazyLock, time::Instant};
+
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
+use tracing::Span;
+
+use crate::utils::constants::SLOW_QUERY_THRESHOLD;
+
+pub const QUERY_DURATION_METRIC: &str = "db_query_duration_seconds";
+pub const SLOW_QUERY_METRIC: &str = "db_slow_queries_total";
+pub const JOB_LEASE_ACQUIRED_METRIC: &str = "job_lease_acquired_total";
+pub const JOB_LEASE_CONTENDED_METRIC: &str = "job_lease_contended_total";
+pub const RETENTION_ROWS_DELETED_METRIC: &str = "retention_rows_deleted_total";
+pub const PASSWORD_HASH_QUEUE_DEPTH_METRIC: &str = "password_hash_queue_depth";
+pub const PASSWORD_HASH_SHED_METRIC: &str = "password_hash_shed_total";
+
+// The recorder is process-wide, but the test harness builds an application per
+// test, so install it once and share the handle.
+pub static METRICS_HANDLE: LazyLock<PrometheusHandle> = LazyLock::new(|| {
+    PrometheusBuilder::new()
+        .install_recorder()
+        .expect("Failed to install metrics recorder")
+});
+
+// Time a database call under a stable operation name. Calls slower than
+// SLOW_QUERY_THRESHOLD are counted and logged inside the caller's span.
+pub async fn timed_query<F: Future>(
+    operation: &'static str,
+    query: F,
+) -> F::Output {
+    let start = Instant::now();
+    let output = query.await;
+    let elapsed = start.elapsed();
+
+    metrics::histogram!(QUERY_DURATION_METRIC, "operation" => operation)
+        .record(elapsed.as_secs_f64());
+
+    if elapsed >= *SLOW_QUERY_THRESHOLD {
+        metrics::counter!(SLOW_QUERY_METRIC, "operation" => operation)
+            .increment(1);
+        tracing::warn!(
+            operation,
+            elapsed_ms = elapsed.as_millis() as u64,
+            span = Span::current().metadata().map(|m| m.name()),
+            "Slow query"
+        );
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_timed_query_records_duration() {
+        LazyLock::force(&METRICS_HANDLE);
+        let output = timed_query("test_operation", async { 42 }).await;
+        assert_eq!(output, 42);
+
+        let rendered = METRICS_HANDLE.render();
+        assert!(rendered.contains(QUERY_DURATION_METRIC));
+        assert!(rendered.contains(r#"operation="test_operation""#));
+    }
+}
@@ -1,4 +1,26 @@
+pub mod access;
+pub mod admin;
 pub mod auth;
+#[cfg(feature = "chaos")]
+pub mod chaos;
 pub mod constants;
+pub mod database;
+pub mod deadline;
+pub mod deprecation;
+pub mod email_replies;
+pub mod email_tracking;
+pub mod hashing_pool;
+pub mod json;
+pub mod listener;
+pub mod locale;
+pub mod metrics;
+pub mod policy_consent;
 pub mod project;
+pub mod pwned_passwords;
+pub mod query;
+pub mod quota;
+pub mod request_capture;
+pub mod residency;
 pub mod tracing;
+pub mod twilio;
+pub mod warm_up;
@@ -0,0 +1,56 @@
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use color_eyre::eyre::eyre;
+
+use crate::{
+    domain::{AuthAPIError, UserStoreError},
+    utils::auth::Claims,
+    AppState,
+};
+
+// Routes that need the current policy version accepted. Account management
+// stays available so users who don't agree can still delete their account.
+const CONSENT_REQUIRED_PREFIXES: &[&str] = &["/projects/", "/me/"];
+
+// Turn away signed-in users who haven't accepted the required version of the
+// terms of service and privacy policy, until they accept it through
+// /auth/accept-policy. Runs after the access guard, which has already turned
+// away requests without a valid session.
+pub async fn require_policy_acceptance(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let required = match state.settings.required_policy_version {
+        Some(required) => required,
+        None => return next.run(request).await,
+    };
+    let path = request.uri().path();
+    if !CONSENT_REQUIRED_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix))
+    {
+        return next.run(request).await;
+    }
+
+    let user_id = match request.extensions().get::<Claims>() {
+        Some(claims) => claims.id.clone(),
+        None => return next.run(request).await,
+    };
+
+    let accepted = state
+        .user_store
+        .read()
+        .await
+        .get_accepted_policy_version(&user_id)
+        .await;
+    match accepted {
+        Ok(Some(version)) if version >= required => next.run(request).await,
+        Ok(_) => AuthAPIError::PolicyNotAccepted(required).into_response(),
+        Err(UserStoreError::UserNotFound) => next.run(request).await,
+        Err(e) => AuthAPIError::UnexpectedError(eyre!(e)).into_response(),
+    }
+}
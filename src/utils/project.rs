@@ -22,7 +22,7 @@ pub async fn check_permissions_for_project(
     let (_project_id, _project_name) = user_projects
         .iter()
         .find(|(id, _)| id == project_id)
-        .ok_or(ProjectAPIError::IDNotFoundError(*project_id.as_ref()))?;
+        .ok_or_else(|| ProjectAPIError::ProjectNotFound(project_id.clone()))?;
 
     Ok(())
 }
@@ -0,0 +1,45 @@
+#[cfg(feature = "pwned-passwords")]
+use std::sync::LazyLock;
+
+use crate::domain::{Password, ValidationError};
+#[cfg(feature = "pwned-passwords")]
+use crate::{
+    services::pwned_passwords::PwnedPasswordsClient,
+    utils::constants::{prod, PWNED_PASSWORDS_URL},
+};
+
+#[cfg(feature = "pwned-passwords")]
+static PWNED_PASSWORDS_CLIENT: LazyLock<PwnedPasswordsClient> =
+    LazyLock::new(|| {
+        let http_client = reqwest::Client::builder()
+            .timeout(prod::pwned_passwords::TIMEOUT)
+            .build()
+            .expect("Failed to build HIBP client");
+        PwnedPasswordsClient::new(PWNED_PASSWORDS_URL.clone(), http_client)
+    });
+
+// Turn away a new password that has been in a data breach. Only checked when
+// built with the pwned-passwords feature. If Have I Been Pwned can't be
+// reached the password is let through, so an outage there doesn't stop
+// anyone signing up or resetting their password.
+pub async fn reject_pwned_password(
+    password: &Password,
+) -> Result<(), ValidationError> {
+    #[cfg(feature = "pwned-passwords")]
+    match PWNED_PASSWORDS_CLIENT.is_pwned(password).await {
+        Ok(true) => {
+            return Err(ValidationError::new(String::from(
+                "This password has appeared in a data breach. Please choose \
+                 another.",
+            )))
+        }
+        Ok(false) => {}
+        Err(e) => {
+            tracing::warn!("Couldn't check password against HIBP: {:?}", e)
+        }
+    }
+    #[cfg(not(feature = "pwned-passwords"))]
+    let _ = password;
+
+    Ok(())
+}
@@ -0,0 +1,154 @@
+use std::ops::Deref;
+
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+
+use crate::{ErrorResponse, FieldError};
+
+// Like axum's Query, but a query string that doesn't fit T is reported as an
+// ErrorResponse naming the parameter at fault, rather than as plain text
+#[derive(Debug)]
+pub struct ValidatedQuery<T>(pub T);
+
+#[async_trait::async_trait]
+impl<T, S> FromRequestParts<S> for ValidatedQuery<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = QueryError;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        let query = parts.uri.query().unwrap_or_default();
+        let deserializer = serde_urlencoded::Deserializer::new(
+            form_urlencoded::parse(query.as_bytes()),
+        );
+        serde_path_to_error::deserialize(deserializer)
+            .map(ValidatedQuery)
+            .map_err(|e| {
+                let field = e.path().to_string();
+                let message = e.into_inner().to_string();
+                QueryError::new(field, message)
+            })
+    }
+}
+
+impl<T> Deref for ValidatedQuery<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+#[derive(Debug)]
+pub struct QueryError {
+    pub field: Option<String>,
+    pub message: String,
+}
+
+impl QueryError {
+    fn new(path: String, message: String) -> Self {
+        // serde reports a missing field at the level above it, so take the
+        // name from its message instead
+        let field = match missing_field(&message) {
+            Some(field) => Some(field.to_owned()),
+            None if path != "." => Some(path),
+            None => None,
+        };
+        Self { field, message }
+    }
+}
+
+fn missing_field(message: &str) -> Option<&str> {
+    message
+        .strip_prefix("missing field `")
+        .and_then(|rest| rest.strip_suffix('`'))
+}
+
+impl IntoResponse for QueryError {
+    fn into_response(self) -> Response {
+        tracing::debug!("Rejected query string: {}", self.message);
+        let error = match &self.field {
+            Some(field) => {
+                format!("Invalid query parameter `{}`: {}", field, self.message)
+            }
+            None => format!("Invalid query string: {}", self.message),
+        };
+        let details = self
+            .field
+            .map(|field| FieldError {
+                field,
+                message: self.message,
+            })
+            .into_iter()
+            .collect();
+        let body =
+            ErrorResponse::new("invalid_query", error).with_details(details);
+        (StatusCode::BAD_REQUEST, Json(body)).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::Request;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize)]
+    struct Params {
+        #[serde(rename = "memberId")]
+        member_id: uuid::Uuid,
+        #[serde(default)]
+        limit: Option<u32>,
+    }
+
+    async fn extract(
+        query: &str,
+    ) -> Result<ValidatedQuery<Params>, QueryError> {
+        let (mut parts, _) = Request::builder()
+            .uri(format!("/?{}", query))
+            .body(())
+            .unwrap()
+            .into_parts();
+        ValidatedQuery::<Params>::from_request_parts(&mut parts, &()).await
+    }
+
+    #[tokio::test]
+    async fn test_accepts_valid_query() {
+        let params =
+            extract("memberId=be9915f0-a4c2-48fb-977b-9f4f959c5729&limit=5")
+                .await
+                .unwrap();
+        assert_eq!(params.limit, Some(5));
+        assert_eq!(
+            params.member_id.to_string(),
+            "be9915f0-a4c2-48fb-977b-9f4f959c5729"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_names_missing_field() {
+        let error = extract("").await.unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("memberId"));
+        assert_eq!(error.message, "missing field `memberId`");
+    }
+
+    #[tokio::test]
+    async fn test_names_invalid_field() {
+        let error =
+            extract("memberId=be9915f0-a4c2-48fb-977b-9f4f959c5729&limit=x")
+                .await
+                .unwrap_err();
+        assert_eq!(error.field.as_deref(), Some("limit"));
+        assert_eq!(error.into_response().status(), StatusCode::BAD_REQUEST);
+    }
+}
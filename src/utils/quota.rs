@@ -0,0 +1,40 @@
+use axum::response::AppendHeaders;
+
+use crate::{
+    domain::{QuotaResource, UserId},
+    utils::constants::{QUOTA_LIMIT_HEADER, QUOTA_REMAINING_HEADER},
+    AppState,
+};
+
+pub type QuotaHeaders = AppendHeaders<Vec<(&'static str, String)>>;
+
+// Tells clients how much of a quota is left after creating something, so
+// they can warn users before they reach it. Quotas aren't enforced yet, so
+// failing to work them out leaves the headers off rather than failing a
+// request that has already succeeded.
+#[tracing::instrument(name = "Get quota headers", skip_all)]
+pub async fn quota_headers(
+    state: &AppState,
+    user_id: &UserId,
+    resource: QuotaResource,
+) -> QuotaHeaders {
+    let usage = match state
+        .project_store
+        .write()
+        .await
+        .get_quota_usage(user_id)
+        .await
+    {
+        Ok(usage) => usage,
+        Err(e) => {
+            tracing::warn!("Failed to get quota usage: {:?}", e);
+            return AppendHeaders(Vec::new());
+        }
+    };
+
+    let quota = usage.quota(resource);
+    AppendHeaders(vec![
+        (QUOTA_LIMIT_HEADER, quota.limit.to_string()),
+        (QUOTA_REMAINING_HEADER, quota.remaining().to_string()),
+    ])
+}
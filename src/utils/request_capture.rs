@@ -0,0 +1,106 @@
+use axum::{
+    body::{to_bytes, Body},
+    extract::{Request, State},
+    http::{HeaderMap, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use axum_extra::extract::CookieJar;
+use secrecy::Secret;
+
+use crate::{
+    domain::{CapturedRequest, UserId},
+    utils::{auth::decode_claims, constants::JWT_COOKIE_NAME},
+    AppState,
+};
+
+// The most of a captured user's request that's read, matching the limit
+// axum's extractors would have applied to it
+const MAX_REQUEST_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+// Record requests made with the sessions of users an admin is capturing,
+// and the responses they got, with secrets redacted. Failing to store a
+// capture doesn't fail the request, but both bodies are read in full first:
+// a request body over the limit gets a 413, as the route's extractors would
+// have given it, and a response body that can't be read becomes a 500.
+// Admin routes are never captured: they're authorised by the admin key, not
+// the session, and what they return is no business of the user's capture.
+pub async fn capture_requests(
+    State(state): State<AppState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    if request.uri().path().starts_with("/admin/") {
+        return next.run(request).await;
+    }
+    let user_id = match capturing_user(&state, request.headers()).await {
+        Some(user_id) => user_id,
+        None => return next.run(request).await,
+    };
+    let method = request.method().to_string();
+    let path = request.uri().path().to_owned();
+    let query = request.uri().query().map(str::to_owned);
+
+    let (parts, body) = request.into_parts();
+    let request_body = match to_bytes(body, MAX_REQUEST_BODY_BYTES).await {
+        Ok(body) => body,
+        Err(_) => return StatusCode::PAYLOAD_TOO_LARGE.into_response(),
+    };
+    let response = next
+        .run(Request::from_parts(parts, Body::from(request_body.clone())))
+        .await;
+
+    let (parts, body) = response.into_parts();
+    let response_body = match to_bytes(body, usize::MAX).await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!("Failed to read response to capture: {:?}", e);
+            return StatusCode::INTERNAL_SERVER_ERROR.into_response();
+        }
+    };
+    let captured = CapturedRequest::new(
+        state.clock.now(),
+        &method,
+        &path,
+        query.as_deref(),
+        parts.status.as_u16(),
+        &request_body,
+        &response_body,
+    );
+    if let Err(e) = state
+        .request_capture_store
+        .write()
+        .await
+        .record(&user_id, &captured)
+        .await
+    {
+        tracing::warn!("Failed to record captured request: {:?}", e);
+    }
+
+    Response::from_parts(parts, Body::from(response_body))
+}
+
+// The user whose session made the request, if their requests are being
+// captured. Whether the session is still valid is left to the route.
+async fn capturing_user(
+    state: &AppState,
+    headers: &HeaderMap,
+) -> Option<UserId> {
+    let jar = CookieJar::from_headers(headers);
+    let token = Secret::new(jar.get(JWT_COOKIE_NAME)?.value().to_owned());
+    let user_id = decode_claims(&token, &state.clock).ok()?.id;
+
+    match state
+        .request_capture_store
+        .read()
+        .await
+        .is_capturing(&user_id)
+        .await
+    {
+        Ok(capturing) => capturing.then_some(user_id),
+        Err(e) => {
+            tracing::warn!("Failed to check for request capture: {:?}", e);
+            None
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use axum::response::AppendHeaders;
+
+use crate::{
+    domain::{DataRegion, UserId, UserStoreError},
+    utils::constants::DATA_REGION_HEADER,
+    AppState,
+};
+
+// Where the user's data has to stay, if anywhere in particular
+#[tracing::instrument(name = "Get data region", skip_all)]
+pub async fn data_region(
+    state: &AppState,
+    user_id: &UserId,
+) -> Result<Option<DataRegion>, UserStoreError> {
+    match state.user_store.read().await.get_data_region(user_id).await {
+        Ok(region) => Ok(region),
+        // The caller decides what a missing user means
+        Err(UserStoreError::UserNotFound) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+pub type DataRegionHeader = AppendHeaders<Option<(&'static str, &'static str)>>;
+
+// Tags an export with the data region of the user it belongs to, so it can
+// be stored accordingly. Nothing is added for users without one.
+pub fn data_region_header(region: Option<DataRegion>) -> DataRegionHeader {
+    AppendHeaders(region.map(|region| (DATA_REGION_HEADER, region.as_str())))
+}
@@ -1,13 +1,19 @@
 use std::time::Duration;
 
 use axum::{body::Body, extract::Request, response::Response};
-use color_eyre::eyre::Result;
+use color_eyre::eyre::{eyre, Result};
+use tokio::task::JoinHandle;
 use tracing::{Level, Span};
 use tracing_error::ErrorLayer;
 use tracing_subscriber::prelude::*;
-use tracing_subscriber::{fmt, EnvFilter};
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
 
-pub fn init_tracing() -> Result<()> {
+use crate::domain::ValidationError;
+
+// Used by SIGUSR1 to turn on debug logs everywhere
+const SIGNAL_LOG_FILTER: &str = "debug";
+
+pub fn init_tracing() -> Result<LogFilter> {
     // Create a formatting layer for tracing output with a compact format
     let fmt_layer = fmt::layer().compact();
 
@@ -16,6 +22,9 @@ pub fn init_tracing() -> Result<()> {
     // If it fails, default to the "info" log level
     let filter_layer = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new("info"))?;
+    let initial = filter_layer.to_string();
+    // Wrapped so the filter can be swapped while the server runs
+    let (filter_layer, handle) = reload::Layer::new(filter_layer);
 
     // Build the tracing subscriber registry with the formatting layer,
     // the filter layer, and the error layer for enhanced error reporting
@@ -25,7 +34,76 @@ pub fn init_tracing() -> Result<()> {
         .with(ErrorLayer::default()) // Add the error layer to capture error contexts
         .init(); // Initialize the tracing subscriber
 
-    Ok(())
+    Ok(LogFilter { handle, initial })
+}
+
+// The filter deciding which logs are kept, which can be changed without a
+// restart, e.g. to see one module's DEBUG logs during an investigation
+#[derive(Debug, Clone)]
+pub struct LogFilter {
+    handle: reload::Handle<EnvFilter, Registry>,
+    // The filter the server started with
+    initial: String,
+}
+
+impl LogFilter {
+    pub fn current(&self) -> Result<String> {
+        self.handle
+            .with_current(|filter| filter.to_string())
+            .map_err(|e| eyre!(e))
+    }
+
+    pub fn set(&self, filter: EnvFilter) -> Result<()> {
+        self.handle.reload(filter).map_err(|e| eyre!(e))
+    }
+
+    // Back to the filter the server started with
+    pub fn reset(&self) -> Result<()> {
+        self.set(EnvFilter::try_new(&self.initial)?)
+    }
+}
+
+// Directives in RUST_LOG's format, such as "info,rota_manager::services=debug"
+pub fn parse_log_filter(
+    directives: &str,
+) -> Result<EnvFilter, ValidationError> {
+    EnvFilter::builder()
+        .parse(directives)
+        .map_err(|e| ValidationError::new(format!("Invalid log filter: {}", e)))
+}
+
+// Each SIGUSR1 switches between debug logs everywhere and the filter the
+// server started with, for when the admin API can't be reached
+#[cfg(unix)]
+pub fn spawn_log_filter_signal(log_filter: LogFilter) -> JoinHandle<()> {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tokio::spawn(async move {
+        let mut signals = match signal(SignalKind::user_defined1()) {
+            Ok(signals) => signals,
+            Err(e) => {
+                tracing::error!("failed to install SIGUSR1 handler: {}", e);
+                return;
+            }
+        };
+        let mut debugging = false;
+        while signals.recv().await.is_some() {
+            debugging = !debugging;
+            let result = match debugging {
+                true => EnvFilter::try_new(SIGNAL_LOG_FILTER)
+                    .map_err(|e| eyre!(e))
+                    .and_then(|filter| log_filter.set(filter)),
+                false => log_filter.reset(),
+            };
+            match result {
+                Ok(()) => tracing::warn!(
+                    "log filter changed by SIGUSR1 to {}",
+                    log_filter.current().unwrap_or_default()
+                ),
+                Err(e) => tracing::error!("failed to change log filter: {}", e),
+            }
+        }
+    })
 }
 
 pub fn make_span_with_request_id(request: &Request<Body>) -> Span {
@@ -66,3 +144,37 @@ pub fn on_response(response: &Response, latency: Duration, _span: &Span) {
         }
     };
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_log_filter_reloads() {
+        let (layer, handle) =
+            reload::Layer::new(EnvFilter::try_new("info").unwrap());
+        // Reloading needs the layer to still be in use
+        let _subscriber = tracing_subscriber::registry().with(layer);
+        let log_filter = LogFilter {
+            handle,
+            initial: String::from("info"),
+        };
+
+        log_filter
+            .set(parse_log_filter("info,rota_manager::services=debug").unwrap())
+            .unwrap();
+        assert!(log_filter
+            .current()
+            .unwrap()
+            .contains("rota_manager::services=debug"));
+
+        log_filter.reset().unwrap();
+        assert_eq!(log_filter.current().unwrap(), "info");
+    }
+
+    #[test]
+    fn test_parse_log_filter() {
+        assert!(parse_log_filter("info,rota_manager=debug").is_ok());
+        assert!(parse_log_filter("rota_manager=loudest").is_err());
+    }
+}
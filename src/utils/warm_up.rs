@@ -0,0 +1,92 @@
+use std::{fmt::Debug, future::Future, sync::LazyLock, time::Instant};
+
+use secrecy::Secret;
+
+use crate::{
+    domain::{BannedTokenStoreError, UserStoreError},
+    utils::{
+        auth::{JWT_DECODING_KEY, JWT_ENCODING_KEY},
+        constants::{
+            APP_SERVICE_EXTERNAL_ADDRESS, ARGON2_PARAMS, SLOW_QUERY_THRESHOLD,
+            TWO_FA_CODE_REGEX,
+        },
+        hashing_pool::HASHING_POOL,
+    },
+    AppState,
+};
+
+// Do the work the first requests would otherwise pay for before serving
+// any: reading configuration, deriving the JWT keys, starting the password
+// hashing threads, opening PostgreSQL connections with the login lookup
+// prepared, and reaching Redis. Email and print templates are compiled into
+// the binary, so need nothing here. A step that fails is logged and skipped,
+// since requests retry a store that isn't reachable yet as usual.
+#[tracing::instrument(name = "Warm up", skip_all)]
+pub async fn warm_up(state: &AppState) {
+    let start = Instant::now();
+
+    timed_step("configuration", async {
+        LazyLock::force(&TWO_FA_CODE_REGEX);
+        lazy_static::initialize(&APP_SERVICE_EXTERNAL_ADDRESS);
+        lazy_static::initialize(&SLOW_QUERY_THRESHOLD);
+        lazy_static::initialize(&ARGON2_PARAMS);
+        state.settings.current_policy();
+        Ok::<_, ()>(())
+    })
+    .await;
+
+    timed_step("jwt keys", async {
+        LazyLock::force(&JWT_ENCODING_KEY);
+        LazyLock::force(&JWT_DECODING_KEY);
+        Ok::<_, ()>(())
+    })
+    .await;
+
+    timed_step("password hashing threads", async {
+        LazyLock::force(&HASHING_POOL);
+        Ok::<_, ()>(())
+    })
+    .await;
+
+    timed_step("postgres", async {
+        let connections = state.user_store.read().await.warm_up().await?;
+        tracing::info!(connections, "Opened PostgreSQL connections");
+        Ok::<_, UserStoreError>(())
+    })
+    .await;
+
+    timed_step("redis", async {
+        match state
+            .banned_token_store
+            .read()
+            .await
+            .check_token(&Secret::new(String::from("warm-up")))
+            .await
+        {
+            Ok(()) | Err(BannedTokenStoreError::BannedToken) => Ok(()),
+            Err(e) => Err(e),
+        }
+    })
+    .await;
+
+    tracing::info!(
+        elapsed_ms = start.elapsed().as_millis() as u64,
+        "Warmed up"
+    );
+}
+
+async fn timed_step<E: Debug>(
+    step: &'static str,
+    work: impl Future<Output = Result<(), E>>,
+) {
+    let start = Instant::now();
+    let result = work.await;
+    let elapsed_ms = start.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(()) => tracing::info!(step, elapsed_ms, "Warm-up step done"),
+        Err(e) => {
+            tracing::warn!(step, elapsed_ms, "Warm-up step failed: {:?}", e)
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use reqwest::{Client, Method, Response};
+use rota_manager::{
+    utils::{
+        access::{Role, RouteAccess, ROUTE_ACCESS},
+        constants::ADMIN_KEY_HEADER,
+    },
+    ErrorResponse,
+};
+use test_context::test_context;
+
+use crate::helpers::{get_admin_key, get_session, TestApp};
+
+const ROLES: [Role; 3] = [Role::Anonymous, Role::User, Role::Admin];
+const AUTH_ERRORS: [&str; 3] =
+    ["Missing token", "Invalid token", "Authentication error"];
+
+async fn call_as(
+    app: &mut TestApp,
+    route: &RouteAccess,
+    role: Role,
+) -> Response {
+    let url = format!("{}{}", app.address, route.path);
+    let mut request = match role {
+        Role::Anonymous => Client::new().request(route.method.clone(), url),
+        Role::User => {
+            // A fresh session each time, as calling logout or delete-user
+            // ends it
+            get_session(app, false).await;
+            app.http_client.request(route.method.clone(), url)
+        }
+        Role::Admin => Client::new()
+            .request(route.method.clone(), url)
+            .header(ADMIN_KEY_HEADER, get_admin_key()),
+    };
+    if route.method != Method::GET {
+        request = request.json(&serde_json::json!({}));
+    }
+    request.send().await.expect("Failed to execute request")
+}
+
+// The auth error in the response, if it has one
+async fn auth_error(response: Response) -> Option<String> {
+    response
+        .json::<ErrorResponse>()
+        .await
+        .ok()
+        .map(|body| body.error)
+        .filter(|error| AUTH_ERRORS.contains(&error.as_str()))
+}
+
+// Every route with every role, so a route that loses its guard, or a guard
+// that turns away the wrong role, fails here
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_enforce_route_access_for_every_role(app: &mut TestApp) {
+    for route in ROUTE_ACCESS {
+        for role in ROLES {
+            let response = call_as(app, route, role).await;
+            let status = response.status().as_u16();
+            let error = auth_error(response).await;
+
+            if route.roles.contains(&role) {
+                assert_eq!(
+                    error, None,
+                    "{:?} should be allowed to call {} {}",
+                    role, route.method, route.path
+                );
+            } else {
+                assert!(
+                    matches!(status, 400 | 401) && error.is_some(),
+                    "{:?} should not be allowed to call {} {}, got {}",
+                    role,
+                    route.method,
+                    route.path,
+                    status
+                );
+            }
+        }
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_405_for_unlisted_method(app: &mut TestApp) {
+    let response = app
+        .http_client
+        .patch(format!("{}/projects/list", app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 405);
+}
@@ -0,0 +1,102 @@
+use crate::helpers::{
+    get_random_email, BootstrapTestApp, TestApp, BOOTSTRAP_TOKEN,
+};
+use rota_manager::{routes::admin::BootstrapResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(BootstrapTestApp)]
+#[tokio::test]
+async fn should_create_admin_once(ctx: &mut BootstrapTestApp) {
+    let app = &ctx.0;
+    let email = get_random_email();
+
+    let response = app
+        .post_bootstrap(&json!({
+            "token": BOOTSTRAP_TOKEN,
+            "email": email,
+            "password": "password123"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(
+        response.json::<BootstrapResponse>().await.unwrap().message,
+        "Admin account created"
+    );
+
+    let is_admin: bool =
+        sqlx::query_scalar("SELECT is_admin FROM users WHERE email = $1")
+            .bind(&email)
+            .fetch_one(&app.pg_pool)
+            .await
+            .unwrap();
+    assert!(is_admin);
+
+    let response = app
+        .post_bootstrap(&json!({
+            "token": BOOTSTRAP_TOKEN,
+            "email": get_random_email(),
+            "password": "password123"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "This instance has already been set up"
+    );
+}
+
+#[test_context(BootstrapTestApp)]
+#[tokio::test]
+async fn should_refuse_once_users_exist(ctx: &mut BootstrapTestApp) {
+    let app = &ctx.0;
+    let response = app
+        .post_signup(&json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_bootstrap(&json!({
+            "token": BOOTSTRAP_TOKEN,
+            "email": get_random_email(),
+            "password": "password123"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[test_context(BootstrapTestApp)]
+#[tokio::test]
+async fn should_reject_wrong_token(ctx: &mut BootstrapTestApp) {
+    let app = &ctx.0;
+    for (token, status) in [
+        ("5e90ca28-e1ad-4795-a190-089959c16e0b", 401),
+        ("not a token", 400),
+    ] {
+        let response = app
+            .post_bootstrap(&json!({
+                "token": token,
+                "email": get_random_email(),
+                "password": "password123"
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), status, "Failed for {}", token);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_be_disabled_without_token(app: &mut TestApp) {
+    let response = app
+        .post_bootstrap(&json!({
+            "token": BOOTSTRAP_TOKEN,
+            "email": get_random_email(),
+            "password": "password123"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+}
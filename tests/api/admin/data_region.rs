@@ -0,0 +1,115 @@
+use crate::helpers::{
+    add_new_project, get_admin_key, get_session, ResidencyTestApp, TestApp,
+};
+use rota_manager::{
+    domain::{DataRegion, Email},
+    routes::admin::DataRegionResponse,
+};
+use secrecy::Secret;
+use serde_json::json;
+use test_context::test_context;
+
+async fn get_user_id(app: &TestApp, email: &str) -> uuid::Uuid {
+    let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+    let user_store = app.user_store.read().await;
+    *user_store.get_user(&email).await.unwrap().id.as_ref()
+}
+
+async fn set_data_region(app: &TestApp, user_id: uuid::Uuid, region: &str) {
+    let response = app
+        .put_data_region(
+            Some(&get_admin_key()),
+            &json!({"userId": user_id, "region": region}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<DataRegionResponse>().await.unwrap(),
+        DataRegionResponse {
+            user_id,
+            region: Some(DataRegion::parse(region).unwrap()),
+        }
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_tag_exports_with_data_region(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+
+    let response = app.get_calendar_feed().await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().get("X-Data-Region").is_none());
+
+    set_data_region(app, user_id, "eu").await;
+
+    let response = app.get_calendar_feed().await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["X-Data-Region"], "eu");
+
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let response = app
+        .get_printed_rota(&[("projectId", &project_id), ("format", "csv")])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["X-Data-Region"], "eu");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_regions_and_users(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+
+    let response = app
+        .put_data_region(
+            Some(&get_admin_key()),
+            &json!({"userId": user_id, "region": "mars"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .put_data_region(
+            Some(&get_admin_key()),
+            &json!({"userId": uuid::Uuid::new_v4(), "region": "eu"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app
+        .put_data_region(
+            Some("not-the-admin-key"),
+            &json!({"userId": user_id, "region": "eu"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(ResidencyTestApp)]
+#[tokio::test]
+async fn should_block_webhooks_outside_data_region(ctx: &mut ResidencyTestApp) {
+    let app = &mut ctx.0;
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let slack = json!({"chatWebhookUrl": "https://hooks.slack.com/services/x"});
+
+    // Unrestricted until the account has a region
+    let response = app.put_project_settings(&project_id, &slack).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    set_data_region(app, user_id, "eu").await;
+
+    let response = app.put_project_settings(&project_id, &slack).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"chatWebhookUrl": "https://team.chat.example.eu/hook"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -0,0 +1,113 @@
+use crate::helpers::{
+    add_new_project, get_admin_key, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    routes::admin::DeprecatedUsageResponse,
+    utils::deprecation::{DEPRECATION_HEADER, SUNSET_HEADER},
+};
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_add_deprecation_headers_to_deprecated_endpoints(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app
+        .post_add_member(&json!({
+            "memberName": "Ted",
+            "projectId": &project_id
+        }))
+        .await;
+
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(response.headers().get(DEPRECATION_HEADER).unwrap(), "true");
+    assert!(response.headers().get(SUNSET_HEADER).is_some());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_add_deprecation_headers_to_current_endpoints(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+    let response = app.get_projects_list().await;
+
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(response.headers().get(DEPRECATION_HEADER).is_none());
+    assert!(response.headers().get(SUNSET_HEADER).is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_usage_per_client(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    // The counts are kept in Redis across test runs
+    let before = usage_count(app, "rota-web").await;
+
+    // Counted by client, whatever the version
+    for (name, client) in [("Ted", "rota-web/1.2"), ("Dougal", "rota-web/1.3")]
+    {
+        let response = app
+            .http_client
+            .post(format!("{}/projects/add-member", &app.address))
+            .header(reqwest::header::USER_AGENT, client)
+            .json(&json!({
+                "memberName": name,
+                "projectId": &project_id
+            }))
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    assert_eq!(usage_count(app, "rota-web").await, before + 2);
+}
+
+// How many times the client has called /projects/add-member
+async fn usage_count(app: &TestApp, client: &str) -> u64 {
+    let response = app.get_deprecated_usage(Some(&get_admin_key())).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response_body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<DeprecatedUsageResponse>(),
+            &response_body
+        ),
+        "response does not match schema"
+    );
+
+    let report: DeprecatedUsageResponse =
+        serde_json::from_value(response_body).unwrap();
+    let endpoint = report
+        .endpoints
+        .iter()
+        .find(|endpoint| endpoint.path == "/projects/add-member")
+        .expect("Deprecated endpoint missing from report");
+    endpoint
+        .clients
+        .iter()
+        .find(|usage| usage.client == client)
+        .map_or(0, |usage| usage.count)
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_admin_key_missing(app: &mut TestApp) {
+    let response = app.get_deprecated_usage(None).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_admin_key_incorrect(app: &mut TestApp) {
+    let response = app.get_deprecated_usage(Some("not-the-admin-key")).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,121 @@
+use crate::helpers::{
+    get_admin_key, get_random_email, InviteOnlyTestApp, TestApp,
+};
+use rota_manager::{routes::admin::InvitationResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+async fn mint_invitation(app: &TestApp, max_uses: i32) -> String {
+    let response = app
+        .post_invitation(
+            Some(&get_admin_key()),
+            &json!({ "maxUses": max_uses, "expiresInDays": 7 }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let invitation = response
+        .json::<InvitationResponse>()
+        .await
+        .expect("Could not deserialise invitation");
+    assert_eq!(invitation.max_uses, max_uses);
+    invitation.code
+}
+
+async fn signup_status(app: &TestApp, invitation_code: Option<&str>) -> u16 {
+    app.post_signup(&json!({
+        "email": get_random_email(),
+        "password": "password",
+        "requires2FA": false,
+        "invitationCode": invitation_code
+    }))
+    .await
+    .status()
+    .as_u16()
+}
+
+#[test_context(InviteOnlyTestApp)]
+#[tokio::test]
+async fn should_require_invitation_code_to_sign_up(
+    ctx: &mut InviteOnlyTestApp,
+) {
+    let app = &ctx.0;
+
+    let response = app
+        .post_signup(&json!({
+            "email": get_random_email(),
+            "password": "password",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 403);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "A valid invitation code is required to sign up"
+    );
+
+    assert_eq!(signup_status(app, Some("abcdefghijklmnop")).await, 403);
+    assert_eq!(signup_status(app, Some("not a code")).await, 400);
+}
+
+#[test_context(InviteOnlyTestApp)]
+#[tokio::test]
+async fn should_limit_invitation_uses(ctx: &mut InviteOnlyTestApp) {
+    let app = &ctx.0;
+    let code = mint_invitation(app, 2).await;
+
+    assert_eq!(signup_status(app, Some(&code)).await, 201);
+    assert_eq!(signup_status(app, Some(&code)).await, 201);
+    assert_eq!(signup_status(app, Some(&code)).await, 403);
+}
+
+#[test_context(InviteOnlyTestApp)]
+#[tokio::test]
+async fn should_not_use_up_invitation_on_failed_signup(
+    ctx: &mut InviteOnlyTestApp,
+) {
+    let app = &ctx.0;
+    let code = mint_invitation(app, 2).await;
+    let email = get_random_email();
+    let request = json!({
+        "email": email,
+        "password": "password",
+        "requires2FA": false,
+        "invitationCode": code
+    });
+
+    assert_eq!(app.post_signup(&request).await.status().as_u16(), 201);
+    assert_eq!(app.post_signup(&request).await.status().as_u16(), 409);
+    assert_eq!(signup_status(app, Some(&code)).await, 201);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_ignore_invitation_code_when_signup_is_open(app: &mut TestApp) {
+    assert_eq!(signup_status(app, None).await, 201);
+    assert_eq!(signup_status(app, Some("abcdefghijklmnop")).await, 201);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_admin_key_to_mint_invitations(app: &mut TestApp) {
+    let body = json!({ "maxUses": 1, "expiresInDays": 7 });
+
+    let response = app.post_invitation(None, &body).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app.post_invitation(Some("wrong-key"), &body).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_limits(app: &mut TestApp) {
+    for body in [
+        json!({ "maxUses": 0, "expiresInDays": 7 }),
+        json!({ "maxUses": 1, "expiresInDays": 0 }),
+        json!({ "maxUses": 1, "expiresInDays": 365 }),
+    ] {
+        let response = app.post_invitation(Some(&get_admin_key()), &body).await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", body);
+    }
+}
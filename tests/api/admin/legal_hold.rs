@@ -0,0 +1,120 @@
+use crate::helpers::{get_admin_key, get_session, TestApp};
+use rota_manager::{
+    domain::Email,
+    routes::admin::{LegalHoldListResponse, LegalHoldResponse},
+    ErrorResponse,
+};
+use secrecy::Secret;
+use serde_json::json;
+use test_context::test_context;
+
+async fn get_user_id(app: &TestApp, email: &str) -> String {
+    let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+    let user_store = app.user_store.read().await;
+    user_store
+        .get_user(&email)
+        .await
+        .unwrap()
+        .id
+        .as_ref()
+        .to_string()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_block_deleting_a_held_account(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+
+    let response = app
+        .put_legal_hold(
+            Some(&get_admin_key()),
+            &json!({"userId": &user_id, "held": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<LegalHoldResponse>().await.unwrap();
+    assert!(body.held);
+    let placed_at = body.placed_at.expect("No placedAt");
+
+    for response in [app.delete_user().await, app.anonymize_user().await] {
+        assert_eq!(response.status().as_u16(), 409);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().code,
+            "legal_hold"
+        );
+    }
+
+    // Placing it again keeps when it was first placed
+    let response = app
+        .put_legal_hold(
+            Some(&get_admin_key()),
+            &json!({"userId": &user_id, "held": true}),
+        )
+        .await;
+    let body = response.json::<LegalHoldResponse>().await.unwrap();
+    assert_eq!(body.placed_at, Some(placed_at.clone()));
+
+    let response = app.get_legal_holds(Some(&get_admin_key())).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<LegalHoldListResponse>().await.unwrap();
+    let hold = body
+        .holds
+        .iter()
+        .find(|hold| hold.user_id.to_string() == user_id)
+        .expect("Held user not listed");
+    assert_eq!(hold.email, email);
+    assert_eq!(hold.placed_at, placed_at);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_allow_deleting_once_the_hold_is_lifted(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+
+    app.put_legal_hold(
+        Some(&get_admin_key()),
+        &json!({"userId": &user_id, "held": true}),
+    )
+    .await;
+    let response = app
+        .put_legal_hold(
+            Some(&get_admin_key()),
+            &json!({"userId": &user_id, "held": false}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<LegalHoldResponse>().await.unwrap();
+    assert!(!body.held);
+    assert_eq!(body.placed_at, None);
+
+    let response = app.get_legal_holds(Some(&get_admin_key())).await;
+    let body = response.json::<LegalHoldListResponse>().await.unwrap();
+    assert!(!body
+        .holds
+        .iter()
+        .any(|hold| hold.user_id.to_string() == user_id));
+
+    let response = app.delete_user().await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_an_unknown_user(app: &mut TestApp) {
+    let response = app
+        .put_legal_hold(
+            Some(&get_admin_key()),
+            &json!({"userId": uuid::Uuid::new_v4(), "held": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_admin_key_incorrect(app: &mut TestApp) {
+    let response = app.get_legal_holds(Some("not-the-admin-key")).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
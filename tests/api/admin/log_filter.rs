@@ -0,0 +1,31 @@
+use crate::helpers::{get_admin_key, TestApp};
+use rota_manager::ErrorResponse;
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_filter(app: &mut TestApp) {
+    let response = app
+        .put_log_filter(
+            Some(&get_admin_key()),
+            &json!({"filter": "rota_manager=loudest"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(response
+        .json::<ErrorResponse>()
+        .await
+        .unwrap()
+        .error
+        .starts_with("Validation error: Invalid log filter"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_admin_key_incorrect(app: &mut TestApp) {
+    let response = app
+        .put_log_filter(Some("not-the-admin-key"), &json!({"filter": "debug"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,8 @@
+mod bootstrap;
+mod data_region;
+mod deprecated_usage;
+mod invitations;
+mod legal_hold;
+mod log_filter;
+mod provisioning;
+mod request_capture;
@@ -0,0 +1,218 @@
+use crate::helpers::{
+    get_admin_key, get_json_response_body, get_random_email, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    domain::{Email, ProvisionStatus},
+    routes::admin::{ProvisionedApiKeyResponse, ProvisionedUserResponse},
+};
+use secrecy::Secret;
+use serde_json::json;
+use test_context::test_context;
+
+async fn provision_user(
+    app: &TestApp,
+    external_id: &str,
+    email: &str,
+) -> (u16, ProvisionedUserResponse) {
+    let response = app
+        .put_provisioned_user(
+            Some(&get_admin_key()),
+            external_id,
+            &json!({"email": email}),
+        )
+        .await;
+    let status = response.status().as_u16();
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProvisionedUserResponse>(), &body),
+        "response does not match schema"
+    );
+    (status, serde_json::from_value(body).unwrap())
+}
+
+async fn provision_key(
+    app: &TestApp,
+    external_id: &str,
+    user_external_id: &str,
+    name: &str,
+) -> reqwest::Response {
+    app.put_provisioned_api_key(
+        Some(&get_admin_key()),
+        external_id,
+        &json!({"userExternalId": user_external_id, "name": name}),
+    )
+    .await
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_provision_users_idempotently(app: &mut TestApp) {
+    let email = get_random_email();
+
+    let (status, created) = provision_user(app, "acme", &email).await;
+    assert_eq!(status, 201);
+    assert_eq!(created.status, ProvisionStatus::Created);
+    assert_eq!(created.email, email);
+    assert!(!created.requires_2fa);
+
+    let (status, again) = provision_user(app, "acme", &email).await;
+    assert_eq!(status, 200);
+    assert_eq!(again.status, ProvisionStatus::Unchanged);
+    assert_eq!(again.user_id, created.user_id);
+
+    let new_email = get_random_email();
+    let (status, updated) = provision_user(app, "acme", &new_email).await;
+    assert_eq!(status, 200);
+    assert_eq!(updated.status, ProvisionStatus::Updated);
+    assert_eq!(updated.user_id, created.user_id);
+
+    let user = app
+        .user_store
+        .read()
+        .await
+        .get_user(&Email::parse(Secret::new(new_email)).unwrap())
+        .await
+        .expect("Provisioned user not found");
+    assert_eq!(*user.id.as_ref(), created.user_id);
+
+    let response = app
+        .put_provisioned_user(
+            Some(&get_admin_key()),
+            "acme",
+            &json!({"email": &updated.email, "requires2FA": true}),
+        )
+        .await;
+    let body = response.json::<ProvisionedUserResponse>().await.unwrap();
+    assert_eq!(body.status, ProvisionStatus::Updated);
+    assert!(body.requires_2fa);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_provision_the_same_user_at_once(app: &mut TestApp) {
+    let email = get_random_email();
+
+    let (first, second) = tokio::join!(
+        provision_user(app, "acme", &email),
+        provision_user(app, "acme", &email)
+    );
+    let mut statuses = [first.0, second.0];
+    statuses.sort();
+    assert_eq!(statuses, [200, 201]);
+    assert_eq!(first.1.user_id, second.1.user_id);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_409_if_email_is_taken(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+
+    let response = app
+        .put_provisioned_user(
+            Some(&get_admin_key()),
+            "acme",
+            &json!({"email": email}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+
+    let (_, other) = provision_user(app, "other", &get_random_email()).await;
+    let response = app
+        .put_provisioned_user(
+            Some(&get_admin_key()),
+            "other",
+            &json!({"email": email}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+    let (_, unchanged) = provision_user(app, "other", &other.email).await;
+    assert_eq!(unchanged.status, ProvisionStatus::Unchanged);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_provision_api_keys_idempotently(app: &mut TestApp) {
+    let (_, user) = provision_user(app, "acme", &get_random_email()).await;
+
+    let response = provision_key(app, "acme-zapier", "acme", "Zapier").await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProvisionedApiKeyResponse>(), &body),
+        "response does not match schema"
+    );
+    let created: ProvisionedApiKeyResponse =
+        serde_json::from_value(body).unwrap();
+    assert_eq!(created.status, ProvisionStatus::Created);
+    assert_eq!(created.user_id, user.user_id);
+    let key = created.key.expect("No key in response");
+
+    // The key works for polling, and its creation is one of the updates
+    app.settle_integration_updates();
+    let response = app.get_integration_updates(Some(&key), &[]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(body["updates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|update| update["type"] == "IntegrationKeyCreated"));
+
+    let response = provision_key(app, "acme-zapier", "acme", "Zapier").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<ProvisionedApiKeyResponse>().await.unwrap();
+    assert_eq!(body.status, ProvisionStatus::Unchanged);
+    assert_eq!(body.key_id, created.key_id);
+    assert_eq!(body.key, None);
+
+    let response = provision_key(app, "acme-zapier", "acme", "Make").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<ProvisionedApiKeyResponse>().await.unwrap();
+    assert_eq!(body.status, ProvisionStatus::Updated);
+    assert_eq!(body.name.as_ref(), "Make");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_move_api_keys_between_users(app: &mut TestApp) {
+    provision_user(app, "acme", &get_random_email()).await;
+    provision_user(app, "globex", &get_random_email()).await;
+    let response = provision_key(app, "shared", "acme", "Zapier").await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = provision_key(app, "shared", "globex", "Zapier").await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = provision_key(app, "other", "initech", "Zapier").await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_requests(app: &mut TestApp) {
+    let response = app
+        .put_provisioned_user(None, "acme", &json!({"email": "a@b.com"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .put_provisioned_user(
+            Some("wrong"),
+            "acme",
+            &json!({"email": "a@b.com"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    for (external_id, email) in [("acme", "not an email"), ("a%20b", "a@b.com")]
+    {
+        let response = app
+            .put_provisioned_user(
+                Some(&get_admin_key()),
+                external_id,
+                &json!({"email": email}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "{}", external_id);
+    }
+}
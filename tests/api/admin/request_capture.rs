@@ -0,0 +1,120 @@
+use crate::helpers::{get_admin_key, get_session, TestApp};
+use rota_manager::{domain::Email, routes::admin::RequestCaptureResponse};
+use secrecy::Secret;
+use serde_json::json;
+use test_context::test_context;
+
+async fn get_user_id(app: &TestApp, email: &str) -> String {
+    let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+    let user_store = app.user_store.read().await;
+    user_store
+        .get_user(&email)
+        .await
+        .unwrap()
+        .id
+        .as_ref()
+        .to_string()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_capture_requests_with_secrets_redacted(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+
+    let response = app
+        .put_request_capture(
+            Some(&get_admin_key()),
+            &json!({"userId": &user_id, "enabled": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<RequestCaptureResponse>().await.unwrap();
+    assert!(body.capturing);
+    assert!(body.requests.is_empty());
+
+    let response = app
+        .put_requires_2fa(
+            &json!({"requires2FA": false, "password": "password"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .get_request_capture(Some(&get_admin_key()), &user_id)
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<RequestCaptureResponse>().await.unwrap();
+    assert_eq!(body.requests.len(), 1);
+    let captured = &body.requests[0];
+    assert_eq!(captured.method, "PUT");
+    assert_eq!(captured.uri, "/auth/2fa");
+    assert_eq!(captured.status, 200);
+    assert_eq!(
+        captured.request_body,
+        Some(json!({"requires2FA": false, "password": "[REDACTED]"}))
+    );
+    assert!(captured.response_body.is_some());
+
+    let response = app
+        .put_request_capture(
+            Some(&get_admin_key()),
+            &json!({"userId": &user_id, "enabled": false}),
+        )
+        .await;
+    let body = response.json::<RequestCaptureResponse>().await.unwrap();
+    assert!(!body.capturing);
+    assert_eq!(body.requests.len(), 1);
+
+    app.put_requires_2fa(
+        &json!({"requires2FA": false, "password": "password"}),
+    )
+    .await;
+    let response = app
+        .get_request_capture(Some(&get_admin_key()), &user_id)
+        .await;
+    let body = response.json::<RequestCaptureResponse>().await.unwrap();
+    assert_eq!(body.requests.len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_redact_new_integration_keys(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let user_id = get_user_id(app, &email).await;
+    app.put_request_capture(
+        Some(&get_admin_key()),
+        &json!({"userId": &user_id, "enabled": true}),
+    )
+    .await;
+
+    let response = app.post_integration_key(&json!({"name": "Zapier"})).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let key = response.json::<serde_json::Value>().await.unwrap()["key"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    let response = app
+        .get_request_capture(Some(&get_admin_key()), &user_id)
+        .await;
+    let body = response.json::<RequestCaptureResponse>().await.unwrap();
+    assert_eq!(body.requests.len(), 1);
+    let captured = &body.requests[0];
+    assert_eq!(captured.uri, "/integrations/keys");
+    let response_body = captured.response_body.as_ref().unwrap();
+    assert_eq!(response_body["key"], "[REDACTED]");
+    assert!(!response_body.to_string().contains(&key));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_admin_key_incorrect(app: &mut TestApp) {
+    let response = app
+        .put_request_capture(
+            Some("not-the-admin-key"),
+            &json!({"userId": uuid::Uuid::new_v4(), "enabled": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,27 @@
+use crate::helpers::{get_json_response_body, TestApp};
+use rota_manager::routes::api_docs::schema_registry;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_200_without_authentication(app: &mut TestApp) {
+    let response = app.get_api_docs_schemas().await;
+    assert_eq!(
+        response.status().as_u16(),
+        200,
+        "Schemas should be public: {:?}",
+        response
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_every_registered_schema(app: &mut TestApp) {
+    let response = app.get_api_docs_schemas().await;
+    let response_body = get_json_response_body(response).await;
+
+    let expected_body = serde_json::to_value(schema_registry())
+        .expect("Failed to serialise schema registry");
+
+    assert_eq!(response_body, expected_body);
+}
@@ -0,0 +1,108 @@
+use crate::helpers::{get_random_email, login, PolicyTestApp, TestApp};
+use rota_manager::{
+    routes::{auth::AcceptPolicyResponse, policies::PolicyResponse},
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+const POLICY_NOT_ACCEPTED: &str =
+    "Version 1 of the terms of service and privacy policy must be accepted";
+
+#[test_context(PolicyTestApp)]
+#[tokio::test]
+async fn should_require_acceptance_at_signup(ctx: &mut PolicyTestApp) {
+    let app = &ctx.0;
+
+    let response = app
+        .post_signup(&json!({
+            "email": get_random_email(),
+            "password": "password",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 451);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        POLICY_NOT_ACCEPTED
+    );
+
+    for (version, status) in [(99, 400), (1, 201)] {
+        let response = app
+            .post_signup(&json!({
+                "email": get_random_email(),
+                "password": "password",
+                "requires2FA": false,
+                "acceptedPolicyVersion": version
+            }))
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            status,
+            "Failed for version {}",
+            version
+        );
+    }
+}
+
+#[test_context(PolicyTestApp)]
+#[tokio::test]
+async fn should_block_projects_until_policy_accepted(ctx: &mut PolicyTestApp) {
+    let app = &mut ctx.0;
+    let email = get_random_email();
+    let response = app
+        .post_signup(&json!({
+            "email": email,
+            "password": "password",
+            "requires2FA": false,
+            "acceptedPolicyVersion": 1
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    login(app, &email, "password").await;
+    assert_eq!(app.get_projects_list().await.status().as_u16(), 200);
+
+    // As for a user who signed up before the required version was published
+    sqlx::query("UPDATE users SET accepted_policy_version = NULL")
+        .execute(&app.pg_pool)
+        .await
+        .unwrap();
+
+    let response = app.get_projects_list().await;
+    assert_eq!(response.status().as_u16(), 451);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        POLICY_NOT_ACCEPTED
+    );
+    assert_eq!(app.get_calendar_feed().await.status().as_u16(), 451);
+
+    let response = app.post_accept_policy(&json!({ "version": 1 })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<AcceptPolicyResponse>().await.unwrap(),
+        AcceptPolicyResponse {
+            accepted_version: 1
+        }
+    );
+    assert_eq!(app.get_projects_list().await.status().as_u16(), 200);
+}
+
+#[test_context(PolicyTestApp)]
+#[tokio::test]
+async fn should_return_required_policy(ctx: &mut PolicyTestApp) {
+    let response = ctx.0.get_policies().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let policy = response.json::<PolicyResponse>().await.unwrap();
+    assert_eq!(policy.version, 1);
+    assert!(policy.required);
+    assert!(policy.terms_of_service.contains("Terms of Service"));
+    assert!(policy.privacy_policy.contains("Privacy Policy"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_require_acceptance_by_default(app: &mut TestApp) {
+    let response = app.get_policies().await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!response.json::<PolicyResponse>().await.unwrap().required);
+}
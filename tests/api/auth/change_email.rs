@@ -0,0 +1,178 @@
+use rota_manager::{
+    domain::Email,
+    utils::deprecation::{DEPRECATION_HEADER, SUNSET_HEADER},
+};
+use secrecy::Secret;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_new_project, get_json_response_body, get_random_email, get_sent_token,
+    get_session, login, logout, signup, TestApp,
+};
+
+async fn user_exists(app: &TestApp, email: &str) -> bool {
+    let email = Email::parse(Secret::new(email.to_owned())).unwrap();
+    app.user_store.read().await.get_user(&email).await.is_ok()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_change_email_once_both_addresses_confirm(app: &mut TestApp) {
+    let old_email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let new_email = get_random_email();
+
+    let response = app
+        .put_email(&serde_json::json!({ "newEmail": new_email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    let old_token = get_sent_token(app, &old_email).await;
+    let new_token = get_sent_token(app, &new_email).await;
+    assert_ne!(old_token, new_token);
+
+    let response = app
+        .post_confirm_email_change(&serde_json::json!({ "token": new_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    assert!(user_exists(app, &old_email).await);
+    assert!(!user_exists(app, &new_email).await);
+
+    let response = app
+        .post_confirm_email_change(&serde_json::json!({ "token": old_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(!user_exists(app, &old_email).await);
+    assert!(user_exists(app, &new_email).await);
+
+    // Both tokens are used up
+    for token in [old_token, new_token] {
+        let response = app
+            .post_confirm_email_change(&serde_json::json!({ "token": token }))
+            .await;
+        assert_eq!(response.status().as_u16(), 401);
+    }
+
+    // Projects belong to the account, not the address
+    logout(app).await;
+    login(app, &new_email, "password").await;
+    let body = get_json_response_body(app.get_projects_list().await).await;
+    assert_eq!(body["projects"][0]["id"], project_id);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_still_change_email_through_deprecated_route(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    let response = app
+        .post_change_email(&serde_json::json!({
+            "newEmail": get_random_email()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    assert_eq!(response.headers().get(DEPRECATION_HEADER).unwrap(), "true");
+    assert!(response.headers().get(SUNSET_HEADER).is_some());
+    assert_eq!(
+        response.headers().get("link").unwrap(),
+        "</auth/email>; rel=\"successor-version\""
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_allow_browsers_to_put_the_successor(app: &mut TestApp) {
+    let response = app
+        .http_client
+        .request(
+            reqwest::Method::OPTIONS,
+            format!("{}/auth/email", &app.address),
+        )
+        .header("origin", "http://localhost:3000")
+        .header("access-control-request-method", "PUT")
+        .send()
+        .await
+        .expect("Failed to execute request");
+
+    assert!(response.status().is_success());
+    let allowed = response
+        .headers()
+        .get("access-control-allow-methods")
+        .unwrap()
+        .to_str()
+        .unwrap();
+    assert!(allowed.contains("PUT"));
+    assert!(allowed.contains("DELETE"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_change_email_on_one_confirmation(app: &mut TestApp) {
+    let old_email = get_session(app, false).await;
+    let new_email = get_random_email();
+
+    app.put_email(&serde_json::json!({ "newEmail": new_email }))
+        .await;
+    let new_token = get_sent_token(app, &new_email).await;
+
+    // Confirming the same address twice doesn't count as both
+    for _ in 0..2 {
+        let response = app
+            .post_confirm_email_change(
+                &serde_json::json!({ "token": new_token }),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 202);
+    }
+    assert!(user_exists(app, &old_email).await);
+    assert!(!user_exists(app, &new_email).await);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_409_if_new_email_is_taken(app: &mut TestApp) {
+    let taken_email = get_random_email();
+    signup(app, &taken_email, "password", false).await;
+    get_session(app, false).await;
+
+    let response = app
+        .put_email(&serde_json::json!({ "newEmail": taken_email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 409);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_invalid_input(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    let response = app
+        .put_email(&serde_json::json!({ "newEmail": "foobar.com" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_confirm_email_change(&serde_json::json!({ "token": "123456" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_unknown_token(app: &mut TestApp) {
+    let response = app
+        .post_confirm_email_change(&serde_json::json!({
+            "token": "aeeddfff-94c3-447f-8dd8-1f779c6412c9"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    let response = app
+        .put_email(&serde_json::json!({ "newEmail": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
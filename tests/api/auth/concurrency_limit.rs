@@ -0,0 +1,41 @@
+use reqwest::header::RETRY_AFTER;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{get_random_email, SaturatedAuthTestApp};
+
+#[test_context(SaturatedAuthTestApp)]
+#[tokio::test]
+async fn should_return_503_when_hashing_is_saturated(
+    app: &mut SaturatedAuthTestApp,
+) {
+    let app = &app.0;
+    let body = json!({
+        "email": get_random_email(),
+        "password": "password",
+        "requires2FA": false
+    });
+
+    for response in [app.post_signup(&body).await, app.post_login(&body).await]
+    {
+        assert_eq!(response.status().as_u16(), 503);
+        assert_eq!(
+            response
+                .headers()
+                .get(RETRY_AFTER)
+                .expect("No Retry-After header"),
+            "1"
+        );
+        let body = response.json::<serde_json::Value>().await.unwrap();
+        assert_eq!(body["code"], "auth_overloaded");
+    }
+}
+
+#[test_context(SaturatedAuthTestApp)]
+#[tokio::test]
+async fn should_serve_other_routes_when_hashing_is_saturated(
+    app: &mut SaturatedAuthTestApp,
+) {
+    let response = app.0.get_version().await;
+    assert_eq!(response.status().as_u16(), 200);
+}
@@ -1,6 +1,12 @@
-use crate::helpers::{add_new_project, delete_user, get_session, TestApp};
-use rota_manager::{domain::Email, routes::auth::DeleteUserResponse};
+use crate::helpers::{
+    add_new_project, delete_user, get_session, login, TestApp,
+};
+use rota_manager::{
+    domain::Email, routes::auth::DeleteUserResponse,
+    utils::constants::JWT_COOKIE_NAME,
+};
 use secrecy::Secret;
+use serde_json::json;
 
 use test_context::test_context;
 
@@ -77,3 +83,100 @@ async fn should_tidy_up_user_data(app: &mut TestApp) {
         assert_eq!(project_list.len(), 0);
     }
 }
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_keep_projects_when_anonymizing(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let _project_id = add_new_project(app, "new project 1").await;
+
+    let parsed_email = Email::parse(Secret::new(email.clone())).unwrap();
+    let user_id = app
+        .user_store
+        .read()
+        .await
+        .get_user(&parsed_email)
+        .await
+        .unwrap()
+        .id;
+
+    let response = app.anonymize_user().await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<DeleteUserResponse>().await.unwrap(),
+        DeleteUserResponse {
+            message: format!("User anonymized: {}", email),
+        }
+    );
+
+    // The old address is gone and can't be signed in with
+    assert!(app
+        .user_store
+        .read()
+        .await
+        .get_user(&parsed_email)
+        .await
+        .is_err());
+    let response = app
+        .post_login(&json!({ "email": email, "password": "password" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let project_list = app
+        .project_store
+        .write()
+        .await
+        .get_project_list(&user_id)
+        .await
+        .unwrap();
+    assert_eq!(project_list.len(), 1);
+
+    let anonymized: String =
+        sqlx::query_scalar("SELECT email FROM users WHERE id = $1")
+            .bind(user_id.as_ref())
+            .fetch_one(&app.pg_pool)
+            .await
+            .unwrap();
+    assert_eq!(
+        anonymized,
+        format!("anonymized-{}@anonymized.invalid", user_id.as_ref())
+    );
+
+    // Recorded for the audit log
+    let recorded: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM event_outbox \
+         WHERE event->>'type' = 'UserAnonymized' \
+         AND event->>'user_id' = $1",
+    )
+    .bind(user_id.as_ref().to_string())
+    .fetch_one(&app.pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(recorded, 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_sign_out_other_sessions_when_anonymizing(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let response = app
+        .post_login(&json!({ "email": email, "password": "password" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let other_token = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie in response")
+        .value()
+        .to_owned();
+    // Carry on with a third session, so the one above isn't the caller's
+    login(app, &email, "password").await;
+
+    let response = app.anonymize_user().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_verify_token(&json!({ "token": other_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,42 @@
+use crate::helpers::{get_schema, get_session, TestApp};
+use rota_manager::routes::auth::LocaleResponse;
+use serde_json::{json, Value};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_set_and_clear_locale(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    for (locale, expected) in [
+        (json!("en-US"), json!("en-US")),
+        (json!("de"), json!("de-DE")),
+        (Value::Null, Value::Null),
+    ] {
+        let response = app.put_locale(&json!({ "locale": locale })).await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let body = response.json::<Value>().await.unwrap();
+        assert!(
+            jsonschema::is_valid(&get_schema::<LocaleResponse>(), &body),
+            "response does not match schema"
+        );
+        assert_eq!(body["locale"], expected, "Failed for {}", locale);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_unsupported_locale(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app.put_locale(&json!({ "locale": "tlh" })).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    let response = app.put_locale(&json!({ "locale": "en-US" })).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
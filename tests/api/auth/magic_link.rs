@@ -0,0 +1,149 @@
+use chrono::TimeDelta;
+use rota_manager::{
+    routes::auth::{MagicLinkResponse, TwoFactorAuthResponse},
+    utils::{auth::MAGIC_LINK_TTL_SECONDS, constants::JWT_COOKIE_NAME},
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+
+use crate::helpers::{
+    get_json_response_body, get_random_email, get_schema, signup, TestApp,
+};
+
+// Sign up and ask for a magic link, returning the address and the link's
+// token
+async fn request_link(app: &mut TestApp, two_fa: bool) -> (String, String) {
+    let email = get_random_email();
+    signup(app, &email, "password", two_fa).await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_magic_link(&json!({ "email": email })).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<MagicLinkResponse>(), &body),
+        "response does not match schema"
+    );
+
+    let token = sent_link_token(app, &email).await;
+    (email, token)
+}
+
+// Magic link tokens are signed, so longer than the tokens get_sent_token
+// finds
+async fn sent_link_token(app: &TestApp, recipient: &str) -> String {
+    app.email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .rev()
+        .find_map(|request| {
+            let body: Value = request.body_json().unwrap();
+            if body["To"] != recipient {
+                return None;
+            }
+            let text = body["TextBody"].as_str().unwrap();
+            let start = text.find("/auth/magic-link/verify?token=")? + 30;
+            text[start..].split_whitespace().next().map(str::to_owned)
+        })
+        .expect("No magic link sent to recipient")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_sign_in_with_emailed_link(app: &mut TestApp) {
+    let (_email, token) = request_link(app, false).await;
+
+    let response = app.get_verify_magic_link(&token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let auth_cookie = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found");
+    assert!(!auth_cookie.value().is_empty());
+
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // The link only works once
+    let response = app.get_verify_magic_link(&token).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_ask_for_2fa_code_if_enabled(app: &mut TestApp) {
+    let (_email, token) = request_link(app, true).await;
+
+    let response = app.get_verify_magic_link(&token).await;
+    assert_eq!(response.status().as_u16(), 206);
+    assert!(response
+        .cookies()
+        .all(|cookie| cookie.name() != JWT_COOKIE_NAME));
+    let body = response
+        .json::<TwoFactorAuthResponse>()
+        .await
+        .expect("Could not deserialize response body");
+    assert_eq!(body.message, "2FA required");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_once_link_has_expired(app: &mut TestApp) {
+    let (_email, token) = request_link(app, false).await;
+
+    app.clock
+        .advance(TimeDelta::seconds(MAGIC_LINK_TTL_SECONDS));
+    let response = app.get_verify_magic_link(&token).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_tokens_that_are_not_magic_links(
+    app: &mut TestApp,
+) {
+    let (_email, token) = request_link(app, false).await;
+    let response = app.get_verify_magic_link(&token).await;
+    let session_token = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found")
+        .value()
+        .to_owned();
+
+    for token in [session_token.as_str(), "not-a-token"] {
+        let response = app.get_verify_magic_link(token).await;
+        assert_eq!(response.status().as_u16(), 401, "Failed for {}", token);
+    }
+
+    let response = app
+        .http_client
+        .get(format!("{}/auth/magic-link/verify", &app.address))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+// Unknown addresses get the same answer, and no email
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_202_for_unknown_email(app: &mut TestApp) {
+    let response = app
+        .post_magic_link(&json!({ "email": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    let sent = app.email_server.received_requests().await.unwrap();
+    assert!(sent.is_empty());
+
+    let response = app.post_magic_link(&json!({ "email": "foobar.com" })).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -1,6 +1,17 @@
+mod accept_policy;
+mod change_email;
+mod concurrency_limit;
 mod delete_user;
+mod locale;
 mod login;
 mod logout;
+mod magic_link;
+mod password_reset;
+mod profile;
+mod quota;
+mod requires_2fa;
+mod sessions;
 mod signup;
+mod totp;
 mod verify_2fa;
 mod verify_token;
@@ -0,0 +1,167 @@
+use chrono::TimeDelta;
+use rota_manager::utils::{
+    auth::PASSWORD_RESET_TTL_SECONDS, constants::JWT_COOKIE_NAME,
+};
+use serde_json::json;
+use test_context::test_context;
+use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+
+use crate::helpers::{get_random_email, get_sent_token, signup, TestApp};
+
+const NEW_PASSWORD: &str = "new password";
+
+// Sign up and ask for a reset link, returning the address and its token
+async fn request_reset(app: &mut TestApp) -> (String, String) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let response = app.post_forgot_password(&json!({ "email": email })).await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    let token = get_sent_token(app, &email).await;
+    (email, token)
+}
+
+async fn login_status(app: &TestApp, email: &str, password: &str) -> u16 {
+    app.post_login(&json!({ "email": email, "password": password }))
+        .await
+        .status()
+        .as_u16()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reset_password_with_emailed_token(app: &mut TestApp) {
+    let (email, token) = request_reset(app).await;
+
+    let response = app
+        .post_reset_password(&json!({
+            "token": token,
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    assert_eq!(login_status(app, &email, "password").await, 401);
+    assert_eq!(login_status(app, &email, NEW_PASSWORD).await, 200);
+
+    // The token is used up
+    let response = app
+        .post_reset_password(&json!({
+            "token": token,
+            "newPassword": "another password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_sign_out_sessions_once_password_reset(app: &mut TestApp) {
+    let (email, token) = request_reset(app).await;
+    let response = app
+        .post_login(&json!({ "email": email, "password": "password" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let old_token = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie in response")
+        .value()
+        .to_owned();
+
+    let response = app
+        .post_reset_password(&json!({
+            "token": token,
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_verify_token(&json!({ "token": old_token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_once_token_has_expired(app: &mut TestApp) {
+    let (email, token) = request_reset(app).await;
+
+    app.clock
+        .advance(TimeDelta::seconds(PASSWORD_RESET_TTL_SECONDS));
+    let response = app
+        .post_reset_password(&json!({
+            "token": token,
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(login_status(app, &email, "password").await, 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_keep_token_if_new_password_is_invalid(app: &mut TestApp) {
+    let (email, token) = request_reset(app).await;
+
+    let response = app
+        .post_reset_password(&json!({ "token": token, "newPassword": "short" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_reset_password(&json!({
+            "token": token,
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(login_status(app, &email, NEW_PASSWORD).await, 200);
+}
+
+// Unknown addresses get the same answer, and no email
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_202_for_unknown_email(app: &mut TestApp) {
+    let response = app
+        .post_forgot_password(&json!({ "email": get_random_email() }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+
+    let sent = app.email_server.received_requests().await.unwrap();
+    assert!(sent.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_invalid_input(app: &mut TestApp) {
+    let response = app
+        .post_forgot_password(&json!({ "email": "foobar.com" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_reset_password(&json!({
+            "token": "123456",
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_unknown_token(app: &mut TestApp) {
+    let response = app
+        .post_reset_password(&json!({
+            "token": "aeeddfff-94c3-447f-8dd8-1f779c6412c9",
+            "newPassword": NEW_PASSWORD
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,68 @@
+use crate::helpers::{get_schema, get_session, TestApp};
+use rota_manager::{domain::Email, routes::auth::ProfileResponse};
+use secrecy::Secret;
+use serde_json::{json, Value};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_set_and_clear_profile(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+
+    let response = app
+        .put_profile(&json!({
+            "displayName": "  Ted Smith ",
+            "timezone": "Europe/London"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<Value>().await.unwrap();
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProfileResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body,
+        json!({"displayName": "Ted Smith", "timezone": "Europe/London"})
+    );
+
+    let email = Email::parse(Secret::new(email)).unwrap();
+    let user = app.user_store.read().await.get_user(&email).await.unwrap();
+    assert_eq!(user.display_name.unwrap().as_ref(), "Ted Smith");
+    assert_eq!(user.timezone.unwrap().name(), "Europe/London");
+
+    let response = app
+        .put_profile(&json!({"displayName": null, "timezone": null}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = response.json::<Value>().await.unwrap();
+    assert_eq!(body, json!({"displayName": null, "timezone": null}));
+
+    let user = app.user_store.read().await.get_user(&email).await.unwrap();
+    assert_eq!(user.display_name, None);
+    assert_eq!(user.timezone, None);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_profile(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    for body in [
+        json!({"displayName": "", "timezone": null}),
+        json!({"displayName": "a".repeat(101), "timezone": null}),
+        json!({"displayName": "Ted", "timezone": "Mars/Olympus_Mons"}),
+    ] {
+        let response = app.put_profile(&body).await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", body);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    let response = app
+        .put_profile(&json!({"displayName": "Ted", "timezone": null}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,118 @@
+use rota_manager::{
+    domain::{QuotaResource, MEMBER_QUOTA, PROJECT_QUOTA, SHIFT_QUOTA},
+    routes::auth::{QuotaEntry, QuotaResponse},
+    utils::constants::{QUOTA_LIMIT_HEADER, QUOTA_REMAINING_HEADER},
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_schema, get_session, TestApp,
+};
+
+fn quota_headers(response: &reqwest::Response) -> (i64, i64) {
+    let header = |name| {
+        response
+            .headers()
+            .get(name)
+            .expect("No quota header in response")
+            .to_str()
+            .unwrap()
+            .parse::<i64>()
+            .unwrap()
+    };
+    (header(QUOTA_LIMIT_HEADER), header(QUOTA_REMAINING_HEADER))
+}
+
+async fn get_quota(app: &TestApp) -> QuotaResponse {
+    let response = app.get_quota().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response.json::<Value>().await.unwrap();
+    assert!(
+        jsonschema::is_valid(&get_schema::<QuotaResponse>(), &body),
+        "response does not match schema"
+    );
+    serde_json::from_value(body).unwrap()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_usage_for_each_quota(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    let quota = get_quota(app).await;
+    assert_eq!(
+        quota.quotas,
+        vec![
+            QuotaEntry {
+                resource: QuotaResource::Projects,
+                limit: PROJECT_QUOTA,
+                used: 0,
+                remaining: PROJECT_QUOTA,
+            },
+            QuotaEntry {
+                resource: QuotaResource::Members,
+                limit: MEMBER_QUOTA,
+                used: 0,
+                remaining: MEMBER_QUOTA,
+            },
+            QuotaEntry {
+                resource: QuotaResource::Shifts,
+                limit: SHIFT_QUOTA,
+                used: 0,
+                remaining: SHIFT_QUOTA,
+            },
+        ]
+    );
+
+    let project_id = add_new_project(app, "Quota").await;
+    add_member(app, "Ted", &project_id).await;
+
+    let quota = get_quota(app).await;
+    assert_eq!(quota.quotas[0].used, 1);
+    assert_eq!(quota.quotas[0].remaining, PROJECT_QUOTA - 1);
+    assert_eq!(quota.quotas[1].used, 1);
+    assert_eq!(quota.quotas[2].used, 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_quota_headers_when_creating(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    let response = app.post_projects_new(&json!({"name": "Quota"})).await;
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(quota_headers(&response), (PROJECT_QUOTA, PROJECT_QUOTA - 1));
+    let project_id = response.json::<Value>().await.unwrap()["id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    let response = app
+        .post_add_member(&json!({"memberName": "Ted", "projectId": project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(quota_headers(&response), (MEMBER_QUOTA, MEMBER_QUOTA - 1));
+    let member_id = response.json::<Value>().await.unwrap()["memberId"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(quota_headers(&response), (SHIFT_QUOTA, SHIFT_QUOTA - 1));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    assert_eq!(app.get_quota().await.status().as_u16(), 400);
+}
@@ -0,0 +1,78 @@
+use crate::helpers::{
+    get_json_response_body, get_schema, get_session, logout, TestApp,
+};
+use rota_manager::{
+    domain::BACKUP_CODE_COUNT, routes::auth::Requires2FAResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_require_2fa_at_login_once_enabled(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+
+    let response = app
+        .put_requires_2fa(&json!({"requires2FA": true, "password": "password"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<Requires2FAResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["requires2FA"], true);
+    assert_eq!(
+        body["backupCodes"].as_array().unwrap().len(),
+        BACKUP_CODE_COUNT
+    );
+
+    logout(app).await;
+    let response = app
+        .post_login(&json!({"email": &email, "password": "password"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_log_in_without_2fa_once_disabled(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+
+    let response = app
+        .put_requires_2fa(
+            &json!({"requires2FA": false, "password": "password"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["requires2FA"], false);
+    assert_eq!(body["backupCodes"], json!([]));
+
+    logout(app).await;
+    let response = app
+        .post_login(&json!({"email": &email, "password": "password"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_wrong_password(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+
+    let response = app
+        .put_requires_2fa(&json!({
+            "requires2FA": false,
+            "password": "wrong password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // Still required
+    logout(app).await;
+    let response = app
+        .post_login(&json!({"email": &email, "password": "password"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+}
@@ -0,0 +1,162 @@
+use chrono::TimeDelta;
+use reqwest::header::USER_AGENT;
+use rota_manager::{
+    routes::auth::SessionListResponse, utils::constants::JWT_COOKIE_NAME,
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    get_random_email, get_schema, signup, ProxiedTestApp, TestApp,
+};
+
+// Sign in from a named device, returning the session's token
+async fn login_from(app: &TestApp, email: &str, device: &str) -> String {
+    let response = app
+        .http_client
+        .post(format!("{}/auth/login", &app.address))
+        .header(USER_AGENT, device)
+        .header("X-Forwarded-For", "203.0.113.7, 198.51.100.1")
+        .json(&json!({ "email": email, "password": "password" }))
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let token = response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie in response")
+        .value()
+        .to_owned();
+    token
+}
+
+async fn get_sessions(app: &TestApp) -> SessionListResponse {
+    let response = app.get_sessions().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = response.json::<Value>().await.unwrap();
+    assert!(
+        jsonschema::is_valid(&get_schema::<SessionListResponse>(), &body),
+        "response does not match schema"
+    );
+    serde_json::from_value(body).unwrap()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_active_sessions_newest_first(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    login_from(app, &email, "Laptop").await;
+    app.clock.advance(TimeDelta::minutes(1));
+    login_from(app, &email, "Phone").await;
+
+    let sessions = get_sessions(app).await.sessions;
+
+    let devices: Vec<_> = sessions
+        .iter()
+        .map(|session| session.device.as_deref())
+        .collect();
+    assert_eq!(devices, vec![Some("Phone"), Some("Laptop")]);
+    assert!(sessions[0].current);
+    assert!(!sessions[1].current);
+    // X-Forwarded-For is ignored unless the app is told it's behind a proxy
+    assert_eq!(sessions[0].ip.as_deref(), Some("127.0.0.1"));
+}
+
+#[test_context(ProxiedTestApp)]
+#[tokio::test]
+async fn should_record_the_address_the_proxy_forwarded_for(
+    ctx: &mut ProxiedTestApp,
+) {
+    let app = &mut ctx.0;
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    login_from(app, &email, "Laptop").await;
+
+    let sessions = get_sessions(app).await.sessions;
+    // The address the proxy added, not the one the client claimed
+    assert_eq!(sessions[0].ip.as_deref(), Some("198.51.100.1"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_sign_out_revoked_session(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    let laptop_token = login_from(app, &email, "Laptop").await;
+    app.clock.advance(TimeDelta::minutes(1));
+    login_from(app, &email, "Phone").await;
+    let laptop = get_sessions(app).await.sessions.remove(1);
+
+    let response = app
+        .delete_session(&laptop.session_id.as_ref().to_string())
+        .await;
+    assert_eq!(response.status().as_u16(), 204);
+
+    let response = app
+        .post_verify_token(&json!({ "token": laptop_token }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    // The phone is still signed in
+    let sessions = get_sessions(app).await.sessions;
+    assert_eq!(sessions.len(), 1);
+    assert_eq!(sessions[0].device.as_deref(), Some("Phone"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_drop_session_on_logout(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    login_from(app, &email, "Laptop").await;
+    app.clock.advance(TimeDelta::minutes(1));
+    login_from(app, &email, "Phone").await;
+
+    assert_eq!(app.post_logout().await.status().as_u16(), 200);
+    login_from(app, &email, "Tablet").await;
+
+    let sessions = get_sessions(app).await.sessions;
+    let devices: Vec<_> = sessions
+        .iter()
+        .map(|session| session.device.as_deref())
+        .collect();
+    assert_eq!(devices, vec![Some("Tablet"), Some("Laptop")]);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_session(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    login_from(app, &email, "Laptop").await;
+    let session = get_sessions(app).await.sessions.remove(0);
+
+    let other = get_random_email();
+    signup(app, &other, "password", false).await;
+    login_from(app, &other, "Phone").await;
+
+    let response = app
+        .delete_session(&session.session_id.as_ref().to_string())
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    let response = app.delete_session(&uuid::Uuid::new_v4().to_string()).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    assert_eq!(app.get_sessions().await.status().as_u16(), 400);
+    assert_eq!(
+        app.delete_session(&uuid::Uuid::new_v4().to_string())
+            .await
+            .status()
+            .as_u16(),
+        400
+    );
+}
@@ -1,5 +1,9 @@
-use crate::helpers::{get_random_email, TestApp};
-use rota_manager::{routes::auth::SignupResponse, ErrorResponse};
+use crate::helpers::{get_random_email, wait_for_event, TestApp};
+use rota_manager::{
+    domain::{DomainEvent, BACKUP_CODE_COUNT},
+    routes::auth::SignupResponse,
+    ErrorResponse,
+};
 use test_context::test_context;
 
 #[test_context(TestApp)]
@@ -35,6 +39,36 @@ async fn should_return_422_if_malformed_input(app: &mut TestApp) {
             "Failed for input: {:?}",
             test_case
         );
+        let error = response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialise response body to ErrorResponse");
+        assert_eq!(error.code, "invalid_body");
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_error_response_for_unreadable_body(app: &mut TestApp) {
+    let url = format!("{}/auth/signup", &app.address);
+    for (content_type, status, code) in [
+        ("application/json", 400, "malformed_json"),
+        ("text/plain", 415, "unsupported_media_type"),
+    ] {
+        let response = app
+            .http_client
+            .post(&url)
+            .header("Content-Type", content_type)
+            .body(r#"{"email": "#)
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), status);
+        let error = response
+            .json::<ErrorResponse>()
+            .await
+            .expect("Could not deserialise response body to ErrorResponse");
+        assert_eq!(error.code, code);
     }
 }
 
@@ -68,17 +102,18 @@ async fn should_return_201_for_valid_requests(app: &mut TestApp) {
             test_case
         );
 
-        let expected_response = SignupResponse {
-            message: "User created successfully".to_owned(),
-        };
+        let response = response
+            .json::<SignupResponse>()
+            .await
+            .expect("Could not deserialise response body to UserBody");
+        assert_eq!(response.message, "User created successfully");
 
-        assert_eq!(
-            response
-                .json::<SignupResponse>()
-                .await
-                .expect("Could not deserialise response body to UserBody"),
-            expected_response
-        );
+        // Backup codes are only issued with 2FA
+        let expected_codes = match test_case["requires2FA"].as_bool() {
+            Some(true) => BACKUP_CODE_COUNT,
+            _ => 0,
+        };
+        assert_eq!(response.backup_codes.len(), expected_codes);
     }
 }
 
@@ -161,3 +196,23 @@ async fn should_return_409_if_email_exists(app: &mut TestApp) {
         "User already exists".to_owned()
     );
 }
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_publish_user_signed_up_event(app: &mut TestApp) {
+    let mut events = app.event_bus.subscribe();
+
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": get_random_email(),
+            "password": "password123",
+            "requires2FA": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    wait_for_event(&mut events, |event| {
+        matches!(event, DomainEvent::UserSignedUp { .. })
+    })
+    .await;
+}
@@ -0,0 +1,186 @@
+use crate::helpers::{
+    get_expected_2fa_details, get_json_response_body, get_random_email,
+    get_schema, get_session, login, logout, TestApp,
+};
+use chrono::TimeDelta;
+use rota_manager::{
+    domain::{Clock, TotpSecret, BACKUP_CODE_COUNT},
+    routes::auth::{SignupResponse, TotpSetupResponse},
+};
+use secrecy::ExposeSecret;
+use test_context::test_context;
+use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+
+// Log in as far as the second factor, returning the login attempt's ID
+async fn start_login(app: &mut TestApp, email: &str) -> String {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+    get_expected_2fa_details(app, email).await.0
+}
+
+async fn setup_2fa(app: &TestApp) -> TotpSecret {
+    let response = app.post_setup_2fa().await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<TotpSetupResponse>(), &body),
+        "response does not match schema"
+    );
+    let secret = body["secret"].as_str().unwrap();
+    assert!(body["otpauthUri"]
+        .as_str()
+        .unwrap()
+        .starts_with("otpauth://totp/"));
+    assert!(body["otpauthUri"]
+        .as_str()
+        .unwrap()
+        .contains(&format!("secret={}", secret)));
+    TotpSecret::from_base32(secret).unwrap()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_log_in_with_authenticator_code(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+    let secret = setup_2fa(app).await;
+    logout(app).await;
+
+    let login_attempt_id = start_login(app, &email).await;
+    let code = secret.code_at(app.clock.now());
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": &email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code.as_ref().expose_secret()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_authenticator_code_reused(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+    let secret = setup_2fa(app).await;
+    logout(app).await;
+    let code = secret.code_at(app.clock.now());
+
+    for expected_status in [200, 401] {
+        let login_attempt_id = start_login(app, &email).await;
+        let response = app
+            .post_verify_2fa(&serde_json::json!({
+                "email": &email,
+                "loginAttemptId": login_attempt_id,
+                "2FACode": code.as_ref().expose_secret()
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), expected_status);
+        if expected_status == 200 {
+            logout(app).await;
+        }
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_for_old_authenticator_code(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+    let secret = setup_2fa(app).await;
+    logout(app).await;
+    let code = secret.code_at(app.clock.now());
+    app.clock.advance(TimeDelta::minutes(2));
+
+    let login_attempt_id = start_login(app, &email).await;
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": &email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": code.as_ref().expose_secret()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_replace_authenticator_on_setup_again(app: &mut TestApp) {
+    let email = get_session(app, true).await;
+    let old_secret = setup_2fa(app).await;
+    let new_secret = setup_2fa(app).await;
+    logout(app).await;
+
+    let now = app.clock.now();
+    let old_code = old_secret.code_at(now);
+    if old_code == new_secret.code_at(now) {
+        // One in a million, but nothing to test
+        return;
+    }
+    let login_attempt_id = start_login(app, &email).await;
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": &email,
+            "loginAttemptId": login_attempt_id,
+            "2FACode": old_code.as_ref().expose_secret()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_replace_backup_codes_on_setup(app: &mut TestApp) {
+    let email = get_random_email();
+    let signup_response = app
+        .post_signup(&serde_json::json!({
+            "email": &email,
+            "password": "password",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(signup_response.status().as_u16(), 201);
+    let signup_codes = signup_response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialise signup response")
+        .backup_codes;
+    login(app, &email, "password").await;
+
+    let response = app.post_setup_2fa().await;
+    assert_eq!(response.status().as_u16(), 201);
+    let setup_codes = response
+        .json::<TotpSetupResponse>()
+        .await
+        .expect("Could not deserialise setup response")
+        .backup_codes;
+    assert_eq!(setup_codes.len(), BACKUP_CODE_COUNT);
+    logout(app).await;
+
+    for (backup_code, expected_status) in
+        [(&signup_codes[0], 401), (&setup_codes[0], 200)]
+    {
+        let login_attempt_id = start_login(app, &email).await;
+        let response = app
+            .post_verify_2fa(&serde_json::json!({
+                "email": &email,
+                "loginAttemptId": login_attempt_id,
+                "2FACode": backup_code
+            }))
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            expected_status,
+            "Unexpected status for backup code {}",
+            backup_code
+        );
+    }
+}
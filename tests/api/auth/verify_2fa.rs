@@ -1,8 +1,14 @@
 use crate::helpers::{get_random_email, TestApp};
-use rota_manager::{domain::Email, utils::constants::JWT_COOKIE_NAME};
+use rota_manager::{
+    domain::Email, routes::auth::SignupResponse,
+    utils::constants::JWT_COOKIE_NAME,
+};
 use secrecy::{ExposeSecret, Secret};
 use test_context::test_context;
-use wiremock::{matchers::method, matchers::path, Mock, ResponseTemplate};
+use wiremock::{
+    matchers::{body_partial_json, method, path},
+    Mock, ResponseTemplate,
+};
 
 #[test_context(TestApp)]
 #[tokio::test]
@@ -344,3 +350,73 @@ async fn should_return_401_if_code_used_twice(app: &mut TestApp) {
         "Code should not be able to be used twice"
     );
 }
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_accept_each_backup_code_once(app: &mut TestApp) {
+    let email = get_random_email();
+    let parsed_email = Email::parse(Secret::new(email.clone())).unwrap();
+    let password = "password";
+
+    let signup_response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": password,
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(signup_response.status().as_u16(), 201);
+    let backup_codes = signup_response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialise signup response")
+        .backup_codes;
+
+    // Only the emailed login codes are counted, as each backup code used
+    // also sets off a security alert
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .and(body_partial_json(
+            serde_json::json!({"Subject": "LGR Bootcamp 2FA Code"}),
+        ))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(3)
+        .mount(&app.email_server)
+        .await;
+
+    for (backup_code, expected_status) in [
+        (&backup_codes[0], 200),
+        (&backup_codes[0], 401),
+        (&backup_codes[1], 200),
+    ] {
+        let login_response = app
+            .post_login(&serde_json::json!({
+                "email": email,
+                "password": password
+            }))
+            .await;
+        assert_eq!(login_response.status().as_u16(), 206);
+
+        let (login_attempt_id, _two_fa_code) = app
+            .two_fa_code_store
+            .read()
+            .await
+            .get_code(&parsed_email)
+            .await
+            .unwrap();
+
+        let response = app
+            .post_verify_2fa(&serde_json::json!({
+              "email": email,
+              "loginAttemptId": login_attempt_id.as_ref().expose_secret(),
+              "2FACode": backup_code.to_uppercase()
+            }))
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            expected_status,
+            "Unexpected status for backup code {}",
+            backup_code
+        );
+    }
+}
@@ -1,5 +1,8 @@
 use crate::helpers::{get_random_email, TestApp};
-use rota_manager::utils::constants::JWT_COOKIE_NAME;
+use chrono::TimeDelta;
+use rota_manager::utils::{
+    auth::TOKEN_TTL_SECONDS, constants::JWT_COOKIE_NAME,
+};
 use serde_json::json;
 use test_context::test_context;
 
@@ -63,6 +66,43 @@ async fn should_return_401_if_invalid_token(app: &mut TestApp) {
     assert_eq!(response.status().as_u16(), 401);
 }
 
+// The app's clock is moved on rather than waiting for the token to expire
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_once_token_has_expired(app: &mut TestApp) {
+    let email = get_random_email();
+    let password = "password";
+    app.post_signup(&json!({
+        "email": email,
+        "password": password,
+        "requires2FA": false
+    }))
+    .await;
+    let login_response = app
+        .post_login(&json!({
+            "email": email,
+            "password": password
+        }))
+        .await;
+    let token = login_response
+        .cookies()
+        .find(|cookie| cookie.name() == JWT_COOKIE_NAME)
+        .expect("No auth cookie found")
+        .value()
+        .to_owned();
+
+    app.clock.advance(TimeDelta::seconds(TOKEN_TTL_SECONDS - 1));
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(app.get_projects_list().await.status().as_u16(), 200);
+
+    // Past the minute's grace given for clocks that disagree
+    app.clock.advance(TimeDelta::minutes(2));
+    let response = app.post_verify_token(&json!({ "token": token })).await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(app.get_projects_list().await.status().as_u16(), 401);
+}
+
 #[test_context(TestApp)]
 #[tokio::test]
 async fn should_return_422_if_malformed_input(app: &mut TestApp) {
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use rota_manager::utils::chaos::Faults;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_random_email,
+    get_session, TestApp,
+};
+
+const COMMIT_FAILS: Faults = Faults {
+    latency: Duration::ZERO,
+    begin_error_rate: 0.0,
+    commit_error_rate: 1.0,
+};
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_500_and_keep_nothing_when_commit_fails(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+
+    app.faults.set(COMMIT_FAILS);
+    let response = app
+        .post_projects_new(&json!({"name": "Craggy Island"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 500);
+    app.faults.clear();
+
+    let response = app.get_projects_list().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["projects"], json!([]));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_add_member_when_commit_fails(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_member(app, "Ted", &project_id).await;
+
+    app.faults.set(COMMIT_FAILS);
+    let response = app
+        .post_add_member(&json!({
+            "memberName": "Dougal",
+            "projectId": &project_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 500);
+    app.faults.clear();
+
+    let response = app.get_members(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["members"].as_array().unwrap().len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_let_signup_be_retried_when_commit_fails(app: &mut TestApp) {
+    let signup = json!({
+        "email": get_random_email(),
+        "password": "password",
+        "requires2FA": false
+    });
+
+    app.faults.set(COMMIT_FAILS);
+    let response = app.post_signup(&signup).await;
+    assert_eq!(response.status().as_u16(), 500);
+    app.faults.clear();
+
+    // Not left half signed up, so the email address isn't taken
+    let response = app.post_signup(&signup).await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_500_when_no_transaction_can_be_started(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+
+    app.faults.set(Faults {
+        begin_error_rate: 1.0,
+        ..Faults::default()
+    });
+    let response = app.get_projects_list().await;
+    assert_eq!(response.status().as_u16(), 500);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["code"], "unexpected_error");
+
+    app.faults.clear();
+    let response = app.get_projects_list().await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_time_out_and_keep_nothing_when_store_is_slow(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+
+    // Longer than the test request timeout
+    app.faults.set(Faults {
+        latency: Duration::from_secs(6),
+        ..Faults::default()
+    });
+    let response = app
+        .post_projects_new(&json!({"name": "Craggy Island"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 503);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["code"], "request_timeout");
+    app.faults.clear();
+
+    let response = app.get_projects_list().await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["projects"], json!([]));
+}
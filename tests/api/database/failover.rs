@@ -0,0 +1,28 @@
+use test_context::test_context;
+
+use crate::helpers::{get_json_response_body, get_session, TestApp};
+
+// With no connection to be had, as during a failover, requests are answered
+// with a 503 to retry rather than a 500
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_503_when_database_is_unreachable(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    app.pg_pool.close().await;
+
+    for response in [
+        app.get_projects_list().await,
+        app.get_notifications(&[]).await,
+    ] {
+        assert_eq!(response.status().as_u16(), 503);
+        assert_eq!(
+            response
+                .headers()
+                .get("Retry-After")
+                .and_then(|value| value.to_str().ok()),
+            Some("5")
+        );
+        let body = get_json_response_body(response).await;
+        assert_eq!(body["code"], "database_unavailable");
+    }
+}
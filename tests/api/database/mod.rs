@@ -0,0 +1,4 @@
+mod failover;
+mod pgbouncer_mode;
+mod query_plans;
+mod retention;
@@ -0,0 +1,40 @@
+use rota_manager::{
+    app_state::DatabaseSettings, get_postgres_pool, run_migrations,
+    utils::constants::DATABASE_URL,
+};
+use secrecy::{ExposeSecret, Secret};
+use test_context::test_context;
+
+use crate::helpers::TestApp;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_query_and_migrate_without_cached_statements(app: &mut TestApp) {
+    let settings = DatabaseSettings {
+        pgbouncer_mode: true,
+    };
+    let url = Secret::new(format!(
+        "{}/{}",
+        DATABASE_URL.expose_secret(),
+        app.tmp_db_name
+    ));
+    let pool = get_postgres_pool(&url, &settings)
+        .await
+        .expect("Failed to connect in pgbouncer mode");
+
+    // Already migrated, so this only checks nothing is left to run
+    run_migrations(&pool, &settings)
+        .await
+        .expect("Failed to migrate without the migration lock");
+
+    // The same statement, prepared again each time
+    for expected in 0..3 {
+        let value: i32 = sqlx::query_scalar("SELECT $1")
+            .bind(expected)
+            .fetch_one(&pool)
+            .await
+            .expect("Failed to run query");
+        assert_eq!(value, expected);
+    }
+    pool.close().await;
+}
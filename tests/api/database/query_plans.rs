@@ -0,0 +1,70 @@
+use serde_json::Value;
+use test_context::test_context;
+
+use crate::helpers::TestApp;
+
+// Lookups made on almost every request, by the table they must not scan in
+// full. The IDs don't need to exist; only the plan matters.
+const HOT_PATHS: [(&str, &str); 4] = [
+    (
+        "projects_list",
+        "SELECT project_id, project_name FROM projects_list \
+         WHERE user_id = '00000000-0000-0000-0000-000000000001'",
+    ),
+    (
+        "members",
+        "SELECT member_id, member_name FROM members \
+         WHERE project_id = '00000000-0000-0000-0000-000000000001'",
+    ),
+    (
+        "shifts",
+        "SELECT id, day, in_time, out_time FROM shifts \
+         WHERE member_id = ANY('{00000000-0000-0000-0000-000000000001}')",
+    ),
+    (
+        "shifts",
+        "SELECT id FROM shifts \
+         WHERE member_id = '00000000-0000-0000-0000-000000000001' AND day = 1",
+    ),
+];
+
+// Every table the plan reads from start to finish
+fn seq_scans(plan: &Value, tables: &mut Vec<String>) {
+    if plan["Node Type"] == "Seq Scan" {
+        if let Some(table) = plan["Relation Name"].as_str() {
+            tables.push(table.to_owned());
+        }
+    }
+    for child in plan["Plans"].as_array().into_iter().flatten() {
+        seq_scans(child, tables);
+    }
+}
+
+// Test tables are tiny, so the planner would happily scan them anyway.
+// Turning sequential scans off leaves it no choice but an index, unless
+// there's no index to use.
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_scan_whole_tables_on_hot_paths(app: &mut TestApp) {
+    let mut tx = app.pg_pool.begin().await.unwrap();
+    sqlx::query("SET LOCAL enable_seqscan = off")
+        .execute(&mut *tx)
+        .await
+        .expect("Failed to turn off sequential scans");
+
+    for (table, query) in HOT_PATHS {
+        let explained: Value =
+            sqlx::query_scalar(&format!("EXPLAIN (FORMAT JSON) {}", query))
+                .fetch_one(&mut *tx)
+                .await
+                .expect("Failed to explain query");
+        let mut tables = Vec::new();
+        seq_scans(&explained[0]["Plan"], &mut tables);
+        assert!(
+            !tables.iter().any(|scanned| scanned == table),
+            "{} is scanned in full by: {}",
+            table,
+            query
+        );
+    }
+}
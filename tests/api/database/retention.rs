@@ -0,0 +1,186 @@
+use chrono::Utc;
+use rota_manager::{
+    domain::{Email, RetentionPolicy},
+    services::retention::prune_expired_records,
+    utils::metrics::RETENTION_ROWS_DELETED_METRIC,
+};
+use secrecy::Secret;
+use test_context::test_context;
+
+use crate::helpers::{add_new_project, get_admin_key, get_session, TestApp};
+
+async fn execute(app: &TestApp, query: &str, project_id: uuid::Uuid) {
+    sqlx::query(query)
+        .bind(project_id)
+        .execute(&app.pg_pool)
+        .await
+        .expect("Failed to insert test rows");
+}
+
+async fn count(app: &TestApp, query: &str) -> i64 {
+    sqlx::query_scalar(query)
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("Failed to count rows")
+}
+
+// A row of each kind from before the default retention windows, alongside
+// rows that are still kept
+async fn add_old_records(app: &TestApp, project_id: uuid::Uuid) {
+    execute(
+        app,
+        "INSERT INTO project_events \
+         (project_id, entity_type, event, occurred_at) \
+         VALUES ($1, 'project', '{}', now() - interval '400 days')",
+        project_id,
+    )
+    .await;
+    execute(
+        app,
+        "INSERT INTO email_outbox \
+         (recipient, subject, content, project_id, created_at, sent_at) \
+         VALUES \
+         ('ted@example.com', 'Old', '', $1, \
+          now() - interval '100 days', now() - interval '100 days'), \
+         ('ted@example.com', 'Unsent', '', $1, \
+          now() - interval '100 days', NULL), \
+         ('ted@example.com', 'Recent', '', $1, \
+          now() - interval '10 days', now() - interval '10 days')",
+        project_id,
+    )
+    .await;
+    sqlx::query(
+        "INSERT INTO sms_outbox (recipient, content, created_at, sent_at) \
+         VALUES ('+447700900123', '', \
+             now() - interval '100 days', now() - interval '100 days')",
+    )
+    .execute(&app.pg_pool)
+    .await
+    .expect("Failed to insert test rows");
+    execute(
+        app,
+        "INSERT INTO rota_versions (version_id, project_id, week_of, shifts) \
+         VALUES \
+         (gen_random_uuid(), $1, current_date - 800, '[]'), \
+         (gen_random_uuid(), $1, current_date - 7, '[]')",
+        project_id,
+    )
+    .await;
+    execute(
+        app,
+        "INSERT INTO rota_approvals \
+         (approval_id, project_id, version_id, week_of, shifts, changes, \
+          approver_email, status) \
+         VALUES \
+         (gen_random_uuid(), $1, gen_random_uuid(), current_date - 800, \
+          '[]', '[]', 'mrs.doyle@example.com', 'approved'), \
+         (gen_random_uuid(), $1, gen_random_uuid(), current_date - 800, \
+          '[]', '[]', 'mrs.doyle@example.com', 'pending')",
+        project_id,
+    )
+    .await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_prune_records_past_their_retention(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let project_id = uuid::Uuid::parse_str(&project_id).unwrap();
+    add_old_records(app, project_id).await;
+    let events = count(app, "SELECT count(*) FROM project_events").await;
+
+    let deleted = prune_expired_records(
+        &app.pg_pool,
+        &RetentionPolicy::default(),
+        Utc::now(),
+    )
+    .await
+    .expect("Failed to prune expired records");
+    assert_eq!(deleted, 5);
+
+    assert_eq!(
+        count(app, "SELECT count(*) FROM project_events").await,
+        events - 1
+    );
+    let subjects: Vec<String> = sqlx::query_scalar(
+        "SELECT subject FROM email_outbox WHERE project_id = $1 ORDER BY id",
+    )
+    .bind(project_id)
+    .fetch_all(&app.pg_pool)
+    .await
+    .unwrap();
+    assert_eq!(subjects, vec!["Unsent", "Recent"]);
+    assert_eq!(count(app, "SELECT count(*) FROM sms_outbox").await, 0);
+    assert_eq!(count(app, "SELECT count(*) FROM rota_versions").await, 1);
+    assert_eq!(
+        count(
+            app,
+            "SELECT count(*) FROM rota_approvals WHERE status = 'pending'"
+        )
+        .await,
+        1
+    );
+    assert_eq!(count(app, "SELECT count(*) FROM rota_approvals").await, 1);
+
+    // Nothing is left to prune
+    let deleted = prune_expired_records(
+        &app.pg_pool,
+        &RetentionPolicy::default(),
+        Utc::now(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(deleted, 0);
+
+    let metrics = app
+        .get_metrics(Some(&get_admin_key()))
+        .await
+        .text()
+        .await
+        .unwrap();
+    assert!(metrics.lines().any(|line| {
+        line.starts_with(RETENTION_ROWS_DELETED_METRIC)
+            && line.contains(r#"table="rota_versions""#)
+    }));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_keep_records_of_held_accounts(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let project_id = uuid::Uuid::parse_str(&project_id).unwrap();
+    add_old_records(app, project_id).await;
+    {
+        let email = Email::parse(Secret::new(email)).unwrap();
+        let mut user_store = app.user_store.write().await;
+        let user_id = user_store.get_user(&email).await.unwrap().id;
+        user_store
+            .set_legal_hold(&user_id, Some(Utc::now()))
+            .await
+            .unwrap();
+    }
+    let events = count(app, "SELECT count(*) FROM project_events").await;
+
+    let deleted = prune_expired_records(
+        &app.pg_pool,
+        &RetentionPolicy::default(),
+        Utc::now(),
+    )
+    .await
+    .expect("Failed to prune expired records");
+
+    // Only the text, which isn't tied to an account
+    assert_eq!(deleted, 1);
+    assert_eq!(
+        count(app, "SELECT count(*) FROM project_events").await,
+        events
+    );
+    let emails = format!(
+        "SELECT count(*) FROM email_outbox WHERE project_id = '{project_id}'"
+    );
+    assert_eq!(count(app, &emails).await, 3);
+    assert_eq!(count(app, "SELECT count(*) FROM rota_versions").await, 2);
+    assert_eq!(count(app, "SELECT count(*) FROM rota_approvals").await, 2);
+}
@@ -0,0 +1,190 @@
+use chrono::{TimeDelta, Utc};
+use rota_manager::{
+    domain::Clock,
+    services::shift_reminders::send_shift_reminders,
+    utils::{constants::JWT_SECRET, email_replies::reply_token},
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_session, PostmarkTestApp, TestApp,
+    POSTMARK_WEBHOOK_TOKEN,
+};
+
+const TED: &str = "ted@example.com";
+const INBOUND_ADDRESS: &str = "replies@inbound.example.com";
+
+// Give Ted a Monday shift and email the reminder for the next one. Returns
+// the reminder's ID.
+async fn remind_ted(app: &mut TestApp) -> i64 {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .put_member_contact(&ted, &json!({"email": TED, "emailOptIn": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let now = app.clock.now().naive_utc();
+    let queued = send_shift_reminders(
+        &app.pg_pool,
+        now,
+        TimeDelta::weeks(1),
+        Some(INBOUND_ADDRESS),
+    )
+    .await
+    .expect("Failed to send shift reminders");
+    assert_eq!(queued, 1);
+
+    sqlx::query_scalar("SELECT reminder_id FROM shift_reminders")
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("Failed to fetch reminder")
+}
+
+fn inbound_email(reminder_id: i64, reply: &str) -> Value {
+    json!({
+        "From": TED,
+        "MailboxHash": reply_token(&JWT_SECRET, reminder_id),
+        "TextBody": format!("{}\n\n> Reply ACCEPT to confirm", reply),
+        "StrippedTextReply": reply
+    })
+}
+
+async fn reply_times(
+    app: &TestApp,
+) -> (Option<chrono::DateTime<Utc>>, Option<chrono::DateTime<Utc>>) {
+    sqlx::query_as(
+        "SELECT confirmed_at, swap_requested_at FROM shift_reminders",
+    )
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to fetch reminder")
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_email_reminder_with_signed_reply_to(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let reminder_id = remind_ted(app).await;
+
+    let (subject, content, reply_to): (String, String, Option<String>) =
+        sqlx::query_as(
+            "SELECT subject, content, reply_to FROM email_outbox
+             WHERE subject LIKE 'Reminder:%'",
+        )
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("Failed to fetch queued email");
+    assert!(subject.starts_with("Reminder: Craggy Island shift on Monday"));
+    assert!(content.contains("Reply ACCEPT to confirm"));
+    assert_eq!(
+        reply_to,
+        Some(format!(
+            "replies+{}@inbound.example.com",
+            reply_token(&JWT_SECRET, reminder_id)
+        ))
+    );
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_confirm_shift_on_accept(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let reminder_id = remind_ted(app).await;
+
+    let response = app
+        .post_inbound_email(
+            &inbound_email(reminder_id, "Accept"),
+            POSTMARK_WEBHOOK_TOKEN,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let (confirmed_at, swap_requested_at) = reply_times(app).await;
+    assert!(confirmed_at.is_some());
+    assert!(swap_requested_at.is_none());
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_email_owner_on_decline(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let reminder_id = remind_ted(app).await;
+
+    let response = app
+        .post_inbound_email(
+            &inbound_email(reminder_id, "DECLINE, sorry"),
+            POSTMARK_WEBHOOK_TOKEN,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let (confirmed_at, swap_requested_at) = reply_times(app).await;
+    assert!(confirmed_at.is_none());
+    assert!(swap_requested_at.is_some());
+
+    let swap_emails: i64 = sqlx::query_scalar(
+        "SELECT count(*) FROM email_outbox
+         WHERE subject = 'Ted asked to swap a shift'",
+    )
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to count queued emails");
+    assert_eq!(swap_emails, 1);
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_ignore_unusable_replies(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let reminder_id = remind_ted(app).await;
+    let other_reminder = reply_token(&JWT_SECRET, reminder_id + 1);
+    let (_, signature) = other_reminder.split_once('-').unwrap();
+
+    let test_cases = [
+        inbound_email(reminder_id, "Maybe"),
+        // A signature for another reminder
+        json!({
+            "MailboxHash": format!("{}-{}", reminder_id, signature),
+            "StrippedTextReply": "ACCEPT"
+        }),
+        // A valid token for a reminder that doesn't exist
+        inbound_email(reminder_id + 1, "ACCEPT"),
+        json!({"TextBody": "ACCEPT"}),
+    ];
+    for body in test_cases {
+        let response =
+            app.post_inbound_email(&body, POSTMARK_WEBHOOK_TOKEN).await;
+        assert_eq!(response.status().as_u16(), 200, "Failed for {}", body);
+    }
+
+    let (confirmed_at, swap_requested_at) = reply_times(app).await;
+    assert!(confirmed_at.is_none());
+    assert!(swap_requested_at.is_none());
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_reject_wrong_webhook_token(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let reminder_id = remind_ted(app).await;
+
+    let response = app
+        .post_inbound_email(&inbound_email(reminder_id, "ACCEPT"), "wrong")
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let (confirmed_at, _) = reply_times(app).await;
+    assert!(confirmed_at.is_none());
+}
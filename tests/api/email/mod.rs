@@ -0,0 +1,6 @@
+mod inbound;
+mod open_tracking;
+mod outbox;
+mod rota_change_digest;
+mod security_emails;
+mod webhook;
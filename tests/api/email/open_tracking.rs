@@ -0,0 +1,171 @@
+use std::time::Duration;
+
+use rota_manager::{
+    routes::{auth::EmailTrackingResponse, projects::ProjectEmailListResponse},
+    utils::constants::test,
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, login, logout, TestApp,
+};
+
+const APPROVAL_SUBJECT: &str =
+    "A Craggy Island rota is waiting for your approval";
+
+// Signs up the approver, who may turn open tracking off, then leaves the
+// owner signed in having submitted a rota for approval. Returns the
+// approver's and owner's emails and the project.
+async fn submit_for_approval(
+    app: &mut TestApp,
+    tracking: bool,
+) -> (String, String, String) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let approver = get_session(app, false).await;
+    if !tracking {
+        let response = app.put_email_tracking(&json!({"enabled": false})).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let body = get_json_response_body(response).await;
+        assert!(
+            jsonschema::is_valid(&get_schema::<EmailTrackingResponse>(), &body),
+            "response does not match schema"
+        );
+        assert_eq!(body["enabled"], false);
+    }
+    logout(app).await;
+
+    let owner = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let response = app
+        .put_project_settings(&project_id, &json!({"approverEmail": approver}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app
+        .post_submit_rota(
+            &json!({"projectId": &project_id, "weekOf": "2025-10-26"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    (approver, owner, project_id)
+}
+
+// The HTML body of the email sent to `recipient`, once the relay sends it
+async fn wait_for_html_body(app: &TestApp, recipient: &str) -> String {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sent = app
+                .email_server
+                .received_requests()
+                .await
+                .expect("Request recording is disabled")
+                .iter()
+                .map(|request| request.body_json::<Value>().unwrap())
+                .find(|body| {
+                    body["To"] == recipient
+                        && body["Subject"] == APPROVAL_SUBJECT
+                });
+            if let Some(body) = sent {
+                return body["HtmlBody"].as_str().unwrap().to_owned();
+            }
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for approval email")
+}
+
+fn open_token(html_body: &str) -> &str {
+    let (_, token) = html_body
+        .split_once("/email/open?token=")
+        .expect("Email has no tracking image");
+    token.split('"').next().unwrap()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_record_tracked_email_being_opened(app: &mut TestApp) {
+    let (approver, _, project_id) = submit_for_approval(app, true).await;
+    let html_body = wait_for_html_body(app, &approver).await;
+    let token = open_token(&html_body).to_owned();
+
+    let body =
+        get_json_response_body(app.get_project_emails(&project_id).await).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectEmailListResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["emails"][0]["recipient"], approver.as_str());
+    assert_eq!(body["emails"][0]["status"], "sent");
+    assert_eq!(body["emails"][0]["tracked"], true);
+
+    // Someone else's guess at a token changes nothing
+    let (delivery_id, _) = token.split_once('.').unwrap();
+    let response = app.get_open_email(&format!("{}.forged", delivery_id)).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body =
+        get_json_response_body(app.get_project_emails(&project_id).await).await;
+    assert_eq!(body["emails"][0]["status"], "sent");
+
+    let response = app.get_open_email(&token).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["content-type"], "image/gif");
+    assert_eq!(response.headers()["cache-control"], "no-store");
+
+    let body =
+        get_json_response_body(app.get_project_emails(&project_id).await).await;
+    assert_eq!(body["emails"][0]["status"], "opened");
+    let opened_at = body["emails"][0]["openedAt"].clone();
+    assert!(opened_at.is_string());
+
+    // Only the first time is kept
+    app.get_open_email(&token).await;
+    let body =
+        get_json_response_body(app.get_project_emails(&project_id).await).await;
+    assert_eq!(body["emails"][0]["openedAt"], opened_at);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_track_recipients_who_opted_out(app: &mut TestApp) {
+    let (approver, _, project_id) = submit_for_approval(app, false).await;
+    let html_body = wait_for_html_body(app, &approver).await;
+    assert!(!html_body.contains("<img"));
+
+    let body =
+        get_json_response_body(app.get_project_emails(&project_id).await).await;
+    assert_eq!(body["emails"][0]["status"], "sent");
+    assert_eq!(body["emails"][0]["tracked"], false);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_show_emails_to_project_owner(app: &mut TestApp) {
+    let (approver, _, project_id) = submit_for_approval(app, true).await;
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let response = app.get_project_emails(&project_id).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use rota_manager::{
+    domain::Email, services::data_stores::enqueue_email, utils::constants::test,
+};
+use secrecy::Secret;
+use serde_json::Value;
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{get_random_email, TestApp};
+
+async fn enqueue_emails(app: &TestApp, recipient: &str, count: usize) {
+    let recipient = Email::parse(Secret::new(recipient.to_owned())).unwrap();
+    let mut tx = app.pg_pool.begin().await.expect("Failed to begin");
+    for i in 0..count {
+        enqueue_email(&mut tx, &recipient, &format!("Update {}", i), "Hello")
+            .await
+            .expect("Failed to enqueue email");
+    }
+    tx.commit().await.expect("Failed to commit");
+}
+
+async fn count_pending_emails(app: &TestApp) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM email_outbox WHERE sent_at IS NULL",
+    )
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to count pending emails")
+}
+
+async fn wait_for_outbox_to_drain(app: &TestApp) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while count_pending_emails(app).await > 0 {
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for emails to be sent");
+}
+
+async fn sent_subjects(app: &TestApp) -> Vec<String> {
+    app.email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .map(|request| {
+            let body: Value = request.body_json().unwrap();
+            body["Subject"].as_str().unwrap().to_owned()
+        })
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_collapse_bursts_beyond_hourly_limit(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let recipient = get_random_email();
+
+    // Within the limit, emails go out as they are
+    enqueue_emails(app, &recipient, 2).await;
+    wait_for_outbox_to_drain(app).await;
+    assert_eq!(sent_subjects(app).await, vec!["Update 0", "Update 1"]);
+
+    // One email left this hour, so the burst becomes a single digest
+    enqueue_emails(app, &recipient, 3).await;
+    wait_for_outbox_to_drain(app).await;
+    let subjects = sent_subjects(app).await;
+    assert_eq!(subjects.len(), 3);
+    assert_eq!(subjects[2], "You have 3 new notifications");
+
+    // The limit is used up, so further emails are held back
+    enqueue_emails(app, &recipient, 1).await;
+    tokio::time::sleep(test::OUTBOX_POLL_INTERVAL * 5).await;
+    assert_eq!(count_pending_emails(app).await, 1);
+    assert_eq!(sent_subjects(app).await.len(), 3);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_limit_each_recipient_separately(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let limit = test::EMAILS_PER_RECIPIENT_PER_HOUR as usize;
+    enqueue_emails(app, &get_random_email(), limit).await;
+    enqueue_emails(app, &get_random_email(), limit).await;
+    wait_for_outbox_to_drain(app).await;
+
+    assert_eq!(sent_subjects(app).await.len(), limit * 2);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_retry_failed_sends(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .mount(&app.email_server)
+        .await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    enqueue_emails(app, &get_random_email(), 1).await;
+    wait_for_outbox_to_drain(app).await;
+
+    assert_eq!(sent_subjects(app).await, vec!["Update 0", "Update 0"]);
+}
@@ -0,0 +1,134 @@
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{add_member, add_new_project, get_session, TestApp};
+
+async fn add_shifts(app: &mut TestApp, member_id: &str, count: i16) {
+    for i in 0..count {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": "Monday",
+                "startTime": 60 * i,
+                "endTime": 60 * i + 30
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+}
+
+async fn wait_for_emails(app: &TestApp, count: usize) -> Vec<Value> {
+    tokio::time::timeout(Duration::from_secs(10), async {
+        loop {
+            let requests = app
+                .email_server
+                .received_requests()
+                .await
+                .expect("Request recording is disabled");
+            if requests.len() >= count {
+                return requests
+                    .iter()
+                    .map(|request| request.body_json().unwrap())
+                    .collect();
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for emails")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_one_digest_for_a_burst_of_edits(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    add_shifts(app, &member_id, 3).await;
+
+    let emails = wait_for_emails(app, 1).await;
+    assert_eq!(emails[0]["To"], email);
+    assert_eq!(emails[0]["Subject"], "3 shifts changed for Ted");
+
+    // Nothing else is waiting to be sent
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    assert_eq!(wait_for_emails(app, 1).await.len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_send_a_digest_per_member(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    add_shifts(app, &ted, 2).await;
+    add_shifts(app, &dougal, 1).await;
+
+    let mut subjects: Vec<String> = wait_for_emails(app, 2)
+        .await
+        .iter()
+        .map(|email| email["Subject"].as_str().unwrap().to_owned())
+        .collect();
+    subjects.sort();
+
+    assert_eq!(
+        subjects,
+        vec!["1 shift changed for Dougal", "2 shifts changed for Ted"]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_tell_owner_about_standby_assignments(app: &mut TestApp) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "standbyMemberId": &dougal
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let mut subjects: Vec<String> = wait_for_emails(app, 2)
+        .await
+        .iter()
+        .map(|email| email["Subject"].as_str().unwrap().to_owned())
+        .collect();
+    subjects.sort();
+
+    assert_eq!(
+        subjects,
+        vec!["1 shift changed for Dougal", "1 shift changed for Ted"]
+    );
+}
@@ -0,0 +1,240 @@
+use std::time::Duration;
+
+use rota_manager::{
+    domain::Email, routes::auth::SignupResponse, utils::constants::test,
+};
+use secrecy::{ExposeSecret, Secret};
+use serde_json::Value;
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{
+    get_random_email, get_sent_token, get_session, signup, TestApp,
+};
+
+const BACKUP_CODE_SUBJECT: &str =
+    "Security alert: A backup code was used to sign in to your account";
+const EMAIL_CHANGED_SUBJECT: &str =
+    "Security alert: Your email address was changed";
+const AUTHENTICATOR_SUBJECT: &str =
+    "Security alert: An authenticator app was set up for your account";
+const INTEGRATION_KEY_SUBJECT: &str =
+    "Security alert: An integration key was created for your account";
+const PASSWORD_CHANGED_SUBJECT: &str =
+    "Security alert: Your password was changed";
+const TWO_FA_OFF_SUBJECT: &str =
+    "Security alert: Two-factor authentication was turned off";
+
+async fn sent_subjects(app: &TestApp, recipient: &str) -> Vec<String> {
+    app.email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .filter_map(|request| {
+            let body: Value = request.body_json().unwrap();
+            (body["To"] == recipient)
+                .then(|| body["Subject"].as_str().unwrap().to_owned())
+        })
+        .collect()
+}
+
+async fn wait_for_email(app: &TestApp, recipient: &str, subject: &str) {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while !sent_subjects(app, recipient)
+            .await
+            .iter()
+            .any(|sent| sent == subject)
+        {
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for security email");
+}
+
+// Sign up with 2FA and return the user's email and backup codes
+async fn signup_with_2fa(app: &TestApp) -> (String, Vec<String>) {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+
+    let email = get_random_email();
+    let response = app
+        .post_signup(&serde_json::json!({
+            "email": email,
+            "password": "password",
+            "requires2FA": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let backup_codes = response
+        .json::<SignupResponse>()
+        .await
+        .expect("Could not deserialise signup response")
+        .backup_codes;
+    (email, backup_codes)
+}
+
+async fn login_with_backup_code(app: &TestApp, email: &str, code: &str) {
+    let response = app
+        .post_login(&serde_json::json!({
+            "email": email,
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 206);
+
+    let (login_attempt_id, _) = app
+        .two_fa_code_store
+        .read()
+        .await
+        .get_code(&Email::parse(Secret::new(email.to_owned())).unwrap())
+        .await
+        .unwrap();
+
+    let response = app
+        .post_verify_2fa(&serde_json::json!({
+            "email": email,
+            "loginAttemptId": login_attempt_id.as_ref().expose_secret(),
+            "2FACode": code
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_email_when_backup_code_used(app: &mut TestApp) {
+    let (email, backup_codes) = signup_with_2fa(app).await;
+
+    login_with_backup_code(app, &email, &backup_codes[0]).await;
+
+    wait_for_email(app, &email, BACKUP_CODE_SUBJECT).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_send_critical_emails_when_turned_off(app: &mut TestApp) {
+    let (email, backup_codes) = signup_with_2fa(app).await;
+    login_with_backup_code(app, &email, &backup_codes[0]).await;
+    wait_for_email(app, &email, BACKUP_CODE_SUBJECT).await;
+
+    let response = app
+        .put_security_emails(&serde_json::json!({ "enabled": false }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    login_with_backup_code(app, &email, &backup_codes[1]).await;
+
+    // Alerts go out in order, so once the critical one has been sent the
+    // backup code alert would have been too
+    let new_email = get_random_email();
+    app.put_email(&serde_json::json!({ "newEmail": new_email }))
+        .await;
+    for recipient in [&email, &new_email] {
+        let token = get_sent_token(app, recipient).await;
+        let response = app
+            .post_confirm_email_change(&serde_json::json!({ "token": token }))
+            .await;
+        assert!(response.status().is_success());
+    }
+    wait_for_email(app, &new_email, EMAIL_CHANGED_SUBJECT).await;
+
+    let backup_code_emails = sent_subjects(app, &email)
+        .await
+        .into_iter()
+        .filter(|subject| subject == BACKUP_CODE_SUBJECT)
+        .count();
+    assert_eq!(backup_code_emails, 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_always_email_when_2fa_turned_off(app: &mut TestApp) {
+    let (email, backup_codes) = signup_with_2fa(app).await;
+    login_with_backup_code(app, &email, &backup_codes[0]).await;
+    let response = app
+        .put_security_emails(&serde_json::json!({ "enabled": false }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .put_requires_2fa(&serde_json::json!({
+            "requires2FA": false,
+            "password": "password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    wait_for_email(app, &email, TWO_FA_OFF_SUBJECT).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_email_when_password_reset(app: &mut TestApp) {
+    let email = get_random_email();
+    signup(app, &email, "password", false).await;
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    let response = app
+        .post_forgot_password(&serde_json::json!({ "email": email }))
+        .await;
+    assert_eq!(response.status().as_u16(), 202);
+    let token = get_sent_token(app, &email).await;
+
+    let response = app
+        .post_reset_password(&serde_json::json!({
+            "token": token,
+            "newPassword": "new password"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    wait_for_email(app, &email, PASSWORD_CHANGED_SUBJECT).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_always_email_when_authenticator_set_up(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let response = app
+        .put_security_emails(&serde_json::json!({ "enabled": false }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app.post_setup_2fa().await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    wait_for_email(app, &email, AUTHENTICATOR_SUBJECT).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_email_when_integration_key_created(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+
+    let response = app
+        .post_integration_key(&serde_json::json!({ "name": "Zapier" }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    wait_for_email(app, &email, INTEGRATION_KEY_SUBJECT).await;
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_missing_token(app: &mut TestApp) {
+    let response = app
+        .put_security_emails(&serde_json::json!({ "enabled": false }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
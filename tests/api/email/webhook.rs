@@ -0,0 +1,217 @@
+use std::time::Duration;
+
+use rota_manager::{
+    domain::Email, routes::me::EmailStatusResponse,
+    services::data_stores::enqueue_email, utils::constants::test,
+};
+use secrecy::Secret;
+use serde_json::{json, Value};
+use test_context::test_context;
+use wiremock::{
+    matchers::{method, path},
+    Mock, ResponseTemplate,
+};
+
+use crate::helpers::{
+    get_json_response_body, get_schema, get_session, PostmarkTestApp, TestApp,
+    POSTMARK_WEBHOOK_TOKEN,
+};
+
+fn bounce(email: &str, bounce_type: &str) -> Value {
+    json!({
+        "RecordType": "Bounce",
+        "Type": bounce_type,
+        "Email": email,
+        "BouncedAt": "2025-11-13T09:00:00Z"
+    })
+}
+
+async fn sign_up(app: &mut TestApp) -> String {
+    Mock::given(path("/email"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&app.email_server)
+        .await;
+    get_session(app, false).await
+}
+
+async fn email_status(app: &TestApp) -> Value {
+    let response = app.get_email_status().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<EmailStatusResponse>(), &body),
+        "response does not match schema"
+    );
+    body
+}
+
+// Queue an email to `recipient` and wait for the relay to deal with it.
+// Returns whether it was suppressed.
+async fn relay_email(app: &TestApp, recipient: &str) -> bool {
+    let email = Email::parse(Secret::new(recipient.to_owned())).unwrap();
+    let mut tx = app.pg_pool.begin().await.expect("Failed to begin");
+    enqueue_email(&mut tx, &email, "Your rota has changed", "Hello")
+        .await
+        .expect("Failed to enqueue email");
+    // Earlier emails to the same address may already have been relayed, so
+    // wait on this one alone
+    let (id,): (i64,) =
+        sqlx::query_as("SELECT MAX(id) FROM email_outbox WHERE recipient = $1")
+            .bind(recipient)
+            .fetch_one(&mut *tx)
+            .await
+            .expect("Failed to fetch the queued email");
+    tx.commit().await.expect("Failed to commit");
+
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let row: Option<(bool,)> = sqlx::query_as(
+                "SELECT suppressed FROM email_outbox \
+                 WHERE id = $1 AND (sent_at IS NOT NULL OR suppressed)",
+            )
+            .bind(id)
+            .fetch_optional(&app.pg_pool)
+            .await
+            .expect("Failed to fetch queued email");
+            if let Some((suppressed,)) = row {
+                return suppressed;
+            }
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for the email to be relayed")
+}
+
+// Every confirmation token sent to `recipient`, oldest first
+async fn sent_tokens(app: &TestApp, recipient: &str) -> Vec<String> {
+    app.email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .filter_map(|request| {
+            let body: Value = request.body_json().unwrap();
+            if body["To"] != recipient {
+                return None;
+            }
+            let text = body["TextBody"].as_str().unwrap();
+            let start = text.find("token=")? + 6;
+            Some(text[start..start + 36].to_owned())
+        })
+        .collect()
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_suppress_email_to_bounced_address_until_confirmed(
+    ctx: &mut PostmarkTestApp,
+) {
+    let app = &mut ctx.0;
+    let email = sign_up(app).await;
+    assert_eq!(email_status(app).await["deliverable"], true);
+
+    let response = app
+        .post_email_webhook(
+            &bounce(&email, "HardBounce"),
+            POSTMARK_WEBHOOK_TOKEN,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = email_status(app).await;
+    assert_eq!(body["email"], email.as_str());
+    assert_eq!(body["deliverable"], false);
+    assert_eq!(body["reason"], "bounced");
+    assert!(body["undeliverableSince"].is_string());
+    assert!(relay_email(app, &email).await);
+
+    // Confirming the same address again lets email through
+    let response = app.put_email(&json!({"newEmail": &email})).await;
+    assert_eq!(response.status().as_u16(), 202);
+    let tokens = sent_tokens(app, &email).await;
+    assert_eq!(tokens.len(), 2);
+    for token in tokens {
+        app.post_confirm_email_change(&json!({"token": token}))
+            .await;
+    }
+
+    let body = email_status(app).await;
+    assert_eq!(body["deliverable"], true);
+    assert_eq!(body["reason"], json!(null));
+    assert!(!relay_email(app, &email).await);
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_mark_complaints_undeliverable(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let email = sign_up(app).await;
+
+    let response = app
+        .post_email_webhook(
+            &json!({
+                "RecordType": "SpamComplaint",
+                "Type": "SpamComplaint",
+                "Email": &email
+            }),
+            POSTMARK_WEBHOOK_TOKEN,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = email_status(app).await;
+    assert_eq!(body["deliverable"], false);
+    assert_eq!(body["reason"], "complained");
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_ignore_soft_bounces_and_other_records(
+    ctx: &mut PostmarkTestApp,
+) {
+    let app = &mut ctx.0;
+    let email = sign_up(app).await;
+
+    for record in [
+        bounce(&email, "SoftBounce"),
+        bounce(&email, "Transient"),
+        json!({"RecordType": "Delivery", "Recipient": &email}),
+    ] {
+        let response = app
+            .post_email_webhook(&record, POSTMARK_WEBHOOK_TOKEN)
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    assert_eq!(email_status(app).await["deliverable"], true);
+    assert!(!relay_email(app, &email).await);
+}
+
+#[test_context(PostmarkTestApp)]
+#[tokio::test]
+async fn should_reject_webhooks_without_token(ctx: &mut PostmarkTestApp) {
+    let app = &mut ctx.0;
+    let email = sign_up(app).await;
+
+    let response = app
+        .post_email_webhook(&bounce(&email, "HardBounce"), "wrong_token")
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+    assert_eq!(email_status(app).await["deliverable"], true);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_webhooks_when_not_configured(app: &mut TestApp) {
+    let email = sign_up(app).await;
+
+    let response = app
+        .post_email_webhook(
+            &bounce(&email, "HardBounce"),
+            POSTMARK_WEBHOOK_TOKEN,
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
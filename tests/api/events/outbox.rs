@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use rota_manager::domain::DomainEvent;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{get_session, wait_for_event, TestApp};
+
+async fn count_outbox_events(app: &TestApp, event_type: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT COUNT(*) FROM event_outbox WHERE event->>'type' = $1",
+    )
+    .bind(event_type)
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to count outbox events")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_mark_relayed_events_delivered(app: &mut TestApp) {
+    let mut events = app.event_bus.subscribe();
+    let _email = get_session(app, false).await;
+
+    wait_for_event(&mut events, |event| {
+        matches!(event, DomainEvent::UserSignedUp { .. })
+    })
+    .await;
+
+    // The relay publishes before committing the batch as delivered
+    let delivered = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let delivered: bool = sqlx::query_scalar(
+                "SELECT delivered_at IS NOT NULL FROM event_outbox \
+                 WHERE event->>'type' = 'UserSignedUp'",
+            )
+            .fetch_one(&app.pg_pool)
+            .await
+            .expect("Failed to read outbox event");
+
+            if delivered {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+    })
+    .await;
+
+    assert!(delivered.is_ok(), "Event was never marked delivered");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_enqueue_events_for_failed_changes(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app
+        .post_add_member(&json!({
+            "memberName": "Ted",
+            "projectId": uuid::Uuid::new_v4().to_string()
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    assert_eq!(count_outbox_events(app, "MemberAdded").await, 0);
+}
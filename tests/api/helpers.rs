@@ -1,32 +1,60 @@
+use chrono::{TimeDelta, Utc};
 use reqwest::{cookie::Jar, Client, Response, StatusCode};
+#[cfg(feature = "chaos")]
+use rota_manager::utils::chaos::FaultInjector;
 use rota_manager::{
     app_state::{
-        AppState, BannedTokenStoreType, ProjectStoreType, TwoFACodeStoreType,
+        AppState, BannedTokenStoreType, DatabaseSettings, EventBusType,
+        JobLockStoreType, ProjectStoreType, Settings, TwoFACodeStoreType,
         UserStoreType,
     },
-    domain::Email,
+    domain::{
+        DomainEvent, Email, EventReceiver, PhoneNumber, ResidencyRules,
+        VerificationToken,
+    },
     get_postgres_pool, get_redis_client,
     services::{
+        broadcast_event_bus::BroadcastEventBus,
+        clock::ManualClock,
         data_stores::{
-            PostgresProjectStore, PostgresUserStore, RedisBannedTokenStore,
-            RedisTwoFACodeStore,
+            HashmapJobLockStore, PostgresIntegrationStore,
+            PostgresProjectStore, PostgresShiftReminderStore,
+            PostgresUserStore, RedisBannedTokenStore,
+            RedisDeprecatedUsageStore, RedisPasswordResetTokenStore,
+            RedisRequestCaptureStore, RedisSessionStore, RedisTwoFACodeStore,
+            RedisVerificationTokenStore,
         },
+        email_outbox_relay::spawn_email_outbox_relay,
+        event_subscribers::{audit_log, spawn_subscriber},
+        id_generator::SequentialIdGenerator,
+        job_worker::spawn_job_worker,
+        notification_digest::spawn_rota_change_digests,
+        notification_inbox::spawn_notification_inbox,
+        outbox_relay::spawn_outbox_relay,
         postmark_email_client::PostmarkEmailClient,
+        security_emails::spawn_security_emails,
+        sms_outbox_relay::spawn_sms_outbox_relay,
+        twilio_sms_client::TwilioSmsClient,
     },
     utils::constants::{
-        test, DATABASE_URL, POSTMARK_EMAIL_SENDER_ADDRESS, REDIS_HOST_NAME,
+        test, ADMIN_API_KEY, ADMIN_KEY_HEADER, APP_SERVICE_EXTERNAL_ADDRESS,
+        DATABASE_URL, EVENT_BUS_CAPACITY, INTEGRATION_KEY_HEADER,
+        INTEGRATION_UPDATE_SETTLE_TIME, POSTMARK_EMAIL_SENDER_ADDRESS,
+        POSTMARK_WEBHOOK_TOKEN_HEADER, REDIS_HOST_NAME,
     },
+    utils::twilio::{twilio_signature, TWILIO_SIGNATURE_HEADER},
     Application,
 };
+use schemars::{schema_for, JsonSchema};
 use secrecy::{ExposeSecret, Secret};
 use serde_json::Value;
 use sqlx::{
     postgres::{PgConnectOptions, PgConnection, PgPoolOptions},
     Connection, Executor, PgPool,
 };
-use std::{str::FromStr, sync::Arc};
+use std::{str::FromStr, sync::Arc, time::Duration};
 use test_context::AsyncTestContext;
-use tokio::sync::RwLock;
+use tokio::{sync::RwLock, task::JoinHandle};
 use uuid::Uuid;
 use wiremock::{
     matchers::method, matchers::path, Mock, MockServer, ResponseTemplate,
@@ -34,10 +62,25 @@ use wiremock::{
 
 pub struct TestApp {
     pub address: String,
+    // Sends admin calls without the session cookies, as an admin's tooling
+    // would
+    pub admin_client: reqwest::Client,
     pub banned_token_store: BannedTokenStoreType,
+    // The app's clock, which only moves when a test moves it
+    pub clock: ManualClock,
     pub cookie_jar: Arc<Jar>,
     pub email_server: MockServer,
+    pub event_bus: EventBusType,
+    // Faults for the app's PostgreSQL stores to run into
+    #[cfg(feature = "chaos")]
+    pub faults: FaultInjector,
     pub http_client: reqwest::Client,
+    // Hands the app the same IDs in the same order in every test
+    pub ids: SequentialIdGenerator,
+    pub pg_pool: PgPool,
+    pub sms_server: MockServer,
+    // The app and its background tasks, stopped when the test is done
+    tasks: Vec<JoinHandle<()>>,
     pub tmp_db_name: String,
     pub two_fa_code_store: TwoFACodeStoreType,
     pub user_store: UserStoreType,
@@ -46,31 +89,124 @@ pub struct TestApp {
 
 impl TestApp {
     pub async fn new() -> Self {
+        Self::with_settings(Settings::default()).await
+    }
+
+    pub async fn with_settings(settings: Settings) -> Self {
         let tmp_db_name = Uuid::new_v4().to_string();
         let pg_pool = configure_postgresql(&tmp_db_name).await;
-        let user_store =
-            Arc::new(RwLock::new(PostgresUserStore::new(pg_pool.clone())));
-        let project_store =
-            Arc::new(RwLock::new(PostgresProjectStore::new(pg_pool)));
+        #[cfg(not(feature = "chaos"))]
+        let (user_store, project_store) = (
+            PostgresUserStore::new(pg_pool.clone()),
+            PostgresProjectStore::new(pg_pool.clone()),
+        );
+        #[cfg(feature = "chaos")]
+        let faults = FaultInjector::default();
+        #[cfg(feature = "chaos")]
+        let (user_store, project_store) = (
+            PostgresUserStore::with_faults(pg_pool.clone(), faults.clone()),
+            PostgresProjectStore::with_faults(pg_pool.clone(), faults.clone()),
+        );
+        let user_store = Arc::new(RwLock::new(user_store));
+        let project_store = Arc::new(RwLock::new(project_store));
+        let shift_reminder_store = Arc::new(RwLock::new(
+            PostgresShiftReminderStore::new(pg_pool.clone()),
+        ));
+        let integration_store = Arc::new(RwLock::new(
+            PostgresIntegrationStore::new(pg_pool.clone()),
+        ));
 
         let redis_connection = Arc::new(RwLock::new(configure_redis()));
         let banned_token_store = Arc::new(RwLock::new(
             RedisBannedTokenStore::new(redis_connection.clone()),
         ));
 
-        let two_fa_code_store =
-            Arc::new(RwLock::new(RedisTwoFACodeStore::new(redis_connection)));
+        let two_fa_code_store = Arc::new(RwLock::new(
+            RedisTwoFACodeStore::new(redis_connection.clone()),
+        ));
+
+        let verification_token_store = Arc::new(RwLock::new(
+            RedisVerificationTokenStore::new(redis_connection.clone()),
+        ));
+
+        let password_reset_token_store = Arc::new(RwLock::new(
+            RedisPasswordResetTokenStore::new(redis_connection.clone()),
+        ));
+
+        let deprecated_usage_store = Arc::new(RwLock::new(
+            RedisDeprecatedUsageStore::new(redis_connection.clone()),
+        ));
+        let request_capture_store = Arc::new(RwLock::new(
+            RedisRequestCaptureStore::new(redis_connection.clone()),
+        ));
+        let session_store =
+            Arc::new(RwLock::new(RedisSessionStore::new(redis_connection)));
+        // Each test app has a database of its own, so its jobs mustn't wait
+        // on leases held by other tests' apps
+        let job_lock: JobLockStoreType =
+            Arc::new(RwLock::new(HashmapJobLockStore::default()));
 
         let email_server = MockServer::start().await;
         let base_url = email_server.uri();
+        let event_bus: EventBusType =
+            Arc::new(BroadcastEventBus::new(EVENT_BUS_CAPACITY));
         let email_client = Arc::new(configure_postmark_email_client(base_url));
-
+        let sms_server = MockServer::start().await;
+        let mut tasks = vec![
+            spawn_outbox_relay(
+                pg_pool.clone(),
+                event_bus.clone(),
+                job_lock.clone(),
+                test::OUTBOX_POLL_INTERVAL,
+            ),
+            spawn_email_outbox_relay(
+                pg_pool.clone(),
+                email_client.clone(),
+                job_lock.clone(),
+                test::OUTBOX_POLL_INTERVAL,
+                test::EMAILS_PER_RECIPIENT_PER_HOUR,
+            ),
+            spawn_sms_outbox_relay(
+                pg_pool.clone(),
+                Arc::new(configure_twilio_sms_client(sms_server.uri())),
+                job_lock.clone(),
+                test::OUTBOX_POLL_INTERVAL,
+            ),
+            spawn_job_worker(
+                pg_pool.clone(),
+                job_lock,
+                test::JOB_POLL_INTERVAL,
+            ),
+            spawn_rota_change_digests(
+                &event_bus,
+                pg_pool.clone(),
+                test::ROTA_CHANGE_DIGEST_WINDOW,
+            ),
+            spawn_subscriber(&event_bus, "audit_log", audit_log),
+            spawn_security_emails(&event_bus, pg_pool.clone()),
+            spawn_notification_inbox(&event_bus, pg_pool.clone()),
+        ];
+
+        let clock = ManualClock::default();
+        let ids = SequentialIdGenerator::default();
         let app_state = AppState::new(
             user_store.clone(),
             banned_token_store.clone(),
             two_fa_code_store.clone(),
+            verification_token_store,
+            password_reset_token_store,
             email_client,
             project_store.clone(),
+            deprecated_usage_store,
+            request_capture_store,
+            session_store,
+            shift_reminder_store,
+            integration_store,
+            test::REQUEST_TIMEOUT,
+            event_bus.clone(),
+            Arc::new(clock.clone()),
+            Arc::new(ids.clone()),
+            settings,
         );
 
         let app = Application::build(app_state, test::APP_ADDRESS)
@@ -78,21 +214,32 @@ impl TestApp {
             .expect("Failed to build app");
         let address = format!("http://{}", app.address.clone());
 
-        #[allow(clippy::let_underscore_future)]
-        let _ = tokio::spawn(app.run());
+        tasks.push(tokio::spawn(async move {
+            app.run().await.expect("Failed to run app");
+        }));
 
         let cookie_jar = Arc::new(Jar::default());
         let http_client = reqwest::Client::builder()
             .cookie_provider(cookie_jar.clone())
             .build()
             .unwrap();
+        let admin_client = reqwest::Client::new();
 
         Self {
             address,
+            admin_client,
             banned_token_store,
+            clock,
             cookie_jar,
             email_server,
+            event_bus,
+            #[cfg(feature = "chaos")]
+            faults,
             http_client,
+            ids,
+            pg_pool,
+            sms_server,
+            tasks,
             tmp_db_name,
             two_fa_code_store,
             user_store,
@@ -100,74 +247,935 @@ impl TestApp {
         }
     }
 
-    pub async fn post_signup<Body>(&self, body: &Body) -> reqwest::Response
+    // Stop the app and its background tasks before dropping the database, so
+    // none of them are left polling it
+    async fn clean_up(self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+        self.pg_pool.close().await;
+        delete_database(&self.tmp_db_name).await;
+    }
+
+    pub async fn post_signup<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/signup", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_login<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/login", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_logout(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/auth/logout", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_verify_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/verify-2fa", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_setup_2fa(&self) -> reqwest::Response {
+        self.http_client
+            .post(format!("{}/auth/2fa/setup", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_verify_token<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/verify-token", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_user(&self) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/auth/delete-user", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn anonymize_user(&self) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/auth/delete-user", &self.address))
+            .query(&[("anonymize", "true")])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_email<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/email", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_change_email<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/change-email", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_forgot_password<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/forgot-password", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_reset_password<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/reset-password", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_magic_link<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/magic-link", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_verify_magic_link(
+        &self,
+        token: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/auth/magic-link/verify", &self.address))
+            .query(&[("token", token)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_confirm_email_change<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/auth/confirm-email-change", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_requires_2fa<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/2fa", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_security_emails<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/security-emails", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_email_tracking<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/email-tracking", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_email_webhook<Body>(
+        &self,
+        body: &Body,
+        token: &str,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/email/webhook", &self.address))
+            .header(POSTMARK_WEBHOOK_TOKEN_HEADER, token)
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_inbound_email<Body>(
+        &self,
+        body: &Body,
+        token: &str,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/email/inbound", &self.address))
+            .header(POSTMARK_WEBHOOK_TOKEN_HEADER, token)
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_open_email(&self, token: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/email/open", &self.address))
+            .query(&[("token", token)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_locale<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/locale", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_profile<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/auth/profile", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_quota(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/auth/quota", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_sessions(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/auth/sessions", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_session(&self, session_id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/auth/sessions/{}", &self.address, session_id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_member_contact(
+        &self,
+        member_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/member-contact", &self.address))
+            .query(&[("memberId", member_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_member_contact<Body>(
+        &self,
+        member_id: &str,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/projects/member-contact", &self.address))
+            .query(&[("memberId", member_id)])
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_open_shift_broadcast<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/open-shifts/broadcast", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    // An inbound text as Twilio would forward it, signed with `auth_token`
+    pub async fn post_inbound_sms(
+        &self,
+        from: &str,
+        body: &str,
+        auth_token: &str,
+    ) -> reqwest::Response {
+        let params = vec![
+            ("Body".to_owned(), body.to_owned()),
+            ("From".to_owned(), from.to_owned()),
+            ("MessageSid".to_owned(), "SM0123456789".to_owned()),
+        ];
+        let signature = twilio_signature(
+            &Secret::new(auth_token.to_owned()),
+            &format!("{}/sms/inbound", *APP_SERVICE_EXTERNAL_ADDRESS),
+            &params,
+        );
+
+        self.http_client
+            .post(format!("{}/sms/inbound", &self.address))
+            .header(TWILIO_SIGNATURE_HEADER, signature)
+            .form(&params)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_calendar_feed(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/me/calendar.ics", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_email_status(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/me/email", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_pending_approvals(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/me/approvals", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_review_rota<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/me/approvals/review", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_bulk_review_rotas<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/me/approvals/bulk-review", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_notifications(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/notifications", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_poll_notifications(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/notifications/poll", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_integration_key<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/integrations/keys", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_integration_keys(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/integrations/keys", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn delete_integration_key(
+        &self,
+        key_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/integrations/keys/{}", &self.address, key_id))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    // Polls without the session cookie, as a no-code tool would
+    pub async fn get_integration_updates(
+        &self,
+        key: Option<&str>,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
+        let mut request = reqwest::Client::new()
+            .get(format!("{}/integrations/updates", &self.address))
+            .query(query);
+        if let Some(key) = key {
+            request = request.header(INTEGRATION_KEY_HEADER, key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    // Move the clock on so the integration updates so far are old enough to
+    // be listed. The database dates them by its own clock, not the app's.
+    pub fn settle_integration_updates(&self) {
+        self.clock.set(
+            Utc::now()
+                + TimeDelta::from_std(INTEGRATION_UPDATE_SETTLE_TIME).unwrap(),
+        );
+    }
+
+    pub async fn post_mark_notifications_read<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/notifications/mark-read", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_projects_new<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/new", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_projects_list(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/list", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_add_member<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/add-member", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_member(&self, member_id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/get-member", &self.address))
+            .query(&[("memberId", member_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_members(&self, project_id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/get-members", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project(&self, project_id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/project", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project_batch(&self, ids: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/batch", &self.address))
+            .query(&[("ids", ids)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project_summary(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/summary", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project_including(
+        &self,
+        project_id: &str,
+        include: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/project", &self.address))
+            .query(&[("projectId", project_id), ("include", include)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project_history(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/history", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_project_emails(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/emails", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn put_member<Body>(
+        &self,
+        member_id: &str,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .put(format!("{}/projects/update-member", &self.address))
+            .json(body)
+            .query(&[("memberId", member_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_shift<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/shifts", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_clear_shifts<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/shifts/clear", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_department<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/departments", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_departments(&self, project_id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/departments", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_shift_slot<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/slots", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_shift_slots(&self, project_id: &str) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/slots", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_borrow_suggestions(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!(
+                "{}/projects/slots/borrow-suggestions",
+                &self.address
+            ))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_fill_shift_slot<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/slots/fill", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_week_template<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/templates", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_week_templates(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/templates", &self.address))
+            .query(&[("projectId", project_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_import_rota<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/import", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_apply_week_template<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.http_client
+            .post(format!("{}/projects/templates/apply", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_publish_rota<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .post(format!("{}/auth/signup", &self.address))
+            .post(format!("{}/projects/rota/publish", &self.address))
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_login<Body>(&self, body: &Body) -> reqwest::Response
+    pub async fn post_submit_rota<Body>(&self, body: &Body) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .post(format!("{}/auth/login", &self.address))
+            .post(format!("{}/projects/rota/submit", &self.address))
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_logout(&self) -> reqwest::Response {
+    pub async fn get_rota_approvals(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
         self.http_client
-            .post(format!("{}/auth/logout", &self.address))
+            .get(format!("{}/projects/rota/approvals", &self.address))
+            .query(&[("projectId", project_id)])
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_verify_2fa<Body>(&self, body: &Body) -> reqwest::Response
-    where
-        Body: serde::Serialize,
-    {
+    pub async fn get_rota_diff(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
         self.http_client
-            .post(format!("{}/auth/verify-2fa", &self.address))
-            .json(body)
+            .get(format!("{}/projects/rota/diff", &self.address))
+            .query(query)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_verify_token<Body>(
+    pub async fn get_rota_reactions(
         &self,
-        body: &Body,
-    ) -> reqwest::Response
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/projects/rota/reactions", &self.address))
+            .query(query)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_comment<Body>(&self, body: &Body) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .post(format!("{}/auth/verify-token", &self.address))
+            .post(format!("{}/projects/comments", &self.address))
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn delete_user(&self) -> reqwest::Response {
+    pub async fn get_comments(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
         self.http_client
-            .delete(format!("{}/auth/delete-user", &self.address))
+            .get(format!("{}/projects/comments", &self.address))
+            .query(query)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_projects_new<Body>(
+    pub async fn delete_comment(&self, comment_id: &str) -> reqwest::Response {
+        self.http_client
+            .delete(format!("{}/projects/comments", &self.address))
+            .query(&[("commentId", comment_id)])
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_validate_shift<Body>(
         &self,
         body: &Body,
     ) -> reqwest::Response
@@ -175,79 +1183,315 @@ impl TestApp {
         Body: serde::Serialize,
     {
         self.http_client
-            .post(format!("{}/projects/new", &self.address))
+            .post(format!("{}/projects/shifts/validate", &self.address))
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn get_projects_list(&self) -> reqwest::Response {
+    pub async fn post_absence<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
         self.http_client
-            .get(format!("{}/projects/list", &self.address))
+            .post(format!("{}/projects/absences", &self.address))
+            .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_add_member<Body>(&self, body: &Body) -> reqwest::Response
-    where
-        Body: serde::Serialize,
-    {
+    pub async fn get_attendance_report(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
         self.http_client
-            .post(format!("{}/projects/add-member", &self.address))
-            .json(body)
+            .get(format!("{}/projects/attendance-report", &self.address))
+            .query(query)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn get_member(&self, member_id: &str) -> reqwest::Response {
+    pub async fn get_printed_rota(
+        &self,
+        query: &[(&str, &str)],
+    ) -> reqwest::Response {
         self.http_client
-            .get(format!("{}/projects/get-member", &self.address))
-            .query(&[("memberId", member_id)])
+            .get(format!("{}/projects/print", &self.address))
+            .query(query)
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn get_members(&self, project_id: &str) -> reqwest::Response {
+    pub async fn get_project_settings(
+        &self,
+        project_id: &str,
+    ) -> reqwest::Response {
         self.http_client
-            .get(format!("{}/projects/get-members", &self.address))
+            .get(format!("{}/projects/settings", &self.address))
             .query(&[("projectId", project_id)])
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn put_member<Body>(
+    pub async fn put_project_settings<Body>(
         &self,
-        member_id: &str,
+        project_id: &str,
         body: &Body,
     ) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .put(format!("{}/projects/update-member", &self.address))
+            .put(format!("{}/projects/settings", &self.address))
+            .query(&[("projectId", project_id)])
             .json(body)
-            .query(&[("memberId", member_id)])
             .send()
             .await
             .expect("Failed to execute request")
     }
 
-    pub async fn post_shift<Body>(&self, body: &Body) -> reqwest::Response
+    pub async fn get_deprecated_usage(
+        &self,
+        admin_key: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request = self
+            .admin_client
+            .get(format!("{}/admin/deprecated-usage", &self.address));
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn get_policies(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/policies", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_accept_policy<Body>(
+        &self,
+        body: &Body,
+    ) -> reqwest::Response
     where
         Body: serde::Serialize,
     {
         self.http_client
-            .post(format!("{}/projects/shifts", &self.address))
+            .post(format!("{}/auth/accept-policy", &self.address))
+            .json(body)
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn post_bootstrap<Body>(&self, body: &Body) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        self.admin_client
+            .post(format!("{}/admin/bootstrap", &self.address))
             .json(body)
             .send()
             .await
             .expect("Failed to execute request")
     }
+
+    pub async fn post_invitation<Body>(
+        &self,
+        admin_key: Option<&str>,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .post(format!("{}/admin/invitations", &self.address))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_log_filter<Body>(
+        &self,
+        admin_key: Option<&str>,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!("{}/admin/log-filter", &self.address))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_provisioned_user<Body>(
+        &self,
+        admin_key: Option<&str>,
+        external_id: &str,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!(
+                "{}/admin/provisioning/users/{}",
+                &self.address, external_id
+            ))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_provisioned_api_key<Body>(
+        &self,
+        admin_key: Option<&str>,
+        external_id: &str,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!(
+                "{}/admin/provisioning/api-keys/{}",
+                &self.address, external_id
+            ))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_data_region<Body>(
+        &self,
+        admin_key: Option<&str>,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!("{}/admin/data-region", &self.address))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn get_request_capture(
+        &self,
+        admin_key: Option<&str>,
+        user_id: &str,
+    ) -> reqwest::Response {
+        let mut request = self
+            .admin_client
+            .get(format!("{}/admin/request-capture", &self.address))
+            .query(&[("userId", user_id)]);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_request_capture<Body>(
+        &self,
+        admin_key: Option<&str>,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!("{}/admin/request-capture", &self.address))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn get_legal_holds(
+        &self,
+        admin_key: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request = self
+            .admin_client
+            .get(format!("{}/admin/legal-holds", &self.address));
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn put_legal_hold<Body>(
+        &self,
+        admin_key: Option<&str>,
+        body: &Body,
+    ) -> reqwest::Response
+    where
+        Body: serde::Serialize,
+    {
+        let mut request = self
+            .admin_client
+            .put(format!("{}/admin/legal-holds", &self.address))
+            .json(body);
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
+
+    pub async fn get_api_docs_schemas(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/api-docs/schemas", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_version(&self) -> reqwest::Response {
+        self.http_client
+            .get(format!("{}/version", &self.address))
+            .send()
+            .await
+            .expect("Failed to execute request")
+    }
+
+    pub async fn get_metrics(
+        &self,
+        admin_key: Option<&str>,
+    ) -> reqwest::Response {
+        let mut request =
+            self.admin_client.get(format!("{}/metrics", &self.address));
+        if let Some(admin_key) = admin_key {
+            request = request.header(ADMIN_KEY_HEADER, admin_key);
+        }
+        request.send().await.expect("Failed to execute request")
+    }
 }
 
 impl AsyncTestContext for TestApp {
@@ -256,7 +1500,175 @@ impl AsyncTestContext for TestApp {
     }
 
     async fn teardown(self) {
-        delete_database(&self.tmp_db_name).await;
+        self.clean_up().await;
+    }
+}
+
+// A TestApp where signing up needs an invitation code
+pub struct InviteOnlyTestApp(pub TestApp);
+
+impl AsyncTestContext for InviteOnlyTestApp {
+    async fn setup() -> InviteOnlyTestApp {
+        InviteOnlyTestApp(
+            TestApp::with_settings(Settings {
+                invite_only_signup: true,
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+// A TestApp where users must accept version 1 of the policies
+pub struct PolicyTestApp(pub TestApp);
+
+impl AsyncTestContext for PolicyTestApp {
+    async fn setup() -> PolicyTestApp {
+        PolicyTestApp(
+            TestApp::with_settings(Settings {
+                required_policy_version: Some(1),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+pub const TWILIO_AUTH_TOKEN: &str = "twilio_auth_token";
+
+// A TestApp that accepts inbound texts signed with TWILIO_AUTH_TOKEN
+pub struct SmsTestApp(pub TestApp);
+
+impl AsyncTestContext for SmsTestApp {
+    async fn setup() -> SmsTestApp {
+        SmsTestApp(
+            TestApp::with_settings(Settings {
+                twilio_auth_token: Some(Secret::new(
+                    TWILIO_AUTH_TOKEN.to_owned(),
+                )),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+pub const POSTMARK_WEBHOOK_TOKEN: &str = "postmark_webhook_token";
+
+// A TestApp that accepts Postmark webhooks carrying POSTMARK_WEBHOOK_TOKEN
+pub struct PostmarkTestApp(pub TestApp);
+
+impl AsyncTestContext for PostmarkTestApp {
+    async fn setup() -> PostmarkTestApp {
+        PostmarkTestApp(
+            TestApp::with_settings(Settings {
+                postmark_webhook_token: Some(Secret::new(
+                    POSTMARK_WEBHOOK_TOKEN.to_owned(),
+                )),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+// A TestApp reached through a proxy, which believes X-Forwarded-For
+pub struct ProxiedTestApp(pub TestApp);
+
+impl AsyncTestContext for ProxiedTestApp {
+    async fn setup() -> ProxiedTestApp {
+        ProxiedTestApp(
+            TestApp::with_settings(Settings {
+                trust_proxy_headers: true,
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+// A TestApp where the webhooks of accounts in the EU can only go to
+// chat.example.eu
+pub struct ResidencyTestApp(pub TestApp);
+
+impl AsyncTestContext for ResidencyTestApp {
+    async fn setup() -> ResidencyTestApp {
+        ResidencyTestApp(
+            TestApp::with_settings(Settings {
+                residency_rules: ResidencyRules::parse("eu=chat.example.eu")
+                    .expect("Residency rules are invalid"),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+pub const BOOTSTRAP_TOKEN: &str = "0a3c6a8e-5d55-4f0b-9d43-1b7e0c9f2e61";
+
+// A TestApp started as a fresh instance, with a bootstrap token issued
+pub struct BootstrapTestApp(pub TestApp);
+
+impl AsyncTestContext for BootstrapTestApp {
+    async fn setup() -> BootstrapTestApp {
+        let token =
+            VerificationToken::parse(Secret::new(BOOTSTRAP_TOKEN.to_owned()))
+                .expect("Bootstrap token is invalid");
+        BootstrapTestApp(
+            TestApp::with_settings(Settings {
+                bootstrap_token: Some(token),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
+    }
+}
+
+// A TestApp with no permits for password hashing, as if every one were
+// taken by other requests
+pub struct SaturatedAuthTestApp(pub TestApp);
+
+impl AsyncTestContext for SaturatedAuthTestApp {
+    async fn setup() -> SaturatedAuthTestApp {
+        SaturatedAuthTestApp(
+            TestApp::with_settings(Settings {
+                auth_concurrency_limit: Some(0),
+                ..Settings::default()
+            })
+            .await,
+        )
+    }
+
+    async fn teardown(self) {
+        self.0.clean_up().await;
     }
 }
 
@@ -276,9 +1688,12 @@ async fn configure_postgresql(db_name: &str) -> PgPool {
     ));
 
     // Create a new connection pool and return it
-    get_postgres_pool(&postgresql_conn_url_with_db)
-        .await
-        .expect("Failed to create Postgres connection pool!")
+    get_postgres_pool(
+        &postgresql_conn_url_with_db,
+        &DatabaseSettings::default(),
+    )
+    .await
+    .expect("Failed to create Postgres connection pool!")
 }
 
 async fn configure_database(db_conn_string: &Secret<String>, db_name: &str) {
@@ -365,6 +1780,21 @@ fn configure_postmark_email_client(base_url: String) -> PostmarkEmailClient {
     PostmarkEmailClient::new(base_url, sender, postmark_auth_token, http_client)
 }
 
+fn configure_twilio_sms_client(base_url: String) -> TwilioSmsClient {
+    let http_client = Client::builder()
+        .timeout(test::sms_client::TIMEOUT)
+        .build()
+        .expect("Failed to build HTTP client");
+
+    TwilioSmsClient::new(
+        base_url,
+        test::sms_client::ACCOUNT_SID.to_owned(),
+        Secret::new("auth_token".to_owned()),
+        PhoneNumber::parse(test::sms_client::SENDER.to_owned()).unwrap(),
+        http_client,
+    )
+}
+
 pub async fn signup(
     app: &mut TestApp,
     email: &str,
@@ -444,6 +1874,26 @@ pub async fn get_expected_2fa_details(
     )
 }
 
+// The confirmation token most recently emailed to `recipient`
+pub async fn get_sent_token(app: &TestApp, recipient: &str) -> String {
+    app.email_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .rev()
+        .find_map(|request| {
+            let body: Value = request.body_json().unwrap();
+            if body["To"] != recipient {
+                return None;
+            }
+            let text = body["TextBody"].as_str().unwrap();
+            let start = text.find("token=")? + 6;
+            Some(text[start..start + 36].to_owned())
+        })
+        .expect("No token sent to recipient")
+}
+
 pub async fn get_session(app: &mut TestApp, two_fa: bool) -> String {
     let email = get_random_email();
     let password = "password";
@@ -480,6 +1930,18 @@ pub async fn add_new_project(app: &mut TestApp, name: &str) -> String {
     response_id
 }
 
+pub fn get_admin_key() -> String {
+    ADMIN_API_KEY
+        .as_ref()
+        .expect("ADMIN_API_KEY must be set to test admin endpoints")
+        .expose_secret()
+        .to_owned()
+}
+
+pub fn get_schema<T: JsonSchema>() -> Value {
+    serde_json::to_value(schema_for!(T)).expect("Failed to serialise schema")
+}
+
 pub async fn get_json_response_body(response: Response) -> Value {
     let body: Value = response
         .json()
@@ -524,3 +1986,24 @@ pub async fn add_member(
         .expect("Failed to create str from memberId field")
         .to_owned()
 }
+
+// Events are relayed from the outbox, so ones caused by test setup may still
+// arrive after subscribing. Skip anything that doesn't match.
+pub async fn wait_for_event<F>(
+    events: &mut EventReceiver,
+    predicate: F,
+) -> DomainEvent
+where
+    F: Fn(&DomainEvent) -> bool,
+{
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let event = events.recv().await.expect("Event bus closed");
+            if predicate(&event) {
+                return event;
+            }
+        }
+    })
+    .await
+    .expect("Timed out waiting for domain event")
+}
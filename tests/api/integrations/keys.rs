@@ -0,0 +1,108 @@
+use rota_manager::{
+    routes::integrations::{
+        CreatedIntegrationKeyResponse, IntegrationKeyListResponse,
+    },
+    ErrorResponse,
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    get_json_response_body, get_schema, get_session, TestApp,
+};
+
+// Returns the new key's ID and the key itself
+async fn create_key(app: &TestApp, name: &str) -> (String, String) {
+    let response = app.post_integration_key(&json!({"name": name})).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<CreatedIntegrationKeyResponse>(),
+            &body
+        ),
+        "response does not match schema"
+    );
+    (
+        body["keyId"].as_str().unwrap().to_owned(),
+        body["key"].as_str().unwrap().to_owned(),
+    )
+}
+
+async fn list_keys(app: &TestApp) -> Value {
+    let response = app.get_integration_keys().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<IntegrationKeyListResponse>(),
+            &body
+        ),
+        "response does not match schema"
+    );
+    body
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_create_list_and_revoke_keys(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    let (key_id, key) = create_key(app, "Zapier").await;
+    assert!(key.starts_with("rmk_"));
+
+    let body = list_keys(app).await;
+    assert_eq!(body["keys"].as_array().unwrap().len(), 1);
+    assert_eq!(body["keys"][0]["keyId"], key_id);
+    assert_eq!(body["keys"][0]["name"], "Zapier");
+    assert_eq!(body["keys"][0]["lastUsedAt"], json!(null));
+    // The key itself is only shown when it's created
+    assert_eq!(body["keys"][0].get("key"), None);
+
+    let response = app.get_integration_updates(Some(&key), &[]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = list_keys(app).await;
+    assert!(body["keys"][0]["lastUsedAt"].is_string());
+
+    let response = app.delete_integration_key(&key_id).await;
+    assert_eq!(response.status().as_u16(), 204);
+    assert_eq!(list_keys(app).await["keys"], json!([]));
+
+    let response = app.get_integration_updates(Some(&key), &[]).await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = app.delete_integration_key(&key_id).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_bad_names_and_too_many_keys(app: &mut TestApp) {
+    get_session(app, false).await;
+
+    for name in ["", "   ", &"a".repeat(101)] {
+        let response = app.post_integration_key(&json!({"name": name})).await;
+        assert_eq!(response.status().as_u16(), 400, "name: {name:?}");
+    }
+
+    for n in 0..10 {
+        create_key(app, &format!("Tool {n}")).await;
+    }
+    let response = app.post_integration_key(&json!({"name": "Tool 10"})).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Accounts can have at most 10 integration keys"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_revoke_other_users_keys(app: &mut TestApp) {
+    get_session(app, false).await;
+    let (key_id, _) = create_key(app, "Zapier").await;
+
+    get_session(app, false).await;
+    assert_eq!(list_keys(app).await["keys"], json!([]));
+    let response = app.delete_integration_key(&key_id).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
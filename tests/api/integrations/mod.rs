@@ -0,0 +1,2 @@
+mod keys;
+mod updates;
@@ -0,0 +1,159 @@
+use rota_manager::routes::integrations::IntegrationUpdatesResponse;
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+async fn create_key(app: &TestApp) -> String {
+    let response = app.post_integration_key(&json!({"name": "Zapier"})).await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["key"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+async fn poll(app: &TestApp, key: &str, query: &[(&str, &str)]) -> Value {
+    app.settle_integration_updates();
+    let response = app.get_integration_updates(Some(key), query).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<IntegrationUpdatesResponse>(),
+            &body
+        ),
+        "response does not match schema"
+    );
+    body
+}
+
+fn types(body: &Value) -> Vec<&str> {
+    body["updates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|update| update["type"].as_str().unwrap())
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_updates_oldest_first(app: &mut TestApp) {
+    get_session(app, false).await;
+    let key = create_key(app).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_member(app, "Ted", &project_id).await;
+
+    let body = poll(app, &key, &[]).await;
+    assert_eq!(
+        types(&body),
+        vec![
+            "UserSignedUp",
+            "IntegrationKeyCreated",
+            "ProjectCreated",
+            "MemberAdded"
+        ]
+    );
+    assert_eq!(body["hasMore"], false);
+    assert_eq!(body["cursor"], body["updates"][3]["id"]);
+    let created = &body["updates"][2];
+    assert_eq!(created["projectId"], project_id.as_str());
+    assert_eq!(created["data"]["project_name"], "Craggy Island");
+    // The user is the key's own, so it's left out
+    assert_eq!(created["data"].get("user_id"), None);
+    assert_eq!(body["updates"][0]["projectId"], json!(null));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_carry_on_from_the_last_update_given(app: &mut TestApp) {
+    get_session(app, false).await;
+    let key = create_key(app).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let first = poll(app, &key, &[("limit", "1")]).await;
+    assert_eq!(types(&first), vec!["UserSignedUp"]);
+    assert_eq!(first["hasMore"], true);
+
+    // Without a cursor, polling resumes where the key left off
+    let second = poll(app, &key, &[]).await;
+    assert_eq!(
+        types(&second),
+        vec!["IntegrationKeyCreated", "ProjectCreated"]
+    );
+    let empty = poll(app, &key, &[]).await;
+    assert_eq!(empty["updates"], json!([]));
+    assert_eq!(empty["cursor"], second["cursor"]);
+
+    add_member(app, "Ted", &project_id).await;
+    assert_eq!(types(&poll(app, &key, &[]).await), vec!["MemberAdded"]);
+
+    // A cursor replays from wherever it points
+    let cursor = first["cursor"].to_string();
+    let replayed = poll(app, &key, &[("cursor", &cursor)]).await;
+    assert_eq!(
+        types(&replayed),
+        vec!["IntegrationKeyCreated", "ProjectCreated", "MemberAdded"]
+    );
+    assert_eq!(poll(app, &key, &[]).await["updates"], json!([]));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_list_the_key_users_updates(app: &mut TestApp) {
+    get_session(app, false).await;
+    let key = create_key(app).await;
+
+    get_session(app, false).await;
+    add_new_project(app, "Rugged Island").await;
+
+    assert_eq!(
+        types(&poll(app, &key, &[]).await),
+        vec!["UserSignedUp", "IntegrationKeyCreated"]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_list_settled_updates(app: &mut TestApp) {
+    get_session(app, false).await;
+    let key = create_key(app).await;
+
+    let response = app.get_integration_updates(Some(&key), &[]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["updates"], json!([]));
+    assert_eq!(body["hasMore"], false);
+
+    assert_eq!(
+        types(&poll(app, &key, &[]).await),
+        vec!["UserSignedUp", "IntegrationKeyCreated"]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_missing_or_unknown_keys(app: &mut TestApp) {
+    get_session(app, false).await;
+    let key = create_key(app).await;
+
+    let response = app.get_integration_updates(None, &[]).await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let unknown = format!("rmk_{}", "a".repeat(40));
+    for key in ["not-a-key", unknown.as_str()] {
+        let response = app.get_integration_updates(Some(key), &[]).await;
+        assert_eq!(response.status().as_u16(), 401, "key: {key}");
+    }
+
+    for limit in ["0", "101"] {
+        let response = app
+            .get_integration_updates(Some(&key), &[("limit", limit)])
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "limit: {limit}");
+    }
+}
@@ -1,3 +1,17 @@
+mod access;
+mod admin;
+mod api_docs;
 mod auth;
+#[cfg(feature = "chaos")]
+mod chaos;
+mod database;
+mod email;
+mod events;
 mod helpers;
+mod integrations;
+mod me;
+mod metrics;
+mod notifications;
 mod projects;
+mod sms;
+mod version;
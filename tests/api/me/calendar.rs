@@ -0,0 +1,66 @@
+use crate::helpers::{add_member, add_new_project, get_session, TestApp};
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_merge_shifts_from_every_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    for (project_name, member_name) in
+        [("Craggy Island", "Ted"), ("Rugged Island", "Dick")]
+    {
+        let project_id = add_new_project(app, project_name).await;
+        let member_id = add_member(app, member_name, &project_id).await;
+        let response = app
+            .post_shift(&json!({
+                "memberId": &member_id,
+                "day": "Monday",
+                "startTime": 540,
+                "endTime": 1020,
+                "tags": ["training"]
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app.get_calendar_feed().await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/calendar; charset=utf-8"
+    );
+
+    let calendar = response.text().await.unwrap();
+    assert!(calendar.starts_with("BEGIN:VCALENDAR\r\n"));
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+    assert!(calendar.contains("SUMMARY:Ted (Craggy Island)\r\n"));
+    assert!(calendar.contains("SUMMARY:Dick (Rugged Island)\r\n"));
+    assert!(calendar.contains("CATEGORIES:Craggy Island,training\r\n"));
+    assert_eq!(calendar.matches("X-APPLE-CALENDAR-COLOR:#").count(), 2);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_include_own_projects(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    app.post_shift(&json!({
+        "memberId": &member_id,
+        "day": "Monday",
+        "startTime": 540,
+        "endTime": 1020
+    }))
+    .await;
+
+    let _email = get_session(app, false).await;
+    let calendar = app.get_calendar_feed().await.text().await.unwrap();
+    assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_not_logged_in(app: &mut TestApp) {
+    let response = app.get_calendar_feed().await;
+    assert_eq!(response.status().as_u16(), 401);
+}
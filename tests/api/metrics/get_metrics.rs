@@ -0,0 +1,47 @@
+use crate::helpers::{get_admin_key, get_session, TestApp};
+use rota_manager::utils::metrics::QUERY_DURATION_METRIC;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_200_with_admin_key(app: &mut TestApp) {
+    let response = app.get_metrics(Some(&get_admin_key())).await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_admin_key_missing(app: &mut TestApp) {
+    let response = app.get_metrics(None).await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_admin_key_incorrect(app: &mut TestApp) {
+    let response = app.get_metrics(Some("not-the-admin-key")).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_record_query_durations_per_operation(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let body = app
+        .get_metrics(Some(&get_admin_key()))
+        .await
+        .text()
+        .await
+        .expect("Failed to read metrics body");
+
+    for operation in ["add_user", "get_user"] {
+        assert!(
+            body.lines()
+                .any(|line| line.starts_with(QUERY_DURATION_METRIC)
+                    && line.contains(&format!(r#"operation="{}""#, operation))),
+            "No duration recorded for {}",
+            operation
+        );
+    }
+}
@@ -0,0 +1,178 @@
+use std::time::Duration;
+
+use rota_manager::{
+    routes::notifications::{MarkReadResponse, NotificationListResponse},
+    utils::constants::test,
+};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, login, logout, TestApp,
+};
+
+// Signs up the approver, then the owner, whose project needs the approver's
+// go-ahead, and submits a rota. Leaves the owner signed in and returns the
+// approver's and owner's emails and the approval.
+async fn submit_rota(app: &mut TestApp) -> (String, String, String) {
+    let approver = get_session(app, false).await;
+    logout(app).await;
+    let owner = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let response = app
+        .put_project_settings(&project_id, &json!({"approverEmail": approver}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_submit_rota(
+            &json!({"projectId": &project_id, "weekOf": "2025-10-26"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let approval_id = get_json_response_body(response).await["approvalId"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    (approver, owner, approval_id)
+}
+
+// Notifications are added as events are relayed, so wait for them
+async fn wait_for_notifications(app: &TestApp, count: usize) -> Value {
+    tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let response = app.get_notifications(&[]).await;
+            assert_eq!(response.status().as_u16(), 200);
+            let body = get_json_response_body(response).await;
+            if body["notifications"].as_array().unwrap().len() >= count {
+                return body;
+            }
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for notifications")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_notify_approver_and_owner(app: &mut TestApp) {
+    let (approver, owner, approval_id) = submit_rota(app).await;
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let body = wait_for_notifications(app, 1).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<NotificationListResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body["notifications"][0]["message"],
+        "The Craggy Island rota for the week of 2025-10-26 is waiting for \
+         your approval"
+    );
+    assert_eq!(body["notifications"][0]["readAt"], json!(null));
+    assert_eq!(body["unread"], 1);
+
+    let response = app
+        .post_review_rota(&json!({
+            "approvalId": &approval_id,
+            "approve": false,
+            "comment": "Dougal's not on it"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    logout(app).await;
+    login(app, &owner, "password").await;
+    let body = wait_for_notifications(app, 1).await;
+    assert_eq!(
+        body["notifications"][0]["message"],
+        "The Craggy Island rota for the week of 2025-10-26 was rejected: \
+         Dougal's not on it"
+    );
+    assert_eq!(body["notifications"].as_array().unwrap().len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_mark_notifications_read(app: &mut TestApp) {
+    let (approver, _, _) = submit_rota(app).await;
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let body = wait_for_notifications(app, 1).await;
+    let notification_id = body["notifications"][0]["notificationId"].clone();
+
+    let response = app
+        .post_mark_notifications_read(
+            &json!({"notificationIds": [notification_id]}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<MarkReadResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["marked"], 1);
+
+    let body = get_json_response_body(app.get_notifications(&[]).await).await;
+    assert_eq!(body["unread"], 0);
+    assert_ne!(body["notifications"][0]["readAt"], json!(null));
+    let body = get_json_response_body(
+        app.get_notifications(&[("unread", "true")]).await,
+    )
+    .await;
+    assert!(body["notifications"].as_array().unwrap().is_empty());
+
+    // Already read
+    let body = get_json_response_body(
+        app.post_mark_notifications_read(&json!({})).await,
+    )
+    .await;
+    assert_eq!(body["marked"], 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_mark_someone_elses_notifications(app: &mut TestApp) {
+    let (approver, _, _) = submit_rota(app).await;
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let body = wait_for_notifications(app, 1).await;
+    let notification_id = body["notifications"][0]["notificationId"].clone();
+
+    logout(app).await;
+    let _email = get_session(app, false).await;
+    let response = app
+        .post_mark_notifications_read(
+            &json!({"notificationIds": [notification_id]}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.json::<MarkReadResponse>().await.unwrap(),
+        MarkReadResponse { marked: 0 }
+    );
+    let body = get_json_response_body(app.get_notifications(&[]).await).await;
+    assert!(body["notifications"].as_array().unwrap().is_empty());
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let body = get_json_response_body(app.get_notifications(&[]).await).await;
+    assert_eq!(body["unread"], 1);
+}
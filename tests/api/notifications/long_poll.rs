@@ -0,0 +1,112 @@
+use std::time::Duration;
+
+use rota_manager::routes::notifications::NotificationPollResponse;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+// A project the signed-in user approves rotas for themselves, so submitting
+// one notifies them. Returns the project.
+async fn set_up_rota(app: &mut TestApp) -> String {
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let response = app
+        .put_project_settings(&project_id, &json!({"approverEmail": email}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    project_id
+}
+
+async fn submit_rota(app: &TestApp, project_id: &str, week_of: &str) {
+    let response = app
+        .post_submit_rota(&json!({"projectId": project_id, "weekOf": week_of}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+async fn start_cursor(app: &TestApp) -> String {
+    let response = app.get_poll_notifications(&[]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(body["notifications"].as_array().unwrap().is_empty());
+    body["cursor"].to_string()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_answer_when_a_notification_arrives(app: &mut TestApp) {
+    let project_id = set_up_rota(app).await;
+    let cursor = start_cursor(app).await;
+
+    let query = [("since", cursor.as_str()), ("wait", "60")];
+    let (response, _) =
+        tokio::join!(app.get_poll_notifications(&query), async {
+            tokio::time::sleep(Duration::from_millis(200)).await;
+            submit_rota(app, &project_id, "2025-10-26").await;
+        });
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<NotificationPollResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body["notifications"][0]["message"],
+        "The Craggy Island rota for the week of 2025-10-26 is waiting for \
+         your approval"
+    );
+    assert_eq!(body["cursor"], body["notifications"][0]["cursor"]);
+    assert_ne!(body["cursor"].to_string(), cursor);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_return_notifications_after_the_cursor(app: &mut TestApp) {
+    let project_id = set_up_rota(app).await;
+    let cursor = start_cursor(app).await;
+    submit_rota(app, &project_id, "2025-10-26").await;
+
+    let response = app
+        .get_poll_notifications(&[("since", &cursor), ("wait", "4")])
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["notifications"].as_array().unwrap().len(), 1);
+    let cursor = body["cursor"].to_string();
+
+    // Nothing new, so it waits it out
+    let response = app
+        .get_poll_notifications(&[("since", &cursor), ("wait", "1")])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(body["notifications"].as_array().unwrap().is_empty());
+    assert_eq!(body["cursor"].to_string(), cursor);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_cursor(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let response = app
+        .get_poll_notifications(&[("since", "latest"), ("wait", "1")])
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .get_poll_notifications(&[("since", "0"), ("wait", "-1")])
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,2 @@
+mod inbox;
+mod long_poll;
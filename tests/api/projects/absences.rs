@@ -0,0 +1,170 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, wait_for_event, TestApp,
+};
+use rota_manager::{
+    domain::DomainEvent, routes::projects::AbsenceResponse, ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+async fn add_shift(
+    app: &mut TestApp,
+    member_id: &str,
+    standby_member_id: Option<&str>,
+) -> String {
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "standbyMemberId": standby_member_id
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_leave_shift_open_without_standby(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let shift_id = add_shift(app, &ted, None).await;
+
+    let mut events = app.event_bus.subscribe();
+
+    let response = app
+        .post_absence(&json!({"shiftId": &shift_id, "date": "2025-10-20"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<AbsenceResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["shiftId"], shift_id);
+    assert_eq!(body["memberId"], ted);
+    assert_eq!(body["date"], "2025-10-20");
+    assert_eq!(body["reason"], "sickness");
+    assert_eq!(body["open"], true);
+    assert!(body["standbyMemberId"].is_null());
+
+    let event = wait_for_event(&mut events, |event| {
+        matches!(event, DomainEvent::AbsenceReported { .. })
+    })
+    .await;
+    assert!(event.describe("Alice").ends_with(
+        "reported Ted off sick on 2025-10-20; the shift is open to claim"
+    ));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_hand_shift_to_standby_member(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    let shift_id = add_shift(app, &ted, Some(&dougal)).await;
+
+    let response = app
+        .post_absence(&json!({
+            "shiftId": &shift_id,
+            "date": "2025-10-20",
+            "reason": "sickness"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["open"], false);
+    assert_eq!(body["standbyMemberId"], dougal);
+
+    let history = get_json_response_body(
+        app.get_project_history(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert!(history["entries"][0]["description"]
+        .as_str()
+        .unwrap()
+        .ends_with(
+            "reported Ted off sick on 2025-10-20; Dougal is on standby"
+        ));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_409_for_repeated_reports(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let shift_id = add_shift(app, &ted, None).await;
+
+    let absence = json!({"shiftId": &shift_id, "date": "2025-10-20"});
+    assert_eq!(app.post_absence(&absence).await.status().as_u16(), 201);
+    assert_eq!(app.post_absence(&absence).await.status().as_u16(), 409);
+
+    // The following week is a separate absence
+    let absence = json!({"shiftId": &shift_id, "date": "2025-10-27"});
+    assert_eq!(app.post_absence(&absence).await.status().as_u16(), 201);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_input(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let shift_id = add_shift(app, &ted, None).await;
+
+    let test_cases = [
+        (
+            json!({"shiftId": &shift_id, "date": "2025-10-21"}),
+            "Validation error: Shift is on Monday, but 2025-10-21 is a \
+             Tuesday",
+        ),
+        (
+            json!({"shiftId": &shift_id, "date": "20/10/2025"}),
+            "Validation error: Date must be in YYYY-MM-DD format",
+        ),
+        (
+            json!({
+                "shiftId": &shift_id,
+                "date": "2025-10-20",
+                "reason": "hangover"
+            }),
+            "Validation error: Invalid absence reason",
+        ),
+    ];
+
+    for (request, error) in test_cases {
+        let response = app.post_absence(&request).await;
+        assert_eq!(response.status().as_u16(), 400);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_shift(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let shift_id = add_shift(app, &ted, None).await;
+
+    let _email = get_session(app, false).await;
+    let response = app
+        .post_absence(&json!({"shiftId": &shift_id, "date": "2025-10-20"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -1,8 +1,8 @@
 use crate::helpers::{
-    add_new_project, get_json_response_body, get_session, logout, TestApp,
+    add_new_project, get_json_response_body, get_schema, get_session, logout,
+    TestApp,
 };
-use rota_manager::ErrorResponse;
-use serde_json::json;
+use rota_manager::{routes::projects::AddMemberResponse, ErrorResponse};
 use test_context::test_context;
 
 #[test_context(TestApp)]
@@ -11,33 +11,7 @@ async fn should_return_200_for_valid_requests(app: &mut TestApp) {
     let _email = get_session(app, false).await;
     let project_id = add_new_project(app, "Craggy Island").await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "memberName": {
-          "type": "string",
-          "minLength": 1,
-          "maxLength": 255
-        },
-        "memberId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        },
-        "projectId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        }
-      },
-      "required": [
-        "memberName",
-        "memberId",
-        "projectId"
-      ]
-    });
+    let schema = get_schema::<AddMemberResponse>();
 
     let members = ["Ted", "Dougal"];
 
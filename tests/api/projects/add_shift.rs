@@ -1,11 +1,40 @@
 use crate::helpers::{
-    add_member, add_new_project, get_json_response_body, get_session, logout,
-    TestApp,
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, wait_for_event, TestApp,
 };
-use rota_manager::ErrorResponse;
-use serde_json::json;
+use rota_manager::{
+    domain::{Day, DomainEvent},
+    routes::projects::AddShiftResponse,
+    services::id_generator::SequentialIdGenerator,
+    ErrorResponse,
+};
+use serde_json::{json, Value};
 use test_context::test_context;
 
+// Test apps hand out IDs in sequence, so each one is known up front
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_give_predictable_ids(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let shift: AddShiftResponse = response.json().await.unwrap();
+
+    assert_eq!(project_id, SequentialIdGenerator::nth(1).to_string());
+    assert_eq!(member_id, SequentialIdGenerator::nth(2).to_string());
+    assert_eq!(shift.id, SequentialIdGenerator::nth(3));
+    assert_eq!(app.ids.count(), 3);
+}
+
 #[test_context(TestApp)]
 #[tokio::test]
 async fn should_return_201_for_valid_requests(app: &mut TestApp) {
@@ -13,44 +42,7 @@ async fn should_return_201_for_valid_requests(app: &mut TestApp) {
     let project_id = add_new_project(app, "Craggy Island").await;
     let member_id = add_member(app, "Ted", &project_id).await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "id": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        },
-        "memberId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        },
-        "day": {
-          "type": "string",
-          "enum": ["Sunday", "Monday", "Tuesday", "Wednesday", "Thursday", "Friday", "Saturday"]
-        },
-        "startTime": {
-          "type": "number",
-          "min": 0,
-          "max": 1440
-        },
-        "endTime": {
-          "type": "number",
-          "min": 0,
-          "max": 1440
-        }
-      },
-      "required": [
-        "id",
-        "memberId",
-        "day",
-        "startTime",
-        "endTime"
-      ]
-    });
+    let schema = get_schema::<AddShiftResponse>();
 
     let requests = [
         &json!(
@@ -214,6 +206,26 @@ async fn should_return_400_if_invalid_input(app: &mut TestApp) {
             }),
             "Validation error: Invalid day",
         ),
+        (
+            &json!({
+                "memberId": &member_id,
+                "day": "Sunday",
+                "startTime": 540,
+                "endTime": 1020,
+                "note": "a".repeat(201)
+            }),
+            "Validation error: Notes cannot be longer than 200 characters",
+        ),
+        (
+            &json!({
+                "memberId": &member_id,
+                "day": "Sunday",
+                "startTime": 540,
+                "endTime": 1020,
+                "location": "Front\ndesk"
+            }),
+            "Validation error: Locations cannot contain control characters",
+        ),
     ];
 
     for (body, expected_error) in test_cases.iter() {
@@ -346,3 +358,175 @@ async fn should_return_404_for_non_existent_project_id(app: &mut TestApp) {
         "Should return 404 for non-existent project IDs",
     );
 }
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_publish_shift_created_event(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+
+    let mut events = app.event_bus.subscribe();
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let event = wait_for_event(&mut events, |event| {
+        matches!(event, DomainEvent::ShiftCreated { .. })
+    })
+    .await;
+
+    match event {
+        DomainEvent::ShiftCreated {
+            member_id: event_member_id,
+            day,
+            start_time,
+            end_time,
+            ..
+        } => {
+            assert_eq!(event_member_id.as_ref().to_string(), member_id);
+            assert_eq!(day, Day::Monday);
+            assert_eq!(start_time.value_of(), 540);
+            assert_eq!(end_time.value_of(), 1020);
+        }
+        event => panic!("Expected ShiftCreated, got {:?}", event),
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_assign_standby_member(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+
+    let mut events = app.event_bus.subscribe();
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "standbyMemberId": &dougal
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["standbyMemberId"], dougal);
+
+    let event = wait_for_event(&mut events, |event| {
+        matches!(event, DomainEvent::ShiftCreated { .. })
+    })
+    .await;
+    assert!(event.describe("Alice").ends_with("with Dougal on standby"));
+
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    let members = project["members"].as_array().unwrap();
+    let shifts = members
+        .iter()
+        .find(|member| member["memberId"] == ted)
+        .unwrap()["shifts"]
+        .as_array()
+        .unwrap();
+    assert_eq!(shifts[0]["standbyMemberId"], dougal);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_standby_members(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let other_project_id = add_new_project(app, "Rugged Island").await;
+    let jack = add_member(app, "Jack", &other_project_id).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let shift = |standby: &str| {
+        json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "standbyMemberId": standby
+        })
+    };
+
+    let test_cases = [
+        (
+            ted.as_str(),
+            400,
+            "Validation error: A member cannot be on standby for their own \
+             shift",
+        ),
+        (
+            jack.as_str(),
+            400,
+            "Validation error: Standby member must be in the same project",
+        ),
+        (
+            "2a6af785-e170-4ab6-ac1f-691772640f31",
+            404,
+            "Member not found: 2a6af785-e170-4ab6-ac1f-691772640f31",
+        ),
+    ];
+
+    for (standby, status, error) in test_cases {
+        let response = app.post_shift(&shift(standby)).await;
+        assert_eq!(response.status().as_u16(), status);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_save_note_and_location(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "note": " Bring the float ",
+            "location": "Front desk"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["note"], "Bring the float");
+    assert_eq!(body["location"], "Front desk");
+
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    let shift = &project["members"][0]["shifts"][0];
+    assert_eq!(shift["note"], "Bring the float");
+    assert_eq!(shift["location"], "Front desk");
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Tuesday",
+            "startTime": 540,
+            "endTime": 1020,
+            "note": "   "
+        }))
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["note"], Value::Null);
+    assert_eq!(body["location"], Value::Null);
+}
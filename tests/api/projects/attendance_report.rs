@@ -0,0 +1,270 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::routes::projects::AttendanceReportResponse;
+use serde_json::{json, Value};
+use test_context::test_context;
+
+// (from, to, scheduled minutes, completed minutes) for each week
+fn weeks(weeks: &[(&str, &str, i64, i64)]) -> Value {
+    weeks
+        .iter()
+        .map(|(from, to, scheduled, completed)| {
+            json!({
+                "from": from,
+                "to": to,
+                "scheduledMinutes": scheduled,
+                "completedMinutes": completed
+            })
+        })
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_absences_and_completed_hours(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    let shift_id = get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+    for date in ["2025-10-06", "2025-11-03"] {
+        let response = app
+            .post_absence(&json!({"shiftId": &shift_id, "date": date}))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app
+        .get_attendance_report(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-01"),
+            ("to", "2025-10-31"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<AttendanceReportResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["weekStartsOn"], "Sunday");
+    assert_eq!(body["locale"], "en-GB");
+    assert_eq!(body["fromLabel"], "Wednesday 01/10/2025");
+    assert_eq!(body["toLabel"], "Friday 31/10/2025");
+    assert_eq!(
+        body["members"],
+        json!([
+            {
+                "memberId": &dougal,
+                "memberName": "Dougal",
+                "scheduledShifts": 0,
+                "absences": 0,
+                "scheduledMinutes": 0,
+                "completedMinutes": 0,
+                "scheduledLabel": "0h 0m",
+                "completedLabel": "0h 0m",
+                "completedPercentage": null,
+                "tagMinutes": {},
+                "weeks": weeks(&[
+                    ("2025-10-01", "2025-10-04", 0, 0),
+                    ("2025-10-05", "2025-10-11", 0, 0),
+                    ("2025-10-12", "2025-10-18", 0, 0),
+                    ("2025-10-19", "2025-10-25", 0, 0),
+                    ("2025-10-26", "2025-10-31", 0, 0)
+                ])
+            },
+            {
+                "memberId": &ted,
+                "memberName": "Ted",
+                "scheduledShifts": 4,
+                "absences": 1,
+                "scheduledMinutes": 1920,
+                "completedMinutes": 1440,
+                "scheduledLabel": "32h 0m",
+                "completedLabel": "24h 0m",
+                "completedPercentage": 75.0,
+                "tagMinutes": {},
+                "weeks": weeks(&[
+                    ("2025-10-01", "2025-10-04", 0, 0),
+                    ("2025-10-05", "2025-10-11", 480, 0),
+                    ("2025-10-12", "2025-10-18", 480, 480),
+                    ("2025-10-19", "2025-10-25", 480, 480),
+                    ("2025-10-26", "2025-10-31", 480, 480)
+                ])
+            }
+        ])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_group_weeks_from_project_week_start(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .put_project_settings(&project_id, &json!({"weekStartsOn": "Monday"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    for day in ["Sunday", "Monday"] {
+        let response = app
+            .post_shift(&json!({
+                "memberId": &ted,
+                "day": day,
+                "startTime": 540,
+                "endTime": 600
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    // Saturday 18 to Monday 27 October 2025
+    let response = app
+        .get_attendance_report(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-18"),
+            ("to", "2025-10-27"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["weekStartsOn"], "Monday");
+    // Sunday ends the first week rather than starting the second
+    assert_eq!(
+        body["members"][0]["weeks"],
+        weeks(&[
+            ("2025-10-18", "2025-10-19", 60, 60),
+            ("2025-10-20", "2025-10-26", 120, 120),
+            ("2025-10-27", "2025-10-27", 60, 60)
+        ])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_ranges(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        ("2025-10-31", "2025-10-01"),
+        ("2024-01-01", "2025-01-01"),
+        ("2025-10-01", "31/10/2025"),
+    ];
+
+    for (from, to) in test_cases {
+        let response = app
+            .get_attendance_report(&[
+                ("projectId", &project_id),
+                ("from", from),
+                ("to", to),
+            ])
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Should fail for {} to {}",
+            from,
+            to
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let _email = get_session(app, false).await;
+    let response = app
+        .get_attendance_report(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-01"),
+            ("to", "2025-10-31"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_format_labels_for_accept_language(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_member(app, "Ted", &project_id).await;
+
+    let test_cases = [
+        ("en-US,en;q=0.5", "en-US", "Wednesday 10/1/2025"),
+        ("fr-CH, fr;q=0.9", "fr-FR", "mercredi 01/10/2025"),
+        ("de", "de-DE", "Mittwoch, 01.10.2025"),
+        ("ja", "en-GB", "Wednesday 01/10/2025"),
+    ];
+
+    for (accept_language, locale, from_label) in test_cases {
+        let response = app
+            .http_client
+            .get(format!("{}/projects/attendance-report", &app.address))
+            .header("Accept-Language", accept_language)
+            .query(&[
+                ("projectId", project_id.as_str()),
+                ("from", "2025-10-01"),
+                ("to", "2025-10-31"),
+            ])
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), 200);
+
+        let body = get_json_response_body(response).await;
+        assert_eq!(body["locale"], locale, "Failed for {}", accept_language);
+        assert_eq!(
+            body["fromLabel"], from_label,
+            "Failed for {}",
+            accept_language
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_prefer_users_locale_to_accept_language(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let response = app.put_locale(&json!({ "locale": "es-ES" })).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .http_client
+        .get(format!("{}/projects/attendance-report", &app.address))
+        .header("Accept-Language", "en-US")
+        .query(&[
+            ("projectId", project_id.as_str()),
+            ("from", "2025-10-01"),
+            ("to", "2025-10-31"),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["locale"], "es-ES");
+    assert_eq!(body["fromLabel"], "miércoles 01/10/2025");
+}
@@ -0,0 +1,181 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use rota_manager::services::rota_publisher::publish_due_rotas;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_session, TestApp,
+};
+
+// Ted works Mondays and Dougal Fridays, and next week's rota is published at
+// 17:00 every Friday. Returns the project and Ted's shift.
+async fn set_up_rota(app: &mut TestApp) -> (String, String) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    let mut shift_ids = Vec::new();
+    for (member_id, day) in [(&ted, "Monday"), (&dougal, "Friday")] {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": day,
+                "startTime": 540,
+                "endTime": 1020
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+        shift_ids.push(get_json_response_body(response).await["id"].clone());
+    }
+
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"autoPublish": "0 17 * * FRI"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    (project_id, shift_ids[0].as_str().unwrap().to_owned())
+}
+
+// In the week of Sunday 19 October 2025
+fn at(day: u32, hour: u32, minute: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 10, day)
+        .unwrap()
+        .and_hms_opt(hour, minute, 0)
+        .unwrap()
+}
+
+async fn publish(app: &TestApp, now: NaiveDateTime) -> usize {
+    publish_due_rotas(&app.pg_pool, now)
+        .await
+        .expect("Failed to publish rotas")
+}
+
+async fn published_weeks(app: &TestApp) -> Vec<(NaiveDate, i32)> {
+    sqlx::query_as(
+        "SELECT week_of, jsonb_array_length(shifts) FROM rota_versions \
+         WHERE automatic ORDER BY week_of",
+    )
+    .fetch_all(&app.pg_pool)
+    .await
+    .expect("Failed to fetch published rotas")
+}
+
+async fn queued_alerts(app: &TestApp) -> Vec<(String, String)> {
+    sqlx::query_as(
+        "SELECT subject, content FROM email_outbox \
+         WHERE subject LIKE '%rota wasn''t published'",
+    )
+    .fetch_all(&app.pg_pool)
+    .await
+    .expect("Failed to fetch queued email")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_publish_next_week_on_schedule(app: &mut TestApp) {
+    let (project_id, _) = set_up_rota(app).await;
+
+    // The first poll works out when the schedule next runs
+    assert_eq!(publish(app, at(22, 10, 0)).await, 0);
+    assert_eq!(publish(app, at(24, 16, 59)).await, 0);
+
+    assert_eq!(publish(app, at(24, 17, 0)).await, 1);
+    let week_of = NaiveDate::from_ymd_opt(2025, 10, 26).unwrap();
+    assert_eq!(published_weeks(app).await, vec![(week_of, 2)]);
+
+    // Each run only happens once
+    assert_eq!(publish(app, at(24, 17, 1)).await, 0);
+    assert_eq!(published_weeks(app).await.len(), 1);
+
+    let history = get_json_response_body(
+        app.get_project_history(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(
+        history["entries"][0]["description"],
+        "The rota for the week of 2025-10-26 was published automatically \
+         with 2 shifts"
+    );
+    assert!(queued_alerts(app).await.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_alert_owner_instead_of_publishing_problems(app: &mut TestApp) {
+    let (_, ted_shift_id) = set_up_rota(app).await;
+    assert_eq!(publish(app, at(22, 10, 0)).await, 0);
+
+    // Ted is off next Monday and nobody is standing by
+    let response = app
+        .post_absence(&json!({"shiftId": &ted_shift_id, "date": "2025-10-27"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    assert_eq!(publish(app, at(24, 17, 0)).await, 0);
+    assert!(published_weeks(app).await.is_empty());
+
+    let alerts = queued_alerts(app).await;
+    assert_eq!(alerts.len(), 1);
+    assert_eq!(alerts[0].0, "Your Craggy Island rota wasn't published");
+    assert!(alerts[0].1.contains(
+        "- Ted is off on 2025-10-27 and nobody is covering their shift"
+    ));
+
+    // The alert isn't repeated until the next scheduled run
+    assert_eq!(publish(app, at(24, 17, 1)).await, 0);
+    assert_eq!(queued_alerts(app).await.len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_work_out_a_changed_schedule_again(app: &mut TestApp) {
+    let (project_id, _) = set_up_rota(app).await;
+    assert_eq!(publish(app, at(22, 10, 0)).await, 0);
+
+    // Moved to Thursdays, a time that has already passed this week
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"autoPublish": "0 9 * * THU"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(publish(app, at(23, 10, 0)).await, 0);
+    assert_eq!(publish(app, at(24, 17, 0)).await, 0);
+    assert_eq!(publish(app, at(30, 9, 0)).await, 1);
+
+    // Turned off
+    let response = app
+        .put_project_settings(&project_id, &json!({"autoPublish": null}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(publish(app, at(31, 17, 0)).await, 0);
+    assert_eq!(published_weeks(app).await.len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_submit_for_approval_when_project_has_approver(
+    app: &mut TestApp,
+) {
+    let (project_id, _) = set_up_rota(app).await;
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"approverEmail": "mrs.doyle@example.com"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(publish(app, at(22, 10, 0)).await, 0);
+
+    assert_eq!(publish(app, at(24, 17, 0)).await, 1);
+    assert!(published_weeks(app).await.is_empty());
+
+    let approvals =
+        get_json_response_body(app.get_rota_approvals(&project_id).await).await;
+    assert_eq!(approvals["approvals"][0]["status"], "pending");
+    assert_eq!(approvals["approvals"][0]["weekOf"], "2025-10-26");
+}
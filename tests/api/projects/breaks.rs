@@ -0,0 +1,125 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_session, TestApp,
+};
+
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_save_break_rules(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let rules = json!([
+        {"shiftLongerThan": 360, "breakLength": 30, "paid": false},
+        {"shiftLongerThan": 600, "breakLength": 45, "paid": true}
+    ]);
+    let response = app
+        .put_project_settings(&project_id, &json!({"breakRules": rules}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body =
+        get_json_response_body(app.get_project_settings(&project_id).await)
+            .await;
+    assert_eq!(body["breakRules"], rules);
+
+    // Other settings leave the rules alone
+    app.put_project_settings(&project_id, &json!({"shiftGranularity": 15}))
+        .await;
+    let body =
+        get_json_response_body(app.get_project_settings(&project_id).await)
+            .await;
+    assert_eq!(body["breakRules"], rules);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_break_rules(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        json!([{"shiftLongerThan": 360, "breakLength": 0}]),
+        json!([{"shiftLongerThan": 30, "breakLength": 45}]),
+        json!([{"shiftLongerThan": 1440, "breakLength": 30}]),
+        json!([
+            {"shiftLongerThan": 360, "breakLength": 30},
+            {"shiftLongerThan": 360, "breakLength": 45}
+        ]),
+    ];
+
+    for rules in test_cases {
+        let response = app
+            .put_project_settings(&project_id, &json!({"breakRules": rules}))
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Should fail for rules {}",
+            rules
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_insert_breaks_when_adding_shifts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    app.put_project_settings(
+        &project_id,
+        &json!({
+            "shiftGranularity": 15,
+            "breakRules": [
+                {"shiftLongerThan": 360, "breakLength": 30, "paid": false}
+            ]
+        }),
+    )
+    .await;
+
+    let expected_break = json!({
+        "startTime": 765,
+        "endTime": 795,
+        "paid": false,
+        "autoInserted": true
+    });
+
+    let shift = json!({
+        "memberId": &member_id,
+        "day": "Monday",
+        "startTime": 540,
+        "endTime": 1020
+    });
+    let body =
+        get_json_response_body(app.post_validate_shift(&shift).await).await;
+    assert_eq!(body["breaks"], json!([expected_break]));
+
+    let response = app.post_shift(&shift).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["breaks"], json!([expected_break]));
+
+    let short_shift = json!({
+        "memberId": &member_id,
+        "day": "Tuesday",
+        "startTime": 540,
+        "endTime": 900
+    });
+    let body = get_json_response_body(app.post_shift(&short_shift).await).await;
+    assert_eq!(body["breaks"], json!([]));
+
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    let shifts = project["members"][0]["shifts"].as_array().unwrap();
+    assert_eq!(shifts.len(), 2);
+    for shift in shifts {
+        let expected = match shift["day"].as_str().unwrap() {
+            "Monday" => json!([expected_break]),
+            _ => json!([]),
+        };
+        assert_eq!(shift["breaks"], expected);
+    }
+}
@@ -0,0 +1,163 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+use rota_manager::{routes::projects::ClearShiftsResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+// Ted works Monday and Tuesday, and Dougal Monday
+async fn add_craggy_island(app: &mut TestApp) -> String {
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for (member_id, day) in
+        [(&ted, "Monday"), (&ted, "Tuesday"), (&dougal, "Monday")]
+    {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": day,
+                "startTime": 540,
+                "endTime": 1020
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+    project_id
+}
+
+async fn shift_days(app: &mut TestApp, project_id: &str) -> Vec<String> {
+    let project =
+        get_json_response_body(app.get_project(project_id).await).await;
+    let mut days: Vec<String> = project["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|member| member["shifts"].as_array().unwrap().clone())
+        .map(|shift| shift["day"].as_str().unwrap().to_owned())
+        .collect();
+    days.sort();
+    days
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_count_shifts_on_a_dry_run(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id, "dryRun": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ClearShiftsResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body, json!({"day": null, "dryRun": true, "shifts": 3}));
+    assert_eq!(shift_days(app, &project_id).await.len(), 3);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_clear_one_day(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id, "day": "Monday"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        get_json_response_body(response).await,
+        json!({"day": "Monday", "dryRun": false, "shifts": 2})
+    );
+    assert_eq!(shift_days(app, &project_id).await, ["Tuesday"]);
+
+    let history = get_json_response_body(
+        app.get_project_history(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(
+        history["entries"][0]["description"],
+        format!("{} cleared 2 shifts on Monday", email)
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_clear_the_whole_week(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(get_json_response_body(response).await["shifts"], 3);
+    assert!(shift_days(app, &project_id).await.is_empty());
+
+    // Clearing an empty week is not an error
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(get_json_response_body(response).await["shifts"], 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_day(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id, "day": "Funday"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Invalid day"
+    );
+    assert_eq!(shift_days(app, &project_id).await.len(), 3);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_422_if_malformed_request(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    for body in [json!({}), json!({"projectId": "not-a-uuid"})] {
+        let response = app.post_clear_shifts(&body).await;
+        assert_eq!(response.status().as_u16(), 422, "{}", body);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_clear_someone_elses_shifts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let _other_email = get_session(app, false).await;
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_logged_out(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+    logout(app).await;
+
+    let response = app
+        .post_clear_shifts(&json!({"projectId": &project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,191 @@
+use rota_manager::{
+    routes::projects::{AddCommentResponse, CommentListResponse},
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+
+const TED: &str = "+447700900123";
+const DOUGAL: &str = "+447700900456";
+
+// Ted works Mondays and takes texts; Dougal has a number but hasn't opted
+// in. Returns the project, Ted, Dougal and Ted's shift.
+async fn set_up_rota(app: &mut TestApp) -> (String, String, String, String) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for (member_id, number, sms_opt_in) in
+        [(&ted, TED, true), (&dougal, DOUGAL, false)]
+    {
+        let response = app
+            .put_member_contact(
+                member_id,
+                &json!({"phoneNumber": number, "smsOptIn": sms_opt_in}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+    }
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let shift_id = get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    (project_id, ted, dougal, shift_id)
+}
+
+async fn queued_sms(app: &TestApp) -> Vec<(String, String)> {
+    sqlx::query_as("SELECT recipient, content FROM sms_outbox ORDER BY id")
+        .fetch_all(&app.pg_pool)
+        .await
+        .expect("Failed to fetch queued SMS")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_comment_on_shift_and_text_mentioned_members(app: &mut TestApp) {
+    let (project_id, ted, dougal, shift_id) = set_up_rota(app).await;
+
+    let response = app
+        .post_comment(&json!({
+            "projectId": &project_id,
+            "shiftId": &shift_id,
+            "body": "@ted the keys are with @Dougal "
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<AddCommentResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["comment"]["body"], "@ted the keys are with @Dougal");
+    assert_eq!(body["comment"]["shiftId"], shift_id.as_str());
+    assert_eq!(body["comment"]["mentions"], json!([ted, dougal]));
+    // Dougal hasn't opted in to texts
+    assert_eq!(body["notified"], 1);
+    assert_eq!(
+        queued_sms(app).await,
+        [(
+            TED.to_owned(),
+            "You were mentioned in a comment on the Craggy Island rota: \
+             \"@ted the keys are with @Dougal\""
+                .to_owned()
+        )]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_and_delete_comments(app: &mut TestApp) {
+    let (project_id, _, _, shift_id) = set_up_rota(app).await;
+    let mut comment_ids = Vec::new();
+    for (shift, body) in [
+        (None, "Mrs Doyle is bringing tea"),
+        (Some(&shift_id), "Who's opening up?"),
+    ] {
+        let response = app
+            .post_comment(&json!({
+                "projectId": &project_id,
+                "shiftId": shift,
+                "body": body
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+        let body = get_json_response_body(response).await;
+        assert_eq!(body["notified"], 0);
+        comment_ids.push(body["comment"]["commentId"].clone());
+    }
+
+    let response = app.get_comments(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<CommentListResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["comments"][0]["body"], "Mrs Doyle is bringing tea");
+    assert_eq!(body["comments"][0]["shiftId"], json!(null));
+    assert_eq!(body["comments"][1]["body"], "Who's opening up?");
+
+    let shift_comments = get_json_response_body(
+        app.get_comments(&[("projectId", &project_id), ("shiftId", &shift_id)])
+            .await,
+    )
+    .await;
+    assert_eq!(shift_comments["comments"].as_array().unwrap().len(), 1);
+
+    let comment_id = comment_ids[1].as_str().unwrap();
+    assert_eq!(app.delete_comment(comment_id).await.status().as_u16(), 204);
+    assert_eq!(app.delete_comment(comment_id).await.status().as_u16(), 404);
+    let body = get_json_response_body(
+        app.get_comments(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(body["comments"].as_array().unwrap().len(), 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_invalid_comments(app: &mut TestApp) {
+    let (project_id, _, _, shift_id) = set_up_rota(app).await;
+
+    let response = app
+        .post_comment(&json!({"projectId": &project_id, "body": "  "}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Comments cannot be empty"
+    );
+
+    // A shift from another project
+    let other_project = add_new_project(app, "Rugged Island").await;
+    let response = app
+        .post_comment(&json!({
+            "projectId": &other_project,
+            "shiftId": &shift_id,
+            "body": "Who's this?"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_someone_elses_comments(app: &mut TestApp) {
+    let (project_id, _, _, _) = set_up_rota(app).await;
+    let response = app
+        .post_comment(&json!({"projectId": &project_id, "body": "Hello"}))
+        .await;
+    let comment_id = get_json_response_body(response).await["comment"]
+        ["commentId"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    logout(app).await;
+    let _email = get_session(app, false).await;
+    let response = app.get_comments(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 404);
+    let response = app
+        .post_comment(&json!({"projectId": &project_id, "body": "Hello"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+    assert_eq!(app.delete_comment(&comment_id).await.status().as_u16(), 404);
+}
@@ -0,0 +1,164 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    routes::projects::{
+        AddMemberResponse, AttendanceReportResponse, RotaVersionResponse,
+    },
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_keep_contracted_hours_until_cleared(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app
+        .post_add_member(&json!({
+            "projectId": &project_id,
+            "memberName": "Ted",
+            "contractedHoursPerWeek": 37.5
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<AddMemberResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["contractedHoursPerWeek"], 37.5);
+    let ted = body["memberId"].as_str().unwrap().to_owned();
+
+    // Renaming leaves the contract alone
+    let response = app
+        .put_member(&ted, &json!({"memberName": "Father Ted"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["contractedHoursPerWeek"], 37.5);
+
+    let response = app
+        .put_member(
+            &ted,
+            &json!({"memberName": "Father Ted", "contractedHoursPerWeek": null}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(app.get_member(&ted).await).await;
+    assert!(body.get("contractedHoursPerWeek").is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_impossible_contracts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    for hours in [-1.0, 200.0] {
+        let response = app
+            .put_member(
+                &ted,
+                &json!({"memberName": "Ted", "contractedHoursPerWeek": hours}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", hours);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            "Validation error: Contracted hours must be between 0 and 168 a \
+             week"
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_hours_against_contract(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .put_member(
+            &ted,
+            &json!({"memberName": "Ted", "contractedHoursPerWeek": 14}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    // A Sunday-to-Saturday week
+    let response = app
+        .get_attendance_report(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-05"),
+            ("to", "2025-10-11"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<AttendanceReportResponse>(), &body),
+        "response does not match schema"
+    );
+    let member = &body["members"][0];
+    assert_eq!(member["contractedMinutes"], 840);
+    assert_eq!(member["contractDeltaMinutes"], -360);
+    assert_eq!(member["weeks"][0]["contractDeltaMinutes"], -360);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_warn_when_publishing_under_contract(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let response = app
+        .post_add_member(&json!({
+            "projectId": &project_id,
+            "memberName": "Ted",
+            "contractedHoursPerWeek": 16
+        }))
+        .await;
+    let ted = get_json_response_body(response).await["memberId"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_publish_rota(&json!({
+            "projectId": &project_id,
+            "weekOf": "2025-10-29"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<RotaVersionResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body["warnings"],
+        json!(["Ted is scheduled for 8h 0m of their 16h 0m contracted hours"])
+    );
+}
@@ -0,0 +1,231 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    routes::projects::{
+        BorrowSuggestionsResponse, DepartmentListResponse, DepartmentResponse,
+        ProjectResponse, ShiftSlotListResponse,
+    },
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+async fn add_department(app: &TestApp, project_id: &str, name: &str) -> String {
+    let response = app
+        .post_department(&json!({"projectId": project_id, "name": name}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<DepartmentResponse>(), &body),
+        "response does not match schema"
+    );
+    body["departmentId"].as_str().unwrap().to_owned()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_departments_by_name(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_department(app, &project_id, "Kitchen").await;
+    add_department(app, &project_id, "Bar").await;
+
+    let response = app.get_departments(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<DepartmentListResponse>(), &body),
+        "response does not match schema"
+    );
+    let names: Vec<&str> = body["departments"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|department| department["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, vec!["Bar", "Kitchen"]);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_duplicate_department(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_department(app, &project_id, "Bar").await;
+
+    let response = app
+        .post_department(&json!({"projectId": &project_id, "name": "Bar"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: The project already has a department by that name"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_filter_rota_by_department(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let bar = add_department(app, &project_id, "Bar").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    add_member(app, "Dougal", &project_id).await;
+
+    let response = app
+        .put_member(&ted, &json!({"memberName": "Ted", "departmentId": &bar}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(get_json_response_body(response).await["departmentId"], bar);
+
+    let response = app
+        .http_client
+        .get(format!("{}/projects/project", &app.address))
+        .query(&[("projectId", project_id.as_str()), ("departmentId", &bar)])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectResponse>(), &body),
+        "response does not match schema"
+    );
+    let members = body["members"].as_array().unwrap();
+    assert_eq!(members.len(), 1);
+    assert_eq!(members[0]["memberId"], ted.as_str());
+    assert_eq!(members[0]["departmentId"], bar.as_str());
+
+    // Renaming keeps the department; null takes the member out of it
+    let response = app
+        .put_member(&ted, &json!({"memberName": "Father Ted"}))
+        .await;
+    assert_eq!(get_json_response_body(response).await["departmentId"], bar);
+    let response = app
+        .put_member(&ted, &json!({"memberName": "Ted", "departmentId": null}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(app.get_member(&ted).await).await;
+    assert!(body.get("departmentId").is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_filter_slot_coverage_by_department(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let kitchen = add_department(app, &project_id, "Kitchen").await;
+    for department_id in [json!(kitchen), json!(null)] {
+        let response = app
+            .post_shift_slot(&json!({
+                "projectId": &project_id,
+                "day": "Saturday",
+                "startTime": 540,
+                "endTime": 1020,
+                "places": 2,
+                "departmentId": department_id
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app
+        .http_client
+        .get(format!("{}/projects/slots", &app.address))
+        .query(&[
+            ("projectId", project_id.as_str()),
+            ("departmentId", &kitchen),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ShiftSlotListResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["uncovered"], 1);
+    assert_eq!(body["slots"][0]["departmentId"], kitchen.as_str());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_department_of_another_project(
+    app: &mut TestApp,
+) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_new_project(app, "Craggy Island").await;
+    let rugged_island = add_new_project(app, "Rugged Island").await;
+    let bar = add_department(app, &rugged_island, "Bar").await;
+
+    let response = app
+        .post_add_member(&json!({
+            "projectId": &craggy_island,
+            "memberName": "Ted",
+            "departmentId": &bar
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_suggest_skilled_members_to_borrow(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let bar = add_department(app, &project_id, "Bar").await;
+    let kitchen = add_department(app, &project_id, "Kitchen").await;
+
+    let mut kitchen_members = Vec::new();
+    for (name, skills) in [("Ted", json!(["barista"])), ("Dougal", json!([]))] {
+        let response = app
+            .post_add_member(&json!({
+                "projectId": &project_id,
+                "memberName": name,
+                "departmentId": &kitchen,
+                "skills": skills
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+        let body = get_json_response_body(response).await;
+        // Skills are left out of the response when there are none
+        assert_eq!(body.get("skills").unwrap_or(&json!([])), &skills);
+        kitchen_members.push(body["memberId"].as_str().unwrap().to_owned());
+    }
+    let response = app
+        .post_shift_slot(&json!({
+            "projectId": &project_id,
+            "day": "Saturday",
+            "startTime": 540,
+            "endTime": 1020,
+            "places": 2,
+            "tag": "barista",
+            "departmentId": &bar
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let slot_id = get_json_response_body(response).await["slotId"].clone();
+
+    let response = app.get_borrow_suggestions(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<BorrowSuggestionsResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["suggestions"][0]["slotId"], slot_id);
+    assert_eq!(body["suggestions"][0]["departmentId"], bar.as_str());
+    assert_eq!(body["suggestions"][0]["openPlaces"], 2);
+    assert_eq!(
+        body["suggestions"][0]["candidates"],
+        json!([{
+            "memberId": &kitchen_members[0],
+            "memberName": "Ted",
+            "departmentId": &kitchen
+        }])
+    );
+}
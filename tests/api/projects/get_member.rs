@@ -1,9 +1,11 @@
 use crate::helpers::{
-    add_member, add_new_project, get_json_response_body, get_session, logout,
-    TestApp,
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
 };
 
-use serde_json::json;
+use rota_manager::{
+    routes::projects::MemberResponse, ErrorResponse, FieldError,
+};
 use test_context::test_context;
 
 #[test_context(TestApp)]
@@ -12,27 +14,7 @@ async fn should_return_200_for_valid_requests(app: &mut TestApp) {
     let _email = get_session(app, false).await;
     let project_id = add_new_project(app, "Craggy Island").await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "name": {
-          "type": "string",
-          "minLength": 1,
-          "maxLength": 255
-        },
-        "id": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        }
-      },
-      "required": [
-        "name",
-        "id",
-      ]
-    });
+    let schema = get_schema::<MemberResponse>();
 
     let members = ["Ted", "Dougal"];
 
@@ -93,10 +75,17 @@ async fn should_return_400_if_no_query_param(app: &mut TestApp) {
         response
     );
 
-    let response_body = response.text().await.unwrap();
+    let error = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(error.code, "invalid_query");
     assert_eq!(
-        response_body,
-        "Failed to deserialize query string: missing field `memberId`"
+        error.details,
+        vec![FieldError {
+            field: "memberId".to_owned(),
+            message: "missing field `memberId`".to_owned(),
+        }]
     );
 }
 
@@ -116,10 +105,17 @@ async fn should_return_400_if_invalid_query_param(app: &mut TestApp) {
         response
     );
 
-    let response_body = response.text().await.unwrap();
+    let error = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(error.code, "invalid_query");
     assert_eq!(
-        response_body,
-        "Failed to deserialize query string: UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `z` at 3"
+        error.details,
+        vec![FieldError {
+            field: "memberId".to_owned(),
+            message: "UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `z` at 3".to_owned(),
+        }]
     );
 }
 
@@ -197,4 +193,16 @@ async fn should_return_404_for_non_existent_member_id(app: &mut TestApp) {
         404,
         "Should return 404 for non-existent member IDs",
     );
+    let body = response.json::<ErrorResponse>().await.unwrap();
+    assert_eq!(body.code, "not_found");
+    assert_eq!(
+        body.error,
+        "Member not found: d8bb2e67-9979-4534-a13f-198664290c44"
+    );
+    let entity = body.entity.expect("Missing entity");
+    assert_eq!(entity.kind, "member");
+    assert_eq!(
+        entity.id.to_string(),
+        "d8bb2e67-9979-4534-a13f-198664290c44"
+    );
 }
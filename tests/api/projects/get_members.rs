@@ -1,8 +1,11 @@
 use crate::helpers::{
-    add_member, add_new_project, get_json_response_body, get_session, logout,
-    TestApp,
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
 };
 
+use rota_manager::{
+    routes::projects::MemberListResponse, ErrorResponse, FieldError,
+};
 use serde_json::json;
 use test_context::test_context;
 
@@ -12,40 +15,7 @@ async fn should_return_200_for_valid_requests(app: &mut TestApp) {
     let _email = get_session(app, false).await;
     let project_id = add_new_project(app, "Barry Island").await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "projectId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        },
-        "members": {
-          "type": "array",
-          "items": {
-            "type": "object",
-            "properties": {
-              "id": {
-                "type": "string",
-                "minLength": 36,
-                "maxLength": 36
-              },
-              "name": {
-                "type": "string",
-                "minLength": 1,
-                "maxLength": 255
-              }
-            },
-          }
-        },
-      },
-      "required": [
-        "projectId",
-        "members",
-      ]
-    });
+    let schema = get_schema::<MemberListResponse>();
 
     let response = app.get_members(&project_id).await;
     assert_eq!(
@@ -122,10 +92,17 @@ async fn should_return_400_if_no_query_param(app: &mut TestApp) {
         response
     );
 
-    let response_body = response.text().await.unwrap();
+    let error = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(error.code, "invalid_query");
     assert_eq!(
-        response_body,
-        "Failed to deserialize query string: missing field `projectId`"
+        error.details,
+        vec![FieldError {
+            field: "projectId".to_owned(),
+            message: "missing field `projectId`".to_owned(),
+        }]
     );
 }
 
@@ -144,10 +121,17 @@ async fn should_return_400_if_invalid_query_param(app: &mut TestApp) {
         response
     );
 
-    let response_body = response.text().await.unwrap();
+    let error = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(error.code, "invalid_query");
     assert_eq!(
-        response_body,
-        "Failed to deserialize query string: UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `z` at 3"
+        error.details,
+        vec![FieldError {
+            field: "projectId".to_owned(),
+            message: "UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `z` at 3".to_owned(),
+        }]
     );
 }
 
@@ -0,0 +1,214 @@
+use rota_manager::{routes::projects::ProjectResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+// Craggy Island, where Ted works Mondays. Returns the project.
+async fn set_up_project(app: &mut TestApp) -> String {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    project_id
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_include_everything_by_default(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+
+    let response = app.get_project(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["members"][0]["memberName"], "Ted");
+    assert_eq!(body["members"][0]["shifts"][0]["day"], "Monday");
+
+    let response = app
+        .get_project_including(&project_id, "members.shifts")
+        .await;
+    assert_eq!(get_json_response_body(response).await, body);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_leave_out_relations_not_included(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+
+    let response = app.get_project_including(&project_id, "members").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["members"][0]["memberName"], "Ted");
+    assert!(body["members"][0].get("shifts").is_none());
+
+    let response = app.get_project_including(&project_id, "").await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["projectName"], "Craggy Island");
+    assert!(body.get("members").is_none());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_order_members_by_name_then_id(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+    let mut toms = Vec::new();
+    for name in ["Tom", "Dougal", "Tom", "Jack"] {
+        let member_id = add_member(app, name, &project_id).await;
+        if name == "Tom" {
+            toms.push(member_id);
+        }
+    }
+    toms.sort();
+
+    let response = app.get_project(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    let members = body["members"].as_array().unwrap();
+    let names: Vec<&str> = members
+        .iter()
+        .map(|member| member["memberName"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["Dougal", "Jack", "Ted", "Tom", "Tom"]);
+    assert_eq!(members[3]["memberId"], toms[0]);
+    assert_eq!(members[4]["memberId"], toms[1]);
+
+    for _ in 0..3 {
+        let response = app.get_project(&project_id).await;
+        assert_eq!(get_json_response_body(response).await, body);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_page_through_members(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+    for name in ["Dougal", "Jack"] {
+        add_member(app, name, &project_id).await;
+    }
+
+    let mut names = Vec::new();
+    for (offset, has_more) in [("0", true), ("2", false)] {
+        let response = app
+            .http_client
+            .get(format!("{}/projects/project", &app.address))
+            .query(&[
+                ("projectId", project_id.as_str()),
+                ("limit", "2"),
+                ("offset", offset),
+            ])
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), 200);
+        let body = get_json_response_body(response).await;
+        assert!(
+            jsonschema::is_valid(&get_schema::<ProjectResponse>(), &body),
+            "response does not match schema"
+        );
+        assert_eq!(body["hasMore"], has_more);
+        for member in body["members"].as_array().unwrap() {
+            names.push(member["memberName"].as_str().unwrap().to_owned());
+        }
+    }
+    assert_eq!(names, ["Dougal", "Jack", "Ted"]);
+
+    let response = app.get_project(&project_id).await;
+    assert_eq!(get_json_response_body(response).await["hasMore"], false);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_include_shifts_on_the_days_given(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+
+    for (days, shifts) in [("Monday", 1), ("Tuesday,Wednesday", 0)] {
+        let response = app
+            .http_client
+            .get(format!("{}/projects/project", &app.address))
+            .query(&[("projectId", project_id.as_str()), ("days", days)])
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), 200);
+        let body = get_json_response_body(response).await;
+        assert_eq!(body["members"][0]["memberName"], "Ted");
+        assert_eq!(
+            body["members"][0]["shifts"].as_array().unwrap().len(),
+            shifts,
+            "Failed for {}",
+            days
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_window(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+
+    for query in [
+        [("limit", "0"), ("include", "members")],
+        [("limit", "101"), ("include", "members")],
+        [("offset", "-1"), ("include", "members")],
+        [("limit", "10"), ("include", "")],
+        [("days", "Caturday"), ("include", "members.shifts")],
+        [("days", "Monday"), ("include", "members")],
+    ] {
+        let response = app
+            .http_client
+            .get(format!("{}/projects/project", &app.address))
+            .query(&[("projectId", project_id.as_str())])
+            .query(&query)
+            .send()
+            .await
+            .expect("Failed to execute request");
+        assert_eq!(response.status().as_u16(), 400, "Failed for {:?}", query);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_include(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+
+    let response = app.get_project_including(&project_id, "shifts").await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Cannot include shifts; use members or \
+         members.shifts"
+    );
+
+    let response = app
+        .http_client
+        .get(format!("{}/projects/project", &app.address))
+        .query(&[
+            ("projectId", project_id.as_str()),
+            ("include", "members"),
+            ("tag", "training"),
+        ])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,96 @@
+use rota_manager::{routes::projects::ProjectBatchResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_summarise_projects_in_the_order_asked(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &craggy).await;
+    add_member(app, "Dougal", &craggy).await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let rugged = add_new_project(app, "Rugged Island").await;
+
+    let response = app
+        .get_project_batch(&format!("{},{}, {}", rugged, craggy, rugged))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectBatchResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body["projects"],
+        json!([
+            {
+                "projectId": rugged,
+                "projectName": "Rugged Island",
+                "weekStartsOn": body["projects"][0]["weekStartsOn"],
+                "members": 0,
+                "shifts": 0
+            },
+            {
+                "projectId": craggy,
+                "projectName": "Craggy Island",
+                "weekStartsOn": body["projects"][1]["weekStartsOn"],
+                "members": 2,
+                "shifts": 1
+            }
+        ])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_if_any_project_is_someone_elses(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let theirs = add_new_project(app, "Craggy Island").await;
+    logout(app).await;
+    let _email = get_session(app, false).await;
+    let mine = add_new_project(app, "Rugged Island").await;
+
+    let response = app.get_project_batch(&format!("{},{}", mine, theirs)).await;
+    assert_eq!(response.status().as_u16(), 404);
+    assert!(response
+        .json::<ErrorResponse>()
+        .await
+        .unwrap()
+        .error
+        .contains(&theirs));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_ids(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let too_many = (0..51)
+        .map(|_| uuid::Uuid::new_v4().to_string())
+        .collect::<Vec<_>>()
+        .join(",");
+
+    for ids in [
+        String::new(),
+        format!("{},not-a-uuid", project_id),
+        format!("{},", project_id),
+        too_many,
+    ] {
+        let response = app.get_project_batch(&ids).await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {:?}", ids);
+    }
+}
@@ -0,0 +1,123 @@
+use chrono::NaiveDate;
+use rota_manager::{routes::projects::ProjectOverviewResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+
+async fn add_shift(
+    app: &mut TestApp,
+    member_id: &str,
+    day: &str,
+    end_time: i16,
+) -> String {
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": day,
+            "startTime": 540,
+            "endTime": end_time
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_total_each_members_next_seven_days(app: &mut TestApp) {
+    // A Sunday
+    app.clock.set(
+        NaiveDate::from_ymd_opt(2025, 10, 19)
+            .unwrap()
+            .and_hms_opt(12, 0, 0)
+            .unwrap()
+            .and_utc(),
+    );
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    app.put_project_settings(
+        &project_id,
+        &json!({
+            "breakRules": [
+                {"shiftLongerThan": 360, "breakLength": 30, "paid": false}
+            ]
+        }),
+    )
+    .await;
+    let monday = add_shift(app, &ted, "Monday", 1020).await;
+    let tuesday = add_shift(app, &ted, "Tuesday", 900).await;
+
+    // Off for Tuesday this week and Monday next week
+    for (shift_id, date) in [(&tuesday, "2025-10-21"), (&monday, "2025-10-27")]
+    {
+        let response = app
+            .post_absence(&json!({"shiftId": shift_id, "date": date}))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    let response = app.get_project_summary(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectOverviewResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["members"], 2);
+    assert_eq!(body["shifts"], 2);
+    assert_eq!(body["from"], "2025-10-19");
+    assert_eq!(body["to"], "2025-10-25");
+    // Monday's shift has an unpaid half hour break
+    assert_eq!(
+        body["upcoming"],
+        json!([
+            {
+                "memberId": dougal,
+                "memberName": "Dougal",
+                "shifts": 0,
+                "minutes": 0,
+                "absences": 0
+            },
+            {
+                "memberId": ted,
+                "memberName": "Ted",
+                "shifts": 1,
+                "minutes": 450,
+                "absences": 1
+            }
+        ])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_someone_elses_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    logout(app).await;
+    let _email = get_session(app, false).await;
+
+    let response = app.get_project_summary(&project_id).await;
+    assert_eq!(response.status().as_u16(), 404);
+    let body = response.json::<ErrorResponse>().await.unwrap();
+    assert_eq!(body.error, format!("Project not found: {}", project_id));
+    assert_eq!(body.entity.unwrap().kind, "project");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_project_id(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app.get_project_summary("not-a-uuid").await;
+    assert_eq!(response.status().as_u16(), 400);
+}
@@ -0,0 +1,202 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+
+use rota_manager::routes::projects::ProjectHistoryResponse;
+use serde_json::json;
+use test_context::test_context;
+
+async fn add_shift(app: &mut TestApp, member_id: &str) {
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+}
+
+fn descriptions(body: &serde_json::Value) -> Vec<&str> {
+    body["entries"]
+        .as_array()
+        .expect("entries should be an array")
+        .iter()
+        .map(|entry| entry["description"].as_str().unwrap())
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_timeline_newest_first(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    add_shift(app, &member_id).await;
+
+    let response = app.get_project_history(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectHistoryResponse>(), &body),
+        "response does not match schema"
+    );
+
+    assert_eq!(
+        descriptions(&body),
+        vec![
+            format!("{} added shift for Ted on Monday 09:00–17:00", email),
+            format!("{} added member Ted", email),
+            format!("{} created project Craggy Island", email),
+        ]
+    );
+    assert_eq!(body["projectId"], json!(project_id));
+    assert_eq!(body["entries"][0]["entityType"], "shift");
+    assert_eq!(body["nextOffset"], json!(null));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_filter_by_entity_type(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    add_member(app, "Dougal", &project_id).await;
+    add_shift(app, &member_id).await;
+
+    let response = app
+        .get_project_history(&[
+            ("projectId", &project_id),
+            ("entityType", "member"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert_eq!(
+        descriptions(&body),
+        vec![
+            format!("{} added member Dougal", email),
+            format!("{} added member Ted", email),
+        ]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_paginate(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    for name in ["Ted", "Dougal", "Jack"] {
+        add_member(app, name, &project_id).await;
+    }
+
+    let response = app
+        .get_project_history(&[("projectId", &project_id), ("limit", "2")])
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["entries"].as_array().unwrap().len(), 2);
+    assert_eq!(body["nextOffset"], json!(2));
+
+    let response = app
+        .get_project_history(&[
+            ("projectId", &project_id),
+            ("limit", "2"),
+            ("offset", "2"),
+        ])
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["entries"].as_array().unwrap().len(), 2);
+    assert_eq!(body["nextOffset"], json!(null));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_paginate_by_cursor(app: &mut TestApp) {
+    let email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    for name in ["Ted", "Dougal", "Jack"] {
+        add_member(app, name, &project_id).await;
+    }
+
+    let response = app
+        .get_project_history(&[("projectId", &project_id), ("limit", "2")])
+        .await;
+    let body = get_json_response_body(response).await;
+    let cursor = body["nextCursor"].as_str().unwrap().to_owned();
+
+    // Entries added meanwhile don't shift the next page
+    add_member(app, "Mrs Doyle", &project_id).await;
+    let response = app
+        .get_project_history(&[
+            ("projectId", &project_id),
+            ("limit", "2"),
+            ("cursor", &cursor),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(
+        descriptions(&body),
+        vec![
+            format!("{} added member Ted", email),
+            format!("{} created project Craggy Island", email),
+        ]
+    );
+    assert_eq!(body["nextCursor"], json!(null));
+    assert_eq!(body["nextOffset"], json!(null));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_query(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        vec![("projectId", project_id.as_str()), ("entityType", "user")],
+        vec![("projectId", project_id.as_str()), ("limit", "0")],
+        vec![("projectId", project_id.as_str()), ("limit", "101")],
+        vec![("projectId", project_id.as_str()), ("offset", "-1")],
+        vec![("projectId", project_id.as_str()), ("cursor", "MTA")],
+        vec![
+            ("projectId", project_id.as_str()),
+            ("cursor", "aGlzdG9yeToxMA"),
+            ("offset", "0"),
+        ],
+        vec![("projectId", "not-a-uuid")],
+    ];
+
+    for query in test_cases.iter() {
+        let response = app.get_project_history(query).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Failed for query: {:?}",
+            query
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    logout(app).await;
+
+    let _email = get_session(app, false).await;
+    let response = app.get_project_history(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_not_logged_in(app: &mut TestApp) {
+    let project_id = uuid::Uuid::new_v4().to_string();
+    let response = app.get_project_history(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,169 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{routes::projects::ImportRotaResponse, ErrorResponse};
+use serde_json::json;
+use test_context::test_context;
+
+// Ted is already in the project; Dougal isn't yet
+const DEPUTY_EXPORT: &str = "Employee,Date,Start,End,Area\n\
+                             Ted,06/01/2025,09:00,17:00,Bar\n\
+                             Dougal,07/01/2025,9:00 am,1:00 pm,\n";
+
+// (member name, day, start time) for each of the project's shifts
+async fn shifts(
+    app: &mut TestApp,
+    project_id: &str,
+) -> Vec<(String, String, i64)> {
+    let project =
+        get_json_response_body(app.get_project(project_id).await).await;
+    let mut shifts: Vec<_> = project["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|member| {
+            member["shifts"].as_array().unwrap().iter().map(|shift| {
+                (
+                    member["memberName"].as_str().unwrap().to_owned(),
+                    shift["day"].as_str().unwrap().to_owned(),
+                    shift["startTime"].as_i64().unwrap(),
+                )
+            })
+        })
+        .collect();
+    shifts.sort();
+    shifts
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_preview_then_import_a_rota(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_member(app, "Ted", &project_id).await;
+
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": &project_id,
+            "csv": DEPUTY_EXPORT,
+            "dryRun": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ImportRotaResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["format"], "deputy");
+    assert_eq!(body["newMembers"], json!(["Dougal"]));
+    assert_eq!(body["shifts"].as_array().unwrap().len(), 2);
+    assert_eq!(body["shifts"][0]["tags"], json!(["bar"]));
+    assert_eq!(body["problems"], json!([]));
+    assert!(shifts(app, &project_id).await.is_empty());
+
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": &project_id,
+            "csv": DEPUTY_EXPORT
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        shifts(app, &project_id).await,
+        vec![
+            ("Dougal".to_owned(), "Tuesday".to_owned(), 540),
+            ("Ted".to_owned(), "Monday".to_owned(), 540),
+        ]
+    );
+
+    // Importing again would put everyone on two shifts at once
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": &project_id,
+            "csv": DEPUTY_EXPORT
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": &project_id,
+            "csv": DEPUTY_EXPORT,
+            "replace": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["newMembers"], json!([]));
+    assert_eq!(body["replaced"], 2);
+    assert_eq!(shifts(app, &project_id).await.len(), 2);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_list_problems_and_import_nothing(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let csv = "First Name,Last Name,Start Date,Start Time,End Date,End Time\n\
+               Ted,Crilly,01/06/2025,9:00 AM,01/06/2025,5:00 PM\n\
+               Jack,Hackett,01/06/2025,10:00 PM,01/07/2025,2:00 AM\n";
+
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": &project_id,
+            "csv": csv,
+            "dryRun": true
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["format"], "when-i-work");
+    assert_eq!(body["problems"][0]["line"], 3);
+
+    let response = app
+        .post_import_rota(&json!({"projectId": &project_id, "csv": csv}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(response
+        .json::<ErrorResponse>()
+        .await
+        .unwrap()
+        .error
+        .starts_with("Validation error: Line 3: "));
+    assert!(shifts(app, &project_id).await.is_empty());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_unrecognised_files(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    for body in [
+        json!({"projectId": &project_id, "csv": "Name,Hours\nTed,8\n"}),
+        json!({
+            "projectId": &project_id,
+            "csv": DEPUTY_EXPORT,
+            "format": "rotacloud"
+        }),
+    ] {
+        let response = app.post_import_rota(&body).await;
+        assert_eq!(response.status().as_u16(), 400);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_unknown_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app
+        .post_import_rota(&json!({
+            "projectId": uuid::Uuid::new_v4(),
+            "csv": DEPUTY_EXPORT
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -1,6 +1,7 @@
 use crate::helpers::{
-    add_new_project, get_json_response_body, get_session, TestApp,
+    add_new_project, get_json_response_body, get_schema, get_session, TestApp,
 };
+use rota_manager::routes::projects::ProjectListResponse;
 use serde_json::json;
 use test_context::test_context;
 
@@ -26,39 +27,7 @@ async fn should_return_empty_collection_if_no_projects(app: &mut TestApp) {
 async fn should_return_valid_list_for_valid_requests(app: &mut TestApp) {
     let _email = get_session(app, true).await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "projects": {
-          "type": "array",
-          "uniqueItems": true,
-          "minItems": 1,
-          "items": {
-            "required": [
-              "id",
-              "name"
-            ],
-            "properties": {
-              "id": {
-                "type": "string",
-                "minLength": 36,
-                "maxLength": 36
-              },
-              "name": {
-                "type": "string",
-                "minLength": 1,
-                "maxLength": 255
-              }
-            }
-          }
-        }
-      },
-      "required": [
-        "projects"
-      ]
-    });
+    let schema = get_schema::<ProjectListResponse>();
 
     // Project 1
     let first_project_name = "My awesome first project";
@@ -1,7 +1,31 @@
+mod absences;
 mod add_member;
 mod add_shift;
+mod attendance_report;
+mod auto_publish;
+mod breaks;
+mod clear_shifts;
+mod comments;
+mod contracted_hours;
+mod departments;
 mod get_member;
 mod get_members;
+mod get_project;
+mod get_project_batch;
+mod get_project_summary;
+mod history;
+mod import_rota;
 mod list;
 mod new;
+mod print;
+mod request_deadline;
+mod rota_approvals;
+mod rota_diff;
+mod row_level_security;
+mod settings;
+mod shift_escalations;
+mod shift_slots;
+mod shift_tags;
 mod update_member;
+mod validate_shift;
+mod week_templates;
@@ -1,6 +1,5 @@
-use crate::helpers::{get_session, TestApp};
-use rota_manager::ErrorResponse;
-use serde_json::json;
+use crate::helpers::{get_schema, get_session, TestApp};
+use rota_manager::{routes::projects::NewProjectResponse, ErrorResponse};
 use test_context::test_context;
 
 #[test_context(TestApp)]
@@ -8,27 +7,7 @@ use test_context::test_context;
 async fn should_return_200_for_valid_requests(app: &mut TestApp) {
     let _email = get_session(app, false).await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "name": {
-          "type": "string",
-          "minLength": 1,
-          "maxLength": 255
-        },
-        "id": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        }
-      },
-      "required": [
-        "name",
-        "id"
-      ]
-    });
+    let schema = get_schema::<NewProjectResponse>();
 
     let project_names = [
         "My hovercraft is full of eels",
@@ -0,0 +1,195 @@
+use crate::helpers::{
+    add_member, add_new_project, get_session, logout, TestApp,
+};
+use rota_manager::ErrorResponse;
+use serde_json::json;
+use test_context::test_context;
+
+// Ted works Monday mornings at the front desk and Dougal Tuesday afternoons
+async fn add_craggy_island(app: &mut TestApp) -> String {
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for body in [
+        json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 780,
+            "location": "Front desk",
+            "note": "Bring the float"
+        }),
+        json!({
+            "memberId": &dougal,
+            "day": "Tuesday",
+            "startTime": 780,
+            "endTime": 1020
+        }),
+    ] {
+        assert_eq!(app.post_shift(&body).await.status().as_u16(), 201);
+    }
+    project_id
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_print_a_row_per_member(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .get_printed_rota(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-20"),
+            ("to", "2025-10-21"),
+            ("locations", "true"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/html; charset=utf-8"
+    );
+
+    let page = response.text().await.unwrap();
+    assert!(page.contains(
+        "<title>Craggy Island: Monday 20/10/2025 – Tuesday 21/10/2025</title>"
+    ));
+    assert!(page.contains(r#"<th scope="col">Monday 20/10/2025</th>"#));
+    assert!(page.contains(r#"<th scope="row">Dougal</th>"#));
+    assert!(
+        page.find(">Dougal<").unwrap() < page.find(">Ted<").unwrap(),
+        "Members should be in alphabetical order"
+    );
+    assert!(page.contains(r#"<div class="times">09:00–13:00</div>"#));
+    assert!(page.contains(r#"<div class="location">Front desk</div>"#));
+    assert!(!page.contains("Bring the float"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_escape_names_in_the_page(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    add_member(app, "<script>alert(1)</script>", &project_id).await;
+
+    let response = app.get_printed_rota(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let page = response.text().await.unwrap();
+    assert!(!page.contains("<script>"));
+    assert!(page.contains("&lt;script&gt;"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_export_a_row_per_day_as_csv(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .get_printed_rota(&[
+            ("projectId", &project_id),
+            ("layout", "days"),
+            ("from", "2025-10-19"),
+            ("to", "2025-10-21"),
+            ("notes", "true"),
+            ("locations", "true"),
+            ("format", "csv"),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        response.headers()["content-type"],
+        "text/csv; charset=utf-8"
+    );
+    assert_eq!(
+        response.headers()["content-disposition"],
+        r#"attachment; filename="rota-2025-10-19-2025-10-21.csv""#
+    );
+    assert_eq!(
+        response.text().await.unwrap(),
+        "Day,Dougal,Ted\r\n\
+         Sunday 19/10/2025,,\r\n\
+         Monday 20/10/2025,,09:00–13:00 · Front desk · Bring the float\r\n\
+         Tuesday 21/10/2025,13:00–17:00,\r\n"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_default_to_the_current_week(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let response = app
+        .get_printed_rota(&[("projectId", &project_id), ("format", "csv")])
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let csv = response.text().await.unwrap();
+    // A header and a row per member, with a column for each day
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[0].starts_with("Member,Sunday "));
+    assert_eq!(lines[0].split(',').count(), 8);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_options(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let test_cases = [
+        (
+            ("layout", "weeks"),
+            "Validation error: Layout must be members or days",
+        ),
+        (
+            ("format", "pdf"),
+            "Validation error: Format must be html or csv",
+        ),
+        (
+            ("to", "2025-11-20"),
+            "Validation error: Printed rotas cannot cover more than 31 days",
+        ),
+    ];
+
+    for (option, error) in test_cases {
+        let response = app
+            .get_printed_rota(&[
+                ("projectId", &project_id),
+                ("from", "2025-10-20"),
+                option,
+            ])
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "{:?}", option);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_someone_elses_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+
+    let _other_email = get_session(app, false).await;
+    let response = app.get_printed_rota(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_logged_out(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_craggy_island(app).await;
+    logout(app).await;
+
+    let response = app.get_printed_rota(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
@@ -0,0 +1,43 @@
+use std::time::Duration;
+
+use rota_manager::{
+    domain::UserId, services::data_stores::begin_tenant_transaction,
+    utils::deadline::Deadline,
+};
+use test_context::test_context;
+
+use crate::helpers::TestApp;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_cancel_queries_running_past_the_deadline(app: &mut TestApp) {
+    let result = Deadline::after(Duration::from_millis(100))
+        .scope(async {
+            let mut tx =
+                begin_tenant_transaction(&app.pg_pool, &UserId::default())
+                    .await
+                    .expect("Failed to begin tenant transaction");
+            sqlx::query("SELECT pg_sleep(2)").execute(&mut *tx).await
+        })
+        .await;
+
+    match result {
+        Err(sqlx::Error::Database(e)) => {
+            // query_canceled
+            assert_eq!(e.code().as_deref(), Some("57014"))
+        }
+        other => panic!("Expected statement timeout, got {:?}", other),
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_limit_queries_without_a_deadline(app: &mut TestApp) {
+    let mut tx = begin_tenant_transaction(&app.pg_pool, &UserId::default())
+        .await
+        .expect("Failed to begin tenant transaction");
+
+    let result = sqlx::query("SELECT pg_sleep(0.2)").execute(&mut *tx).await;
+
+    assert!(result.is_ok());
+}
@@ -0,0 +1,386 @@
+use chrono::TimeDelta;
+use rota_manager::{
+    routes::{
+        me::{BulkReviewRotasResponse, PendingApprovalListResponse},
+        projects::{RotaApprovalResponse, RotaVersionResponse},
+    },
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, login, logout, TestApp,
+};
+
+// Ted works Mondays and Dougal Fridays. Returns the project and Ted.
+async fn set_up_rota(app: &mut TestApp) -> (String, String) {
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for (member_id, day) in [(&ted, "Monday"), (&dougal, "Friday")] {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": day,
+                "startTime": 540,
+                "endTime": 1020
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+    (project_id, ted)
+}
+
+// Signs up the approver and leaves the owner signed in with a project that
+// needs the approver's go-ahead. Returns the approver's and owner's emails,
+// the project and Ted.
+async fn set_up_approval(
+    app: &mut TestApp,
+) -> (String, String, String, String) {
+    let approver = get_session(app, false).await;
+    logout(app).await;
+    let owner = get_session(app, false).await;
+    let (project_id, ted) = set_up_rota(app).await;
+    let response = app
+        .put_project_settings(&project_id, &json!({"approverEmail": approver}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    (approver, owner, project_id, ted)
+}
+
+async fn queued_email(app: &TestApp, subject: &str) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT content FROM email_outbox WHERE subject = $1 ORDER BY id",
+    )
+    .bind(subject)
+    .fetch_all(&app.pg_pool)
+    .await
+    .expect("Failed to fetch queued email")
+}
+
+async fn published_versions(app: &TestApp, project_id: &str) -> i64 {
+    sqlx::query_scalar(
+        "SELECT count(*) FROM rota_versions WHERE project_id = $1::UUID",
+    )
+    .bind(project_id)
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to count published rotas")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_publish_rota_without_approver(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let (project_id, _) = set_up_rota(app).await;
+
+    let response = app
+        .post_publish_rota(&json!({
+            "projectId": &project_id,
+            "weekOf": "2025-10-29"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<RotaVersionResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["weekOf"], "2025-10-26");
+    assert_eq!(body["automatic"], false);
+    assert_eq!(body["shifts"].as_array().unwrap().len(), 2);
+    assert_eq!(published_versions(app, &project_id).await, 1);
+
+    let response = app
+        .post_submit_rota(&json!({"projectId": &project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: This project has no approver; publish the rota \
+         instead"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_publish_rota_with_problems(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let (project_id, _) = set_up_rota(app).await;
+    let response = app
+        .put_project_settings(&project_id, &json!({"minShiftLength": 600}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let response = app
+        .post_publish_rota(&json!({
+            "projectId": &project_id,
+            "weekOf": "2025-10-26"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: The rota can't be published yet: Dougal's Friday \
+         09:00–17:00 shift: Shift must be at least 600 minutes long; Ted's \
+         Monday 09:00–17:00 shift: Shift must be at least 600 minutes long"
+    );
+    assert_eq!(published_versions(app, &project_id).await, 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_publish_rota_once_approved(app: &mut TestApp) {
+    let (approver, owner, project_id, _) = set_up_approval(app).await;
+
+    // Publishing straight away isn't allowed any more
+    let request = json!({"projectId": &project_id, "weekOf": "2025-10-26"});
+    let response = app.post_publish_rota(&request).await;
+    assert_eq!(response.status().as_u16(), 400);
+
+    let response = app.post_submit_rota(&request).await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<RotaApprovalResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["status"], "pending");
+    assert_eq!(body["approverEmail"], approver.as_str());
+    assert_eq!(body["changes"]["added"].as_array().unwrap().len(), 2);
+    let approval_id = body["approvalId"].as_str().unwrap().to_owned();
+    assert_eq!(
+        queued_email(app, "A Craggy Island rota is waiting for your approval")
+            .await
+            .len(),
+        1
+    );
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let response = app.get_pending_approvals().await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<PendingApprovalListResponse>(),
+            &body
+        ),
+        "response does not match schema"
+    );
+    assert_eq!(body["approvals"][0]["approvalId"], approval_id.as_str());
+    assert_eq!(body["approvals"][0]["projectName"], "Craggy Island");
+
+    let response = app
+        .post_review_rota(&json!({
+            "approvalId": &approval_id,
+            "approve": true,
+            "comment": "Looks good"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["status"], "approved");
+    assert_eq!(body["comment"], "Looks good");
+    assert_eq!(published_versions(app, &project_id).await, 1);
+    assert_eq!(
+        queued_email(app, "Your Craggy Island rota was approved").await,
+        [
+            "The Craggy Island rota for the week of 2025-10-26 was approved \
+             and published.\n\nComment:\nLooks good"
+        ]
+    );
+
+    // It can only be reviewed once
+    let response = app
+        .post_review_rota(
+            &json!({"approvalId": &approval_id, "approve": false}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert!(
+        get_json_response_body(app.get_pending_approvals().await).await
+            ["approvals"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+
+    logout(app).await;
+    login(app, &owner, "password").await;
+    let history = get_json_response_body(
+        app.get_project_history(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(
+        history["entries"][1]["description"],
+        "The rota for the week of 2025-10-26 was approved: Looks good"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_compare_resubmitted_rota_with_published_one(app: &mut TestApp) {
+    let (approver, owner, project_id, ted) = set_up_approval(app).await;
+    let request = json!({"projectId": &project_id, "weekOf": "2025-10-26"});
+    let response = app.post_submit_rota(&request).await;
+    let first = get_json_response_body(response).await["approvalId"].clone();
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let response = app
+        .post_review_rota(&json!({"approvalId": &first, "approve": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    // Ted picks up a Tuesday, then the rota is submitted twice
+    logout(app).await;
+    login(app, &owner, "password").await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Tuesday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    app.clock.advance(TimeDelta::minutes(1));
+    let withdrawn =
+        get_json_response_body(app.post_submit_rota(&request).await).await
+            ["approvalId"]
+            .clone();
+    app.clock.advance(TimeDelta::minutes(1));
+    let response = app.post_submit_rota(&request).await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["changes"]["added"][0]["day"], "Tuesday");
+    assert!(body["changes"]["removed"].as_array().unwrap().is_empty());
+    assert!(body["changes"]["changed"].as_array().unwrap().is_empty());
+
+    let approvals =
+        get_json_response_body(app.get_rota_approvals(&project_id).await).await;
+    let statuses: Vec<_> = approvals["approvals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|approval| approval["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["pending", "withdrawn", "approved"]);
+
+    // The withdrawn one can't be approved, and it's rejected with a reason
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let response = app
+        .post_review_rota(&json!({"approvalId": &withdrawn, "approve": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    let response = app
+        .post_review_rota(&json!({
+            "approvalId": &body["approvalId"],
+            "approve": false,
+            "comment": "Ted can't do Tuesdays"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(published_versions(app, &project_id).await, 1);
+    assert_eq!(
+        queued_email(app, "Your Craggy Island rota was rejected").await,
+        [
+            "The Craggy Island rota for the week of 2025-10-26 was rejected.\
+             \n\nComment:\nTed can't do Tuesdays"
+        ]
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_review_several_rotas_at_once(app: &mut TestApp) {
+    let (approver, _, project_id, _) = set_up_approval(app).await;
+    let mut approval_ids = Vec::new();
+    for week_of in ["2025-10-26", "2025-11-02"] {
+        let response = app
+            .post_submit_rota(
+                &json!({"projectId": &project_id, "weekOf": week_of}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+        approval_ids
+            .push(get_json_response_body(response).await["approvalId"].clone());
+    }
+    let unknown = uuid::Uuid::new_v4().to_string();
+
+    logout(app).await;
+    login(app, &approver, "password").await;
+    let response = app
+        .post_bulk_review_rotas(&json!({
+            "approvalIds": [
+                &approval_ids[0], &approval_ids[1], &unknown, &approval_ids[0]
+            ],
+            "approve": true,
+            "comment": "All fine"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<BulkReviewRotasResponse>(), &body),
+        "response does not match schema"
+    );
+    let results = body["results"].as_array().unwrap();
+    assert_eq!(results.len(), 4);
+    for (result, approval_id) in results[..2].iter().zip(&approval_ids) {
+        assert_eq!(result["approvalId"], *approval_id);
+        assert_eq!(result["approval"]["status"], "approved");
+        assert_eq!(result["approval"]["comment"], "All fine");
+    }
+    assert_eq!(results[2]["approvalId"], unknown.as_str());
+    assert_eq!(results[2]["error"], "Approval ID not found");
+    assert_eq!(
+        results[3]["error"],
+        "This rota is no longer waiting for approval"
+    );
+    assert_eq!(published_versions(app, &project_id).await, 2);
+    assert_eq!(
+        queued_email(app, "Your Craggy Island rota was approved")
+            .await
+            .len(),
+        2
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_empty_bulk_review(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app
+        .post_bulk_review_rotas(&json!({"approvalIds": [], "approve": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_someone_elses_approval(app: &mut TestApp) {
+    let (_, _, project_id, _) = set_up_approval(app).await;
+    let response = app
+        .post_submit_rota(&json!({"projectId": &project_id}))
+        .await;
+    let approval_id =
+        get_json_response_body(response).await["approvalId"].clone();
+
+    // Not even the owner can approve their own rota
+    let response = app
+        .post_review_rota(&json!({"approvalId": &approval_id, "approve": true}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+    assert!(
+        get_json_response_body(app.get_pending_approvals().await).await
+            ["approvals"]
+            .as_array()
+            .unwrap()
+            .is_empty()
+    );
+}
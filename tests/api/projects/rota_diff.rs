@@ -0,0 +1,124 @@
+use rota_manager::routes::projects::RotaDiffResponse;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+async fn add_shift(app: &TestApp, member_id: &str, day: &str) -> String {
+    let response = app
+        .post_shift(&json!({
+            "memberId": member_id,
+            "day": day,
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+async fn publish(app: &TestApp, project_id: &str) -> String {
+    let response = app
+        .post_publish_rota(&json!({"projectId": project_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["versionId"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_compare_draft_with_published_rota(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    // Nothing published yet, so every shift is new
+    add_shift(app, &ted, "Monday").await;
+    let response = app.get_rota_diff(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<RotaDiffResponse>(), &body),
+        "response does not match schema"
+    );
+    assert!(body["from"].is_null());
+    assert!(body["to"].is_null());
+    assert_eq!(body["changes"]["added"][0]["day"], "Monday");
+
+    let first = publish(app, &project_id).await;
+    let body = get_json_response_body(
+        app.get_rota_diff(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(body["from"], first.as_str());
+    assert_eq!(
+        body["changes"],
+        json!({"added": [], "removed": [], "changed": []})
+    );
+
+    add_shift(app, &ted, "Tuesday").await;
+    let second = publish(app, &project_id).await;
+    let body = get_json_response_body(
+        app.get_rota_diff(&[
+            ("projectId", &project_id),
+            ("from", &second),
+            ("to", &first),
+        ])
+        .await,
+    )
+    .await;
+    assert_eq!(body["from"], second.as_str());
+    assert_eq!(body["to"], first.as_str());
+    assert!(body["changes"]["added"].as_array().unwrap().is_empty());
+    assert_eq!(body["changes"]["removed"][0]["day"], "Tuesday");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_show_changed_shifts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    add_shift(app, &ted, "Monday").await;
+    publish(app, &project_id).await;
+
+    let response = app
+        .put_member(&ted, &json!({"memberName": "Father Ted"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(
+        app.get_rota_diff(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    let changed = &body["changes"]["changed"][0];
+    assert_eq!(changed["before"]["memberName"], "Ted");
+    assert_eq!(changed["after"]["memberName"], "Father Ted");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_unknown_version(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let version_id = uuid::Uuid::new_v4().to_string();
+
+    let response = app
+        .get_rota_diff(&[("projectId", &project_id), ("from", &version_id)])
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+
+    // Someone else's project
+    let _email = get_session(app, false).await;
+    let response = app.get_rota_diff(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,93 @@
+use rota_manager::{
+    domain::{Member, MemberId, MemberName, ProjectId, ProjectName, UserId},
+    services::data_stores::begin_tenant_transaction,
+};
+use test_context::test_context;
+
+use crate::helpers::TestApp;
+
+// These queries deliberately omit any ownership filter. Row-level security
+// must still restrict them to the tenant set on the transaction.
+async fn count_visible_rows(app: &TestApp, user_id: &UserId) -> (i64, i64) {
+    let mut tx = begin_tenant_transaction(&app.pg_pool, user_id)
+        .await
+        .expect("Failed to begin tenant transaction");
+
+    let projects: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM projects_list")
+            .fetch_one(&mut *tx)
+            .await
+            .expect("Failed to count projects");
+    let members: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM members")
+        .fetch_one(&mut *tx)
+        .await
+        .expect("Failed to count members");
+
+    (projects, members)
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_expose_rows_owned_by_tenant(app: &mut TestApp) {
+    let owner = UserId::default();
+    let other = UserId::default();
+    let project_id = ProjectId::default();
+
+    {
+        let mut project_store = app.project_store.write().await;
+        project_store
+            .add_project(
+                &owner,
+                &project_id,
+                &ProjectName::parse("Craggy Island").unwrap(),
+            )
+            .await
+            .expect("Failed to add project");
+        project_store
+            .add_member(
+                &owner,
+                &Member::new(
+                    project_id.clone(),
+                    MemberId::default(),
+                    MemberName::parse("Ted".to_owned()).unwrap(),
+                ),
+            )
+            .await
+            .expect("Failed to add member");
+    }
+
+    assert_eq!(count_visible_rows(app, &owner).await, (1, 1));
+    assert_eq!(count_visible_rows(app, &other).await, (0, 0));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_writes_into_another_tenants_project(app: &mut TestApp) {
+    let owner = UserId::default();
+    let other = UserId::default();
+    let project_id = ProjectId::default();
+
+    app.project_store
+        .write()
+        .await
+        .add_project(
+            &owner,
+            &project_id,
+            &ProjectName::parse("Craggy Island").unwrap(),
+        )
+        .await
+        .expect("Failed to add project");
+
+    let mut tx = begin_tenant_transaction(&app.pg_pool, &other)
+        .await
+        .expect("Failed to begin tenant transaction");
+    let result = sqlx::query(
+        "INSERT INTO members (member_id, project_id, member_name) \
+         VALUES (gen_random_uuid(), $1, 'Dougal')",
+    )
+    .bind(project_id.as_ref())
+    .execute(&mut *tx)
+    .await;
+
+    assert!(result.is_err(), "RLS should reject the insert");
+}
@@ -0,0 +1,442 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+use rota_manager::{routes::projects::ProjectSettingsResponse, ErrorResponse};
+use serde_json::{json, Value};
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_default_settings(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app.get_project_settings(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectSettingsResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body,
+        json!({
+            "projectId": &project_id,
+            "shiftGranularity": 1,
+            "minShiftLength": 1,
+            "maxShiftLength": 1440,
+            "breakRules": [],
+            "weekStartsOn": "Sunday",
+            "escalationSteps": [],
+            "chatWebhookUrl": null,
+            "escalationPhone": null,
+            "autoPublish": null,
+            "approverEmail": null
+        })
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_update_shift_granularity(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app
+        .put_project_settings(&project_id, &json!({"shiftGranularity": 15}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body =
+        get_json_response_body(app.get_project_settings(&project_id).await)
+            .await;
+    assert_eq!(body["shiftGranularity"], 15);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_granularity(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    for granularity in [0, 7, 1441] {
+        let response = app
+            .put_project_settings(
+                &project_id,
+                &json!({"shiftGranularity": granularity}),
+            )
+            .await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Should fail for granularity {}",
+            granularity
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let _email = get_session(app, false).await;
+    assert_eq!(app.get_project_settings(&project_id).await.status(), 404);
+    assert_eq!(
+        app.put_project_settings(&project_id, &json!({"shiftGranularity": 15}))
+            .await
+            .status(),
+        404
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_enforce_granularity_when_adding_shifts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    app.put_project_settings(&project_id, &json!({"shiftGranularity": 15}))
+        .await;
+
+    let shift = json!({
+        "memberId": &member_id,
+        "day": "Monday",
+        "startTime": 541,
+        "endTime": 1020
+    });
+
+    let response = app.post_shift(&shift).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Start time must be a multiple of 15 minutes"
+    );
+
+    let response = app
+        .http_client
+        .post(format!("{}/projects/shifts", &app.address))
+        .query(&[("snap", "true")])
+        .json(&shift)
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 201);
+
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["startTime"], 540);
+    assert_eq!(body["endTime"], 1020);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_enforce_shift_length_limits(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"minShiftLength": 60, "maxShiftLength": 600}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let test_cases = [
+        (
+            540,
+            550,
+            "Validation error: Shift must be at least 60 minutes long",
+        ),
+        (
+            0,
+            960,
+            "Validation error: Shift cannot be longer than 600 minutes",
+        ),
+    ];
+
+    for (start_time, end_time, expected_error) in test_cases {
+        let response = app
+            .post_shift(&json!({
+                "memberId": &member_id,
+                "day": "Monday",
+                "startTime": start_time,
+                "endTime": end_time
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 400);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            expected_error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_if_min_length_above_max(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        json!({"minShiftLength": 600, "maxShiftLength": 60}),
+        json!({"maxShiftLength": 0}),
+        json!({"minShiftLength": 1441}),
+    ];
+
+    for body in test_cases.iter() {
+        let response = app.put_project_settings(&project_id, body).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Should fail for settings {}",
+            body
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_update_escalation_settings(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let escalation = json!({
+        "escalationSteps": [
+            {"channel": "email", "hoursBeforeStart": 12},
+            {"channel": "chat", "hoursBeforeStart": 3},
+            {"channel": "ownerPhone", "hoursBeforeStart": 1}
+        ],
+        "chatWebhookUrl": "https://hooks.example.com/rota",
+        "escalationPhone": "+44 7700 900999"
+    });
+    let response = app.put_project_settings(&project_id, &escalation).await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body =
+        get_json_response_body(app.get_project_settings(&project_id).await)
+            .await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ProjectSettingsResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["escalationSteps"], escalation["escalationSteps"]);
+    assert_eq!(body["chatWebhookUrl"], "https://hooks.example.com/rota");
+    assert_eq!(body["escalationPhone"], "+447700900999");
+
+    // Turning escalation off lets the contacts be removed
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({
+                "escalationSteps": [],
+                "chatWebhookUrl": null,
+                "escalationPhone": null
+            }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["escalationSteps"], json!([]));
+    assert!(body["chatWebhookUrl"].is_null());
+    assert!(body["escalationPhone"].is_null());
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_escalation_settings(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        (
+            json!({"escalationSteps": [{"channel": "email", "hoursBeforeStart": 0}]}),
+            "Validation error: Escalation steps must be between 1 and 168 \
+             hours before the shift",
+        ),
+        (
+            json!({"escalationSteps": [
+                {"channel": "sms", "hoursBeforeStart": 6},
+                {"channel": "sms", "hoursBeforeStart": 2}
+            ]}),
+            "Validation error: Each escalation channel can only be used once",
+        ),
+        (
+            json!({"escalationSteps": [{"channel": "chat", "hoursBeforeStart": 3}]}),
+            "Validation error: Escalating to chat needs a chat webhook URL",
+        ),
+        (
+            json!({"escalationSteps": [
+                {"channel": "ownerPhone", "hoursBeforeStart": 1}
+            ]}),
+            "Validation error: Escalating to the owner's phone needs an \
+             escalation phone",
+        ),
+        (
+            json!({"chatWebhookUrl": "not a url"}),
+            "Validation error: Chat webhook URL must be an http or https URL",
+        ),
+    ];
+
+    for (body, expected_error) in test_cases.iter() {
+        let response = app.put_project_settings(&project_id, body).await;
+        assert_eq!(
+            response.status().as_u16(),
+            400,
+            "Should fail for settings {}",
+            body
+        );
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            *expected_error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_update_auto_publish_schedule(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app
+        .put_project_settings(
+            &project_id,
+            &json!({"autoPublish": "0 17 * * FRI"}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body =
+        get_json_response_body(app.get_project_settings(&project_id).await)
+            .await;
+    assert_eq!(body["autoPublish"], "0 17 * * FRI");
+
+    let response = app
+        .put_project_settings(&project_id, &json!({"autoPublish": null}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert!(get_json_response_body(response).await["autoPublish"].is_null());
+
+    for (schedule, expected_error) in [
+        (
+            "0 17 * *",
+            "Validation error: Schedule must have five fields: minute, hour, \
+             day of the month, month and day of the week",
+        ),
+        (
+            "0 17 * * FUN",
+            "Validation error: Invalid day of the week in schedule: FUN",
+        ),
+        ("0 0 30 2 *", "Validation error: Schedule never runs"),
+    ] {
+        let response = app
+            .put_project_settings(
+                &project_id,
+                &json!({"autoPublish": schedule}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "{}", schedule);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            expected_error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_order_shifts_from_week_start(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    for (day, start_time) in [
+        ("Wednesday", 600),
+        ("Sunday", 540),
+        ("Monday", 540),
+        ("Wednesday", 480),
+    ] {
+        let response = app
+            .post_shift(&json!({
+                "memberId": &member_id,
+                "day": day,
+                "startTime": start_time,
+                "endTime": start_time + 60
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+    let shift_order = |project: &Value| -> Vec<(String, i64)> {
+        project["members"][0]["shifts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|shift| {
+                (
+                    shift["day"].as_str().unwrap().to_owned(),
+                    shift["startTime"].as_i64().unwrap(),
+                )
+            })
+            .collect()
+    };
+    let expected = |days: [(&str, i64); 4]| -> Vec<(String, i64)> {
+        days.iter()
+            .map(|(day, start)| (day.to_string(), *start))
+            .collect()
+    };
+
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    assert_eq!(project["weekStartsOn"], "Sunday");
+    assert_eq!(
+        shift_order(&project),
+        expected([
+            ("Sunday", 540),
+            ("Monday", 540),
+            ("Wednesday", 480),
+            ("Wednesday", 600)
+        ])
+    );
+
+    let response = app
+        .put_project_settings(&project_id, &json!({"weekStartsOn": "Tuesday"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        get_json_response_body(response).await["weekStartsOn"],
+        "Tuesday"
+    );
+
+    // Sunday and Monday wrap round to the end of a Tuesday week
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    assert_eq!(project["weekStartsOn"], "Tuesday");
+    assert_eq!(
+        shift_order(&project),
+        expected([
+            ("Wednesday", 480),
+            ("Wednesday", 600),
+            ("Sunday", 540),
+            ("Monday", 540)
+        ])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_week_start(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let response = app
+        .put_project_settings(&project_id, &json!({"weekStartsOn": "Caturday"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Invalid day"
+    );
+}
@@ -0,0 +1,264 @@
+use chrono::{NaiveDate, NaiveDateTime};
+use reqwest::Client;
+use rota_manager::{
+    domain::ResidencyRules,
+    services::shift_escalations::escalate_uncovered_shifts,
+};
+use serde_json::json;
+use test_context::test_context;
+use wiremock::{
+    matchers::{body_json, header, method, path},
+    Mock, MockServer, ResponseTemplate,
+};
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_session, TestApp,
+};
+
+const TED: &str = "+447700900123";
+const DOUGAL: &str = "+447700900456";
+const OWNER: &str = "+447700900999";
+
+// Ted's Monday 09:00 shift, with Dougal on the team. Both take texts.
+async fn set_up_rota(app: &mut TestApp, tags: &[&str]) -> (String, String) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for (member_id, number) in [(&ted, TED), (&dougal, DOUGAL)] {
+        app.put_member_contact(
+            member_id,
+            &json!({"phoneNumber": number, "smsOptIn": true}),
+        )
+        .await;
+    }
+
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020,
+            "tags": tags
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let shift_id = get_json_response_body(response).await["id"]
+        .as_str()
+        .unwrap()
+        .to_owned();
+
+    (project_id, shift_id)
+}
+
+async fn escalate_all_channels(
+    app: &TestApp,
+    project_id: &str,
+    chat_server: &MockServer,
+) {
+    let response = app
+        .put_project_settings(
+            project_id,
+            &json!({
+                "escalationSteps": [
+                    {"channel": "email", "hoursBeforeStart": 12},
+                    {"channel": "sms", "hoursBeforeStart": 6},
+                    {"channel": "chat", "hoursBeforeStart": 3},
+                    {"channel": "ownerPhone", "hoursBeforeStart": 1}
+                ],
+                "chatWebhookUrl": format!("{}/hook", chat_server.uri()),
+                "escalationPhone": OWNER
+            }),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+}
+
+// `hours` before Ted's shift on Monday 20 October 2025
+fn hours_before(hours: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2025, 10, 20)
+        .unwrap()
+        .and_hms_opt(9, 0, 0)
+        .unwrap()
+        - chrono::TimeDelta::hours(hours.into())
+}
+
+async fn escalate(app: &TestApp, now: NaiveDateTime) -> usize {
+    escalate_with_rules(app, &ResidencyRules::default(), now).await
+}
+
+async fn escalate_with_rules(
+    app: &TestApp,
+    residency_rules: &ResidencyRules,
+    now: NaiveDateTime,
+) -> usize {
+    escalate_uncovered_shifts(
+        &app.pg_pool,
+        &Client::new(),
+        residency_rules,
+        now,
+    )
+    .await
+    .expect("Failed to escalate shifts")
+}
+
+async fn queued_sms(app: &TestApp) -> Vec<String> {
+    sqlx::query_scalar("SELECT recipient FROM sms_outbox ORDER BY recipient")
+        .fetch_all(&app.pg_pool)
+        .await
+        .expect("Failed to fetch queued SMS")
+}
+
+async fn queued_email_subjects(app: &TestApp) -> Vec<String> {
+    sqlx::query_scalar(
+        "SELECT subject FROM email_outbox WHERE subject LIKE 'A critical%'",
+    )
+    .fetch_all(&app.pg_pool)
+    .await
+    .expect("Failed to fetch queued email")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_escalate_unconfirmed_critical_shift(app: &mut TestApp) {
+    let (project_id, _) = set_up_rota(app, &["Critical"]).await;
+    let chat_server = MockServer::start().await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    Mock::given(path("/hook"))
+        .and(method("POST"))
+        .and(body_json(json!({
+            "text": "Critical shift not confirmed: Ted hasn't confirmed the \
+                     Craggy Island shift on Monday 20/10/2025 at 09:00."
+        })))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&chat_server)
+        .await;
+
+    assert_eq!(escalate(app, hours_before(13)).await, 0);
+
+    assert_eq!(escalate(app, hours_before(12)).await, 1);
+    assert_eq!(
+        queued_email_subjects(app).await,
+        vec!["A critical Craggy Island shift needs cover"]
+    );
+    // Each step is only taken once
+    assert_eq!(escalate(app, hours_before(11)).await, 0);
+
+    // Ted hasn't confirmed, so he's texted along with the rest of the team
+    assert_eq!(escalate(app, hours_before(6)).await, 1);
+    assert_eq!(queued_sms(app).await, vec![TED, DOUGAL]);
+
+    assert_eq!(escalate(app, hours_before(3)).await, 1);
+
+    assert_eq!(escalate(app, hours_before(1)).await, 1);
+    assert_eq!(queued_sms(app).await, vec![TED, DOUGAL, OWNER]);
+
+    assert_eq!(escalate(app, hours_before(0)).await, 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_escalate_open_shift_to_rest_of_team(app: &mut TestApp) {
+    let (project_id, shift_id) = set_up_rota(app, &["critical"]).await;
+    let chat_server = MockServer::start().await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    let response = app
+        .post_absence(&json!({"shiftId": &shift_id, "date": "2025-10-20"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    // Steps that came due while nobody was looking are all taken at once
+    Mock::given(path("/hook"))
+        .and(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&chat_server)
+        .await;
+    assert_eq!(escalate(app, hours_before(2)).await, 3);
+
+    assert_eq!(queued_sms(app).await, vec![DOUGAL]);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_retry_failed_chat_escalation(app: &mut TestApp) {
+    let (project_id, _) = set_up_rota(app, &["critical"]).await;
+    let chat_server = MockServer::start().await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    assert_eq!(escalate(app, hours_before(4)).await, 2);
+
+    Mock::given(path("/hook"))
+        .respond_with(ResponseTemplate::new(500))
+        .up_to_n_times(1)
+        .expect(1)
+        .mount(&chat_server)
+        .await;
+    assert_eq!(escalate(app, hours_before(3)).await, 0);
+
+    Mock::given(path("/hook"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&chat_server)
+        .await;
+    assert_eq!(escalate(app, hours_before(3)).await, 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_only_post_to_chat_within_data_region(app: &mut TestApp) {
+    let (project_id, _) = set_up_rota(app, &["critical"]).await;
+    let chat_server = MockServer::start().await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    sqlx::query("UPDATE users SET data_region = 'eu'")
+        .execute(&app.pg_pool)
+        .await
+        .expect("Failed to set data region");
+    assert_eq!(escalate(app, hours_before(4)).await, 2);
+
+    Mock::given(path("/hook"))
+        .and(header("X-Data-Region", "eu"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(1)
+        .mount(&chat_server)
+        .await;
+
+    let elsewhere = ResidencyRules::parse("eu=chat.example.eu").unwrap();
+    assert_eq!(
+        escalate_with_rules(app, &elsewhere, hours_before(3)).await,
+        0
+    );
+
+    let here = ResidencyRules::parse("eu=127.0.0.1").unwrap();
+    assert_eq!(escalate_with_rules(app, &here, hours_before(3)).await, 1);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_not_escalate_covered_shifts(app: &mut TestApp) {
+    let chat_server = MockServer::start().await;
+
+    // Not tagged critical
+    let (project_id, _) = set_up_rota(app, &["training"]).await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    assert_eq!(escalate(app, hours_before(1)).await, 0);
+
+    // Confirmed by Ted
+    let (project_id, shift_id) = set_up_rota(app, &["critical"]).await;
+    escalate_all_channels(app, &project_id, &chat_server).await;
+    sqlx::query(
+        "INSERT INTO shift_reminders (shift_id, shift_date, confirmed_at) \
+         VALUES ($1::uuid, '2025-10-20', now())",
+    )
+    .bind(&shift_id)
+    .execute(&app.pg_pool)
+    .await
+    .expect("Failed to confirm shift");
+    assert_eq!(escalate(app, hours_before(1)).await, 0);
+
+    // Critical but escalation is off
+    let (_, _) = set_up_rota(app, &["critical"]).await;
+    assert_eq!(escalate(app, hours_before(1)).await, 0);
+
+    assert!(queued_sms(app).await.is_empty());
+}
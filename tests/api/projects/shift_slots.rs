@@ -0,0 +1,145 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    routes::projects::{
+        AddShiftResponse, ShiftSlotListResponse, ShiftSlotResponse,
+    },
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+
+async fn add_slot(app: &TestApp, project_id: &str, places: i16) -> String {
+    let response = app
+        .post_shift_slot(&json!({
+            "projectId": project_id,
+            "day": "Saturday",
+            "startTime": 540,
+            "endTime": 1020,
+            "places": places,
+            "tag": "barista"
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ShiftSlotResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["openPlaces"], places);
+    body["slotId"].as_str().unwrap().to_owned()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_fill_places_until_slot_is_covered(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    let jack = add_member(app, "Jack", &project_id).await;
+    let slot_id = add_slot(app, &project_id, 2).await;
+
+    for member_id in [&ted, &dougal] {
+        let response = app
+            .post_fill_shift_slot(
+                &json!({"slotId": &slot_id, "memberId": member_id}),
+            )
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+        let body = get_json_response_body(response).await;
+        assert!(
+            jsonschema::is_valid(&get_schema::<AddShiftResponse>(), &body),
+            "response does not match schema"
+        );
+        assert_eq!(body["memberId"], member_id.as_str());
+        assert_eq!(body["tags"], json!(["barista"]));
+    }
+
+    let response = app
+        .post_fill_shift_slot(&json!({"slotId": &slot_id, "memberId": &jack}))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Every place in the slot is filled"
+    );
+
+    let response = app.get_shift_slots(&project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ShiftSlotListResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["uncovered"], 0);
+    assert_eq!(body["slots"][0]["openPlaces"], 0);
+    assert_eq!(body["slots"][0]["assignedMemberIds"], json!([ted, dougal]));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_when_member_already_in_slot(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let slot_id = add_slot(app, &project_id, 3).await;
+
+    let body = json!({"slotId": &slot_id, "memberId": &ted});
+    let response = app.post_fill_shift_slot(&body).await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app.post_fill_shift_slot(&body).await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: The member already has a place in the slot"
+    );
+
+    let body =
+        get_json_response_body(app.get_shift_slots(&project_id).await).await;
+    assert_eq!(body["uncovered"], 1);
+    assert_eq!(body["slots"][0]["openPlaces"], 2);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_slots(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    for places in [0, 51] {
+        let response = app
+            .post_shift_slot(&json!({
+                "projectId": &project_id,
+                "day": "Saturday",
+                "startTime": 540,
+                "endTime": 1020,
+                "places": places
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", places);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            "Validation error: A slot must need between 1 and 50 people"
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_unknown_slot(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let response = app
+        .post_fill_shift_slot(&json!({
+            "slotId": uuid::Uuid::new_v4(),
+            "memberId": &ted
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
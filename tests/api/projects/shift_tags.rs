@@ -0,0 +1,124 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_session, TestApp,
+};
+use rota_manager::ErrorResponse;
+use serde_json::{json, Value};
+use test_context::test_context;
+
+async fn add_tagged_shift(
+    app: &mut TestApp,
+    member_id: &str,
+    day: &str,
+    tags: Value,
+) -> reqwest::Response {
+    app.post_shift(&json!({
+        "memberId": member_id,
+        "day": day,
+        "startTime": 540,
+        "endTime": 1020,
+        "tags": tags
+    }))
+    .await
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_save_normalised_tags(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let response =
+        add_tagged_shift(app, &ted, "Monday", json!(["Training", " bar "]))
+            .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["tags"], json!(["bar", "training"]));
+
+    let project =
+        get_json_response_body(app.get_project(&project_id).await).await;
+    assert_eq!(
+        project["members"][0]["shifts"][0]["tags"],
+        json!(["bar", "training"])
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_tags(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let too_many: Vec<String> = (0..11).map(|i| format!("tag {}", i)).collect();
+    let test_cases = [
+        (json!([""]), "Validation error: Tag cannot be empty"),
+        (
+            json!(["drop table;"]),
+            "Validation error: Tags can only contain letters, numbers, \
+             spaces, hyphens and underscores",
+        ),
+        (
+            json!(too_many),
+            "Validation error: A shift cannot have more than 10 tags",
+        ),
+    ];
+
+    for (tags, error) in test_cases {
+        let response = add_tagged_shift(app, &ted, "Monday", tags).await;
+        assert_eq!(response.status().as_u16(), 400);
+        assert_eq!(
+            response.json::<ErrorResponse>().await.unwrap().error,
+            error
+        );
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_filter_project_by_tag(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    add_tagged_shift(app, &ted, "Monday", json!(["training"])).await;
+    add_tagged_shift(app, &ted, "Tuesday", json!([])).await;
+
+    let response = app
+        .http_client
+        .get(format!("{}/projects/project", &app.address))
+        .query(&[("projectId", project_id.as_str()), ("tag", "Training")])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 200);
+
+    let project = get_json_response_body(response).await;
+    let shifts = project["members"][0]["shifts"].as_array().unwrap();
+    assert_eq!(shifts.len(), 1);
+    assert_eq!(shifts[0]["day"], "Monday");
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_total_hours_by_tag_in_reports(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    add_tagged_shift(app, &ted, "Monday", json!(["training"])).await;
+    add_tagged_shift(app, &ted, "Tuesday", json!(["training", "bar"])).await;
+
+    // Monday 20 to Sunday 26 October 2025
+    let report = get_json_response_body(
+        app.get_attendance_report(&[
+            ("projectId", &project_id),
+            ("from", "2025-10-20"),
+            ("to", "2025-10-26"),
+        ])
+        .await,
+    )
+    .await;
+    assert_eq!(
+        report["members"][0]["tagMinutes"],
+        json!({"bar": 480, "training": 960})
+    );
+}
@@ -1,9 +1,10 @@
 use crate::helpers::{
-    add_member, add_new_project, get_json_response_body, get_session, logout,
-    TestApp,
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+use rota_manager::{
+    routes::projects::UpdateMemberResponse, ErrorResponse, FieldError,
 };
-use rota_manager::ErrorResponse;
-use serde_json::json;
 use test_context::test_context;
 
 #[test_context(TestApp)]
@@ -13,33 +14,7 @@ async fn should_return_200_for_valid_requests(app: &mut TestApp) {
     let project_id = add_new_project(app, "Craggy Island").await;
     let member_id = add_member(app, "Ted", &project_id).await;
 
-    let schema = json!({
-      "$schema": "http://json-schema.org/draft-04/schema#",
-      "description": "",
-      "type": "object",
-      "properties": {
-        "memberName": {
-          "type": "string",
-          "minLength": 1,
-          "maxLength": 255
-        },
-        "memberId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        },
-        "projectId": {
-          "type": "string",
-          "minLength": 36,
-          "maxLength": 36
-        }
-      },
-      "required": [
-        "memberName",
-        "memberId",
-        "projectId"
-      ]
-    });
+    let schema = get_schema::<UpdateMemberResponse>();
 
     let response = app
         .put_member(
@@ -166,10 +141,17 @@ async fn should_return_400_if_invalid_query_param(app: &mut TestApp) {
         response
     );
 
-    let response_body = response.text().await.unwrap();
+    let error = response
+        .json::<ErrorResponse>()
+        .await
+        .expect("Could not deserialise response body to ErrorResponse");
+    assert_eq!(error.code, "invalid_query");
     assert_eq!(
-        response_body,
-        "Failed to deserialize query string: UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `o` at 2"
+        error.details,
+        vec![FieldError {
+            field: "memberId".to_owned(),
+            message: "UUID parsing failed: invalid character: expected an optional prefix of `urn:uuid:` followed by [0-9a-fA-F-], found `o` at 2".to_owned(),
+        }]
     );
 }
 
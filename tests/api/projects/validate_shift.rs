@@ -0,0 +1,111 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+
+use rota_manager::routes::projects::ValidateShiftResponse;
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_valid_shift_without_saving(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+
+    let response = app
+        .post_validate_shift(&json!({
+            "memberId": &member_id,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ValidateShiftResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body,
+        json!({
+            "valid": true,
+            "error": null,
+            "startTime": 540,
+            "endTime": 1020,
+            "breaks": []
+        })
+    );
+
+    let shifts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM shifts")
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("Failed to count shifts");
+    assert_eq!(shifts, 0);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_report_rule_violations(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let member_id = add_member(app, "Ted", &project_id).await;
+    app.put_project_settings(
+        &project_id,
+        &json!({"shiftGranularity": 15, "maxShiftLength": 600}),
+    )
+    .await;
+
+    let test_cases = [
+        (
+            541,
+            600,
+            "Validation error: Start time must be a multiple of 15 minutes",
+        ),
+        (
+            0,
+            960,
+            "Validation error: Shift cannot be longer than 600 minutes",
+        ),
+        (
+            600,
+            540,
+            "Validation error: Start time must be before end time",
+        ),
+    ];
+
+    for (start_time, end_time, expected_error) in test_cases {
+        let response = app
+            .post_validate_shift(&json!({
+                "memberId": &member_id,
+                "day": "Monday",
+                "startTime": start_time,
+                "endTime": end_time
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 200);
+
+        let body = get_json_response_body(response).await;
+        assert_eq!(body["valid"], false);
+        assert_eq!(body["error"], expected_error);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_unknown_member(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+
+    let response = app
+        .post_validate_shift(&json!({
+            "memberId": uuid::Uuid::new_v4(),
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
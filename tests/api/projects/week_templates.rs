@@ -0,0 +1,315 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, logout, TestApp,
+};
+use rota_manager::{
+    routes::projects::{
+        ApplyWeekTemplateResponse, WeekTemplateListResponse,
+        WeekTemplateResponse,
+    },
+    ErrorResponse,
+};
+use serde_json::json;
+use test_context::test_context;
+use uuid::Uuid;
+
+struct CraggyIsland {
+    project_id: String,
+    ted: String,
+    dougal: String,
+}
+
+// Ted works Monday and Tuesday mornings, and Dougal Monday afternoon
+async fn add_craggy_island(app: &mut TestApp) -> CraggyIsland {
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    for (member_id, day, start_time) in [
+        (&ted, "Monday", 540),
+        (&ted, "Tuesday", 540),
+        (&dougal, "Monday", 780),
+    ] {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": day,
+                "startTime": start_time,
+                "endTime": start_time + 240,
+                "note": "Bring the float"
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+    CraggyIsland {
+        project_id,
+        ted,
+        dougal,
+    }
+}
+
+async fn save_template(app: &mut TestApp, project_id: &str) -> String {
+    let response = app
+        .post_week_template(&json!({"projectId": project_id, "name": "Usual"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["templateId"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+// (member ID, day, start time, note) for each of the project's shifts
+async fn shifts(
+    app: &mut TestApp,
+    project_id: &str,
+) -> Vec<(String, String, i64, String)> {
+    let project =
+        get_json_response_body(app.get_project(project_id).await).await;
+    let mut shifts: Vec<_> = project["members"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|member| {
+            member["shifts"].as_array().unwrap().iter().map(|shift| {
+                (
+                    member["memberId"].as_str().unwrap().to_owned(),
+                    shift["day"].as_str().unwrap().to_owned(),
+                    shift["startTime"].as_i64().unwrap(),
+                    shift["note"].as_str().unwrap_or_default().to_owned(),
+                )
+            })
+        })
+        .collect();
+    shifts.sort();
+    shifts
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_save_and_list_templates(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+
+    let response = app
+        .post_week_template(
+            &json!({"projectId": project_id, "name": " Usual "}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<WeekTemplateResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["name"], "Usual");
+    assert_eq!(body["shiftCount"], 3);
+
+    let response = app
+        .post_week_template(&json!({"projectId": project_id, "name": "Lent"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    // Saving under the same name again replaces the template
+    app.post_clear_shifts(&json!({"projectId": project_id, "day": "Monday"}))
+        .await;
+    let response = app
+        .post_week_template(&json!({"projectId": project_id, "name": "Usual"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    assert_eq!(
+        get_json_response_body(response).await["templateId"],
+        body["templateId"]
+    );
+
+    let response = app.get_week_templates(project_id).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let list = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<WeekTemplateListResponse>(), &list),
+        "response does not match schema"
+    );
+    let templates: Vec<(&str, u64)> = list["templates"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|template| {
+            (
+                template["name"].as_str().unwrap(),
+                template["shiftCount"].as_u64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(templates, [("Lent", 3), ("Usual", 1)]);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_apply_a_template_in_place_of_the_current_week(
+    app: &mut TestApp,
+) {
+    let email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+    let template_id = save_template(app, project_id).await;
+    let saved = shifts(app, project_id).await;
+
+    app.post_clear_shifts(&json!({"projectId": project_id, "day": "Tuesday"}))
+        .await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &craggy_island.dougal,
+            "day": "Friday",
+            "startTime": 540,
+            "endTime": 780
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let response = app
+        .post_apply_week_template(&json!({"templateId": &template_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<ApplyWeekTemplateResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(
+        body,
+        json!({"templateId": &template_id, "shifts": 3, "replaced": 3})
+    );
+    assert_eq!(shifts(app, project_id).await, saved);
+
+    let history = get_json_response_body(
+        app.get_project_history(&[("projectId", project_id)]).await,
+    )
+    .await;
+    assert_eq!(
+        history["entries"][0]["description"],
+        format!(
+            "{} applied template Usual, adding 3 shifts in place of 3 shifts",
+            email
+        )
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_map_members_when_applying(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+    let template_id = save_template(app, project_id).await;
+    app.post_clear_shifts(&json!({"projectId": project_id}))
+        .await;
+
+    // Dougal covers Ted's shifts as well as his own
+    let response = app
+        .post_apply_week_template(&json!({
+            "templateId": &template_id,
+            "members": {&craggy_island.ted: &craggy_island.dougal}
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let days: Vec<(String, String)> = shifts(app, project_id)
+        .await
+        .into_iter()
+        .map(|(member_id, day, _, _)| (member_id, day))
+        .collect();
+    assert_eq!(
+        days,
+        [
+            (craggy_island.dougal.clone(), "Monday".to_owned()),
+            (craggy_island.dougal.clone(), "Monday".to_owned()),
+            (craggy_island.dougal.clone(), "Tuesday".to_owned()),
+        ]
+    );
+
+    let response = app
+        .post_apply_week_template(&json!({
+            "templateId": &template_id,
+            "members": {&craggy_island.ted: Uuid::new_v4()}
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_overlapping_shifts(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+    let template_id = save_template(app, project_id).await;
+
+    let response = app
+        .post_apply_week_template(&json!({
+            "templateId": &template_id,
+            "replace": false
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Ted would have overlapping shifts on Monday"
+    );
+    assert_eq!(shifts(app, project_id).await.len(), 3);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_name(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+
+    let response = app
+        .post_week_template(&json!({
+            "projectId": &craggy_island.project_id,
+            "name": "  "
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 400);
+    assert_eq!(
+        response.json::<ErrorResponse>().await.unwrap().error,
+        "Validation error: Template name cannot be empty"
+    );
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_someone_elses_templates(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+    let template_id = save_template(app, project_id).await;
+
+    let _other_email = get_session(app, false).await;
+    let response = app
+        .post_week_template(&json!({"projectId": project_id, "name": "Mine"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+    let response = app.get_week_templates(project_id).await;
+    assert_eq!(response.status().as_u16(), 404);
+    let response = app
+        .post_apply_week_template(&json!({"templateId": &template_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_401_if_logged_out(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let craggy_island = add_craggy_island(app).await;
+    let project_id = &craggy_island.project_id;
+    let template_id = save_template(app, project_id).await;
+    logout(app).await;
+
+    let response = app.get_week_templates(project_id).await;
+    assert_eq!(response.status().as_u16(), 401);
+    let response = app
+        .post_apply_week_template(&json!({"templateId": &template_id}))
+        .await;
+    assert_eq!(response.status().as_u16(), 401);
+}
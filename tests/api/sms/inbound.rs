@@ -0,0 +1,157 @@
+use chrono::{NaiveDate, TimeDelta, Utc};
+use rota_manager::{
+    domain::Clock, services::shift_reminders::send_shift_reminders,
+};
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_session, SmsTestApp, TestApp,
+    TWILIO_AUTH_TOKEN,
+};
+
+const TED: &str = "+447700900123";
+
+// Give Ted a Monday shift and send the reminder for the next one
+async fn remind_ted(app: &mut TestApp) -> NaiveDate {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    app.put_member_contact(
+        &ted,
+        &json!({"phoneNumber": TED, "smsOptIn": true}),
+    )
+    .await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+
+    let now = app.clock.now().naive_utc();
+    let queued =
+        send_shift_reminders(&app.pg_pool, now, TimeDelta::weeks(1), None)
+            .await
+            .expect("Failed to send shift reminders");
+    assert_eq!(queued, 1);
+
+    sqlx::query_scalar("SELECT shift_date FROM shift_reminders")
+        .fetch_one(&app.pg_pool)
+        .await
+        .expect("Failed to fetch reminder")
+}
+
+async fn reply_times(
+    app: &TestApp,
+) -> (Option<chrono::DateTime<Utc>>, Option<chrono::DateTime<Utc>>) {
+    sqlx::query_as(
+        "SELECT confirmed_at, swap_requested_at FROM shift_reminders",
+    )
+    .fetch_one(&app.pg_pool)
+    .await
+    .expect("Failed to fetch reminder")
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_confirm_shift_on_yes(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    remind_ted(app).await;
+
+    let response = app.post_inbound_sms(TED, "Yes", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(response.headers()["content-type"], "text/xml");
+    let twiml = response.text().await.unwrap();
+    assert!(twiml.contains(
+        "<Message>Thanks, you&apos;re confirmed for Craggy Island on Monday"
+    ));
+
+    let (confirmed_at, swap_requested_at) = reply_times(app).await;
+    assert!(confirmed_at.is_some());
+    assert!(swap_requested_at.is_none());
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_email_owner_on_swap(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    remind_ted(app).await;
+
+    let response = app.post_inbound_sms(TED, "SWAP", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let twiml = response.text().await.unwrap();
+    assert!(twiml.contains("rota manager to find someone to swap"));
+
+    let (confirmed_at, swap_requested_at) = reply_times(app).await;
+    assert!(confirmed_at.is_none());
+    assert!(swap_requested_at.is_some());
+
+    let subject: String =
+        sqlx::query_scalar("SELECT subject FROM email_outbox")
+            .fetch_one(&app.pg_pool)
+            .await
+            .expect("Failed to fetch queued email");
+    assert_eq!(subject, "Ted asked to swap a shift");
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_explain_unknown_replies(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    remind_ted(app).await;
+
+    let test_cases = [
+        (TED, "Maybe", "Reply YES to confirm your next shift"),
+        (
+            "+447700900999",
+            "YES",
+            "couldn&apos;t find an upcoming shift",
+        ),
+        (
+            "not a number",
+            "YES",
+            "couldn&apos;t find an upcoming shift",
+        ),
+    ];
+    for (from, body, expected) in test_cases {
+        let response =
+            app.post_inbound_sms(from, body, TWILIO_AUTH_TOKEN).await;
+        assert_eq!(response.status().as_u16(), 200);
+        let twiml = response.text().await.unwrap();
+        assert!(twiml.contains(expected), "Failed for {} {}", from, body);
+    }
+
+    assert_eq!(reply_times(app).await, (None, None));
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_reject_unsigned_texts(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    remind_ted(app).await;
+
+    let response = app.post_inbound_sms(TED, "YES", "wrong_token").await;
+    assert_eq!(response.status().as_u16(), 401);
+
+    let response = app
+        .http_client
+        .post(format!("{}/sms/inbound", &app.address))
+        .form(&[("From", TED), ("Body", "YES")])
+        .send()
+        .await
+        .expect("Failed to execute request");
+    assert_eq!(response.status().as_u16(), 400);
+
+    assert_eq!(reply_times(app).await, (None, None));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_reject_texts_when_twilio_not_configured(app: &mut TestApp) {
+    let response = app.post_inbound_sms(TED, "YES", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 401);
+}
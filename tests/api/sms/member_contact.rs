@@ -0,0 +1,143 @@
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::routes::projects::MemberContactResponse;
+use serde_json::json;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_update_member_contact(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let response = app.get_member_contact(&ted).await;
+    assert_eq!(response.status().as_u16(), 200);
+    assert_eq!(
+        get_json_response_body(response).await,
+        json!({
+            "memberId": &ted,
+            "phoneNumber": null,
+            "smsOptIn": false,
+            "email": null,
+            "emailOptIn": false
+        })
+    );
+
+    let response = app
+        .put_member_contact(
+            &ted,
+            &json!({"phoneNumber": "+44 7700 900123", "smsOptIn": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<MemberContactResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["phoneNumber"], "+447700900123");
+    assert_eq!(body["smsOptIn"], true);
+
+    // Leaving the number out keeps it
+    let response = app
+        .put_member_contact(&ted, &json!({"smsOptIn": false}))
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["phoneNumber"], "+447700900123");
+    assert_eq!(body["smsOptIn"], false);
+
+    let response = app
+        .put_member_contact(&ted, &json!({"phoneNumber": null}))
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["phoneNumber"], serde_json::Value::Null);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_turn_off_sms_when_number_changes(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    app.put_member_contact(
+        &ted,
+        &json!({"phoneNumber": "+447700900123", "smsOptIn": true}),
+    )
+    .await;
+
+    let response = app
+        .put_member_contact(&ted, &json!({"phoneNumber": "+447700900456"}))
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["smsOptIn"], false);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_update_member_email(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let response = app
+        .put_member_contact(
+            &ted,
+            &json!({"email": "ted@example.com", "emailOptIn": true}),
+        )
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<MemberContactResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["email"], "ted@example.com");
+    assert_eq!(body["emailOptIn"], true);
+
+    // A new address hasn't agreed to emails
+    let response = app
+        .put_member_contact(&ted, &json!({"email": "father.ted@example.com"}))
+        .await;
+    let body = get_json_response_body(response).await;
+    assert_eq!(body["email"], "father.ted@example.com");
+    assert_eq!(body["emailOptIn"], false);
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_contact(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let test_cases = [
+        json!({"phoneNumber": "07700 900123"}),
+        json!({"smsOptIn": true}),
+        json!({"phoneNumber": null, "smsOptIn": true}),
+        json!({"email": "not an email"}),
+        json!({"emailOptIn": true}),
+    ];
+    for test_case in test_cases {
+        let response = app.put_member_contact(&ted, &test_case).await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", test_case);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_member(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+
+    let _email = get_session(app, false).await;
+    let response = app.get_member_contact(&ted).await;
+    assert_eq!(response.status().as_u16(), 404);
+    let response = app
+        .put_member_contact(&ted, &json!({"phoneNumber": "+447700900123"}))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,5 @@
+mod inbound;
+mod member_contact;
+mod open_shift_broadcast;
+mod rota_reactions;
+mod shift_reminders;
@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, TestApp,
+};
+use rota_manager::{
+    routes::projects::OpenShiftBroadcastResponse, utils::constants::test,
+};
+use serde_json::json;
+use test_context::test_context;
+use wiremock::{matchers::method, Mock, ResponseTemplate};
+
+// The form-encoded bodies of the texts Twilio has been asked to send
+async fn sent_sms(app: &TestApp) -> Vec<String> {
+    app.sms_server
+        .received_requests()
+        .await
+        .expect("Request recording is disabled")
+        .iter()
+        .map(|request| String::from_utf8_lossy(&request.body).into_owned())
+        .collect()
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_text_members_who_opted_in(app: &mut TestApp) {
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&app.sms_server)
+        .await;
+
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    let _jack = add_member(app, "Jack", &project_id).await;
+    app.put_member_contact(
+        &ted,
+        &json!({"phoneNumber": "+447700900123", "smsOptIn": true}),
+    )
+    .await;
+    app.put_member_contact(&dougal, &json!({"phoneNumber": "+447700900456"}))
+        .await;
+
+    let response = app
+        .post_open_shift_broadcast(&json!({
+            "projectId": &project_id,
+            "day": "Friday",
+            "startTime": 540,
+            "endTime": 1020,
+            "note": "Call Mrs Doyle."
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(
+            &get_schema::<OpenShiftBroadcastResponse>(),
+            &body
+        ),
+        "response does not match schema"
+    );
+    assert_eq!(body["recipients"], 1);
+
+    let sent = tokio::time::timeout(Duration::from_secs(5), async {
+        loop {
+            let sent = sent_sms(app).await;
+            if !sent.is_empty() {
+                return sent;
+            }
+            tokio::time::sleep(test::OUTBOX_POLL_INTERVAL).await;
+        }
+    })
+    .await
+    .expect("Timed out waiting for SMS");
+
+    assert_eq!(sent.len(), 1);
+    assert!(sent[0].contains("To=%2B447700900123"));
+    assert!(sent[0].contains("needs+cover+on+Friday"));
+    assert!(sent[0].contains("Call+Mrs+Doyle."));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_400_for_invalid_broadcast(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let test_cases = [
+        json!({"projectId": &project_id, "day": "Friday", "startTime": -1, "endTime": 1020}),
+        json!({"projectId": &project_id, "day": "Friday", "startTime": 540, "endTime": 1441}),
+        json!({
+            "projectId": &project_id,
+            "day": "Friday",
+            "startTime": 540,
+            "endTime": 1020,
+            "note": "x".repeat(161)
+        }),
+    ];
+    for test_case in test_cases {
+        let response = app.post_open_shift_broadcast(&test_case).await;
+        assert_eq!(response.status().as_u16(), 400, "Failed for {}", test_case);
+    }
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_another_users_project(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+
+    let _email = get_session(app, false).await;
+    let response = app
+        .post_open_shift_broadcast(&json!({
+            "projectId": &project_id,
+            "day": "Friday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
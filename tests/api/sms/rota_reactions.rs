@@ -0,0 +1,141 @@
+use chrono::TimeDelta;
+use rota_manager::routes::projects::RotaReactionsResponse;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{
+    add_member, add_new_project, get_json_response_body, get_schema,
+    get_session, SmsTestApp, TestApp, TWILIO_AUTH_TOKEN,
+};
+
+const TED: &str = "+447700900123";
+
+// Ted takes texts and Dougal doesn't. Returns the project.
+async fn set_up_project(app: &mut TestApp) -> String {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    add_member(app, "Dougal", &project_id).await;
+    app.put_member_contact(
+        &ted,
+        &json!({"phoneNumber": TED, "smsOptIn": true}),
+    )
+    .await;
+    let response = app
+        .post_shift(&json!({
+            "memberId": &ted,
+            "day": "Monday",
+            "startTime": 540,
+            "endTime": 1020
+        }))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    project_id
+}
+
+async fn publish(app: &TestApp, project_id: &str, week_of: &str) -> String {
+    let response = app
+        .post_publish_rota(&json!({"projectId": project_id, "weekOf": week_of}))
+        .await;
+    assert_eq!(response.status().as_u16(), 201);
+    get_json_response_body(response).await["versionId"]
+        .as_str()
+        .unwrap()
+        .to_owned()
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_record_reactions_to_latest_rota(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    let project_id = set_up_project(app).await;
+    let first = publish(app, &project_id, "2025-10-26").await;
+    app.clock.advance(TimeDelta::minutes(1));
+    let latest = publish(app, &project_id, "2025-11-02").await;
+
+    let response = app.post_inbound_sms(TED, "👍", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let twiml = response.text().await.unwrap();
+    assert!(twiml.contains(
+        "know you&apos;ve seen the rota for the week of Sunday 02/11/2025"
+    ));
+
+    let response = app.get_rota_reactions(&[("projectId", &project_id)]).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<RotaReactionsResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["versionId"], latest.as_str());
+    assert_eq!(body["reactions"][0]["memberName"], "Ted");
+    assert_eq!(body["reactions"][0]["reaction"], "👍");
+    assert_eq!(body["waiting"][0]["memberName"], "Dougal");
+
+    // Reacting again replaces the reaction
+    let response = app.post_inbound_sms(TED, "🎉", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(
+        app.get_rota_reactions(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(body["reactions"].as_array().unwrap().len(), 1);
+    assert_eq!(body["reactions"][0]["reaction"], "🎉");
+
+    // Nobody reacted to the earlier week
+    let body = get_json_response_body(
+        app.get_rota_reactions(&[
+            ("projectId", &project_id),
+            ("versionId", &first),
+        ])
+        .await,
+    )
+    .await;
+    assert!(body["reactions"].as_array().unwrap().is_empty());
+    assert_eq!(body["waiting"].as_array().unwrap().len(), 2);
+}
+
+#[test_context(SmsTestApp)]
+#[tokio::test]
+async fn should_treat_seen_as_thumbs_up(ctx: &mut SmsTestApp) {
+    let app = &mut ctx.0;
+    let project_id = set_up_project(app).await;
+
+    // Nothing has been published yet
+    let response = app.post_inbound_sms(TED, "Seen", TWILIO_AUTH_TOKEN).await;
+    let twiml = response.text().await.unwrap();
+    assert!(twiml.contains("couldn&apos;t find a published rota"));
+    let body = get_json_response_body(
+        app.get_rota_reactions(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(body["versionId"], json!(null));
+
+    publish(app, &project_id, "2025-10-26").await;
+    let response = app.post_inbound_sms(TED, "Seen", TWILIO_AUTH_TOKEN).await;
+    assert_eq!(response.status().as_u16(), 200);
+    let body = get_json_response_body(
+        app.get_rota_reactions(&[("projectId", &project_id)]).await,
+    )
+    .await;
+    assert_eq!(body["reactions"][0]["reaction"], "👍");
+
+    let response = app
+        .post_inbound_sms("+447700900999", "👍", TWILIO_AUTH_TOKEN)
+        .await;
+    let twiml = response.text().await.unwrap();
+    assert!(twiml.contains("couldn&apos;t find a published rota"));
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_404_for_unknown_version(app: &mut TestApp) {
+    let project_id = set_up_project(app).await;
+    let response = app
+        .get_rota_reactions(&[
+            ("projectId", &project_id),
+            ("versionId", &uuid::Uuid::new_v4().to_string()),
+        ])
+        .await;
+    assert_eq!(response.status().as_u16(), 404);
+}
@@ -0,0 +1,65 @@
+use chrono::{NaiveDate, TimeDelta};
+use rota_manager::services::shift_reminders::send_shift_reminders;
+use serde_json::json;
+use test_context::test_context;
+
+use crate::helpers::{add_member, add_new_project, get_session, TestApp};
+
+async fn queued_sms(app: &TestApp) -> Vec<(String, String)> {
+    sqlx::query_as("SELECT recipient, content FROM sms_outbox ORDER BY id")
+        .fetch_all(&app.pg_pool)
+        .await
+        .expect("Failed to fetch queued SMS")
+}
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_remind_opted_in_members_once(app: &mut TestApp) {
+    let _email = get_session(app, false).await;
+    let project_id = add_new_project(app, "Craggy Island").await;
+    let ted = add_member(app, "Ted", &project_id).await;
+    let dougal = add_member(app, "Dougal", &project_id).await;
+    app.put_member_contact(
+        &ted,
+        &json!({"phoneNumber": "+447700900123", "smsOptIn": true}),
+    )
+    .await;
+    app.put_member_contact(&dougal, &json!({"phoneNumber": "+447700900456"}))
+        .await;
+    for (member_id, day) in
+        [(&ted, "Monday"), (&ted, "Friday"), (&dougal, "Monday")]
+    {
+        let response = app
+            .post_shift(&json!({
+                "memberId": member_id,
+                "day": day,
+                "startTime": 540,
+                "endTime": 1020
+            }))
+            .await;
+        assert_eq!(response.status().as_u16(), 201);
+    }
+
+    // The Sunday evening before a Monday shift
+    let now = NaiveDate::from_ymd_opt(2025, 10, 19)
+        .unwrap()
+        .and_hms_opt(21, 0, 0)
+        .unwrap();
+    for _ in 0..2 {
+        let queued =
+            send_shift_reminders(&app.pg_pool, now, TimeDelta::hours(12), None)
+                .await
+                .expect("Failed to send shift reminders");
+        assert!(queued <= 1);
+    }
+
+    assert_eq!(
+        queued_sms(app).await,
+        vec![(
+            "+447700900123".to_owned(),
+            "Reminder: you're on the Craggy Island rota on Monday 20/10/2025 \
+             at 09:00. Reply YES to confirm or SWAP to ask to swap."
+                .to_owned()
+        )]
+    );
+}
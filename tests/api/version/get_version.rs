@@ -0,0 +1,27 @@
+use crate::helpers::{get_json_response_body, get_schema, TestApp};
+use rota_manager::routes::version::VersionResponse;
+use test_context::test_context;
+
+#[test_context(TestApp)]
+#[tokio::test]
+async fn should_return_build_info_without_authentication(app: &mut TestApp) {
+    let response = app.get_version().await;
+    assert_eq!(response.status().as_u16(), 200);
+
+    let body = get_json_response_body(response).await;
+    assert!(
+        jsonschema::is_valid(&get_schema::<VersionResponse>(), &body),
+        "response does not match schema"
+    );
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(!body["gitSha"].as_str().unwrap().is_empty());
+    assert!(chrono::DateTime::parse_from_rfc3339(
+        body["buildTimestamp"].as_str().unwrap()
+    )
+    .is_ok());
+    // The suite only builds with Postmark
+    assert!(body["features"]
+        .as_array()
+        .unwrap()
+        .contains(&"postmark".into()));
+}